@@ -41,6 +41,9 @@ pub enum Command {
     /// Processes an input file to extract provided metadata
     Query(QueryCommand),
 
+    /// Lists all insertions, deletions, and comments in an input file
+    Review(ReviewCommand),
+
     /// Lists all discovered fonts in system and custom font paths
     Fonts(FontsCommand),
 
@@ -71,9 +74,37 @@ pub struct CompileCommand {
     #[arg(long = "ppi", default_value_t = 144.0)]
     pub ppi: f32,
 
+    /// The maximum PPI (pixels per inch) of raster images in PDF export;
+    /// images with a higher resolution are downsampled
+    #[arg(long = "image-max-ppi", value_name = "PPI")]
+    pub image_max_ppi: Option<f32>,
+
+    /// The quality (1-100) at which JPEG images are recompressed in PDF export
+    #[arg(
+        long = "jpeg-quality",
+        value_name = "QUALITY",
+        value_parser = clap::value_parser!(u8).range(1..=100),
+    )]
+    pub jpeg_quality: Option<u8>,
+
+    /// The PDF standards that the exported file conforms to, separated by
+    /// commas
+    #[arg(long = "pdf-standard", value_name = "STANDARD", value_delimiter = ',')]
+    pub pdf_standard: Vec<PdfStandard>,
+
     /// Produces a flamegraph of the compilation process
     #[arg(long = "flamegraph", value_name = "OUTPUT_SVG")]
     pub flamegraph: Option<Option<PathBuf>>,
+    /// Persists shaped text, decoded images, and exported pages in the given
+    /// directory and reuses them in subsequent compilations. The least
+    /// recently used entries are evicted once the directory exceeds 1 GiB
+    #[arg(long = "cache-dir", env = "TYPST_CACHE_DIR", value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Fails if labels are duplicated or references and citations don't
+    /// resolve, even if show rules replaced them with placeholders
+    #[arg(long = "strict-labels")]
+    pub strict_labels: bool,
 }
 
 /// Processes an input file to extract provided metadata
@@ -97,16 +128,33 @@ pub struct QueryCommand {
     /// The format to serialize in
     #[clap(long = "format", default_value = "json")]
     pub format: SerializationFormat,
+
+    /// Preserves the types of values that the format can't represent, like
+    /// lengths and content, instead of serializing them as strings
+    #[clap(long = "typed", default_value = "false")]
+    pub typed: bool,
+}
+
+/// Lists all insertions, deletions, and comments in an input file
+#[derive(Debug, Clone, Parser)]
+pub struct ReviewCommand {
+    /// Shared arguments
+    #[clap(flatten)]
+    pub common: SharedArgs,
+
+    /// The format to serialize in
+    #[clap(long = "format", default_value = "json")]
+    pub format: SerializationFormat,
 }
 
-// Output file format for query command
+// Output file format for query and review commands
 #[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
 pub enum SerializationFormat {
     Json,
     Yaml,
 }
 
-/// Common arguments of compile, watch, and query.
+/// Common arguments of compile, watch, query, and review.
 #[derive(Debug, Clone, Args)]
 pub struct SharedArgs {
     /// Path to input Typst file
@@ -116,6 +164,15 @@ pub struct SharedArgs {
     #[clap(long = "root", env = "TYPST_ROOT", value_name = "DIR")]
     pub root: Option<PathBuf>,
 
+    /// Adds a string key-value pair, visible through `sys.inputs`
+    #[clap(
+        long = "input",
+        value_name = "key=value",
+        action = ArgAction::Append,
+        value_parser = parse_input_pair,
+    )]
+    pub inputs: Vec<(String, String)>,
+
     /// Adds additional directories to search for fonts
     #[clap(
         long = "font-path",
@@ -132,6 +189,22 @@ pub struct SharedArgs {
         value_parser = clap::value_parser!(DiagnosticFormat)
     )]
     pub diagnostic_format: DiagnosticFormat,
+
+    /// Turns warnings of the given categories into errors
+    #[clap(long = "promote-warnings", value_name = "CATEGORY", value_delimiter = ',')]
+    pub promote_warnings: Vec<WarningCategory>,
+}
+
+/// Parses a key-value pair, split at the first equals sign.
+fn parse_input_pair(raw: &str) -> Result<(String, String), String> {
+    let (key, value) = raw
+        .split_once('=')
+        .ok_or("input must be a key and a value separated by an equals sign")?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("input key must not be empty".into());
+    }
+    Ok((key.into(), value.trim().into()))
 }
 
 /// Lists all discovered fonts in system and custom font paths
@@ -167,6 +240,54 @@ impl Display for DiagnosticFormat {
     }
 }
 
+/// A standard that an exported PDF file conforms to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum PdfStandard {
+    /// PDF/A-2b
+    #[value(name = "a-2b")]
+    A2b,
+    /// PDF/A-3b
+    #[value(name = "a-3b")]
+    A3b,
+    /// PDF/UA-1
+    #[value(name = "ua-1")]
+    Ua1,
+}
+
+impl From<PdfStandard> for typst_pdf::PdfStandard {
+    fn from(standard: PdfStandard) -> Self {
+        match standard {
+            PdfStandard::A2b => Self::A2b,
+            PdfStandard::A3b => Self::A3b,
+            PdfStandard::Ua1 => Self::Ua1,
+        }
+    }
+}
+
+/// A category of warnings that can be promoted to errors.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, ValueEnum)]
+pub enum WarningCategory {
+    Markup,
+    Import,
+    Convergence,
+    User,
+    UnknownFont,
+    UnresolvedReference,
+}
+
+impl From<WarningCategory> for typst::diag::WarningCategory {
+    fn from(category: WarningCategory) -> Self {
+        match category {
+            WarningCategory::Markup => Self::Markup,
+            WarningCategory::Import => Self::Import,
+            WarningCategory::Convergence => Self::Convergence,
+            WarningCategory::User => Self::User,
+            WarningCategory::UnknownFont => Self::UnknownFont,
+            WarningCategory::UnresolvedReference => Self::UnresolvedReference,
+        }
+    }
+}
+
 /// Update the CLI using a pre-compiled binary from a Typst GitHub release.
 #[derive(Debug, Clone, Parser)]
 pub struct UpdateCommand {
@@ -189,6 +310,8 @@ pub enum OutputFormat {
     Pdf,
     Png,
     Svg,
+    Html,
+    Epub,
 }
 
 impl Display for OutputFormat {
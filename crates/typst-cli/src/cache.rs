@@ -0,0 +1,187 @@
+use std::fs;
+use std::hash::Hash;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use ecow::eco_format;
+use tempfile::NamedTempFile;
+use typst::diag::StrResult;
+use typst::util::hash128;
+use typst::util::persist::Store;
+
+/// The size up to which the cache may grow before old entries are evicted.
+const LIMIT: u64 = 1024 * 1024 * 1024;
+
+/// A persistent on-disk cache for expensive, serializable results.
+///
+/// Entries are keyed by the hash of their inputs together with the compiler
+/// version, so that a cache directory can safely be shared between different
+/// versions of Typst. The cache is opt-in and only used if a cache directory
+/// is configured with `--cache-dir`.
+///
+/// The cache holds two kinds of entries: The results of shaping text and
+/// decoding images, which the compiler persists through the world's
+/// [`Store`], and the rendered output of pages whose frames did not change.
+/// Layouted frames themselves cannot be persisted, since they are memoized in
+/// memory and reference fonts and images of the current process.
+#[derive(Clone)]
+pub struct DiskCache {
+    /// The directory the entries are stored in.
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Open the cache in the given directory, creating it if necessary.
+    pub fn new(dir: &Path) -> StrResult<Self> {
+        fs::create_dir_all(dir)
+            .map_err(|err| eco_format!("failed to create cache directory ({err})"))?;
+        Ok(Self { dir: dir.into() })
+    }
+
+    /// Open the cache and evict old entries, so that it can serve as the
+    /// world's store for shaping and image decoding results.
+    pub fn open(dir: &Path) -> StrResult<Self> {
+        let cache = Self::new(dir)?;
+        cache.evict(LIMIT);
+        Ok(cache)
+    }
+
+    /// Retrieve the entry for the given key, if any.
+    pub fn get<K: Hash>(&self, kind: &str, key: &K) -> Option<Vec<u8>> {
+        fs::read(self.path(kind, key)).ok()
+    }
+
+    /// Store an entry for the given key.
+    ///
+    /// Failing to write to the cache is not an error since the cache is only
+    /// an optimization. The entry is written to a uniquely named temporary
+    /// file in the cache directory first and then moved into place, so that
+    /// concurrent processes never observe partially written entries.
+    pub fn put<K: Hash>(&self, kind: &str, key: &K, data: &[u8]) {
+        let path = self.path(kind, key);
+        let result = NamedTempFile::new_in(&self.dir).and_then(|mut temp| {
+            temp.write_all(data)?;
+            temp.persist(&path).map_err(|err| err.error)?;
+            Ok(())
+        });
+
+        if result.is_err() {
+            tracing::warn!("failed to write cache entry {}", path.display());
+        }
+    }
+
+    /// Remove the least recently used entries until the cache is at most
+    /// `limit` bytes large.
+    ///
+    /// Whether an entry was used recently is determined by its access time,
+    /// falling back to its modification time on file systems that do not
+    /// track access.
+    pub fn evict(&self, limit: u64) {
+        let Ok(entries) = fs::read_dir(&self.dir) else { return };
+        let mut entries: Vec<_> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let metadata = entry.metadata().ok()?;
+                if !metadata.is_file() {
+                    return None;
+                }
+                let used = metadata
+                    .accessed()
+                    .or_else(|_| metadata.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                Some((used, metadata.len(), entry.path()))
+            })
+            .collect();
+
+        let mut size: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort_by_key(|(used, _, _)| *used);
+        for (_, len, path) in entries {
+            if size <= limit {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                size -= len;
+            }
+        }
+    }
+
+    /// The path of the entry for the given key.
+    fn path<K: Hash>(&self, kind: &str, key: &K) -> PathBuf {
+        let hash = hash128(&(crate::typst_version(), kind, key));
+        self.dir.join(format!("{hash:032x}.{kind}"))
+    }
+}
+
+impl Store for DiskCache {
+    fn load(&self, kind: &str, key: u128) -> Option<Vec<u8>> {
+        self.get(kind, &key)
+    }
+
+    fn save(&self, kind: &str, key: u128, data: &[u8]) {
+        self.put(kind, &key, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disk_cache_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path()).unwrap();
+        assert_eq!(cache.get("png", &1), None);
+        cache.put("png", &1, b"first");
+        cache.put("svg", &1, b"second");
+        assert_eq!(cache.get("png", &1).as_deref(), Some(&b"first"[..]));
+        assert_eq!(cache.get("svg", &1).as_deref(), Some(&b"second"[..]));
+        assert_eq!(cache.get("png", &2), None);
+    }
+
+    #[test]
+    fn test_disk_cache_leaves_no_temporary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path()).unwrap();
+        for i in 0..10 {
+            cache.put("png", &i, b"data");
+        }
+        cache.put("png", &0, b"overwritten");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 10);
+        assert_eq!(cache.get("png", &0).as_deref(), Some(&b"overwritten"[..]));
+    }
+
+    #[test]
+    fn test_disk_cache_concurrent_writers() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path()).unwrap();
+        std::thread::scope(|s| {
+            for i in 0..8u8 {
+                let cache = cache.clone();
+                s.spawn(move || cache.put("png", &0, &[i; 4096]));
+            }
+        });
+
+        // Whichever writer won, the entry is complete.
+        let data = cache.get("png", &0).unwrap();
+        assert_eq!(data.len(), 4096);
+        assert!(data.iter().all(|&b| b == data[0]));
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_disk_cache_evicts_down_to_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path()).unwrap();
+        for i in 0..4 {
+            cache.put("png", &i, &[0; 100]);
+        }
+
+        cache.evict(250);
+        let remaining = (0..4).filter(|i| cache.get("png", i).is_some()).count();
+        assert_eq!(remaining, 2);
+
+        cache.evict(0);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
@@ -6,15 +6,18 @@ use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term::{self, termcolor};
 use ecow::eco_format;
 use termcolor::{ColorChoice, StandardStream};
-use typst::diag::{bail, Severity, SourceDiagnostic, StrResult};
+use typst::diag::{bail, Severity, SourceDiagnostic, StrResult, WarningCategory};
 use typst::eval::Tracer;
 use typst::foundations::Datetime;
-use typst::model::Document;
+use typst::layout::Frame;
+use typst::model::{Document, Target};
 use typst::syntax::{FileId, Source, Span};
-use typst::visualize::Color;
+use typst::visualize::{Color, Image, ImageFormat, RasterFormat};
 use typst::{World, WorldExt};
+use typst_pdf::{ImageOptions, PdfExporter};
 
-use crate::args::{CompileCommand, DiagnosticFormat, OutputFormat};
+use crate::args::{CompileCommand, DiagnosticFormat, OutputFormat, PdfStandard};
+use crate::cache::DiskCache;
 use crate::watch::Status;
 use crate::world::SystemWorld;
 use crate::{color_stream, set_failed};
@@ -31,6 +34,8 @@ impl CompileCommand {
                     OutputFormat::Pdf => "pdf",
                     OutputFormat::Png => "png",
                     OutputFormat::Svg => "svg",
+                    OutputFormat::Html => "html",
+                    OutputFormat::Epub => "epub",
                 },
             )
         })
@@ -47,17 +52,34 @@ impl CompileCommand {
                 Some(ext) if ext.eq_ignore_ascii_case("pdf") => OutputFormat::Pdf,
                 Some(ext) if ext.eq_ignore_ascii_case("png") => OutputFormat::Png,
                 Some(ext) if ext.eq_ignore_ascii_case("svg") => OutputFormat::Svg,
+                Some(ext) if ext.eq_ignore_ascii_case("html") => OutputFormat::Html,
+                Some(ext) if ext.eq_ignore_ascii_case("epub") => OutputFormat::Epub,
                 _ => bail!("could not infer output format for path {}.\nconsider providing the format manually with `--format/-f`", output.display()),
             }
         } else {
             OutputFormat::Pdf
         })
     }
+
+    /// The export target that documents are compiled for.
+    pub fn target(&self) -> StrResult<Target> {
+        Ok(match self.output_format()? {
+            OutputFormat::Pdf => Target::Pdf,
+            OutputFormat::Png => Target::Png,
+            OutputFormat::Svg => Target::Svg,
+            OutputFormat::Html => Target::Html,
+            OutputFormat::Epub => Target::Epub,
+        })
+    }
 }
 
 /// Execute a compilation command.
 pub fn compile(mut command: CompileCommand) -> StrResult<()> {
     let mut world = SystemWorld::new(&command.common)?;
+    world.set_target(command.target()?);
+    if let Some(dir) = &command.cache_dir {
+        world.set_store(DiskCache::open(dir)?);
+    }
     compile_once(&mut world, &mut command, false)?;
     Ok(())
 }
@@ -82,13 +104,41 @@ pub fn compile_once(
     world.source(world.main()).map_err(|err| err.to_string())?;
 
     let mut tracer = Tracer::new();
-    let result = typst::compile(world, &mut tracer);
-    let warnings = tracer.warnings();
+    for &category in &command.common.promote_warnings {
+        tracer.promote(category.into());
+    }
+
+    // In strict mode, problems with labels fail the compilation.
+    if command.strict_labels {
+        tracer.promote(WarningCategory::UnresolvedReference);
+    }
+
+    // PDF/UA is exported from the tags in the frames.
+    if command.pdf_standard.contains(&PdfStandard::Ua1) {
+        tracer.record_tags();
+    }
+
+    let mut result =
+        typst::compile_incremental(world, &mut tracer, world.compilation_cache());
+
+    // Duplicate labels don't affect compilation, so they are audited
+    // afterwards.
+    if command.strict_labels {
+        if let Ok(document) = &result {
+            let errors = typst::audit(document);
+            if !errors.is_empty() {
+                result = Err(errors);
+            }
+        }
+    }
 
     match result {
         // Export the PDF / PNG.
         Ok(document) => {
-            export(world, &document, command, watching)?;
+            // Exporting into HTML and EPUB evaluates the document once more,
+            // which may produce further warnings.
+            export(world, &document, command, watching, &mut tracer)?;
+            let warnings = tracer.warnings();
             let duration = start.elapsed();
 
             tracing::info!("Compilation succeeded in {duration:?}");
@@ -110,6 +160,7 @@ pub fn compile_once(
 
         // Print diagnostics.
         Err(errors) => {
+            let warnings = tracer.warnings();
             set_failed();
             tracing::info!("Compilation failed");
 
@@ -136,6 +187,7 @@ fn export(
     document: &Document,
     command: &CompileCommand,
     watching: bool,
+    tracer: &mut Tracer,
 ) -> StrResult<()> {
     match command.output_format()? {
         OutputFormat::Png => {
@@ -145,6 +197,8 @@ fn export(
             export_image(world, document, command, watching, ImageExportFormat::Svg)
         }
         OutputFormat::Pdf => export_pdf(document, command, world),
+        OutputFormat::Html => export_html(document, command, world, tracer),
+        OutputFormat::Epub => export_epub(document, command, world, tracer),
     }
 }
 
@@ -155,13 +209,76 @@ fn export_pdf(
     world: &SystemWorld,
 ) -> StrResult<()> {
     let ident = world.input().to_string_lossy();
-    let buffer = typst_pdf::pdf(document, Some(&ident), now());
+    let options = ImageOptions {
+        max_ppi: command.image_max_ppi,
+        jpeg_quality: command.jpeg_quality,
+    };
+
+    let mut exporter = PdfExporter::new()
+        .with_image_options(options)
+        .with_rasterizer(rasterize);
+    for &standard in &command.pdf_standard {
+        exporter = exporter.with_standard(standard.into());
+    }
+
+    for frame in &document.pages {
+        exporter.push_page(frame);
+    }
+
+    let buffer = exporter
+        .finish(&document.info, Some(&ident), now())
+        .map_err(|err| eco_format!("failed to export PDF ({err})"))?;
     let output = command.output();
     fs::write(output, buffer)
         .map_err(|err| eco_format!("failed to write PDF file ({err})"))?;
     Ok(())
 }
 
+/// Rasterize a frame for content that PDF cannot express.
+fn rasterize(frame: &Frame, pixel_per_pt: f32) -> Option<Image> {
+    let pixmap = typst_render::render(frame, pixel_per_pt, Color::WHITE.with_alpha(0.0));
+    let data = pixmap.encode_png().ok()?;
+    Image::new(data.into(), ImageFormat::Raster(RasterFormat::Png), None).ok()
+}
+
+/// Export to an HTML page.
+fn export_html(
+    document: &Document,
+    command: &CompileCommand,
+    world: &SystemWorld,
+    tracer: &mut Tracer,
+) -> StrResult<()> {
+    let html =
+        typst_html::html(world, document, tracer).map_err(|errors| {
+            match errors.first() {
+                Some(error) => eco_format!("failed to export HTML ({})", error.message),
+                None => "failed to export HTML".into(),
+            }
+        })?;
+    fs::write(command.output(), html)
+        .map_err(|err| eco_format!("failed to write HTML file ({err})"))?;
+    Ok(())
+}
+
+/// Export to an EPUB publication.
+fn export_epub(
+    document: &Document,
+    command: &CompileCommand,
+    world: &SystemWorld,
+    tracer: &mut Tracer,
+) -> StrResult<()> {
+    let epub =
+        typst_html::epub(world, document, tracer, now()).map_err(
+            |errors| match errors.first() {
+                Some(error) => eco_format!("failed to export EPUB ({})", error.message),
+                None => "failed to export EPUB".into(),
+            },
+        )?;
+    fs::write(command.output(), epub)
+        .map_err(|err| eco_format!("failed to write EPUB file ({err})"))?;
+    Ok(())
+}
+
 /// Get the current date and time in UTC.
 fn now() -> Option<Datetime> {
     let now = chrono::Local::now().naive_utc();
@@ -203,6 +320,8 @@ fn export_image(
     let width = 1 + document.pages.len().checked_ilog10().unwrap_or(0) as usize;
     let mut storage;
 
+    let disk = command.cache_dir.as_deref().map(DiskCache::new).transpose()?;
+
     let cache = world.export_cache();
     for (i, frame) in document.pages.iter().enumerate() {
         let path = if numbered {
@@ -219,20 +338,34 @@ fn export_image(
             continue;
         }
 
-        match fmt {
-            ImageExportFormat::Png => {
-                let pixmap =
-                    typst_render::render(frame, command.ppi / 72.0, Color::WHITE);
-                pixmap
-                    .save_png(path)
-                    .map_err(|err| eco_format!("failed to write PNG file ({err})"))?;
-            }
-            ImageExportFormat::Svg => {
-                let svg = typst_svg::svg(frame);
-                fs::write(path, svg.as_bytes())
-                    .map_err(|err| eco_format!("failed to write SVG file ({err})"))?;
+        let (kind, what) = match fmt {
+            ImageExportFormat::Png => ("png", "PNG"),
+            ImageExportFormat::Svg => ("svg", "SVG"),
+        };
+
+        // Reuse the output of a previous process if the page did not change.
+        let key = (frame, command.ppi.to_bits());
+        let cached = disk.as_ref().and_then(|disk| disk.get(kind, &key));
+        let buffer = match cached {
+            Some(buffer) => buffer,
+            None => {
+                let buffer = match fmt {
+                    ImageExportFormat::Png => {
+                        typst_render::render(frame, command.ppi / 72.0, Color::WHITE)
+                            .encode_png()
+                            .map_err(|err| eco_format!("failed to encode PNG ({err})"))?
+                    }
+                    ImageExportFormat::Svg => typst_svg::svg(frame).into_bytes(),
+                };
+                if let Some(disk) = &disk {
+                    disk.put(kind, &key, &buffer);
+                }
+                buffer
             }
-        }
+        };
+
+        fs::write(path, buffer)
+            .map_err(|err| eco_format!("failed to write {what} file ({err})"))?;
     }
 
     Ok(())
@@ -269,19 +402,31 @@ pub fn print_diagnostics(
     }
 
     for diagnostic in warnings.iter().chain(errors) {
+        let mut notes: Vec<String> = diagnostic
+            .hints
+            .iter()
+            .map(|e| (eco_format!("hint: {e}")).into())
+            .collect();
+
+        // Grouped diagnostics point to a few more places and say how often
+        // the problem occurred overall.
+        if diagnostic.count > 1 {
+            notes.push(format!("this occurred {} times in total", diagnostic.count));
+        }
+
+        let labels = label(world, diagnostic.span).into_iter().chain(
+            diagnostic.repeats.iter().filter_map(|&span| {
+                Some(Label::secondary(span.id()?, world.range(span)?))
+            }),
+        );
+
         let diag = match diagnostic.severity {
             Severity::Error => Diagnostic::error(),
             Severity::Warning => Diagnostic::warning(),
         }
         .with_message(diagnostic.message.clone())
-        .with_notes(
-            diagnostic
-                .hints
-                .iter()
-                .map(|e| (eco_format!("hint: {e}")).into())
-                .collect(),
-        )
-        .with_labels(label(world, diagnostic.span).into_iter().collect());
+        .with_notes(notes)
+        .with_labels(labels.collect());
 
         term::emit(&mut w, &config, world, &diag)?;
 
@@ -3,6 +3,7 @@ use std::fs;
 use std::path::PathBuf;
 
 use fontdb::{Database, Source};
+use rayon::prelude::*;
 use typst::diag::StrResult;
 use typst::text::{Font, FontBook, FontInfo, FontVariant};
 
@@ -75,7 +76,18 @@ impl FontSearcher {
         // System fonts have second priority.
         db.load_system_fonts();
 
-        for face in db.faces() {
+        // Parsing the font metadata is the slowest part of font discovery
+        // and independent for each face, so we do it in parallel.
+        let faces: Vec<_> = db.faces().collect();
+        let infos: Vec<_> = faces
+            .par_iter()
+            .map(|face| {
+                db.with_face_data(face.id, FontInfo::new)
+                    .expect("database must contain this font")
+            })
+            .collect();
+
+        for (face, info) in faces.into_iter().zip(infos) {
             let path = match &face.source {
                 Source::File(path) | Source::SharedFile(path, _) => path,
                 // We never add binary sources to the database, so there
@@ -83,10 +95,6 @@ impl FontSearcher {
                 Source::Binary(_) => continue,
             };
 
-            let info = db
-                .with_face_data(face.id, FontInfo::new)
-                .expect("database must contain this font");
-
             if let Some(info) = info {
                 self.book.push(info);
                 self.fonts.push(FontSlot {
@@ -140,3 +148,40 @@ impl FontSearcher {
         add!("DejaVuSansMono-BoldOblique.ttf");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_font_searcher_loads_faces_lazily() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../assets/fonts");
+        let mut searcher = FontSearcher::new();
+        searcher.search(&[dir.clone()]);
+
+        let slots: Vec<_> = searcher
+            .fonts
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.path.starts_with(&dir))
+            .collect();
+        assert!(slots.len() > 1);
+
+        // Discovering the fonts only parses their metadata.
+        assert!(slots.iter().all(|(_, slot)| slot.font.get().is_none()));
+
+        // A face is loaded on first access, independently of the others.
+        slots[0].1.get().unwrap();
+        assert!(slots[0].1.font.get().is_some());
+        assert!(slots[1].1.font.get().is_none());
+
+        // The metadata that was parsed in parallel is in the same order as the
+        // slots.
+        for (i, slot) in slots {
+            let font = slot.get().unwrap();
+            assert_eq!(searcher.book.info(i), Some(font.info()));
+        }
+    }
+}
@@ -1,9 +1,11 @@
 mod args;
+mod cache;
 mod compile;
 mod download;
 mod fonts;
 mod package;
 mod query;
+mod review;
 mod tracing;
 #[cfg(feature = "self-update")]
 mod update;
@@ -43,6 +45,7 @@ fn main() -> ExitCode {
         Command::Compile(command) => crate::compile::compile(command.clone()),
         Command::Watch(command) => crate::watch::watch(command.clone()),
         Command::Query(command) => crate::query::query(command),
+        Command::Review(command) => crate::review::review(command),
         Command::Fonts(command) => crate::fonts::fonts(command),
         Command::Update(command) => crate::update::update(command),
     };
@@ -3,7 +3,7 @@ use ecow::{eco_format, EcoString};
 use serde::Serialize;
 use typst::diag::{bail, StrResult};
 use typst::eval::{eval_string, EvalMode, Tracer};
-use typst::foundations::{Content, IntoValue, LocatableSelector, Scope};
+use typst::foundations::{Content, IntoValue, LocatableSelector, Scope, Typed};
 use typst::introspection::Introspector;
 use typst::model::Document;
 use typst::syntax::Span;
@@ -24,6 +24,10 @@ pub fn query(command: &QueryCommand) -> StrResult<()> {
     world.source(world.main()).map_err(|err| err.to_string())?;
 
     let mut tracer = Tracer::new();
+    for &category in &command.common.promote_warnings {
+        tracer.promote(category.into());
+    }
+
     let result = typst::compile(&world, &mut tracer);
     let warnings = tracer.warnings();
 
@@ -101,14 +105,24 @@ fn format(elements: Vec<Content>, command: &QueryCommand) -> StrResult<String> {
         let Some(value) = mapped.first() else {
             bail!("no such field found for element");
         };
-        serialize(value, command.format)
+        if command.typed {
+            serialize(&Typed(value), command.format)
+        } else {
+            serialize(value, command.format)
+        }
+    } else if command.typed {
+        let typed: Vec<_> = mapped.iter().map(Typed).collect();
+        serialize(&typed, command.format)
     } else {
         serialize(&mapped, command.format)
     }
 }
 
 /// Serialize data to the output format.
-fn serialize(data: &impl Serialize, format: SerializationFormat) -> StrResult<String> {
+pub fn serialize(
+    data: &impl Serialize,
+    format: SerializationFormat,
+) -> StrResult<String> {
     match format {
         SerializationFormat::Json => {
             serde_json::to_string_pretty(data).map_err(|e| eco_format!("{e}"))
@@ -0,0 +1,112 @@
+use std::num::NonZeroUsize;
+
+use ecow::{eco_format, EcoString};
+use serde::Serialize;
+use typst::diag::StrResult;
+use typst::eval::Tracer;
+use typst::foundations::{NativeElement, Selector, Smart, StyleChain};
+use typst::introspection::Introspector;
+use typst::model::{CommentElem, DelElem, Document, InsElem};
+use typst::World;
+
+use crate::args::ReviewCommand;
+use crate::compile::print_diagnostics;
+use crate::query::serialize;
+use crate::set_failed;
+use crate::world::SystemWorld;
+
+/// Execute a review command.
+pub fn review(command: &ReviewCommand) -> StrResult<()> {
+    let mut world = SystemWorld::new(&command.common)?;
+    tracing::info!("Starting review");
+
+    // Reset everything and ensure that the main file is present.
+    world.reset();
+    world.source(world.main()).map_err(|err| err.to_string())?;
+
+    let mut tracer = Tracer::new();
+    let result = typst::compile(&world, &mut tracer);
+    let warnings = tracer.warnings();
+
+    match result {
+        // Print the annotations.
+        Ok(document) => {
+            let annotations = collect(&document);
+            let serialized = serialize(&annotations, command.format)?;
+            println!("{serialized}");
+            print_diagnostics(&world, &[], &warnings, command.common.diagnostic_format)
+                .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+        }
+
+        // Print diagnostics.
+        Err(errors) => {
+            set_failed();
+            print_diagnostics(
+                &world,
+                &errors,
+                &warnings,
+                command.common.diagnostic_format,
+            )
+            .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A review annotation in the document.
+#[derive(Serialize)]
+struct Annotation {
+    /// Whether this is an insertion, a deletion, or a comment.
+    kind: &'static str,
+    /// The author of the annotation.
+    author: Option<EcoString>,
+    /// When the annotation was made.
+    date: Option<EcoString>,
+    /// The plain text of the annotated content or the comment.
+    text: EcoString,
+    /// The page the annotation is on, starting at 1.
+    page: NonZeroUsize,
+    /// The horizontal position on the page in points.
+    x: f64,
+    /// The vertical position on the page in points.
+    y: f64,
+}
+
+/// Collect all review annotations in document order.
+fn collect(document: &Document) -> Vec<Annotation> {
+    let introspector = Introspector::new(&document.pages);
+    let selector = Selector::Or(
+        [InsElem::elem(), DelElem::elem(), CommentElem::elem()]
+            .into_iter()
+            .map(|elem| Selector::Elem(elem, None))
+            .collect(),
+    );
+
+    introspector
+        .query(&selector)
+        .iter()
+        .filter_map(|elem| {
+            let styles = StyleChain::default();
+            let (kind, author, date, body) = if let Some(ins) = elem.to::<InsElem>() {
+                ("insertion", ins.author(styles), ins.date(styles), ins.body())
+            } else if let Some(del) = elem.to::<DelElem>() {
+                ("deletion", del.author(styles), del.date(styles), del.body())
+            } else {
+                let comment = elem.to::<CommentElem>()?;
+                ("comment", comment.author(styles), comment.date(styles), comment.body())
+            };
+
+            let position = introspector.position(elem.location()?);
+            Some(Annotation {
+                kind,
+                author,
+                date: date.and_then(|date| date.display(Smart::Auto).ok()),
+                text: body.plain_text(),
+                page: position.page,
+                x: position.point.x.to_pt(),
+                y: position.point.y.to_pt(),
+            })
+        })
+        .collect()
+}
@@ -10,6 +10,7 @@ use termcolor::WriteColor;
 use typst::diag::StrResult;
 
 use crate::args::CompileCommand;
+use crate::cache::DiskCache;
 use crate::color_stream;
 use crate::compile::compile_once;
 use crate::world::SystemWorld;
@@ -18,6 +19,10 @@ use crate::world::SystemWorld;
 pub fn watch(mut command: CompileCommand) -> StrResult<()> {
     // Create the world that serves sources, files, and fonts.
     let mut world = SystemWorld::new(&command.common)?;
+    world.set_target(command.target()?);
+    if let Some(dir) = &command.cache_dir {
+        world.set_store(DiskCache::open(dir)?);
+    }
 
     // Perform initial compilation.
     compile_once(&mut world, &mut command, true)?;
@@ -7,14 +7,18 @@ use chrono::{DateTime, Datelike, Local};
 use comemo::Prehashed;
 use ecow::eco_format;
 use typst::diag::{FileError, FileResult, StrResult};
-use typst::foundations::{Bytes, Datetime};
+use typst::engine::CompilationCache;
+use typst::foundations::{Bytes, Datetime, IntoValue};
 use typst::layout::Frame;
+use typst::model::{DocumentElem, Target};
 use typst::syntax::{FileId, Source, VirtualPath};
 use typst::text::{Font, FontBook};
 use typst::util::hash128;
+use typst::util::persist::Store;
 use typst::{Library, World};
 
 use crate::args::SharedArgs;
+use crate::cache::DiskCache;
 use crate::fonts::{FontSearcher, FontSlot};
 use crate::package::prepare_package;
 
@@ -42,6 +46,11 @@ pub struct SystemWorld {
     /// The export cache, used for caching output files in `typst watch`
     /// sessions.
     export_cache: ExportCache,
+    /// Retains the introspector across compilations in `typst watch` sessions.
+    compilation_cache: CompilationCache,
+    /// Persists shaping and image decoding results across processes if a
+    /// cache directory is configured.
+    store: Option<DiskCache>,
 }
 
 impl SystemWorld {
@@ -71,20 +80,40 @@ impl SystemWorld {
         let main_path = VirtualPath::within_root(&input, &root)
             .ok_or("input file must be contained in project root")?;
 
+        // Make the inputs available through `sys.inputs`.
+        let mut library = Library::build();
+        library.set_inputs(
+            command
+                .inputs
+                .iter()
+                .map(|(key, value)| (key.as_str().into(), value.as_str().into_value()))
+                .collect(),
+        );
+
         Ok(Self {
             workdir: std::env::current_dir().ok(),
             input,
             root,
             main: FileId::new(None, main_path),
-            library: Prehashed::new(Library::build()),
+            library: Prehashed::new(library),
             book: Prehashed::new(searcher.book),
             fonts: searcher.fonts,
             slots: RefCell::default(),
             now: OnceCell::new(),
             export_cache: ExportCache::new(),
+            compilation_cache: CompilationCache::new(),
+            store: None,
         })
     }
 
+    /// Set the export target that documents see through the `target`
+    /// function.
+    pub fn set_target(&mut self, target: Target) {
+        let mut library = (*self.library).clone();
+        library.styles.set(DocumentElem::set_target(target));
+        self.library = Prehashed::new(library);
+    }
+
     /// The id of the main source file.
     pub fn main(&self) -> FileId {
         self.main
@@ -128,6 +157,16 @@ impl SystemWorld {
         self.source(id).expect("file id does not point to any source file")
     }
 
+    /// Persist shaping and image decoding results in the given on-disk cache.
+    pub fn set_store(&mut self, cache: DiskCache) {
+        self.store = Some(cache);
+    }
+
+    /// Gets access to the compilation cache.
+    pub fn compilation_cache(&self) -> &CompilationCache {
+        &self.compilation_cache
+    }
+
     /// Gets access to the export cache.
     pub fn export_cache(&mut self) -> &mut ExportCache {
         &mut self.export_cache
@@ -173,6 +212,10 @@ impl World for SystemWorld {
             naive.day().try_into().ok()?,
         )
     }
+
+    fn store(&self) -> Option<&dyn Store> {
+        self.store.as_ref().map(|cache| cache as &dyn Store)
+    }
 }
 
 impl SystemWorld {
@@ -88,6 +88,41 @@ pub fn provide(resolver: &dyn Resolver) -> Vec<PageModel> {
     ]
 }
 
+/// Extract the signatures of all functions and types in the standard library.
+///
+/// In contrast to [`provide`], this doesn't produce pages, but a flat list of
+/// all definitions with their parameters, types, defaults, and docs. The
+/// result can be serialized to keep external editors and documentation sites
+/// in sync with the library.
+pub fn signatures(resolver: &dyn Resolver) -> LibraryModel {
+    let mut model = LibraryModel { funcs: vec![], types: vec![] };
+    collect_signatures(resolver, &mut model, LIBRARY.global.scope(), &mut vec![]);
+    model
+}
+
+/// Collect the signatures of the definitions in a scope and its submodules.
+fn collect_signatures<'a>(
+    resolver: &dyn Resolver,
+    model: &mut LibraryModel,
+    scope: &'a Scope,
+    path: &mut Vec<&'a str>,
+) {
+    for (name, value) in scope.iter() {
+        match value {
+            Value::Func(func) => {
+                model.funcs.push(func_model(resolver, func, path, false))
+            }
+            Value::Type(ty) => model.types.push(type_model(resolver, ty)),
+            Value::Module(module) => {
+                path.push(name.as_str());
+                collect_signatures(resolver, model, module.scope(), path);
+                path.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
 /// Resolve consumer dependencies.
 pub trait Resolver {
     /// Try to resolve a link that the system cannot resolve itself.
@@ -782,6 +817,42 @@ mod tests {
         provide(&TestResolver);
     }
 
+    #[test]
+    fn test_signatures() {
+        let model = signatures(&TestResolver);
+        let func = |path: &[&str], name: &str| {
+            model
+                .funcs
+                .iter()
+                .find(|func| func.path == path && func.name == name)
+                .unwrap_or_else(|| panic!("missing function {path:?} {name}"))
+        };
+
+        // Element functions record their settable parameters with types.
+        let text = func(&[], "text");
+        assert!(text.element);
+        let size = text.params.iter().find(|param| param.name == "size").unwrap();
+        assert!(size.named && size.settable && !size.required);
+        assert_eq!(size.types, ["length"]);
+        assert!(size.default.is_some());
+
+        // Required positional parameters and return types are kept.
+        let frac = func(&["math"], "frac");
+        let num = frac.params.iter().find(|param| param.name == "num").unwrap();
+        assert!(num.positional && num.required);
+        assert_eq!(func(&["calc"], "abs").path, ["calc"]);
+        assert!(!func(&[], "range").returns.is_empty());
+
+        // Types come with their constructor and methods.
+        let str = model.types.iter().find(|ty| ty.name == "str").unwrap();
+        assert!(str.constructor.is_some());
+        assert!(str.scope.iter().any(|method| method.name == "split"));
+
+        // The model can be serialized for external consumers.
+        let yaml = serde_yaml::to_string(&model).unwrap();
+        assert!(yaml.contains("name: frac"));
+    }
+
     struct TestResolver;
 
     impl Resolver for TestResolver {
@@ -130,6 +130,13 @@ pub struct GroupModel {
     pub functions: Vec<FuncModel>,
 }
 
+/// Signatures and documentation of all definitions in the standard library.
+#[derive(Debug, Serialize)]
+pub struct LibraryModel {
+    pub funcs: Vec<FuncModel>,
+    pub types: Vec<TypeModel>,
+}
+
 /// Details about a type.
 #[derive(Debug, Serialize)]
 pub struct TypeModel {
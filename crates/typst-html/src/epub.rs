@@ -0,0 +1,455 @@
+use std::collections::{BTreeSet, HashMap};
+use std::io::{Cursor, Write};
+
+use comemo::Track;
+use ecow::{eco_format, EcoString};
+use typst::diag::SourceResult;
+use typst::engine::{Engine, Route};
+use typst::eval::Tracer;
+use typst::foundations::{Datetime, Smart, StyleChain};
+use typst::introspection::{Introspector, Locator};
+use typst::layout::{Frame, FrameItem};
+use typst::model::Document;
+use typst::text::{Font, FontStyle};
+use typst::World;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::{escape, evaluate, Chapter, HtmlWriter, Outlined, STYLE};
+
+/// Export the main source file of a world into a reflowable EPUB 3
+/// publication.
+///
+/// Like [`html`](crate::html), this works on the evaluated content instead of
+/// the layouted pages, so that e-readers can reflow the text. The publication
+/// is split into one XHTML chapter per top-level heading of the first level.
+/// Its navigation document lists all outlined headings with their numbers.
+/// Images are embedded into the publication, as are subsets of the fonts that
+/// the layouted `document` uses.
+///
+/// The `document` is the result of compiling the same world and provides
+/// metadata like the title, the authors, and the language as well as the
+/// results of introspections. Warnings are reported to the `tracer`. The
+/// `timestamp`, if given, is recorded as the time of the last modification
+/// unless the document sets its own date.
+#[tracing::instrument(skip_all)]
+pub fn epub(
+    world: &dyn World,
+    document: &Document,
+    tracer: &mut Tracer,
+    timestamp: Option<Datetime>,
+) -> SourceResult<Vec<u8>> {
+    let world = world.track();
+    let introspector = Introspector::new(&document.pages);
+    let content = evaluate(world, introspector.track(), tracer)?;
+    let library = world.library();
+    let styles = StyleChain::new(&library.styles);
+
+    let mut locator = Locator::new();
+    let engine = Engine {
+        world,
+        introspector: introspector.track(),
+        route: Route::default(),
+        locator: &mut locator,
+        tracer: tracer.track_mut(),
+    };
+
+    let mut writer = HtmlWriter::new(engine, true);
+    writer.flow(&content, styles, true);
+    writer.break_chapter();
+    writer.finish()?;
+
+    let mut chapters = std::mem::take(&mut writer.chapters);
+    if chapters.is_empty() {
+        chapters.push(Chapter {
+            body: String::new(),
+            outline: vec![],
+            ids: vec![],
+            links: vec![],
+        });
+    }
+
+    let fonts = fonts(document, &chapters);
+    let meta = Metadata::new(document, timestamp);
+    let files = package(&meta, &chapters, &writer, &fonts);
+    Ok(archive(files).expect("writing to memory should not fail"))
+}
+
+/// Metadata of a publication.
+struct Metadata {
+    title: EcoString,
+    lang: EcoString,
+    modified: Option<EcoString>,
+    author: Vec<EcoString>,
+    keywords: Vec<EcoString>,
+    identifier: EcoString,
+}
+
+impl Metadata {
+    /// Collect the metadata of a document.
+    fn new(document: &Document, timestamp: Option<Datetime>) -> Self {
+        let date = match document.info.date {
+            Smart::Custom(date) => date,
+            Smart::Auto => timestamp,
+        };
+
+        let title = document.info.title.clone().unwrap_or_else(|| "Untitled".into());
+        let identifier = eco_format!(
+            "urn:typst:{:032x}",
+            typst::util::hash128(&(&title, &document.info.author, &document.pages.len()))
+        );
+
+        Self {
+            title,
+            lang: lang(document),
+            modified: date.and_then(modified),
+            author: document.info.author.clone(),
+            keywords: document.info.keywords.clone(),
+            identifier,
+        }
+    }
+}
+
+/// Assemble the files of the publication, with their paths in the container
+/// and whether they should be compressed.
+fn package(
+    meta: &Metadata,
+    chapters: &[Chapter],
+    writer: &HtmlWriter,
+    fonts: &[(Font, Vec<u8>)],
+) -> Vec<(EcoString, bool, Vec<u8>)> {
+    let mut files = vec![
+        ("mimetype".into(), false, b"application/epub+zip".to_vec()),
+        ("META-INF/container.xml".into(), true, CONTAINER.as_bytes().to_vec()),
+    ];
+
+    // Links to labels in other chapters must point to that chapter's file.
+    let mut targets = HashMap::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        for id in &chapter.ids {
+            targets.entry(id.clone()).or_insert(i);
+        }
+    }
+
+    for (i, chapter) in chapters.iter().enumerate() {
+        let body = relink(chapter, i, &targets);
+        let xhtml = xhtml(meta, &body);
+        files.push((eco_format!("EPUB/{}", chapter_href(i)), true, xhtml.into_bytes()));
+    }
+
+    let outline: Vec<(EcoString, &Outlined)> = chapters
+        .iter()
+        .enumerate()
+        .flat_map(|(i, chapter)| {
+            chapter.outline.iter().map(move |entry| (chapter_href(i), entry))
+        })
+        .collect();
+    files.push(("EPUB/nav.xhtml".into(), true, nav(meta, &outline).into_bytes()));
+
+    let mut style = String::from(STYLE);
+    let mut families: Vec<&str> = vec![];
+    for (i, (font, _)) in fonts.iter().enumerate() {
+        let info = font.info();
+        let (ext, _) = font_type(font);
+        style.push_str(&eco_format!(
+            "@font-face {{ font-family: \"{}\"; font-weight: {}; font-style: {}; src: url(\"fonts/{}.{ext}\"); }}\n",
+            info.family,
+            info.variant.weight.to_number(),
+            match info.variant.style {
+                FontStyle::Normal => "normal",
+                FontStyle::Italic => "italic",
+                FontStyle::Oblique => "oblique",
+            },
+            i + 1,
+        ));
+        if !families.contains(&info.family.as_str()) {
+            families.push(&info.family);
+        }
+    }
+    if !families.is_empty() {
+        let families: Vec<_> =
+            families.iter().map(|family| eco_format!("\"{family}\"")).collect();
+        style
+            .push_str(&eco_format!("body {{ font-family: {}; }}\n", families.join(", ")));
+    }
+    files.push(("EPUB/style.css".into(), true, style.into_bytes()));
+
+    for image in &writer.images {
+        files.push((eco_format!("EPUB/{}", image.href), true, image.data.clone()));
+    }
+
+    for (i, (font, data)) in fonts.iter().enumerate() {
+        let (ext, _) = font_type(font);
+        let path = eco_format!("EPUB/fonts/{}.{ext}", i + 1);
+        files.push((path, true, data.clone()));
+    }
+
+    files.push((
+        "EPUB/content.opf".into(),
+        true,
+        opf(meta, chapters, writer, fonts).into_bytes(),
+    ));
+    files
+}
+
+/// Write the files into a ZIP container.
+fn archive(files: Vec<(EcoString, bool, Vec<u8>)>) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (path, compressed, data) in files {
+        let method = if compressed {
+            CompressionMethod::Deflated
+        } else {
+            CompressionMethod::Stored
+        };
+        zip.start_file(path.as_str(), FileOptions::default().compression_method(method))?;
+        zip.write_all(&data)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+/// The container file that points to the package document.
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+<rootfiles>
+<rootfile full-path="EPUB/content.opf" media-type="application/oebps-package+xml"/>
+</rootfiles>
+</container>
+"#;
+
+/// The package document, which lists the publication's metadata and files.
+fn opf(
+    meta: &Metadata,
+    chapters: &[Chapter],
+    writer: &HtmlWriter,
+    fonts: &[(Font, Vec<u8>)],
+) -> String {
+    let mut opf = String::new();
+    opf.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    opf.push_str(&eco_format!(
+        "<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"id\" xml:lang=\"{}\">\n",
+        escape(&meta.lang),
+    ));
+
+    opf.push_str("<metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n");
+    opf.push_str(&eco_format!(
+        "<dc:identifier id=\"id\">{}</dc:identifier>\n",
+        escape(&meta.identifier)
+    ));
+    opf.push_str(&eco_format!("<dc:title>{}</dc:title>\n", escape(&meta.title)));
+    for author in &meta.author {
+        opf.push_str(&eco_format!("<dc:creator>{}</dc:creator>\n", escape(author)));
+    }
+    for keyword in &meta.keywords {
+        opf.push_str(&eco_format!("<dc:subject>{}</dc:subject>\n", escape(keyword)));
+    }
+    opf.push_str(&eco_format!("<dc:language>{}</dc:language>\n", escape(&meta.lang)));
+    if let Some(modified) = &meta.modified {
+        opf.push_str(&eco_format!(
+            "<meta property=\"dcterms:modified\">{modified}</meta>\n"
+        ));
+    }
+    opf.push_str("</metadata>\n");
+
+    opf.push_str("<manifest>\n");
+    opf.push_str("<item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n");
+    opf.push_str("<item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>\n");
+    for i in 0..chapters.len() {
+        opf.push_str(&eco_format!(
+            "<item id=\"chapter-{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n",
+            i + 1,
+            chapter_href(i),
+        ));
+    }
+    for (i, image) in writer.images.iter().enumerate() {
+        opf.push_str(&eco_format!(
+            "<item id=\"image-{}\" href=\"{}\" media-type=\"{}\"/>\n",
+            i + 1,
+            image.href,
+            image.media_type,
+        ));
+    }
+    for (i, (font, _)) in fonts.iter().enumerate() {
+        let (ext, media_type) = font_type(font);
+        opf.push_str(&eco_format!(
+            "<item id=\"font-{0}\" href=\"fonts/{0}.{ext}\" media-type=\"{media_type}\"/>\n",
+            i + 1,
+        ));
+    }
+    opf.push_str("</manifest>\n");
+
+    opf.push_str("<spine>\n");
+    for i in 0..chapters.len() {
+        opf.push_str(&eco_format!("<itemref idref=\"chapter-{}\"/>\n", i + 1));
+    }
+    opf.push_str("</spine>\n</package>\n");
+    opf
+}
+
+/// The navigation document with the publication's outline.
+fn nav(meta: &Metadata, outline: &[(EcoString, &Outlined)]) -> String {
+    let mut body = String::from("<nav epub:type=\"toc\" id=\"toc\">\n");
+    body.push_str(&eco_format!("<h1>{}</h1>\n<ol>\n", escape(&meta.title)));
+
+    // The navigation must not be empty, so it links at least to the start.
+    if outline.is_empty() {
+        body.push_str(&eco_format!(
+            "<li><a href=\"{}\">{}</a></li>\n",
+            chapter_href(0),
+            escape(&meta.title),
+        ));
+    }
+
+    // Nest the entries by level. A level is never deeper than one below the
+    // previous entry's.
+    let mut depth = 0;
+    for (href, entry) in outline {
+        let level = entry.level.min(depth + 1).max(1);
+        if level > depth && depth > 0 {
+            body.push_str("\n<ol>\n");
+        } else if depth > 0 {
+            body.push_str("</li>\n");
+            for _ in level..depth {
+                body.push_str("</ol>\n</li>\n");
+            }
+        }
+        depth = level;
+        body.push_str(&eco_format!(
+            "<li><a href=\"{href}#{}\">{}</a>",
+            escape(&entry.id),
+            escape(&entry.title),
+        ));
+    }
+    if depth > 0 {
+        body.push_str("</li>\n");
+        for _ in 1..depth {
+            body.push_str("</ol>\n</li>\n");
+        }
+    }
+
+    body.push_str("</ol>\n</nav>\n");
+    xhtml(meta, &body)
+}
+
+/// Wrap a body into an XHTML content document.
+fn xhtml(meta: &Metadata, body: &str) -> String {
+    let mut xhtml = String::new();
+    xhtml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!DOCTYPE html>\n");
+    xhtml.push_str(&eco_format!(
+        "<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" lang=\"{0}\" xml:lang=\"{0}\">\n",
+        escape(&meta.lang),
+    ));
+    xhtml.push_str("<head>\n<meta charset=\"utf-8\"/>\n");
+    xhtml.push_str(&eco_format!("<title>{}</title>\n", escape(&meta.title)));
+    xhtml.push_str("<link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/>\n");
+    xhtml.push_str("</head>\n<body>\n");
+    xhtml.push_str(body);
+    xhtml.push_str("</body>\n</html>\n");
+    xhtml
+}
+
+/// Make links to labels in other chapters point to that chapter's file.
+fn relink(chapter: &Chapter, i: usize, targets: &HashMap<EcoString, usize>) -> String {
+    let body = &chapter.body;
+    let mut out = String::with_capacity(body.len());
+    let mut cursor = 0;
+    for link in &chapter.links {
+        match targets.get(&link.label) {
+            Some(&target) if target != i => {
+                out.push_str(&body[cursor..link.offset]);
+                out.push_str(&chapter_href(target));
+                cursor = link.offset;
+            }
+            _ => {}
+        }
+    }
+    out.push_str(&body[cursor..]);
+    out
+}
+
+/// The path of a chapter's file.
+fn chapter_href(i: usize) -> EcoString {
+    eco_format!("chapter-{}.xhtml", i + 1)
+}
+
+/// The language of a document's first text.
+fn lang(document: &Document) -> EcoString {
+    fn find(frame: &Frame) -> Option<EcoString> {
+        frame.items().find_map(|(_, item)| match item {
+            FrameItem::Group(group) => find(&group.frame),
+            FrameItem::Text(text) => Some(text.lang.as_str().into()),
+            _ => None,
+        })
+    }
+
+    document.pages.iter().find_map(find).unwrap_or_else(|| "en".into())
+}
+
+/// The fonts a document uses that can be embedded into a publication, with
+/// their subsetted data.
+///
+/// Font collections can't be embedded. Each font is subsetted to the glyphs
+/// that the document uses from it and the glyphs of all characters in the
+/// chapters, since e-readers lay out the text anew.
+fn fonts(document: &Document, chapters: &[Chapter]) -> Vec<(Font, Vec<u8>)> {
+    fn collect(frame: &Frame, fonts: &mut Vec<(Font, BTreeSet<u16>)>) {
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => collect(&group.frame, fonts),
+                FrameItem::Text(text) => {
+                    if text.font.data().starts_with(b"ttcf") {
+                        continue;
+                    }
+                    let glyphs = text.glyphs.iter().map(|glyph| glyph.id);
+                    match fonts.iter_mut().find(|(font, _)| *font == text.font) {
+                        Some((_, used)) => used.extend(glyphs),
+                        None => fonts.push((text.font.clone(), glyphs.collect())),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut fonts = vec![];
+    for page in &document.pages {
+        collect(page, &mut fonts);
+    }
+
+    let chars: BTreeSet<char> =
+        chapters.iter().flat_map(|chapter| chapter.body.chars()).collect();
+    fonts
+        .into_iter()
+        .map(|(font, mut glyphs)| {
+            let ttf = font.ttf();
+            glyphs
+                .extend(chars.iter().filter_map(|&c| ttf.glyph_index(c)).map(|id| id.0));
+            let glyphs: Vec<u16> = glyphs.into_iter().collect();
+            let profile = subsetter::Profile::pdf(&glyphs);
+            let data = subsetter::subset(font.data(), font.index(), profile)
+                .unwrap_or_else(|_| font.data().to_vec());
+            (font, data)
+        })
+        .collect()
+}
+
+/// The file extension and media type of a font.
+fn font_type(font: &Font) -> (&'static str, &'static str) {
+    if font.data().starts_with(b"OTTO") {
+        ("otf", "font/otf")
+    } else {
+        ("ttf", "font/ttf")
+    }
+}
+
+/// Format a date as required for the modification time of a publication.
+fn modified(date: Datetime) -> Option<EcoString> {
+    Some(eco_format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        date.year()?,
+        date.month()?,
+        date.day()?,
+        date.hour().unwrap_or(0),
+        date.minute().unwrap_or(0),
+        date.second().unwrap_or(0),
+    ))
+}
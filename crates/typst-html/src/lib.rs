@@ -0,0 +1,721 @@
+//! Exporting of Typst documents into HTML and EPUB.
+
+mod epub;
+
+pub use self::epub::epub;
+
+use std::ffi::OsStr;
+use std::path::Path;
+
+use comemo::{Track, Tracked};
+use ecow::{eco_format, EcoString, EcoVec};
+use typst::diag::{SourceDiagnostic, SourceResult};
+use typst::engine::{Engine, Route};
+use typst::eval::Tracer;
+use typst::foundations::{Content, Guard, Label, PlainText, Smart, StyleChain, Value};
+use typst::introspection::{Introspector, Locator, MetaElem};
+use typst::layout::{place_cells, GridCell};
+use typst::loading::Readable;
+use typst::math::EquationElem;
+use typst::model::{
+    Destination, Document, EmphElem, EnumElem, EnumItem, FigureElem, HeadingElem,
+    LinkElem, LinkTarget, ListElem, ListItem, Numbering, ParbreakElem, StrongElem,
+    TableElem, TermItem, TermsElem,
+};
+use typst::text::{LinebreakElem, RawElem, SpaceElem, TextElem};
+use typst::visualize::{ImageElem, ImageFormat, RasterFormat, VectorFormat};
+use typst::World;
+
+/// The style sheet that is embedded into each page.
+const STYLE: &str = "\
+body { max-width: 40em; margin: 2em auto; padding: 0 1em; line-height: 1.5; }
+figure { margin: 1.5em 0; text-align: center; }
+img { max-width: 100%; }
+table { border-collapse: collapse; margin: 0 auto; }
+td { border: 1px solid; padding: 0.25em 0.5em; }
+pre { overflow-x: auto; }
+";
+
+/// Export the main source file of a world into an HTML page.
+///
+/// Instead of converting the layouted pages, this works on the semantic
+/// structure of the evaluated content. Headings, paragraphs, strong and
+/// emphasized text, lists, term lists, tables, figures, images, raw text, and
+/// links are exported as their HTML counterparts. User-defined show rules
+/// apply to all elements, while built-in ones only apply to the elements
+/// without an HTML counterpart. What remains of these is exported as plain
+/// text. Images are referenced with the path they were loaded from, so the
+/// page should be placed next to the main source file.
+///
+/// The `document` is the result of compiling the same world. It provides
+/// metadata like the page's title and answers the introspections of counters,
+/// references, and queries. Warnings that arise while evaluating the content
+/// once more are reported to the `tracer`.
+#[tracing::instrument(skip_all)]
+pub fn html(
+    world: &dyn World,
+    document: &Document,
+    tracer: &mut Tracer,
+) -> SourceResult<String> {
+    let world = world.track();
+    let introspector = Introspector::new(&document.pages);
+    let content = evaluate(world, introspector.track(), tracer)?;
+    let library = world.library();
+    let styles = StyleChain::new(&library.styles);
+
+    let mut locator = Locator::new();
+    let engine = Engine {
+        world,
+        introspector: introspector.track(),
+        route: Route::default(),
+        locator: &mut locator,
+        tracer: tracer.track_mut(),
+    };
+
+    let mut writer = HtmlWriter::new(engine, false);
+    writer.buf.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
+    writer.buf.push_str("<meta charset=\"utf-8\">\n");
+    if let Some(title) = &document.info.title {
+        writer
+            .buf
+            .push_str(&eco_format!("<title>{}</title>\n", escape(title)));
+    }
+    writer.buf.push_str("<style>\n");
+    writer.buf.push_str(STYLE);
+    writer.buf.push_str("</style>\n</head>\n<body>\n");
+    writer.flow(&content, styles, true);
+    writer.buf.push_str("</body>\n</html>\n");
+    writer.finish()?;
+    Ok(writer.buf)
+}
+
+/// Evaluate the main source file of a world into content.
+///
+/// The introspector should be the one of the compiled document, so that the
+/// evaluation sees the same introspections as the compilation did.
+fn evaluate(
+    world: Tracked<dyn World + '_>,
+    introspector: Tracked<Introspector>,
+    tracer: &mut Tracer,
+) -> SourceResult<Content> {
+    let module = typst::eval::eval(
+        world,
+        introspector,
+        Route::default().track(),
+        tracer.track_mut(),
+        &world.main(),
+    )?;
+    Ok(module.content())
+}
+
+/// Writes content as HTML.
+struct HtmlWriter<'a> {
+    /// Applies show rules.
+    engine: Engine<'a>,
+    /// The errors that occurred while applying show rules.
+    errors: EcoVec<SourceDiagnostic>,
+    buf: String,
+    /// Whether to write the XHTML chapters of an EPUB publication.
+    epub: bool,
+    /// How deeply flows are nested.
+    depth: usize,
+    /// The current numbers of numbered headings, one per level.
+    numbers: Vec<usize>,
+    /// The headings of the current chapter that appear in the outline.
+    outline: Vec<Outlined>,
+    /// The labels of the elements in the current chapter.
+    ids: Vec<EcoString>,
+    /// The links to labels in the current chapter.
+    links: Vec<Relink>,
+    /// The chapters finished so far.
+    chapters: Vec<Chapter>,
+    /// The images that are embedded into the publication.
+    images: Vec<Embedded>,
+}
+
+/// A chapter of an EPUB publication.
+///
+/// Chapters start at top-level headings of the first level.
+struct Chapter {
+    /// The chapter's XHTML body.
+    body: String,
+    /// The chapter's headings that appear in the outline.
+    outline: Vec<Outlined>,
+    /// The labels of the elements in the chapter.
+    ids: Vec<EcoString>,
+    /// The links to labels in the chapter.
+    links: Vec<Relink>,
+}
+
+/// A link to a label, which must point to another chapter's file if the
+/// label is defined there.
+struct Relink {
+    /// The offset in the chapter's body at which the link's `href` value
+    /// starts.
+    offset: usize,
+    /// The label the link points to.
+    label: EcoString,
+}
+
+/// A heading that appears in the outline.
+struct Outlined {
+    /// The heading's level.
+    level: usize,
+    /// The heading's id in its chapter.
+    id: EcoString,
+    /// The heading's number and title as plain text.
+    title: EcoString,
+}
+
+/// An image that is embedded into an EPUB publication.
+struct Embedded {
+    /// The image's path in the publication.
+    href: EcoString,
+    /// The image's media type.
+    media_type: &'static str,
+    /// The image's data.
+    data: Vec<u8>,
+}
+
+/// The state of a sequence of block-level content.
+struct Flow {
+    /// Whether paragraphs are wrapped in `<p>` tags. Tight list items and
+    /// table cells don't need this.
+    paragraphs: bool,
+    /// Whether a paragraph is currently open.
+    par: bool,
+    /// The tag of the list that is currently open.
+    list: Option<&'static str>,
+}
+
+impl<'a> HtmlWriter<'a> {
+    /// Create a writer for an HTML page or the chapters of an EPUB
+    /// publication.
+    fn new(engine: Engine<'a>, epub: bool) -> Self {
+        Self {
+            engine,
+            errors: EcoVec::new(),
+            buf: String::new(),
+            epub,
+            depth: 0,
+            numbers: vec![],
+            outline: vec![],
+            ids: vec![],
+            links: vec![],
+            chapters: vec![],
+            images: vec![],
+        }
+    }
+
+    /// Fail if applying a show rule failed.
+    fn finish(&mut self) -> SourceResult<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    /// Apply the show rules that affect an element.
+    ///
+    /// Elements with an HTML counterpart are only affected by user-defined
+    /// show rules. Returns `None` if no show rule applies.
+    fn show(&mut self, content: &Content, styles: StyleChain) -> Option<Content> {
+        // Elements are prepared like during layout, so that they get the same
+        // locations and the introspector knows about them.
+        let target = if is_native(content) && !content.needs_preparation() {
+            content.clone().guarded(Guard::Base(content.func()))
+        } else {
+            content.clone()
+        };
+
+        if !typst::realize::applicable(&target, styles) {
+            return None;
+        }
+
+        match typst::realize::realize(&mut self.engine, &target, styles) {
+            Ok(realized) => realized,
+            Err(errors) => {
+                self.errors.extend(errors);
+                None
+            }
+        }
+    }
+
+    /// Write block-level content and close all elements opened in it.
+    fn flow(&mut self, content: &Content, styles: StyleChain, paragraphs: bool) {
+        let mut flow = Flow { paragraphs, par: false, list: None };
+        self.depth += 1;
+        self.block(&mut flow, content, styles);
+        self.close_par(&mut flow);
+        self.close_list(&mut flow);
+        self.depth -= 1;
+    }
+
+    /// Finish the current chapter and start a new one.
+    fn break_chapter(&mut self) {
+        self.trim();
+        if !self.buf.is_empty() || !self.outline.is_empty() {
+            self.buf.push('\n');
+            self.chapters.push(Chapter {
+                body: std::mem::take(&mut self.buf),
+                outline: std::mem::take(&mut self.outline),
+                ids: std::mem::take(&mut self.ids),
+                links: std::mem::take(&mut self.links),
+            });
+        }
+    }
+
+    /// Write a piece of block-level content.
+    fn block(&mut self, flow: &mut Flow, content: &Content, styles: StyleChain) {
+        if let Some(children) = content.to_sequence() {
+            for child in children {
+                self.block(flow, child, styles);
+            }
+            return;
+        }
+
+        if let Some((child, local)) = content.to_styled() {
+            self.block(flow, child, styles.chain(local));
+            return;
+        }
+
+        if let Some(realized) = self.show(content, styles) {
+            self.block(flow, &realized, styles);
+            return;
+        }
+
+        // Markers of located elements don't open a paragraph. Spaces and
+        // paragraph breaks may separate the items of a list.
+        if content.is::<MetaElem>() {
+            return;
+        } else if content.is::<SpaceElem>() {
+            if flow.par {
+                self.buf.push(' ');
+            }
+            return;
+        } else if content.is::<ParbreakElem>() {
+            self.close_par(flow);
+            return;
+        }
+
+        if let Some(item) = content.to::<ListItem>() {
+            self.open_list(flow, "ul");
+            self.list_item(None, item.body(), styles);
+            return;
+        } else if let Some(item) = content.to::<EnumItem>() {
+            self.open_list(flow, "ol");
+            self.list_item(item.number(styles), item.body(), styles);
+            return;
+        } else if let Some(item) = content.to::<TermItem>() {
+            self.open_list(flow, "dl");
+            self.term_item(item, styles);
+            return;
+        }
+
+        self.close_list(flow);
+
+        if let Some(list) = content.to::<ListElem>() {
+            self.close_par(flow);
+            self.buf.push_str("<ul>\n");
+            for item in list.children() {
+                self.list_item(None, item.body(), styles);
+            }
+            self.buf.push_str("</ul>\n");
+        } else if let Some(list) = content.to::<EnumElem>() {
+            self.close_par(flow);
+            self.buf.push_str("<ol>\n");
+            for item in list.children() {
+                self.list_item(item.number(styles), item.body(), styles);
+            }
+            self.buf.push_str("</ol>\n");
+        } else if let Some(list) = content.to::<TermsElem>() {
+            self.close_par(flow);
+            self.buf.push_str("<dl>\n");
+            for item in list.children() {
+                self.term_item(item, styles);
+            }
+            self.buf.push_str("</dl>\n");
+        } else if let Some(heading) = content.to::<HeadingElem>() {
+            self.close_par(flow);
+            self.heading(heading, content.label(), styles);
+        } else if let Some(figure) = content.to::<FigureElem>() {
+            self.close_par(flow);
+            let id = self.id(content.label());
+            self.buf.push_str(&eco_format!("<figure{id}>\n"));
+            self.flow(figure.body(), styles, false);
+            if let Some(caption) = figure.caption(styles) {
+                self.buf.push_str("<figcaption>");
+                self.inline(caption.body(), styles);
+                self.buf.push_str("</figcaption>\n");
+            }
+            self.buf.push_str("</figure>\n");
+        } else if let Some(table) = content.to::<TableElem>() {
+            self.close_par(flow);
+            self.table(table, styles);
+        } else if let Some(raw) = content.to::<RawElem>().filter(|raw| raw.block(styles))
+        {
+            self.close_par(flow);
+            self.buf.push_str("<pre><code");
+            if let Some(lang) = raw.lang(styles) {
+                self.buf
+                    .push_str(&eco_format!(" class=\"language-{}\"", escape(lang)));
+            }
+            self.buf.push('>');
+            self.buf.push_str(&escape(raw.text()));
+            self.buf.push_str("</code></pre>\n");
+        } else if let Some(equation) =
+            content.to::<EquationElem>().filter(|equation| equation.block(styles))
+        {
+            self.close_par(flow);
+            self.buf.push_str("<div class=\"math\">");
+            self.buf.push_str(&escape(&equation.body().plain_text()));
+            self.buf.push_str("</div>\n");
+        } else if let Some(body) = body(content).filter(|_| !is_inline(content)) {
+            self.block(flow, &body, styles);
+        } else {
+            self.open_par(flow);
+            self.inline(content, styles);
+        }
+    }
+
+    /// Write a piece of inline content.
+    fn inline(&mut self, content: &Content, styles: StyleChain) {
+        if let Some(children) = content.to_sequence() {
+            for child in children {
+                self.inline(child, styles);
+            }
+        } else if let Some((child, local)) = content.to_styled() {
+            self.inline(child, styles.chain(local));
+        } else if let Some(realized) = self.show(content, styles) {
+            self.inline(&realized, styles);
+        } else if let Some(text) = content.to::<TextElem>() {
+            self.buf.push_str(&escape(text.text()));
+        } else if content.is::<SpaceElem>() || content.is::<ParbreakElem>() {
+            self.buf.push(' ');
+        } else if content.is::<LinebreakElem>() {
+            self.buf.push_str(if self.epub { "<br/>" } else { "<br>" });
+        } else if let Some(strong) = content.to::<StrongElem>() {
+            self.buf.push_str("<strong>");
+            self.inline(strong.body(), styles);
+            self.buf.push_str("</strong>");
+        } else if let Some(emph) = content.to::<EmphElem>() {
+            self.buf.push_str("<em>");
+            self.inline(emph.body(), styles);
+            self.buf.push_str("</em>");
+        } else if let Some(link) = content.to::<LinkElem>() {
+            let href = match link.dest() {
+                LinkTarget::Dest(Destination::Url(url)) => Some(url.clone()),
+                LinkTarget::Label(label) => Some(eco_format!("#{}", label.as_str())),
+                LinkTarget::Dest(_) => None,
+            };
+            match href {
+                Some(href) => {
+                    self.buf.push_str("<a href=\"");
+                    if let LinkTarget::Label(label) = link.dest() {
+                        let offset = self.buf.len();
+                        self.links.push(Relink { offset, label: label.as_str().into() });
+                    }
+                    self.buf.push_str(&eco_format!("{}\">", escape(&href)));
+                    self.inline(link.body(), styles);
+                    self.buf.push_str("</a>");
+                }
+                None => self.inline(link.body(), styles),
+            }
+        } else if let Some(raw) = content.to::<RawElem>() {
+            self.buf.push_str("<code>");
+            self.buf.push_str(&escape(raw.text()));
+            self.buf.push_str("</code>");
+        } else if let Some(equation) = content.to::<EquationElem>() {
+            self.buf.push_str("<span class=\"math\">");
+            self.buf.push_str(&escape(&equation.body().plain_text()));
+            self.buf.push_str("</span>");
+        } else if let Some(image) = content.to::<ImageElem>() {
+            let src = if self.epub { self.embed(image, styles) } else { None };
+            let src = src.unwrap_or_else(|| image.path().clone());
+            self.buf.push_str(&eco_format!("<img src=\"{}\"", escape(&src)));
+            let alt = image.alt(styles);
+            if alt.is_some() || self.epub {
+                let alt = alt.unwrap_or_default();
+                self.buf.push_str(&eco_format!(" alt=\"{}\"", escape(&alt)));
+            }
+            self.buf.push_str(if self.epub { "/>" } else { ">" });
+        } else if content.can::<dyn PlainText>() {
+            self.buf.push_str(&escape(&content.plain_text()));
+        } else if let Some(body) = body(content) {
+            self.inline(&body, styles);
+        }
+    }
+
+    /// Write a heading.
+    ///
+    /// In an EPUB publication, first-level headings start a new chapter,
+    /// headings are numbered, and they are collected for the outline.
+    fn heading(
+        &mut self,
+        heading: &HeadingElem,
+        label: Option<Label>,
+        styles: StyleChain,
+    ) {
+        let level = heading.level(styles).get();
+        let numbering = heading.numbering(styles);
+        if numbering.is_some() {
+            self.numbers.resize(level, 0);
+            self.numbers[level - 1] += 1;
+        }
+
+        if !self.epub {
+            let tag = level.min(6);
+            let id = self.id(label);
+            self.buf.push_str(&eco_format!("<h{tag}{id}>"));
+            self.inline(heading.body(), styles);
+            self.buf.push_str(&eco_format!("</h{tag}>\n"));
+            return;
+        }
+
+        if level == 1 && self.depth == 1 {
+            self.break_chapter();
+        }
+
+        // Headings without a label get an id, so that the outline can link
+        // to them.
+        let id = match label {
+            Some(label) => label.as_str().into(),
+            None => eco_format!("heading-{}", self.ids.len() + 1),
+        };
+
+        let number = match numbering {
+            Some(Numbering::Pattern(pattern)) => Some(pattern.apply(&self.numbers)),
+            _ => None,
+        };
+
+        let tag = level.min(6);
+        self.buf.push_str(&eco_format!("<h{tag} id=\"{}\">", escape(&id)));
+        if let Some(number) = &number {
+            self.buf.push_str(&eco_format!("{} ", escape(number)));
+        }
+        self.inline(heading.body(), styles);
+        self.buf.push_str(&eco_format!("</h{tag}>\n"));
+
+        if heading.outlined(styles) {
+            let mut title =
+                number.map(|number| eco_format!("{number} ")).unwrap_or_default();
+            title.push_str(&heading.body().plain_text());
+            self.outline.push(Outlined { level, id: id.clone(), title });
+        }
+        self.ids.push(id);
+    }
+
+    /// Embed an image into the publication and return its path.
+    ///
+    /// Returns `None` if the image's format is unknown.
+    fn embed(&mut self, image: &ImageElem, styles: StyleChain) -> Option<EcoString> {
+        let data = match image.data() {
+            Readable::Bytes(bytes) => bytes.to_vec(),
+            Readable::Str(text) => text.as_bytes().to_vec(),
+        };
+
+        let format = match image.format(styles) {
+            Smart::Custom(format) => format,
+            Smart::Auto => {
+                let ext = Path::new(image.path().as_str())
+                    .extension()
+                    .and_then(OsStr::to_str)
+                    .unwrap_or_default()
+                    .to_lowercase();
+                match ext.as_str() {
+                    "png" => ImageFormat::Raster(RasterFormat::Png),
+                    "jpg" | "jpeg" => ImageFormat::Raster(RasterFormat::Jpg),
+                    "gif" => ImageFormat::Raster(RasterFormat::Gif),
+                    "svg" => ImageFormat::Vector(VectorFormat::Svg),
+                    _ => ImageFormat::Raster(RasterFormat::detect(&data)?),
+                }
+            }
+        };
+
+        let (ext, media_type) = match format {
+            ImageFormat::Raster(RasterFormat::Png) => ("png", "image/png"),
+            ImageFormat::Raster(RasterFormat::Jpg) => ("jpg", "image/jpeg"),
+            ImageFormat::Raster(RasterFormat::Gif) => ("gif", "image/gif"),
+            ImageFormat::Vector(VectorFormat::Svg) => ("svg", "image/svg+xml"),
+        };
+
+        let href = eco_format!("images/{}.{ext}", self.images.len() + 1);
+        self.images.push(Embedded { href: href.clone(), media_type, data });
+        Some(href)
+    }
+
+    /// An `id` attribute for an element's label.
+    fn id(&mut self, label: Option<Label>) -> EcoString {
+        match label {
+            Some(label) => {
+                self.ids.push(label.as_str().into());
+                eco_format!(" id=\"{}\"", escape(label.as_str()))
+            }
+            None => EcoString::new(),
+        }
+    }
+
+    /// Write an item of a bullet or numbered list.
+    fn list_item(&mut self, number: Option<usize>, body: &Content, styles: StyleChain) {
+        match number {
+            Some(number) => self.buf.push_str(&eco_format!("<li value=\"{number}\">")),
+            None => self.buf.push_str("<li>"),
+        }
+        self.flow(body, styles, has_parbreak(body));
+        self.trim();
+        self.buf.push_str("</li>\n");
+    }
+
+    /// Write an item of a term list.
+    fn term_item(&mut self, item: &TermItem, styles: StyleChain) {
+        self.buf.push_str("<dt>");
+        self.inline(item.term(), styles);
+        self.buf.push_str("</dt>\n<dd>");
+        self.flow(item.description(), styles, has_parbreak(item.description()));
+        self.trim();
+        self.buf.push_str("</dd>\n");
+    }
+
+    /// Write a table, distributing its cells into rows like the layout does.
+    fn table(&mut self, table: &TableElem, styles: StyleChain) {
+        let columns = table.columns(styles).0.len().max(1);
+        let positions = match place_cells(table.children(), columns, styles) {
+            Ok(positions) => positions,
+            Err(errors) => {
+                self.errors.extend(errors);
+                return;
+            }
+        };
+
+        // The cells are placed in row-major order. Rows that are fully
+        // covered by cells spanning multiple rows are still written, so that
+        // the row spans add up.
+        let rows = positions.iter().map(|pos| pos.y + pos.rowspan).max().unwrap_or(0);
+        let mut cells = table.children().iter().zip(&positions).peekable();
+        self.buf.push_str("<table>\n");
+        for y in 0..rows {
+            self.buf.push_str("<tr>");
+            while let Some((cell, pos)) = cells.next_if(|(_, pos)| pos.y == y) {
+                self.buf.push_str("<td");
+                if pos.colspan > 1 {
+                    self.buf.push_str(&eco_format!(" colspan=\"{}\"", pos.colspan));
+                }
+                if pos.rowspan > 1 {
+                    self.buf.push_str(&eco_format!(" rowspan=\"{}\"", pos.rowspan));
+                }
+                self.buf.push('>');
+                let body = cell.to::<GridCell>().map_or(cell, |cell| cell.body());
+                self.flow(body, styles, has_parbreak(body));
+                self.trim();
+                self.buf.push_str("</td>");
+            }
+            self.buf.push_str("</tr>\n");
+        }
+        self.buf.push_str("</table>\n");
+    }
+
+    /// Open a paragraph if none is open yet.
+    fn open_par(&mut self, flow: &mut Flow) {
+        if !flow.par {
+            if flow.paragraphs {
+                self.buf.push_str("<p>");
+            }
+            flow.par = true;
+        }
+    }
+
+    /// Close the open paragraph, if any.
+    fn close_par(&mut self, flow: &mut Flow) {
+        if flow.par {
+            self.trim();
+            if flow.paragraphs {
+                self.buf.push_str("</p>");
+            }
+            self.buf.push('\n');
+            flow.par = false;
+        }
+    }
+
+    /// Open a list with the given tag, closing a different one that is open.
+    fn open_list(&mut self, flow: &mut Flow, tag: &'static str) {
+        if flow.list != Some(tag) {
+            self.close_par(flow);
+            self.close_list(flow);
+            self.buf.push_str(&eco_format!("<{tag}>\n"));
+            flow.list = Some(tag);
+        }
+    }
+
+    /// Close the open list, if any.
+    fn close_list(&mut self, flow: &mut Flow) {
+        if let Some(tag) = flow.list.take() {
+            self.buf.push_str(&eco_format!("</{tag}>\n"));
+        }
+    }
+
+    /// Remove trailing whitespace from the output.
+    fn trim(&mut self) {
+        self.buf.truncate(self.buf.trim_end().len());
+    }
+}
+
+/// Whether an element is exported as inline content.
+fn is_inline(content: &Content) -> bool {
+    content.is::<StrongElem>()
+        || content.is::<EmphElem>()
+        || content.is::<LinkElem>()
+        || content.is::<RawElem>()
+        || content.is::<EquationElem>()
+        || content.is::<ImageElem>()
+        || content.can::<dyn PlainText>()
+}
+
+/// Whether an element is exported as its HTML counterpart instead of by its
+/// built-in show rule.
+fn is_native(content: &Content) -> bool {
+    is_inline(content)
+        || content.is::<ListItem>()
+        || content.is::<EnumItem>()
+        || content.is::<TermItem>()
+        || content.is::<ListElem>()
+        || content.is::<EnumElem>()
+        || content.is::<TermsElem>()
+        || content.is::<HeadingElem>()
+        || content.is::<FigureElem>()
+        || content.is::<TableElem>()
+}
+
+/// The body of an element that isn't exported specially, if it has one.
+fn body(content: &Content) -> Option<Content> {
+    match content.get_by_name("body")? {
+        Value::Content(body) => Some(body),
+        _ => None,
+    }
+}
+
+/// Whether content consists of multiple paragraphs.
+fn has_parbreak(content: &Content) -> bool {
+    if let Some(mut children) = content.to_sequence() {
+        children.any(|child| has_parbreak(child))
+    } else if let Some((child, _)) = content.to_styled() {
+        has_parbreak(child)
+    } else {
+        content.is::<ParbreakElem>()
+    }
+}
+
+/// Escape text for use in HTML content and attribute values.
+fn escape(text: &str) -> EcoString {
+    let mut escaped = EcoString::new();
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
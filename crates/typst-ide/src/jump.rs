@@ -49,6 +49,7 @@ pub fn jump_from_click(
                             .get_or_insert_with(|| Introspector::new(frames))
                             .position(*loc),
                     ),
+                    Destination::Document(..) => continue,
                 });
             }
         }
@@ -113,6 +114,57 @@ pub fn jump_from_click(
     None
 }
 
+/// A show rule that generated the content at a position in the output.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Origin {
+    /// Where the element the rule was applied to is written.
+    pub call: Option<Jump>,
+    /// Where the show rule is defined.
+    pub rule: Option<Jump>,
+}
+
+/// Determine which show rules generated the content at a click in a frame.
+///
+/// While [`jump_from_click`] points to the code that directly produced the
+/// clicked content, this finds the elements and show rules that were involved
+/// in generating it. If content was generated by nested show rules, there is
+/// one origin per rule.
+///
+/// The show rules are only known if the document was compiled with a tracer
+/// that [records origins](typst::eval::Tracer::record_origins).
+pub fn origins_from_click(world: &dyn World, frame: &Frame, click: Point) -> Vec<Origin> {
+    let mut spans = vec![];
+    find_origins(frame, click, &mut spans);
+    spans
+        .into_iter()
+        .map(|(call, rule)| Origin {
+            call: Jump::from_span(world, call),
+            rule: Jump::from_span(world, rule),
+        })
+        .collect()
+}
+
+/// Collect the spans of the origin metadata covering a click in a frame.
+fn find_origins(frame: &Frame, click: Point, spans: &mut Vec<(Span, Span)>) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                // Map the click into the group's coordinate system.
+                let Some(inverse) = group.transform.invert() else { continue };
+                find_origins(&group.frame, (click - *pos).transform(inverse), spans);
+            }
+            FrameItem::Meta(Meta::Origin { call, rule }, size)
+                if is_in_rect(*pos, *size, click) =>
+            {
+                if !spans.contains(&(*call, *rule)) {
+                    spans.push((*call, *rule));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 /// Find the output location in the document for a cursor position.
 pub fn jump_from_cursor(
     frames: &[Frame],
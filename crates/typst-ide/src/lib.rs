@@ -3,11 +3,15 @@
 mod analyze;
 mod complete;
 mod jump;
+mod outline;
 mod tooltip;
 
 pub use self::analyze::analyze_labels;
 pub use self::complete::{autocomplete, Completion, CompletionKind};
-pub use self::jump::{jump_from_click, jump_from_cursor, Jump};
+pub use self::jump::{
+    jump_from_click, jump_from_cursor, origins_from_click, Jump, Origin,
+};
+pub use self::outline::{outline, Symbol, SymbolKind};
 pub use self::tooltip::{tooltip, Tooltip};
 
 use std::fmt::Write;
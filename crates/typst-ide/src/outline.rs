@@ -0,0 +1,231 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+use typst::syntax::ast::{self, AstNode};
+use typst::syntax::{Source, Span, SyntaxKind, SyntaxNode};
+
+/// A symbol in the outline of a source file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Symbol {
+    /// The name of the symbol, e.g. the plain text of a heading.
+    pub name: EcoString,
+    /// The kind of the symbol.
+    pub kind: SymbolKind,
+    /// The span of the symbol's syntax node.
+    pub span: Span,
+    /// The symbols nested in this one.
+    pub children: Vec<Symbol>,
+}
+
+/// What kind of symbol an outline entry is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SymbolKind {
+    /// A heading of the given level.
+    Heading(NonZeroUsize),
+    /// A label.
+    Label,
+    /// A figure. Its name is its caption, if it has one.
+    Figure,
+}
+
+/// Extract the outline of a source file for an editor sidebar.
+///
+/// This is a lightweight, purely syntactic analysis that does not require
+/// compiling the document. Headings contain the symbols up to the next heading
+/// of the same or a lower level. Labels within a heading are nested in it.
+pub fn outline(source: &Source) -> Vec<Symbol> {
+    let mut builder = OutlineBuilder { output: vec![], stack: vec![] };
+    builder.visit(source.root());
+    builder.finish()
+}
+
+/// Assembles the nested outline from the symbols in source order.
+struct OutlineBuilder {
+    /// The finished top-level symbols.
+    output: Vec<Symbol>,
+    /// The headings whose sections are still open, outermost first.
+    stack: Vec<(NonZeroUsize, Symbol)>,
+}
+
+impl OutlineBuilder {
+    /// Collect the symbols in a syntax node and its descendants.
+    fn visit(&mut self, node: &SyntaxNode) {
+        if let Some(heading) = node.cast::<ast::Heading>() {
+            let level = heading.level();
+            self.close(level);
+            let name = plain_text(heading.body().to_untyped());
+            let symbol = Symbol {
+                name,
+                kind: SymbolKind::Heading(level),
+                span: node.span(),
+                children: vec![],
+            };
+            self.stack.push((level, symbol));
+        } else if let Some(label) = node.cast::<ast::Label>() {
+            self.push(Symbol {
+                name: label.get().into(),
+                kind: SymbolKind::Label,
+                span: node.span(),
+                children: vec![],
+            });
+        } else if let Some(call) = node.cast::<ast::FuncCall>() {
+            if let ast::Expr::Ident(ident) = call.callee() {
+                if ident.as_str() == "figure" {
+                    let caption = call.args().items().find_map(|arg| match arg {
+                        ast::Arg::Named(named) if named.name().as_str() == "caption" => {
+                            Some(match named.expr() {
+                                ast::Expr::Str(string) => string.get(),
+                                expr => plain_text(expr.to_untyped()),
+                            })
+                        }
+                        _ => None,
+                    });
+                    self.push(Symbol {
+                        name: caption.unwrap_or_else(|| "figure".into()),
+                        kind: SymbolKind::Figure,
+                        span: node.span(),
+                        children: vec![],
+                    });
+                }
+            }
+        }
+
+        for child in node.children() {
+            self.visit(child);
+        }
+    }
+
+    /// Add a symbol to the innermost open section.
+    fn push(&mut self, symbol: Symbol) {
+        match self.stack.last_mut() {
+            Some((_, heading)) => heading.children.push(symbol),
+            None => self.output.push(symbol),
+        }
+    }
+
+    /// Close all sections whose level is at least `level`.
+    fn close(&mut self, level: NonZeroUsize) {
+        while self.stack.last().is_some_and(|&(open, _)| open >= level) {
+            let (_, heading) = self.stack.pop().unwrap();
+            self.push(heading);
+        }
+    }
+
+    /// Close all remaining sections and return the outline.
+    fn finish(mut self) -> Vec<Symbol> {
+        self.close(NonZeroUsize::new(1).unwrap());
+        self.output
+    }
+}
+
+/// The plain text of a syntax node without markup and labels.
+fn plain_text(node: &SyntaxNode) -> EcoString {
+    fn collect(node: &SyntaxNode, text: &mut EcoString) {
+        match node.kind() {
+            SyntaxKind::Label
+            | SyntaxKind::LeftBracket
+            | SyntaxKind::RightBracket
+            | SyntaxKind::Star
+            | SyntaxKind::Underscore => {}
+            SyntaxKind::Space | SyntaxKind::Parbreak => text.push(' '),
+            _ if node.children().next().is_none() => text.push_str(node.text()),
+            _ => node.children().for_each(|child| collect(child, text)),
+        }
+    }
+
+    let mut text = EcoString::new();
+    collect(node, &mut text);
+    text.split_whitespace().collect::<Vec<_>>().join(" ").into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The outline's symbols in depth-first order with their nesting depth.
+    fn flatten(text: &str) -> Vec<(usize, EcoString, SymbolKind)> {
+        fn walk(
+            symbols: &[Symbol],
+            depth: usize,
+            out: &mut Vec<(usize, EcoString, SymbolKind)>,
+        ) {
+            for symbol in symbols {
+                out.push((depth, symbol.name.clone(), symbol.kind));
+                walk(&symbol.children, depth + 1, out);
+            }
+        }
+
+        let mut out = vec![];
+        walk(&outline(&Source::detached(text)), 0, &mut out);
+        out
+    }
+
+    fn sym(depth: usize, name: &str, kind: SymbolKind) -> (usize, EcoString, SymbolKind) {
+        (depth, name.into(), kind)
+    }
+
+    fn level(n: usize) -> SymbolKind {
+        SymbolKind::Heading(NonZeroUsize::new(n).unwrap())
+    }
+
+    #[test]
+    fn test_outline_nests_symbols_in_sections() {
+        let text = "= Intro\n\
+                    Some text <start>\n\
+                    == Details\n\
+                    #figure([A], caption: [A *bold* box])\n\
+                    = Outro\n\
+                    #figure(rect(), caption: \"Plain\")\n\
+                    #figure(rect())";
+        assert_eq!(
+            flatten(text),
+            [
+                sym(0, "Intro", level(1)),
+                sym(1, "start", SymbolKind::Label),
+                sym(1, "Details", level(2)),
+                sym(2, "A bold box", SymbolKind::Figure),
+                sym(0, "Outro", level(1)),
+                sym(1, "Plain", SymbolKind::Figure),
+                sym(1, "figure", SymbolKind::Figure),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outline_closes_sections_by_level() {
+        let text = "== Late start\n= Top\n=== Deep\n== Mid\n= Next";
+        assert_eq!(
+            flatten(text),
+            [
+                sym(0, "Late start", level(2)),
+                sym(0, "Top", level(1)),
+                sym(1, "Deep", level(3)),
+                sym(1, "Mid", level(2)),
+                sym(0, "Next", level(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outline_heading_names_and_spans() {
+        let source = Source::detached("Text\n\n= A _styled_ heading <sec>\n");
+        let symbols = outline(&source);
+        assert_eq!(symbols.len(), 1);
+
+        // The label is excluded from the name, but nested as a symbol.
+        let heading = &symbols[0];
+        assert_eq!(heading.name, "A styled heading");
+        assert_eq!(heading.children.len(), 1);
+        assert_eq!(heading.children[0].name, "sec");
+
+        // The span points to the heading in the source.
+        let range = source.range(heading.span).unwrap();
+        assert!(source.text()[range].starts_with("= A _styled_"));
+    }
+
+    #[test]
+    fn test_outline_of_empty_source() {
+        assert!(flatten("").is_empty());
+        assert!(flatten("Just a paragraph.").is_empty());
+    }
+}
@@ -903,6 +903,35 @@ fn create_native_elem_impl(element: &Elem) -> TokenStream {
         )
         .unwrap_or_else(|| quote! { None });
 
+    let field_from_styles_arms = element
+        .settable_fields()
+        .filter(|field| !field.internal)
+        .map(|field| {
+            let Field { ty, enum_ident, .. } = field;
+            let elem = quote! { <#ident as #foundations::NativeElement>::elem() };
+
+            // Folded properties combine the values of all set rules with the
+            // default, so that they are always known.
+            let access = if field.fold {
+                let getter = if field.resolve {
+                    quote! { get_resolve_fold }
+                } else {
+                    quote! { get_fold }
+                };
+                let default = field.default.clone().unwrap_or_else(
+                    || parse_quote! { ::std::default::Default::default() },
+                );
+                quote! { Some(styles.#getter::<#ty>(#elem, id, None, || #default)) }
+            } else {
+                quote! { styles.properties::<#ty>(#elem, id, None).next().cloned() }
+            };
+
+            quote! {
+                <#ident as #foundations::ElementFields>::Fields::#enum_ident => #access
+                    .map(#foundations::IntoValue::into_value),
+            }
+        });
+
     let unknown_field = format!("unknown field {{}} on {}", name);
     let label_error = format!("cannot set label on {}", name);
     let data = quote! {
@@ -922,6 +951,15 @@ fn create_native_elem_impl(element: &Elem) -> TokenStream {
                 <
                     <#ident as #foundations::ElementFields>::Fields as ::std::convert::TryFrom<u8>
                 >::try_from(id).ok().map(<#ident as #foundations::ElementFields>::Fields::to_str),
+            field_from_styles: |id, styles| {
+                let field = <
+                    <#ident as #foundations::ElementFields>::Fields as ::std::convert::TryFrom<u8>
+                >::try_from(id).ok()?;
+                match field {
+                    #(#field_from_styles_arms)*
+                    _ => None,
+                }
+            },
             local_name: #local_name,
             scope: #foundations::Lazy::new(|| #scope),
             params: #foundations::Lazy::new(|| ::std::vec![#(#params),*])
@@ -1104,11 +1142,28 @@ fn create_set_impl(element: &Elem) -> TokenStream {
     let handlers = element.settable_fields().map(|field| {
         let set_ident = &field.set_ident;
         let (prefix, value) = create_field_parser(field);
-        quote! {
+        let handler = quote! {
             #prefix
             if let Some(value) = #value {
                 styles.set(Self::#set_ident(value));
             }
+        };
+
+        if field.positional || field.internal {
+            return handler;
+        }
+
+        let name = &field.name;
+        let enum_ident = &field.enum_ident;
+        quote! {
+            if args.revert(#name) {
+                styles.set(#foundations::Property::revert(
+                    <Self as #foundations::NativeElement>::elem(),
+                    <#ident as #foundations::ElementFields>::Fields::#enum_ident as u8,
+                ));
+            } else {
+                #handler
+            }
         }
     });
 
@@ -39,6 +39,16 @@ static HSV_DEFLATED: Lazy<Vec<u8>> =
 static HSL_DEFLATED: Lazy<Vec<u8>> =
     Lazy::new(|| deflate(minify(include_str!("postscript/hsl.ps")).as_bytes()));
 
+/// Write the sRGB ICC profile that serves as the output profile of a PDF/A
+/// file.
+pub fn write_output_profile(chunk: &mut Chunk, id: Ref) {
+    chunk
+        .icc_profile(id, &SRGB_ICC_DEFLATED)
+        .n(3)
+        .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
+        .filter(Filter::FlateDecode);
+}
+
 /// The color spaces present in the PDF document
 #[derive(Default)]
 pub struct ColorSpaces {
@@ -286,7 +296,7 @@ pub(super) trait PaintEncode {
     fn set_as_fill(&self, ctx: &mut PageContext, on_text: bool, transforms: Transforms);
 
     /// Set the paint as the stroke color.
-    fn set_as_stroke(&self, ctx: &mut PageContext, transforms: Transforms);
+    fn set_as_stroke(&self, ctx: &mut PageContext, on_text: bool, transforms: Transforms);
 }
 
 impl PaintEncode for Paint {
@@ -298,18 +308,28 @@ impl PaintEncode for Paint {
         }
     }
 
-    fn set_as_stroke(&self, ctx: &mut PageContext, transforms: Transforms) {
+    fn set_as_stroke(
+        &self,
+        ctx: &mut PageContext,
+        on_text: bool,
+        transforms: Transforms,
+    ) {
         match self {
-            Self::Solid(c) => c.set_as_stroke(ctx, transforms),
-            Self::Gradient(gradient) => gradient.set_as_stroke(ctx, transforms),
-            Self::Pattern(pattern) => pattern.set_as_stroke(ctx, transforms),
+            Self::Solid(c) => c.set_as_stroke(ctx, on_text, transforms),
+            Self::Gradient(gradient) => gradient.set_as_stroke(ctx, on_text, transforms),
+            Self::Pattern(pattern) => pattern.set_as_stroke(ctx, on_text, transforms),
         }
     }
 }
 
 impl PaintEncode for Color {
-    fn set_as_fill(&self, ctx: &mut PageContext, _: bool, _: Transforms) {
+    fn set_as_fill(&self, ctx: &mut PageContext, on_text: bool, transforms: Transforms) {
         match self {
+            // PDF/A forbids device colors that don't match the sRGB output
+            // intent, so CMYK is converted to sRGB.
+            Color::Cmyk(_) if ctx.parent.pdfa().is_some() => {
+                self.to_rgb().set_as_fill(ctx, on_text, transforms);
+            }
             Color::Luma(_) => {
                 ctx.parent.colors.d65_gray(&mut ctx.parent.alloc);
                 ctx.set_fill_color_space(D65_GRAY);
@@ -362,8 +382,16 @@ impl PaintEncode for Color {
         }
     }
 
-    fn set_as_stroke(&self, ctx: &mut PageContext, _: Transforms) {
+    fn set_as_stroke(
+        &self,
+        ctx: &mut PageContext,
+        on_text: bool,
+        transforms: Transforms,
+    ) {
         match self {
+            Color::Cmyk(_) if ctx.parent.pdfa().is_some() => {
+                self.to_rgb().set_as_stroke(ctx, on_text, transforms);
+            }
             Color::Luma(_) => {
                 ctx.parent.colors.d65_gray(&mut ctx.parent.alloc);
                 ctx.set_stroke_color_space(D65_GRAY);
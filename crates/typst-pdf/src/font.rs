@@ -6,7 +6,7 @@ use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap};
 use pdf_writer::{Filter, Finish, Name, Rect, Str};
 use ttf_parser::{name_id, GlyphId, Tag};
 use typst::text::Font;
-use typst::util::SliceExt;
+use typst::util::{Deferred, SliceExt};
 use unicode_properties::{GeneralCategory, UnicodeGeneralCategory};
 
 use crate::{deflate, EmExt, PdfContext};
@@ -23,7 +23,19 @@ const SYSTEM_INFO: SystemInfo = SystemInfo {
 /// Embed all used fonts into the PDF.
 #[tracing::instrument(skip_all)]
 pub(crate) fn write_fonts(ctx: &mut PdfContext) {
-    for font in ctx.font_map.items() {
+    // Subsetting is the most expensive part of font embedding and independent
+    // for each font, so we kick it off for all fonts in parallel.
+    let subsets: Vec<_> = ctx
+        .font_map
+        .items()
+        .map(|font| {
+            let font = font.clone();
+            let glyphs: Vec<_> = ctx.glyph_sets[&font].keys().copied().collect();
+            Deferred::new(move || subset_font(&font, &glyphs))
+        })
+        .collect();
+
+    for (font, subset) in ctx.font_map.items().zip(subsets) {
         let type0_ref = ctx.alloc.bump();
         let cid_ref = ctx.alloc.bump();
         let descriptor_ref = ctx.alloc.bump();
@@ -149,11 +161,9 @@ pub(crate) fn write_fonts(ctx: &mut PdfContext) {
         let cmap = create_cmap(ttf, glyph_set);
         ctx.pdf.cmap(cmap_ref, &cmap.finish());
 
-        // Subset and write the font's bytes.
-        let glyphs: Vec<_> = glyph_set.keys().copied().collect();
-        let data = subset_font(font, &glyphs);
-
-        let mut stream = ctx.pdf.stream(data_ref, &data);
+        // Write the subsetted font's bytes.
+        let data = subset.wait();
+        let mut stream = ctx.pdf.stream(data_ref, data);
         stream.filter(Filter::FlateDecode);
         if is_cff {
             stream.pair(Name(b"Subtype"), Name(b"CIDFontType0C"));
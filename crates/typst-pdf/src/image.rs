@@ -1,29 +1,78 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::Cursor;
 
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
 use image::{DynamicImage, GenericImageView, Rgba};
 use pdf_writer::{Chunk, Filter, Finish, Ref};
+use typst::layout::Size;
 use typst::util::Deferred;
-use typst::visualize::{
-    ColorSpace, Image, ImageKind, RasterFormat, RasterImage, SvgImage,
-};
+use typst::visualize::{ColorSpace, Image, ImageKind, RasterFormat, SvgImage};
 
 use crate::{deflate, PdfContext};
 
+/// Options for embedding raster images into the PDF.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ImageOptions {
+    /// The maximum resolution of embedded raster images in pixels per inch.
+    ///
+    /// Images with a higher resolution at their largest placed size are
+    /// downsampled. If `None`, images are embedded at their full resolution.
+    pub max_ppi: Option<f32>,
+    /// The quality (between 1 and 100) at which JPEG images are recompressed.
+    ///
+    /// If `None`, a default quality is used.
+    pub jpeg_quality: Option<u8>,
+}
+
+impl ImageOptions {
+    /// The pixel dimensions an image should be embedded at, given the largest
+    /// size it is placed at in the document. Returns `None` if the image should
+    /// keep its original dimensions.
+    fn target(&self, image: &Image, size: Size) -> Option<(u32, u32)> {
+        let ppi = f64::from(self.max_ppi?);
+        let (width, height) = (f64::from(image.width()), f64::from(image.height()));
+
+        // Scale such that the resolution is reached along both axes.
+        let scale =
+            (size.x.to_inches() * ppi / width).max(size.y.to_inches() * ppi / height);
+        if !scale.is_finite() || scale <= 0.0 || scale >= 1.0 {
+            return None;
+        }
+
+        let target = ((width * scale).ceil() as u32, (height * scale).ceil() as u32);
+        Some((target.0.max(1), target.1.max(1)))
+    }
+}
+
 /// Creates a new PDF image from the given image.
 ///
-/// Also starts the deferred encoding of the image.
+/// Also starts the deferred encoding of the image. Raster images are resized to
+/// the `target` pixel dimensions if given.
 #[comemo::memoize]
-pub fn deferred_image(image: Image) -> Deferred<EncodedImage> {
+pub fn deferred_image(
+    image: Image,
+    target: Option<(u32, u32)>,
+    quality: Option<u8>,
+) -> Deferred<EncodedImage> {
     Deferred::new(move || match image.kind() {
         ImageKind::Raster(raster) => {
-            let raster = raster.clone();
-            let (width, height) = (image.width(), image.height());
-            let (data, filter, has_color) = encode_raster_image(&raster);
-            let icc = raster.icc().map(deflate);
+            let format = raster.format();
+            let dynamic = match target {
+                Some((width, height)) => Cow::Owned(raster.dynamic().resize_exact(
+                    width,
+                    height,
+                    FilterType::Lanczos3,
+                )),
+                None => Cow::Borrowed(raster.dynamic()),
+            };
 
-            let alpha =
-                raster.dynamic().color().has_alpha().then(|| encode_alpha(&raster));
+            let (width, height) = dynamic.dimensions();
+            let (data, filter, has_color) =
+                encode_raster_image(format, &dynamic, quality);
+            let icc = raster.icc().map(deflate);
+            let alpha = dynamic.color().has_alpha().then(|| encode_alpha(&dynamic));
 
             EncodedImage::Raster { data, filter, has_color, width, height, icc, alpha }
         }
@@ -34,6 +83,16 @@ pub fn deferred_image(image: Image) -> Deferred<EncodedImage> {
 /// Embed all used images into the PDF.
 #[tracing::instrument(skip_all)]
 pub(crate) fn write_images(ctx: &mut PdfContext) {
+    // Start the encoding of images that were not encoded eagerly because
+    // their largest size was not known until all pages were constructed.
+    let options = ctx.image_options;
+    for (i, image) in ctx.image_map.items().enumerate() {
+        ctx.image_deferred_map.entry(i).or_insert_with(|| {
+            let target = options.target(image, ctx.image_sizes[&i]);
+            deferred_image(image.clone(), target, options.jpeg_quality)
+        });
+    }
+
     for (i, _) in ctx.image_map.items().enumerate() {
         let handle = ctx.image_deferred_map.get(&i).unwrap();
         match handle.wait() {
@@ -112,21 +171,20 @@ pub(crate) fn write_images(ctx: &mut PdfContext) {
 ///
 /// Skips the alpha channel as that's encoded separately.
 #[tracing::instrument(skip_all)]
-fn encode_raster_image(image: &RasterImage) -> (Vec<u8>, Filter, bool) {
-    let dynamic = image.dynamic();
-    match (image.format(), dynamic) {
+fn encode_raster_image(
+    format: RasterFormat,
+    dynamic: &DynamicImage,
+    quality: Option<u8>,
+) -> (Vec<u8>, Filter, bool) {
+    match (format, dynamic) {
         // 8-bit gray JPEG.
         (RasterFormat::Jpg, DynamicImage::ImageLuma8(_)) => {
-            let mut data = Cursor::new(vec![]);
-            dynamic.write_to(&mut data, image::ImageFormat::Jpeg).unwrap();
-            (data.into_inner(), Filter::DctDecode, false)
+            (encode_jpeg(dynamic, quality), Filter::DctDecode, false)
         }
 
         // 8-bit RGB JPEG (CMYK JPEGs get converted to RGB earlier).
         (RasterFormat::Jpg, DynamicImage::ImageRgb8(_)) => {
-            let mut data = Cursor::new(vec![]);
-            dynamic.write_to(&mut data, image::ImageFormat::Jpeg).unwrap();
-            (data.into_inner(), Filter::DctDecode, true)
+            (encode_jpeg(dynamic, quality), Filter::DctDecode, true)
         }
 
         // TODO: Encode flate streams with PNG-predictor?
@@ -153,14 +211,24 @@ fn encode_raster_image(image: &RasterImage) -> (Vec<u8>, Filter, bool) {
     }
 }
 
+/// Encode an image as a JPEG with the given quality.
+fn encode_jpeg(dynamic: &DynamicImage, quality: Option<u8>) -> Vec<u8> {
+    let mut data = Cursor::new(vec![]);
+    match quality {
+        Some(quality) => {
+            JpegEncoder::new_with_quality(&mut data, quality.clamp(1, 100))
+                .encode_image(dynamic)
+                .unwrap();
+        }
+        None => dynamic.write_to(&mut data, image::ImageFormat::Jpeg).unwrap(),
+    }
+    data.into_inner()
+}
+
 /// Encode an image's alpha channel if present.
 #[tracing::instrument(skip_all)]
-fn encode_alpha(raster: &RasterImage) -> (Vec<u8>, Filter) {
-    let pixels: Vec<_> = raster
-        .dynamic()
-        .pixels()
-        .map(|(_, _, Rgba([_, _, _, a]))| a)
-        .collect();
+fn encode_alpha(dynamic: &DynamicImage) -> (Vec<u8>, Filter) {
+    let pixels: Vec<_> = dynamic.pixels().map(|(_, _, Rgba([_, _, _, a]))| a).collect();
     (deflate(&pixels), Filter::FlateDecode)
 }
 
@@ -8,6 +8,9 @@ mod image;
 mod outline;
 mod page;
 mod pattern;
+mod tags;
+
+pub use self::image::ImageOptions;
 
 use std::cmp::Eq;
 use std::collections::{BTreeMap, HashMap};
@@ -16,16 +19,19 @@ use std::sync::Arc;
 
 use base64::Engine;
 use ecow::{eco_format, EcoString};
-use pdf_writer::types::Direction;
+use pdf_writer::types::{Direction, OutputIntentSubtype};
+use pdf_writer::writers::OutputIntent;
 use pdf_writer::{Finish, Name, Pdf, Ref, TextStr};
+use ttf_parser::Permissions;
+use typst::diag::{bail, StrResult};
 use typst::foundations::Datetime;
 use typst::introspection::Introspector;
-use typst::layout::{Abs, Dir, Em, Transform};
-use typst::model::Document;
+use typst::layout::{Abs, Dir, Em, Frame, Size, Transform};
+use typst::model::{Document, DocumentInfo};
 use typst::text::{Font, Lang};
 use typst::util::Deferred;
-use typst::visualize::Image;
-use xmp_writer::{DateTime, LangId, RenditionClass, Timezone, XmpWriter};
+use typst::visualize::{ColorSpace, Image};
+use xmp_writer::{DateTime, LangId, Namespace, RenditionClass, Timezone, XmpWriter};
 
 use crate::color::ColorSpaces;
 use crate::extg::ExtGState;
@@ -33,6 +39,11 @@ use crate::gradient::PdfGradient;
 use crate::image::EncodedImage;
 use crate::page::Page;
 use crate::pattern::PdfPattern;
+use crate::tags::StructTree;
+
+/// The XMP namespace that identifies the part of the PDF/UA standard.
+const PDFUA_ID: Namespace =
+    Namespace::Custom(("pdfuaid", "http://www.aiim.org/pdfua/ns/id/"));
 
 /// Export a document into a PDF file.
 ///
@@ -54,29 +65,176 @@ pub fn pdf(
     ident: Option<&str>,
     timestamp: Option<Datetime>,
 ) -> Vec<u8> {
-    let mut ctx = PdfContext::new(document);
-    page::construct_pages(&mut ctx, &document.pages);
-    font::write_fonts(&mut ctx);
-    image::write_images(&mut ctx);
-    gradient::write_gradients(&mut ctx);
-    extg::write_external_graphics_states(&mut ctx);
-    pattern::write_patterns(&mut ctx);
-    page::write_page_tree(&mut ctx);
-    write_catalog(&mut ctx, ident, timestamp);
-    ctx.pdf.finish()
+    let mut exporter = PdfExporter::new();
+    for frame in &document.pages {
+        exporter.push_page(frame);
+    }
+    exporter
+        .finish(&document.info, ident, timestamp)
+        .expect("export without a standard should not fail")
+}
+
+/// A standard that an exported PDF file conforms to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PdfStandard {
+    /// PDF/A-2b, for long-term archival of the document's visual appearance.
+    A2b,
+    /// PDF/A-3b, which is like PDF/A-2b, but also allows embedded files.
+    A3b,
+    /// PDF/UA-1, for accessibility of the document's content.
+    Ua1,
+}
+
+impl PdfStandard {
+    /// The part of the PDF/A standard, as recorded in the XMP metadata, or
+    /// `None` if this is not a PDF/A standard.
+    fn pdfa_part(self) -> Option<&'static str> {
+        match self {
+            Self::A2b => Some("2"),
+            Self::A3b => Some("3"),
+            Self::Ua1 => None,
+        }
+    }
+
+    /// The name of the standard, for error messages.
+    fn name(self) -> &'static str {
+        match self {
+            Self::A2b => "PDF/A-2b",
+            Self::A3b => "PDF/A-3b",
+            Self::Ua1 => "PDF/UA-1",
+        }
+    }
+}
+
+/// Rasterizes a frame at the given resolution in pixels per point.
+///
+/// PDF cannot express some effects, like blurs, natively. Content with such
+/// effects is rasterized with this function.
+pub type Rasterizer = fn(&Frame, f32) -> Option<Image>;
+
+/// Exports a document into a PDF file page by page.
+///
+/// In contrast to [`pdf`], this does not require all pages at once. The content
+/// of each page is encoded and written as soon as the page is pushed, so the
+/// caller is free to drop the page's frame afterwards. This keeps the peak
+/// memory usage low for documents with many pages.
+pub struct PdfExporter {
+    ctx: PdfContext,
+}
+
+impl PdfExporter {
+    /// Create a new exporter without any pages.
+    pub fn new() -> Self {
+        Self { ctx: PdfContext::new() }
+    }
+
+    /// Configure how raster images are embedded.
+    pub fn with_image_options(mut self, options: ImageOptions) -> Self {
+        self.ctx.image_options = options;
+        self
+    }
+
+    /// Rasterize content that PDF cannot express with the given function.
+    ///
+    /// Without a rasterizer, such content is written without the effect that
+    /// PDF cannot express.
+    pub fn with_rasterizer(mut self, rasterizer: Rasterizer) -> Self {
+        self.ctx.rasterizer = Some(rasterizer);
+        self
+    }
+
+    /// Make the exported file conform to a standard. Can be called multiple
+    /// times to conform to a PDF/A standard and PDF/UA at once.
+    ///
+    /// For PDF/A, this embeds an sRGB output intent, converts CMYK colors to
+    /// sRGB, and identifies the standard in the XMP metadata. Exporting fails
+    /// if the document uses fonts that must not be embedded, characters that
+    /// are missing from their font, or gradients in the CMYK color space.
+    ///
+    /// For PDF/UA, the pages are written with the structure tree built from
+    /// the tags in the frames. The document must thus be compiled with
+    /// [`Tracer::record_tags`](typst::eval::Tracer::record_tags). Exporting
+    /// fails if the frames are untagged, the document has no title, or an
+    /// image has no alternative description.
+    pub fn with_standard(mut self, standard: PdfStandard) -> Self {
+        if !self.ctx.standards.contains(&standard) {
+            self.ctx.standards.push(standard);
+        }
+        self
+    }
+
+    /// Resolve links into the other documents of a project.
+    ///
+    /// Documents compiled together with
+    /// [`compile_project`](typst::compile_project) can link into each other.
+    /// Pass all documents of the project in the order they were compiled, each
+    /// with the name of the file it is exported to. Links into another document
+    /// then open that file at the linked position. The name is written as is,
+    /// so it should be relative to the exported file, e.g. just the file name
+    /// if all documents end up in the same directory.
+    ///
+    /// Without this, links into other documents are left out.
+    pub fn with_project(mut self, documents: &[(EcoString, &Document)]) -> Self {
+        self.ctx.project = documents
+            .iter()
+            .map(|(name, document)| {
+                (name.clone(), document.pages.iter().map(Frame::height).collect())
+            })
+            .collect();
+        self
+    }
+
+    /// Encode the next page of the document.
+    #[tracing::instrument(skip_all)]
+    pub fn push_page(&mut self, frame: &Frame) {
+        self.ctx.introspector.push_page(frame);
+        page::push_page(&mut self.ctx, frame);
+    }
+
+    /// Finish the PDF file with the given metadata and return its raw bytes.
+    ///
+    /// See [`pdf`] for the meaning of `ident` and `timestamp`.
+    ///
+    /// Fails if the document can't be exported in conformance with the
+    /// [standard](Self::with_standard).
+    #[tracing::instrument(skip_all)]
+    pub fn finish(
+        mut self,
+        info: &DocumentInfo,
+        ident: Option<&str>,
+        timestamp: Option<Datetime>,
+    ) -> StrResult<Vec<u8>> {
+        let ctx = &mut self.ctx;
+        for &standard in &ctx.standards {
+            validate(ctx, info, standard)?;
+        }
+
+        font::write_fonts(ctx);
+        image::write_images(ctx);
+        gradient::write_gradients(ctx);
+        extg::write_external_graphics_states(ctx);
+        pattern::write_patterns(ctx);
+        page::write_page_tree(ctx);
+        write_catalog(ctx, info, ident, timestamp);
+        Ok(self.ctx.pdf.finish())
+    }
+}
+
+impl Default for PdfExporter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Context for exporting a whole PDF document.
-struct PdfContext<'a> {
-    /// The document that we're currently exporting.
-    document: &'a Document,
+struct PdfContext {
     /// An introspector for the document, used to resolve locations links and
     /// the document outline.
     introspector: Introspector,
 
     /// The writer we are writing the PDF into.
     pdf: Pdf,
-    /// Content of exported pages.
+    /// Exported pages.
     pages: Vec<Page>,
     /// For each font a mapping from used glyphs to their text representation.
     /// May contain multiple chars in case of ligatures or similar things. The
@@ -88,6 +246,8 @@ struct PdfContext<'a> {
     /// The number of glyphs for all referenced languages in the document.
     /// We keep track of this to determine the main document language.
     languages: HashMap<Lang, usize>,
+    /// The logical structure of the document.
+    struct_tree: StructTree,
 
     /// Allocator for indirect reference IDs.
     alloc: Ref,
@@ -95,6 +255,8 @@ struct PdfContext<'a> {
     page_tree_ref: Ref,
     /// The IDs of written pages.
     page_refs: Vec<Ref>,
+    /// The IDs of the written pages' content streams.
+    content_refs: Vec<Ref>,
     /// The IDs of written fonts.
     font_refs: Vec<Ref>,
     /// The IDs of written images.
@@ -114,28 +276,40 @@ struct PdfContext<'a> {
     image_map: Remapper<Image>,
     /// Handles to deferred image conversions.
     image_deferred_map: HashMap<usize, Deferred<EncodedImage>>,
+    /// The largest size each image is placed at.
+    image_sizes: HashMap<usize, Size>,
+    /// How to embed raster images.
+    image_options: ImageOptions,
+    /// Rasterizes content that PDF cannot express.
+    rasterizer: Option<Rasterizer>,
     /// Deduplicates gradients used across the document.
     gradient_map: Remapper<PdfGradient>,
     /// Deduplicates patterns used across the document.
     pattern_map: Remapper<PdfPattern>,
     /// Deduplicates external graphics states used across the document.
     extg_map: Remapper<ExtGState>,
+    /// The standards the file conforms to.
+    standards: Vec<PdfStandard>,
+    /// The file names and page heights of the documents of the project, for
+    /// links into other documents.
+    project: Vec<(EcoString, Vec<Abs>)>,
 }
 
-impl<'a> PdfContext<'a> {
-    fn new(document: &'a Document) -> Self {
+impl PdfContext {
+    fn new() -> Self {
         let mut alloc = Ref::new(1);
         let page_tree_ref = alloc.bump();
         Self {
-            document,
-            introspector: Introspector::new(&document.pages),
+            introspector: Introspector::default(),
             pdf: Pdf::new(),
             pages: vec![],
             glyph_sets: HashMap::new(),
             languages: HashMap::new(),
+            struct_tree: StructTree::default(),
             alloc,
             page_tree_ref,
             page_refs: vec![],
+            content_refs: vec![],
             font_refs: vec![],
             image_refs: vec![],
             gradient_refs: vec![],
@@ -145,16 +319,102 @@ impl<'a> PdfContext<'a> {
             font_map: Remapper::new(),
             image_map: Remapper::new(),
             image_deferred_map: HashMap::default(),
+            image_sizes: HashMap::default(),
+            image_options: ImageOptions::default(),
+            rasterizer: None,
             gradient_map: Remapper::new(),
             pattern_map: Remapper::new(),
             extg_map: Remapper::new(),
+            standards: vec![],
+            project: vec![],
+        }
+    }
+
+    /// The PDF/A standard the file conforms to, if any.
+    fn pdfa(&self) -> Option<PdfStandard> {
+        self.standards
+            .iter()
+            .copied()
+            .find(|standard| standard.pdfa_part().is_some())
+    }
+
+    /// Whether the pages are written with tags for PDF/UA.
+    fn tagged(&self) -> bool {
+        self.standards.contains(&PdfStandard::Ua1)
+    }
+}
+
+/// Check that the document can be exported in conformance with a standard.
+fn validate(
+    ctx: &PdfContext,
+    info: &DocumentInfo,
+    standard: PdfStandard,
+) -> StrResult<()> {
+    if standard == PdfStandard::Ua1 {
+        if !ctx.struct_tree.is_tagged() {
+            bail!(
+                "{} requires a tagged document, but the document was compiled without recording tags",
+                standard.name(),
+            );
+        }
+
+        if info.title.is_none() {
+            bail!("{} requires the document to have a title", standard.name());
+        }
+
+        if ctx.struct_tree.missing_alt() {
+            bail!(
+                "{} requires all images to have an alternative description",
+                standard.name(),
+            );
+        }
+
+        return Ok(());
+    }
+
+    // Solid CMYK colors are converted to sRGB, but gradients are interpolated
+    // in their color space and can't be converted.
+    if ctx
+        .gradient_map
+        .items()
+        .any(|pdf_gradient| pdf_gradient.gradient.space() == ColorSpace::Cmyk)
+    {
+        bail!(
+            "{} forbids gradients in the CMYK color space, as it only allows sRGB colors",
+            standard.name(),
+        );
+    }
+
+    for font in ctx.font_map.items() {
+        let family = &font.info().family;
+        if font.ttf().permissions() == Some(Permissions::Restricted) {
+            bail!(
+                "{} requires all fonts to be embedded, but the license of font `{family}` forbids it",
+                standard.name(),
+            );
+        }
+
+        // Glyph zero is the `.notdef` glyph, which is used for characters
+        // that are missing from the font.
+        if ctx.glyph_sets[font].contains_key(&0) {
+            bail!(
+                "{} forbids the `.notdef` glyph, but font `{family}` is missing some characters of the text",
+                standard.name(),
+            );
         }
     }
+
+    Ok(())
 }
 
 /// Write the document catalog.
 #[tracing::instrument(skip_all)]
-fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Datetime>) {
+fn write_catalog(
+    ctx: &mut PdfContext,
+    document: &DocumentInfo,
+    ident: Option<&str>,
+    timestamp: Option<Datetime>,
+) {
     let lang = ctx
         .languages
         .iter()
@@ -173,33 +433,44 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
     // Write the page labels.
     let page_labels = page::write_page_labels(ctx);
 
+    // Write the structure tree.
+    let struct_tree_root = tags::write_struct_tree(ctx);
+
     // Write the document information.
     let mut info = ctx.pdf.document_info(ctx.alloc.bump());
     let mut xmp = XmpWriter::new();
-    if let Some(title) = &ctx.document.title {
+    if let Some(title) = &document.title {
         info.title(TextStr(title));
         xmp.title([(None, title.as_str())]);
     }
 
-    let authors = &ctx.document.author;
+    let authors = &document.author;
     if !authors.is_empty() {
-        info.author(TextStr(&authors.join(", ")));
-        xmp.creator(authors.iter().map(|s| s.as_str()));
+        let joined = authors.join(", ");
+        info.author(TextStr(&joined));
+
+        // PDF/A requires the author in the document information to match the
+        // XMP metadata, so we can only write a single creator.
+        if ctx.pdfa().is_some() {
+            xmp.creator([joined.as_str()]);
+        } else {
+            xmp.creator(authors.iter().map(|s| s.as_str()));
+        }
     }
 
     let creator = eco_format!("Typst {}", env!("CARGO_PKG_VERSION"));
     info.creator(TextStr(&creator));
     xmp.creator_tool(&creator);
 
-    let keywords = &ctx.document.keywords;
+    let keywords = &document.keywords;
     if !keywords.is_empty() {
         let joined = keywords.join(", ");
         info.keywords(TextStr(&joined));
         xmp.pdf_keywords(&joined);
     }
 
-    if let Some(date) = ctx.document.date.unwrap_or(timestamp) {
-        let tz = ctx.document.date.is_auto();
+    if let Some(date) = document.date.unwrap_or(timestamp) {
+        let tz = document.date.is_auto();
         if let Some(pdf_date) = pdf_date(date, tz) {
             info.creation_date(pdf_date);
             info.modified_date(pdf_date);
@@ -211,7 +482,7 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
     }
 
     info.finish();
-    xmp.num_pages(ctx.document.pages.len() as u32);
+    xmp.num_pages(ctx.pages.len() as u32);
     xmp.format("application/pdf");
     xmp.language(ctx.languages.keys().map(|lang| LangId(lang.as_str())));
 
@@ -235,6 +506,15 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
     xmp.rendition_class(RenditionClass::Proof);
     xmp.pdf_version("1.7");
 
+    if let Some(part) = ctx.pdfa().and_then(PdfStandard::pdfa_part) {
+        xmp.pdfa_part(part);
+        xmp.pdfa_conformance("B");
+    }
+
+    if ctx.tagged() {
+        xmp.element("part", PDFUA_ID).value(1_i64);
+    }
+
     let xmp_buf = xmp.finish(None);
     let meta_ref = ctx.alloc.bump();
     ctx.pdf
@@ -242,12 +522,46 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
         .pair(Name(b"Type"), Name(b"Metadata"))
         .pair(Name(b"Subtype"), Name(b"XML"));
 
+    // PDF/A requires an output intent that defines how colors are meant to
+    // look. We use sRGB, which all our colors can be converted into.
+    let output_profile = ctx.pdfa().map(|_| {
+        let id = ctx.alloc.bump();
+        color::write_output_profile(&mut ctx.pdf, id);
+        id
+    });
+
     // Write the document catalog.
     let mut catalog = ctx.pdf.catalog(ctx.alloc.bump());
     catalog.pages(ctx.page_tree_ref);
-    catalog.viewer_preferences().direction(dir);
+
+    let mut preferences = catalog.viewer_preferences();
+    preferences.direction(dir);
+    if document.title.is_some() {
+        preferences.pair(Name(b"DisplayDocTitle"), true);
+    }
+    preferences.finish();
+
     catalog.metadata(meta_ref);
 
+    if let Some(profile) = output_profile {
+        catalog
+            .insert(Name(b"OutputIntents"))
+            .array()
+            .push()
+            .start::<OutputIntent>()
+            .subtype(OutputIntentSubtype::PDFA)
+            .output_condition(TextStr("sRGB"))
+            .output_condition_identifier(TextStr("sRGB IEC61966-2.1"))
+            .registry_name(TextStr("http://www.color.org"))
+            .dest_output_profile(profile);
+    }
+
+    // Mark the document as tagged.
+    if let Some(struct_tree_root) = struct_tree_root {
+        catalog.pair(Name(b"StructTreeRoot"), struct_tree_root);
+        catalog.mark_info().marked(true);
+    }
+
     // Insert the page labels.
     if !page_labels.is_empty() {
         let mut num_tree = catalog.page_labels();
@@ -1,20 +1,21 @@
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
+use std::ops::Range;
 
 use ecow::{eco_format, EcoString};
 use pdf_writer::types::{
-    ActionType, AnnotationType, ColorSpaceOperand, LineCapStyle, LineJoinStyle,
-    NumberingStyle,
+    ActionType, AnnotationFlags, AnnotationType, ColorSpaceOperand, LineCapStyle,
+    LineJoinStyle, NumberingStyle, TabOrder, TextRenderingMode,
 };
-use pdf_writer::writers::PageLabel;
+use pdf_writer::writers::{Annotation, PageLabel};
 use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str, TextStr};
 use typst::introspection::Meta;
 use typst::layout::{
     Abs, Em, Frame, FrameItem, GroupItem, PdfPageLabel, PdfPageLabelStyle, Point, Ratio,
-    Size, Transform,
+    Size, Tag, Transform,
 };
 use typst::model::Destination;
-use typst::text::{Font, TextItem};
+use typst::text::{Font, Glyph, TextItem};
 use typst::util::Numeric;
 use typst::visualize::{
     FixedStroke, Geometry, Image, LineCap, LineJoin, Paint, Path, PathItem, Shape,
@@ -23,26 +24,45 @@ use typst::visualize::{
 use crate::color::PaintEncode;
 use crate::extg::ExtGState;
 use crate::image::deferred_image;
+use crate::tags::{Leaf, PageTags};
 use crate::{deflate_memoized, AbsExt, EmExt, PdfContext};
 
-/// Construct page objects.
+/// The resolution at which blurred content is rasterized.
+const BLUR_PIXEL_PER_PT: f32 = 3.0;
+
+/// Construct the page object for the next page of the document.
+///
+/// The page's content stream is written right away so that it doesn't need to
+/// be kept in memory until the end of the export.
 #[tracing::instrument(skip_all)]
-pub(crate) fn construct_pages(ctx: &mut PdfContext, frames: &[Frame]) {
-    for frame in frames {
-        let (page_ref, page) = construct_page(ctx, frame);
-        ctx.page_refs.push(page_ref);
-        ctx.pages.push(page);
-    }
+pub(crate) fn push_page(ctx: &mut PdfContext, frame: &Frame) {
+    let tagged = ctx.tagged();
+    let (page_ref, mut page) = construct_page(ctx, frame, tagged);
+    let content_ref = ctx.alloc.bump();
+    let data = deflate_memoized(&std::mem::take(&mut page.content));
+    ctx.pdf.stream(content_ref, &data).filter(Filter::FlateDecode);
+    ctx.page_refs.push(page_ref);
+    ctx.content_refs.push(content_ref);
+    ctx.pages.push(page);
 }
 
 /// Construct a page object.
+///
+/// If `tagged` is true, the page's contents are marked up and added to the
+/// document's structure tree.
 #[tracing::instrument(skip_all)]
-pub(crate) fn construct_page(ctx: &mut PdfContext, frame: &Frame) -> (Ref, Page) {
+pub(crate) fn construct_page(
+    ctx: &mut PdfContext,
+    frame: &Frame,
+    tagged: bool,
+) -> (Ref, Page) {
     let page_ref = ctx.alloc.bump();
+    let tags = tagged.then(|| ctx.struct_tree.start_page());
 
     let mut ctx = PageContext {
         parent: ctx,
         page_ref,
+        tags,
         label: None,
         uses_opacities: false,
         content: Content::new(),
@@ -145,7 +165,7 @@ pub(crate) fn write_page_tree(ctx: &mut PdfContext) {
 #[tracing::instrument(skip_all)]
 fn write_page(ctx: &mut PdfContext, i: usize) {
     let page = &ctx.pages[i];
-    let content_id = ctx.alloc.bump();
+    let content_id = ctx.content_refs[i];
 
     let mut page_writer = ctx.pdf.page(page.id);
     page_writer.parent(ctx.page_tree_ref);
@@ -155,6 +175,11 @@ fn write_page(ctx: &mut PdfContext, i: usize) {
     page_writer.media_box(Rect::new(0.0, 0.0, w, h));
     page_writer.contents(content_id);
 
+    if !ctx.struct_tree.is_empty() {
+        page_writer.struct_parents(i as i32);
+        page_writer.tab_order(TabOrder::StructureOrder);
+    }
+
     if page.uses_opacities {
         page_writer
             .group()
@@ -165,12 +190,29 @@ fn write_page(ctx: &mut PdfContext, i: usize) {
             .srgb();
     }
 
-    let mut annotations = page_writer.annotations();
-    for (dest, rect) in &page.links {
-        let mut annotation = annotations.push();
+    page_writer
+        .insert(Name(b"Annots"))
+        .array()
+        .items(page.links.iter().map(|&(_, _, id, _)| id));
+    page_writer.finish();
+
+    // Annotations are written as indirect objects so that tagged links can
+    // reference them from the structure tree.
+    let pdfa = ctx.pdfa().is_some();
+    for (dest, rect, id, tagged) in &page.links {
+        let mut annotation = ctx.pdf.indirect(*id).start::<Annotation>();
         annotation.subtype(AnnotationType::Link).rect(*rect);
         annotation.border(0.0, 0.0, 0.0, None);
 
+        // PDF/A requires annotations to be printed.
+        if pdfa {
+            annotation.flags(AnnotationFlags::PRINT);
+        }
+
+        if let Some(index) = *tagged {
+            annotation.struct_parent(ctx.struct_tree.link_key(index));
+        }
+
         let pos = match dest {
             Destination::Url(uri) => {
                 annotation
@@ -181,6 +223,26 @@ fn write_page(ctx: &mut PdfContext, i: usize) {
             }
             Destination::Position(pos) => *pos,
             Destination::Location(loc) => ctx.introspector.position(*loc),
+            Destination::Document(index, pos) => {
+                // Links into unknown documents were already left out.
+                let (file, heights) = &ctx.project[*index];
+                let index = pos.page.get() - 1;
+                let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
+                let height = heights.get(index).copied().unwrap_or_default();
+                let mut action = annotation.action();
+                action.action_type(ActionType::RemoteGoTo);
+                action.file_spec().path(Str(file.as_bytes()));
+
+                // The target page is given by its index since it isn't part
+                // of this file.
+                let mut dest = action.insert(Name(b"D")).array();
+                dest.item(index as i32);
+                dest.item(Name(b"XYZ"));
+                dest.item(pos.point.x.to_f32());
+                dest.item((height - y).to_f32());
+                dest.item(0.0_f32);
+                continue;
+            }
         };
 
         let index = pos.page.get() - 1;
@@ -194,12 +256,6 @@ fn write_page(ctx: &mut PdfContext, i: usize) {
                 .xyz(pos.point.x.to_f32(), (page.size.y - y).to_f32(), None);
         }
     }
-
-    annotations.finish();
-    page_writer.finish();
-
-    let data = deflate_memoized(&page.content);
-    ctx.pdf.stream(content_id, &data).filter(Filter::FlateDecode);
 }
 
 /// Write the page labels.
@@ -257,12 +313,14 @@ pub struct Page {
     pub id: Ref,
     /// The page's dimensions.
     pub size: Size,
-    /// The page's content stream.
+    /// The page's content stream. Empty for pages of the document, whose
+    /// content streams are written as soon as they are constructed.
     pub content: Vec<u8>,
     /// Whether the page uses opacities.
     pub uses_opacities: bool,
-    /// Links in the PDF coordinate system.
-    pub links: Vec<(Destination, Rect)>,
+    /// Links in the PDF coordinate system, with the IDs of their annotations
+    /// and their indices among the tagged links.
+    pub links: Vec<(Destination, Rect, Ref, Option<usize>)>,
     /// The page's PDF label.
     pub label: Option<PdfPageLabel>,
     /// The page's used resources
@@ -325,16 +383,18 @@ impl PageResource {
 }
 
 /// An exporter for the contents of a single PDF page.
-pub struct PageContext<'a, 'b> {
-    pub(crate) parent: &'a mut PdfContext<'b>,
+pub struct PageContext<'a> {
+    pub(crate) parent: &'a mut PdfContext,
     page_ref: Ref,
+    /// The open structure elements, if the page is tagged.
+    tags: Option<PageTags>,
     label: Option<PdfPageLabel>,
     pub content: Content,
     state: State,
     saves: Vec<State>,
     bottom: f32,
     uses_opacities: bool,
-    links: Vec<(Destination, Rect)>,
+    links: Vec<(Destination, Rect, Ref, Option<usize>)>,
     /// Keep track of the resources being used in the page.
     pub resources: HashMap<PageResource, usize>,
 }
@@ -397,7 +457,7 @@ pub(super) struct Transforms {
     pub size: Size,
 }
 
-impl PageContext<'_, '_> {
+impl PageContext<'_> {
     fn save_state(&mut self) {
         self.saves.push(self.state.clone());
         self.content.save_state();
@@ -485,7 +545,7 @@ impl PageContext<'_, '_> {
 
     fn set_fill(&mut self, fill: &Paint, on_text: bool, transforms: Transforms) {
         if self.state.fill.as_ref() != Some(fill)
-            || matches!(self.state.fill, Some(Paint::Gradient(_)))
+            || matches!(self.state.fill, Some(Paint::Gradient(_) | Paint::Pattern(_)))
         {
             fill.set_as_fill(self, on_text, transforms);
             self.state.fill = Some(fill.clone());
@@ -503,11 +563,16 @@ impl PageContext<'_, '_> {
         self.state.fill_space = None;
     }
 
-    fn set_stroke(&mut self, stroke: &FixedStroke, transforms: Transforms) {
+    fn set_stroke(
+        &mut self,
+        stroke: &FixedStroke,
+        on_text: bool,
+        transforms: Transforms,
+    ) {
         if self.state.stroke.as_ref() != Some(stroke)
             || matches!(
                 self.state.stroke.as_ref().map(|s| &s.paint),
-                Some(Paint::Gradient(_))
+                Some(Paint::Gradient(_) | Paint::Pattern(_))
             )
         {
             let FixedStroke {
@@ -519,7 +584,7 @@ impl PageContext<'_, '_> {
                 miter_limit,
             } = stroke;
 
-            paint.set_as_stroke(self, transforms);
+            paint.set_as_stroke(self, on_text, transforms);
 
             self.content.set_line_width(thickness.to_f32());
             if self.state.stroke.as_ref().map(|s| &s.line_cap) != Some(line_cap) {
@@ -555,6 +620,36 @@ impl PageContext<'_, '_> {
     pub fn reset_stroke_color_space(&mut self) {
         self.state.stroke_space = None;
     }
+
+    fn open_tag(&mut self, tag: &Tag) {
+        if let Some(tags) = &mut self.tags {
+            tags.open(&mut self.parent.struct_tree, tag);
+        }
+    }
+
+    fn close_tag(&mut self) {
+        if let Some(tags) = &mut self.tags {
+            tags.close();
+        }
+    }
+
+    /// Start a marked content sequence for a leaf item. Returns whether a
+    /// sequence was started, which is the case if the page is tagged.
+    fn begin_leaf(&mut self, leaf: Leaf) -> bool {
+        let Some(tags) = &mut self.tags else { return false };
+        match tags.mark(&mut self.parent.struct_tree, leaf) {
+            Some((kind, mcid)) => {
+                self.content
+                    .begin_marked_content_with_properties(kind.name())
+                    .properties()
+                    .identify(mcid);
+            }
+            None => {
+                self.content.begin_marked_content(Name(b"Artifact"));
+            }
+        }
+        true
+    }
 }
 
 /// Encode a frame into the content stream.
@@ -571,8 +666,11 @@ fn write_frame(ctx: &mut PageContext, frame: &Frame) {
             FrameItem::Meta(meta, size) => match meta {
                 Meta::Link(dest) => write_link(ctx, pos, dest, *size),
                 Meta::Elem(_) => {}
+                Meta::Parent { .. } => {}
+                Meta::Origin { .. } => {}
                 Meta::Hide => {}
                 Meta::PageNumbering(_) => {}
+                Meta::PageMetadata(_) => {}
                 Meta::PdfPageLabel(label) => ctx.label = Some(label.clone()),
             },
         }
@@ -581,6 +679,12 @@ fn write_frame(ctx: &mut PageContext, frame: &Frame) {
 
 /// Encode a group into the content stream.
 fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
+    if let Some(radius) = group.blur {
+        if write_blurred_group(ctx, pos, group, radius).is_some() {
+            return;
+        }
+    }
+
     let translation = Transform::translate(pos.x, pos.y);
 
     ctx.save_state();
@@ -603,10 +707,44 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
         ctx.content.end_path();
     }
 
+    if let Some(tag) = &group.tag {
+        ctx.open_tag(tag);
+    }
+
     write_frame(ctx, &group.frame);
+
+    if group.tag.is_some() {
+        ctx.close_tag();
+    }
+
     ctx.restore_state();
 }
 
+/// Encode a blurred group into the content stream.
+///
+/// PDF cannot express blurs, so the group is rasterized into an image if the
+/// exporter has a [rasterizer](crate::Rasterizer). Otherwise, or if
+/// rasterization fails, the group is written without the blur.
+fn write_blurred_group(
+    ctx: &mut PageContext,
+    pos: Point,
+    group: &GroupItem,
+    radius: Abs,
+) -> Option<()> {
+    let rasterize = ctx.parent.rasterizer?;
+
+    // Leave room for the blur to extend beyond the group's bounds.
+    let margin = radius * 3.0;
+    let mut frame = Frame::soft(group.frame.size() + Size::splat(2.0 * margin));
+    frame.push(Point::splat(margin), FrameItem::Group(group.clone()));
+
+    let image = rasterize(&frame, BLUR_PIXEL_PER_PT)?;
+    let x = (pos.x - margin).to_f32();
+    let y = (pos.y - margin).to_f32();
+    write_image(ctx, x, y, &image, frame.size());
+    Some(())
+}
+
 /// Encode a text run into the content stream.
 fn write_text(ctx: &mut PageContext, pos: Point, text: &TextItem) {
     let x = pos.x.to_f32();
@@ -621,21 +759,66 @@ fn write_text(ctx: &mut PageContext, pos: Point, text: &TextItem) {
     }
 
     ctx.set_fill(&text.fill, true, ctx.state.transforms(Size::zero(), pos));
+    if let Some(stroke) = &text.stroke {
+        ctx.set_stroke(stroke, true, ctx.state.transforms(Size::zero(), pos));
+    }
     ctx.set_font(&text.font, text.size);
-    ctx.set_opacities(None, Some(&text.fill));
+    ctx.set_opacities(text.stroke.as_ref(), Some(&text.fill));
+    let marked = ctx.begin_leaf(Leaf::Text);
     ctx.content.begin_text();
 
+    // Stroked text is both filled and stroked.
+    if text.stroke.is_some() {
+        ctx.content.set_text_rendering_mode(TextRenderingMode::FillStroke);
+    }
+
     // Positiosn the text.
     ctx.content.set_text_matrix([1.0, 0.0, 0.0, -1.0, x, y]);
 
+    // Write the glyphs segment by segment. Segments whose text can't be
+    // recovered from the font's character map are wrapped in a marked
+    // content sequence with an `/ActualText` entry.
+    let mut adjustment = Em::zero();
+    for (glyphs, actual) in segments(text) {
+        if let Some(actual) = actual {
+            ctx.content
+                .begin_marked_content_with_properties(Name(b"Span"))
+                .properties()
+                .actual_text(TextStr(actual));
+        }
+
+        write_glyphs(ctx, text, glyphs, &mut adjustment);
+
+        if actual.is_some() {
+            ctx.content.end_marked_content();
+        }
+    }
+
+    // The rendering mode is part of the graphics state, so reset it.
+    if text.stroke.is_some() {
+        ctx.content.set_text_rendering_mode(TextRenderingMode::Fill);
+    }
+
+    ctx.content.end_text();
+
+    if marked {
+        ctx.content.end_marked_content();
+    }
+}
+
+/// Write glyphs with kerning adjustments.
+fn write_glyphs(
+    ctx: &mut PageContext,
+    text: &TextItem,
+    glyphs: &[Glyph],
+    adjustment: &mut Em,
+) {
     let mut positioned = ctx.content.show_positioned();
     let mut items = positioned.items();
-    let mut adjustment = Em::zero();
     let mut encoded = vec![];
 
-    // Write the glyphs with kerning adjustments.
-    for glyph in &text.glyphs {
-        adjustment += glyph.x_offset;
+    for glyph in glyphs {
+        *adjustment += glyph.x_offset;
 
         if !adjustment.is_zero() {
             if !encoded.is_empty() {
@@ -644,7 +827,7 @@ fn write_text(ctx: &mut PageContext, pos: Point, text: &TextItem) {
             }
 
             items.adjust(-adjustment.to_font_units());
-            adjustment = Em::zero();
+            *adjustment = Em::zero();
         }
 
         let cid = crate::font::glyph_cid(&text.font, glyph.id);
@@ -652,10 +835,10 @@ fn write_text(ctx: &mut PageContext, pos: Point, text: &TextItem) {
         encoded.push((cid & 0xff) as u8);
 
         if let Some(advance) = text.font.advance(glyph.id) {
-            adjustment += glyph.x_advance - advance;
+            *adjustment += glyph.x_advance - advance;
         }
 
-        adjustment -= glyph.x_offset;
+        *adjustment -= glyph.x_offset;
     }
 
     if !encoded.is_empty() {
@@ -664,7 +847,52 @@ fn write_text(ctx: &mut PageContext, pos: Point, text: &TextItem) {
 
     items.finish();
     positioned.finish();
-    ctx.content.end_text();
+}
+
+/// Split the glyphs of a text item into segments.
+///
+/// A cluster of glyphs needs explicit actual text if it isn't a single glyph
+/// that the font's character map assigns to the cluster's single character.
+/// This is the case for ligatures, small capitals and other substituted
+/// glyphs, as well as for hyphens inserted at line breaks, whose actual text
+/// is empty. Consecutive clusters of the same kind are merged into one
+/// segment.
+fn segments(text: &TextItem) -> Vec<(&[Glyph], Option<&str>)> {
+    let ttf = text.font.ttf();
+    let mut segments: Vec<(Range<usize>, Range<usize>, bool)> = vec![];
+
+    let mut i = 0;
+    while i < text.glyphs.len() {
+        let range = text.glyphs[i].range();
+        let len = text.glyphs[i..].iter().take_while(|g| g.range() == range).count();
+
+        let mut chars = text.text[range.clone()].chars();
+        let mapped = len == 1
+            && match (chars.next(), chars.next()) {
+                (Some(c), None) => {
+                    ttf.glyph_index(c).map(|id| id.0) == Some(text.glyphs[i].id)
+                }
+                _ => false,
+            };
+
+        match segments.last_mut() {
+            Some((glyphs, chars, actual)) if *actual != mapped => {
+                glyphs.end = i + len;
+                chars.start = chars.start.min(range.start);
+                chars.end = chars.end.max(range.end);
+            }
+            _ => segments.push((i..i + len, range, !mapped)),
+        }
+
+        i += len;
+    }
+
+    segments
+        .into_iter()
+        .map(|(glyphs, chars, actual)| {
+            (&text.glyphs[glyphs], actual.then(|| &text.text[chars]))
+        })
+        .collect()
 }
 
 /// Encode a geometrical shape into the content stream.
@@ -689,10 +917,20 @@ fn write_shape(ctx: &mut PageContext, pos: Point, shape: &Shape) {
     }
 
     if let Some(stroke) = stroke {
-        ctx.set_stroke(stroke, ctx.state.transforms(shape.geometry.bbox_size(), pos));
+        // Lines have a zero-sized bounding box in one dimension, which would
+        // make the transform of a gradient or pattern stroke degenerate.
+        let mut size = shape.geometry.bbox_size();
+        if size.x.to_pt() == 0.0 {
+            size.x = Abs::pt(1.0);
+        }
+        if size.y.to_pt() == 0.0 {
+            size.y = Abs::pt(1.0);
+        }
+        ctx.set_stroke(stroke, false, ctx.state.transforms(size, pos));
     }
 
     ctx.set_opacities(stroke, shape.fill.as_ref());
+    let marked = ctx.begin_leaf(Leaf::Shape);
 
     match shape.geometry {
         Geometry::Line(target) => {
@@ -719,6 +957,10 @@ fn write_shape(ctx: &mut PageContext, pos: Point, shape: &Shape) {
         (None, Some(_)) => ctx.content.stroke(),
         (Some(_), Some(_)) => ctx.content.fill_nonzero_and_stroke(),
     };
+
+    if marked {
+        ctx.content.end_marked_content();
+    }
 }
 
 /// Encode a bezier path into the content stream.
@@ -747,10 +989,18 @@ fn write_path(ctx: &mut PageContext, x: f32, y: f32, path: &Path) {
 /// Encode a vector or raster image into the content stream.
 fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size) {
     let index = ctx.parent.image_map.insert(image.clone());
-    ctx.parent
-        .image_deferred_map
-        .entry(index)
-        .or_insert_with(|| deferred_image(image.clone()));
+    let largest = ctx.parent.image_sizes.entry(index).or_insert(size);
+    *largest = (*largest).max(size);
+
+    // If images aren't downsampled, their encoding doesn't depend on the size
+    // they are placed at, so we can start it right away.
+    let options = ctx.parent.image_options;
+    if options.max_ppi.is_none() {
+        ctx.parent
+            .image_deferred_map
+            .entry(index)
+            .or_insert_with(|| deferred_image(image.clone(), None, options.jpeg_quality));
+    }
 
     let name = eco_format!("Im{index}");
     let w = size.x.to_f32();
@@ -758,7 +1008,11 @@ fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size)
     ctx.content.save_state();
     ctx.content.transform([w, 0.0, 0.0, -h, x, y + h]);
 
-    if let Some(alt) = image.alt() {
+    if ctx.begin_leaf(Leaf::Image(image.alt())) {
+        // The alternate description is part of the structure tree.
+        ctx.content.x_object(Name(name.as_bytes()));
+        ctx.content.end_marked_content();
+    } else if let Some(alt) = image.alt() {
         let mut image_span =
             ctx.content.begin_marked_content_with_properties(Name(b"Span"));
         let mut image_alt = image_span.properties();
@@ -779,6 +1033,18 @@ fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size)
 
 /// Save a link for later writing in the annotations dictionary.
 fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size) {
+    // Links into other documents can only be written if it is known which
+    // files those documents end up in.
+    if let Destination::Document(index, _) = dest {
+        if *index >= ctx.parent.project.len() {
+            tracing::warn!(
+                "leaving out link into document {index} of the project \
+                 since its file is unknown"
+            );
+            return;
+        }
+    }
+
     let mut min_x = Abs::inf();
     let mut min_y = Abs::inf();
     let mut max_x = -Abs::inf();
@@ -804,7 +1070,14 @@ fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size)
     let y2 = min_y.to_f32();
     let rect = Rect::new(x1, y1, x2, y2);
 
-    ctx.links.push((dest.clone(), rect));
+    // Links are tagged with the structure element they are part of.
+    let id = ctx.parent.alloc.bump();
+    let tagged = match &mut ctx.tags {
+        Some(tags) => tags.link(&mut ctx.parent.struct_tree, id),
+        None => None,
+    };
+
+    ctx.links.push((dest.clone(), rect, id, tagged));
 }
 
 fn to_pdf_line_cap(cap: LineCap) -> LineCapStyle {
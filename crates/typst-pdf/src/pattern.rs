@@ -113,7 +113,7 @@ fn register_pattern(
     };
 
     // Render the body.
-    let (_, content) = construct_page(ctx.parent, pattern.frame());
+    let (_, content) = construct_page(ctx.parent, pattern.frame(), false);
 
     let pdf_pattern = PdfPattern {
         transform,
@@ -139,10 +139,15 @@ impl PaintEncode for Pattern {
             .insert(PageResource::new(ResourceKind::Pattern, id), index);
     }
 
-    fn set_as_stroke(&self, ctx: &mut PageContext, transforms: Transforms) {
+    fn set_as_stroke(
+        &self,
+        ctx: &mut PageContext,
+        on_text: bool,
+        transforms: Transforms,
+    ) {
         ctx.reset_stroke_color_space();
 
-        let index = register_pattern(ctx, self, false, transforms);
+        let index = register_pattern(ctx, self, on_text, transforms);
         let id = eco_format!("P{index}");
         let name = Name(id.as_bytes());
 
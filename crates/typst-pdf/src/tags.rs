@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+use pdf_writer::types::StructRole;
+use pdf_writer::writers::StructTreeRoot;
+use pdf_writer::{Finish, Name, Ref, TextStr};
+use typst::introspection::Location;
+use typst::layout::{Role, Tag};
+
+use crate::PdfContext;
+
+/// The logical structure of the document, collected while its pages are
+/// written.
+#[derive(Default)]
+pub(crate) struct StructTree {
+    /// All structure elements of the document.
+    elems: Vec<StructElem>,
+    /// The top-level structure elements.
+    roots: Vec<usize>,
+    /// The structure element of each tag location. Parts of an element that
+    /// are spread across pages are thereby merged into one.
+    located: HashMap<Location, usize>,
+    /// For each page, the structure element each marked content id belongs
+    /// to.
+    pages: Vec<Vec<usize>>,
+    /// The link structure element of each tagged link annotation.
+    links: Vec<usize>,
+    /// Whether any page contained tagged groups.
+    tagged: bool,
+    /// Whether an image without an alternate description was tagged.
+    missing_alt: bool,
+}
+
+impl StructTree {
+    /// Whether the tree has any structure elements.
+    pub fn is_empty(&self) -> bool {
+        self.elems.is_empty()
+    }
+
+    /// Whether the frames contained tagged groups. Frames are only tagged if
+    /// the document was compiled with tags being recorded.
+    pub fn is_tagged(&self) -> bool {
+        self.tagged
+    }
+
+    /// Whether an image without an alternate description was tagged.
+    pub fn missing_alt(&self) -> bool {
+        self.missing_alt
+    }
+
+    /// The key of a tagged link annotation in the parent tree. The keys
+    /// following the ones of the pages are used for annotations.
+    pub fn link_key(&self, index: usize) -> i32 {
+        (self.pages.len() + index) as i32
+    }
+
+    /// Start tracking the structure of the next page.
+    pub fn start_page(&mut self) -> PageTags {
+        self.pages.push(vec![]);
+        PageTags {
+            page: self.pages.len() - 1,
+            stack: vec![],
+            loose: None,
+        }
+    }
+
+    /// Add a new structure element.
+    fn push(&mut self, kind: Kind, parent: Option<usize>) -> usize {
+        let elem = self.elems.len();
+        self.elems
+            .push(StructElem { kind, parent, kids: vec![], alt: None, row: None });
+        match parent {
+            Some(parent) => self.elems[parent].kids.push(StructKid::Elem(elem)),
+            None => self.roots.push(elem),
+        }
+        elem
+    }
+
+    /// The structure element for a tag, creating it if it doesn't exist yet.
+    fn locate(&mut self, tag: &Tag, kind: Kind, parent: Option<usize>) -> usize {
+        if let Some(&elem) = self.located.get(&tag.location) {
+            return elem;
+        }
+
+        let elem = self.push(kind, parent);
+        self.located.insert(tag.location, elem);
+        elem
+    }
+
+    /// The row of a table, creating it if it doesn't exist yet.
+    fn row(&mut self, table: usize, row: usize) -> usize {
+        if self.elems[table].kind != Kind::Table {
+            return table;
+        }
+
+        let existing = self.elems[table].kids.iter().rev().find_map(|kid| match *kid {
+            StructKid::Elem(elem) if self.elems[elem].row == Some(row) => Some(elem),
+            _ => None,
+        });
+
+        existing.unwrap_or_else(|| {
+            let elem = self.push(Kind::TableRow, Some(table));
+            self.elems[elem].row = Some(row);
+            elem
+        })
+    }
+
+    /// Assign the next marked content id of a page to a structure element.
+    fn mark(&mut self, page: usize, elem: usize) -> (Kind, i32) {
+        let mcids = &mut self.pages[page];
+        let mcid = mcids.len() as i32;
+        mcids.push(elem);
+        self.elems[elem].kids.push(StructKid::Content { page, mcid });
+        (self.elems[elem].kind, mcid)
+    }
+}
+
+/// A structure element.
+struct StructElem {
+    /// The structure type of the element.
+    kind: Kind,
+    /// The parent element, if this is not a top-level element.
+    parent: Option<usize>,
+    /// The child elements and marked content sequences, in reading order.
+    kids: Vec<StructKid>,
+    /// An alternate description of the element.
+    alt: Option<EcoString>,
+    /// For a table row, its index in the table.
+    row: Option<usize>,
+}
+
+/// A child of a structure element.
+enum StructKid {
+    /// Another structure element.
+    Elem(usize),
+    /// A marked content sequence on a page.
+    Content { page: usize, mcid: i32 },
+    /// An annotation on a page.
+    Annotation { page: usize, annot: Ref },
+}
+
+/// The structure type of an element.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Kind {
+    Paragraph,
+    Heading(NonZeroUsize),
+    Figure,
+    Table,
+    TableRow,
+    TableCell,
+    Link,
+    /// Untagged content at the top level.
+    Div,
+}
+
+impl Kind {
+    /// The structure type for a tag's role or `None` for artifacts.
+    fn of(role: Role) -> Option<Self> {
+        Some(match role {
+            Role::Paragraph => Self::Paragraph,
+            Role::Heading(level) => Self::Heading(level),
+            Role::Figure => Self::Figure,
+            Role::Table => Self::Table,
+            Role::TableCell { .. } => Self::TableCell,
+            Role::Artifact => return None,
+        })
+    }
+
+    /// The standard structure role.
+    fn role(self) -> StructRole {
+        match self {
+            Self::Paragraph => StructRole::P,
+            Self::Heading(level) => match level.get() {
+                1 => StructRole::H1,
+                2 => StructRole::H2,
+                3 => StructRole::H3,
+                4 => StructRole::H4,
+                5 => StructRole::H5,
+                _ => StructRole::H6,
+            },
+            Self::Figure => StructRole::Figure,
+            Self::Table => StructRole::Table,
+            Self::TableRow => StructRole::TR,
+            Self::TableCell => StructRole::TD,
+            Self::Link => StructRole::Link,
+            Self::Div => StructRole::Div,
+        }
+    }
+
+    /// The tag of marked content sequences that belong to this structure
+    /// type.
+    pub fn name(self) -> Name<'static> {
+        let name: &'static [u8] = match self {
+            Self::Paragraph => b"P",
+            Self::Heading(level) => match level.get() {
+                1 => b"H1",
+                2 => b"H2",
+                3 => b"H3",
+                4 => b"H4",
+                5 => b"H5",
+                _ => b"H6",
+            },
+            Self::Figure => b"Figure",
+            Self::Table => b"Table",
+            Self::TableRow => b"TR",
+            Self::TableCell => b"TD",
+            Self::Link => b"Link",
+            Self::Div => b"Div",
+        };
+        Name(name)
+    }
+}
+
+/// A drawable item on a page that is part of a marked content sequence.
+pub(crate) enum Leaf<'a> {
+    /// A run of text.
+    Text,
+    /// An image with an optional alternate description.
+    Image(Option<&'a str>),
+    /// A geometric shape. Shapes are always treated as decoration.
+    Shape,
+}
+
+/// Tracks the structure elements that are open while a page is written.
+pub(crate) struct PageTags {
+    /// The index of the page.
+    page: usize,
+    /// The structure elements of the tagged groups we are in. `None` marks an
+    /// artifact.
+    stack: Vec<Option<usize>>,
+    /// The element that collects untagged content at the top level.
+    loose: Option<usize>,
+}
+
+impl PageTags {
+    /// Enter a tagged group.
+    pub fn open(&mut self, tree: &mut StructTree, tag: &Tag) {
+        tree.tagged = true;
+        let parent = match self.stack.last() {
+            Some(None) => {
+                self.stack.push(None);
+                return;
+            }
+            Some(Some(elem)) => Some(*elem),
+            None => {
+                self.loose = None;
+                None
+            }
+        };
+
+        let elem = match tag.role {
+            // Paragraphs that make up the text of a heading or are nested in
+            // another paragraph don't get an element of their own.
+            Role::Paragraph
+                if parent.is_some_and(|parent| {
+                    matches!(tree.elems[parent].kind, Kind::Paragraph | Kind::Heading(_))
+                }) =>
+            {
+                parent
+            }
+            Role::TableCell { row } => {
+                let parent = parent.map(|table| tree.row(table, row));
+                Some(tree.locate(tag, Kind::TableCell, parent))
+            }
+            role => Kind::of(role).map(|kind| tree.locate(tag, kind, parent)),
+        };
+
+        self.stack.push(elem);
+    }
+
+    /// Leave the innermost tagged group.
+    pub fn close(&mut self) {
+        self.stack.pop();
+    }
+
+    /// Determine the structure type and marked content id of a leaf item or
+    /// `None` if it is an artifact.
+    pub fn mark(&mut self, tree: &mut StructTree, leaf: Leaf) -> Option<(Kind, i32)> {
+        let parent = match self.stack.last() {
+            Some(None) => return None,
+            Some(Some(elem)) => Some(*elem),
+            None => None,
+        };
+
+        let elem = match leaf {
+            Leaf::Shape => return None,
+            Leaf::Image(alt) => {
+                tree.missing_alt |= alt.is_none();
+                match parent {
+                    Some(elem)
+                        if tree.elems[elem].kind == Kind::Figure
+                            && tree.elems[elem].alt.is_none() =>
+                    {
+                        tree.elems[elem].alt = alt.map(Into::into);
+                        elem
+                    }
+                    _ => {
+                        let elem = tree.push(Kind::Figure, parent);
+                        tree.elems[elem].alt = alt.map(Into::into);
+                        elem
+                    }
+                }
+            }
+            Leaf::Text => match parent {
+                Some(elem) => elem,
+                None => *self.loose.get_or_insert_with(|| tree.push(Kind::Div, None)),
+            },
+        };
+
+        Some(tree.mark(self.page, elem))
+    }
+
+    /// Add a link annotation to the innermost structure element. Returns the
+    /// index of the annotation among the tagged ones or `None` if the link is
+    /// part of an artifact.
+    pub fn link(&mut self, tree: &mut StructTree, annot: Ref) -> Option<usize> {
+        let parent = match self.stack.last() {
+            Some(None) => return None,
+            Some(Some(elem)) => Some(*elem),
+            None => None,
+        };
+
+        let elem = tree.push(Kind::Link, parent);
+        tree.elems[elem]
+            .kids
+            .push(StructKid::Annotation { page: self.page, annot });
+        tree.links.push(elem);
+        Some(tree.links.len() - 1)
+    }
+}
+
+/// Write the structure tree and return the reference of its root, if the
+/// document has any structure.
+#[tracing::instrument(skip_all)]
+pub(crate) fn write_struct_tree(ctx: &mut PdfContext) -> Option<Ref> {
+    let tree = &ctx.struct_tree;
+    if tree.is_empty() {
+        return None;
+    }
+
+    let root_ref = ctx.alloc.bump();
+    let refs: Vec<Ref> = tree.elems.iter().map(|_| ctx.alloc.bump()).collect();
+
+    for (elem, &id) in tree.elems.iter().zip(&refs) {
+        let mut writer = ctx.pdf.struct_element(id);
+        writer.kind(elem.kind.role());
+        writer.parent(elem.parent.map_or(root_ref, |parent| refs[parent]));
+        if let Some(alt) = &elem.alt {
+            writer.alt(TextStr(alt));
+        }
+
+        let mut kids = writer.children();
+        for kid in &elem.kids {
+            match *kid {
+                StructKid::Elem(kid) => {
+                    kids.struct_element(refs[kid]);
+                }
+                StructKid::Content { page, mcid } => {
+                    kids.marked_content_ref()
+                        .page(ctx.page_refs[page])
+                        .marked_content_id(mcid);
+                }
+                StructKid::Annotation { page, annot } => {
+                    kids.object_ref().page(ctx.page_refs[page]).object(annot);
+                }
+            }
+        }
+    }
+
+    // The parent tree maps each page's marked content ids and each tagged
+    // annotation back to the structure elements they belong to.
+    let mut parents = vec![];
+    for mcids in &tree.pages {
+        let id = ctx.alloc.bump();
+        ctx.pdf
+            .indirect(id)
+            .array()
+            .items(mcids.iter().map(|&elem| refs[elem]));
+        parents.push(id);
+    }
+
+    let mut root = ctx.pdf.indirect(root_ref).start::<StructTreeRoot>();
+    root.children().items(tree.roots.iter().map(|&elem| refs[elem]));
+
+    let mut parent_tree = root.parent_tree();
+    let mut nums = parent_tree.nums();
+    for (i, &id) in parents.iter().enumerate() {
+        nums.insert(i as i32, id);
+    }
+    for (i, &elem) in tree.links.iter().enumerate() {
+        nums.insert(tree.link_key(i), refs[elem]);
+    }
+    nums.finish();
+    parent_tree.finish();
+
+    root.parent_tree_next_key(tree.link_key(tree.links.len()));
+    root.finish();
+
+    Some(root_ref)
+}
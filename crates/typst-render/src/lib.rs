@@ -164,7 +164,10 @@ fn render_frame(canvas: &mut sk::Pixmap, state: State, frame: &Frame) {
             FrameItem::Meta(meta, _) => match meta {
                 Meta::Link(_) => {}
                 Meta::Elem(_) => {}
+                Meta::Parent { .. } => {}
+                Meta::Origin { .. } => {}
                 Meta::PageNumbering(_) => {}
+                Meta::PageMetadata(_) => {}
                 Meta::PdfPageLabel(_) => {}
                 Meta::Hide => {}
             },
@@ -227,9 +230,131 @@ fn render_group(canvas: &mut sk::Pixmap, state: State, pos: Point, group: &Group
         }
     }
 
+    if let Some(radius) = group.blur {
+        render_blurred_group(canvas, state, mask, group, radius);
+        return;
+    }
+
     render_frame(canvas, state.with_mask(mask), &group.frame);
 }
 
+/// Render a blurred group into the canvas.
+///
+/// The group is rendered into a separate layer that just covers its bounds and
+/// the extent of the blur. The layer is then blurred and composited onto the
+/// canvas.
+fn render_blurred_group(
+    canvas: &mut sk::Pixmap,
+    state: State,
+    mask: Option<&sk::Mask>,
+    group: &GroupItem,
+    radius: Abs,
+) -> Option<()> {
+    let ts = state.transform;
+    let scale = (ts.sx * ts.sy - ts.kx * ts.ky).abs().sqrt();
+    let sigma = radius.to_f32() * scale;
+
+    // Beyond three standard deviations, a Gaussian blur is negligible.
+    let size = group.frame.size();
+    let rect = sk::Rect::from_xywh(0.0, 0.0, size.x.to_f32(), size.y.to_f32())?;
+    let bounds = sk::PathBuilder::from_rect(rect).transform(ts)?.bounds();
+    let extent = 3.0 * sigma;
+    let x0 = (bounds.left() - extent).floor().max(0.0) as u32;
+    let y0 = (bounds.top() - extent).floor().max(0.0) as u32;
+    let x1 = ((bounds.right() + extent).ceil() as u32).min(canvas.width());
+    let y1 = ((bounds.bottom() + extent).ceil() as u32).min(canvas.height());
+    let mut layer = sk::Pixmap::new(x1.checked_sub(x0)?, y1.checked_sub(y0)?)?;
+
+    let (dx, dy) = (-(x0 as f32), -(y0 as f32));
+    let shifted = State {
+        transform: ts.post_translate(dx, dy),
+        container_transform: state.container_transform.post_translate(dx, dy),
+        mask: None,
+        ..state
+    };
+
+    render_frame(&mut layer, shifted, &group.frame);
+    blur(&mut layer, sigma);
+
+    canvas.draw_pixmap(
+        x0 as i32,
+        y0 as i32,
+        layer.as_ref(),
+        &sk::PixmapPaint::default(),
+        sk::Transform::identity(),
+        mask,
+    );
+
+    Some(())
+}
+
+/// Apply an approximate Gaussian blur with the given standard deviation in
+/// pixels to a pixmap.
+///
+/// The blur is approximated by three successive box blurs.
+fn blur(pixmap: &mut sk::Pixmap, sigma: f32) {
+    if sigma <= 0.0 {
+        return;
+    }
+
+    // Determine the sizes of the boxes. See "Fast Almost-Gaussian Filtering"
+    // by Peter Kovesi.
+    let n = 3.0;
+    let ideal = (12.0 * sigma * sigma / n + 1.0).sqrt();
+    let mut lower = ideal.floor() as i32;
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+    let upper = lower + 2;
+    let lf = lower as f32;
+    let m = ((12.0 * sigma * sigma - n * lf * lf - 4.0 * n * lf - 3.0 * n)
+        / (-4.0 * lf - 4.0))
+        .round();
+
+    let (w, h) = (pixmap.width() as usize, pixmap.height() as usize);
+    let data = pixmap.data_mut();
+    let mut buf = vec![0; data.len()];
+    for i in 0..3 {
+        let size = if (i as f32) < m { lower } else { upper };
+        let radius = ((size - 1) / 2).max(0) as usize;
+        box_blur(data, &mut buf, w, h, radius, true);
+        box_blur(&buf, data, w, h, radius, false);
+    }
+}
+
+/// Apply a horizontal or vertical box blur with the given radius to
+/// premultiplied RGBA pixel data. Pixels outside of the image are considered
+/// transparent.
+fn box_blur(
+    src: &[u8],
+    dst: &mut [u8],
+    w: usize,
+    h: usize,
+    radius: usize,
+    horizontal: bool,
+) {
+    let (lines, len, step, stride) =
+        if horizontal { (h, w, 4, 4 * w) } else { (w, h, 4 * w, 4) };
+
+    let norm = 1.0 / (2 * radius + 1) as f32;
+    for line in 0..lines {
+        let base = line * stride;
+        for c in 0..4 {
+            let at = |i: usize| u32::from(src[base + i * step + c]);
+            let mut sum: u32 = (0..=radius.min(len - 1)).map(at).sum();
+            for i in 0..len {
+                dst[base + i * step + c] = (sum as f32 * norm).round() as u8;
+                if i + radius + 1 < len {
+                    sum += at(i + radius + 1);
+                }
+                if i >= radius {
+                    sum -= at(i - radius);
+                }
+            }
+        }
+    }
+}
+
 /// Render a text run into the canvas.
 fn render_text(canvas: &mut sk::Pixmap, state: State, text: &TextItem) {
     let mut x = 0.0;
@@ -375,9 +500,14 @@ fn render_outline_glyph(
     let ppem = text.size.to_f32() * ts.sy;
 
     // Render a glyph directly as a path. This only happens when the fast glyph
-    // rasterization can't be used due to very large text size or weird
-    // scale/skewing transforms.
-    if ppem > 100.0 || ts.kx != 0.0 || ts.ky != 0.0 || ts.sx != ts.sy {
+    // rasterization can't be used due to very large text size, weird
+    // scale/skewing transforms, or a stroke.
+    if ppem > 100.0
+        || ts.kx != 0.0
+        || ts.ky != 0.0
+        || ts.sx != ts.sy
+        || text.stroke.is_some()
+    {
         let path = {
             let mut builder = WrappedPathBuilder(sk::PathBuilder::new());
             text.font.ttf().outline_glyph(id, &mut builder)?;
@@ -403,10 +533,66 @@ fn render_outline_glyph(
         // system is Y-up.
         let ts = ts.pre_scale(scale, -scale);
         canvas.fill_path(&path, &paint, rule, ts, state.mask);
+
+        if let Some(FixedStroke {
+            paint,
+            thickness,
+            line_cap,
+            line_join,
+            dash_pattern,
+            miter_limit,
+        }) = &text.stroke
+        {
+            // Don't draw zero-pt stroke.
+            if thickness.to_f32() > 0.0 {
+                // The path is in font units, so the stroke must be scaled
+                // accordingly.
+                let dash = dash_pattern.as_ref().and_then(|pattern| {
+                    let pattern_len = pattern.array.len();
+                    let len =
+                        if pattern_len % 2 == 1 { 2 * pattern_len } else { pattern_len };
+                    let dash_array = pattern
+                        .array
+                        .iter()
+                        .map(|l| l.to_f32() / scale)
+                        .cycle()
+                        .take(len)
+                        .collect();
+
+                    sk::StrokeDash::new(dash_array, pattern.phase.to_f32() / scale)
+                });
+
+                let mut pixmap = None;
+                let paint = to_sk_paint(
+                    paint,
+                    state.pre_concat(sk::Transform::from_scale(scale, -scale)),
+                    Size::zero(),
+                    true,
+                    None,
+                    &mut pixmap,
+                    None,
+                );
+                let stroke = sk::Stroke {
+                    width: thickness.to_f32() / scale,
+                    line_cap: to_sk_line_cap(*line_cap),
+                    line_join: to_sk_line_join(*line_join),
+                    dash,
+                    miter_limit: miter_limit.get() as f32,
+                };
+                canvas.stroke_path(&path, &paint, &stroke, ts, state.mask);
+            }
+        }
+
         return Some(());
     }
 
     // Rasterize the glyph with `pixglyph`.
+    //
+    // The rasterization only depends on the subpixel part of the glyph's
+    // position, so we split off the integral part and shift the bitmap
+    // afterwards. This way, the same glyph at the same size is rasterized only
+    // once across pages and compilations, unless it is positioned at a
+    // different subpixel offset.
     #[comemo::memoize]
     fn rasterize(
         font: &Font,
@@ -425,17 +611,26 @@ fn render_outline_glyph(
 
     // Try to retrieve a prepared glyph or prepare it from scratch if it
     // doesn't exist, yet.
-    let bitmap =
-        rasterize(&text.font, id, ts.tx.to_bits(), ts.ty.to_bits(), ppem.to_bits())?;
+    let (dx, dy) = (ts.tx.floor(), ts.ty.floor());
+    let bitmap = rasterize(
+        &text.font,
+        id,
+        (ts.tx - dx).to_bits(),
+        (ts.ty - dy).to_bits(),
+        ppem.to_bits(),
+    )?;
+
+    let offset = (dx as i32, dy as i32);
     match &text.fill {
         Paint::Gradient(gradient) => {
             let sampler = GradientSampler::new(gradient, &state, Size::zero(), true);
-            write_bitmap(canvas, &bitmap, &state, sampler)?;
+            write_bitmap(canvas, &bitmap, offset, &state, sampler)?;
         }
         Paint::Solid(color) => {
             write_bitmap(
                 canvas,
                 &bitmap,
+                offset,
                 &state,
                 to_sk_color_u8_without_alpha(*color).premultiply(),
             )?;
@@ -443,16 +638,19 @@ fn render_outline_glyph(
         Paint::Pattern(pattern) => {
             let pixmap = render_pattern_frame(&state, pattern);
             let sampler = PatternSampler::new(pattern, &pixmap, &state, true);
-            write_bitmap(canvas, &bitmap, &state, sampler)?;
+            write_bitmap(canvas, &bitmap, offset, &state, sampler)?;
         }
     }
 
     Some(())
 }
 
+/// Blend a rasterized glyph into the canvas, shifted by the given integral
+/// pixel offset.
 fn write_bitmap<S: PaintSampler>(
     canvas: &mut sk::Pixmap,
     bitmap: &Bitmap,
+    (dx, dy): (i32, i32),
     state: &State,
     sampler: S,
 ) -> Option<()> {
@@ -480,8 +678,8 @@ fn write_bitmap<S: PaintSampler>(
             }
         }
 
-        let left = bitmap.left;
-        let top = bitmap.top;
+        let left = bitmap.left + dx;
+        let top = bitmap.top + dy;
 
         canvas.draw_pixmap(
             left - 1,
@@ -498,9 +696,9 @@ fn write_bitmap<S: PaintSampler>(
         let mh = bitmap.height as i32;
 
         // Determine the pixel bounding box that we actually need to draw.
-        let left = bitmap.left;
+        let left = bitmap.left + dx;
         let right = left + mw;
-        let top = bitmap.top;
+        let top = bitmap.top + dy;
         let bottom = top + mh;
 
         // Blend the glyph bitmap with the existing pixels on the canvas.
@@ -97,6 +97,9 @@ struct SVGRenderer {
     patterns: Deduplicator<Pattern>,
     /// These are the gradients that compose a conic gradient.
     conic_subgradients: Deduplicator<SVGSubGradient>,
+    /// Blur filters, identified by their standard deviation. They are
+    /// referenced by the `filter` attribute of blurred groups.
+    filters: Deduplicator<Abs>,
 }
 
 /// Contextual information for rendering.
@@ -226,6 +229,7 @@ impl SVGRenderer {
             conic_subgradients: Deduplicator::new('s'),
             pattern_refs: Deduplicator::new('p'),
             patterns: Deduplicator::new('t'),
+            filters: Deduplicator::new('b'),
         }
     }
 
@@ -318,6 +322,11 @@ impl SVGRenderer {
             self.xml.write_attribute_fmt("clip-path", format_args!("url(#{id})"));
         }
 
+        if let Some(radius) = group.blur {
+            let id = self.filters.insert_with(hash128(&radius), || radius);
+            self.xml.write_attribute_fmt("filter", format_args!("url(#{id})"));
+        }
+
         self.render_frame(state, group.transform, &group.frame);
         self.xml.end_element();
     }
@@ -452,6 +461,13 @@ impl SVGRenderer {
             Size::new(Abs::pt(width), Abs::pt(height)),
             self.text_paint_transform(state, &text.fill),
         );
+        if let Some(stroke) = &text.stroke {
+            self.write_stroke(
+                stroke,
+                Size::new(Abs::pt(width), Abs::pt(height)),
+                self.text_paint_transform(state, &stroke.paint),
+            );
+        }
         self.xml.end_element();
 
         Some(())
@@ -695,6 +711,7 @@ impl SVGRenderer {
     fn finalize(mut self) -> String {
         self.write_glyph_defs();
         self.write_clip_path_defs();
+        self.write_filter_defs();
         self.write_gradients();
         self.write_gradient_refs();
         self.write_subgradients();
@@ -763,6 +780,33 @@ impl SVGRenderer {
         self.xml.end_element();
     }
 
+    /// Build the blur filter definitions.
+    fn write_filter_defs(&mut self) {
+        if self.filters.is_empty() {
+            return;
+        }
+
+        self.xml.start_element("defs");
+        self.xml.write_attribute("id", "filters");
+
+        for (id, radius) in self.filters.iter() {
+            // Enlarge the filter region so that the blur isn't cut off at the
+            // group's bounding box.
+            self.xml.start_element("filter");
+            self.xml.write_attribute("id", &id);
+            self.xml.write_attribute("x", "-50%");
+            self.xml.write_attribute("y", "-50%");
+            self.xml.write_attribute("width", "200%");
+            self.xml.write_attribute("height", "200%");
+            self.xml.start_element("feGaussianBlur");
+            self.xml.write_attribute("stdDeviation", &radius.to_pt());
+            self.xml.end_element();
+            self.xml.end_element();
+        }
+
+        self.xml.end_element();
+    }
+
     /// Write the raw gradients (without transform) to the SVG file.
     fn write_gradients(&mut self) {
         if self.gradients.is_empty() {
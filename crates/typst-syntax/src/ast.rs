@@ -159,6 +159,8 @@ pub enum Expr<'a> {
     Numeric(Numeric<'a>),
     /// A quoted string: `"..."`.
     Str(Str<'a>),
+    /// An interpolated string: `f"Total: {sum} items"`.
+    FStr(FStr<'a>),
     /// A code block: `{ let x = 1; x + 2 }`.
     Code(CodeBlock<'a>),
     /// A content block: `[*Hi* there!]`.
@@ -250,6 +252,7 @@ impl<'a> AstNode<'a> for Expr<'a> {
             SyntaxKind::Float => node.cast().map(Self::Float),
             SyntaxKind::Numeric => node.cast().map(Self::Numeric),
             SyntaxKind::Str => node.cast().map(Self::Str),
+            SyntaxKind::FStr => node.cast().map(Self::FStr),
             SyntaxKind::CodeBlock => node.cast().map(Self::Code),
             SyntaxKind::ContentBlock => node.cast().map(Self::Content),
             SyntaxKind::Parenthesized => node.cast().map(Self::Parenthesized),
@@ -312,6 +315,7 @@ impl<'a> AstNode<'a> for Expr<'a> {
             Self::Float(v) => v.to_untyped(),
             Self::Numeric(v) => v.to_untyped(),
             Self::Str(v) => v.to_untyped(),
+            Self::FStr(v) => v.to_untyped(),
             Self::Code(v) => v.to_untyped(),
             Self::Content(v) => v.to_untyped(),
             Self::Array(v) => v.to_untyped(),
@@ -351,6 +355,7 @@ impl Expr<'_> {
                 | Self::Float(_)
                 | Self::Numeric(_)
                 | Self::Str(_)
+                | Self::FStr(_)
                 | Self::Code(_)
                 | Self::Content(_)
                 | Self::Array(_)
@@ -1073,45 +1078,74 @@ impl Str<'_> {
     /// Get the string value with resolved escape sequences.
     pub fn get(self) -> EcoString {
         let text = self.0.text();
-        let unquoted = &text[1..text.len() - 1];
-        if !unquoted.contains('\\') {
-            return unquoted.into();
-        }
+        unescape(&text[1..text.len() - 1], false)
+    }
+}
 
-        let mut out = EcoString::with_capacity(unquoted.len());
-        let mut s = Scanner::new(unquoted);
+node! {
+    /// An interpolated string: `f"Total: {sum} items"`.
+    FStr
+}
 
-        while let Some(c) = s.eat() {
-            if c != '\\' {
-                out.push(c);
-                continue;
-            }
+impl<'a> FStr<'a> {
+    /// The literal text and embedded code blocks, in order.
+    pub fn parts(self) -> impl DoubleEndedIterator<Item = FStrPart<'a>> {
+        self.0.children().filter_map(|node| match node.kind() {
+            SyntaxKind::FStrText => Some(FStrPart::Text(unescape(node.text(), true))),
+            SyntaxKind::CodeBlock => node.cast().map(FStrPart::Code),
+            _ => Option::None,
+        })
+    }
+}
+
+/// A part of an interpolated string.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum FStrPart<'a> {
+    /// Literal text with resolved escape sequences.
+    Text(EcoString),
+    /// An embedded code block: `{sum}`.
+    Code(CodeBlock<'a>),
+}
+
+/// Resolve the escape sequences in the text of a string. Escaped braces are
+/// only resolved in interpolated strings.
+fn unescape(text: &str, braces: bool) -> EcoString {
+    if !text.contains('\\') {
+        return text.into();
+    }
 
-            let start = s.locate(-1);
-            match s.eat() {
-                Some('\\') => out.push('\\'),
-                Some('"') => out.push('"'),
-                Some('n') => out.push('\n'),
-                Some('r') => out.push('\r'),
-                Some('t') => out.push('\t'),
-                Some('u') if s.eat_if('{') => {
-                    let sequence = s.eat_while(char::is_ascii_hexdigit);
-                    s.eat_if('}');
-
-                    match u32::from_str_radix(sequence, 16)
-                        .ok()
-                        .and_then(std::char::from_u32)
-                    {
-                        Some(c) => out.push(c),
-                        Option::None => out.push_str(s.from(start)),
-                    }
+    let mut out = EcoString::with_capacity(text.len());
+    let mut s = Scanner::new(text);
+
+    while let Some(c) = s.eat() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        let start = s.locate(-1);
+        match s.eat() {
+            Some('\\') => out.push('\\'),
+            Some('"') => out.push('"'),
+            Some(c @ ('{' | '}')) if braces => out.push(c),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some('u') if s.eat_if('{') => {
+                let sequence = s.eat_while(char::is_ascii_hexdigit);
+                s.eat_if('}');
+
+                match u32::from_str_radix(sequence, 16).ok().and_then(std::char::from_u32)
+                {
+                    Some(c) => out.push(c),
+                    Option::None => out.push_str(s.from(start)),
                 }
-                _ => out.push_str(s.from(start)),
             }
+            _ => out.push_str(s.from(start)),
         }
-
-        out
     }
+
+    out
 }
 
 node! {
@@ -2079,14 +2113,23 @@ impl<'a> RenamedImportItem<'a> {
 }
 
 node! {
-    /// A module include: `include "chapter1.typ"`.
+    /// A module include: `include "chapter1.typ"`, `include "chapter1.typ" as ch1`.
     ModuleInclude
 }
 
 impl<'a> ModuleInclude<'a> {
     /// The module or path from which the content should be included.
     pub fn source(self) -> Expr<'a> {
-        self.0.cast_last_match().unwrap_or_default()
+        self.0.cast_first_match().unwrap_or_default()
+    }
+
+    /// The namespace the labels of the included content are placed into, if
+    /// any (`ch1` in `include "chapter1.typ" as ch1`).
+    pub fn namespace(self) -> Option<Ident<'a>> {
+        self.0
+            .children()
+            .skip_while(|child| child.kind() != SyntaxKind::As)
+            .find_map(SyntaxNode::cast)
     }
 }
 
@@ -0,0 +1,127 @@
+use std::ops::Range;
+
+use crate::{ast, LinkedNode, Source, SyntaxKind};
+
+/// A range of source text that an editor can fold.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FoldingRange {
+    /// The byte range of the folded text.
+    pub range: Range<usize>,
+    /// The syntactical structure the range belongs to.
+    pub kind: FoldingKind,
+}
+
+/// The kind of structure a folding range belongs to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FoldingKind {
+    /// A code block: `{ let x = 1; x + 2 }`.
+    CodeBlock,
+    /// A content block: `[*Hi* there!]`.
+    ContentBlock,
+    /// A section, from its heading up to the next heading of the same or a
+    /// lower level.
+    Section,
+    /// An item in a bullet, numbered, or term list.
+    ListItem,
+    /// A block comment.
+    Comment,
+}
+
+/// Compute the ranges that an editor can fold in a source file.
+///
+/// Only ranges that span multiple lines are returned. They are ordered by
+/// their start offset, outer ranges before inner ones.
+pub fn folding_ranges(source: &Source) -> Vec<FoldingRange> {
+    let mut ranges = vec![];
+    collect(&mut ranges, &LinkedNode::new(source.root()));
+    ranges.retain(|folding| {
+        let end = folding.range.end.saturating_sub(1).max(folding.range.start);
+        source.byte_to_line(folding.range.start) != source.byte_to_line(end)
+    });
+    ranges.sort_by_key(|folding| (folding.range.start, usize::MAX - folding.range.end));
+    ranges
+}
+
+/// Collect the folding ranges of a node and its descendants.
+fn collect(ranges: &mut Vec<FoldingRange>, node: &LinkedNode) {
+    let kind = match node.kind() {
+        SyntaxKind::CodeBlock => Some(FoldingKind::CodeBlock),
+        SyntaxKind::ContentBlock => Some(FoldingKind::ContentBlock),
+        SyntaxKind::ListItem | SyntaxKind::EnumItem | SyntaxKind::TermItem => {
+            Some(FoldingKind::ListItem)
+        }
+        SyntaxKind::BlockComment => Some(FoldingKind::Comment),
+        SyntaxKind::Markup => {
+            collect_sections(ranges, node);
+            None
+        }
+        _ => None,
+    };
+
+    if let Some(kind) = kind {
+        ranges.push(FoldingRange { range: node.range(), kind });
+    }
+
+    for child in node.children() {
+        collect(ranges, &child);
+    }
+}
+
+/// Collect the sections started by the headings in a markup node.
+fn collect_sections(ranges: &mut Vec<FoldingRange>, markup: &LinkedNode) {
+    // The level and start offset of the sections that are still open.
+    let mut open: Vec<(usize, usize)> = vec![];
+    // The end of the last child that isn't whitespace or a comment.
+    let mut end = markup.offset();
+
+    for child in markup.children() {
+        if let Some(heading) = child.cast::<ast::Heading>() {
+            let level = heading.level().get();
+            while open.last().is_some_and(|&(outer, _)| outer >= level) {
+                let (_, start) = open.pop().unwrap();
+                ranges
+                    .push(FoldingRange { range: start..end, kind: FoldingKind::Section });
+            }
+            open.push((level, child.offset()));
+        }
+
+        if !child.kind().is_trivia() {
+            end = child.range().end;
+        }
+    }
+
+    for (_, start) in open.into_iter().rev() {
+        ranges.push(FoldingRange { range: start..end, kind: FoldingKind::Section });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folding_ranges() {
+        use FoldingKind::*;
+
+        #[track_caller]
+        fn test(text: &str, goal: &[(Range<usize>, FoldingKind)]) {
+            let source = Source::detached(text);
+            let ranges: Vec<_> = folding_ranges(&source)
+                .into_iter()
+                .map(|folding| (folding.range, folding.kind))
+                .collect();
+            assert_eq!(ranges, goal);
+        }
+
+        test("= A\nText", &[(0..8, Section)]);
+        test("= A", &[]);
+        test(
+            "= A\n== B\nx\n\n= C\ny\n",
+            &[(0..10, Section), (4..10, Section), (12..17, Section)],
+        );
+        test("#{\n  1\n}", &[(1..8, CodeBlock)]);
+        test("#[a]\n#box[\n  b\n]", &[(9..16, ContentBlock)]);
+        test("- a\n  b\n- c", &[(0..7, ListItem)]);
+        test("/* a\n b */", &[(0..10, Comment)]);
+    }
+}
@@ -0,0 +1,316 @@
+use crate::{ast, split_newlines, LinkedNode, Source, SyntaxKind};
+
+/// Configuration for the canonical formatting of a source file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FormatConfig {
+    /// The number of spaces per indentation level in code.
+    pub indent: usize,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self { indent: 2 }
+    }
+}
+
+/// Format a source file canonically.
+///
+/// The formatter only changes whitespace in ways that preserve the meaning of
+/// the file:
+/// - Code is indented by its nesting in blocks, parentheses, and continued
+///   expressions, relative to the line it is embedded in.
+/// - Spacing in code is normalized to single spaces around binary operators,
+///   assignments, and arrows, and after commas and colons. Spaces just inside
+///   of parentheses are removed.
+/// - Trailing whitespace is removed and runs of blank lines are collapsed into
+///   a single one. The result ends with exactly one newline.
+///
+/// Since indentation is significant in markup (e.g., for nested lists), the
+/// indentation of markup lines is left as is. Equations and raw text are not
+/// touched at all. Files with syntax errors are returned unchanged.
+pub fn format(source: &Source, config: &FormatConfig) -> String {
+    let root = LinkedNode::new(source.root());
+    if root.erroneous() {
+        return source.text().into();
+    }
+
+    let mut formatter = Formatter { config, output: String::new() };
+    formatter.visit(&root, Mode::Markup, 0);
+
+    let mut output = formatter.output;
+    output.truncate(output.trim_end().len());
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    output
+}
+
+/// The syntactical mode a node is formatted in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Mode {
+    Markup,
+    Code,
+}
+
+/// Writes the formatted text of a syntax tree.
+struct Formatter<'a> {
+    config: &'a FormatConfig,
+    output: String,
+}
+
+impl Formatter<'_> {
+    /// Format a node and its descendants. The `base` is the indentation of the
+    /// line the surrounding code is embedded in.
+    fn visit(&mut self, node: &LinkedNode, mode: Mode, base: usize) {
+        let kind = node.kind();
+        if matches!(kind, SyntaxKind::Equation | SyntaxKind::Raw) {
+            self.output.push_str(&node.get().clone().into_text());
+            return;
+        }
+
+        let (mode, base) = match kind {
+            SyntaxKind::Markup => (Mode::Markup, base),
+            _ if is_code(kind) && mode == Mode::Markup => {
+                (Mode::Code, self.line_indent())
+            }
+            _ if is_code(kind) => (Mode::Code, base),
+            _ => (mode, base),
+        };
+
+        if node.get().children().len() == 0 {
+            match kind {
+                SyntaxKind::LineComment => self.output.push_str(node.text().trim_end()),
+                _ => self.output.push_str(node.text()),
+            }
+            return;
+        }
+
+        let children: Vec<_> = node.children().collect();
+        for (i, child) in children.iter().enumerate() {
+            let prev = i.checked_sub(1).map(|k| &children[k]);
+            let next = children.get(i + 1);
+            let spaced = |other: Option<&LinkedNode>| {
+                other.map_or(true, |other| other.kind().is_trivia())
+            };
+
+            if matches!(child.kind(), SyntaxKind::Space | SyntaxKind::Parbreak) {
+                match mode {
+                    Mode::Markup => self.markup_space(child),
+                    Mode::Code => self.code_space(child, next, base),
+                }
+                continue;
+            }
+
+            let around = mode == Mode::Code && space_around(kind, child.kind());
+            if around && !spaced(prev) {
+                self.output.push(' ');
+            }
+
+            self.visit(child, mode, base);
+
+            if (around || mode == Mode::Code && space_after(kind, child.kind()))
+                && !spaced(next)
+                && next.is_some_and(|next| next.kind() != SyntaxKind::RightParen)
+            {
+                self.output.push(' ');
+            }
+        }
+    }
+
+    /// Format whitespace in markup, keeping the indentation of the next line.
+    fn markup_space(&mut self, space: &LinkedNode) {
+        let lines = split_newlines(space.text());
+        match lines.split_last() {
+            Some((last, rest)) if !rest.is_empty() => {
+                self.newlines(rest.len());
+                self.output.push_str(last);
+            }
+            _ => self.output.push_str(space.text()),
+        }
+    }
+
+    /// Format whitespace in code, re-indenting the next line if there is one.
+    fn code_space(&mut self, space: &LinkedNode, next: Option<&LinkedNode>, base: usize) {
+        let lines = split_newlines(space.text()).len() - 1;
+        if lines > 0 {
+            self.newlines(lines);
+            let width = base + indent_level(space) * self.config.indent;
+            self.output.extend(std::iter::repeat(' ').take(width));
+            return;
+        }
+
+        let comment = next.is_some_and(|next| next.kind().is_trivia());
+        let opened = space
+            .prev_leaf()
+            .is_some_and(|prev| prev.kind() == SyntaxKind::LeftParen);
+        let closed = space.next_leaf().is_some_and(|next| {
+            matches!(
+                next.kind(),
+                SyntaxKind::RightParen
+                    | SyntaxKind::Comma
+                    | SyntaxKind::Colon
+                    | SyntaxKind::Semicolon
+            )
+        });
+
+        if comment || !(opened || closed) {
+            self.output.push(' ');
+        }
+    }
+
+    /// Start new lines, dropping trailing whitespace and collapsing multiple
+    /// blank lines into one.
+    fn newlines(&mut self, count: usize) {
+        self.output.truncate(self.output.trim_end_matches([' ', '\t']).len());
+        for _ in 0..count.min(2) {
+            self.output.push('\n');
+        }
+    }
+
+    /// The indentation of the current output line.
+    fn line_indent(&self) -> usize {
+        let line = match self.output.rfind('\n') {
+            Some(i) => &self.output[i + 1..],
+            None => &self.output,
+        };
+        line.len() - line.trim_start_matches([' ', '\t']).len()
+    }
+}
+
+/// The indentation level of the code line following a newline.
+fn indent_level(space: &LinkedNode) -> usize {
+    let mut level = 0;
+    let mut node = space.parent();
+    while let Some(ancestor) = node {
+        match ancestor.kind() {
+            SyntaxKind::Markup => break,
+            kind if is_indenting(kind) => level += 1,
+            _ => {}
+        }
+        node = ancestor.parent();
+    }
+
+    // A line starting with a closing delimiter belongs to the outer level.
+    if space.next_leaf().is_some_and(|next| {
+        matches!(
+            next.kind(),
+            SyntaxKind::RightParen | SyntaxKind::RightBrace | SyntaxKind::RightBracket
+        )
+    }) {
+        level = level.saturating_sub(1);
+    }
+
+    // A line within an expression continues the previous one.
+    if let Some(parent) = space.parent_kind() {
+        if !is_indenting(parent) && parent != SyntaxKind::Code {
+            level += 1;
+        }
+    }
+
+    level
+}
+
+/// Whether a node kind only occurs in code.
+fn is_code(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Code
+            | SyntaxKind::CodeBlock
+            | SyntaxKind::Parenthesized
+            | SyntaxKind::Array
+            | SyntaxKind::Dict
+            | SyntaxKind::Named
+            | SyntaxKind::Keyed
+            | SyntaxKind::Unary
+            | SyntaxKind::Binary
+            | SyntaxKind::FieldAccess
+            | SyntaxKind::FuncCall
+            | SyntaxKind::Args
+            | SyntaxKind::Spread
+            | SyntaxKind::Closure
+            | SyntaxKind::Params
+            | SyntaxKind::LetBinding
+            | SyntaxKind::SetRule
+            | SyntaxKind::ShowRule
+            | SyntaxKind::Conditional
+            | SyntaxKind::WhileLoop
+            | SyntaxKind::ForLoop
+            | SyntaxKind::ModuleImport
+            | SyntaxKind::ImportItems
+            | SyntaxKind::RenamedImportItem
+            | SyntaxKind::ModuleInclude
+            | SyntaxKind::LoopBreak
+            | SyntaxKind::LoopContinue
+            | SyntaxKind::FuncReturn
+            | SyntaxKind::Destructuring
+            | SyntaxKind::DestructAssignment
+    )
+}
+
+/// Whether a node kind increases the indentation of the code lines in it.
+fn is_indenting(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::CodeBlock
+            | SyntaxKind::Parenthesized
+            | SyntaxKind::Array
+            | SyntaxKind::Dict
+            | SyntaxKind::Args
+            | SyntaxKind::Params
+            | SyntaxKind::Destructuring
+    )
+}
+
+/// Whether a token in a code node should be surrounded by spaces.
+fn space_around(parent: SyntaxKind, token: SyntaxKind) -> bool {
+    match parent {
+        SyntaxKind::Binary => {
+            ast::BinOp::from_kind(token).is_some() || token == SyntaxKind::Not
+        }
+        SyntaxKind::LetBinding | SyntaxKind::DestructAssignment => {
+            token == SyntaxKind::Eq
+        }
+        SyntaxKind::Closure => matches!(token, SyntaxKind::Eq | SyntaxKind::Arrow),
+        _ => false,
+    }
+}
+
+/// Whether a token in a code node should be followed by a space.
+fn space_after(parent: SyntaxKind, token: SyntaxKind) -> bool {
+    match token {
+        SyntaxKind::Comma => true,
+        SyntaxKind::Colon => matches!(
+            parent,
+            SyntaxKind::Named | SyntaxKind::Keyed | SyntaxKind::ModuleImport
+        ),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        #[track_caller]
+        fn test(text: &str, goal: &str) {
+            let source = Source::detached(text);
+            assert_eq!(format(&source, &FormatConfig::default()), goal);
+        }
+
+        test("#let  x=1+2", "#let x = 1 + 2\n");
+        test("#f( a ,b:1 )", "#f(a, b: 1)\n");
+        test("#let f(x,y)=x", "#let f(x, y) = x\n");
+        test("#let g=(x)=>x", "#let g = (x) => x\n");
+        test(
+            "#{\nlet x = (\n1,\n2,\n)\n    x\n}",
+            "#{\n  let x = (\n    1,\n    2,\n  )\n  x\n}\n",
+        );
+        test("- a\n  #f(\n  1)\n", "- a\n  #f(\n    1)\n");
+        test("Hello  \n\n\n\nWorld  ", "Hello\n\nWorld\n");
+        test("- a\n  - b", "- a\n  - b\n");
+        test("$x+y$ `a  `", "$x+y$ `a  `\n");
+        test("#f(", "#f(");
+    }
+}
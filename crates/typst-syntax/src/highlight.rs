@@ -1,4 +1,5 @@
-use crate::{ast, LinkedNode, SyntaxKind, SyntaxNode};
+use crate::ast::{self, AstNode};
+use crate::{LinkedNode, SyntaxKind, SyntaxNode};
 
 /// A syntax highlighting tag.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
@@ -249,6 +250,10 @@ pub fn highlight(node: &LinkedNode) -> Option<Tag> {
         SyntaxKind::Float => Some(Tag::Number),
         SyntaxKind::Numeric => Some(Tag::Number),
         SyntaxKind::Str => Some(Tag::String),
+        SyntaxKind::FStr => None,
+        SyntaxKind::FStrStart => Some(Tag::String),
+        SyntaxKind::FStrText => Some(Tag::String),
+        SyntaxKind::FStrEnd => Some(Tag::String),
         SyntaxKind::CodeBlock => None,
         SyntaxKind::ContentBlock => None,
         SyntaxKind::Parenthesized => None,
@@ -351,6 +356,159 @@ fn highlight_hash(node: &LinkedNode) -> Option<Tag> {
     highlight(&next.leftmost_leaf()?)
 }
 
+/// What an identifier refers to, as far as can be determined from the syntax
+/// tree alone.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IdentKind {
+    /// A user-defined function, bound with `{let f(x) = ..}` or to a closure.
+    Function,
+    /// A user-defined variable.
+    Variable,
+    /// A parameter of an enclosing closure.
+    Parameter,
+    /// A name imported from another module.
+    Import,
+    /// A name without a binding in the file. This is typically a definition
+    /// of the standard library, like an element function.
+    Unresolved,
+}
+
+/// Classify an identifier by resolving it to its binding.
+///
+/// The resolution is lightweight: It only considers bindings in the same file
+/// that precede the identifier in an enclosing block or markup, parameters of
+/// enclosing closures, and for loop patterns. Editors can use this to color
+/// calls to user-defined functions distinctly from those of built-in
+/// functions.
+///
+/// Returns `None` if the node is not an identifier that refers to a binding,
+/// e.g. a field name or the name of a named argument.
+pub fn classify_ident(node: &LinkedNode) -> Option<IdentKind> {
+    if !is_ident(node) {
+        return None;
+    }
+
+    // Field names and named arguments don't refer to bindings.
+    let parent = node.parent()?;
+    match parent.kind() {
+        SyntaxKind::FieldAccess if node.prev_sibling().is_some() => return None,
+        SyntaxKind::Named
+            if matches!(
+                parent.parent_kind(),
+                Some(SyntaxKind::Args | SyntaxKind::Dict)
+            ) && node.prev_sibling().is_none() =>
+        {
+            return None
+        }
+        _ => {}
+    }
+
+    let name = node.text().as_str();
+    let mut child = node.clone();
+    while let Some(parent) = child.parent() {
+        if let Some(kind) = enclosing_binding(&parent, &child, name) {
+            return Some(kind);
+        }
+
+        let mut sibling = child.prev_sibling();
+        while let Some(prev) = sibling {
+            if let Some(kind) = preceding_binding(&prev, name) {
+                return Some(kind);
+            }
+            sibling = prev.prev_sibling();
+        }
+
+        child = parent;
+    }
+
+    Some(IdentKind::Unresolved)
+}
+
+/// Find a binding for `name` introduced by an ancestor of the identifier that
+/// is visible from its `child`.
+fn enclosing_binding(
+    parent: &LinkedNode,
+    child: &LinkedNode,
+    name: &str,
+) -> Option<IdentKind> {
+    if let Some(closure) = parent.cast::<ast::Closure>() {
+        let bound = closure.params().children().any(|param| match param {
+            ast::Param::Pos(pattern) => {
+                pattern.idents().iter().any(|ident| ident.as_str() == name)
+            }
+            ast::Param::Named(named) => named.name().as_str() == name,
+            ast::Param::Sink(spread) => {
+                spread.name().is_some_and(|ident| ident.as_str() == name)
+            }
+        });
+        if bound {
+            return Some(IdentKind::Parameter);
+        }
+        if closure.name().is_some_and(|ident| ident.as_str() == name) {
+            return Some(IdentKind::Function);
+        }
+    } else if let Some(binding) = parent.cast::<ast::LetBinding>() {
+        // The binding is only visible from its own pattern, not from the
+        // expression it is initialized with.
+        let init = binding.init();
+        if init.is_some_and(|init| std::ptr::eq(init.to_untyped(), child.get())) {
+            return None;
+        }
+        if binding.kind().idents().iter().any(|ident| ident.as_str() == name) {
+            return Some(binding_kind(binding));
+        }
+    } else if let Some(for_loop) = parent.cast::<ast::ForLoop>() {
+        if !std::ptr::eq(for_loop.iter().to_untyped(), child.get())
+            && for_loop.pattern().idents().iter().any(|ident| ident.as_str() == name)
+        {
+            return Some(IdentKind::Variable);
+        }
+    } else if let Some(import) = parent.cast::<ast::ModuleImport>() {
+        if !std::ptr::eq(import.source().to_untyped(), child.get())
+            && import_binds(import, name)
+        {
+            return Some(IdentKind::Import);
+        }
+    }
+    None
+}
+
+/// Find a binding for `name` introduced by a preceding sibling of the
+/// identifier or one of its ancestors.
+fn preceding_binding(node: &LinkedNode, name: &str) -> Option<IdentKind> {
+    if let Some(binding) = node.cast::<ast::LetBinding>() {
+        if binding.kind().idents().iter().any(|ident| ident.as_str() == name) {
+            return Some(binding_kind(binding));
+        }
+    } else if let Some(import) = node.cast::<ast::ModuleImport>() {
+        if import_binds(import, name) {
+            return Some(IdentKind::Import);
+        }
+    }
+    None
+}
+
+/// Whether a module import binds `name`.
+fn import_binds(import: ast::ModuleImport, name: &str) -> bool {
+    import.new_name().is_some_and(|ident| ident.as_str() == name)
+        || match import.imports() {
+            Some(ast::Imports::Items(items)) => {
+                items.iter().any(|item| item.bound_name().as_str() == name)
+            }
+            _ => false,
+        }
+}
+
+/// Whether a let binding defines a function or a variable.
+fn binding_kind(binding: ast::LetBinding) -> IdentKind {
+    match (binding.kind(), binding.init()) {
+        (ast::LetBindingKind::Closure(_), _) | (_, Some(ast::Expr::Closure(_))) => {
+            IdentKind::Function
+        }
+        _ => IdentKind::Variable,
+    }
+}
+
 /// Whether the node is one of the two identifier nodes.
 fn is_ident(node: &LinkedNode) -> bool {
     matches!(node.kind(), SyntaxKind::Ident | SyntaxKind::MathIdent)
@@ -455,4 +613,62 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_ident_classification() {
+        use IdentKind::*;
+
+        #[track_caller]
+        fn test(text: &str, goal: &[(&str, Option<IdentKind>)]) {
+            let mut vec = vec![];
+            let root = crate::parse(text);
+            classify_tree(&mut vec, &LinkedNode::new(&root));
+            assert_eq!(vec, goal);
+        }
+
+        fn classify_tree<'a>(
+            idents: &mut Vec<(&'a str, Option<IdentKind>)>,
+            node: &LinkedNode<'a>,
+        ) {
+            if is_ident(node) {
+                idents.push((node.get().text().as_str(), classify_ident(node)));
+            }
+
+            for child in node.children() {
+                classify_tree(idents, &child);
+            }
+        }
+
+        test(
+            "#let f(x) = x\n#f(y)",
+            &[
+                ("f", Some(Function)),
+                ("x", Some(Parameter)),
+                ("x", Some(Parameter)),
+                ("f", Some(Function)),
+                ("y", Some(Unresolved)),
+            ],
+        );
+
+        test(
+            "#let x = x\n#x.y",
+            &[
+                ("x", Some(Variable)),
+                ("x", Some(Unresolved)),
+                ("x", Some(Variable)),
+                ("y", None),
+            ],
+        );
+
+        test(
+            "#import \"a.typ\": g\n#for i in g(t: 1) { i }",
+            &[
+                ("g", Some(Import)),
+                ("i", Some(Variable)),
+                ("g", Some(Import)),
+                ("t", None),
+                ("i", Some(Variable)),
+            ],
+        );
+    }
 }
@@ -196,6 +196,14 @@ pub enum SyntaxKind {
     Numeric,
     /// A quoted string: `"..."`.
     Str,
+    /// An interpolated string: `f"Total: {sum} items"`.
+    FStr,
+    /// The start of an interpolated string: `f"`.
+    FStrStart,
+    /// Literal text in an interpolated string.
+    FStrText,
+    /// The closing quote of an interpolated string.
+    FStrEnd,
     /// A code block: `{ let x = 1; x + 2 }`.
     CodeBlock,
     /// A content block: `[*Hi* there!]`.
@@ -444,6 +452,10 @@ impl SyntaxKind {
             Self::Float => "float",
             Self::Numeric => "numeric value",
             Self::Str => "string",
+            Self::FStr => "interpolated string",
+            Self::FStrStart => "opening quote",
+            Self::FStrText => "string text",
+            Self::FStrEnd => "closing quote",
             Self::CodeBlock => "code block",
             Self::ContentBlock => "content block",
             Self::Parenthesized => "group",
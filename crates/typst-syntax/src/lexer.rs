@@ -29,6 +29,8 @@ pub(super) enum LexMode {
     Math,
     /// Keywords, literals and operators.
     Code,
+    /// Text and embedded code blocks of an interpolated string.
+    Str,
 }
 
 impl<'s> Lexer<'s> {
@@ -90,6 +92,7 @@ impl Lexer<'_> {
         self.error = None;
         let start = self.s.cursor();
         match self.s.eat() {
+            Some(c) if self.mode == LexMode::Str => self.fstr(c),
             Some(c) if c.is_whitespace() => self.whitespace(start, c),
             Some('/') if self.s.eat_if('/') => self.line_comment(),
             Some('/') if self.s.eat_if('*') => self.block_comment(),
@@ -100,7 +103,7 @@ impl Lexer<'_> {
             Some(c) => match self.mode {
                 LexMode::Markup => self.markup(start, c),
                 LexMode::Math => self.math(start, c),
-                LexMode::Code => self.code(start, c),
+                LexMode::Code | LexMode::Str => self.code(start, c),
             },
 
             None => SyntaxKind::Eof,
@@ -505,7 +508,9 @@ impl Lexer<'_> {
             }
         }
 
-        if ident == "_" {
+        if ident == "f" && self.s.eat_if('"') {
+            SyntaxKind::FStrStart
+        } else if ident == "_" {
             SyntaxKind::Underscore
         } else {
             SyntaxKind::Ident
@@ -604,6 +609,39 @@ impl Lexer<'_> {
     }
 }
 
+/// Interpolated strings.
+impl Lexer<'_> {
+    fn fstr(&mut self, c: char) -> SyntaxKind {
+        match c {
+            '"' => return SyntaxKind::FStrEnd,
+            '{' => return SyntaxKind::LeftBrace,
+            _ => {}
+        }
+
+        let mut escaped = c == '\\';
+        while let Some(c) = self.s.peek() {
+            if escaped {
+                escaped = false;
+                self.s.eat();
+                if c == 'u' && self.s.eat_if('{') {
+                    self.s.eat_while(char::is_ascii_hexdigit);
+                    self.s.eat_if('}');
+                }
+                continue;
+            }
+
+            if c == '"' || c == '{' {
+                break;
+            }
+
+            escaped = c == '\\';
+            self.s.eat();
+        }
+
+        SyntaxKind::FStrText
+    }
+}
+
 /// Try to parse an identifier into a keyword.
 fn keyword(ident: &str) -> Option<SyntaxKind> {
     Some(match ident {
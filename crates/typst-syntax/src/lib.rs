@@ -3,6 +3,8 @@
 pub mod ast;
 
 mod file;
+mod fold;
+mod format;
 mod highlight;
 mod kind;
 mod lexer;
@@ -13,7 +15,9 @@ mod source;
 mod span;
 
 pub use self::file::{FileId, PackageSpec, PackageVersion, VirtualPath};
-pub use self::highlight::{highlight, highlight_html, Tag};
+pub use self::fold::{folding_ranges, FoldingKind, FoldingRange};
+pub use self::format::{format, FormatConfig};
+pub use self::highlight::{classify_ident, highlight, highlight_html, IdentKind, Tag};
 pub use self::kind::SyntaxKind;
 pub use self::lexer::{
     is_id_continue, is_id_start, is_ident, is_newline, link_prefix, split_newlines,
@@ -746,6 +746,8 @@ fn code_primary(p: &mut Parser, atomic: bool, allow_destructuring: bool) {
         | SyntaxKind::Label
         | SyntaxKind::Raw => p.eat(),
 
+        SyntaxKind::FStrStart => fstr(p),
+
         _ => p.expected("expression"),
     }
 }
@@ -782,6 +784,22 @@ fn code_block(p: &mut Parser) {
     p.wrap(m, SyntaxKind::CodeBlock);
 }
 
+fn fstr(p: &mut Parser) {
+    let m = p.marker();
+    p.enter(LexMode::Str);
+    p.assert(SyntaxKind::FStrStart);
+    loop {
+        match p.current() {
+            SyntaxKind::FStrText => p.eat(),
+            SyntaxKind::LeftBrace => code_block(p),
+            _ => break,
+        }
+    }
+    p.expect_closing_delimiter(m, SyntaxKind::FStrEnd);
+    p.exit();
+    p.wrap(m, SyntaxKind::FStr);
+}
+
 fn content_block(p: &mut Parser) {
     let m = p.marker();
     p.enter(LexMode::Markup);
@@ -1163,6 +1181,10 @@ fn module_include(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::Include);
     code_expr(p);
+    if p.eat_if(SyntaxKind::As) {
+        // Place the labels of the included content into a namespace.
+        p.expect(SyntaxKind::Ident);
+    }
     p.wrap(m, SyntaxKind::ModuleInclude);
 }
 
@@ -70,7 +70,7 @@ fn try_reparse(
             }
 
             // If the child is a block, try to reparse the block.
-            if child.kind().is_block() {
+            if child.kind().is_block() && node_kind != SyntaxKind::FStr {
                 if let Some(newborn) = reparse_block(text, new_range.clone()) {
                     return node
                         .replace_children(i..i + 1, vec![newborn])
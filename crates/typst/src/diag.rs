@@ -9,7 +9,9 @@ use std::string::FromUtf8Error;
 use comemo::Tracked;
 use ecow::{eco_vec, EcoVec};
 
-use crate::syntax::{PackageSpec, Span, Spanned, SyntaxError};
+use crate::foundations::Cast;
+use crate::syntax::{FileId, PackageSpec, Span, Spanned, SyntaxError};
+use crate::text::FontVariant;
 use crate::{World, WorldExt};
 
 /// Early-return with a [`StrResult`] or [`SourceResult`].
@@ -101,6 +103,15 @@ pub struct SourceDiagnostic {
     /// Additional hints to the user, indicating how this problem could be avoided
     /// or worked around.
     pub hints: EcoVec<EcoString>,
+    /// How often the problem occurred. This is more than one if diagnostics
+    /// for the same problem in different places were grouped into this one.
+    pub count: usize,
+    /// The spans of a few further places where the problem occurred, if this
+    /// diagnostic is a group.
+    pub repeats: EcoVec<Span>,
+    /// The category of a warning. Warnings with a category can be promoted to
+    /// errors.
+    pub category: Option<WarningCategory>,
 }
 
 /// The severity of a [`SourceDiagnostic`].
@@ -112,6 +123,31 @@ pub enum Severity {
     Warning,
 }
 
+/// A category of warnings.
+///
+/// Categories can be promoted to errors, either for a whole compilation
+/// through [`Tracer::promote`](crate::eval::Tracer::promote) or for a single
+/// file through `warn.promote`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum WarningCategory {
+    /// Markup that likely doesn't do what was intended, like empty strong
+    /// emphasis.
+    Markup,
+    /// Unnecessary imports and renames.
+    Import,
+    /// Layout that did not converge.
+    Convergence,
+    /// Warnings raised by the document itself through `warn` or soft
+    /// assertions.
+    User,
+    /// Font families that are not available.
+    UnknownFont,
+    /// References and citations that a show rule replaced although their
+    /// target doesn't exist. These are only reported when the category is
+    /// promoted.
+    UnresolvedReference,
+}
+
 impl SourceDiagnostic {
     /// Create a new, bare error.
     pub fn error(span: Span, message: impl Into<EcoString>) -> Self {
@@ -121,6 +157,9 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            count: 1,
+            repeats: eco_vec![],
+            category: None,
         }
     }
 
@@ -132,6 +171,9 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            count: 1,
+            repeats: eco_vec![],
+            category: None,
         }
     }
 
@@ -151,6 +193,12 @@ impl SourceDiagnostic {
         self.hints.extend(hints);
         self
     }
+
+    /// Sets the category of a warning.
+    pub fn with_category(mut self, category: WarningCategory) -> Self {
+        self.category = Some(category);
+        self
+    }
 }
 
 impl From<SyntaxError> for SourceDiagnostic {
@@ -161,6 +209,9 @@ impl From<SyntaxError> for SourceDiagnostic {
             message: error.message,
             trace: eco_vec![],
             hints: error.hints,
+            count: 1,
+            repeats: eco_vec![],
+            category: None,
         }
     }
 }
@@ -440,6 +491,48 @@ impl From<PackageError> for EcoString {
     }
 }
 
+/// A resource lookup that failed, as reported to [`World::missing`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Lookup {
+    /// A file that does not exist.
+    File {
+        /// The file that was looked up.
+        id: FileId,
+        /// The error the world returned for the file.
+        error: FileError,
+    },
+    /// A package that could not be loaded.
+    Package {
+        /// The package that was looked up.
+        spec: PackageSpec,
+        /// The error the world returned for the package.
+        error: PackageError,
+    },
+    /// A font family that is unavailable in the requested variant.
+    Font {
+        /// The requested family, in lowercase.
+        family: EcoString,
+        /// The requested variant.
+        variant: FontVariant,
+        /// Whether the family exists in a different variant.
+        similar: bool,
+    },
+}
+
+impl Lookup {
+    /// The lookup that caused an error when accessing a file, if the error
+    /// stems from the file or its package being missing.
+    pub fn from_file_error(id: FileId, error: &FileError) -> Option<Self> {
+        match error {
+            FileError::NotFound(_) => Some(Self::File { id, error: error.clone() }),
+            FileError::Package(error) => {
+                Some(Self::Package { spec: id.package()?.clone(), error: error.clone() })
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Format a user-facing error message for an XML-like file format.
 pub fn format_xml_like_error(format: &str, error: roxmltree::Error) -> EcoString {
     match error {
@@ -1,4 +1,5 @@
 use std::cell::Cell;
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
 use comemo::{Track, Tracked, TrackedMut, Validate};
 
@@ -157,3 +158,45 @@ impl Default for Route<'_> {
         Self::root()
     }
 }
+
+/// Retains the introspector across consecutive compilations of a document.
+///
+/// Pass the same cache to each call of
+/// [`compile_incremental`](crate::compile_incremental). It only holds on to
+/// the introspector of the last compilation, so that the next one starts out
+/// with its results. Layouted frames are not retained.
+#[derive(Default)]
+pub struct CompilationCache {
+    introspector: Mutex<Introspector>,
+}
+
+impl CompilationCache {
+    /// Create a new, empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget all retained work.
+    pub fn clear(&self) {
+        self.take();
+    }
+
+    /// Take the retained introspector out of the cache.
+    ///
+    /// The introspector is not borrowed during compilation so that a panic
+    /// can't leave behind a partially updated one.
+    pub(crate) fn take(&self) -> Introspector {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// Retain an introspector for the next compilation.
+    pub(crate) fn store(&self, introspector: Introspector) {
+        *self.lock() = introspector;
+    }
+
+    /// Lock the retained introspector.
+    fn lock(&self) -> MutexGuard<'_, Introspector> {
+        // The lock is never held while a panic can occur.
+        self.introspector.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -1,8 +1,8 @@
-use ecow::{eco_vec, EcoVec};
+use ecow::{eco_vec, EcoString, EcoVec};
 
 use crate::diag::{bail, error, At, SourceDiagnostic, SourceResult};
 use crate::eval::{ops, Eval, Vm};
-use crate::foundations::{Array, Content, Dict, Str, Value};
+use crate::foundations::{Array, Content, Dict, Str, ToStr, Value};
 use crate::syntax::ast::{self, AstNode};
 
 impl Eval for ast::Code<'_> {
@@ -104,6 +104,7 @@ impl Eval for ast::Expr<'_> {
             Self::Float(v) => v.eval(vm),
             Self::Numeric(v) => v.eval(vm),
             Self::Str(v) => v.eval(vm),
+            Self::FStr(v) => v.eval(vm),
             Self::Code(v) => v.eval(vm),
             Self::Content(v) => v.eval(vm).map(Value::Content),
             Self::Array(v) => v.eval(vm).map(Value::Array),
@@ -209,6 +210,34 @@ impl Eval for ast::Str<'_> {
     }
 }
 
+impl Eval for ast::FStr<'_> {
+    type Output = Value;
+
+    #[tracing::instrument(name = "FStr::eval", skip_all)]
+    fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        let mut string = EcoString::new();
+        for part in self.parts() {
+            match part {
+                ast::FStrPart::Text(text) => string.push_str(&text),
+                ast::FStrPart::Code(block) => {
+                    let value = block.eval(vm)?;
+                    let ty = value.ty();
+                    match value.cast::<ToStr>() {
+                        Ok(ToStr::Str(s)) => string.push_str(&s),
+                        Ok(ToStr::Int(n)) => string.push_str(&n.to_string()),
+                        Err(_) => bail!(error!(
+                            block.span(),
+                            "cannot interpolate {ty} into a string"
+                        )
+                        .with_hint("use `repr` to get a textual representation")),
+                    }
+                }
+            }
+        }
+        Ok(Value::Str(string.into()))
+    }
+}
+
 impl Eval for ast::Array<'_> {
     type Output = Array;
 
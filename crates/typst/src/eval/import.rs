@@ -4,12 +4,13 @@ use serde::{Deserialize, Serialize};
 
 use crate::diag::{
     bail, error, warning, At, FileError, SourceResult, StrResult, Trace, Tracepoint,
+    WarningCategory,
 };
 use crate::eval::{eval, Eval, Vm};
-use crate::foundations::{Content, Module, Value};
+use crate::foundations::{Content, LabelNamespaceElem, Module, Value};
 use crate::syntax::ast::{self, AstNode};
 use crate::syntax::{FileId, PackageSpec, PackageVersion, Span, VirtualPath};
-use crate::World;
+use crate::Load;
 
 impl Eval for ast::ModuleImport<'_> {
     type Output = Value;
@@ -38,10 +39,13 @@ impl Eval for ast::ModuleImport<'_> {
             if let ast::Expr::Ident(ident) = self.source() {
                 if ident.as_str() == new_name.as_str() {
                     // Warn on `import x as x`
-                    vm.engine.tracer.warn(warning!(
-                        new_name.span(),
-                        "unnecessary import rename to same name",
-                    ));
+                    vm.engine.tracer.warn(
+                        warning!(
+                            new_name.span(),
+                            "unnecessary import rename to same name",
+                        )
+                        .with_category(WarningCategory::Import),
+                    );
                 }
             }
 
@@ -73,10 +77,13 @@ impl Eval for ast::ModuleImport<'_> {
                             if renamed_item.original_name().as_str()
                                 == renamed_item.new_name().as_str()
                             {
-                                vm.engine.tracer.warn(warning!(
-                                    renamed_item.new_name().span(),
-                                    "unnecessary import rename to same name",
-                                ));
+                                vm.engine.tracer.warn(
+                                    warning!(
+                                        renamed_item.new_name().span(),
+                                        "unnecessary import rename to same name",
+                                    )
+                                    .with_category(WarningCategory::Import),
+                                );
                             }
                         }
 
@@ -103,7 +110,12 @@ impl Eval for ast::ModuleInclude<'_> {
         let span = self.source().span();
         let source = self.source().eval(vm)?;
         let module = import(vm, source, span, false)?;
-        Ok(module.content())
+        let mut content = module.content();
+        if let Some(namespace) = self.namespace() {
+            let namespaces = vec![namespace.get().clone()];
+            content = content.styled(LabelNamespaceElem::set_namespaces(namespaces));
+        }
+        Ok(content)
     }
 }
 
@@ -137,16 +149,17 @@ pub fn import(
 fn import_package(vm: &mut Vm, spec: PackageSpec, span: Span) -> SourceResult<Module> {
     // Evaluate the manifest.
     let manifest_id = FileId::new(Some(spec.clone()), VirtualPath::new("typst.toml"));
-    let bytes = vm.world().file(manifest_id).at(span)?;
+    let bytes = vm.world().load_file(manifest_id).at(span)?;
     let manifest = PackageManifest::parse(&bytes).at(span)?;
     manifest.validate(&spec).at(span)?;
 
     // Evaluate the entry point.
     let entrypoint_id = manifest_id.join(&manifest.package.entrypoint);
-    let source = vm.world().source(entrypoint_id).at(span)?;
+    let source = vm.world().load_source(entrypoint_id).at(span)?;
     let point = || Tracepoint::Import;
     Ok(eval(
         vm.world(),
+        vm.engine.introspector,
         vm.engine.route.track(),
         TrackedMut::reborrow_mut(&mut vm.engine.tracer),
         &source,
@@ -160,7 +173,7 @@ fn import_file(vm: &mut Vm, path: &str, span: Span) -> SourceResult<Module> {
     // Load the source file.
     let world = vm.world();
     let id = span.resolve_path(path).at(span)?;
-    let source = world.source(id).at(span)?;
+    let source = world.load_source(id).at(span)?;
 
     // Prevent cyclic importing.
     if vm.engine.route.contains(source.id()) {
@@ -171,6 +184,7 @@ fn import_file(vm: &mut Vm, path: &str, span: Span) -> SourceResult<Module> {
     let point = || Tracepoint::Import;
     eval(
         world,
+        vm.engine.introspector,
         vm.engine.route.track(),
         TrackedMut::reborrow_mut(&mut vm.engine.tracer),
         &source,
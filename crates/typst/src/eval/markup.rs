@@ -1,9 +1,9 @@
-use crate::diag::{warning, SourceResult};
+use crate::diag::{warning, SourceResult, WarningCategory};
 use crate::eval::{Eval, Vm};
 use crate::foundations::{Content, Label, NativeElement, Smart, Unlabellable, Value};
 use crate::math::EquationElem;
 use crate::model::{
-    EmphElem, EnumItem, HeadingElem, LinkElem, ListItem, ParbreakElem, RefElem,
+    ByTarget, EmphElem, EnumItem, HeadingElem, LinkElem, ListItem, ParbreakElem, RefElem,
     StrongElem, Supplement, TermItem,
 };
 use crate::symbols::Symbol;
@@ -142,7 +142,8 @@ impl Eval for ast::Strong<'_> {
             vm.engine.tracer.warn(
                 warning!(self.span(), "no text within stars").with_hint(
                     "using multiple consecutive stars (e.g. **) has no additional effect",
-                ),
+                )
+                .with_category(WarningCategory::Markup),
             );
         }
 
@@ -161,7 +162,7 @@ impl Eval for ast::Emph<'_> {
                 .tracer
                 .warn(warning!(self.span(), "no text within underscores").with_hint(
                     "using multiple consecutive underscores (e.g. __) has no additional effect"
-            ));
+            ).with_category(WarningCategory::Markup));
         }
 
         Ok(EmphElem::new(body.eval(vm)?).pack())
@@ -207,8 +208,8 @@ impl Eval for ast::Ref<'_> {
         let target = Label::new(self.target());
         let mut elem = RefElem::new(target);
         if let Some(supplement) = self.supplement() {
-            elem.push_supplement(Smart::Custom(Some(Supplement::Content(
-                supplement.eval(vm)?,
+            elem.push_supplement(ByTarget::all(Smart::Custom(Some(
+                Supplement::Content(supplement.eval(vm)?),
             ))));
         }
         Ok(elem.pack())
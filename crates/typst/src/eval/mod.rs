@@ -34,10 +34,15 @@ use crate::syntax::{ast, parse, parse_code, parse_math, Source, Span};
 use crate::World;
 
 /// Evaluate a source file and return the resulting module.
+///
+/// The introspector is the one of the previous layout iteration, so that code
+/// at the top level of a module can use introspection results that only need
+/// to converge, like the final value of a counter.
 #[comemo::memoize]
 #[tracing::instrument(skip_all)]
 pub fn eval(
     world: Tracked<dyn World + '_>,
+    introspector: Tracked<Introspector>,
     route: Tracked<Route>,
     tracer: TrackedMut<Tracer>,
     source: &Source,
@@ -50,11 +55,10 @@ pub fn eval(
 
     // Prepare the engine.
     let mut locator = Locator::new();
-    let introspector = Introspector::default();
     let engine = Engine {
         world,
         route: Route::insert(route, id),
-        introspector: introspector.track(),
+        introspector,
         locator: &mut locator,
         tracer,
     };
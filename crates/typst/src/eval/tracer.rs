@@ -2,8 +2,8 @@ use std::collections::HashSet;
 
 use ecow::EcoVec;
 
-use crate::diag::SourceDiagnostic;
-use crate::foundations::Value;
+use crate::diag::{Severity, SourceDiagnostic, WarningCategory};
+use crate::foundations::{Element, Value};
 use crate::syntax::{FileId, Span};
 use crate::util::hash128;
 
@@ -15,6 +15,12 @@ pub struct Tracer {
     warnings_set: HashSet<u128>,
     delayed: EcoVec<SourceDiagnostic>,
     values: EcoVec<Value>,
+    inspected_elem: Option<Span>,
+    origins: EcoVec<StyleOrigin>,
+    origins_set: HashSet<u128>,
+    promoted: HashSet<(Option<FileId>, WarningCategory)>,
+    record_origins: bool,
+    record_tags: bool,
 }
 
 impl Tracer {
@@ -31,9 +37,41 @@ impl Tracer {
         std::mem::take(&mut self.delayed)
     }
 
+    /// Turn all warnings of a category into errors.
+    pub fn promote(&mut self, category: WarningCategory) {
+        self.promoted.insert((None, category));
+    }
+
+    /// Whether warnings of a category are promoted to errors, for the whole
+    /// compilation or for any file.
+    pub fn promotes(&self, category: WarningCategory) -> bool {
+        self.promoted.iter().any(|&(_, promoted)| promoted == category)
+    }
+
+    /// Take the warnings that were promoted to errors, either for the whole
+    /// compilation or for the file they stem from.
+    pub fn promoted(&mut self) -> EcoVec<SourceDiagnostic> {
+        let is_promoted = |warning: &SourceDiagnostic| {
+            warning.category.map_or(false, |category| {
+                self.promoted.contains(&(None, category))
+                    || self.promoted.contains(&(warning.span.id(), category))
+            })
+        };
+
+        let (promoted, warnings): (EcoVec<_>, EcoVec<_>) =
+            self.warnings.iter().cloned().partition(is_promoted);
+        self.warnings = warnings;
+        promoted
+            .into_iter()
+            .map(|warning| SourceDiagnostic { severity: Severity::Error, ..warning })
+            .collect()
+    }
+
     /// Get the stored warnings.
+    ///
+    /// Warnings that were reported very often are grouped.
     pub fn warnings(self) -> EcoVec<SourceDiagnostic> {
-        self.warnings
+        crate::deduplicate(self.warnings)
     }
 
     /// Mark a span as inspected. All values observed for this span can be
@@ -46,6 +84,30 @@ impl Tracer {
     pub fn values(self) -> EcoVec<Value> {
         self.values
     }
+
+    /// Mark the element created at a span as inspected. All set and show
+    /// rules that contribute to its styling can be retrieved via `origins`
+    /// later.
+    pub fn inspect_origins(&mut self, span: Span) {
+        self.inspected_elem = Some(span);
+    }
+
+    /// Get the style origins for the inspected element.
+    pub fn origins(self) -> EcoVec<StyleOrigin> {
+        self.origins
+    }
+
+    /// Record in the frames which show rules generated which content, so
+    /// that inverse search can find them.
+    pub fn record_origins(&mut self) {
+        self.record_origins = true;
+    }
+
+    /// Record the semantic roles of elements in the frames, for exporters
+    /// that produce tagged output.
+    pub fn record_tags(&mut self) {
+        self.record_tags = true;
+    }
 }
 
 #[comemo::track]
@@ -64,6 +126,11 @@ impl Tracer {
         }
     }
 
+    /// Turn warnings of a category that stem from the given file into errors.
+    pub fn promote_in(&mut self, id: FileId, category: WarningCategory) {
+        self.promoted.insert((Some(id), category));
+    }
+
     /// The inspected span if it is part of the given source file.
     pub fn inspected(&self, id: FileId) -> Option<Span> {
         if self.inspected.and_then(Span::id) == Some(id) {
@@ -73,6 +140,30 @@ impl Tracer {
         }
     }
 
+    /// Whether the show rules that generated content should be recorded in
+    /// the frames.
+    pub fn records_origins(&self) -> bool {
+        self.record_origins
+    }
+
+    /// Whether the semantic roles of elements should be recorded in the
+    /// frames.
+    pub fn records_tags(&self) -> bool {
+        self.record_tags
+    }
+
+    /// The span of the element whose style origins are inspected.
+    pub fn inspected_elem(&self) -> Option<Span> {
+        self.inspected_elem
+    }
+
+    /// Trace a style origin for the inspected element.
+    pub fn origin(&mut self, origin: StyleOrigin) {
+        if self.origins_set.insert(hash128(&origin)) {
+            self.origins.push(origin);
+        }
+    }
+
     /// Trace a value for the span.
     pub fn value(&mut self, v: Value) {
         if self.values.len() < Self::MAX_VALUES {
@@ -80,3 +171,22 @@ impl Tracer {
         }
     }
 }
+
+/// A rule that contributes to the styling of an inspected element.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum StyleOrigin {
+    /// A set rule for a property of an element.
+    Set {
+        /// The element the property belongs to.
+        elem: Element,
+        /// The name of the property.
+        field: &'static str,
+        /// The span of the set rule.
+        span: Span,
+    },
+    /// A show rule that was applied to the element.
+    Show {
+        /// The span of the show rule.
+        span: Span,
+    },
+}
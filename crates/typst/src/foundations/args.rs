@@ -4,7 +4,7 @@ use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 
 use crate::diag::{bail, At, SourceDiagnostic, SourceResult};
 use crate::foundations::{
-    func, repr, scope, ty, Array, Dict, FromValue, IntoValue, Repr, Str, Value,
+    func, repr, scope, ty, Array, Dict, FromValue, IntoValue, Repr, Revert, Str, Value,
 };
 use crate::syntax::{Span, Spanned};
 
@@ -198,6 +198,22 @@ impl Args {
         }
     }
 
+    /// Consume all named arguments with the given name if the last of them is
+    /// [`revert`](Revert).
+    pub fn revert(&mut self, name: &str) -> bool {
+        let last = self.items.iter().rev().find(|arg| arg.name.as_deref() == Some(name));
+        let Some(Value::Dyn(dynamic)) = last.map(|arg| &arg.value.v) else {
+            return false;
+        };
+
+        if !dynamic.is::<Revert>() {
+            return false;
+        }
+
+        self.items.retain(|arg| arg.name.as_deref() != Some(name));
+        true
+    }
+
     /// Take out all arguments into a new instance.
     pub fn take(&mut self) -> Self {
         Self {
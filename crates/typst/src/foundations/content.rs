@@ -12,7 +12,7 @@ use smallvec::smallvec;
 use crate::diag::{SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, func, scope, ty, Dict, Element, FromValue, Guard, IntoValue, Label,
+    elem, func, scope, ty, Array, Dict, Element, FromValue, Guard, IntoValue, Label,
     NativeElement, Recipe, Repr, Selector, Str, Style, Styles, Value,
 };
 use crate::introspection::{Location, Meta, MetaElem};
@@ -387,24 +387,32 @@ impl Content {
     }
 
     /// Traverse this content.
-    fn traverse<F>(&self, f: &mut F)
+    pub(crate) fn traverse<F>(&self, f: &mut F)
     where
         F: FnMut(Content),
     {
         f(self.clone());
+        self.for_each_child(&mut |child| child.traverse(f));
+    }
 
+    /// Call a function for each direct child of this content, that is, for
+    /// all content in its fields.
+    fn for_each_child<F>(&self, f: &mut F)
+    where
+        F: FnMut(Content),
+    {
         self.0
             .fields()
             .into_iter()
             .for_each(|(_, value)| walk_value(value, f));
 
-        /// Walks a given value to find any content that matches the selector.
+        /// Walks a given value to find any content in it.
         fn walk_value<F>(value: Value, f: &mut F)
         where
             F: FnMut(Content),
         {
             match value {
-                Value::Content(content) => content.traverse(f),
+                Value::Content(content) => f(content),
                 Value::Array(array) => {
                     for value in array {
                         walk_value(value, f);
@@ -572,6 +580,56 @@ impl Content {
         self.0.fields()
     }
 
+    /// Returns the content nested directly in this content.
+    ///
+    /// For a sequence of content, these are the items of the sequence. For
+    /// other elements, these are all content values in the element's fields,
+    /// like the body of a heading or the items of a list, in field order.
+    ///
+    /// Unlike the `children` field that some elements like
+    /// [lists]($list.children) have, this method works the same way for all
+    /// content.
+    ///
+    /// ```example
+    /// #let it = [Hello *world*]
+    /// #it.nodes().len() \
+    /// #it.nodes().last().nodes()
+    /// ```
+    #[func]
+    pub fn nodes(&self) -> Array {
+        let mut nodes = Array::new();
+        self.for_each_child(&mut |child| nodes.push(child.into_value()));
+        nodes
+    }
+
+    /// Finds all elements in this content tree that match the given selector.
+    ///
+    /// The content is searched depth-first, with each element being visited
+    /// before its children. Note that the content is searched as it was
+    /// written. Elements produced by show rules are not included in the
+    /// results.
+    ///
+    /// ```example
+    /// #let body = [
+    ///   = Intro
+    ///   Some *strong* text
+    ///   and *more*.
+    /// ]
+    ///
+    /// #body.find(strong).len() \
+    /// #body.find(heading).first().body
+    /// ```
+    #[func]
+    pub fn find(
+        &self,
+        /// The selector to match against. Can be an element function like
+        /// `heading`, a label or a more complex selector like
+        /// `{heading.where(level: 1)}`.
+        selector: Selector,
+    ) -> Array {
+        self.query(selector).into_iter().map(IntoValue::into_value).collect()
+    }
+
     /// The location of the content. This is only available on content returned
     /// by [query]($query) or provided by a
     /// [show rule]($reference/styling/#show-rules), for other content it will
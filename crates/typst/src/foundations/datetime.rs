@@ -162,40 +162,32 @@ impl Datetime {
             return None;
         };
 
-        if let Ok(d) = time::PrimitiveDateTime::parse(
-            string,
-            &format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]Z"),
-        ) {
-            Self::from_ymd_hms(
-                d.year(),
-                d.month() as u8,
-                d.day(),
-                d.hour(),
-                d.minute(),
-                d.second(),
-            )
-        } else if let Ok(d) = time::PrimitiveDateTime::parse(
-            string,
-            &format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
-        ) {
-            Self::from_ymd_hms(
-                d.year(),
-                d.month() as u8,
-                d.day(),
-                d.hour(),
-                d.minute(),
-                d.second(),
-            )
-        } else if let Ok(d) =
-            time::Date::parse(string, &format_description!("[year]-[month]-[day]"))
-        {
-            Self::from_ymd(d.year(), d.month() as u8, d.day())
-        } else if let Ok(d) =
+        Self::from_iso(string)
+    }
+
+    /// Try to parse an ISO 8601 date, time, or combined date and time.
+    ///
+    /// Fractional seconds and UTC offsets are accepted, but discarded.
+    pub fn from_iso(string: &str) -> Option<Self> {
+        let parse_date = |string: &str| {
+            time::Date::parse(string, &format_description!("[year]-[month]-[day]")).ok()
+        };
+
+        let parse_time = |string: &str| {
+            let string = string.strip_suffix(['Z', 'z']).unwrap_or(string);
+            let string = string.rfind(['+', '-']).map_or(string, |i| &string[..i]);
+            let string = string.split_once('.').map_or(string, |(string, _)| string);
             time::Time::parse(string, &format_description!("[hour]:[minute]:[second]"))
-        {
-            Self::from_hms(d.hour(), d.minute(), d.second())
+                .ok()
+        };
+
+        if let Some((date, time)) = string.split_once(['T', 't', ' ']) {
+            let (date, time) = (parse_date(date)?, parse_time(time)?);
+            Some(Datetime::Datetime(PrimitiveDateTime::new(date, time)))
+        } else if let Some(date) = parse_date(string) {
+            Some(Datetime::Date(date))
         } else {
-            None
+            parse_time(string).map(Datetime::Time)
         }
     }
 
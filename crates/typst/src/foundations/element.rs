@@ -43,6 +43,13 @@ impl Element {
         (self.0.field_name)(id)
     }
 
+    /// The innermost value of a settable field in the given style chain, if
+    /// it was set. For folded fields, this is the value of all set rules
+    /// combined with the default.
+    pub fn field_from_styles(&self, id: u8, styles: StyleChain) -> Option<Value> {
+        (self.0.field_from_styles)(id, styles)
+    }
+
     /// The element's normal name (e.g. `enum`).
     pub fn name(self) -> &'static str {
         self.0.name
@@ -303,6 +310,7 @@ pub struct NativeElementData {
     pub vtable: fn(of: TypeId) -> Option<*const ()>,
     pub field_id: fn(name: &str) -> Option<u8>,
     pub field_name: fn(u8) -> Option<&'static str>,
+    pub field_from_styles: fn(u8, StyleChain) -> Option<Value>,
     pub local_name: Option<fn(Lang, Option<Region>) -> &'static str>,
     pub scope: Lazy<Scope>,
     pub params: Lazy<Vec<ParamInfo>>,
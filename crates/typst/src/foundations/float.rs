@@ -2,8 +2,12 @@ use std::num::ParseFloatError;
 
 use ecow::{eco_format, EcoString};
 
-use crate::foundations::{cast, func, repr, scope, ty, Repr, Str};
+use crate::diag::StrResult;
+use crate::foundations::{
+    cast, func, repr, scope, ty, NumberFormat, NumberStyle, Repr, Str,
+};
 use crate::layout::Ratio;
+use crate::text::{Lang, Region};
 
 /// A floating-point number.
 ///
@@ -47,6 +51,59 @@ impl f64 {
     ) -> f64 {
         value.0
     }
+
+    /// Formats the float according to the conventions of a language.
+    ///
+    /// This is useful to display numbers from data files in reports.
+    ///
+    /// ```example
+    /// #let amount = 1234.5
+    /// #amount.format() \
+    /// #amount.format(locale: "de", precision: 2) \
+    /// #amount.format(locale: "fr", currency: "€") \
+    /// #float.format(0.125, style: "percent")
+    /// ```
+    #[func]
+    pub fn format(
+        self,
+        /// The language whose conventions to follow.
+        #[named]
+        #[default(Lang::ENGLISH)]
+        locale: Lang,
+        /// The region whose conventions to follow, if they differ from those
+        /// of the language in general, e.g. `{"CH"}` for Swiss German.
+        #[named]
+        #[default]
+        region: Option<Region>,
+        /// The number of digits after the decimal separator. Defaults to as
+        /// many as needed or two for currency amounts.
+        #[named]
+        #[default]
+        precision: Option<usize>,
+        /// Whether to separate groups of thousands.
+        #[named]
+        #[default(true)]
+        grouping: bool,
+        /// Whether to format a decimal number or a percentage.
+        #[named]
+        #[default]
+        style: NumberStyle,
+        /// A currency symbol. If given, the number is formatted as an amount of
+        /// money.
+        #[named]
+        #[default]
+        currency: Option<Str>,
+    ) -> StrResult<Str> {
+        NumberFormat {
+            lang: locale,
+            region,
+            precision,
+            grouping,
+            style,
+            currency,
+        }
+        .float(self)
+    }
 }
 
 impl Repr for f64 {
@@ -2,7 +2,11 @@ use std::num::{NonZeroI64, NonZeroIsize, NonZeroU64, NonZeroUsize, ParseIntError
 
 use ecow::{eco_format, EcoString};
 
-use crate::foundations::{cast, func, repr, scope, ty, Repr, Str, Value};
+use crate::diag::StrResult;
+use crate::foundations::{
+    cast, func, repr, scope, ty, NumberFormat, NumberStyle, Repr, Str, Value,
+};
+use crate::text::{Lang, Region};
 
 /// A whole number.
 ///
@@ -49,6 +53,56 @@ impl i64 {
     ) -> i64 {
         value.0
     }
+
+    /// Formats the integer according to the conventions of a language.
+    ///
+    /// See the float's [`format`]($float.format) method for details.
+    ///
+    /// ```example
+    /// #int.format(12345, locale: "de") \
+    /// #int.format(42, style: "percent")
+    /// ```
+    #[func]
+    pub fn format(
+        self,
+        /// The language whose conventions to follow.
+        #[named]
+        #[default(Lang::ENGLISH)]
+        locale: Lang,
+        /// The region whose conventions to follow, if they differ from those
+        /// of the language in general, e.g. `{"CH"}` for Swiss German.
+        #[named]
+        #[default]
+        region: Option<Region>,
+        /// The number of digits after the decimal separator. Defaults to as
+        /// many as needed, i.e. none, or two for currency amounts.
+        #[named]
+        #[default]
+        precision: Option<usize>,
+        /// Whether to separate groups of thousands.
+        #[named]
+        #[default(true)]
+        grouping: bool,
+        /// Whether to format a decimal number or a percentage.
+        #[named]
+        #[default]
+        style: NumberStyle,
+        /// A currency symbol. If given, the number is formatted as an amount of
+        /// money.
+        #[named]
+        #[default]
+        currency: Option<Str>,
+    ) -> StrResult<Str> {
+        NumberFormat {
+            lang: locale,
+            region,
+            precision,
+            grouping,
+            style,
+            currency,
+        }
+        .int(self)
+    }
 }
 
 impl Repr for i64 {
@@ -1,6 +1,8 @@
+use std::borrow::Cow;
+
 use ecow::{eco_format, EcoString};
 
-use crate::foundations::{func, scope, ty, Repr};
+use crate::foundations::{elem, func, scope, ty, Recipe, Repr, Selector, StyleChain};
 use crate::util::PicoStr;
 
 /// A label for an element.
@@ -25,6 +27,14 @@ use crate::util::PicoStr;
 /// This function also has dedicated syntax: You can create a label by enclosing
 /// its name in angle brackets. This works both in markup and code.
 ///
+/// # Namespaces
+/// When a file is included with `{include "chapter1.typ" as ch1}`, all labels
+/// within the included content are placed into the `ch1` namespace: A label
+/// `<intro>` in `chapter1.typ` becomes `<ch1.intro>`. Within the chapter, you
+/// can still reference it as `@intro`, while the rest of the document refers to
+/// it as `@ch1.intro`. This prevents collisions between equally named labels of
+/// different chapters.
+///
 /// Currently, labels can only be attached to elements in markup mode, not in
 /// code mode. This might change in the future.
 #[ty(scope)]
@@ -74,5 +84,64 @@ impl From<Label> for PicoStr {
     }
 }
 
+/// Places the labels of content into a namespace.
+///
+/// This is set by `{include "file.typ" as name}`.
+#[elem]
+pub struct LabelNamespaceElem {
+    /// The active namespaces, from the innermost to the outermost one.
+    #[internal]
+    #[fold]
+    pub namespaces: Vec<EcoString>,
+}
+
+impl LabelNamespaceElem {
+    /// Qualify a label with all namespaces that are active in the styles.
+    pub fn qualify(label: Label, styles: StyleChain) -> Label {
+        Self::candidates(label, styles).next().unwrap_or(label)
+    }
+
+    /// Qualify the label selector of a show rule with the namespaces that are
+    /// active where the rule is defined, i.e. in the styles `outer` to it.
+    ///
+    /// This way, a `{show <intro>: ..}` rule within an included chapter
+    /// applies to the chapter's own, qualified `<intro>` label.
+    pub fn qualify_recipe<'a>(recipe: &'a Recipe, outer: StyleChain) -> Cow<'a, Recipe> {
+        let Some(Selector::Label(label)) = recipe.selector else {
+            return Cow::Borrowed(recipe);
+        };
+
+        let qualified = Self::qualify(label, outer);
+        if qualified == label {
+            return Cow::Borrowed(recipe);
+        }
+
+        let mut recipe = recipe.clone();
+        recipe.selector = Some(Selector::Label(qualified));
+        Cow::Owned(recipe)
+    }
+
+    /// The labels that a reference to `label` could resolve to, from the most
+    /// to the least specific one. The last candidate is always the unqualified
+    /// label itself.
+    pub fn candidates(label: Label, styles: StyleChain) -> impl Iterator<Item = Label> {
+        let mut namespaces = Self::namespaces_in(styles);
+        namespaces.reverse();
+        (0..=namespaces.len()).rev().map(move |n| {
+            if n == 0 {
+                return label;
+            }
+
+            let mut name = EcoString::new();
+            for namespace in &namespaces[..n] {
+                name.push_str(namespace);
+                name.push('.');
+            }
+            name.push_str(label.as_str());
+            Label::new(name.as_str())
+        })
+    }
+}
+
 /// Indicates that an element cannot be labelled.
 pub trait Unlabellable {}
@@ -23,12 +23,14 @@ mod label;
 mod methods;
 mod module;
 mod none;
+mod number;
 mod plugin;
 mod scope;
 mod selector;
 mod str;
 mod styles;
 mod ty;
+mod typed;
 mod value;
 mod version;
 
@@ -50,6 +52,7 @@ pub use self::label::*;
 pub use self::methods::*;
 pub use self::module::*;
 pub use self::none::*;
+pub use self::number::*;
 pub use self::plugin::*;
 pub use self::repr::Repr;
 pub use self::scope::*;
@@ -57,6 +60,7 @@ pub use self::selector::*;
 pub use self::str::*;
 pub use self::styles::*;
 pub use self::ty::*;
+pub use self::typed::*;
 pub use self::value::*;
 pub use self::version::*;
 
@@ -70,10 +74,10 @@ pub use {
 
 use ecow::EcoString;
 
-use crate::diag::{bail, SourceResult, StrResult};
+use crate::diag::{bail, SourceDiagnostic, SourceResult, StrResult, WarningCategory};
 use crate::engine::Engine;
 use crate::eval::EvalMode;
-use crate::syntax::Spanned;
+use crate::syntax::{Span, Spanned};
 
 /// Foundational types and functions.
 ///
@@ -107,10 +111,12 @@ pub(super) fn define(global: &mut Scope) {
     global.define_func::<repr::repr>();
     global.define_func::<panic>();
     global.define_func::<assert>();
+    global.define_func::<warn>();
     global.define_func::<eval>();
     global.define_func::<style>();
+    global.define("revert", Revert);
     global.define_module(calc::module());
-    global.define_module(sys::module());
+    global.define_module(sys::module(Dict::new()));
 }
 
 /// Fails with an error.
@@ -239,6 +245,95 @@ impl assert {
         }
         Ok(NoneValue)
     }
+
+    /// Checks that a condition is fulfilled without aborting compilation.
+    ///
+    /// Emits a [warning]($warn) if the condition is not fulfilled. Does not
+    /// produce any output in the document.
+    ///
+    /// ```typ
+    /// #assert.soft(1 < 2, message: "math broke")
+    /// ```
+    #[func(title = "Soft Assert")]
+    pub fn soft(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite span.
+        span: Span,
+        /// The condition that should be true.
+        condition: bool,
+        /// The warning message when the assertion fails.
+        #[named]
+        message: Option<EcoString>,
+    ) -> NoneValue {
+        if !condition {
+            let message = match message {
+                Some(message) => eco_format!("assertion failed: {message}"),
+                None => "assertion failed".into(),
+            };
+            engine.tracer.warn(
+                SourceDiagnostic::warning(span, message)
+                    .with_category(WarningCategory::User),
+            );
+        }
+        NoneValue
+    }
+}
+
+/// Emits a warning without aborting compilation.
+///
+/// The warning is reported at the place where `warn` is called. Templates can
+/// use this to flag questionable usage, like a deprecated argument, while
+/// still producing a document. Does not produce any output in the document.
+///
+/// # Example
+/// ```typ
+/// #warn("the `title` argument is deprecated")
+/// ```
+#[func(scope, keywords = ["warning", "deprecated"])]
+pub fn warn(
+    /// The engine.
+    engine: &mut Engine,
+    /// The callsite span.
+    span: Span,
+    /// The message to display to the user.
+    message: EcoString,
+) -> NoneValue {
+    engine.tracer.warn(
+        SourceDiagnostic::warning(span, message).with_category(WarningCategory::User),
+    );
+    NoneValue
+}
+
+#[scope]
+impl warn {
+    /// Turns warnings of the given categories into errors.
+    ///
+    /// This applies to all warnings that stem from the file in which it is
+    /// called, no matter whether they are raised before or after the call. A
+    /// template can use this to insist on clean markup in its own files, while
+    /// a build can promote categories for all files at once.
+    ///
+    /// ```typ
+    /// #warn.promote("user", "import")
+    /// ```
+    #[func]
+    pub fn promote(
+        /// The engine.
+        engine: &mut Engine,
+        /// The callsite span.
+        span: Span,
+        /// The categories of warnings to promote.
+        #[variadic]
+        categories: Vec<WarningCategory>,
+    ) -> NoneValue {
+        if let Some(id) = span.id() {
+            for category in categories {
+                engine.tracer.promote_in(id, category);
+            }
+        }
+        NoneValue
+    }
 }
 
 /// Evaluates a string as Typst code.
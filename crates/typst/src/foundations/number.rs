@@ -0,0 +1,235 @@
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, StrResult};
+use crate::foundations::{repr, Cast, Str};
+use crate::text::{Lang, Region};
+use crate::util::option_eq;
+
+/// How to format a number.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum NumberStyle {
+    /// A plain decimal number.
+    #[default]
+    Decimal,
+    /// A percentage. The number is multiplied by 100.
+    Percent,
+}
+
+/// Options for formatting a number.
+pub(crate) struct NumberFormat {
+    /// The language whose conventions to follow.
+    pub lang: Lang,
+    /// The region whose conventions to follow.
+    pub region: Option<Region>,
+    /// The number of fractional digits or `None` for as many as needed.
+    pub precision: Option<usize>,
+    /// Whether to separate groups of thousands.
+    pub grouping: bool,
+    /// Whether to format a decimal number or a percentage.
+    pub style: NumberStyle,
+    /// The currency symbol, if this is an amount of money.
+    pub currency: Option<Str>,
+}
+
+impl NumberFormat {
+    /// Format a float.
+    pub fn float(&self, value: f64) -> StrResult<Str> {
+        self.check()?;
+
+        if !value.is_finite() {
+            return Ok(repr::format_float(value, None, "").into());
+        }
+
+        let abs = value.abs();
+        let digits = match (self.precision(), self.style) {
+            (Some(precision), NumberStyle::Decimal) => format!("{abs:.precision$}"),
+            (Some(precision), NumberStyle::Percent) => {
+                format!("{:.precision$}", abs * 100.0)
+            }
+            (None, NumberStyle::Decimal) => abs.to_string(),
+            // Multiplying could introduce binary rounding errors that would
+            // show up without a fixed precision, so we move the decimal point
+            // instead.
+            (None, NumberStyle::Percent) => shift_percent(&abs.to_string()),
+        };
+
+        let (integer, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+
+        // Rounding may have produced a zero, which shouldn't have a sign.
+        let zero = integer.bytes().chain(fraction.bytes()).all(|b| b == b'0');
+        Ok(self.finish(value < 0.0 && !zero, integer, fraction))
+    }
+
+    /// Format an integer.
+    pub fn int(&self, value: i64) -> StrResult<Str> {
+        self.check()?;
+
+        let value = match self.style {
+            NumberStyle::Decimal => i128::from(value),
+            NumberStyle::Percent => i128::from(value) * 100,
+        };
+
+        let integer = value.unsigned_abs().to_string();
+        let fraction = "0".repeat(self.precision().unwrap_or(0));
+        Ok(self.finish(value < 0, &integer, &fraction))
+    }
+
+    /// Ensure that the options are compatible.
+    fn check(&self) -> StrResult<()> {
+        if self.style == NumberStyle::Percent && self.currency.is_some() {
+            bail!("cannot format a percentage as a currency amount");
+        }
+        Ok(())
+    }
+
+    /// The number of fractional digits, where currency amounts default to
+    /// two.
+    fn precision(&self) -> Option<usize> {
+        self.precision.or(self.currency.as_ref().map(|_| 2))
+    }
+
+    /// Assemble the formatted number from its ASCII digits.
+    fn finish(&self, negative: bool, integer: &str, fraction: &str) -> Str {
+        let conventions = Conventions::of(self.lang, self.region);
+
+        let mut number = EcoString::new();
+        for (i, c) in integer.chars().enumerate() {
+            let remaining = integer.len() - i;
+            if self.grouping && i > 0 && remaining % 3 == 0 {
+                number.push_str(conventions.group);
+            }
+            number.push(c);
+        }
+
+        if !fraction.is_empty() {
+            number.push(conventions.decimal);
+            number.push_str(fraction);
+        }
+
+        let number = match (&self.currency, self.style) {
+            (Some(symbol), _) => conventions.currency.apply(&number, symbol),
+            (None, NumberStyle::Percent) => conventions.percent.apply(&number, "%"),
+            (None, NumberStyle::Decimal) => number,
+        };
+
+        // The sign precedes the currency or percent symbol.
+        if negative {
+            eco_format!("{}{number}", repr::MINUS_SIGN).into()
+        } else {
+            number.into()
+        }
+    }
+}
+
+/// Multiply a number in decimal notation by 100.
+fn shift_percent(digits: &str) -> String {
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let fraction = format!("{fraction:0<2}");
+    let (moved, rest) = fraction.split_at(2);
+    let integer = format!("{integer}{moved}");
+    let integer = match integer.trim_start_matches('0') {
+        "" => "0",
+        trimmed => trimmed,
+    };
+
+    if rest.is_empty() {
+        integer.into()
+    } else {
+        format!("{integer}.{rest}")
+    }
+}
+
+/// The conventions for formatting numbers in a locale.
+struct Conventions {
+    /// The decimal separator.
+    decimal: char,
+    /// The separator between groups of thousands.
+    group: &'static str,
+    /// Where the percent sign goes.
+    percent: Affix,
+    /// Where the currency symbol goes.
+    currency: Affix,
+}
+
+impl Conventions {
+    /// The conventions of a language, specialized for a region.
+    fn of(lang: Lang, region: Option<Region>) -> Self {
+        const NBSP: &str = "\u{a0}";
+        const NNBSP: &str = "\u{202f}";
+
+        let (decimal, group) = match lang {
+            Lang::GERMAN if option_eq(region, "CH") => ('.', "’"),
+            Lang::GERMAN
+            | Lang::DUTCH
+            | Lang::ITALIAN
+            | Lang::SPANISH
+            | Lang::PORTUGUESE
+            | Lang::DANISH
+            | Lang::GREEK
+            | Lang::ROMANIAN
+            | Lang::SLOVENIAN
+            | Lang::TURKISH
+            | Lang::VIETNAMESE => (',', "."),
+            Lang::FRENCH => (',', NNBSP),
+            Lang::ALBANIAN
+            | Lang::BOKMÅL
+            | Lang::CZECH
+            | Lang::FINNISH
+            | Lang::HUNGARIAN
+            | Lang::NYNORSK
+            | Lang::POLISH
+            | Lang::RUSSIAN
+            | Lang::SWEDISH
+            | Lang::UKRAINIAN => (',', NBSP),
+            _ => ('.', ","),
+        };
+
+        let percent = match lang {
+            Lang::TURKISH => Affix::Prefix(""),
+            Lang::FRENCH => Affix::Suffix(NNBSP),
+            Lang::ENGLISH
+            | Lang::CHINESE
+            | Lang::JAPANESE
+            | Lang::FILIPINO
+            | Lang::ARABIC
+            | Lang::DUTCH
+            | Lang::ITALIAN
+            | Lang::PORTUGUESE
+            | Lang::HUNGARIAN
+            | Lang::POLISH
+            | Lang::ROMANIAN => Affix::Suffix(""),
+            _ => Affix::Suffix(NBSP),
+        };
+
+        let currency = match lang {
+            Lang::ENGLISH
+            | Lang::CHINESE
+            | Lang::JAPANESE
+            | Lang::FILIPINO
+            | Lang::ARABIC
+            | Lang::TURKISH => Affix::Prefix(""),
+            Lang::GERMAN if option_eq(region, "CH") => Affix::Prefix(NBSP),
+            Lang::DUTCH => Affix::Prefix(NBSP),
+            Lang::FRENCH => Affix::Suffix(NNBSP),
+            _ => Affix::Suffix(NBSP),
+        };
+
+        Self { decimal, group, percent, currency }
+    }
+}
+
+/// Where a symbol is placed relative to a number and with which separator.
+enum Affix {
+    Prefix(&'static str),
+    Suffix(&'static str),
+}
+
+impl Affix {
+    /// Attach the symbol to the number.
+    fn apply(&self, number: &str, symbol: &str) -> EcoString {
+        match self {
+            Self::Prefix(sep) => eco_format!("{symbol}{sep}{number}"),
+            Self::Suffix(sep) => eco_format!("{number}{sep}{symbol}"),
+        }
+    }
+}
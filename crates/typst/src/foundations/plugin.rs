@@ -7,9 +7,9 @@ use wasmi::{AsContext, AsContextMut};
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::foundations::{func, repr, scope, ty, Bytes};
-use crate::syntax::Spanned;
-use crate::World;
+use crate::foundations::{func, repr, scope, ty, Bytes, Dict};
+use crate::syntax::{Span, Spanned};
+use crate::Load;
 
 /// A WebAssembly plugin.
 ///
@@ -20,8 +20,9 @@ use crate::World;
 /// and bytes.
 ///
 /// Plugins run in isolation from your system, which means that printing,
-/// reading files, or anything like that will not be supported for security
-/// reasons. To run as a plugin, a program needs to be compiled to a 32-bit
+/// reading arbitrary files, or anything like that will not be supported for
+/// security reasons. A plugin can only read the files that it was explicitly
+/// [granted access to]($plugin.files). To run as a plugin, a program needs to be compiled to a 32-bit
 /// shared WebAssembly library. Many compilers will use the
 /// [WASI ABI](https://wasi.dev/) by default or as their only option (e.g.
 /// emscripten), which allows printing, reading files, etc. This ABI will not
@@ -105,6 +106,41 @@ use crate::World;
 ///   immediately after this function returns. If the message should be
 ///   interpreted as an error message, it should be encoded as UTF-8.
 ///
+/// ## Optional imports
+/// The runtime provides further functions that a plugin module may import.
+/// They let plugins exchange large data in chunks instead of copying it in one
+/// piece and read files that they were granted access to.
+///
+/// - `(import "typst_env" "wasm_minimal_protocol_read_arg" (func (param i32 i32 i32 i32) (result i32)))`
+///
+///   Copies a chunk of an argument into plugin memory, as an alternative to
+///   `wasm_minimal_protocol_write_args_to_buffer`. The parameters are the
+///   index of the argument, the offset of the chunk in the argument, a `ptr`
+///   to a buffer, and the buffer's length. Returns the number of copied bytes,
+///   which is less than the length of the buffer at the end of the argument,
+///   or `-1` if there is no such argument.
+///
+/// - `(import "typst_env" "wasm_minimal_protocol_send_chunk_to_host" (func (param i32 i32)))`
+///
+///   Like `wasm_minimal_protocol_send_result_to_host`, but appends the buffer
+///   to the output instead of replacing it, so that the output can be sent in
+///   chunks.
+///
+/// - `(import "typst_env" "wasm_minimal_protocol_read_file" (func (param i32 i32) (result i32)))`
+///
+///   Loads a file that the plugin was granted access to. The parameters are a
+///   `ptr` to and the length of the UTF-8 encoded name of the file. Returns
+///   the length of the file, whose contents can then be copied with
+///   `wasm_minimal_protocol_read_buffer`, or `-1` if the plugin has no access
+///   to a file with this name.
+///
+/// - `(import "typst_env" "wasm_minimal_protocol_read_buffer" (func (param i32 i32 i32) (result i32)))`
+///
+///   Copies a chunk of the most recently loaded file into plugin memory. The
+///   parameters are the offset of the chunk in the file, a `ptr` to a buffer,
+///   and the buffer's length. Returns the number of copied bytes or `-1` if
+///   no file was loaded.
+///
 /// # Resources
 /// For more resources, check out the
 /// [wasm-minimal-protocol repository](https://github.com/astrale-sharp/wasm-minimal-protocol).
@@ -123,6 +159,8 @@ pub struct Plugin(Arc<Repr>);
 struct Repr {
     /// The raw WebAssembly bytes.
     bytes: Bytes,
+    /// The files the plugin may read, by the names it reads them under.
+    files: Vec<(EcoString, Bytes)>,
     /// The function defined by the WebAssembly module.
     functions: Vec<(EcoString, wasmi::Func)>,
     /// Owns all data associated with the WebAssembly module.
@@ -144,6 +182,8 @@ struct MemoryError {
 struct StoreData {
     args: Vec<Bytes>,
     output: Vec<u8>,
+    files: Vec<(EcoString, Bytes)>,
+    buffer: Option<Bytes>,
     memory_error: Option<MemoryError>,
 }
 
@@ -156,18 +196,42 @@ impl Plugin {
         engine: &mut Engine,
         /// Path to a WebAssembly file.
         path: Spanned<EcoString>,
+        /// The files the plugin may read, as a dictionary from the names under
+        /// which the plugin reads them to their paths.
+        ///
+        /// ```typ
+        /// #let highlighter = plugin(
+        ///   "highlight.wasm",
+        ///   files: ("grammar": "rust.sublime-syntax"),
+        /// )
+        /// ```
+        #[named]
+        #[default(Spanned::new(Dict::new(), Span::detached()))]
+        files: Spanned<Dict>,
     ) -> SourceResult<Plugin> {
+        let Spanned { v: files, span: files_span } = files;
+        let files = files
+            .into_iter()
+            .map(|(name, path)| {
+                let path = path.cast::<EcoString>().at(files_span)?;
+                let id = files_span.resolve_path(&path).at(files_span)?;
+                let data = engine.world.load_file(id).at(files_span)?;
+                Ok((name.into(), data))
+            })
+            .collect::<SourceResult<_>>()?;
+
         let Spanned { v: path, span } = path;
         let id = span.resolve_path(&path).at(span)?;
-        let data = engine.world.file(id).at(span)?;
-        Plugin::new(data).at(span)
+        let data = engine.world.load_file(id).at(span)?;
+        Plugin::new(data, files).at(span)
     }
 }
 
 impl Plugin {
-    /// Create a new plugin from raw WebAssembly bytes.
+    /// Create a new plugin from raw WebAssembly bytes that may read the given
+    /// files.
     #[comemo::memoize]
-    pub fn new(bytes: Bytes) -> StrResult<Self> {
+    pub fn new(bytes: Bytes, files: Vec<(EcoString, Bytes)>) -> StrResult<Self> {
         let engine = wasmi::Engine::default();
         let module = wasmi::Module::new(&engine, bytes.as_slice())
             .map_err(|err| format!("failed to load WebAssembly module ({err})"))?;
@@ -187,8 +251,37 @@ impl Plugin {
                 wasm_minimal_protocol_write_args_to_buffer,
             )
             .unwrap();
+        linker
+            .func_wrap(
+                "typst_env",
+                "wasm_minimal_protocol_read_arg",
+                wasm_minimal_protocol_read_arg,
+            )
+            .unwrap();
+        linker
+            .func_wrap(
+                "typst_env",
+                "wasm_minimal_protocol_send_chunk_to_host",
+                wasm_minimal_protocol_send_chunk_to_host,
+            )
+            .unwrap();
+        linker
+            .func_wrap(
+                "typst_env",
+                "wasm_minimal_protocol_read_file",
+                wasm_minimal_protocol_read_file,
+            )
+            .unwrap();
+        linker
+            .func_wrap(
+                "typst_env",
+                "wasm_minimal_protocol_read_buffer",
+                wasm_minimal_protocol_read_buffer,
+            )
+            .unwrap();
 
-        let mut store = Store::new(&engine, StoreData::default());
+        let data = StoreData { files: files.clone(), ..Default::default() };
+        let mut store = Store::new(&engine, data);
         let instance = linker
             .instantiate(&mut store, &module)
             .and_then(|pre_instance| pre_instance.start(&mut store))
@@ -211,7 +304,7 @@ impl Plugin {
             })
             .collect();
 
-        Ok(Plugin(Arc::new(Repr { bytes, functions, store: Mutex::new(store) })))
+        Ok(Plugin(Arc::new(Repr { bytes, files, functions, store: Mutex::new(store) })))
     }
 
     /// Call the plugin function with the given `name`.
@@ -258,8 +351,11 @@ impl Plugin {
             .map(|a| wasmi::Value::I32(a.len() as i32))
             .collect::<Vec<_>>();
 
-        // Store the input data.
-        store.data_mut().args = args;
+        // Store the input data and discard leftovers from failed calls.
+        let data = store.data_mut();
+        data.args = args;
+        data.output.clear();
+        data.buffer = None;
 
         // Call the function.
         let mut code = wasmi::Value::I32(-1);
@@ -274,8 +370,11 @@ impl Plugin {
             ));
         }
 
-        // Extract the returned data.
-        let output = std::mem::take(&mut store.data_mut().output);
+        // Extract the returned data and release the inputs.
+        let data = store.data_mut();
+        let output = std::mem::take(&mut data.output);
+        data.args.clear();
+        data.buffer = None;
 
         // Parse the functions return value.
         match code {
@@ -312,13 +411,14 @@ impl repr::Repr for Plugin {
 
 impl PartialEq for Plugin {
     fn eq(&self, other: &Self) -> bool {
-        self.0.bytes == other.0.bytes
+        self.0.bytes == other.0.bytes && self.0.files == other.0.files
     }
 }
 
 impl Hash for Plugin {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.0.bytes.hash(state);
+        self.0.files.hash(state);
     }
 }
 
@@ -328,7 +428,7 @@ fn wasm_minimal_protocol_write_args_to_buffer(
     ptr: u32,
 ) {
     let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
-    let arguments = std::mem::take(&mut caller.data_mut().args);
+    let arguments = caller.data().args.clone();
     let mut offset = ptr as usize;
     for arg in arguments {
         if memory.write(&mut caller, offset, arg.as_slice()).is_err() {
@@ -359,3 +459,97 @@ fn wasm_minimal_protocol_send_result_to_host(
     }
     caller.data_mut().output = buffer;
 }
+
+/// Copies a chunk of an argument of the plugin function into the plugin's
+/// memory.
+fn wasm_minimal_protocol_read_arg(
+    mut caller: wasmi::Caller<StoreData>,
+    index: u32,
+    offset: u32,
+    ptr: u32,
+    len: u32,
+) -> i32 {
+    let Some(arg) = caller.data().args.get(index as usize).cloned() else {
+        return -1;
+    };
+    copy_chunk(&mut caller, &arg, offset, ptr, len)
+}
+
+/// Appends a chunk to the output of the plugin function.
+fn wasm_minimal_protocol_send_chunk_to_host(
+    mut caller: wasmi::Caller<StoreData>,
+    ptr: u32,
+    len: u32,
+) {
+    let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
+    let mut buffer = std::mem::take(&mut caller.data_mut().output);
+    let start = buffer.len();
+    buffer.resize(start + len as usize, 0);
+    if memory.read(&caller, ptr as _, &mut buffer[start..]).is_err() {
+        caller.data_mut().memory_error =
+            Some(MemoryError { offset: ptr, length: len, write: false });
+        return;
+    }
+    caller.data_mut().output = buffer;
+}
+
+/// Loads a file that the plugin may read into the host buffer.
+fn wasm_minimal_protocol_read_file(
+    mut caller: wasmi::Caller<StoreData>,
+    ptr: u32,
+    len: u32,
+) -> i32 {
+    let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
+    let mut name = vec![0; len as usize];
+    if memory.read(&caller, ptr as _, &mut name).is_err() {
+        caller.data_mut().memory_error =
+            Some(MemoryError { offset: ptr, length: len, write: false });
+        return -1;
+    }
+
+    let data = caller.data_mut();
+    let Some((_, file)) = data.files.iter().find(|(v, _)| v.as_bytes() == name) else {
+        return -1;
+    };
+
+    let len = file.len() as i32;
+    data.buffer = Some(file.clone());
+    len
+}
+
+/// Copies a chunk of the host buffer into the plugin's memory.
+fn wasm_minimal_protocol_read_buffer(
+    mut caller: wasmi::Caller<StoreData>,
+    offset: u32,
+    ptr: u32,
+    len: u32,
+) -> i32 {
+    let Some(buffer) = caller.data().buffer.clone() else { return -1 };
+    copy_chunk(&mut caller, &buffer, offset, ptr, len)
+}
+
+/// Copies the chunk of `data` with at most `len` bytes starting at `offset`
+/// into the plugin's memory at `ptr`.
+///
+/// Returns the number of copied bytes.
+fn copy_chunk(
+    caller: &mut wasmi::Caller<StoreData>,
+    data: &[u8],
+    offset: u32,
+    ptr: u32,
+    len: u32,
+) -> i32 {
+    let start = (offset as usize).min(data.len());
+    let end = start.saturating_add(len as usize).min(data.len());
+    let chunk = &data[start..end];
+    let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
+    if memory.write(&mut *caller, ptr as usize, chunk).is_err() {
+        caller.data_mut().memory_error = Some(MemoryError {
+            offset: ptr,
+            length: chunk.len() as u32,
+            write: true,
+        });
+        return -1;
+    }
+    chunk.len() as i32
+}
@@ -100,6 +100,9 @@ pub enum Selector {
     Before { selector: Arc<Self>, end: Arc<Self>, inclusive: bool },
     /// Matches all matches of `selector` after `start`.
     After { selector: Arc<Self>, start: Arc<Self>, inclusive: bool },
+    /// Matches all matches of `selector` that are nested inside of a match of
+    /// `ancestor`.
+    Within { selector: Arc<Self>, ancestor: Arc<Self> },
 }
 
 impl Selector {
@@ -147,7 +150,7 @@ impl Selector {
             Self::And(selectors) => selectors.iter().all(move |sel| sel.matches(target)),
             Self::Location(location) => target.location() == Some(*location),
             // Not supported here.
-            Self::Before { .. } | Self::After { .. } => false,
+            Self::Before { .. } | Self::After { .. } | Self::Within { .. } => false,
         }
     }
 }
@@ -229,6 +232,22 @@ impl Selector {
             inclusive,
         }
     }
+
+    /// Returns a modified selector that will only match elements that are
+    /// nested inside of a match of `ancestor`, for example, all figures within
+    /// a particular labelled block.
+    #[func]
+    pub fn within(
+        self,
+        /// The element within which matches are searched, typically given as a
+        /// location or a label.
+        ancestor: LocatableSelector,
+    ) -> Selector {
+        Self::Within {
+            selector: Arc::new(self),
+            ancestor: Arc::new(ancestor.0),
+        }
+    }
 }
 
 impl From<Location> for Selector {
@@ -274,6 +293,9 @@ impl Repr for Selector {
                     inclusive_arg
                 )
             }
+            Self::Within { selector, ancestor } => {
+                eco_format!("{}.within({})", selector.repr(), ancestor.repr())
+            }
         }
     }
 }
@@ -339,7 +361,8 @@ impl FromValue for LocatableSelector {
                     }
                 }
                 Selector::Before { selector, end: split, .. }
-                | Selector::After { selector, start: split, .. } => {
+                | Selector::After { selector, start: split, .. }
+                | Selector::Within { selector, ancestor: split } => {
                     for selector in [selector, split] {
                         validate(selector)?;
                     }
@@ -414,7 +437,8 @@ impl FromValue for ShowableSelector {
                 | Selector::Location(_)
                 | Selector::Can(_)
                 | Selector::Before { .. }
-                | Selector::After { .. } => {
+                | Selector::After { .. }
+                | Selector::Within { .. } => {
                     bail!("this selector cannot be used with show")
                 }
             }
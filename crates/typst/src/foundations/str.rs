@@ -67,6 +67,20 @@ pub use ecow::eco_format;
 /// - `[\r]` for a carriage return
 /// - `[\t]` for a tab
 /// - `[\u{1f600}]` for a hexadecimal Unicode escape sequence
+///
+/// # Interpolation { #interpolation }
+/// Prefixing a string with `f` turns it into an interpolated string. Within
+/// it, code in curly braces is evaluated and its result is inserted into the
+/// string. Strings are inserted as-is and numbers, versions, labels, and types
+/// are converted just like with this type's constructor. To insert literal
+/// braces, escape them with a backslash.
+///
+/// ```example
+/// #let items = (1, 2, 3)
+/// #f"Total: {items.sum()} items" \
+/// #f"Average: {items.sum() / items.len()}" \
+/// #f"Braces: \{ and \}"
+/// ```
 #[ty(scope, title = "String")]
 #[derive(Default, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[derive(Serialize, Deserialize)]
@@ -9,20 +9,21 @@ use ecow::{eco_vec, EcoString, EcoVec};
 use once_cell::sync::Lazy;
 use smallvec::SmallVec;
 
-use crate::diag::{SourceResult, Trace, Tracepoint};
+use crate::diag::{bail, SourceResult, StrResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, func, ty, Content, Element, Func, NativeElement, Repr, Selector, Show,
+    cast, elem, func, scope, ty, Content, Element, Func, NativeElement, Repr, Selector,
+    Show, Str, Value,
 };
 use crate::syntax::Span;
 use crate::text::{FontFamily, FontList, TextElem};
 
 /// Provides access to active styles.
 ///
-/// The styles are currently opaque and only useful in combination with the
-/// [`measure`]($measure) function. See its documentation for more details. In
-/// the future, the provided styles might also be directly accessed to look up
-/// styles defined by [set rules]($styling/#set-rules).
+/// The styles can be used in combination with the [`measure`]($measure)
+/// function. See its documentation for more details. Moreover, the values of
+/// properties defined by [set rules]($styling/#set-rules) can be looked up
+/// with the [`get`]($styles.get) method.
 ///
 /// ```example
 /// #let thing(body) = style(styles => {
@@ -62,10 +63,54 @@ impl Show for StyleElem {
 }
 
 /// A list of style properties.
-#[ty]
+#[ty(scope)]
 #[derive(Default, PartialEq, Clone, Hash)]
 pub struct Styles(EcoVec<Prehashed<Style>>);
 
+#[scope]
+impl Styles {
+    /// Retrieves the active value of a settable property.
+    ///
+    /// Returns the value of the innermost set rule for the property or the
+    /// property's default value if there is none. The value is generally
+    /// returned as it was set. Some properties, however, combine the values of
+    /// all set rules, like the horizontal and vertical components of an
+    /// alignment or a relative text size with the outer size. For these, the
+    /// combined value is returned, so a text size of `{1.2em}` comes back as an
+    /// absolute length.
+    ///
+    /// ```example
+    /// #set text(size: 14pt)
+    /// #style(styles => {
+    ///   let size = styles.get(text, "size")
+    ///   [The text size is #size.]
+    /// })
+    /// ```
+    #[func]
+    pub fn get(
+        &self,
+        /// The element the property belongs to.
+        element: Element,
+        /// The name of the property.
+        field: Str,
+    ) -> StrResult<Value> {
+        let Some(param) = element
+            .params()
+            .iter()
+            .find(|param| param.name == field.as_str() && param.settable)
+        else {
+            bail!("{} does not have a settable property `{}`", element.name(), field);
+        };
+
+        let id = element.field_id(&field).unwrap();
+        let styles = StyleChain::new(self);
+        Ok(element
+            .field_from_styles(id, styles)
+            .or_else(|| param.default.map(|default| default()))
+            .unwrap_or_default())
+    }
+}
+
 impl Styles {
     /// Create a new, empty style list.
     pub fn new() -> Self {
@@ -229,6 +274,12 @@ impl Property {
         Self { elem, id, value: Block::new(value), span: None }
     }
 
+    /// Create a property that reverts the given one to its value before the
+    /// innermost set rule.
+    pub fn revert(elem: Element, id: u8) -> Self {
+        Self::new(elem, id, Revert)
+    }
+
     /// Whether this property is the given one.
     pub fn is(&self, elem: Element, id: u8) -> bool {
         self.elem == elem && self.id == id
@@ -253,6 +304,36 @@ impl Debug for Property {
     }
 }
 
+/// Reverts a style property to its value before the innermost set rule.
+///
+/// When a property is set to `{revert}` in a set rule, the innermost other set
+/// rule for the property is ignored. If there is no such rule, the property
+/// falls back to its default value. This makes it possible to restore an outer
+/// configuration without repeating it.
+///
+/// ```example
+/// #set text(fill: red)
+/// #[
+///   #set text(fill: blue)
+///   Blue
+///   #set text(fill: revert)
+///   Red
+/// ]
+/// ```
+#[ty]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Revert;
+
+cast! {
+    type Revert,
+}
+
+impl Repr for Revert {
+    fn repr(&self) -> EcoString {
+        "revert".into()
+    }
+}
+
 /// A block storage for storing style values.
 ///
 /// We're using a `Box` since values will either be contained in an `Arc` and
@@ -537,6 +618,29 @@ impl<'a> StyleChain<'a> {
         self.entries().filter_map(Style::recipe)
     }
 
+    /// Iterate over all style recipes in the chain, each paired with the part
+    /// of the chain that lies further out than the recipe.
+    pub fn recipes_with_outer(
+        self,
+    ) -> impl Iterator<Item = (&'a Recipe, StyleChain<'a>)> {
+        std::iter::successors(Some(self), |chain| chain.tail.copied()).flat_map(|chain| {
+            chain.head.iter().enumerate().rev().filter_map(move |(i, style)| {
+                let outer = StyleChain { head: &chain.head[..i], tail: chain.tail };
+                Some((style.recipe()?, outer))
+            })
+        })
+    }
+
+    /// Iterate over the set rules for properties of the given element in the
+    /// chain, innermost first, yielding the IDs of the properties alongside
+    /// the spans of the rules.
+    pub fn set_rules(self, elem: Element) -> impl Iterator<Item = (u8, Span)> + 'a {
+        self.entries()
+            .filter_map(Style::property)
+            .filter(move |property| property.is_of(elem))
+            .filter_map(|property| Some((property.id, property.span?)))
+    }
+
     /// Iterate over all values for the given property in the chain.
     pub fn properties<T: 'static>(
         self,
@@ -549,6 +653,19 @@ impl<'a> StyleChain<'a> {
                 .filter_map(Style::property)
                 .filter(move |property| property.is(func, id))
                 .map(|property| &property.value)
+                .scan(0usize, |reverted, value| {
+                    // Each revert marker skips the next outer value.
+                    if value.downcast::<Revert>().is_some() {
+                        *reverted += 1;
+                        Some(None)
+                    } else if *reverted > 0 {
+                        *reverted -= 1;
+                        Some(None)
+                    } else {
+                        Some(Some(value))
+                    }
+                })
+                .flatten()
                 .map(move |value| {
                     value.downcast().unwrap_or_else(|| {
                         panic!(
@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+
+use ecow::{eco_format, EcoString};
+use once_cell::sync::Lazy;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::eval::{eval_string, EvalMode};
+use crate::foundations::{
+    Arg, Args, Array, Bytes, Content, Datetime, Dict, Duration, FromValue, Func,
+    IntoValue, Label, NativeElement, Repr, Scope, Str, Value, Version,
+};
+use crate::layout::{Abs, Angle, Em, Fr, Length, Ratio, Rel};
+use crate::symbols::Symbol;
+use crate::syntax::{Span, Spanned};
+use crate::text::{SpaceElem, TextElem};
+use crate::util::hash128;
+use crate::Library;
+
+/// A value in its typed serialization.
+///
+/// The plain serialization of values targets data formats like JSON: Values
+/// without a counterpart in these formats, like lengths, are written as their
+/// `repr`. The typed serialization instead preserves the type of every value,
+/// so that the value can be restored with [`Typed::decode`]:
+///
+/// - `none`, booleans, integers, floats, and strings are written as in the
+///   plain serialization and arrays as sequences of typed values.
+/// - All other values are written as a map whose `type` key holds the short
+///   name of the value's type. A dictionary is written as
+///   `{type: "dictionary", value: ..}`, so that its own `type` key is never
+///   mistaken for a type tag.
+/// - Content is written with the path of its element function, like
+///   `list.item`, its fields, and its label. Fields that the element
+///   synthesizes itself are not restored. Neither is content with styles
+///   applied to it, like the result of a set rule in a content block.
+/// - Lengths, angles, ratios, relative lengths, fractions, labels, versions,
+///   datetimes, durations, bytes, and symbols are written with their
+///   components.
+/// - All other values are written with their `repr` and restored by
+///   evaluating it. This fails for values whose `repr` isn't valid code, like
+///   closures.
+pub struct Typed<'a>(pub &'a Value);
+
+impl Typed<'_> {
+    /// Restore a value from the plain deserialization of its typed
+    /// serialization, like the result of `json.decode`.
+    pub fn decode(engine: &mut Engine, data: Value, span: Span) -> SourceResult<Value> {
+        match data {
+            Value::Array(array) => array
+                .into_iter()
+                .map(|item| Self::decode(engine, item, span))
+                .collect::<SourceResult<Array>>()
+                .map(Value::Array),
+            Value::Dict(dict) => decode_tagged(engine, dict, span),
+            other => Ok(other),
+        }
+    }
+}
+
+impl Serialize for Typed<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let value = self.0;
+        let mut map = match value {
+            Value::None
+            | Value::Bool(_)
+            | Value::Int(_)
+            | Value::Float(_)
+            | Value::Str(_) => return value.serialize(serializer),
+            Value::Array(array) => {
+                return serializer.collect_seq(array.iter().map(Typed))
+            }
+            _ => serializer.serialize_map(None)?,
+        };
+
+        map.serialize_entry("type", value.ty().short_name())?;
+        match value {
+            Value::Dict(dict) => map.serialize_entry("value", &TypedDict(dict))?,
+            Value::Content(content) => {
+                map.serialize_entry("func", &path(&content.func().into()))?;
+                map.serialize_entry("fields", &TypedDict(&content.fields()))?;
+                if let Some(label) = content.label() {
+                    map.serialize_entry("label", label.as_str())?;
+                }
+            }
+            Value::Auto => {}
+            Value::Length(v) => {
+                map.serialize_entry("abs", &v.abs.to_pt())?;
+                map.serialize_entry("em", &v.em.get())?;
+            }
+            Value::Angle(v) => map.serialize_entry("deg", &v.to_deg())?,
+            Value::Ratio(v) => map.serialize_entry("ratio", &v.get())?,
+            Value::Relative(v) => {
+                map.serialize_entry("ratio", &v.rel.get())?;
+                map.serialize_entry("abs", &v.abs.abs.to_pt())?;
+                map.serialize_entry("em", &v.abs.em.get())?;
+            }
+            Value::Fraction(v) => map.serialize_entry("fr", &v.get())?,
+            Value::Label(v) => map.serialize_entry("name", v.as_str())?,
+            Value::Version(v) => map.serialize_entry("values", v.values())?,
+            Value::Datetime(v) => {
+                let components = [
+                    ("year", v.year().map(i64::from)),
+                    ("month", v.month().map(i64::from)),
+                    ("day", v.day().map(i64::from)),
+                    ("hour", v.hour().map(i64::from)),
+                    ("minute", v.minute().map(i64::from)),
+                    ("second", v.second().map(i64::from)),
+                ];
+                for (key, component) in components {
+                    if let Some(component) = component {
+                        map.serialize_entry(key, &component)?;
+                    }
+                }
+            }
+            Value::Duration(v) => map.serialize_entry("seconds", &v.seconds())?,
+            Value::Bytes(v) => map.serialize_entry("data", v)?,
+            Value::Symbol(v) => map.serialize_entry("value", &v.get())?,
+            Value::Func(func) => map.serialize_entry("repr", &path(func))?,
+            other => map.serialize_entry("repr", &other.repr())?,
+        }
+
+        map.end()
+    }
+}
+
+/// Serializes the values of a dictionary in their typed serialization.
+struct TypedDict<'a>(&'a Dict);
+
+impl Serialize for TypedDict<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(self.0.iter().map(|(key, value)| (key, Typed(value))))
+    }
+}
+
+/// Restore a value that was serialized as a map with a type tag.
+fn decode_tagged(engine: &mut Engine, mut dict: Dict, span: Span) -> SourceResult<Value> {
+    let ty: Str = take(&mut dict, "type").at(span)?;
+    let length = |dict: &mut Dict| -> StrResult<Length> {
+        Ok(Length {
+            abs: Abs::pt(take(dict, "abs")?),
+            em: Em::new(take(dict, "em")?),
+        })
+    };
+
+    Ok(match ty.as_str() {
+        "dictionary" => {
+            let mut value = Dict::new();
+            for (key, item) in take::<Dict>(&mut dict, "value").at(span)? {
+                value.insert(key, Typed::decode(engine, item, span)?);
+            }
+            value.into_value()
+        }
+        "content" => decode_content(engine, dict, span)?.into_value(),
+        "auto" => Value::Auto,
+        "length" => length(&mut dict).at(span)?.into_value(),
+        "angle" => Angle::deg(take(&mut dict, "deg").at(span)?).into_value(),
+        "ratio" => Ratio::new(take(&mut dict, "ratio").at(span)?).into_value(),
+        "relative" => {
+            let rel = Ratio::new(take(&mut dict, "ratio").at(span)?);
+            Rel::new(rel, length(&mut dict).at(span)?).into_value()
+        }
+        "fraction" => Fr::new(take(&mut dict, "fr").at(span)?).into_value(),
+        "label" => {
+            Label::new(take::<Str>(&mut dict, "name").at(span)?.as_str()).into_value()
+        }
+        "version" => take::<Vec<u32>>(&mut dict, "values")
+            .at(span)?
+            .into_iter()
+            .collect::<Version>()
+            .into_value(),
+        "datetime" => decode_datetime(&mut dict).at(span)?.into_value(),
+        "duration" => {
+            let seconds: f64 = take(&mut dict, "seconds").at(span)?;
+            Duration::from(time::Duration::seconds_f64(seconds)).into_value()
+        }
+        "bytes" => match dict.take("data").at(span)? {
+            Value::Bytes(bytes) => bytes.into_value(),
+            data => Bytes::from(data.cast::<Vec<u8>>().at(span)?).into_value(),
+        },
+        "symbol" => {
+            let value: Str = take(&mut dict, "value").at(span)?;
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Symbol::single(c).into_value(),
+                _ => bail!(span, "symbol must consist of exactly one character"),
+            }
+        }
+        _ => {
+            let repr: Str = take(&mut dict, "repr").at(span)?;
+            let value =
+                eval_string(engine.world, &repr, span, EvalMode::Code, Scope::new())?;
+            if value.ty().short_name() != ty.as_str() {
+                bail!(span, "expected {ty}, but `{repr}` is {}", value.ty().long_name());
+            }
+            value
+        }
+    })
+}
+
+/// Restore content from its element function's path, fields, and label.
+fn decode_content(
+    engine: &mut Engine,
+    mut dict: Dict,
+    span: Span,
+) -> SourceResult<Content> {
+    let path: Str = take(&mut dict, "func").at(span)?;
+    let label = dict.take("label").ok().map(Value::cast::<Str>).transpose().at(span)?;
+
+    let mut fields = Dict::new();
+    for (key, item) in take::<Dict>(&mut dict, "fields").at(span)? {
+        fields.insert(key, Typed::decode(engine, item, span)?);
+    }
+
+    let content = match path.as_str() {
+        "sequence" => {
+            Content::sequence(take::<Vec<Content>>(&mut fields, "children").at(span)?)
+        }
+        "text" => TextElem::packed(take::<EcoString>(&mut fields, "text").at(span)?),
+        "space" => SpaceElem::new().pack(),
+        _ => {
+            let func =
+                eval_string(engine.world, &path, span, EvalMode::Code, Scope::new())?;
+            let Some(elem) = (match &func {
+                Value::Func(func) => func.element(),
+                _ => None,
+            }) else {
+                bail!(span, "`{path}` is not an element function");
+            };
+
+            // Pass the fields to the element's constructor like they would be
+            // passed in a call.
+            let mut args = Args { span, items: Default::default() };
+            for param in elem.params() {
+                let Ok(value) = fields.take(param.name) else { continue };
+                if param.variadic {
+                    for item in value.cast::<Array>().at(span)? {
+                        args.push(span, item);
+                    }
+                } else if param.positional {
+                    args.push(span, value);
+                } else {
+                    args.items.push(Arg {
+                        span,
+                        name: Some(param.name.into()),
+                        value: Spanned::new(value, span),
+                    });
+                }
+            }
+
+            let content = elem.construct(engine, &mut args)?;
+            args.finish()?;
+            content
+        }
+    };
+
+    Ok(match label {
+        Some(label) => content.labelled(Label::new(label.as_str())),
+        None => content,
+    })
+}
+
+/// Restore a datetime from its components.
+fn decode_datetime(dict: &mut Dict) -> StrResult<Datetime> {
+    let mut component =
+        |key: &str| dict.take(key).ok().map(Value::cast::<u8>).transpose();
+    let (month, day) = (component("month")?, component("day")?);
+    let (hour, minute, second) =
+        (component("hour")?, component("minute")?, component("second")?);
+    let year = dict.take("year").ok().map(Value::cast::<i32>).transpose()?;
+    let datetime = match (year, month, day, hour, minute, second) {
+        (Some(y), Some(mo), Some(d), None, None, None) => Datetime::from_ymd(y, mo, d),
+        (None, None, None, Some(h), Some(mi), Some(s)) => Datetime::from_hms(h, mi, s),
+        (Some(y), Some(mo), Some(d), Some(h), Some(mi), Some(s)) => {
+            Datetime::from_ymd_hms(y, mo, d, h, mi, s)
+        }
+        _ => bail!("datetime must have a full date, a full time, or both"),
+    };
+    datetime.ok_or_else(|| "datetime is invalid".into())
+}
+
+/// Take a key from a dictionary and cast its value.
+fn take<T: FromValue>(dict: &mut Dict, key: &str) -> StrResult<T> {
+    dict.take(key)?.cast()
+}
+
+/// The path under which a function is defined in the standard library, like
+/// `list.item`, or its `repr` if it isn't part of it.
+fn path(func: &Func) -> EcoString {
+    /// The paths of all functions in the standard library, keyed by the hash
+    /// of the function.
+    static PATHS: Lazy<HashMap<u128, EcoString>> = Lazy::new(|| {
+        let mut paths = HashMap::new();
+        collect_paths(Library::build().global.scope(), "", &mut paths);
+        paths
+    });
+
+    PATHS.get(&key(func)).cloned().unwrap_or_else(|| func.repr())
+}
+
+/// Identifies a function independently of where it is used.
+fn key(func: &Func) -> u128 {
+    hash128(&func.clone().spanned(Span::detached()))
+}
+
+/// Record the paths of all functions in a scope and the scopes nested in it.
+/// A function that is reachable under multiple paths keeps the shortest one.
+fn collect_paths(scope: &Scope, prefix: &str, paths: &mut HashMap<u128, EcoString>) {
+    for (name, value) in scope.iter() {
+        let path =
+            if prefix.is_empty() { name.clone() } else { eco_format!("{prefix}.{name}") };
+
+        let nested = match value {
+            Value::Func(func) => {
+                let known = paths.entry(key(func)).or_insert_with(|| path.clone());
+                if known.len() > path.len() {
+                    *known = path.clone();
+                }
+                func.scope()
+            }
+            Value::Module(module) => Some(module.scope()),
+            Value::Type(ty) => Some(ty.scope()),
+            _ => None,
+        };
+
+        // The standard library is shallow, so this bounds the recursion in
+        // case a scope refers back to an enclosing one.
+        if let Some(nested) = nested.filter(|_| prefix.matches('.').count() < 2) {
+            collect_paths(nested, &path, paths);
+        }
+    }
+}
@@ -675,7 +675,7 @@ primitive! { Plugin: "plugin", Plugin }
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::foundations::{array, dict};
+    use crate::foundations::{array, dict, Typed};
 
     #[track_caller]
     fn test(value: impl IntoValue, exp: &str) {
@@ -707,4 +707,38 @@ mod tests {
         test(dict!["one" => 1], "(one: 1)");
         test(dict!["two" => false, "one" => 1], "(two: false, one: 1)");
     }
+
+    #[track_caller]
+    fn test_json(value: impl IntoValue, exp: &str) {
+        assert_eq!(serde_json::to_string(&value.into_value()).unwrap(), exp);
+    }
+
+    #[test]
+    fn test_value_serialize() {
+        test_json(Value::None, "null");
+        test_json(12i64, "12");
+        test_json(array![1, "a"], r#"[1,"a"]"#);
+        test_json(Value::Auto, r#""auto""#);
+        test_json(Abs::pt(5.5), r#""5.5pt""#);
+        test_json(Label::new("intro"), r#""<intro>""#);
+    }
+
+    #[track_caller]
+    fn test_typed(value: impl IntoValue, exp: &str) {
+        let value = value.into_value();
+        assert_eq!(serde_json::to_string(&Typed(&value)).unwrap(), exp);
+    }
+
+    #[test]
+    fn test_value_serialize_typed() {
+        test_typed(Value::None, "null");
+        test_typed(array![1, Value::Auto], r#"[1,{"type":"auto"}]"#);
+        test_typed(Abs::pt(5.5), r#"{"type":"length","abs":5.5,"em":0.0}"#);
+        test_typed(Ratio::one() / 2.0, r#"{"type":"ratio","ratio":0.5}"#);
+        test_typed(Label::new("intro"), r#"{"type":"label","name":"intro"}"#);
+        test_typed(
+            dict!["type" => "user"],
+            r#"{"type":"dictionary","value":{"type":"user"}}"#,
+        );
+    }
 }
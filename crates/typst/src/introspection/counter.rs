@@ -236,8 +236,11 @@ impl Counter {
         let (mut at_state, at_page) = sequence[offset].clone();
         let (mut final_state, final_page) = sequence.last().unwrap().clone();
         if self.is_page() {
-            let at_delta =
-                engine.introspector.page(location).get().saturating_sub(at_page.get());
+            let at_delta = engine
+                .introspector
+                .absolute_page(location)
+                .get()
+                .saturating_sub(at_page.get());
             at_state.step(NonZeroUsize::ONE, at_delta);
             let final_delta =
                 engine.introspector.pages().get().saturating_sub(final_page.get());
@@ -289,7 +292,7 @@ impl Counter {
         for elem in introspector.query(&self.selector()) {
             if self.is_page() {
                 let prev = page;
-                page = introspector.page(elem.location().unwrap());
+                page = introspector.absolute_page(elem.location().unwrap());
 
                 let delta = page.get() - prev.get();
                 if delta > 0 {
@@ -426,8 +429,11 @@ impl Counter {
             .len();
         let (mut state, page) = sequence[offset].clone();
         if self.is_page() {
-            let delta =
-                engine.introspector.page(location).get().saturating_sub(page.get());
+            let delta = engine
+                .introspector
+                .absolute_page(location)
+                .get()
+                .saturating_sub(page.get());
             state.step(NonZeroUsize::ONE, delta);
         }
 
@@ -436,20 +442,30 @@ impl Counter {
 
     /// Gets the value of the counter at the end of the document. Always returns
     /// an array of integers, even if the counter has just one number.
+    ///
+    /// This can be called anywhere, also outside of [`locate`]($locate).
+    /// Typst then evaluates the calling code repeatedly until the final value
+    /// has converged.
+    ///
+    /// ```example
+    /// #let total = counter(figure).final().first()
+    /// This document has #total figures.
+    ///
+    /// #figure(rect(), caption: [One])
+    /// #figure(rect(), caption: [Two])
+    /// ```
     #[func]
     pub fn final_(
         &self,
         /// The engine.
         engine: &mut Engine,
         /// Can be an arbitrary location, as its value is irrelevant for the
-        /// method's return value. Why is it required then? Typst has to
-        /// evaluate parts of your code multiple times to determine all counter
-        /// values. By only allowing this method within [`locate`]($locate)
-        /// calls, the amount of code that can depend on the method's result is
-        /// reduced. If you could call `final` directly at the top level of a
-        /// module, the evaluation of the whole module and its exports could
-        /// depend on the counter's value.
-        location: Location,
+        /// method's return value. It can be omitted, but passing it restricts
+        /// the code that depends on the counter's value to a
+        /// [`locate`]($locate) call, which reduces how much of the document
+        /// Typst has to evaluate multiple times.
+        #[default]
+        location: Option<Location>,
     ) -> SourceResult<CounterState> {
         let _ = location;
         let sequence = self.sequence(engine)?;
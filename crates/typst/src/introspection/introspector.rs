@@ -1,5 +1,5 @@
-use std::cell::RefCell;
-use std::collections::{BTreeSet, HashMap};
+use std::cell::{OnceCell, RefCell};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::hash::Hash;
 use std::num::NonZeroUsize;
 
@@ -7,21 +7,40 @@ use comemo::Prehashed;
 use ecow::{eco_format, EcoVec};
 use indexmap::IndexMap;
 
+use super::statistics_::page_text;
 use crate::diag::{bail, StrResult};
-use crate::foundations::{Content, Label, Repr, Selector};
-use crate::introspection::{Location, Meta};
-use crate::layout::{Frame, FrameItem, Point, Position, Transform};
-use crate::model::Numbering;
+use crate::foundations::{Content, Label, NativeElement, Repr, Selector, StyleChain};
+use crate::introspection::{Location, Meta, Statistics};
+use crate::layout::{
+    Bounds, Frame, FrameItem, PageMetadata, Point, Position, Size, Transform,
+};
+use crate::model::{HeadingElem, Numbering};
 use crate::util::NonZeroExt;
 
 /// Can be queried for elements and their positions.
 pub struct Introspector {
     /// The number of pages in the document.
     pages: usize,
-    /// All introspectable elements.
-    elems: IndexMap<Location, (Prehashed<Content>, Position)>,
+    /// The number of pages before each document except for the first one, if
+    /// the introspector spans multiple documents.
+    starts: Vec<usize>,
+    /// All introspectable elements with their positions, bounding boxes, and
+    /// transforms.
+    elems: IndexMap<Location, (Prehashed<Content>, Position, Bounds, Transform)>,
     /// The page numberings, indexed by page number minus 1.
     page_numberings: Vec<Option<Numbering>>,
+    /// The page metadata, indexed by page number minus 1.
+    page_metadata: Vec<PageMetadata>,
+    /// Maps from the location of each nested element to the location of its
+    /// innermost enclosing element.
+    parents: IndexMap<Location, Location>,
+    /// The text of each page, from which words and characters are counted.
+    texts: Vec<String>,
+    /// Statistics about the document, computed on first use.
+    statistics: OnceCell<Statistics>,
+    /// The state after each page, used to roll back to the unchanged pages
+    /// when the introspector is updated.
+    checkpoints: Vec<Checkpoint>,
     /// Caches queries done on the introspector. This is important because
     /// even if all top-level queries are distinct, they often have shared
     /// subqueries. Example: Individual counter queries with `before` that
@@ -34,18 +53,137 @@ impl Introspector {
     #[tracing::instrument(skip(frames))]
     pub fn new(frames: &[Frame]) -> Self {
         let mut introspector = Self {
-            pages: frames.len(),
+            pages: 0,
+            starts: vec![],
             elems: IndexMap::new(),
             page_numberings: vec![],
+            page_metadata: vec![],
+            parents: IndexMap::new(),
+            texts: vec![],
+            statistics: OnceCell::new(),
+            checkpoints: vec![],
             queries: RefCell::default(),
         };
-        for (i, frame) in frames.iter().enumerate() {
-            let page = NonZeroUsize::new(1 + i).unwrap();
-            introspector.extract(frame, page, Transform::identity());
+        for frame in frames {
+            introspector.push_page(frame);
         }
         introspector
     }
 
+    /// Extend the introspector with the next page of the document.
+    ///
+    /// This allows building up an introspector while the pages are streamed,
+    /// without holding on to all of their frames.
+    pub fn push_page(&mut self, frame: &Frame) {
+        self.push_hashed_page(frame, crate::util::hash128(frame));
+        self.queries.get_mut().clear();
+        self.statistics = OnceCell::new();
+    }
+
+    /// Update the introspector for a new version of the document.
+    ///
+    /// Only the pages from the first one that differs from the previous
+    /// version onwards are extracted again. This keeps relayouts that only
+    /// affect the later pages of a large document cheap.
+    ///
+    /// Returns whether any page changed. If none did, all introspections
+    /// performed on the previous version still hold. Changes are only
+    /// tracked per page and not per query: Which of the introspections are
+    /// actually affected by a change is determined by validating the
+    /// constraints that comemo recorded for them.
+    #[tracing::instrument(skip_all)]
+    pub fn update(&mut self, frames: &[Frame]) -> bool {
+        let hashes: Vec<u128> = frames.iter().map(crate::util::hash128).collect();
+        let unchanged = self
+            .checkpoints
+            .iter()
+            .zip(&hashes)
+            .take_while(|(checkpoint, &hash)| checkpoint.hash == hash)
+            .count();
+
+        if unchanged == self.pages && unchanged == frames.len() {
+            return false;
+        }
+
+        self.truncate(unchanged);
+        for (frame, hash) in frames.iter().zip(hashes).skip(unchanged) {
+            self.push_hashed_page(frame, hash);
+        }
+        self.queries.get_mut().clear();
+        self.statistics = OnceCell::new();
+        true
+    }
+
+    /// Divide the pages into consecutive documents with the given numbers of
+    /// pages.
+    ///
+    /// Page numbers and positions are then relative to the document an
+    /// element is in. Returns whether the division changed.
+    pub fn divide(&mut self, counts: &[usize]) -> bool {
+        let starts: Vec<usize> = counts
+            .iter()
+            .scan(0, |start, count| {
+                *start += count;
+                Some(*start)
+            })
+            .take(counts.len().saturating_sub(1))
+            .collect();
+
+        if starts == self.starts {
+            return false;
+        }
+
+        self.starts = starts;
+        self.queries.get_mut().clear();
+        true
+    }
+
+    /// The index of the document the page with the given absolute number is
+    /// in, along with the number of pages before that document.
+    fn document_of(&self, page: NonZeroUsize) -> (usize, usize) {
+        let index = self.starts.partition_point(|&start| start < page.get());
+        let offset = index.checked_sub(1).map_or(0, |i| self.starts[i]);
+        (index, offset)
+    }
+
+    /// Make an absolute page number relative to its document.
+    fn relative(&self, page: NonZeroUsize) -> NonZeroUsize {
+        let (_, offset) = self.document_of(page);
+        NonZeroUsize::new(page.get() - offset).unwrap()
+    }
+
+    /// Extend the introspector with a page whose frame has the given hash.
+    fn push_hashed_page(&mut self, frame: &Frame, hash: u128) {
+        self.pages += 1;
+        self.texts.push(page_text(frame));
+        let page = NonZeroUsize::new(self.pages).unwrap();
+        self.extract(frame, page, Transform::identity());
+        self.checkpoints.push(Checkpoint {
+            hash,
+            elems: self.elems.len(),
+            page_numberings: self.page_numberings.len(),
+            page_metadata: self.page_metadata.len(),
+            parents: self.parents.len(),
+        });
+    }
+
+    /// Roll the introspector back to the state after the given number of
+    /// pages.
+    fn truncate(&mut self, pages: usize) {
+        let checkpoint = match pages.checked_sub(1) {
+            Some(i) => self.checkpoints[i].clone(),
+            None => Checkpoint::default(),
+        };
+
+        self.elems.truncate(checkpoint.elems);
+        self.pages = pages;
+        self.texts.truncate(pages);
+        self.page_numberings.truncate(checkpoint.page_numberings);
+        self.page_metadata.truncate(checkpoint.page_metadata);
+        self.parents.truncate(checkpoint.parents);
+        self.checkpoints.truncate(pages);
+    }
+
     /// Extract metadata from a frame.
     #[tracing::instrument(skip_all)]
     fn extract(&mut self, frame: &Frame, page: NonZeroUsize, ts: Transform) {
@@ -57,19 +195,27 @@ impl Introspector {
                         .pre_concat(group.transform);
                     self.extract(&group.frame, page, ts);
                 }
-                FrameItem::Meta(Meta::Elem(content), _)
+                FrameItem::Meta(Meta::Elem(content), size)
                     if !self.elems.contains_key(&content.location().unwrap()) =>
                 {
-                    let pos = pos.transform(ts);
+                    let position = Position { page, point: pos.transform(ts) };
+                    let bounds = bounding_box(page, *pos, *size, ts);
+                    let transform = ts.pre_concat(Transform::translate(pos.x, pos.y));
                     let ret = self.elems.insert(
                         content.location().unwrap(),
-                        (Prehashed::new(content.clone()), Position { page, point: pos }),
+                        (Prehashed::new(content.clone()), position, bounds, transform),
                     );
                     assert!(ret.is_none(), "duplicate locations");
                 }
                 FrameItem::Meta(Meta::PageNumbering(numbering), _) => {
                     self.page_numberings.push(numbering.clone());
                 }
+                FrameItem::Meta(Meta::PageMetadata(metadata), _) => {
+                    self.page_metadata.push(metadata.clone());
+                }
+                FrameItem::Meta(Meta::Parent { child, parent }, _) => {
+                    self.parents.insert(*child, *parent);
+                }
                 _ => {}
             }
         }
@@ -77,12 +223,12 @@ impl Introspector {
 
     /// Iterate over all locatable elements.
     pub fn all(&self) -> impl Iterator<Item = &Prehashed<Content>> + '_ {
-        self.elems.values().map(|(c, _)| c)
+        self.elems.values().map(|(c, ..)| c)
     }
 
     /// Get an element by its location.
     fn get(&self, location: &Location) -> Option<&Prehashed<Content>> {
-        self.elems.get(location).map(|(elem, _)| elem)
+        self.elems.get(location).map(|(elem, ..)| elem)
     }
 
     /// Get the index of this element among all.
@@ -92,6 +238,18 @@ impl Introspector {
             .unwrap_or(usize::MAX)
     }
 
+    /// Whether the element at `location` is nested inside of any of the
+    /// `ancestors`.
+    fn is_within(&self, mut location: Location, ancestors: &HashSet<Location>) -> bool {
+        while let Some(&parent) = self.parents.get(&location) {
+            if ancestors.contains(&parent) {
+                return true;
+            }
+            location = parent;
+        }
+        false
+    }
+
     /// Perform a binary search for `elem` among the `list`.
     fn binary_search(
         &self,
@@ -149,6 +307,18 @@ impl Introspector {
                 }
                 list
             }
+            Selector::Within { selector, ancestor } => {
+                let ancestors: HashSet<Location> = self
+                    .query(ancestor)
+                    .iter()
+                    .filter_map(|elem| elem.location())
+                    .collect();
+                self.query(selector)
+                    .iter()
+                    .filter(|elem| self.is_within(elem.location().unwrap(), &ancestors))
+                    .cloned()
+                    .collect()
+            }
             Selector::And(selectors) => {
                 let mut results: Vec<_> =
                     selectors.iter().map(|sel| self.query(sel)).collect();
@@ -208,31 +378,181 @@ impl Introspector {
         })
     }
 
-    /// The total number pages.
+    /// The elements enclosing the given location, innermost first.
+    pub fn ancestors(&self, mut location: Location) -> EcoVec<Prehashed<Content>> {
+        let mut ancestors = EcoVec::new();
+        while let Some(&parent) = self.parents.get(&location) {
+            if let Some(elem) = self.get(&parent) {
+                ancestors.push(elem.clone());
+            }
+            location = parent;
+        }
+        ancestors
+    }
+
+    /// The headings of the sections the given location belongs to, outermost
+    /// first.
+    ///
+    /// A section extends from its heading up to the next heading of the same
+    /// or a lower level. A heading belongs to its own section.
+    pub fn headings(&self, location: Location) -> EcoVec<Prehashed<Content>> {
+        let Some(end) = self.elems.get_index_of(&location) else {
+            return EcoVec::new();
+        };
+
+        let mut headings = vec![];
+        let mut depth = usize::MAX;
+        let selector = Selector::Elem(HeadingElem::elem(), None);
+        for elem in self.query(&selector).iter().rev() {
+            if self.index(elem) > end {
+                continue;
+            }
+
+            let level =
+                elem.to::<HeadingElem>().unwrap().level(StyleChain::default()).get();
+
+            if level < depth {
+                headings.push(elem.clone());
+                depth = level;
+            }
+
+            if depth == 1 {
+                break;
+            }
+        }
+
+        headings.into_iter().rev().collect()
+    }
+
+    /// The total number pages, across all documents.
     pub fn pages(&self) -> NonZeroUsize {
         NonZeroUsize::new(self.pages).unwrap_or(NonZeroUsize::ONE)
     }
 
+    /// Statistics about the document.
+    pub fn statistics(&self) -> Statistics {
+        self.statistics
+            .get_or_init(|| {
+                Statistics::collect(
+                    &self.texts,
+                    self.elems.values().map(|(content, ..)| &**content),
+                )
+            })
+            .clone()
+    }
+
     /// Gets the page numbering for the given location, if any.
     pub fn page_numbering(&self, location: Location) -> Option<&Numbering> {
-        let page = self.page(location);
+        let page = self.absolute_page(location);
         self.page_numberings
             .get(page.get() - 1)
             .and_then(|slot| slot.as_ref())
     }
 
-    /// Find the page number for the given location.
+    /// Gets the metadata of the page at the given location.
+    pub fn page_metadata(&self, location: Location) -> PageMetadata {
+        let page = self.absolute_page(location);
+        self.page_metadata.get(page.get() - 1).cloned().unwrap_or_default()
+    }
+
+    /// Find the page number for the given location, relative to the document
+    /// it is in.
     pub fn page(&self, location: Location) -> NonZeroUsize {
-        self.position(location).page
+        self.relative(self.absolute_page(location))
+    }
+
+    /// Find the page number for the given location, counting the pages of all
+    /// documents the introspector spans.
+    pub fn absolute_page(&self, location: Location) -> NonZeroUsize {
+        self.elems
+            .get(&location)
+            .map_or(NonZeroUsize::ONE, |(_, pos, ..)| pos.page)
     }
 
-    /// Find the position for the given location.
+    /// Find the index of the document the given location is in, if the
+    /// location is known.
+    ///
+    /// This is always zero unless the introspector spans multiple documents,
+    /// as for [`compile_project`](crate::compile_project).
+    pub fn document(&self, location: Location) -> Option<usize> {
+        self.elems
+            .get(&location)
+            .map(|(_, pos, ..)| self.document_of(pos.page).0)
+    }
+
+    /// Find the position for the given location, relative to the document it
+    /// is in.
     pub fn position(&self, location: Location) -> Position {
         self.elems
             .get(&location)
-            .map(|(_, loc)| *loc)
+            .map(|(_, pos, ..)| Position { page: self.relative(pos.page), ..*pos })
             .unwrap_or(Position { page: NonZeroUsize::ONE, point: Point::zero() })
     }
+
+    /// Find the bounding box of the element with the given location, relative
+    /// to the document it is in.
+    pub fn bounds(&self, location: Location) -> Bounds {
+        self.elems
+            .get(&location)
+            .map(|(_, _, bounds, _)| Bounds {
+                page: self.relative(bounds.page),
+                ..*bounds
+            })
+            .unwrap_or(Bounds {
+                page: NonZeroUsize::ONE,
+                point: Point::zero(),
+                size: Size::zero(),
+            })
+    }
+
+    /// Find the locations and bounding boxes of all elements with a label,
+    /// relative to the document they are in.
+    pub fn labelled_bounds(&self) -> EcoVec<(Location, Bounds)> {
+        self.elems
+            .iter()
+            .filter(|(_, (elem, ..))| elem.label().is_some())
+            .map(|(&location, _)| (location, self.bounds(location)))
+            .collect()
+    }
+
+    /// Find the transform from the coordinate system of the element with the
+    /// given location, whose origin is at the element's position, to the
+    /// coordinate system of its page.
+    ///
+    /// This includes the transformations of all groups the element is in, so
+    /// that content drawn by the element can be aligned with page
+    /// coordinates even if the element is rotated or scaled.
+    pub fn transform(&self, location: Location) -> Transform {
+        self.elems
+            .get(&location)
+            .map_or(Transform::identity(), |(.., transform)| *transform)
+    }
+}
+
+/// The axis-aligned bounding box of a frame of the given size at `pos` after
+/// it was transformed by `ts`.
+fn bounding_box(page: NonZeroUsize, pos: Point, size: Size, ts: Transform) -> Bounds {
+    let corners =
+        [Point::zero(), Point::with_x(size.x), Point::with_y(size.y), size.to_point()]
+            .map(|corner| (pos + corner).transform(ts));
+    let min = corners.into_iter().reduce(Point::min).unwrap();
+    let max = corners.into_iter().reduce(Point::max).unwrap();
+    Bounds { page, point: min, size: (max - min).to_size() }
+}
+
+/// The state of an [`Introspector`] after one of the pages.
+#[derive(Debug, Default, Clone)]
+struct Checkpoint {
+    /// The hash of the page's frame.
+    hash: u128,
+    /// The number of elements.
+    elems: usize,
+    /// The number of page numberings.
+    page_numberings: usize,
+    /// The number of page metadata entries.
+    page_metadata: usize,
+    /// The number of parent relations.
+    parents: usize,
 }
 
 impl Default for Introspector {
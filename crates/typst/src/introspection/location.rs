@@ -3,7 +3,8 @@ use std::num::NonZeroUsize;
 use ecow::EcoString;
 
 use crate::engine::Engine;
-use crate::foundations::{cast, func, scope, ty, Dict, Repr};
+use crate::foundations::{cast, func, scope, ty, Array, Content, Dict, Repr, Value};
+use crate::layout::PageMetadata;
 use crate::model::Numbering;
 
 /// Identifies an element in the document.
@@ -60,6 +61,26 @@ impl Location {
         engine.introspector.position(self).into()
     }
 
+    /// Return a dictionary with the page number, the x, y position, and the
+    /// width and height of the element at this location.
+    ///
+    /// The box is measured like the position from the top-left of the page.
+    /// It encloses the element after rotation and scaling. For an element that
+    /// breaks across pages, it only covers the part on the first page. The
+    /// `geom` module has helpers for working with these bounds.
+    ///
+    /// ```example
+    /// #rect[Box] <box>
+    /// #locate(loc => {
+    ///   let bounds = query(<box>, loc).first().location().bounds()
+    ///   [#bounds.width × #bounds.height]
+    /// })
+    /// ```
+    #[func]
+    pub fn bounds(self, engine: &mut Engine) -> Dict {
+        engine.introspector.bounds(self).into()
+    }
+
     /// Returns the page numbering pattern of the page at this location. This
     /// can be used when displaying the page counter in order to obtain the
     /// local numbering. This is useful if you are building custom indices or
@@ -71,6 +92,75 @@ impl Location {
     pub fn page_numbering(self, engine: &mut Engine) -> Option<Numbering> {
         engine.introspector.page_numbering(self).cloned()
     }
+
+    /// Returns the innermost located element that encloses this location, or
+    /// `{none}` if there is none.
+    ///
+    /// Only elements that can be [queried]($query) are considered. For
+    /// example, a location inside of a figure's body has the figure as its
+    /// parent.
+    #[func]
+    pub fn parent(self, engine: &mut Engine) -> Option<Content> {
+        engine
+            .introspector
+            .ancestors(self)
+            .first()
+            .map(|elem| elem.clone().into_inner())
+    }
+
+    /// Returns all located elements that enclose this location, from the
+    /// innermost to the outermost.
+    ///
+    /// ```example
+    /// #figure(locate(loc => {
+    ///   loc.ancestors().map(it => it.func()).first()
+    /// }), caption: [Nested])
+    /// ```
+    #[func]
+    pub fn ancestors(self, engine: &mut Engine) -> Array {
+        engine
+            .introspector
+            .ancestors(self)
+            .into_iter()
+            .map(|elem| Value::Content(elem.into_inner()))
+            .collect()
+    }
+
+    /// Returns the headings of the sections this location belongs to, from
+    /// the outermost to the innermost. This is useful to build breadcrumbs in
+    /// page headers.
+    ///
+    /// ```example
+    /// = Introduction
+    /// == Motivation
+    /// #locate(loc => {
+    ///   loc.headings().map(it => it.body).join[ › ]
+    /// })
+    /// ```
+    #[func]
+    pub fn headings(self, engine: &mut Engine) -> Array {
+        engine
+            .introspector
+            .headings(self)
+            .into_iter()
+            .map(|elem| Value::Content(elem.into_inner()))
+            .collect()
+    }
+
+    /// Returns the metadata of the page at this location as a dictionary with
+    /// the page's [`name`]($page.name) and [`tags`]($page.tags).
+    ///
+    /// ```example
+    /// #set page(name: "appendix", tags: ("back-matter",))
+    /// #locate(loc => {
+    ///   let meta = loc.page-metadata()
+    ///   [#meta.name: #meta.tags.join(", ")]
+    /// })
+    /// ```
+    #[func]
+    pub fn page_metadata(self, engine: &mut Engine) -> PageMetadata {
+        engine.introspector.page_metadata(self)
+    }
 }
 
 impl Repr for Location {
@@ -76,15 +76,14 @@ impl<'a> Locator<'a> {
     pub fn visit_frame(&mut self, frame: &Frame) {
         for (_, item) in frame.items() {
             match item {
-                FrameItem::Group(group) => self.visit_frame(&group.frame),
+                FrameItem::Group(group) => {
+                    if let Some(tag) = &group.tag {
+                        self.visit_location(tag.location);
+                    }
+                    self.visit_frame(&group.frame);
+                }
                 FrameItem::Meta(Meta::Elem(elem), _) => {
-                    let mut hashes = self.hashes.borrow_mut();
-                    let loc = elem.location().unwrap();
-                    let entry = hashes.entry(loc.hash).or_default();
-
-                    // Next disambiguator needs to be at least one larger than
-                    // the maximum we've seen so far.
-                    *entry = (*entry).max(loc.disambiguator + 1);
+                    self.visit_location(elem.location().unwrap());
                 }
                 _ => {}
             }
@@ -98,6 +97,16 @@ impl<'a> Locator<'a> {
         }
     }
 
+    /// Advance past a location that was produced by another locator.
+    fn visit_location(&mut self, loc: Location) {
+        let mut hashes = self.hashes.borrow_mut();
+        let entry = hashes.entry(loc.hash).or_default();
+
+        // Next disambiguator needs to be at least one larger than the maximum
+        // we've seen so far.
+        *entry = (*entry).max(loc.disambiguator + 1);
+    }
+
     /// The current disambiguator for the given hash.
     fn disambiguator_impl(&self, hash: u128) -> usize {
         *self
@@ -10,6 +10,8 @@ mod metadata;
 #[path = "query.rs"]
 mod query_;
 mod state;
+#[path = "statistics.rs"]
+mod statistics_;
 
 pub use self::counter::*;
 pub use self::introspector::*;
@@ -19,6 +21,7 @@ pub use self::locator::*;
 pub use self::metadata::*;
 pub use self::query_::*;
 pub use self::state::*;
+pub use self::statistics_::*;
 
 use std::fmt::{self, Debug, Formatter};
 
@@ -28,8 +31,9 @@ use smallvec::SmallVec;
 use crate::foundations::{
     cast, category, elem, ty, Behave, Behaviour, Category, Content, Repr, Scope,
 };
-use crate::layout::PdfPageLabel;
+use crate::layout::{PageMetadata, PdfPageLabel};
 use crate::model::{Destination, Numbering};
+use crate::syntax::Span;
 
 /// Interactions between document parts.
 ///
@@ -50,6 +54,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<MetadataElem>();
     global.define_func::<locate>();
     global.define_func::<query>();
+    global.define_func::<statistics>();
 }
 
 /// Hosts metadata and ensures metadata is produced even for empty elements.
@@ -80,6 +85,15 @@ pub enum Meta {
     PageNumbering(Option<Numbering>),
     /// A PDF page label of the current page.
     PdfPageLabel(PdfPageLabel),
+    /// The name and tags of the current page.
+    PageMetadata(PageMetadata),
+    /// Indicates that the element at `child` is nested inside of the element
+    /// at `parent` in the content tree.
+    Parent { child: Location, parent: Location },
+    /// Indicates that the content in this area was generated by a show rule.
+    /// The `call` is the span of the element the rule was applied to and the
+    /// `rule` is the span of the show rule itself.
+    Origin { call: Span, rule: Span },
     /// Indicates that content should be hidden. This variant doesn't appear
     /// in the final frames as it is removed alongside the content that should
     /// be hidden.
@@ -97,6 +111,9 @@ impl Debug for Meta {
             Self::Elem(content) => write!(f, "Elem({:?})", content.func()),
             Self::PageNumbering(value) => write!(f, "PageNumbering({value:?})"),
             Self::PdfPageLabel(label) => write!(f, "PdfPageLabel({label:?})"),
+            Self::PageMetadata(metadata) => write!(f, "PageMetadata({metadata:?})"),
+            Self::Parent { child, parent } => write!(f, "Parent({child:?}, {parent:?})"),
+            Self::Origin { call, rule } => write!(f, "Origin({call:?}, {rule:?})"),
             Self::Hide => f.pad("Hide"),
         }
     }
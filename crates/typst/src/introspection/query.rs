@@ -9,6 +9,10 @@ use crate::introspection::Location;
 /// retrieve the current document location with the [`locate`]($locate)
 /// function.
 ///
+/// The results are in document order, that is, the order in which the
+/// elements appear in the document's content. For floating and other
+/// placed elements, this may differ from the order on the pages.
+///
 /// # Finding elements
 /// In the example below, we create a custom page header that displays the text
 /// "Typst Academy" in small capitals and the current section title. On the
@@ -88,6 +92,24 @@ use crate::introspection::Location;
 /// })
 /// ```
 ///
+/// # Scoped queries
+/// With the `within` argument, a query can be restricted to the elements that
+/// are nested inside of another element. This makes it easy to, for instance,
+/// create a list of figures for just a single chapter that is wrapped in a
+/// labelled block.
+///
+/// ```example
+/// #block[
+///   #figure(rect(), caption: [Inside])
+/// ] <chapter>
+/// #figure(rect(), caption: [Outside])
+///
+/// #locate(loc => {
+///   let found = query(figure, loc, within: <chapter>)
+///   found.map(it => it.caption.body).join()
+/// })
+/// ```
+///
 /// # Command line queries
 /// You can also perform queries from the command line with the `typst query`
 /// command. This command executes an arbitrary query on the document and
@@ -150,10 +172,52 @@ pub fn query(
     /// level of a module, the evaluation of the whole module and its exports
     /// could depend on the query's result.
     location: Location,
+    /// Restricts the results to elements that are nested inside of the given
+    /// element, typically specified through its location or label. This is
+    /// useful to, for example, list only the figures of a particular chapter.
+    #[named]
+    within: Option<LocatableSelector>,
+    /// Whether to return the results in reverse document order. Combined with
+    /// a [`before`]($selector.before) selector and a `limit` of `{1}`, this
+    /// finds the closest preceding element.
+    ///
+    /// ```example
+    /// #locate(loc => {
+    ///   let prev = query(
+    ///     selector(heading).before(loc),
+    ///     loc,
+    ///     reverse: true,
+    ///     limit: 1,
+    ///   )
+    ///   if prev != () [In: #prev.first().body]
+    /// })
+    /// ```
+    #[named]
+    #[default(false)]
+    reverse: bool,
+    /// How many results to skip. Applied after reversing, so that it can be
+    /// used to page through the results.
+    #[named]
+    #[default(0)]
+    offset: usize,
+    /// The maximum number of results to return. If `{none}`, all remaining
+    /// results are returned.
+    #[named]
+    #[default]
+    limit: Option<usize>,
 ) -> Array {
     let _ = location;
-    let vec = engine.introspector.query(&target.0);
+    let selector = match within {
+        Some(ancestor) => target.0.within(ancestor),
+        None => target.0,
+    };
+    let mut vec = engine.introspector.query(&selector);
+    if reverse {
+        vec.make_mut().reverse();
+    }
     vec.into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
         .map(|elem| Value::Content(elem.into_inner()))
         .collect()
 }
@@ -319,20 +319,22 @@ impl State {
     }
 
     /// Get the value of the state at the end of the document.
+    ///
+    /// This can be called anywhere, also outside of [`locate`]($locate).
+    /// Typst then evaluates the calling code repeatedly until the final value
+    /// has converged.
     #[func]
     pub fn final_(
         &self,
         /// The engine.
         engine: &mut Engine,
         /// Can be an arbitrary location, as its value is irrelevant for the
-        /// method's return value. Why is it required then? As noted before,
-        /// Typst has to evaluate parts of your code multiple times to determine
-        /// the values of all state. By only allowing this method within
-        /// [`locate`]($locate) calls, the amount of code that can depend on the
-        /// method's result is reduced. If you could call `final` directly at
-        /// the top level of a module, the evaluation of the whole module and
-        /// its exports could depend on the state's value.
-        location: Location,
+        /// method's return value. It can be omitted, but passing it restricts
+        /// the code that depends on the state's value to a
+        /// [`locate`]($locate) call, which reduces how much of the document
+        /// Typst has to evaluate multiple times.
+        #[default]
+        location: Option<Location>,
     ) -> SourceResult<Value> {
         let _ = location;
         let sequence = self.sequence(engine)?;
@@ -0,0 +1,160 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, dict, func, Content, IntoValue, NativeElement, Smart, StyleChain,
+};
+use crate::introspection::{Introspector, Location};
+use crate::layout::{Frame, FrameItem, Point, Transform};
+use crate::model::{FigureElem, FigureKind, HeadingElem, TableElem};
+
+/// Provides statistics about the whole document.
+///
+/// The returned dictionary contains the following keys:
+/// - `words`: The number of words in the document.
+/// - `characters`: The number of characters in the document.
+/// - `pages`: The number of pages.
+/// - `headings`: An array with the number of headings per level, starting at
+///   level one.
+/// - `figures`: The number of figures.
+/// - `tables`: The number of figures containing a table.
+///
+/// Words and characters are counted on the laid out pages, so text in headers
+/// and footers is included while [hidden]($hide) text is not. Hyphenated words
+/// that are split across lines count as two words.
+///
+/// Just like [`query`]($query), this function can only be used within a
+/// [`locate`]($locate) call.
+///
+/// # Example
+/// ```example
+/// #set page(footer: locate(loc => {
+///   let stats = statistics(loc)
+///   [#stats.words words]
+/// }))
+///
+/// = Introduction
+/// #lorem(40)
+/// ```
+#[func]
+pub fn statistics(
+    /// The engine.
+    engine: &mut Engine,
+    /// Can be an arbitrary location, as its value is irrelevant for the
+    /// function's return value. It is required for the same reasons as the
+    /// location of [`query`]($query).
+    location: Location,
+) -> Statistics {
+    let _ = location;
+    engine.introspector.statistics()
+}
+
+/// Statistics about a laid out document.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Statistics {
+    /// The number of words.
+    pub words: usize,
+    /// The number of characters.
+    pub characters: usize,
+    /// The number of pages.
+    pub pages: usize,
+    /// The number of headings per level, starting at level one.
+    pub headings: Vec<usize>,
+    /// The number of figures.
+    pub figures: usize,
+    /// The number of figures containing a table.
+    pub tables: usize,
+}
+
+impl Statistics {
+    /// Compute statistics for the given pages.
+    pub fn new(pages: &[Frame]) -> Self {
+        Introspector::new(pages).statistics()
+    }
+
+    /// Compute statistics from the extracted text of each page and the
+    /// introspectable elements of the document.
+    pub(crate) fn collect<'a>(
+        texts: &[String],
+        elems: impl IntoIterator<Item = &'a Content>,
+    ) -> Self {
+        let mut stats = Self { pages: texts.len(), ..Self::default() };
+        for text in texts {
+            stats.words += text.unicode_words().count();
+            stats.characters += text.chars().filter(|&c| c != '\n').count();
+        }
+
+        for elem in elems {
+            if let Some(heading) = elem.to::<HeadingElem>() {
+                let level = heading.level(StyleChain::default()).get();
+                if stats.headings.len() < level {
+                    stats.headings.resize(level, 0);
+                }
+                stats.headings[level - 1] += 1;
+            } else if let Some(figure) = elem.to::<FigureElem>() {
+                stats.figures += 1;
+                if figure.kind(StyleChain::default())
+                    == Smart::Custom(FigureKind::Elem(TableElem::elem()))
+                {
+                    stats.tables += 1;
+                }
+            }
+        }
+
+        stats
+    }
+}
+
+/// Extract the text of a page for counting its words and characters.
+///
+/// Text items that directly continue each other, like the differently styled
+/// parts of one word, are joined. All others are separated by a line break, so
+/// that words are never joined across lines or columns.
+pub(crate) fn page_text(frame: &Frame) -> String {
+    let mut text = String::new();
+    extract_text(frame, Transform::identity(), &mut text, &mut None);
+    text
+}
+
+/// Append the text of a frame, given where the previous text item ended.
+fn extract_text(
+    frame: &Frame,
+    ts: Transform,
+    text: &mut String,
+    end: &mut Option<Point>,
+) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts
+                    .pre_concat(Transform::translate(pos.x, pos.y))
+                    .pre_concat(group.transform);
+                extract_text(&group.frame, ts, text, end);
+            }
+            FrameItem::Text(item) => {
+                let start = pos.transform(ts);
+                let adjacent = end.map_or(false, |end| {
+                    end.x.approx_eq(start.x) && end.y.approx_eq(start.y)
+                });
+                if !text.is_empty() && !adjacent {
+                    text.push('\n');
+                }
+                text.push_str(&item.text);
+                *end = Some((*pos + Point::with_x(item.width())).transform(ts));
+            }
+            _ => {}
+        }
+    }
+}
+
+cast! {
+    Statistics,
+    self => dict! {
+        "words" => self.words,
+        "characters" => self.characters,
+        "pages" => self.pages,
+        "headings" => self.headings,
+        "figures" => self.figures,
+        "tables" => self.tables,
+    }.into_value(),
+}
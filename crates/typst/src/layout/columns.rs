@@ -12,10 +12,10 @@ use crate::util::Numeric;
 /// Separates a region into multiple equally sized columns.
 ///
 /// The `column` function allows to separate the interior of any container into
-/// multiple columns. It will not equalize the height of the columns, instead,
-/// the columns will take up the height of their container or the remaining
-/// height on the page. The columns function can break across pages if
-/// necessary.
+/// multiple columns. By default, it will not equalize the height of the
+/// columns, instead, the columns will take up the height of their container or
+/// the remaining height on the page. The columns function can break across
+/// pages if necessary.
 ///
 /// If you need to insert columns across your whole document, you can use the
 /// [`{page}` function's `columns` parameter]($page.columns) instead.
@@ -52,6 +52,26 @@ pub struct ColumnsElem {
     #[default(Ratio::new(0.04).into())]
     pub gutter: Rel<Length>,
 
+    /// Whether to balance the columns in the last region.
+    ///
+    /// When enabled, the content on the last page (or in the last container
+    /// region) is distributed so that the columns end up about equally tall,
+    /// instead of filling one column after the other. Column breaks are still
+    /// respected and [weak column breaks]($colbreak.weak) that end up at the
+    /// top of a column are skipped.
+    ///
+    /// ```example
+    /// #columns(2, balance: true)[
+    ///   #set par(justify: true)
+    ///   This research was funded by the
+    ///   National Academy of Sciences.
+    ///   NAoS provided support for field
+    ///   tests and interviews.
+    /// ]
+    /// ```
+    #[default(false)]
+    pub balance: bool,
+
     /// The content that should be layouted into the columns.
     #[required]
     pub body: Content,
@@ -78,23 +98,20 @@ impl Layout for ColumnsElem {
         let gutter = self.gutter(styles).relative_to(regions.base().x);
         let width = (regions.size.x - gutter * (columns - 1) as f64) / columns as f64;
 
-        let backlog: Vec<_> = std::iter::once(&regions.size.y)
+        // The height of each column and of the columns in further regions.
+        let mut heights: Vec<_> = std::iter::once(&regions.size.y)
             .chain(regions.backlog)
             .flat_map(|&height| std::iter::repeat(height).take(columns))
-            .skip(1)
             .collect();
+        let mut last = regions.last;
 
-        // Create the pod regions.
-        let pod = Regions {
-            size: Size::new(width, regions.size.y),
-            full: regions.full,
-            backlog: &backlog,
-            last: regions.last,
-            expand: Axes::new(true, regions.expand.y),
-            root: regions.root,
-        };
+        if self.balance(styles) && columns > 1 {
+            (heights, last) =
+                balance(engine, body, styles, &regions, width, columns, heights, last)?;
+        }
 
         // Layout the children.
+        let pod = pod(&regions, width, &heights, last);
         let mut frames = body.layout(engine, styles, pod)?.into_iter();
         let mut finished = vec![];
 
@@ -135,11 +152,82 @@ impl Layout for ColumnsElem {
     }
 }
 
+/// Create the pod regions for columns with the given heights.
+fn pod<'a>(
+    regions: &Regions,
+    width: Abs,
+    heights: &'a [Abs],
+    last: Option<Abs>,
+) -> Regions<'a> {
+    Regions {
+        size: Size::new(width, heights[0]),
+        full: regions.full,
+        backlog: &heights[1..],
+        last,
+        expand: Axes::new(true, regions.expand.y),
+        root: regions.root,
+    }
+}
+
+/// Determine column heights with which the columns in the last region are
+/// about equally tall.
+///
+/// The body is measured repeatedly to find the smallest height of the last
+/// region's columns with which it still fits into the same number of columns.
+#[allow(clippy::too_many_arguments)]
+fn balance(
+    engine: &mut Engine,
+    body: &Content,
+    styles: StyleChain,
+    regions: &Regions,
+    width: Abs,
+    columns: usize,
+    heights: Vec<Abs>,
+    last: Option<Abs>,
+) -> SourceResult<(Vec<Abs>, Option<Abs>)> {
+    let frames = body.measure(engine, styles, pod(regions, width, &heights, last))?;
+    let total_regions = (frames.len() as f32 / columns as f32).ceil() as usize;
+    if total_regions == 0 {
+        return Ok((heights, last));
+    }
+
+    // The columns before the last region keep their heights.
+    let count = total_regions * columns;
+    let mut before = heights.clone();
+    before.resize(count - columns, last.or(heights.last().copied()).unwrap_or_default());
+
+    let mut lo = Abs::zero();
+    let mut hi = frames
+        .iter()
+        .skip(count - columns)
+        .fold(Abs::zero(), |max, frame| max.max(frame.height()));
+
+    let mut best = None;
+    while hi - lo > Abs::pt(1.0) {
+        let mid = (lo + hi) / 2.0;
+        let mut trial = before.clone();
+        trial.extend(std::iter::repeat(mid).take(columns));
+
+        let frames =
+            body.measure(engine, styles, pod(regions, width, &trial, Some(mid)))?;
+        if frames.len() <= count {
+            hi = mid;
+            best = Some((trial, Some(mid)));
+        } else {
+            lo = mid;
+        }
+    }
+
+    Ok(best.unwrap_or((heights, last)))
+}
+
 /// Forces a column break.
 ///
 /// The function will behave like a [page break]($pagebreak) when used in a
 /// single column layout or the last column on a page. Otherwise, content after
-/// the column break will be placed in the next column.
+/// the column break will be placed in the next column. In
+/// [balanced columns]($columns.balance), prefer weak column breaks so that a
+/// break that ends up at the top of a column doesn't leave it empty.
 ///
 /// # Example
 /// ```example
@@ -161,7 +249,8 @@ impl Layout for ColumnsElem {
 #[elem(title = "Column Break", Behave)]
 pub struct ColbreakElem {
     /// If `{true}`, the column break is skipped if the current column is
-    /// already empty.
+    /// already empty, for example because the content before it filled the
+    /// previous column.
     #[default(false)]
     pub weak: bool,
 }
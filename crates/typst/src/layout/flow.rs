@@ -9,8 +9,8 @@ use crate::layout::{
     Frame, FrameItem, Layout, PlaceElem, Point, Regions, Rel, Size, Spacing, VAlign,
     VElem,
 };
-use crate::model::{FootnoteElem, FootnoteEntry, ParElem};
-use crate::util::Numeric;
+use crate::model::{FootnoteElem, FootnoteEntry, ParElem, PrecedingBlock};
+use crate::util::{hash128, Numeric};
 use crate::visualize::{
     CircleElem, EllipseElem, ImageElem, LineElem, PathElem, PolygonElem, RectElem,
     SquareElem,
@@ -46,9 +46,11 @@ impl Layout for FlowElem {
         for mut child in self.children().iter().map(|c| &**c) {
             let outer = styles;
             let mut styles = styles;
+            let mut local = None;
             if let Some((elem, map)) = child.to_styled() {
                 child = elem;
                 styles = outer.chain(map);
+                local = Some(map);
             }
 
             if let Some(elem) = child.to::<VElem>() {
@@ -79,7 +81,19 @@ impl Layout for FlowElem {
                 layouter.layout_placed(engine, placed, styles)?;
             } else if child.can::<dyn Layout>() {
                 layouter.layout_multiple(engine, child, styles)?;
-            } else if child.is::<ColbreakElem>() {
+                if let Some(block) = local.and_then(PrecedingBlock::located) {
+                    layouter.last_block = block;
+                }
+            } else if let Some(colbreak) = child.to::<ColbreakElem>() {
+                // A weak column break is skipped in a column that is still
+                // empty.
+                let is_empty = layouter.items.iter().all(|item| {
+                    !matches!(item, FlowItem::Frame { frame, .. } if !frame.height().is_zero())
+                });
+                if colbreak.weak(styles) && is_empty {
+                    continue;
+                }
+
                 if !layouter.regions.backlog.is_empty() || layouter.regions.last.is_some()
                 {
                     layouter.finish_region(engine)?;
@@ -106,8 +120,8 @@ struct FlowLayouter<'a> {
     /// The initial size of `regions.size` that was available before we started
     /// subtracting.
     initial: Size,
-    /// Whether the last block was a paragraph.
-    last_was_par: bool,
+    /// What kind of block was laid out last.
+    last_block: PrecedingBlock,
     /// Spacing and layouted blocks for the current region.
     items: Vec<FlowItem>,
     /// A queue of floats.
@@ -151,17 +165,6 @@ enum FlowItem {
     Footnote(Frame),
 }
 
-impl FlowItem {
-    /// The inherent height of the item.
-    fn height(&self) -> Abs {
-        match self {
-            Self::Absolute(v, _) => *v,
-            Self::Fractional(_) | Self::Placed { .. } => Abs::zero(),
-            Self::Frame { frame, .. } | Self::Footnote(frame) => frame.height(),
-        }
-    }
-}
-
 impl<'a> FlowLayouter<'a> {
     /// Create a new flow layouter.
     fn new(mut regions: Regions<'a>, styles: StyleChain<'a>) -> Self {
@@ -177,7 +180,7 @@ impl<'a> FlowLayouter<'a> {
             styles,
             expand,
             initial: regions.size,
-            last_was_par: false,
+            last_block: PrecedingBlock::Other,
             items: vec![],
             pending_floats: vec![],
             has_footnotes: false,
@@ -219,8 +222,14 @@ impl<'a> FlowLayouter<'a> {
         styles: StyleChain,
     ) -> SourceResult<()> {
         let align = AlignElem::alignment_in(styles).resolve(styles);
-        let leading = ParElem::leading_in(styles);
-        let consecutive = self.last_was_par;
+        let leading = if ParElem::line_height_in(styles).is_auto() {
+            ParElem::leading_in(styles)
+        } else {
+            Abs::zero()
+        };
+        let indent = ParElem::first_line_indent_in(styles);
+        let hash = hash128(&styles);
+        let consecutive = indent.applies(self.last_block, hash, par.standalone(styles));
         let lines = par
             .layout(
                 engine,
@@ -261,7 +270,7 @@ impl<'a> FlowLayouter<'a> {
             )?;
         }
 
-        self.last_was_par = true;
+        self.last_block = PrecedingBlock::Par(hash);
         Ok(())
     }
 
@@ -281,7 +290,7 @@ impl<'a> FlowLayouter<'a> {
             engine,
             FlowItem::Frame { frame, align, sticky, movable: true },
         )?;
-        self.last_was_par = false;
+        self.last_block = PrecedingBlock::Other;
         Ok(())
     }
 
@@ -358,7 +367,7 @@ impl<'a> FlowLayouter<'a> {
 
         self.root = is_root;
         self.regions.root = false;
-        self.last_was_par = false;
+        self.last_block = PrecedingBlock::Other;
 
         Ok(())
     }
@@ -404,22 +413,40 @@ impl<'a> FlowLayouter<'a> {
                     return Ok(());
                 }
             }
-            FlowItem::Placed { float: false, .. } => {}
+            FlowItem::Placed { ref frame, float: false, .. } => {
+                // Find footnotes in the frame.
+                if self.root {
+                    let mut notes = vec![];
+                    find_footnotes(&mut notes, frame);
+                    self.try_handle_footnotes(engine, notes)?;
+                }
+            }
             FlowItem::Placed {
                 ref mut frame,
+                x_align,
                 ref mut y_align,
+                delta,
                 float: true,
                 clearance,
-                ..
             } => {
                 // If the float doesn't fit, queue it for the next region.
-                if !self.regions.size.y.fits(frame.height() + clearance)
-                    && !self.regions.in_last()
-                {
+                let in_last = self.regions.in_last();
+                if !self.regions.size.y.fits(frame.height() + clearance) && !in_last {
                     self.pending_floats.push(item);
                     return Ok(());
                 }
 
+                // Keep the unplaced float around in case its footnotes don't
+                // fit.
+                let original = self.root.then(|| FlowItem::Placed {
+                    frame: frame.clone(),
+                    x_align,
+                    y_align: *y_align,
+                    delta,
+                    float: true,
+                    clearance,
+                });
+
                 // Select the closer placement, top or bottom.
                 if y_align.is_auto() {
                     let ratio = (self.regions.size.y
@@ -437,13 +464,22 @@ impl<'a> FlowLayouter<'a> {
                     frame.translate(Point::with_y(clearance));
                 }
 
-                self.regions.size.y -= frame.height();
+                let height = frame.height();
+                self.regions.size.y -= height;
 
-                // Find footnotes in the frame.
-                if self.root {
+                // Find footnotes in the frame. The float's footnotes must end
+                // up on the same page as the float, so if they don't fit, both
+                // are queued for the next region.
+                if let Some(original) = original {
                     let mut notes = vec![];
                     find_footnotes(&mut notes, frame);
-                    self.try_handle_footnotes(engine, notes)?;
+                    self.items.push(item);
+                    if !self.handle_footnotes(engine, &mut notes, true, in_last)? {
+                        self.items.pop();
+                        self.regions.size.y += height;
+                        self.pending_floats.push(original);
+                    }
+                    return Ok(());
                 }
             }
             FlowItem::Footnote(_) => {}
@@ -626,8 +662,12 @@ impl FlowLayouter<'_> {
         movable: bool,
         force: bool,
     ) -> SourceResult<bool> {
+        // Snapshot the region so that a failed attempt can be undone.
         let items_len = self.items.len();
         let notes_len = notes.len();
+        let finished_len = self.finished.len();
+        let size = self.regions.size.y;
+        let has_footnotes = self.has_footnotes;
 
         // Process footnotes one at a time.
         let mut k = 0;
@@ -649,19 +689,22 @@ impl FlowLayouter<'_> {
                 .into_frames();
 
             // If the entries didn't fit, abort (to keep footnote and entry
-            // together).
+            // together). Once an earlier entry broke across regions, the
+            // attempt can't be undone anymore.
             if !force
                 && (k == 0 || movable)
+                && self.finished.len() == finished_len
                 && frames.first().map_or(false, Frame::is_empty)
             {
                 // Remove existing footnotes attempts because we need to
                 // move the item to the next page.
                 notes.truncate(notes_len);
 
-                // Undo region modifications.
-                for item in self.items.drain(items_len..) {
-                    self.regions.size.y -= item.height();
-                }
+                // Undo region modifications, including the gaps and the
+                // separator.
+                self.items.truncate(items_len);
+                self.regions.size.y = size;
+                self.has_footnotes = has_footnotes;
 
                 // Undo locator modifications.
                 *engine.locator = checkpoint;
@@ -723,6 +766,10 @@ fn find_footnotes(notes: &mut Vec<FootnoteElem>, frame: &Frame) {
                 if !notes.iter().any(|note| note.location() == content.location()) =>
             {
                 let Some(footnote) = content.to::<FootnoteElem>() else { continue };
+                let default = StyleChain::default();
+                if footnote.suppressed(default) || footnote.endnote(default) {
+                    continue;
+                }
                 notes.push(footnote.clone());
             }
             _ => {}
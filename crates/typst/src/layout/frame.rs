@@ -6,11 +6,12 @@ use std::sync::Arc;
 
 use ecow::{eco_format, EcoString};
 
-use crate::foundations::{cast, dict, Dict, Repr, StyleChain, Value};
-use crate::introspection::{Meta, MetaElem};
+use crate::foundations::{cast, dict, ty, Dict, Repr, StyleChain, Value};
+use crate::introspection::{Location, Meta, MetaElem};
 use crate::layout::{
     Abs, Axes, Corners, FixedAlign, Length, Point, Rel, Sides, Size, Transform,
 };
+use crate::model::Destination;
 use crate::syntax::Span;
 use crate::text::TextItem;
 use crate::util::Numeric;
@@ -298,7 +299,11 @@ impl Frame {
         }
         if hide {
             Arc::make_mut(&mut self.items).retain(|(_, item)| {
-                matches!(item, FrameItem::Group(_) | FrameItem::Meta(Meta::Elem(_), _))
+                matches!(
+                    item,
+                    FrameItem::Group(_)
+                        | FrameItem::Meta(Meta::Elem(_) | Meta::Parent { .. }, _)
+                )
             });
         }
     }
@@ -348,6 +353,33 @@ impl Frame {
         }
     }
 
+    /// Blur the contents of a frame with the given standard deviation.
+    pub fn blur(&mut self, radius: Abs) {
+        if !self.is_empty() && radius > Abs::zero() {
+            self.group(|g| g.blur = Some(radius));
+        }
+    }
+
+    /// Mark the contents of the frame with a semantic role for exporters
+    /// that produce tagged output.
+    pub fn tag(&mut self, tag: Tag) {
+        if !self.is_empty() {
+            self.group(|g| g.tag = Some(tag));
+        }
+    }
+
+    /// Modify the destinations of all links in the frame, including those in
+    /// nested groups.
+    pub fn map_links(&mut self, f: &mut dyn FnMut(&mut Destination)) {
+        for (_, item) in Arc::make_mut(&mut self.items) {
+            match item {
+                FrameItem::Group(group) => group.frame.map_links(f),
+                FrameItem::Meta(Meta::Link(dest), _) => f(dest),
+                _ => {}
+            }
+        }
+    }
+
     /// Wrap the frame's contents in a group and modify that group with `f`.
     fn group<F>(&mut self, f: F)
     where
@@ -496,6 +528,10 @@ pub struct GroupItem {
     pub transform: Transform,
     /// Whether the frame should be a clipping boundary.
     pub clip_path: Option<Path>,
+    /// The standard deviation of a Gaussian blur to apply to the group.
+    pub blur: Option<Abs>,
+    /// The semantic role of the group's contents.
+    pub tag: Option<Tag>,
 }
 
 impl GroupItem {
@@ -505,6 +541,8 @@ impl GroupItem {
             frame,
             transform: Transform::identity(),
             clip_path: None,
+            blur: None,
+            tag: None,
         }
     }
 }
@@ -516,6 +554,49 @@ impl Debug for GroupItem {
     }
 }
 
+/// Marks a group as (part of) a semantic element.
+///
+/// Exporters that produce tagged output, like PDF, use tags to rebuild the
+/// logical structure of the document from its frames.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Tag {
+    /// The role of the element.
+    pub role: Role,
+    /// Identifies the element. Groups with the same location belong to the
+    /// same element, e.g. the parts of a paragraph that was split across
+    /// pages.
+    pub location: Location,
+}
+
+/// The semantic role of a tagged group.
+#[ty]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Role {
+    /// A paragraph.
+    Paragraph,
+    /// A heading of the given level.
+    Heading(NonZeroUsize),
+    /// A figure.
+    Figure,
+    /// A table.
+    Table,
+    /// A cell in the given row of a table.
+    TableCell { row: usize },
+    /// Content that isn't part of the document's logical structure, like
+    /// page headers and footers.
+    Artifact,
+}
+
+cast! {
+    type Role,
+}
+
+impl Repr for Role {
+    fn repr(&self) -> EcoString {
+        eco_format!("{self:?}")
+    }
+}
+
 /// A physical position in a document.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Position {
@@ -547,6 +628,76 @@ impl From<Position> for Dict {
     }
 }
 
+/// The physical extent of an element in a document.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Bounds {
+    /// The page, starting at 1.
+    pub page: NonZeroUsize,
+    /// The coordinates of the top-left corner on the page.
+    pub point: Point,
+    /// The width and height.
+    pub size: Size,
+}
+
+impl Bounds {
+    /// The center point.
+    pub fn center(&self) -> Point {
+        self.point + self.size.to_point() / 2.0
+    }
+
+    /// The point where a line from the center toward another point crosses
+    /// the border. Returns the center if the other point is the center.
+    pub fn boundary(&self, toward: Point) -> Point {
+        let center = self.center();
+        let delta = toward - center;
+
+        // Scale the line so that it ends on the nearest edge in its direction.
+        let scale = |half: Abs, extent: Abs| {
+            if extent.is_zero() {
+                f64::INFINITY
+            } else {
+                (half / extent).abs()
+            }
+        };
+        let t = scale(self.size.x / 2.0, delta.x).min(scale(self.size.y / 2.0, delta.y));
+        if t.is_finite() {
+            center + delta * t
+        } else {
+            center
+        }
+    }
+}
+
+cast! {
+    Bounds,
+    self => Value::Dict(self.into()),
+    mut dict: Dict => {
+        let page = dict.take("page")?.cast()?;
+        let x: Length = dict.take("x")?.cast()?;
+        let y: Length = dict.take("y")?.cast()?;
+        let width: Length = dict.take("width")?.cast()?;
+        let height: Length = dict.take("height")?.cast()?;
+        dict.finish(&["page", "x", "y", "width", "height"])?;
+        Self {
+            page,
+            point: Point::new(x.abs, y.abs),
+            size: Size::new(width.abs, height.abs),
+        }
+    },
+}
+
+impl From<Bounds> for Dict {
+    fn from(bounds: Bounds) -> Self {
+        dict! {
+            "page" => bounds.page,
+            "x" => bounds.point.x,
+            "y" => bounds.point.y,
+            "width" => bounds.size.x,
+            "height" => bounds.size.y,
+        }
+    }
+}
+
 /// Specification for a PDF page label.
 #[derive(Debug, Clone, PartialEq, Hash, Default)]
 pub struct PdfPageLabel {
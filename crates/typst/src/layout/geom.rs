@@ -0,0 +1,139 @@
+//! Geometry of laid-out content.
+
+use crate::foundations::{func, Module, Scope};
+use crate::layout::{Abs, Bounds, Point};
+use crate::util::Numeric;
+
+/// A module with geometry helpers for laid-out content.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define_func::<center>();
+    scope.define_func::<contains>();
+    scope.define_func::<overlap>();
+    scope.define_func::<boundary>();
+    scope.define_func::<crossing>();
+    Module::new("geom", scope)
+}
+
+/// Returns the center point of bounds.
+///
+/// Bounds are dictionaries with the keys `page`, `x`, `y`, `width`, and
+/// `height`, like the ones returned by [`location.bounds`]($location.bounds).
+/// Points are arrays of two lengths.
+///
+/// ```example
+/// #geom.center(
+///   (page: 1, x: 0pt, y: 0pt, width: 20pt, height: 10pt),
+/// )
+/// ```
+#[func]
+pub fn center(
+    /// The bounds whose center to return.
+    bounds: Bounds,
+) -> Point {
+    bounds.center()
+}
+
+/// Checks whether a point lies within bounds, including their border.
+///
+/// ```example
+/// #let bounds = (page: 1, x: 0pt, y: 0pt, width: 20pt, height: 10pt)
+/// #geom.contains(bounds, (5pt, 5pt)) \
+/// #geom.contains(bounds, (5pt, 15pt))
+/// ```
+#[func]
+pub fn contains(
+    /// The bounds to check.
+    bounds: Bounds,
+    /// The point to check.
+    point: Point,
+) -> bool {
+    let end = bounds.point + bounds.size.to_point();
+    (bounds.point.x..=end.x).contains(&point.x)
+        && (bounds.point.y..=end.y).contains(&point.y)
+}
+
+/// Returns the bounds of the area in which two bounds overlap or `{none}` if
+/// they don't overlap.
+///
+/// Bounds on different pages never overlap.
+///
+/// ```example
+/// #geom.overlap(
+///   (page: 1, x: 0pt, y: 0pt, width: 20pt, height: 10pt),
+///   (page: 1, x: 10pt, y: 5pt, width: 20pt, height: 10pt),
+/// )
+/// ```
+#[func]
+pub fn overlap(
+    /// The first bounds.
+    a: Bounds,
+    /// The second bounds.
+    b: Bounds,
+) -> Option<Bounds> {
+    let start = a.point.max(b.point);
+    let end = (a.point + a.size.to_point()).min(b.point + b.size.to_point());
+    let size = (end - start).to_size();
+    (a.page == b.page && size.x > Abs::zero() && size.y > Abs::zero()).then_some(Bounds {
+        page: a.page,
+        point: start,
+        size,
+    })
+}
+
+/// Returns the point where a line from the center of bounds toward another
+/// point crosses their border.
+///
+/// This is where a connector between two elements should start or end. If the
+/// other point is the center itself, the center is returned.
+///
+/// ```example
+/// #geom.boundary(
+///   (page: 1, x: 0pt, y: 0pt, width: 20pt, height: 10pt),
+///   (50pt, 5pt),
+/// )
+/// ```
+#[func]
+pub fn boundary(
+    /// The bounds whose border to find.
+    bounds: Bounds,
+    /// The point toward which the line goes.
+    toward: Point,
+) -> Point {
+    bounds.boundary(toward)
+}
+
+/// Returns the point where two line segments cross or `{none}` if they don't.
+///
+/// Parallel segments are considered not to cross.
+///
+/// ```example
+/// #geom.crossing(
+///   (0pt, 0pt), (20pt, 20pt),
+///   (0pt, 20pt), (20pt, 0pt),
+/// )
+/// ```
+#[func]
+pub fn crossing(
+    /// The start of the first segment.
+    a: Point,
+    /// The end of the first segment.
+    b: Point,
+    /// The start of the second segment.
+    c: Point,
+    /// The end of the second segment.
+    d: Point,
+) -> Option<Point> {
+    let cross =
+        |p: Point, q: Point| p.x.to_raw() * q.y.to_raw() - p.y.to_raw() * q.x.to_raw();
+    let r = b - a;
+    let s = d - c;
+    let denom = cross(r, s);
+    if denom.abs() < f64::EPSILON {
+        return None;
+    }
+
+    let t = cross(c - a, s) / denom;
+    let u = cross(c - a, r) / denom;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| a + r * t)
+}
@@ -5,15 +5,17 @@ use smallvec::{smallvec, SmallVec};
 use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Array, Content, NativeElement, Resolve, StyleChain, Value,
+    cast, dict, elem, scope, Array, Content, Dict, IntoValue, NativeElement, Resolve,
+    Show, StyleChain, Value,
 };
 use crate::layout::{
-    Abs, Axes, Dir, Fr, Fragment, Frame, Layout, Length, Point, Regions, Rel, Size,
-    Sizing,
+    Abs, Axes, Dir, Fr, Fragment, Frame, FrameItem, Layout, Length, Point, Regions, Rel,
+    Size, Sizing,
 };
 use crate::syntax::Span;
 use crate::text::TextElem;
-use crate::util::Numeric;
+use crate::util::{NonZeroExt, Numeric};
+use crate::visualize::{FixedStroke, Geometry, Stroke};
 
 /// Arranges content in a grid.
 ///
@@ -81,7 +83,22 @@ use crate::util::Numeric;
 ///   ..range(25).map(str)
 /// )
 /// ```
-#[elem(Layout)]
+///
+/// To let a cell span multiple columns or rows, wrap it in a
+/// [`grid.cell`]($grid.cell).
+///
+/// ```example
+/// #set rect(width: 100%, height: 100%)
+/// #grid(
+///   columns: 3,
+///   rows: 20pt,
+///   gutter: 3pt,
+///   grid.cell(colspan: 2, rect[Wide]),
+///   grid.cell(rowspan: 2, rect[Tall]),
+///   rect[A], rect[B],
+/// )
+/// ```
+#[elem(scope, Layout)]
 pub struct GridElem {
     /// The column sizes.
     ///
@@ -118,13 +135,53 @@ pub struct GridElem {
     #[borrowed]
     pub row_gutter: TrackSizings,
 
+    /// How to [stroke]($stroke) the gutters between rows and columns.
+    ///
+    /// The rules are drawn centered in the gutters, independently of the
+    /// cells. Empty gutters have no rules.
+    ///
+    /// ```example
+    /// #grid(
+    ///   columns: 3,
+    ///   gutter: 8pt,
+    ///   rule: 0.5pt + gray,
+    ///   ..range(9).map(str)
+    /// )
+    /// ```
+    #[resolve]
+    #[fold]
+    pub rule: Option<Stroke>,
+
+    /// How far the gutter rules are inset from the edges of the grid.
+    ///
+    /// This can be a length, applying to both ends of each rule, or a
+    /// dictionary with the keys `start` and `end`.
+    ///
+    /// ```example
+    /// #grid(
+    ///   columns: 3,
+    ///   gutter: 8pt,
+    ///   rule: 0.5pt,
+    ///   rule-inset: (start: 4pt, end: 0pt),
+    ///   ..range(9).map(str)
+    /// )
+    /// ```
+    pub rule_inset: RuleInset,
+
     /// The contents of the grid cells.
     ///
-    /// The cells are populated in row-major order.
+    /// The cells are populated in row-major order. Positions that are covered
+    /// by a cell spanning multiple rows are skipped.
     #[variadic]
     pub children: Vec<Content>,
 }
 
+#[scope]
+impl GridElem {
+    #[elem]
+    type GridCell;
+}
+
 impl Layout for GridElem {
     #[tracing::instrument(name = "GridElem::layout", skip_all)]
     fn layout(
@@ -146,10 +203,165 @@ impl Layout for GridElem {
             regions,
             styles,
             self.span(),
-        );
+        )?;
 
         // Measure the columns and layout the grid row-by-row.
-        Ok(layouter.layout(engine)?.fragment)
+        let mut layout = layouter.layout(engine)?;
+
+        // Draw rules in the gutters.
+        if let Some(rule) = self.rule(styles) {
+            let inset = self.rule_inset(styles);
+            layout.stroke_gutters(
+                &rule.unwrap_or_default(),
+                inset.start.resolve(styles),
+                inset.end.resolve(styles),
+                self.span(),
+            );
+        }
+
+        Ok(layout.fragment)
+    }
+}
+
+/// A cell in a grid or table that spans multiple columns or rows.
+///
+/// Cells are placed in row-major order into the first position where they fit
+/// without overlapping the cells before them. Strokes and fills of a
+/// [table]($table) treat the spanned area as a single cell.
+///
+/// # Example
+/// ```example
+/// #table(
+///   columns: 3,
+///   table.cell(rowspan: 2)[*Region*],
+///   table.cell(colspan: 2)[*Sales*],
+///   [2022], [2023],
+///   [North], [120], [135],
+/// )
+/// ```
+#[elem(name = "cell", title = "Grid Cell", Show)]
+pub struct GridCell {
+    /// The cell's body.
+    #[required]
+    pub body: Content,
+
+    /// The number of columns the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub colspan: NonZeroUsize,
+
+    /// The number of rows the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub rowspan: NonZeroUsize,
+}
+
+impl Show for GridCell {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body().clone())
+    }
+}
+
+/// The position of a cell among the content tracks of a grid.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CellPosition {
+    /// The column of the cell's top-left corner.
+    pub x: usize,
+    /// The row of the cell's top-left corner.
+    pub y: usize,
+    /// The number of columns the cell spans.
+    pub colspan: usize,
+    /// The number of rows the cell spans.
+    pub rowspan: usize,
+}
+
+/// Determine the positions of the cells in a grid with the given number of
+/// columns.
+pub fn place_cells(
+    cells: &[Content],
+    columns: usize,
+    styles: StyleChain,
+) -> SourceResult<Vec<CellPosition>> {
+    let mut occupied: Vec<bool> = vec![];
+    let mut positions = Vec::with_capacity(cells.len());
+    let mut cursor = 0;
+
+    for cell in cells {
+        let (colspan, rowspan) = match cell.to::<GridCell>() {
+            Some(cell) => (cell.colspan(styles).get(), cell.rowspan(styles).get()),
+            None => (1, 1),
+        };
+
+        if colspan > columns {
+            bail!(cell.span(), "cell's colspan would exceed the number of columns");
+        }
+
+        // Find the first position where the cell fits. There always is one
+        // past the occupied positions.
+        let fits = |i: usize| {
+            let (x, y) = (i % columns, i / columns);
+            x + colspan <= columns
+                && (y..y + rowspan).all(|y| {
+                    (x..x + colspan)
+                        .all(|x| !occupied.get(y * columns + x).copied().unwrap_or(false))
+                })
+        };
+        let i = (cursor..).find(|&i| fits(i)).unwrap();
+
+        let (x, y) = (i % columns, i / columns);
+        let end = (y + rowspan) * columns;
+        if occupied.len() < end {
+            occupied.resize(end, false);
+        }
+
+        for y in y..y + rowspan {
+            occupied[y * columns + x..y * columns + x + colspan].fill(true);
+        }
+
+        positions.push(CellPosition { x, y, colspan, rowspan });
+        cursor = i + colspan;
+    }
+
+    Ok(positions)
+}
+
+/// The tracks covered by a cell, including gutter tracks.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CellSpan {
+    /// The index of the cell among the grid's children.
+    pub index: usize,
+    /// The first column track of the cell.
+    pub x: usize,
+    /// The first row track of the cell.
+    pub y: usize,
+    /// The number of column tracks the cell covers.
+    pub colspan: usize,
+    /// The number of row tracks the cell covers.
+    pub rowspan: usize,
+}
+
+/// The tracks covered by the cells of a grid.
+#[derive(Debug, Clone)]
+pub struct CellSpans {
+    /// The tracks covered by each cell.
+    spans: Vec<CellSpan>,
+    /// For each position in the tracks, the index of the cell covering it.
+    slots: Vec<Option<usize>>,
+    /// The number of column tracks.
+    cols: usize,
+}
+
+impl CellSpans {
+    /// The cell covering column `x` and row `y`, if any.
+    pub fn at(&self, x: usize, y: usize) -> Option<&CellSpan> {
+        if x >= self.cols {
+            return None;
+        }
+        let index = self.slots.get(y * self.cols + x).copied().flatten()?;
+        self.spans.get(index)
+    }
+
+    /// Iterate over the tracks covered by each cell.
+    pub fn iter(&self) -> std::slice::Iter<'_, CellSpan> {
+        self.spans.iter()
     }
 }
 
@@ -165,10 +377,39 @@ cast! {
     values: Array => Self(values.into_iter().map(Value::cast).collect::<StrResult<_>>()?),
 }
 
+/// How far gutter rules are inset from the edges of a grid.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct RuleInset {
+    /// The inset at the start of each rule.
+    pub start: Length,
+    /// The inset at the end of each rule.
+    pub end: Length,
+}
+
+cast! {
+    RuleInset,
+    self => dict! {
+        "start" => self.start.into_value(),
+        "end" => self.end.into_value(),
+    }.into_value(),
+    v: Length => Self { start: v, end: v },
+    mut dict: Dict => {
+        let start = dict.take("start").ok().map(Value::cast).transpose()?;
+        let end = dict.take("end").ok().map(Value::cast).transpose()?;
+        dict.finish(&["start", "end"])?;
+        Self {
+            start: start.unwrap_or_default(),
+            end: end.unwrap_or_default(),
+        }
+    },
+}
+
 /// Performs grid layout.
 pub struct GridLayouter<'a> {
     /// The grid cells.
     cells: &'a [Content],
+    /// The tracks covered by the cells.
+    spans: CellSpans,
     /// Whether this is an RTL grid.
     is_rtl: bool,
     /// Whether this grid has gutters.
@@ -206,6 +447,91 @@ pub struct GridLayout {
     pub cols: Vec<Abs>,
     /// The heights of the resulting rows segments, by region.
     pub rows: Vec<Vec<RowPiece>>,
+    /// Whether the grid has gutter tracks.
+    pub has_gutter: bool,
+    /// The tracks covered by the cells.
+    pub spans: CellSpans,
+}
+
+impl GridLayout {
+    /// Draw rules centered in the gutter tracks, inset by `start` and `end`
+    /// from the edges of the grid.
+    pub fn stroke_gutters(
+        &mut self,
+        stroke: &FixedStroke,
+        start: Abs,
+        end: Abs,
+        span: Span,
+    ) {
+        if !self.has_gutter {
+            return;
+        }
+
+        // Rules are interrupted by cells spanning across the gutter.
+        let covered = |x: usize, y: usize| self.spans.at(x, y).is_some();
+
+        for (frame, rows) in self.fragment.iter_mut().zip(&self.rows) {
+            // Gutter tracks are at odd indices. Empty gutters have no rules.
+            let mut dy = Abs::zero();
+            for row in rows {
+                if row.y % 2 == 1 && !row.height.is_zero() {
+                    let tracks = self.cols.iter().enumerate();
+                    let segments =
+                        line_segments(tracks.map(|(x, &col)| (col, covered(x, row.y))));
+                    let limit = frame.width() - end;
+                    for (a, b) in segments {
+                        let (a, b) = (a.max(start), b.min(limit));
+                        if a < b {
+                            let hline = Geometry::Line(Point::with_x(b - a))
+                                .stroked(stroke.clone());
+                            let pos = Point::new(a, dy + row.height / 2.0);
+                            frame.push(pos, FrameItem::Shape(hline, span));
+                        }
+                    }
+                }
+                dy += row.height;
+            }
+
+            let mut dx = Abs::zero();
+            for (x, &col) in self.cols.iter().enumerate() {
+                if x % 2 == 1 && !col.is_zero() {
+                    let tracks = rows.iter();
+                    let segments =
+                        line_segments(tracks.map(|row| (row.height, covered(x, row.y))));
+                    let limit = frame.height() - end;
+                    for (a, b) in segments {
+                        let (a, b) = (a.max(start), b.min(limit));
+                        if a < b {
+                            let vline = Geometry::Line(Point::with_y(b - a))
+                                .stroked(stroke.clone());
+                            let pos = Point::new(dx + col / 2.0, a);
+                            frame.push(pos, FrameItem::Shape(vline, span));
+                        }
+                    }
+                }
+                dx += col;
+            }
+        }
+    }
+}
+
+/// Split a line along tracks into the segments that are not covered by a cell.
+///
+/// Takes the extent of each track and whether it is covered and returns the
+/// start and end offsets of the segments.
+pub fn line_segments(tracks: impl IntoIterator<Item = (Abs, bool)>) -> Vec<(Abs, Abs)> {
+    let mut segments: Vec<(Abs, Abs)> = vec![];
+    let mut offset = Abs::zero();
+    for (extent, covered) in tracks {
+        if !covered {
+            match segments.last_mut() {
+                Some((_, end)) if *end == offset => *end += extent,
+                _ => segments.push((offset, offset + extent)),
+            }
+        }
+        offset += extent;
+    }
+    segments
 }
 
 /// Details about a resulting row piece.
@@ -237,7 +563,7 @@ impl<'a> GridLayouter<'a> {
         regions: Regions<'a>,
         styles: StyleChain<'a>,
         span: Span,
-    ) -> Self {
+    ) -> SourceResult<Self> {
         let mut cols = vec![];
         let mut rows = vec![];
 
@@ -246,11 +572,11 @@ impl<'a> GridLayouter<'a> {
 
         // Number of content rows: At least as many as given, but also at least
         // as many as needed to place each item.
+        let positions = place_cells(cells, c, styles)?;
         let r = {
-            let len = cells.len();
             let given = tracks.y.len();
-            let needed = len / c + (len % c).clamp(0, 1);
-            given.max(needed)
+            let needed = positions.iter().map(|pos| pos.y + pos.rowspan).max();
+            given.max(needed.unwrap_or(0))
         };
 
         let has_gutter = gutter.any(|tracks| !tracks.is_empty());
@@ -288,13 +614,43 @@ impl<'a> GridLayouter<'a> {
             cols.reverse();
         }
 
+        // Determine the tracks covered by each cell.
+        let track = |i: usize| if has_gutter { 2 * i } else { i };
+        let extent = |n: usize| if has_gutter { 2 * n - 1 } else { n };
+        let mut spans = vec![];
+        let mut slots = vec![None; cols.len() * rows.len()];
+        for (index, pos) in positions.into_iter().enumerate() {
+            let colspan = extent(pos.colspan);
+            let mut x = track(pos.x);
+            if is_rtl {
+                x = cols.len() - x - colspan;
+            }
+
+            let cell = CellSpan {
+                index,
+                x,
+                y: track(pos.y),
+                colspan,
+                rowspan: extent(pos.rowspan),
+            };
+            for y in cell.y..cell.y + cell.rowspan {
+                let start = y * cols.len() + cell.x;
+                slots[start..start + cell.colspan].fill(Some(index));
+            }
+
+            spans.push(cell);
+        }
+
+        let spans = CellSpans { spans, slots, cols: cols.len() };
+
         // We use these regions for auto row measurement. Since at that moment,
         // columns are already sized, we can enable horizontal expansion.
         let mut regions = regions;
         regions.expand = Axes::new(true, false);
 
-        Self {
+        Ok(Self {
             cells,
+            spans,
             is_rtl,
             has_gutter,
             rows,
@@ -308,7 +664,7 @@ impl<'a> GridLayouter<'a> {
             initial: regions.size,
             finished: vec![],
             span,
-        }
+        })
     }
 
     /// Determines the columns sizes and then layouts the grid row-by-row.
@@ -330,11 +686,14 @@ impl<'a> GridLayouter<'a> {
         }
 
         self.finish_region(engine)?;
+        self.layout_row_spans(engine)?;
 
         Ok(GridLayout {
             fragment: Fragment::frames(self.finished),
             cols: self.rcols,
             rows: self.rrows,
+            has_gutter: self.has_gutter,
+            spans: self.spans,
         })
     }
 
@@ -402,19 +761,9 @@ impl<'a> GridLayouter<'a> {
 
             let mut resolved = Abs::zero();
             for y in 0..self.rows.len() {
-                if let Some(cell) = self.cell(x, y) {
-                    // For relative rows, we can already resolve the correct
-                    // base and for auto and fr we could only guess anyway.
-                    let height = match self.rows[y] {
-                        Sizing::Rel(v) => {
-                            v.resolve(self.styles).relative_to(self.regions.base().y)
-                        }
-                        _ => self.regions.base().y,
-                    };
-
-                    let size = Size::new(available, height);
-                    let pod = Regions::one(size, Axes::splat(false));
-                    let frame = cell.measure(engine, self.styles, pod)?.into_frame();
+                // Cells spanning multiple columns are measured below.
+                if let Some(cell) = self.cell(x, y).filter(|cell| cell.colspan == 1) {
+                    let frame = self.measure_cell(engine, &cell, available)?;
                     resolved.set_max(frame.width());
                 }
             }
@@ -424,9 +773,48 @@ impl<'a> GridLayouter<'a> {
             count += 1;
         }
 
+        // If a cell spanning multiple columns doesn't fit into them, the last
+        // auto column it spans grows by the difference.
+        for cell in self.spans.iter().filter(|cell| cell.colspan > 1) {
+            let tracks = cell.x..cell.x + cell.colspan;
+            let Some(last) = tracks.clone().rev().find(|&x| self.cols[x] == Sizing::Auto)
+            else {
+                continue;
+            };
+
+            let frame = self.measure_cell(engine, cell, available)?;
+            let spanned: Abs = self.rcols[tracks].iter().sum();
+            if frame.width() > spanned {
+                let excess = frame.width() - spanned;
+                self.rcols[last] += excess;
+                auto += excess;
+            }
+        }
+
         Ok((auto, count))
     }
 
+    /// Measure a cell with the given available width.
+    fn measure_cell(
+        &self,
+        engine: &mut Engine,
+        cell: &CellSpan,
+        available: Abs,
+    ) -> SourceResult<Frame> {
+        // For relative rows, we can already resolve the correct base and for
+        // auto and fr we could only guess anyway.
+        let height = match self.rows[cell.y] {
+            Sizing::Rel(v) if cell.rowspan == 1 => {
+                v.resolve(self.styles).relative_to(self.regions.base().y)
+            }
+            _ => self.regions.base().y,
+        };
+
+        let size = Size::new(available, height);
+        let pod = Regions::one(size, Axes::splat(false));
+        Ok(self.cells[cell.index].measure(engine, self.styles, pod)?.into_frame())
+    }
+
     /// Distribute remaining space to fractional columns.
     fn grow_fractional_columns(&mut self, remaining: Abs, fr: Fr) {
         if fr.is_zero() {
@@ -533,12 +921,14 @@ impl<'a> GridLayouter<'a> {
     ) -> SourceResult<Option<Vec<Abs>>> {
         let mut resolved: Vec<Abs> = vec![];
 
-        for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
+        for x in 0..self.rcols.len() {
+            if let Some(cell) = self.cell(x, y).filter(|cell| cell.rowspan == 1) {
                 let mut pod = self.regions;
-                pod.size.x = rcol;
+                pod.size.x = self.width_of(&cell);
 
-                let frames = cell.measure(engine, self.styles, pod)?.into_frames();
+                let frames = self.cells[cell.index]
+                    .measure(engine, self.styles, pod)?
+                    .into_frames();
 
                 // Skip the first region if one cell in it is empty. Then,
                 // remeasure.
@@ -562,6 +952,34 @@ impl<'a> GridLayouter<'a> {
             }
         }
 
+        // Cells spanning multiple rows that end in this row must fit into
+        // their rows. The parts of their rows that are in previous regions
+        // are not taken into account.
+        for cell in self.spans.iter().filter(|cell| cell.rowspan > 1) {
+            if cell.y + cell.rowspan != y + 1 {
+                continue;
+            }
+
+            let size = Size::new(self.width_of(cell), self.regions.base().y);
+            let pod = Regions::one(size, Axes::splat(false));
+            let frame = self.cells[cell.index].measure(engine, self.styles, pod)?;
+            let above: Abs = self
+                .lrows
+                .iter()
+                .filter_map(|row| match row {
+                    Row::Frame(row, i) if *i >= cell.y => Some(row.height()),
+                    _ => None,
+                })
+                .sum();
+
+            let needed = frame.into_frame().height() - above;
+            match resolved.first_mut() {
+                Some(first) => first.set_max(needed),
+                None if needed > Abs::zero() => resolved.push(needed),
+                None => {}
+            }
+        }
+
         Ok(Some(resolved))
     }
 
@@ -607,13 +1025,14 @@ impl<'a> GridLayouter<'a> {
         let mut pos = Point::zero();
 
         for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                let size = Size::new(rcol, height);
+            if let Some(cell) = self.cell(x, y).filter(|cell| cell.rowspan == 1) {
+                let size = Size::new(self.width_of(&cell), height);
                 let mut pod = Regions::one(size, Axes::splat(true));
                 if self.rows[y] == Sizing::Auto {
                     pod.full = self.regions.full;
                 }
-                let frame = cell.layout(engine, self.styles, pod)?.into_frame();
+                let frame =
+                    self.cells[cell.index].layout(engine, self.styles, pod)?.into_frame();
                 output.push_frame(pos, frame);
             }
 
@@ -645,11 +1064,11 @@ impl<'a> GridLayouter<'a> {
         // Layout the row.
         let mut pos = Point::zero();
         for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                pod.size.x = rcol;
+            if let Some(cell) = self.cell(x, y).filter(|cell| cell.rowspan == 1) {
+                pod.size.x = self.width_of(&cell);
 
                 // Push the layouted frames into the individual output frames.
-                let fragment = cell.layout(engine, self.styles, pod)?;
+                let fragment = self.cells[cell.index].layout(engine, self.styles, pod)?;
                 for (output, frame) in outputs.iter_mut().zip(fragment) {
                     output.push_frame(pos, frame);
                 }
@@ -661,6 +1080,46 @@ impl<'a> GridLayouter<'a> {
         Ok(Fragment::frames(outputs))
     }
 
+    /// Layout the cells spanning multiple rows into the finished regions.
+    ///
+    /// Such a cell is laid out once all of its rows have been sized. It is
+    /// split into one part per region that its rows ended up in.
+    fn layout_row_spans(&mut self, engine: &mut Engine) -> SourceResult<()> {
+        for cell in self.spans.iter().filter(|cell| cell.rowspan > 1) {
+            let tracks = cell.y..cell.y + cell.rowspan;
+
+            // Find the offset and height of the cell's part in each region.
+            let mut parts = vec![];
+            for (i, rows) in self.rrows.iter().enumerate() {
+                let mut dy = Abs::zero();
+                let mut part: Option<(Abs, Abs)> = None;
+                for row in rows {
+                    if tracks.contains(&row.y) {
+                        part.get_or_insert((dy, Abs::zero())).1 += row.height;
+                    }
+                    dy += row.height;
+                }
+                if let Some((dy, height)) = part {
+                    parts.push((i, dy, height));
+                }
+            }
+
+            let Some(&(_, _, first)) = parts.first() else { continue };
+            let heights: Vec<_> = parts.iter().map(|&(_, _, height)| height).collect();
+            let size = Size::new(self.width_of(cell), first);
+            let mut pod = Regions::one(size, Axes::splat(true));
+            pod.backlog = &heights[1..];
+
+            let dx = self.rcols[..cell.x].iter().sum();
+            let fragment = self.cells[cell.index].layout(engine, self.styles, pod)?;
+            for (&(i, dy, _), frame) in parts.iter().zip(fragment) {
+                self.finished[i].push_frame(Point::new(dx, dy), frame);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Push a row frame into the current region.
     fn push_row(&mut self, frame: Frame, y: usize) {
         self.regions.size.y -= frame.height();
@@ -716,30 +1175,16 @@ impl<'a> GridLayouter<'a> {
         Ok(())
     }
 
-    /// Get the content of the cell in column `x` and row `y`.
+    /// Get the cell whose top-left corner is in column `x` and row `y`.
     ///
-    /// Returns `None` if it's a gutter cell.
-    #[track_caller]
-    fn cell(&self, mut x: usize, y: usize) -> Option<&'a Content> {
-        assert!(x < self.cols.len());
-        assert!(y < self.rows.len());
-
-        // Columns are reorder, but the cell slice is not.
-        if self.is_rtl {
-            x = self.cols.len() - 1 - x;
-        }
-
-        if self.has_gutter {
-            // Even columns and rows are children, odd ones are gutter.
-            if x % 2 == 0 && y % 2 == 0 {
-                let c = 1 + self.cols.len() / 2;
-                self.cells.get((y / 2) * c + x / 2)
-            } else {
-                None
-            }
-        } else {
-            let c = self.cols.len();
-            self.cells.get(y * c + x)
-        }
+    /// Returns `None` if it's a gutter cell, empty, or covered by a cell
+    /// starting elsewhere.
+    fn cell(&self, x: usize, y: usize) -> Option<CellSpan> {
+        self.spans.at(x, y).filter(|cell| cell.x == x && cell.y == y).copied()
+    }
+
+    /// The width of the columns a cell spans.
+    fn width_of(&self, cell: &CellSpan) -> Abs {
+        self.rcols[cell.x..cell.x + cell.colspan].iter().sum()
     }
 }
@@ -161,6 +161,11 @@ pub(super) fn breakpoints<'a>(
                     continue;
                 }
 
+                // Filter out hyphenation opportunities in unbreakable text.
+                if nobreak_at(p, offset) {
+                    continue;
+                }
+
                 // Filter out forbidden hyphenation opportunities.
                 if matches!(
                     syllable.chars().next_back().map(|c| lb.get(c)),
@@ -174,8 +179,11 @@ pub(super) fn breakpoints<'a>(
             }
         }
 
-        // Call `f` for the UAX #14 break opportunity.
-        f(point, breakpoint);
+        // Call `f` for the UAX #14 break opportunity, unless it is in
+        // unbreakable text.
+        if breakpoint == Breakpoint::Mandatory || !nobreak_at(p, point) {
+            f(point, breakpoint);
+        }
 
         last = point;
     }
@@ -249,6 +257,22 @@ fn hyphenate_at(p: &Preparation, offset: usize) -> bool {
         .unwrap_or(false)
 }
 
+/// Whether line breaks are forbidden at the given offset because the text on
+/// both sides of it must not be broken.
+fn nobreak_at(p: &Preparation, offset: usize) -> bool {
+    if let Some(nobreak) = p.nobreak {
+        return nobreak;
+    }
+
+    let nobreak = |offset| {
+        p.find(offset)
+            .and_then(|item| item.text())
+            .map_or(false, |shaped| TextElem::nobreak_in(shaped.styles))
+    };
+
+    offset > 0 && nobreak(offset - 1) && nobreak(offset)
+}
+
 /// The text language at the given offset.
 fn lang_at(p: &Preparation, offset: usize) -> Option<hypher::Lang> {
     let lang = p.lang.or_else(|| {
@@ -7,25 +7,26 @@ use unicode_script::{Script, UnicodeScript};
 
 use self::linebreak::{breakpoints, Breakpoint};
 use self::shaping::{
-    is_gb_style, is_of_cjk_script, shape, ShapedGlyph, ShapedText, BEGIN_PUNCT_PAT,
-    END_PUNCT_PAT,
+    is_gb_style, is_of_cjk_script, report_missing_fonts, shape, ShapedGlyph, ShapedText,
+    BEGIN_PUNCT_PAT, END_PUNCT_PAT,
 };
 use crate::diag::{bail, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
 use crate::foundations::{Content, Resolve, Smart, StyleChain};
-use crate::introspection::{Introspector, Locator, MetaElem};
+use crate::introspection::{Introspector, Locator, Meta, MetaElem};
 use crate::layout::{
-    Abs, AlignElem, Axes, BoxElem, Dir, Em, FixedAlign, Fr, Fragment, Frame, HElem,
-    Layout, Point, Regions, Size, Sizing, Spacing,
+    Abs, AlignElem, Axes, BoxElem, Dir, Em, FixedAlign, Fr, Fragment, Frame, FrameItem,
+    HElem, Layout, Point, Regions, Size, Sizing, Spacing,
 };
-use crate::math::EquationElem;
-use crate::model::{Linebreaks, ParElem};
+use crate::math::{EquationElem, MathParItem};
+use crate::model::{JustifyLast, LineHeight, Linebreaks, ParElem, ParLine};
 use crate::syntax::Span;
 use crate::text::{
-    Lang, LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem, TextElem,
+    families, variant, FontMetrics, Lang, LinebreakElem, SmartQuoteElem, SmartQuoter,
+    SmartQuotes, SpaceElem, TextElem,
 };
-use crate::util::Numeric;
+use crate::util::{hash128, Numeric};
 use crate::World;
 
 /// Layout's content inline.
@@ -61,7 +62,8 @@ pub(crate) fn layout_inline(
         };
 
         // Collect all text into one string for BiDi analysis.
-        let (text, segments, spans) = collect(children, &styles, consecutive)?;
+        let (text, segments, spans) =
+            collect(children, &mut engine, &styles, region, consecutive)?;
 
         // Perform BiDi analysis and then prepare paragraph layout by building a
         // representation on which we can do line breaking without layouting
@@ -115,12 +117,16 @@ struct Preparation<'a> {
     spans: SpanMapper,
     /// Whether to hyphenate if it's the same for all children.
     hyphenate: Option<bool>,
+    /// Whether breaking is forbidden if it's the same for all children.
+    nobreak: Option<bool>,
     /// The text language if it's the same for all children.
     lang: Option<Lang>,
     /// The paragraph's resolved horizontal alignment.
     align: FixedAlign,
     /// Whether to justify the paragraph.
     justify: bool,
+    /// How to treat the last line of the paragraph if it is justified.
+    justify_last: JustifyLast,
     /// The paragraph's hanging indent.
     hang: Abs,
     /// Whether to add spacing between CJK and Latin characters.
@@ -129,13 +135,29 @@ struct Preparation<'a> {
     fallback: bool,
     /// The leading of the paragraph.
     leading: Abs,
+    /// How to determine the height of lines.
+    line_sizing: LineSizing,
     /// How to determine line breaks.
     linebreaks: Smart<Linebreaks>,
     /// The text size.
     size: Abs,
+    /// The marker for numbered lines, if the lines are numbered.
+    line: Option<ParLine>,
+    /// The text offsets at which inline equations can be broken, together
+    /// with the cost of doing so.
+    math_breaks: Vec<(usize, f64)>,
 }
 
 impl<'a> Preparation<'a> {
+    /// The cost of breaking the line at the given text offset inside of an
+    /// inline equation, if it is such an offset.
+    fn math_break(&self, text_offset: usize) -> Option<f64> {
+        self.math_breaks
+            .binary_search_by_key(&text_offset, |&(offset, _)| offset)
+            .ok()
+            .map(|i| self.math_breaks[i].1)
+    }
+
     /// Find the item that contains the given `text_offset`.
     fn find(&self, text_offset: usize) -> Option<&Item<'a>> {
         let mut cursor = 0;
@@ -179,16 +201,72 @@ impl<'a> Preparation<'a> {
     }
 }
 
-/// A segment of one or multiple collapsed children.
+/// How the height of a paragraph's lines is determined.
 #[derive(Debug, Copy, Clone)]
+enum LineSizing {
+    /// By the extent of the line's content.
+    Auto,
+    /// By the vertical metrics of the line's fonts.
+    Normal,
+    /// By fixed distances above and below the baseline.
+    Fixed { top: Abs, bottom: Abs },
+}
+
+impl LineSizing {
+    /// Resolve the line sizing of a paragraph.
+    fn new(engine: &Engine, styles: StyleChain) -> Self {
+        let height = match ParElem::line_height_in(styles) {
+            Smart::Auto => return Self::Auto,
+            Smart::Custom(LineHeight::Normal) => return Self::Normal,
+            Smart::Custom(LineHeight::Fixed(height)) => height.resolve(styles),
+        };
+
+        // Distribute the space around the paragraph font's ascender and
+        // descender, like half-leading in CSS.
+        let size = TextElem::size_in(styles);
+        let world = engine.world;
+        let variant = variant(styles);
+        let (top, bottom) = families(styles)
+            .find_map(|family| {
+                let id = world.book().select(family, variant)?;
+                let font = world.font(id)?;
+                Some(half_leading(height, font.metrics(), size))
+            })
+            .unwrap_or_else(|| {
+                let half = (height - Em::one().at(size)) / 2.0;
+                (Em::new(0.8).at(size) + half, Em::new(0.2).at(size) + half)
+            });
+
+        Self::Fixed { top, bottom }
+    }
+}
+
+/// The extent of text above and below the baseline with the given line height,
+/// distributed evenly above the font's ascender and below its descender.
+fn half_leading(height: Abs, metrics: &FontMetrics, size: Abs) -> (Abs, Abs) {
+    let ascent = metrics.ascender.at(size);
+    let descent = -metrics.descender.at(size);
+    let half = (height - ascent - descent) / 2.0;
+    (ascent + half, descent + half)
+}
+
+/// The extent of text above and below the baseline with normal line height,
+/// as given by the font's metrics.
+fn normal_extent(metrics: &FontMetrics, size: Abs) -> (Abs, Abs) {
+    let gap = metrics.line_gap.at(size) / 2.0;
+    (metrics.ascender.at(size) + gap, -metrics.descender.at(size) + gap)
+}
+
+/// A segment of one or multiple collapsed children.
+#[derive(Debug, Clone)]
 enum Segment<'a> {
     /// One or multiple collapsed text or text-equivalent children. Stores how
     /// long the segment is (in bytes of the full text string).
     Text(usize),
     /// Horizontal spacing between other segments.
     Spacing(Spacing),
-    /// A mathematical equation.
-    Equation(&'a EquationElem),
+    /// A mathematical equation, already split into breakable pieces.
+    Equation(Vec<MathParItem>),
     /// A box with arbitrary content.
     Box(&'a BoxElem, bool),
     /// Metadata.
@@ -202,7 +280,14 @@ impl Segment<'_> {
             Self::Text(len) => len,
             Self::Spacing(_) => SPACING_REPLACE.len_utf8(),
             Self::Box(_, true) => SPACING_REPLACE.len_utf8(),
-            Self::Equation(_) | Self::Box(_, _) => OBJ_REPLACE.len_utf8(),
+            Self::Equation(ref items) => items
+                .iter()
+                .map(|item| match item {
+                    MathParItem::Break(..) => SPACING_REPLACE.len_utf8(),
+                    MathParItem::Frame(_) => OBJ_REPLACE.len_utf8(),
+                })
+                .sum(),
+            Self::Box(_, _) => OBJ_REPLACE.len_utf8(),
             Self::Meta => 0,
         }
     }
@@ -400,7 +485,9 @@ impl<'a> Line<'a> {
 #[allow(clippy::type_complexity)]
 fn collect<'a>(
     children: &'a [Prehashed<Content>],
+    engine: &mut Engine<'_>,
     styles: &'a StyleChain<'a>,
+    region: Size,
     consecutive: bool,
 ) -> SourceResult<(String, Vec<(Segment<'a>, StyleChain<'a>)>, SpanMapper)> {
     let mut full = String::new();
@@ -409,7 +496,7 @@ fn collect<'a>(
     let mut spans = SpanMapper::new();
     let mut iter = children.iter().map(|c| &**c).peekable();
 
-    let first_line_indent = ParElem::first_line_indent_in(*styles);
+    let first_line_indent = ParElem::first_line_indent_in(*styles).amount;
     if !first_line_indent.is_zero()
         && consecutive
         && AlignElem::alignment_in(*styles).resolve(*styles).x
@@ -493,8 +580,15 @@ fn collect<'a>(
             }
             Segment::Text(full.len() - prev)
         } else if let Some(elem) = child.to::<EquationElem>() {
-            full.push(OBJ_REPLACE);
-            Segment::Equation(elem)
+            let pod = Regions::one(region, Axes::splat(false));
+            let items = elem.layout_inline(engine, styles, pod)?;
+            for item in &items {
+                full.push(match item {
+                    MathParItem::Break(..) => SPACING_REPLACE,
+                    MathParItem::Frame(_) => OBJ_REPLACE,
+                });
+            }
+            Segment::Equation(items)
         } else if let Some(elem) = child.to::<BoxElem>() {
             let frac = elem.width(styles).is_fractional();
             full.push(if frac { SPACING_REPLACE } else { OBJ_REPLACE });
@@ -512,7 +606,7 @@ fn collect<'a>(
         spans.push(segment.len(), child.span());
 
         if let (Some((Segment::Text(last_len), last_styles)), Segment::Text(len)) =
-            (segments.last_mut(), segment)
+            (segments.last_mut(), &segment)
         {
             if *last_styles == styles {
                 *last_len += len;
@@ -549,6 +643,7 @@ fn prepare<'a>(
 
     let mut cursor = 0;
     let mut items = Vec::with_capacity(segments.len());
+    let mut math_breaks = vec![];
 
     // Shape / layout the children and collect them into items.
     for (segment, styles) in segments {
@@ -566,11 +661,22 @@ fn prepare<'a>(
                     items.push(Item::Fractional(v, None));
                 }
             },
-            Segment::Equation(equation) => {
-                let pod = Regions::one(region, Axes::splat(false));
-                let mut frame = equation.layout(engine, styles, pod)?.into_frame();
-                frame.translate(Point::with_y(TextElem::baseline_in(styles)));
-                items.push(Item::Frame(frame));
+            Segment::Equation(pieces) => {
+                let mut offset = cursor;
+                for piece in pieces {
+                    match piece {
+                        MathParItem::Break(space, penalty) => {
+                            offset += SPACING_REPLACE.len_utf8();
+                            math_breaks.push((offset, penalty / 100.0));
+                            items.push(Item::Absolute(space));
+                        }
+                        MathParItem::Frame(mut frame) => {
+                            offset += OBJ_REPLACE.len_utf8();
+                            frame.translate(Point::with_y(TextElem::baseline_in(styles)));
+                            items.push(Item::Frame(frame));
+                        }
+                    }
+                }
             }
             Segment::Box(elem, _) => {
                 if let Sizing::Fr(v) = elem.width(styles) {
@@ -592,6 +698,7 @@ fn prepare<'a>(
         cursor = end;
     }
 
+    let line_sizing = LineSizing::new(engine, styles);
     let cjk_latin_spacing = TextElem::cjk_latin_spacing_in(styles).is_auto();
     if cjk_latin_spacing {
         add_cjk_latin_spacing(&mut items);
@@ -602,15 +709,23 @@ fn prepare<'a>(
         items,
         spans,
         hyphenate: shared_get(styles, children, TextElem::hyphenate_in),
+        nobreak: shared_get(styles, children, TextElem::nobreak_in),
         lang: shared_get(styles, children, TextElem::lang_in),
         align: AlignElem::alignment_in(styles).resolve(styles).x,
         justify: ParElem::justify_in(styles),
+        justify_last: ParElem::justify_last_in(styles),
         hang: ParElem::hanging_indent_in(styles),
         cjk_latin_spacing,
         fallback: TextElem::fallback_in(styles),
-        leading: ParElem::leading_in(styles),
+        leading: match line_sizing {
+            LineSizing::Auto => ParElem::leading_in(styles),
+            _ => Abs::zero(),
+        },
+        line_sizing,
         linebreaks: ParElem::linebreaks_in(styles),
         size: TextElem::size_in(styles),
+        line: ParLine::marker(styles),
+        math_breaks,
     })
 }
 
@@ -670,6 +785,10 @@ fn shape_range<'a>(
     spans: &SpanMapper,
     styles: StyleChain<'a>,
 ) {
+    if !range.is_empty() {
+        report_missing_fonts(engine, styles, variant(styles));
+    }
+
     let script = TextElem::script_in(styles);
     let lang = TextElem::lang_in(styles);
     let region = TextElem::region_in(styles);
@@ -777,6 +896,12 @@ fn linebreak_simple<'a>(
     let mut last = None;
 
     breakpoints(p, |end, breakpoint| {
+        // Inline equations only break in optimized line breaking, which can
+        // weigh their penalties against the other breakpoints.
+        if p.math_break(end).is_some() {
+            return;
+        }
+
         // Compute the line and its size.
         let mut attempt = line(engine, p, start..end, breakpoint);
 
@@ -940,6 +1065,11 @@ fn linebreak_optimized<'a>(
                 cost += HYPH_COST;
             }
 
+            // Penalize breaks in inline equations.
+            if let Some(penalty) = p.math_break(end) {
+                cost += penalty;
+            }
+
             // In Knuth paper, cost = (1 + 100|r|^3 + p)^2 + a,
             // where r is the ratio, p=50 is the penalty, and a=3000 is consecutive the penalty.
             // We divide the whole formula by 10, resulting (0.01 + |r|^3 + p)^2 + a,
@@ -984,8 +1114,8 @@ fn line<'a>(
     breakpoint: Breakpoint,
 ) -> Line<'a> {
     let end = range.end;
-    let mut justify =
-        p.justify && end < p.bidi.text.len() && breakpoint != Breakpoint::Mandatory;
+    let last = end == p.bidi.text.len() || breakpoint == Breakpoint::Mandatory;
+    let mut justify = p.justify && (!last || p.justify_last == JustifyLast::Justify);
 
     if range.is_empty() {
         return Line {
@@ -1005,6 +1135,14 @@ fn line<'a>(
     let (expanded, mut inner) = p.slice(range.clone());
     let mut width = Abs::zero();
 
+    // Trim the spacing of an inline equation that is broken at the line end.
+    if p.math_break(range.end).is_some() {
+        if let Some((Item::Absolute(_), before)) = inner.split_last() {
+            inner = before;
+            range.end -= SPACING_REPLACE.len_utf8();
+        }
+    }
+
     // Reshape the last item if it's split in half or hyphenated.
     let mut last = None;
     let mut dash = false;
@@ -1178,6 +1316,18 @@ fn finalize(
         .map(|line| commit(engine, p, line, width, region.y))
         .collect::<SourceResult<_>>()?;
 
+    // Mark the baselines of numbered lines. The page places the numbers.
+    if let Some(line) = &p.line {
+        for frame in &mut frames {
+            let mut marker = line.clone().pack();
+            marker.set_location(engine.locator.locate(hash128(&marker)));
+            frame.push(
+                Point::with_y(frame.baseline()),
+                FrameItem::Meta(Meta::Elem(marker), Size::zero()),
+            );
+        }
+    }
+
     // Prevent orphans.
     if frames.len() >= 2 && !frames[1].is_empty() {
         let second = frames.remove(1);
@@ -1248,6 +1398,20 @@ fn commit(
         }
     }
 
+    // Deal with the last line of a justified paragraph, which is only
+    // justified if configured so.
+    let mut align = p.align;
+    let mut justify = line.justify;
+    if p.justify && !justify {
+        match p.justify_last {
+            JustifyLast::Align | JustifyLast::Justify => {}
+            JustifyLast::Center => align = FixedAlign::Center,
+            JustifyLast::Threshold(ratio) => {
+                justify = line.width >= ratio.of(width - p.hang);
+            }
+        }
+    }
+
     // Determine how much additional space is needed.
     // The justicication_ratio is for the first step justification,
     // extra_justification is for the last step.
@@ -1263,7 +1427,7 @@ fn commit(
         // Attempt to reduce the length of the line, using shrinkability.
         justification_ratio = (remaining / shrink).max(-1.0);
         remaining = (remaining + shrink).min(Abs::zero());
-    } else if line.justify && fr.is_zero() {
+    } else if justify && fr.is_zero() {
         // Attempt to increase the length of the line, using stretchability.
         if stretch > Abs::zero() {
             justification_ratio = (remaining / stretch).min(1.0);
@@ -1281,6 +1445,32 @@ fn commit(
     let mut top = Abs::zero();
     let mut bottom = Abs::zero();
 
+    // Size the line according to the line height of its text, unless its
+    // baselines are fixed.
+    if !matches!(p.line_sizing, LineSizing::Fixed { .. }) {
+        for shaped in reordered.iter().filter_map(|item| item.text()) {
+            let height = match TextElem::line_height_in(shaped.styles) {
+                Smart::Custom(height) => height,
+                Smart::Auto if matches!(p.line_sizing, LineSizing::Normal) => {
+                    LineHeight::Normal
+                }
+                Smart::Auto => continue,
+            };
+
+            for glyph in shaped.glyphs.iter() {
+                let metrics = glyph.font.metrics();
+                let (ascent, descent) = match height {
+                    LineHeight::Normal => normal_extent(metrics, shaped.size),
+                    LineHeight::Fixed(height) => {
+                        half_leading(height.resolve(shaped.styles), metrics, shaped.size)
+                    }
+                };
+                top.set_max(ascent);
+                bottom.set_max(descent);
+            }
+        }
+    }
+
     // Build the frames and determine the height and baseline.
     let mut frames = vec![];
     for item in reordered {
@@ -1324,13 +1514,19 @@ fn commit(
         remaining = Abs::zero();
     }
 
+    // Keep the baselines at a fixed distance.
+    if let LineSizing::Fixed { top: fixed_top, bottom: fixed_bottom } = p.line_sizing {
+        top = fixed_top;
+        bottom = fixed_bottom;
+    }
+
     let size = Size::new(width, top + bottom);
     let mut output = Frame::soft(size);
     output.set_baseline(top);
 
     // Construct the line's frame.
     for (offset, frame) in frames {
-        let x = offset + p.align.position(remaining);
+        let x = offset + align.position(remaining);
         let y = top - frame.baseline();
         output.push_frame(Point::new(x, y), frame);
     }
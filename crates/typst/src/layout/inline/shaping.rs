@@ -9,15 +9,17 @@ use rustybuzz::{Tag, UnicodeBuffer};
 use unicode_script::{Script, UnicodeScript};
 
 use super::SpanMapper;
+use crate::diag::Lookup;
 use crate::engine::Engine;
-use crate::foundations::StyleChain;
+use crate::foundations::{Smart, StyleChain};
 use crate::layout::{Abs, Dir, Em, Frame, FrameItem, Point, Size};
 use crate::syntax::Span;
 use crate::text::{
     decorate, families, features, variant, Font, FontVariant, Glyph, Lang, Region,
     TextElem, TextItem,
 };
-use crate::util::SliceExt;
+use crate::util::{hash128, SliceExt};
+use crate::visualize::RelativeTo;
 use crate::World;
 
 /// The result of shaping text.
@@ -227,8 +229,11 @@ impl<'a> ShapedText<'a> {
 
         let shift = TextElem::baseline_in(self.styles);
         let lang = TextElem::lang_in(self.styles);
+        let region = TextElem::region_in(self.styles);
         let decos = TextElem::deco_in(self.styles);
         let fill = TextElem::fill_in(self.styles);
+        let stroke = TextElem::stroke_in(self.styles).map(|s| s.unwrap_or_default());
+        let per_glyph = fill.relative() == Smart::Custom(RelativeTo::Self_);
 
         for ((font, y_offset), group) in
             self.glyphs.as_ref().group_by_key(|g| (g.font.clone(), g.y_offset))
@@ -282,20 +287,28 @@ impl<'a> ShapedText<'a> {
                 font,
                 size: self.size,
                 lang,
+                region,
                 fill: fill.clone(),
+                stroke: stroke.clone(),
                 text: self.text[range.start - self.base..range.end - self.base].into(),
                 glyphs,
             };
 
             let width = item.width();
-            if decos.is_empty() {
+            if per_glyph {
+                // Paint each glyph relative to its own box.
+                push_per_glyph(&mut frame, &item, pos);
+            } else if decos.is_empty() {
                 frame.push(pos, FrameItem::Text(item));
+                offset += width;
+                continue;
             } else {
-                // Apply line decorations.
                 frame.push(pos, FrameItem::Text(item.clone()));
-                for deco in &decos {
-                    decorate(&mut frame, deco, &item, width, shift, pos);
-                }
+            }
+
+            // Apply line decorations.
+            for deco in &decos {
+                decorate(&mut frame, deco, &item, width, shift, pos);
             }
 
             offset += width;
@@ -566,6 +579,39 @@ struct ShapingContext<'a, 'v> {
     dir: Dir,
 }
 
+/// Push a text item glyph by glyph, with each glyph wrapped in a hard frame
+/// spanning its advance and the full height of `frame`.
+///
+/// A self-relative fill is turned into a parent-relative one, so that it is
+/// resolved against the frame of its glyph.
+fn push_per_glyph(frame: &mut Frame, item: &TextItem, pos: Point) {
+    let height = frame.height();
+    let fill = item.fill.as_decoration();
+
+    let mut x = pos.x;
+    for glyph in &item.glyphs {
+        let width = glyph.x_advance.at(item.size);
+        let single = TextItem {
+            font: item.font.clone(),
+            size: item.size,
+            fill: fill.clone(),
+            stroke: item.stroke.clone(),
+            lang: item.lang,
+            region: item.region,
+            text: item.text[glyph.range()].into(),
+            glyphs: vec![Glyph {
+                range: 0..glyph.range.end - glyph.range.start,
+                ..glyph.clone()
+            }],
+        };
+
+        let mut inner = Frame::hard(Size::new(width, height));
+        inner.push(Point::with_y(pos.y), FrameItem::Text(single));
+        frame.push_frame(Point::with_x(x), inner);
+        x += width;
+    }
+}
+
 /// Shape text into [`ShapedText`].
 #[allow(clippy::too_many_arguments)]
 pub(super) fn shape<'a>(
@@ -618,6 +664,34 @@ pub(super) fn shape<'a>(
     }
 }
 
+/// Report the font families the user requested that aren't available in the
+/// requested variant to the world.
+///
+/// This is not done in [`shape`] because text is reshaped at every line break
+/// candidate. Instead, it is done once per uniformly styled piece of text when
+/// a paragraph is prepared. The check itself is just a lookup in the font
+/// book per requested family, and since paragraph layout is memoized,
+/// unchanged paragraphs don't repeat it.
+pub(super) fn report_missing_fonts(
+    engine: &Engine,
+    styles: StyleChain,
+    variant: FontVariant,
+) {
+    let world = engine.world;
+    let book = world.book();
+    for family in TextElem::font_in(styles) {
+        let family = family.as_str();
+        let similar = match book.select(family, variant) {
+            Some(id) if book.info(id).is_some_and(|info| info.variant == variant) => {
+                continue;
+            }
+            Some(_) => true,
+            None => false,
+        };
+        world.missing(&Lookup::Font { family: family.into(), variant, similar });
+    }
+}
+
 /// Shape text with font fallback using the `families` iterator.
 fn shape_segment<'a>(
     ctx: &mut ShapingContext,
@@ -658,25 +732,8 @@ fn shape_segment<'a>(
 
     ctx.used.push(font.clone());
 
-    // Fill the buffer with our text.
-    let mut buffer = UnicodeBuffer::new();
-    buffer.push_str(text);
-    buffer.set_language(language(ctx.styles));
-    if let Some(script) = TextElem::script_in(ctx.styles).as_custom().and_then(|script| {
-        rustybuzz::Script::from_iso15924_tag(Tag::from_bytes(script.as_bytes()))
-    }) {
-        buffer.set_script(script)
-    }
-    buffer.set_direction(match ctx.dir {
-        Dir::LTR => rustybuzz::Direction::LeftToRight,
-        Dir::RTL => rustybuzz::Direction::RightToLeft,
-        _ => unimplemented!("vertical text layout"),
-    });
-
     // Shape!
-    let buffer = rustybuzz::shape(font.rusty(), &ctx.features, buffer);
-    let infos = buffer.glyph_infos();
-    let pos = buffer.glyph_positions();
+    let infos = shape_raw(ctx, &font, text);
     let ltr = ctx.dir.is_positive();
 
     // Collect the shaped glyphs, doing fallback and shaping parts again with
@@ -697,17 +754,17 @@ fn shape_segment<'a>(
 
             let c = text[cluster..].chars().next().unwrap();
             let script = c.script();
-            let x_advance = font.to_em(pos[i].x_advance);
+            let x_advance = font.to_em(info.x_advance);
             ctx.glyphs.push(ShapedGlyph {
                 font: font.clone(),
                 glyph_id: info.glyph_id as u16,
                 // TODO: Don't ignore y_advance.
                 x_advance,
-                x_offset: font.to_em(pos[i].x_offset),
-                y_offset: font.to_em(pos[i].y_offset),
+                x_offset: font.to_em(info.x_offset),
+                y_offset: font.to_em(info.y_offset),
                 adjustability: Adjustability::default(),
                 range: start..end,
-                safe_to_break: !info.unsafe_to_break(),
+                safe_to_break: !info.unsafe_to_break,
                 c,
                 span: ctx.spans.span_at(start),
                 is_justifiable: is_justifiable(
@@ -765,6 +822,121 @@ fn shape_segment<'a>(
     ctx.used.pop();
 }
 
+/// A glyph as it is produced by the shaper, in font units.
+#[derive(Debug, Copy, Clone)]
+struct RawGlyph {
+    glyph_id: u32,
+    cluster: u32,
+    x_advance: i32,
+    x_offset: i32,
+    y_offset: i32,
+    unsafe_to_break: bool,
+}
+
+impl RawGlyph {
+    /// The number of bytes of an encoded glyph.
+    const ENCODED_LEN: usize = 21;
+
+    /// Encode glyphs for persisting them.
+    fn encode(glyphs: &[Self]) -> Vec<u8> {
+        let mut data = Vec::with_capacity(glyphs.len() * Self::ENCODED_LEN);
+        for glyph in glyphs {
+            data.extend(glyph.glyph_id.to_le_bytes());
+            data.extend(glyph.cluster.to_le_bytes());
+            data.extend(glyph.x_advance.to_le_bytes());
+            data.extend(glyph.x_offset.to_le_bytes());
+            data.extend(glyph.y_offset.to_le_bytes());
+            data.push(glyph.unsafe_to_break as u8);
+        }
+        data
+    }
+
+    /// Decode persisted glyphs, returning `None` if the data is malformed.
+    fn decode(data: &[u8]) -> Option<Vec<Self>> {
+        if data.len() % Self::ENCODED_LEN != 0 {
+            return None;
+        }
+
+        let word = |chunk: &[u8], i: usize| -> [u8; 4] {
+            chunk[4 * i..4 * i + 4].try_into().unwrap()
+        };
+
+        Some(
+            data.chunks_exact(Self::ENCODED_LEN)
+                .map(|chunk| Self {
+                    glyph_id: u32::from_le_bytes(word(chunk, 0)),
+                    cluster: u32::from_le_bytes(word(chunk, 1)),
+                    x_advance: i32::from_le_bytes(word(chunk, 2)),
+                    x_offset: i32::from_le_bytes(word(chunk, 3)),
+                    y_offset: i32::from_le_bytes(word(chunk, 4)),
+                    unsafe_to_break: chunk[20] != 0,
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Shape text with a single font.
+///
+/// The result only depends on the font, the text, and the shaping
+/// properties, so it is persisted if the world has a
+/// [store](crate::World::store).
+fn shape_raw(ctx: &ShapingContext, font: &Font, text: &str) -> Vec<RawGlyph> {
+    let lang = language(ctx.styles);
+    let script = TextElem::script_in(ctx.styles);
+    let persist = ctx.engine.world.store().map(|store| {
+        let features: Vec<_> = ctx
+            .features
+            .iter()
+            .map(|f| (f.tag.to_bytes(), f.value, f.start, f.end))
+            .collect();
+        (store, hash128(&(font, text, lang.as_str(), script, ctx.dir, features)))
+    });
+
+    if let Some(glyphs) = persist
+        .and_then(|(store, key)| store.load("shape", key))
+        .and_then(|data| RawGlyph::decode(&data))
+    {
+        return glyphs;
+    }
+
+    // Fill the buffer with our text.
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.set_language(lang);
+    if let Some(script) = script.as_custom().and_then(|script| {
+        rustybuzz::Script::from_iso15924_tag(Tag::from_bytes(script.as_bytes()))
+    }) {
+        buffer.set_script(script)
+    }
+    buffer.set_direction(match ctx.dir {
+        Dir::LTR => rustybuzz::Direction::LeftToRight,
+        Dir::RTL => rustybuzz::Direction::RightToLeft,
+        _ => unimplemented!("vertical text layout"),
+    });
+
+    let buffer = rustybuzz::shape(font.rusty(), &ctx.features, buffer);
+    let glyphs: Vec<_> = buffer
+        .glyph_infos()
+        .iter()
+        .zip(buffer.glyph_positions())
+        .map(|(info, pos)| RawGlyph {
+            glyph_id: info.glyph_id,
+            cluster: info.cluster,
+            x_advance: pos.x_advance,
+            x_offset: pos.x_offset,
+            y_offset: pos.y_offset,
+            unsafe_to_break: info.unsafe_to_break(),
+        })
+        .collect();
+
+    if let Some((store, key)) = persist {
+        store.save("shape", key, &RawGlyph::encode(&glyphs));
+    }
+
+    glyphs
+}
+
 /// Shape the text with tofus from the given font.
 fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
     let x_advance = font.advance(0).unwrap_or_default();
@@ -1,5 +1,7 @@
 //! Composable layouts.
 
+pub mod geom;
+
 mod abs;
 mod align;
 mod angle;
@@ -33,6 +35,7 @@ mod sides;
 mod size;
 mod spacing;
 mod stack;
+mod tag;
 mod transform;
 
 pub use self::abs::*;
@@ -65,6 +68,7 @@ pub use self::sides::*;
 pub use self::size::*;
 pub use self::spacing::*;
 pub use self::stack::*;
+pub use self::tag::*;
 pub use self::transform::*;
 
 pub(crate) use self::inline::*;
@@ -76,7 +80,7 @@ use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
 use crate::foundations::{category, Category, Content, Scope, StyleChain};
 use crate::introspection::{Introspector, Locator};
-use crate::model::Document;
+use crate::model::{Document, DocumentElem};
 use crate::realize::{realize_block, realize_root, Scratch};
 use crate::World;
 
@@ -116,6 +120,7 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<HideElem>();
     global.define_func::<measure>();
     global.define_func::<layout>();
+    global.define_module(geom::module());
 }
 
 /// Root-level layout.
@@ -208,6 +213,40 @@ impl LayoutRoot for Content {
     }
 }
 
+/// Layout content into a document like [`LayoutRoot::layout_root`], handing
+/// out each page to `emit` as soon as the run of pages it belongs to is
+/// finished.
+///
+/// Unlike with `layout_root`, the document as a whole is not memoized since
+/// `emit` must observe the pages while they are produced. The runs of pages
+/// are still memoized individually.
+#[tracing::instrument(skip_all)]
+pub(crate) fn layout_root_streaming(
+    content: &Content,
+    engine: &mut Engine,
+    styles: StyleChain,
+    emit: &mut dyn FnMut(&Frame),
+) -> SourceResult<Document> {
+    tracing::info!("Starting streaming layout");
+    let mut locator = Locator::chained(engine.locator.track());
+    let mut engine = Engine {
+        world: engine.world,
+        introspector: engine.introspector,
+        route: Route::extend(engine.route.track()),
+        locator: &mut locator,
+        tracer: TrackedMut::reborrow_mut(&mut engine.tracer),
+    };
+    let scratch = Scratch::default();
+    let (realized, styles) = realize_root(&mut engine, &scratch, content, styles)?;
+    match realized.to::<DocumentElem>() {
+        Some(document) => document.layout_pages(&mut engine, styles, emit),
+        None => realized
+            .with::<dyn LayoutRoot>()
+            .unwrap()
+            .layout_root(&mut engine, styles),
+    }
+}
+
 impl Layout for Content {
     #[tracing::instrument(name = "Content::layout", skip_all)]
     fn layout(
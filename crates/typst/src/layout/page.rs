@@ -3,23 +3,31 @@ use std::num::NonZeroUsize;
 use std::ptr;
 use std::str::FromStr;
 
+use comemo::{Tracked, TrackedMut};
+use ecow::EcoString;
+
 use crate::diag::{bail, SourceResult};
-use crate::engine::Engine;
+use crate::engine::{Engine, Route};
+use crate::eval::Tracer;
 use crate::foundations::{
-    cast, elem, AutoValue, Cast, Content, Dict, Fold, Func, NativeElement, Resolve,
-    Smart, StyleChain, Value,
+    cast, dict, elem, scope, AutoValue, Cast, Content, Dict, Fold, Func, IntoValue,
+    NativeElement, Resolve, Show, Smart, StyleChain, Value,
+};
+use crate::introspection::{
+    Counter, CounterKey, Introspector, Locator, ManualPageCounter, Meta,
 };
-use crate::introspection::{Counter, CounterKey, ManualPageCounter, Meta};
 use crate::layout::{
-    Abs, Align, AlignElem, Axes, ColumnsElem, Dir, Fragment, Frame, HAlign, Layout,
-    Length, Point, Ratio, Regions, Rel, Sides, Size, VAlign,
+    Abs, Align, AlignElem, Axes, ColumnsElem, Dir, Fragment, Frame, FrameItem, FrameKind,
+    HAlign, Layout, Length, Point, Ratio, Regions, Rel, Role, Sides, Size, TagElem,
+    Transform, VAlign,
 };
 
-use crate::model::Numbering;
+use crate::model::{LineNumberingScope, NumberMargin, Numbering, ParLine};
 use crate::syntax::Spanned;
 use crate::text::TextElem;
 use crate::util::{NonZeroExt, Numeric, Scalar};
 use crate::visualize::Paint;
+use crate::World;
 
 /// Layouts its child onto one or multiple pages.
 ///
@@ -156,7 +164,8 @@ pub struct PageElem {
     /// How many columns the page has.
     ///
     /// If you need to insert columns into a page or other container, you can
-    /// also use the [`columns` function]($columns).
+    /// also use the [`columns` function]($columns). Its settings, like
+    /// [`balance`]($columns.balance), also apply to the page's columns.
     ///
     /// ```example:single
     /// #set page(columns: 2, height: 4.8cm)
@@ -320,6 +329,27 @@ pub struct PageElem {
     #[borrowed]
     pub foreground: Option<Content>,
 
+    /// A name for the page(s), like `{"cover"}` or `{"appendix"}`.
+    ///
+    /// The name doesn't affect the page's appearance. It can be retrieved for
+    /// the page at a location with
+    /// [`location.page-metadata`]($location.page-metadata) and is available to
+    /// exporters and other tools that process the document.
+    ///
+    /// ```example
+    /// #set page(name: "cover")
+    /// #locate(loc => loc.page-metadata().name)
+    /// ```
+    #[borrowed]
+    pub name: Option<EcoString>,
+
+    /// Tags for the page(s), like `{("front-matter", "unnumbered")}`.
+    ///
+    /// Like the [`name`]($page.name), the tags don't affect the page's
+    /// appearance, but can be retrieved by scripts and other tools.
+    #[borrowed]
+    pub tags: Vec<EcoString>,
+
     /// The contents of the page(s).
     ///
     /// Multiple pages will be created if the content does not fit on a single
@@ -340,6 +370,11 @@ impl PageElem {
     /// while we post-process the pages in this function. This function returns
     /// a fragment consisting of multiple frames, one per output page of this
     /// page run.
+    ///
+    /// Page runs are memoized as a whole: As long as a run's content, its
+    /// incoming styles, and the page counter state at its start are unchanged,
+    /// its pages are reused without any relayout. This way, editing a late
+    /// chapter of a long document doesn't relayout the chapters before it.
     #[tracing::instrument(skip_all)]
     pub fn layout(
         &self,
@@ -347,6 +382,58 @@ impl PageElem {
         styles: StyleChain,
         page_counter: &mut ManualPageCounter,
         extend_to: Option<Parity>,
+    ) -> SourceResult<Fragment> {
+        #[allow(clippy::too_many_arguments)]
+        #[comemo::memoize]
+        fn cached(
+            elem: &PageElem,
+            world: Tracked<dyn World + '_>,
+            introspector: Tracked<Introspector>,
+            route: Tracked<Route>,
+            locator: Tracked<Locator>,
+            tracer: TrackedMut<Tracer>,
+            styles: StyleChain,
+            page_counter: ManualPageCounter,
+            extend_to: Option<Parity>,
+        ) -> SourceResult<(Fragment, ManualPageCounter)> {
+            let mut page_counter = page_counter;
+            let mut locator = Locator::chained(locator);
+            let mut engine = Engine {
+                world,
+                introspector,
+                route: Route::extend(route),
+                locator: &mut locator,
+                tracer,
+            };
+            let fragment =
+                elem.layout_run(&mut engine, styles, &mut page_counter, extend_to)?;
+            Ok((fragment, page_counter))
+        }
+
+        let (fragment, counter) = cached(
+            self,
+            engine.world,
+            engine.introspector,
+            engine.route.track(),
+            engine.locator.track(),
+            TrackedMut::reborrow_mut(&mut engine.tracer),
+            styles,
+            *page_counter,
+            extend_to,
+        )?;
+
+        *page_counter = counter;
+        engine.locator.visit_frames(&fragment);
+        Ok(fragment)
+    }
+
+    /// Layout the page run without memoization.
+    fn layout_run(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        page_counter: &mut ManualPageCounter,
+        extend_to: Option<Parity>,
     ) -> SourceResult<Fragment> {
         tracing::info!("Page layout");
 
@@ -390,6 +477,7 @@ impl PageElem {
         }
 
         let area = size - margin.sum_by_axis();
+        let gutter = ColumnsElem::gutter_in(styles).relative_to(area.x);
         let mut regions = Regions::repeat(area, area.map(Abs::is_finite));
         regions.root = true;
 
@@ -398,13 +486,21 @@ impl PageElem {
 
         // Align the child to the pagebreak's parity.
         // Check for page count after adding the pending frames
+        let mut blank = None;
         if extend_to
             .is_some_and(|p| !p.matches(page_counter.physical().get() + frames.len()))
         {
-            // Insert empty page after the current pages.
+            // Insert a blank page after the current pages. Its content is
+            // determined by show rules on `pagebreak.blank`.
             let size = area.map(Abs::is_finite).select(area, Size::zero());
-            frames.push(Frame::hard(size));
+            let pod = Regions::one(size, Axes::splat(true));
+            let mut frame =
+                BlankPageElem::new().pack().layout(engine, styles, pod)?.into_frame();
+            frame.set_kind(FrameKind::Hard);
+            blank = Some(frames.len());
+            frames.push(frame);
         }
+        let blank_marginals = BlankPageElem::marginals_in(styles);
 
         let fill = self.fill(styles);
         let foreground = Cow::Borrowed(self.foreground(styles));
@@ -413,6 +509,10 @@ impl PageElem {
         let footer_descent = self.footer_descent(styles);
         let numbering = self.numbering(styles);
         let numbering_meta = Meta::PageNumbering(numbering.clone());
+        let metadata_meta = Meta::PageMetadata(PageMetadata {
+            name: self.name(styles).clone(),
+            tags: self.tags(styles).clone(),
+        });
         let number_align = self.number_align(styles);
         let mut header = Cow::Borrowed(self.header(styles));
         let mut footer = Cow::Borrowed(self.footer(styles));
@@ -443,7 +543,7 @@ impl PageElem {
         }
 
         // Post-process pages.
-        for frame in frames.iter_mut() {
+        for (i, frame) in frames.iter_mut().enumerate() {
             tracing::info!("Layouting page #{}", page_counter.physical());
 
             // The padded width of the page's content without margins.
@@ -461,6 +561,10 @@ impl PageElem {
             frame.set_size(frame.size() + margin.sum_by_axis());
             frame.translate(Point::new(margin.left, margin.top));
             frame.push_positionless_meta(numbering_meta.clone());
+            frame.push_positionless_meta(metadata_meta.clone());
+
+            // Number the lines of paragraphs.
+            number_lines(engine, styles, frame, margin, columns, gutter)?;
 
             // The page size with margins.
             let size = frame.size();
@@ -476,6 +580,14 @@ impl PageElem {
 
                 let Some(content) = &**marginal else { continue };
 
+                // Blank pages may opt out of headers and footers.
+                if blank == Some(i)
+                    && !blank_marginals
+                    && (ptr::eq(marginal, &header) || ptr::eq(marginal, &footer))
+                {
+                    continue;
+                }
+
                 let (pos, area, align);
                 if ptr::eq(marginal, &header) {
                     let ascent = header_ascent.relative_to(margin.top);
@@ -493,10 +605,14 @@ impl PageElem {
                     align = HAlign::Center + VAlign::Horizon;
                 };
 
+                // The lines of marginals are neither numbered nor counted.
                 let pod = Regions::one(area, Axes::splat(true));
-                let sub = content
+                let aligned = content
                     .clone()
                     .styled(AlignElem::set_alignment(align))
+                    .styled(ParLine::set_numbering(None));
+                let sub = TagElem::new(Role::Artifact, aligned)
+                    .pack()
                     .layout(engine, styles, pod)?
                     .into_frame();
 
@@ -527,6 +643,127 @@ impl PageElem {
     }
 }
 
+/// Place the numbers of the numbered paragraph lines on a page in its margin.
+///
+/// With multiple columns, the numbers of the first column are placed in the
+/// left margin, those of the last column in the right margin, and those of
+/// the columns in between in the gutter before them.
+fn number_lines(
+    engine: &mut Engine,
+    styles: StyleChain,
+    frame: &mut Frame,
+    margin: Sides<Abs>,
+    columns: NonZeroUsize,
+    gutter: Abs,
+) -> SourceResult<()> {
+    let mut lines = vec![];
+    collect_lines(frame, Transform::identity(), &mut lines);
+
+    // With continuous numbering, the numbers on this page continue from the
+    // number of lines on the previous pages.
+    let Some((_, first)) = lines.first() else { return Ok(()) };
+    let before = engine
+        .introspector
+        .query(
+            &ParLine::elem()
+                .select()
+                .before(first.location().unwrap().into(), false),
+        )
+        .len();
+
+    let size = frame.size();
+    let count = columns.get();
+    let width = size.x - margin.sum_by_axis().x;
+    let column = (width - gutter * (count - 1) as f64) / count as f64;
+    let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+    for (i, (pos, line)) in lines.into_iter().enumerate() {
+        let line = line.to::<ParLine>().unwrap();
+        let Some(numbering) = line.numbering(StyleChain::default()) else { continue };
+        let number = match line.numbering_scope(StyleChain::default()) {
+            LineNumberingScope::Document => before + i + 1,
+            LineNumberingScope::Page => i + 1,
+        };
+
+        let body = numbering.apply(engine, &[number])?.display();
+        let sub = TagElem::new(Role::Artifact, body)
+            .pack()
+            .layout(engine, styles, pod)?
+            .into_frame();
+
+        let clearance = line.number_clearance(StyleChain::default());
+        let left = margin.left - clearance - sub.width();
+        let right = size.x - margin.right + clearance;
+        let x = if count > 1 {
+            let offset = (pos.x - margin.left) / (column + gutter);
+            match (offset.max(0.0) as usize).min(count - 1) {
+                0 => left,
+                i if i == count - 1 => right,
+                i => left + (column + gutter) * i as f64,
+            }
+        } else {
+            match line.number_margin(StyleChain::default()) {
+                NumberMargin::Right | NumberMargin::End => right,
+                _ => left,
+            }
+        };
+
+        frame.push_frame(Point::new(x, pos.y - sub.baseline()), sub);
+    }
+
+    Ok(())
+}
+
+/// Collect the markers of numbered paragraph lines in a frame, together with
+/// their positions.
+fn collect_lines(frame: &Frame, ts: Transform, lines: &mut Vec<(Point, Content)>) {
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => {
+                let ts = ts
+                    .pre_concat(Transform::translate(pos.x, pos.y))
+                    .pre_concat(group.transform);
+                collect_lines(&group.frame, ts, lines);
+            }
+            FrameItem::Meta(Meta::Elem(elem), _) if elem.is::<ParLine>() => {
+                lines.push((pos.transform(ts), elem.clone()));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Metadata attached to a page, which identifies it to scripts and tools.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct PageMetadata {
+    /// The page's name.
+    pub name: Option<EcoString>,
+    /// The page's tags.
+    pub tags: Vec<EcoString>,
+}
+
+impl PageMetadata {
+    /// Find the metadata of a page frame in a laid out document.
+    pub fn of(page: &Frame) -> Option<&Self> {
+        page.items().find_map(|(_, item)| match item {
+            FrameItem::Meta(Meta::PageMetadata(metadata), _) => Some(metadata),
+            _ => None,
+        })
+    }
+
+    /// Whether the page has the given tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+cast! {
+    PageMetadata,
+    self => dict! {
+        "name" => self.name.into_value(),
+        "tags" => self.tags.into_value(),
+    }.into_value(),
+}
+
 /// Specification of the page's margins.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Margin {
@@ -711,7 +948,7 @@ cast! {
 /// == Compound Theory
 /// In 1984, the first ...
 /// ```
-#[elem(title = "Page Break")]
+#[elem(scope, title = "Page Break")]
 pub struct PagebreakElem {
     /// If `{true}`, the page break is skipped if the current page is already
     /// empty.
@@ -719,7 +956,8 @@ pub struct PagebreakElem {
     pub weak: bool,
 
     /// If given, ensures that the next page will be an even/odd page, with an
-    /// empty page in between if necessary.
+    /// empty page in between if necessary. The look of the inserted page can
+    /// be customized through [`pagebreak.blank`]($pagebreak.blank).
     ///
     /// ```example
     /// #set page(height: 30pt)
@@ -731,6 +969,52 @@ pub struct PagebreakElem {
     pub to: Option<Parity>,
 }
 
+#[scope]
+impl PagebreakElem {
+    #[elem]
+    type BlankPageElem;
+}
+
+/// A blank page inserted by a [page break]($pagebreak) to reach an even or
+/// odd page.
+///
+/// By default, blank pages are empty apart from their header, footer,
+/// background, and foreground. Use a show rule to give them content.
+///
+/// ```example
+/// #set page(height: 80pt, footer: align(right, counter(page).display()))
+/// #show pagebreak.blank: align(center + horizon)[
+///   _This page intentionally left blank._
+/// ]
+///
+/// First.
+/// #pagebreak(to: "odd")
+/// Third.
+/// ```
+#[elem(name = "blank", title = "Blank Page", Show)]
+pub struct BlankPageElem {
+    /// Whether to show the page's header and footer on blank pages. This
+    /// includes the page number.
+    ///
+    /// ```example
+    /// #set page(height: 60pt, numbering: "1")
+    /// #set pagebreak.blank(marginals: false)
+    ///
+    /// First.
+    /// #pagebreak(to: "odd")
+    /// Third.
+    /// ```
+    #[default(true)]
+    pub marginals: bool,
+}
+
+impl Show for BlankPageElem {
+    #[tracing::instrument(name = "BlankPageElem::show", skip_all)]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
 /// Whether something should be even or odd.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum Parity {
@@ -1,7 +1,9 @@
 use std::fmt::{self, Debug, Formatter};
 use std::ops::{Add, Div, Mul, Neg};
 
-use crate::layout::{Abs, Axis, Size, Transform};
+use crate::diag::bail;
+use crate::foundations::{array, cast, Array};
+use crate::layout::{Abs, Axis, Length, Size, Transform};
 use crate::util::{Get, Numeric};
 
 /// A point in 2D.
@@ -148,3 +150,17 @@ assign_impl!(Point += Point);
 assign_impl!(Point -= Point);
 assign_impl!(Point *= f64);
 assign_impl!(Point /= f64);
+
+cast! {
+    Point,
+    self => array![self.x, self.y].into_value(),
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => {
+                Point::new(a.cast::<Length>()?.abs, b.cast::<Length>()?.abs)
+            }
+            _ => bail!("point array must contain exactly two entries"),
+        }
+    },
+}
@@ -27,11 +27,52 @@ use crate::util::Numeric;
 ///   Berlin, the 22nd of December, 2022
 /// ]
 /// ```
+///
+/// # Leaders
+/// To fill the space between two pieces of inline content, put the repeated
+/// content into a [box]($box) with [fractional]($fraction) width. Multiple
+/// such boxes in one line share the remaining space according to their
+/// fractions.
+///
+/// By default, the instances are spread out to fill the available space
+/// exactly, so their positions depend on the surrounding content. For
+/// leaders that line up across multiple lines, disable `justify`. The
+/// instances are then packed towards the current [alignment]($align).
+///
+/// ```example
+/// #set align(right)
+/// #let entry(name, price) = [
+///   #name
+///   #box(width: 1fr, repeat(gap: 2pt, justify: false)[.])
+///   #box(width: 2em, align(right, price))
+/// ]
+///
+/// #entry[Coffee][3] \
+/// #entry[Iced Tea][4] \
+/// #entry[Hot Chocolate][12]
+/// ```
 #[elem(Layout)]
 pub struct RepeatElem {
     /// The content to repeat.
     #[required]
     pub body: Content,
+
+    /// The gap between each instance of the body.
+    ///
+    /// ```example
+    /// #box(width: 1fr, repeat(gap: 0.5em)[/])
+    /// ```
+    #[resolve]
+    pub gap: Length,
+
+    /// Whether to increase the gap between instances to completely fill the
+    /// available space.
+    ///
+    /// ```example
+    /// #box(width: 1fr, repeat(justify: false)[ A ])
+    /// ```
+    #[default(true)]
+    pub justify: bool,
 }
 
 impl Layout for RepeatElem {
@@ -46,11 +87,17 @@ impl Layout for RepeatElem {
         let piece = self.body().layout(engine, styles, pod)?.into_frame();
         let align = AlignElem::alignment_in(styles).resolve(styles);
 
+        let gap = self.gap(styles);
+        let justify = self.justify(styles);
+
+        // A gap only occurs between instances, so the available space
+        // effectively grows by one gap.
         let fill = regions.size.x;
         let width = piece.width();
-        let count = (fill / width).floor();
-        let remaining = fill % width;
-        let apart = remaining / (count - 1.0);
+        let step = width + gap;
+        let count = ((fill + gap) / step).floor();
+        let remaining = (fill + gap) % step;
+        let apart = if justify { gap + remaining / (count - 1.0) } else { gap };
 
         let size = Size::new(regions.size.x, piece.height());
 
@@ -64,11 +111,11 @@ impl Layout for RepeatElem {
         }
 
         let mut offset = Abs::zero();
-        if count == 1.0 {
+        if count == 1.0 || !justify {
             offset += align.x.position(remaining);
         }
 
-        if width > Abs::zero() {
+        if width > Abs::zero() && step > Abs::zero() {
             for _ in 0..(count as usize).min(1000) {
                 frame.push_frame(Point::with_x(offset), piece.clone());
                 offset += piece.width() + apart;
@@ -0,0 +1,55 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, StyleChain};
+use crate::layout::{Fragment, Layout, Regions, Role, Tag};
+use crate::syntax::Span;
+use crate::util::hash128;
+
+/// Marks its content with a semantic role for tagged export.
+///
+/// This element is not exposed to users. Elements with a meaningful role in
+/// the document's structure wrap their realized content in it.
+#[elem(Layout)]
+pub struct TagElem {
+    /// The role of the content.
+    #[required]
+    pub role: Role,
+
+    /// The tagged content.
+    #[required]
+    pub body: Content,
+}
+
+impl Layout for TagElem {
+    #[tracing::instrument(name = "TagElem::layout", skip_all)]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let mut fragment = self.body().layout(engine, styles, regions)?;
+        if let Some(tag) = locate_tag(engine, *self.role(), self.span()) {
+            for frame in &mut fragment {
+                frame.tag(tag.clone());
+            }
+        }
+        Ok(fragment)
+    }
+}
+
+/// Create a tag for an element with a location that is unique in the document.
+///
+/// Returns `None` if tags are not recorded for this compilation. The location
+/// only needs to be unique and the locator disambiguates equal hashes, so the
+/// element's role and span suffice as its identity.
+pub(crate) fn locate_tag(engine: &mut Engine, role: Role, span: Span) -> Option<Tag> {
+    if !engine.tracer.records_tags() {
+        return None;
+    }
+
+    Some(Tag {
+        role,
+        location: engine.locator.locate(hash128(&(role, span))),
+    })
+}
@@ -53,26 +53,39 @@ pub mod symbols;
 pub mod text;
 pub mod visualize;
 
+mod prefetch;
+
 #[doc(inline)]
 pub use typst_syntax as syntax;
 
-use std::collections::HashSet;
+pub use crate::prefetch::{compile_async, AsyncWorld, BoxFuture, Prefetch};
+
+use std::collections::{HashMap, HashSet};
 use std::ops::Range;
 
 use comemo::{Prehashed, Track, Tracked, Validate};
 use ecow::{EcoString, EcoVec};
+use indexmap::IndexMap;
 
-use crate::diag::{warning, FileResult, SourceDiagnostic, SourceResult};
-use crate::engine::{Engine, Route};
-use crate::eval::Tracer;
+use crate::diag::{
+    error, warning, At, FileError, FileResult, Lookup, SourceDiagnostic, SourceResult,
+    WarningCategory,
+};
+use crate::engine::{CompilationCache, Engine, Route};
+use crate::eval::{eval_string, EvalMode, Tracer};
 use crate::foundations::{
-    Array, Bytes, Content, Datetime, Module, Scope, StyleChain, Styles,
+    Array, Bytes, Datetime, Dict, IntoValue, Label, LocatableSelector, Module,
+    NativeElement, Repr, Scope, StyleChain, Styles, Value,
 };
 use crate::introspection::{Introspector, Locator};
-use crate::layout::{Align, Dir, LayoutRoot};
-use crate::model::Document;
+use crate::layout::{Align, Dir, Frame, LayoutRoot};
+use crate::model::{
+    BibliographyElem, CiteGroup, CustomStyle, Destination, Document, RefElem,
+};
+use crate::realize::RealizeHook;
 use crate::syntax::{FileId, PackageSpec, Source, Span};
 use crate::text::{Font, FontBook};
+use crate::util::persist::Store;
 use crate::visualize::Color;
 
 /// Compile a source file into a fully layouted document.
@@ -88,31 +101,395 @@ pub fn compile(world: &dyn World, tracer: &mut Tracer) -> SourceResult<Document>
     // Call `track` on the world just once to keep comemo's ID stable.
     let world = world.track();
 
-    // Try to evaluate the source file into a module.
-    let module = crate::eval::eval(
-        world,
-        Route::default().track(),
-        tracer.track_mut(),
-        &world.main(),
-    )
-    .map_err(deduplicate)?;
-
-    // Typeset the module's content, relayouting until convergence.
-    typeset(world, tracer, &module.content()).map_err(deduplicate)
+    // Evaluate and typeset the main source file, relayouting until
+    // convergence.
+    let mut introspector = Introspector::default();
+    let mut documents = typeset(world, tracer, &[world.main()], &mut introspector, None)
+        .map_err(deduplicate)?;
+    Ok(documents.remove(0))
+}
+
+/// Compile a source file into a fully layouted document, starting out with
+/// the introspection results of a previous compilation with the same `cache`.
+///
+/// Behaves like [`compile`], but the first layout iteration sees the
+/// introspection results (counters, references, page numbers, ...) of the
+/// previous compilation instead of empty ones. Small edits rarely change
+/// these, so the document typically converges after a single iteration
+/// instead of two or more. This is intended for long-running hosts like
+/// `typst watch` or language servers.
+///
+/// The cache does not retain layouted frames and doesn't invalidate anything
+/// by edit range. Reusing the evaluation and layout of unchanged parts of the
+/// document is left to memoization, just like with [`compile`].
+///
+/// The output is the same as with [`compile`]. A cache that was last used for
+/// a different document is safe to use, but does not save any work.
+#[tracing::instrument(skip_all)]
+pub fn compile_incremental(
+    world: &dyn World,
+    tracer: &mut Tracer,
+    cache: &CompilationCache,
+) -> SourceResult<Document> {
+    // Call `track` on the world just once to keep comemo's ID stable.
+    let world = world.track();
+
+    // Evaluate and typeset the main source file, relayouting until
+    // convergence.
+    let mut introspector = cache.take();
+    let result = typeset(world, tracer, &[world.main()], &mut introspector, None);
+    cache.store(introspector);
+    let mut documents = result.map_err(deduplicate)?;
+    Ok(documents.remove(0))
+}
+
+/// Compile a source file into a fully layouted document, handing out pages
+/// while the document is still being layouted.
+///
+/// The root layouter calls `emit` with the index and frame of each page as
+/// soon as the run of pages it belongs to is finished, so that a preview can
+/// show the first pages of a long document before the rest is layouted.
+/// Layout runs in iterations until all introspections (counters, references,
+/// page numbers, ...) stabilize. In later iterations, only pages that differ
+/// from the version emitted before are emitted again. The last emission of a
+/// page is final and the returned document tells how many pages there are in
+/// the end.
+///
+/// Pages may already have been emitted when compilation fails. Otherwise,
+/// this behaves like [`compile`].
+#[tracing::instrument(skip_all)]
+pub fn compile_streaming(
+    world: &dyn World,
+    tracer: &mut Tracer,
+    emit: &mut dyn FnMut(usize, &Frame),
+) -> SourceResult<Document> {
+    // Call `track` on the world just once to keep comemo's ID stable.
+    let world = world.track();
+
+    // The hashes of the pages emitted so far.
+    let mut emitted: Vec<u128> = vec![];
+    let mut stream = |i: usize, page: &Frame| {
+        let hash = crate::util::hash128(page);
+        match emitted.get_mut(i) {
+            Some(prev) if *prev == hash => return,
+            Some(prev) => *prev = hash,
+            None => emitted.push(hash),
+        }
+        emit(i, page);
+    };
+
+    // Evaluate and typeset the main source file, relayouting until
+    // convergence.
+    let mut introspector = Introspector::default();
+    let mut documents =
+        typeset(world, tracer, &[world.main()], &mut introspector, Some(&mut stream))
+            .map_err(deduplicate)?;
+    Ok(documents.remove(0))
+}
+
+/// Compile multiple source files into separate, fully layouted documents that
+/// share their introspection state.
+///
+/// Each of the `entries` is typeset into its own document. All documents are
+/// introspected together, as if they were consecutive parts of one big
+/// document: Counters continue from one document to the next, labels and
+/// bibliography entries defined in one document can be referenced from all
+/// others, and queries find elements across all documents.
+///
+/// Physical page numbers and positions, as returned by `loc.page()` and
+/// `loc.position()`, are relative to the document an element is in. Links
+/// into another document are resolved to a [`Destination::Document`] with the
+/// target document's index and a position within it.
+///
+/// - Returns `Ok(documents)` with one document per entry if there were no
+///   fatal errors.
+/// - Returns `Err(errors)` if there were fatal errors.
+///
+/// Like [`compile`], this requires a mutable reference to a tracer, which
+/// collects the warnings of all documents.
+#[tracing::instrument(skip_all)]
+pub fn compile_project(
+    world: &dyn World,
+    entries: &[FileId],
+    tracer: &mut Tracer,
+) -> SourceResult<Vec<Document>> {
+    // Call `track` on the world just once to keep comemo's ID stable.
+    let world = world.track();
+
+    // Load each of the source files.
+    let sources = entries
+        .iter()
+        .map(|&id| world.load_source(id).at(Span::detached()))
+        .collect::<SourceResult<Vec<_>>>()?;
+
+    // Evaluate and typeset all sources together, relayouting until
+    // convergence.
+    let mut introspector = Introspector::default();
+    let mut documents =
+        typeset(world, tracer, &sources, &mut introspector, None).map_err(deduplicate)?;
+
+    // Each document is exported on its own, so links into other documents
+    // must already be resolved to a position in the target document.
+    for (i, document) in documents.iter_mut().enumerate() {
+        for page in &mut document.pages {
+            page.map_links(&mut |dest| {
+                let Destination::Location(loc) = *dest else { return };
+                match introspector.document(loc) {
+                    Some(target) if target != i => {
+                        *dest = Destination::Document(target, introspector.position(loc));
+                    }
+                    _ => {}
+                }
+            });
+        }
+    }
+
+    Ok(documents)
+}
+
+/// Compile the main source file once for each set of inputs.
+///
+/// Each variant sees its inputs as `sys.inputs`. This way, one document can
+/// produce, e.g., an exercise sheet with and without solutions or one version
+/// per locale. All variants are compiled against the same world: Sources are
+/// parsed just once and memoized work that doesn't depend on the inputs, like
+/// the layout of unaffected parts of the document, is shared between them.
+///
+/// - Returns `Ok(documents)` with one document per variant if there were no
+///   fatal errors.
+/// - Returns `Err(errors)` with the errors of the first failing variant.
+///
+/// Like [`compile`], this requires a mutable reference to a tracer, which
+/// collects the warnings of all variants.
+#[tracing::instrument(skip_all)]
+pub fn compile_variants(
+    world: &dyn World,
+    tracer: &mut Tracer,
+    variants: &[Dict],
+) -> SourceResult<Vec<Document>> {
+    variants
+        .iter()
+        .map(|inputs| compile(&VariantWorld::new(world, inputs.clone()), tracer))
+        .collect()
+}
+
+/// A world that only differs from another one in the inputs of its library.
+struct VariantWorld<'a> {
+    world: &'a dyn World,
+    library: Prehashed<Library>,
+}
+
+impl<'a> VariantWorld<'a> {
+    /// Create a variant of a world with different inputs.
+    fn new(world: &'a dyn World, inputs: Dict) -> Self {
+        let mut library = (**world.library()).clone();
+        library.set_inputs(inputs);
+        Self { world, library: Prehashed::new(library) }
+    }
+}
+
+impl World for VariantWorld<'_> {
+    fn library(&self) -> &Prehashed<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        self.world.book()
+    }
+
+    fn main(&self) -> Source {
+        self.world.main()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        self.world.source(id)
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        self.world.file(id)
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.world.font(index)
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        self.world.today(offset)
+    }
+
+    fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
+        self.world.packages()
+    }
+
+    fn missing(&self, lookup: &Lookup) {
+        self.world.missing(lookup)
+    }
+
+    fn citation_style(&self, name: &str) -> Option<CustomStyle> {
+        self.world.citation_style(name)
+    }
 }
 
-/// Relayout until introspection converges.
+/// Compile the main source file and query the resulting document.
+///
+/// The `selector` is evaluated as Typst code and must yield a locatable
+/// selector, for example `heading`, `<intro>`, or
+/// `figure.where(kind: table)`. Each matching element is returned as a
+/// content value. If a `field` is given, the value of this field is returned
+/// instead and elements without the field are skipped. The values can be
+/// serialized, e.g. to extract a table of contents or metadata for build tools.
+///
+/// Like [`compile`], this requires a mutable reference to a tracer, which
+/// collects the warnings of the compilation.
+#[tracing::instrument(skip_all)]
+pub fn query(
+    world: &dyn World,
+    tracer: &mut Tracer,
+    selector: &str,
+    field: Option<&str>,
+) -> SourceResult<Vec<Value>> {
+    let document = compile(world, tracer)?;
+    let selector = eval_string(
+        world.track(),
+        selector,
+        Span::detached(),
+        EvalMode::Code,
+        Scope::default(),
+    )?
+    .cast::<LocatableSelector>()
+    .at(Span::detached())?;
+
+    Ok(Introspector::new(&document.pages)
+        .query(&selector.0)
+        .into_iter()
+        .filter_map(|elem| {
+            let elem = elem.into_inner();
+            match field {
+                Some(field) => elem.get_by_name(field),
+                None => Some(elem.into_value()),
+            }
+        })
+        .collect())
+}
+
+/// Check a compiled document for labels that are attached to more than one
+/// element.
+///
+/// Such labels are fine as long as nothing refers to them. References and
+/// citations with a missing or ambiguous target normally fail the
+/// compilation. But when a show rule replaces them without looking at their
+/// target, they end up in the document as placeholders. A build that must not
+/// contain such placeholders can promote the [`UnresolvedReference`] category,
+/// so that compilation reports them as errors.
+///
+/// [`UnresolvedReference`]: WarningCategory::UnresolvedReference
+pub fn audit(document: &Document) -> EcoVec<SourceDiagnostic> {
+    let introspector = Introspector::new(&document.pages);
+
+    let mut diags = EcoVec::new();
+    for (label, spans) in &labelled(&introspector) {
+        for &span in spans.iter().skip(1) {
+            diags.push(
+                error!(
+                    span,
+                    "label `{}` occurs multiple times in the document",
+                    label.repr()
+                )
+                .with_hint("a label should only be attached to one element"),
+            );
+        }
+    }
+
+    deduplicate(diags)
+}
+
+/// The spans of the elements with each label.
+fn labelled(introspector: &Introspector) -> IndexMap<Label, Vec<Span>> {
+    let mut labelled = IndexMap::<Label, Vec<Span>>::new();
+    for elem in introspector.all() {
+        if let Some(label) = elem.label() {
+            labelled.entry(label).or_default().push(elem.span());
+        }
+    }
+    labelled
+}
+
+/// Warn about references and citations in the document whose target is
+/// missing or ambiguous.
+///
+/// Since such references and citations fail their built-in show rule, all of
+/// them were replaced by a user's show rule.
+fn unresolved(
+    introspector: Tracked<Introspector>,
+    labelled: &IndexMap<Label, Vec<Span>>,
+) -> EcoVec<SourceDiagnostic> {
+    let mut diags = EcoVec::new();
+    for elem in introspector.query(&RefElem::elem().select()) {
+        let elem = elem.to::<RefElem>().unwrap();
+        let target = *elem.target();
+        if BibliographyElem::contains(introspector, target) {
+            continue;
+        }
+
+        let message = match labelled.get(&target).map_or(0, Vec::len) {
+            0 => "does not exist in the document",
+            1 => continue,
+            _ => "occurs multiple times in the document",
+        };
+
+        diags.push(
+            warning!(elem.span(), "label `{}` {message}", target.repr())
+                .with_category(WarningCategory::UnresolvedReference),
+        );
+    }
+
+    for group in introspector.query(&CiteGroup::elem().select()) {
+        for child in group.to::<CiteGroup>().unwrap().children() {
+            let key = *child.key();
+            if !BibliographyElem::contains(introspector, key) {
+                diags.push(
+                    warning!(
+                        child.span(),
+                        "key `{}` does not exist in the bibliography",
+                        key.as_str()
+                    )
+                    .with_category(WarningCategory::UnresolvedReference),
+                );
+            }
+        }
+    }
+
+    diags
+}
+
+/// Evaluate and relayout until introspection converges.
+///
+/// Produces one document per source. The introspector sees the pages of all
+/// documents, in order. The sources are evaluated with the introspector of
+/// the previous iteration, so that their code can depend on introspection
+/// results like the final value of a counter. The first iteration uses the
+/// given `introspector`, which is left with the state of the last one.
+///
+/// Convergence is decided for each iteration as a whole: Layout stops once no
+/// page changed or all introspections performed in the iteration still yield
+/// the same results. Otherwise, the next iteration runs the root layout again
+/// and memoization reuses every subtree whose own introspections didn't
+/// change. Which individual queries changed is not tracked.
+///
+/// If a `stream` is given, it receives the index and frame of each page as
+/// soon as the root layouter finished it, in each iteration.
 fn typeset(
     world: Tracked<dyn World + '_>,
     tracer: &mut Tracer,
-    content: &Content,
-) -> SourceResult<Document> {
+    sources: &[Source],
+    introspector: &mut Introspector,
+    mut stream: Option<&mut dyn FnMut(usize, &Frame)>,
+) -> SourceResult<Vec<Document>> {
     let library = world.library();
     let styles = StyleChain::new(&library.styles);
 
     let mut iter = 0;
-    let mut document;
-    let mut introspector = Introspector::new(&[]);
+    let mut documents;
 
     // Relayout until all introspections stabilize.
     // If that doesn't happen within five attempts, we give up.
@@ -124,28 +501,77 @@ fn typeset(
 
         let constraint = <Introspector as Validate>::Constraint::new();
         let mut locator = Locator::new();
-        let mut engine = Engine {
-            world,
-            route: Route::default(),
-            tracer: tracer.track_mut(),
-            locator: &mut locator,
-            introspector: introspector.track_with(&constraint),
-        };
-
-        // Layout!
-        document = content.layout_root(&mut engine, styles)?;
+        documents = Vec::with_capacity(sources.len());
+
+        for source in sources {
+            // Try to evaluate the source file into a module and apply the
+            // embedder's hooks to its content.
+            let module = crate::eval::eval(
+                world,
+                introspector.track_with(&constraint),
+                Route::default().track(),
+                tracer.track_mut(),
+                source,
+            )?;
+            let content = library
+                .hooks
+                .iter()
+                .try_fold(module.content(), |content, hook| hook.apply(content))?;
+
+            // Each document gets its own chained locator so that elements in
+            // later documents are disambiguated from those in earlier ones.
+            let document = {
+                let mut chained = Locator::chained(locator.track());
+                let mut engine = Engine {
+                    world,
+                    route: Route::default(),
+                    tracer: tracer.track_mut(),
+                    locator: &mut chained,
+                    introspector: introspector.track_with(&constraint),
+                };
+
+                // Layout!
+                match &mut stream {
+                    Some(stream) => {
+                        let mut index: usize =
+                            documents.iter().map(|doc| doc.pages.len()).sum();
+                        let mut emit = |page: &Frame| {
+                            stream(index, page);
+                            index += 1;
+                        };
+                        crate::layout::layout_root_streaming(
+                            &content,
+                            &mut engine,
+                            styles,
+                            &mut emit,
+                        )?
+                    }
+                    None => content.layout_root(&mut engine, styles)?,
+                }
+            };
+
+            locator.visit_frames(&document.pages);
+            documents.push(document);
+        }
 
-        introspector = Introspector::new(&document.pages);
+        let pages: Vec<Frame> =
+            documents.iter().flat_map(|doc| doc.pages.iter().cloned()).collect();
+        let counts: Vec<usize> = documents.iter().map(|doc| doc.pages.len()).collect();
+        let mut changed = introspector.update(&pages);
+        changed |= introspector.divide(&counts);
         iter += 1;
 
-        if introspector.validate(&constraint) {
+        // If no page changed, the introspections of this iteration trivially
+        // hold and we can skip re-running them.
+        if !changed || introspector.validate(&constraint) {
             break;
         }
 
         if iter >= 5 {
             tracer.warn(
                 warning!(Span::detached(), "layout did not converge within 5 attempts",)
-                    .with_hint("check if any states or queries are updating themselves"),
+                    .with_hint("check if any states or queries are updating themselves")
+                    .with_category(WarningCategory::Convergence),
             );
             break;
         }
@@ -157,17 +583,83 @@ fn typeset(
         return Err(delayed);
     }
 
-    Ok(document)
+    // References to missing targets fail their show rule. If they still made
+    // it into the document, a show rule deliberately replaced them. They are
+    // only reported if the build asks to fail on them.
+    if tracer.promotes(WarningCategory::UnresolvedReference) {
+        for warning in unresolved(introspector.track(), &labelled(introspector)) {
+            tracer.warn(warning);
+        }
+    }
+
+    // Promote warnings that should be errors.
+    let promoted = tracer.promoted();
+    if !promoted.is_empty() {
+        return Err(promoted);
+    }
+
+    Ok(documents)
 }
 
-/// Deduplicate diagnostics.
-fn deduplicate(mut diags: EcoVec<SourceDiagnostic>) -> EcoVec<SourceDiagnostic> {
+/// How often the same problem must be reported before its diagnostics are
+/// grouped.
+const GROUP_THRESHOLD: usize = 10;
+
+/// How many further spans a group of diagnostics keeps.
+const GROUP_SPANS: usize = 3;
+
+/// Deduplicate diagnostics and group those that are reported very often.
+///
+/// Diagnostics with the same span and message are reported just once. Beyond
+/// that, if the same problem occurs in many places (e.g. because a font is
+/// missing), its diagnostics are merged into the first one, which then keeps a
+/// few representative spans and counts all occurrences.
+pub(crate) fn deduplicate(
+    mut diags: EcoVec<SourceDiagnostic>,
+) -> EcoVec<SourceDiagnostic> {
     let mut unique = HashSet::new();
     diags.retain(|diag| {
         let hash = crate::util::hash128(&(&diag.span, &diag.message));
         unique.insert(hash)
     });
-    diags
+
+    let problem =
+        |diag: &SourceDiagnostic| crate::util::hash128(&(diag.severity, &diag.message));
+
+    let mut counts = HashMap::<u128, usize>::new();
+    for diag in &diags {
+        *counts.entry(problem(diag)).or_default() += 1;
+    }
+
+    if counts.values().all(|&count| count < GROUP_THRESHOLD) {
+        return diags;
+    }
+
+    let mut groups = HashMap::new();
+    let mut grouped = EcoVec::with_capacity(diags.len());
+    for diag in diags {
+        let key = problem(&diag);
+        if counts[&key] < GROUP_THRESHOLD {
+            grouped.push(diag);
+            continue;
+        }
+
+        match groups.get(&key) {
+            Some(&i) => {
+                let group: &mut SourceDiagnostic = &mut grouped.make_mut()[i];
+                group.count += diag.count;
+                if group.repeats.len() < GROUP_SPANS {
+                    group.repeats.push(diag.span);
+                }
+            }
+            None => {
+                groups.insert(key, grouped.len());
+                grouped.push(diag);
+            }
+        }
+    }
+
+    grouped
 }
 
 /// The environment in which typesetting occurs.
@@ -225,6 +717,77 @@ pub trait World {
     fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
         &[]
     }
+
+    /// Notifies the world that a file, package, or font could not be found.
+    ///
+    /// This function is optional to implement. The lookup describes exactly
+    /// which resource the compiler tried to access, so that hosts can fetch
+    /// resources on demand or present more helpful errors than a plain
+    /// diagnostic. It does not change the outcome of the compilation: A
+    /// missing file still results in an error and a missing font is still
+    /// replaced by a fallback. Since the compiler caches its work across
+    /// compilations, a lookup is not necessarily reported again when a
+    /// document is recompiled without changes to the affected part.
+    fn missing(&self, lookup: &Lookup) {
+        let _ = lookup;
+    }
+
+    /// Look up a custom citation style by name.
+    ///
+    /// This function is optional to implement. It lets hosts provide citation
+    /// styles that can't be expressed in CSL. A style returned here can be
+    /// selected with the `style` parameter of `bibliography` and takes
+    /// precedence over a built-in style of the same name.
+    fn citation_style(&self, name: &str) -> Option<CustomStyle> {
+        let _ = name;
+        None
+    }
+
+    /// A store for expensive results that outlives the process.
+    ///
+    /// This function is optional to implement. The compiler loads the results
+    /// of shaping text and of decoding raster images from the store and saves
+    /// them into it, so that a later process can skip that work. Layouted
+    /// frames are not persisted since they reference fonts and images of the
+    /// current process.
+    fn store(&self) -> Option<&dyn Store> {
+        None
+    }
+}
+
+/// Loading methods that report missing resources to the world.
+pub(crate) trait Load {
+    /// Access a file through [`World::file`], reporting it if it is missing.
+    fn load_file(self, id: FileId) -> FileResult<Bytes>;
+
+    /// Access a source file through [`World::source`], reporting it if it is
+    /// missing.
+    fn load_source(self, id: FileId) -> FileResult<Source>;
+}
+
+impl Load for Tracked<'_, dyn World + '_> {
+    fn load_file(self, id: FileId) -> FileResult<Bytes> {
+        let result = self.file(id);
+        if let Err(error) = &result {
+            report(self, id, error);
+        }
+        result
+    }
+
+    fn load_source(self, id: FileId) -> FileResult<Source> {
+        let result = self.source(id);
+        if let Err(error) = &result {
+            report(self, id, error);
+        }
+        result
+    }
+}
+
+/// Report a failed file access to the world if the file is missing.
+fn report(world: Tracked<dyn World + '_>, id: FileId, error: &FileError) {
+    if let Some(lookup) = Lookup::from_file_error(id, error) {
+        world.missing(&lookup);
+    }
 }
 
 /// Helper methods on [`World`] implementations.
@@ -251,6 +814,9 @@ pub struct Library {
     /// The default style properties (for page size, font selection, and
     /// everything else configurable via set and show rules).
     pub styles: Styles,
+    /// Transformations that are applied to the content of each document
+    /// before layout.
+    pub hooks: EcoVec<RealizeHook>,
 }
 
 impl Library {
@@ -258,7 +824,19 @@ impl Library {
     pub fn build() -> Self {
         let math = math::module();
         let global = global(math.clone());
-        Self { global, math, styles: Styles::new() }
+        Self {
+            global,
+            math,
+            styles: Styles::new(),
+            hooks: EcoVec::new(),
+        }
+    }
+
+    /// Set the inputs that documents can access through `sys.inputs`.
+    pub fn set_inputs(&mut self, inputs: Dict) {
+        if let Some(Ok(slot)) = self.global.scope_mut().get_mut("sys") {
+            *slot = foundations::sys::module(inputs).into_value();
+        }
     }
 }
 
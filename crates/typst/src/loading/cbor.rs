@@ -3,8 +3,8 @@ use ecow::{eco_format, EcoString};
 use crate::diag::{At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{func, scope, Bytes, Value};
+use crate::loading::load_data;
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from a CBOR file.
 ///
@@ -22,8 +22,7 @@ pub fn cbor(
     path: Spanned<EcoString>,
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
-    let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = load_data(engine, &path, span)?;
     cbor::decode(Spanned::new(data, span))
 }
 
@@ -1,18 +1,24 @@
 use ecow::{eco_format, EcoString};
 
-use crate::diag::{bail, At, SourceResult};
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::foundations::{cast, func, scope, Array, IntoValue, Value};
-use crate::loading::Readable;
+use crate::foundations::{
+    cast, func, scope, Array, AutoValue, Dict, IntoValue, NoneValue, Str, Type, Value,
+};
+use crate::loading::{load_data, Readable};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from a CSV file.
 ///
 /// The CSV file will be read and parsed into a 2-dimensional array of strings:
 /// Each row in the CSV file will be represented as an array of strings, and all
 /// rows will be collected into a single array. Header rows will not be
-/// stripped.
+/// stripped unless `header` is set to `{true}`. In that case, each row is
+/// represented as a dictionary from the header's column names to the row's
+/// fields.
+///
+/// By default, all fields are strings. With the `types` argument, fields can
+/// also be converted to integers, floats, and booleans.
 ///
 /// # Example
 /// ```example
@@ -35,11 +41,55 @@ pub fn csv(
     #[named]
     #[default]
     delimiter: Delimiter,
+    /// Whether the first row contains the column names. If so, the remaining
+    /// rows are returned as dictionaries.
+    #[named]
+    #[default(false)]
+    header: bool,
+    /// The character that is used to quote fields.
+    /// Must be a single ASCII character.
+    #[named]
+    #[default]
+    quote: Quote,
+    /// The character that is used to escape quotes within quoted fields. If
+    /// `{none}`, quotes are escaped by doubling them.
+    #[named]
+    #[default]
+    escape: Option<Escape>,
+    /// How to convert the fields.
+    ///
+    /// - `{none}`: All fields are kept as strings.
+    /// - `{auto}`: The type of each field is inferred. Fields that look like
+    ///   integers, floats, or booleans are converted accordingly and empty
+    ///   fields become `{none}`.
+    /// - An array of types: The types of the columns in order. Columns beyond
+    ///   the array's length are kept as strings.
+    /// - A dictionary from column names to types: Requires `header` to be
+    ///   `{true}`. Columns that are not in the dictionary are kept as strings.
+    ///
+    /// Supported types are `{str}`, `{int}`, `{float}`, and `{bool}`. Empty
+    /// fields in typed columns become `{none}`.
+    #[named]
+    #[default]
+    types: ColumnTypes,
+    /// Whether rows may have differing numbers of fields. If `header` is
+    /// `{true}`, missing fields become `{none}` and surplus fields are
+    /// dropped.
+    #[named]
+    #[default(false)]
+    ragged: bool,
 ) -> SourceResult<Array> {
     let Spanned { v: path, span } = path;
-    let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
-    self::csv::decode(Spanned::new(Readable::Bytes(data), span), delimiter)
+    let data = load_data(engine, &path, span)?;
+    self::csv::decode(
+        Spanned::new(Readable::Bytes(data), span),
+        delimiter,
+        header,
+        quote,
+        escape,
+        types,
+        ragged,
+    )
 }
 
 #[scope]
@@ -54,13 +104,44 @@ impl csv {
         #[named]
         #[default]
         delimiter: Delimiter,
+        /// Whether the first row contains the column names. See the
+        /// [`csv`]($csv) function for details.
+        #[named]
+        #[default(false)]
+        header: bool,
+        /// The character that is used to quote fields.
+        #[named]
+        #[default]
+        quote: Quote,
+        /// The character that is used to escape quotes within quoted fields.
+        #[named]
+        #[default]
+        escape: Option<Escape>,
+        /// How to convert the fields. See the [`csv`]($csv) function for
+        /// details.
+        #[named]
+        #[default]
+        types: ColumnTypes,
+        /// Whether rows may have differing numbers of fields.
+        #[named]
+        #[default(false)]
+        ragged: bool,
     ) -> SourceResult<Array> {
         let Spanned { v: data, span } = data;
         let mut builder = ::csv::ReaderBuilder::new();
         builder.has_headers(false);
         builder.delimiter(delimiter.0 as u8);
+        builder.quote(quote.0 as u8);
+        builder.escape(escape.map(|escape| escape.0 as u8));
+        builder.double_quote(escape.is_none());
+        builder.flexible(ragged);
         let mut reader = builder.from_reader(data.as_slice());
         let mut array = Array::new();
+        let mut columns: Option<Vec<Str>> = None;
+
+        if !header && matches!(types, ColumnTypes::Named(_)) {
+            bail!(span, "column types can only be given by name if `header` is true");
+        }
 
         for (line, result) in reader.records().enumerate() {
             // Original solution use line from error, but that is incorrect with
@@ -68,12 +149,97 @@ impl csv {
             // https://github.com/BurntSushi/rust-csv/issues/184
             let line = line + 1; // Counting lines from 1
             let row = result.map_err(|err| format_csv_error(err, line)).at(span)?;
-            let sub = row.into_iter().map(|field| field.into_value()).collect();
-            array.push(Value::Array(sub))
+
+            if header && columns.is_none() {
+                columns = Some(row.iter().map(Str::from).collect());
+                continue;
+            }
+
+            let mut fields = row
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let name = columns.as_ref().and_then(|columns| columns.get(i));
+                    types.convert(i, name, field).map_err(|err| {
+                        eco_format!("failed to parse CSV ({err} in line {line})")
+                    })
+                })
+                .collect::<StrResult<Vec<_>>>()
+                .at(span)?;
+
+            let value = match &columns {
+                Some(columns) => {
+                    fields.resize(columns.len(), Value::None);
+                    Value::Dict(columns.iter().cloned().zip(fields).collect())
+                }
+                None => Value::Array(fields.into_iter().collect()),
+            };
+
+            array.push(value);
         }
 
         Ok(array)
     }
+
+    /// Encodes an array of rows into a CSV string.
+    ///
+    /// Each row must be an array of fields. Strings, integers, floats and
+    /// booleans are written as is, `{none}` is written as an empty field.
+    ///
+    /// ```example
+    /// #csv.encode((
+    ///   ("Name", "Age"),
+    ///   ("Alice", 23),
+    /// ))
+    /// ```
+    #[func(title = "Encode CSV")]
+    pub fn encode(
+        /// The rows to encode.
+        rows: Spanned<Array>,
+        /// The delimiter that separates columns in the CSV output.
+        /// Must be a single ASCII character.
+        #[named]
+        #[default]
+        delimiter: Delimiter,
+    ) -> SourceResult<Str> {
+        let Spanned { v: rows, span } = rows;
+        let mut builder = ::csv::WriterBuilder::new();
+        builder.delimiter(delimiter.0 as u8);
+        builder.flexible(true);
+        let mut writer = builder.from_writer(vec![]);
+
+        for row in rows {
+            let Value::Array(row) = row else {
+                bail!(span, "expected array of rows, found {}", row.ty());
+            };
+
+            let fields = row
+                .into_iter()
+                .map(|field| match field {
+                    Value::None => Ok(EcoString::new()),
+                    Value::Str(v) => Ok(v.into()),
+                    Value::Int(v) => Ok(eco_format!("{v}")),
+                    Value::Float(v) => Ok(eco_format!("{v}")),
+                    Value::Bool(v) => Ok(eco_format!("{v}")),
+                    other => bail!(span, "cannot encode {} as CSV field", other.ty()),
+                })
+                .collect::<SourceResult<Vec<_>>>()?;
+
+            writer
+                .write_record(fields.iter().map(|field| field.as_bytes()))
+                .map_err(|err| eco_format!("failed to encode value as CSV ({err})"))
+                .at(span)?;
+        }
+
+        let data = writer
+            .into_inner()
+            .map_err(|err| eco_format!("failed to encode value as CSV ({err})"))
+            .at(span)?;
+        String::from_utf8(data)
+            .map(Into::into)
+            .map_err(|err| eco_format!("failed to encode value as CSV ({err})"))
+            .at(span)
+    }
 }
 
 /// The delimiter to use when parsing CSV files.
@@ -88,21 +254,156 @@ impl Default for Delimiter {
 cast! {
     Delimiter,
     self => self.0.into_value(),
-    v: EcoString => {
-        let mut chars = v.chars();
-        let first = chars.next().ok_or("delimiter must not be empty")?;
-        if chars.next().is_some() {
-            bail!("delimiter must be a single character");
-        }
+    v: EcoString => Self(ascii_char(&v, "delimiter")?),
+}
+
+/// The quote character to use when parsing CSV files.
+pub struct Quote(char);
 
-        if !first.is_ascii() {
-            bail!("delimiter must be an ASCII character");
+impl Default for Quote {
+    fn default() -> Self {
+        Self('"')
+    }
+}
+
+cast! {
+    Quote,
+    self => self.0.into_value(),
+    v: EcoString => Self(ascii_char(&v, "quote")?),
+}
+
+/// The escape character to use when parsing CSV files.
+#[derive(Copy, Clone)]
+pub struct Escape(char);
+
+cast! {
+    Escape,
+    self => self.0.into_value(),
+    v: EcoString => Self(ascii_char(&v, "escape character")?),
+}
+
+/// Extract a single ASCII character from a string.
+fn ascii_char(v: &str, what: &str) -> StrResult<char> {
+    let mut chars = v.chars();
+    let first = chars.next().ok_or_else(|| eco_format!("{what} must not be empty"))?;
+    if chars.next().is_some() {
+        bail!("{what} must be a single character");
+    }
+
+    if !first.is_ascii() {
+        bail!("{what} must be an ASCII character");
+    }
+
+    Ok(first)
+}
+
+/// How to convert the fields of CSV rows.
+#[derive(Default)]
+pub enum ColumnTypes {
+    /// Keep all fields as strings.
+    #[default]
+    Strings,
+    /// Infer the type of each field.
+    Infer,
+    /// The types of the columns in order.
+    Positional(Vec<Type>),
+    /// The types of the columns by name.
+    Named(Dict),
+}
+
+impl ColumnTypes {
+    /// Convert the field in the given column.
+    fn convert(&self, i: usize, name: Option<&Str>, field: &str) -> StrResult<Value> {
+        let ty = match self {
+            Self::Strings => return Ok(field.into_value()),
+            Self::Infer => return Ok(infer_field(field)),
+            Self::Positional(types) => types.get(i).copied(),
+            Self::Named(types) => name
+                .and_then(|name| types.get(name).ok())
+                .map(|ty| ty.clone().cast::<Type>())
+                .transpose()?,
+        };
+
+        let Some(ty) = ty else { return Ok(field.into_value()) };
+        if field.is_empty() && ty != Type::of::<Str>() {
+            return Ok(Value::None);
         }
 
-        Self(first)
+        let parsed = if ty == Type::of::<Str>() {
+            Some(field.into_value())
+        } else if ty == Type::of::<i64>() {
+            field.trim().parse::<i64>().ok().map(Value::Int)
+        } else if ty == Type::of::<f64>() {
+            field.trim().parse::<f64>().ok().map(Value::Float)
+        } else if ty == Type::of::<bool>() {
+            parse_bool(field).map(Value::Bool)
+        } else {
+            None
+        };
+
+        parsed.ok_or_else(|| eco_format!("cannot convert `{field}` to {ty}"))
+    }
+}
+
+cast! {
+    ColumnTypes,
+    self => match self {
+        Self::Strings => Value::None,
+        Self::Infer => Value::Auto,
+        Self::Positional(types) => types.into_value(),
+        Self::Named(types) => types.into_value(),
+    },
+    _: NoneValue => Self::Strings,
+    _: AutoValue => Self::Infer,
+    v: Array => Self::Positional(
+        v.into_iter()
+            .map(|v| v.cast().and_then(check_type))
+            .collect::<StrResult<_>>()?,
+    ),
+    v: Dict => {
+        for (_, ty) in v.iter() {
+            check_type(ty.clone().cast()?)?;
+        }
+        Self::Named(v)
     },
 }
 
+/// Ensure that CSV fields can be converted to the given type.
+fn check_type(ty: Type) -> StrResult<Type> {
+    if [Type::of::<Str>(), Type::of::<i64>(), Type::of::<f64>(), Type::of::<bool>()]
+        .contains(&ty)
+    {
+        Ok(ty)
+    } else {
+        bail!("cannot convert CSV fields to {ty}")
+    }
+}
+
+/// Infer the type of a CSV field.
+fn infer_field(field: &str) -> Value {
+    let trimmed = field.trim();
+    if trimmed.is_empty() {
+        Value::None
+    } else if let Ok(v) = trimmed.parse::<i64>() {
+        Value::Int(v)
+    } else if let Some(v) = trimmed.parse::<f64>().ok().filter(|v| v.is_finite()) {
+        Value::Float(v)
+    } else if let Some(v) = parse_bool(trimmed) {
+        Value::Bool(v)
+    } else {
+        field.into_value()
+    }
+}
+
+/// Parse a boolean CSV field.
+fn parse_bool(field: &str) -> Option<bool> {
+    match field.trim() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
 /// Format the user-facing CSV error message.
 fn format_csv_error(err: ::csv::Error, line: usize) -> EcoString {
     match err.kind() {
@@ -0,0 +1,778 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use ecow::{eco_format, EcoString, EcoVec};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::eval::ops;
+use crate::foundations::{
+    cast, func, repr, scope, ty, Array, Cast, Dict, Func, IntoValue, Repr, Str, Type,
+    Value,
+};
+use crate::loading::{
+    csv, json, load_data, xlsx, ColumnTypes, Delimiter, Quote, Readable, Sheet,
+};
+use crate::syntax::{Span, Spanned};
+use crate::util::hash128;
+
+/// A table of data with named, typed columns.
+///
+/// A data frame stores its data column by column. All values in a column have
+/// the same type or are `{none}`, so a data frame can be filtered, sorted,
+/// grouped, and joined without converting every row into a dictionary. This
+/// makes it much faster than arrays of dictionaries for larger data sets.
+///
+/// Columns with both integers and floats become float columns. Any other mix
+/// of types in a column is an error.
+///
+/// # Example
+/// ```example
+/// #let sales = dataframe((
+///   (region: "North", amount: 120),
+///   (region: "South", amount: 80),
+///   (region: "North", amount: 45),
+/// ))
+///
+/// #let totals = sales
+///   .group-by("region", aggregate: (amount: "sum"))
+///   .sort("amount", reverse: true)
+///
+/// #table(
+///   columns: 2,
+///   ..totals.columns(),
+///   ..totals.rows().map(row => row.values().map(str)).flatten(),
+/// )
+/// ```
+#[ty(scope, name = "dataframe", title = "Data Frame")]
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct DataFrame {
+    /// The columns, which all have `len` values.
+    columns: EcoVec<Column>,
+    /// The number of rows.
+    len: usize,
+}
+
+/// A named column in a data frame.
+#[derive(Debug, Clone, PartialEq, Hash)]
+struct Column {
+    /// The name of the column.
+    name: Str,
+    /// The type of the values or `None` if all values are `none`.
+    ty: Option<Type>,
+    /// The values, which are either of the column's type or `none`.
+    values: EcoVec<Value>,
+}
+
+impl DataFrame {
+    /// Create a data frame from rows given as dictionaries.
+    ///
+    /// The columns are ordered by their first appearance and fields that are
+    /// missing in a row become `none`.
+    pub fn from_rows(rows: Array) -> StrResult<Self> {
+        let mut names = Vec::<Str>::new();
+        let mut indices = HashMap::<Str, usize>::new();
+        let mut columns = Vec::<EcoVec<Value>>::new();
+        let len = rows.len();
+
+        for (i, row) in rows.into_iter().enumerate() {
+            let Value::Dict(row) = row else {
+                bail!("expected dictionary as row, found {}", row.ty());
+            };
+
+            for (name, value) in row {
+                let k = *indices.entry(name.clone()).or_insert_with(|| {
+                    names.push(name);
+                    columns.push(EcoVec::new());
+                    columns.len() - 1
+                });
+                let column = &mut columns[k];
+                column.extend((column.len()..i).map(|_| Value::None));
+                column.push(value);
+            }
+        }
+
+        for column in &mut columns {
+            column.extend((column.len()..len).map(|_| Value::None));
+        }
+
+        Self::from_columns(names.into_iter().zip(columns))
+    }
+
+    /// Create a data frame from named columns.
+    pub fn from_columns(
+        columns: impl IntoIterator<Item = (Str, EcoVec<Value>)>,
+    ) -> StrResult<Self> {
+        let mut len = None;
+        let columns = columns
+            .into_iter()
+            .map(|(name, values)| {
+                let expected = *len.get_or_insert(values.len());
+                if values.len() != expected {
+                    bail!(
+                        "column `{name}` has {} values, but other columns have {expected}",
+                        values.len(),
+                    );
+                }
+                Column::new(name, values)
+            })
+            .collect::<StrResult<_>>()?;
+        Ok(Self { columns, len: len.unwrap_or(0) })
+    }
+
+    /// Create a data frame from a decoded value.
+    fn from_value(value: Value) -> StrResult<Self> {
+        match value {
+            Value::Array(rows) => Self::from_rows(rows),
+            Value::Dict(columns) => Self::from_columns(
+                columns
+                    .into_iter()
+                    .map(|(name, values)| {
+                        Ok((name, values.cast::<Array>()?.into_iter().collect()))
+                    })
+                    .collect::<StrResult<Vec<_>>>()?,
+            ),
+            v => {
+                bail!("expected array of rows or dictionary of columns, found {}", v.ty())
+            }
+        }
+    }
+
+    /// Whether the data frame has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The column with the given name.
+    fn find(&self, name: &str) -> StrResult<&Column> {
+        self.columns
+            .iter()
+            .find(|column| column.name == name)
+            .ok_or_else(|| eco_format!("data frame has no column `{name}`"))
+    }
+
+    /// The row at the given index as a dictionary.
+    fn row(&self, i: usize) -> Dict {
+        self.columns
+            .iter()
+            .map(|column| (column.name.clone(), column.values[i].clone()))
+            .collect()
+    }
+
+    /// A data frame with the rows at the given indices.
+    fn take(&self, indices: &[usize]) -> Self {
+        let columns = self.columns.iter().map(|column| column.take(indices)).collect();
+        Self { columns, len: indices.len() }
+    }
+
+    /// The key of each row for the given key columns.
+    ///
+    /// Rows with `none` in a key column have no key.
+    fn keys(&self, names: &[Str]) -> StrResult<Vec<Option<u128>>> {
+        let columns = names
+            .iter()
+            .map(|name| self.find(name))
+            .collect::<StrResult<Vec<_>>>()?;
+        Ok((0..self.len)
+            .map(|i| {
+                let key =
+                    columns.iter().map(|column| &column.values[i]).collect::<Vec<_>>();
+                (!key.iter().any(|value| matches!(value, Value::None)))
+                    .then(|| hash128(&key))
+            })
+            .collect())
+    }
+}
+
+impl Column {
+    /// Create a column and determine its type.
+    fn new(name: Str, mut values: EcoVec<Value>) -> StrResult<Self> {
+        let int = Type::of::<i64>();
+        let float = Type::of::<f64>();
+
+        let mut ty = None;
+        for value in values.iter().filter(|value| !matches!(value, Value::None)) {
+            let other = value.ty();
+            ty = match ty {
+                None => Some(other),
+                Some(ty) if ty == other => Some(ty),
+                Some(ty)
+                    if [ty, other] == [int, float] || [ty, other] == [float, int] =>
+                {
+                    Some(float)
+                }
+                Some(ty) => bail!("column `{name}` contains both {ty} and {other}"),
+            };
+        }
+
+        if ty == Some(float) {
+            for value in values.make_mut() {
+                if let Value::Int(v) = *value {
+                    *value = Value::Float(v as f64);
+                }
+            }
+        }
+
+        Ok(Self { name, ty, values })
+    }
+
+    /// A column with the values at the given indices.
+    fn take(&self, indices: &[usize]) -> Self {
+        Self {
+            name: self.name.clone(),
+            ty: self.ty,
+            values: indices.iter().map(|&i| self.values[i].clone()).collect(),
+        }
+    }
+
+    /// A column with the values at the given indices, where missing indices
+    /// produce `none`.
+    fn take_or_none(&self, indices: &[Option<usize>]) -> Self {
+        Self {
+            name: self.name.clone(),
+            ty: self.ty,
+            values: indices
+                .iter()
+                .map(|i| i.map_or(Value::None, |i| self.values[i].clone()))
+                .collect(),
+        }
+    }
+
+    /// Aggregate the values at the given indices.
+    fn aggregate(&self, op: Aggregate, indices: &[usize]) -> StrResult<Value> {
+        let mut values = indices
+            .iter()
+            .map(|&i| &self.values[i])
+            .filter(|value| !matches!(value, Value::None))
+            .cloned();
+
+        if let (Aggregate::Sum | Aggregate::Mean, Some(ty)) = (op, self.ty) {
+            if ty != Type::of::<i64>() && ty != Type::of::<f64>() {
+                let what = if op == Aggregate::Sum { "sum" } else { "mean" };
+                bail!("cannot compute the {what} of column `{}` of type {ty}", self.name);
+            }
+        }
+
+        Ok(match op {
+            Aggregate::Count => values.count().into_value(),
+            Aggregate::Sum => values.try_fold(Value::Int(0), ops::add)?,
+            Aggregate::Mean => {
+                let count = values.clone().count();
+                if count == 0 {
+                    return Ok(Value::None);
+                }
+                let sum = values.try_fold(Value::Int(0), ops::add)?;
+                ops::div(sum, Value::Int(count as i64))?
+            }
+            Aggregate::Min | Aggregate::Max => {
+                let mut best: Option<Value> = None;
+                for value in values {
+                    let ordering = match &best {
+                        Some(best) => ops::compare(&value, best)?,
+                        None => Ordering::Equal,
+                    };
+                    let better = match op {
+                        Aggregate::Min => ordering == Ordering::Less,
+                        _ => ordering == Ordering::Greater,
+                    };
+                    if best.is_none() || better {
+                        best = Some(value);
+                    }
+                }
+                best.unwrap_or(Value::None)
+            }
+            Aggregate::First => values.next().unwrap_or(Value::None),
+            Aggregate::Last => values.last().unwrap_or(Value::None),
+        })
+    }
+}
+
+#[scope]
+impl DataFrame {
+    /// Creates a data frame from rows or columns.
+    ///
+    /// ```example
+    /// #let people = dataframe((
+    ///   name: ("Ana", "Ben"),
+    ///   age: (31, 27),
+    /// ))
+    /// #people.len() rows
+    /// ```
+    #[func(constructor)]
+    pub fn construct(
+        /// The data, either as an array of rows that are dictionaries or as a
+        /// dictionary from column names to arrays of values.
+        data: Spanned<Value>,
+    ) -> SourceResult<DataFrame> {
+        Self::from_value(data.v).at(data.span)
+    }
+
+    /// Reads a data frame from a CSV file whose first row contains the column
+    /// names.
+    ///
+    /// The types of the fields are inferred unless `types` is given. See the
+    /// [`csv`]($csv) function for details.
+    #[func(title = "Data Frame from CSV")]
+    pub fn csv(
+        /// The engine.
+        engine: &mut Engine,
+        /// Path to a CSV file.
+        path: Spanned<EcoString>,
+        /// The delimiter that separates columns in the CSV file.
+        #[named]
+        #[default]
+        delimiter: Delimiter,
+        /// How to convert the fields.
+        #[named]
+        #[default(ColumnTypes::Infer)]
+        types: ColumnTypes,
+    ) -> SourceResult<DataFrame> {
+        let Spanned { v: path, span } = path;
+        let data = load_data(engine, &path, span)?;
+        let rows = csv::decode(
+            Spanned::new(Readable::Bytes(data), span),
+            delimiter,
+            true,
+            Quote::default(),
+            None,
+            types,
+            false,
+        )?;
+        Self::from_rows(rows).at(span)
+    }
+
+    /// Reads a data frame from a JSON file that contains an array of objects
+    /// or an object of arrays.
+    #[func(title = "Data Frame from JSON")]
+    pub fn json(
+        /// The engine.
+        engine: &mut Engine,
+        /// Path to a JSON file.
+        path: Spanned<EcoString>,
+    ) -> SourceResult<DataFrame> {
+        let Spanned { v: path, span } = path;
+        let data = load_data(engine, &path, span)?;
+        let data = Spanned::new(Readable::Bytes(data), span);
+        let value = json::decode(engine, data, false, false, false)?;
+        Self::from_value(value).at(span)
+    }
+
+    /// Reads a data frame from a sheet of an Excel file whose first row
+    /// contains the column names.
+    #[func(title = "Data Frame from XLSX")]
+    pub fn xlsx(
+        /// The engine.
+        engine: &mut Engine,
+        /// Path to an XLSX file.
+        path: Spanned<EcoString>,
+        /// The sheet to read. See the [`xlsx`]($xlsx) function for details.
+        #[named]
+        #[default]
+        sheet: Option<Sheet>,
+    ) -> SourceResult<DataFrame> {
+        let Spanned { v: path, span } = path;
+        let data = load_data(engine, &path, span)?;
+        let rows = xlsx::decode(Spanned::new(data, span), sheet, true)?;
+        Self::from_rows(rows).at(span)
+    }
+
+    /// The number of rows.
+    #[func(title = "Length")]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The names of the columns.
+    #[func]
+    pub fn columns(&self) -> Array {
+        self.columns
+            .iter()
+            .map(|column| column.name.clone().into_value())
+            .collect()
+    }
+
+    /// The types of the columns as a dictionary from column names to types.
+    /// Columns that only contain `{none}` have the type `{none}`.
+    #[func]
+    pub fn types(&self) -> Dict {
+        self.columns
+            .iter()
+            .map(|column| (column.name.clone(), column.ty.into_value()))
+            .collect()
+    }
+
+    /// The values of a column.
+    #[func]
+    pub fn column(
+        &self,
+        /// The name of the column.
+        name: Str,
+    ) -> StrResult<Array> {
+        Ok(self.find(&name)?.values.iter().cloned().collect())
+    }
+
+    /// The rows as dictionaries from column names to values.
+    #[func]
+    pub fn rows(&self) -> Array {
+        (0..self.len).map(|i| self.row(i).into_value()).collect()
+    }
+
+    /// Returns the row at the given index as a dictionary. Errors if the index
+    /// is out of bounds.
+    #[func]
+    pub fn at(
+        &self,
+        /// The index at which to retrieve the row. If negative, indexes from
+        /// the back.
+        index: i64,
+    ) -> StrResult<Dict> {
+        index
+            .checked_add(if index < 0 { self.len as i64 } else { 0 })
+            .and_then(|i| usize::try_from(i).ok())
+            .filter(|&i| i < self.len)
+            .map(|i| self.row(i))
+            .ok_or_else(|| {
+                eco_format!("row index out of bounds (index: {index}, len: {})", self.len)
+            })
+    }
+
+    /// Produces a data frame with only the given columns, in the given order.
+    #[func]
+    pub fn select(
+        &self,
+        /// The names of the columns to keep.
+        #[variadic]
+        names: Vec<Str>,
+    ) -> StrResult<DataFrame> {
+        let columns = names
+            .iter()
+            .map(|name| self.find(name).cloned())
+            .collect::<StrResult<_>>()?;
+        Ok(Self { columns, len: self.len })
+    }
+
+    /// Produces a data frame with only the rows for which the given function
+    /// returns `{true}`.
+    ///
+    /// ```example
+    /// #let people = dataframe((
+    ///   name: ("Ana", "Ben", "Cleo"),
+    ///   age: (31, 27, 45),
+    /// ))
+    /// #people.filter(row => row.age > 30).column("name")
+    /// ```
+    #[func]
+    pub fn filter(
+        &self,
+        /// The engine.
+        engine: &mut Engine,
+        /// The function to apply to each row, given as a dictionary. Must
+        /// return a boolean.
+        test: Func,
+    ) -> SourceResult<DataFrame> {
+        let mut kept = vec![];
+        for i in 0..self.len {
+            if test.call(engine, [self.row(i)])?.cast::<bool>().at(test.span())? {
+                kept.push(i);
+            }
+        }
+        Ok(self.take(&kept))
+    }
+
+    /// Produces a data frame with the rows sorted by the given columns.
+    ///
+    /// Rows are compared by the first column and ties are broken by the
+    /// following ones. Values of `{none}` come last. The sort is stable, so
+    /// rows that compare equal keep their order.
+    #[func]
+    pub fn sort(
+        &self,
+        /// The callsite span.
+        span: Span,
+        /// The names of the columns to sort by.
+        #[variadic]
+        by: Vec<Str>,
+        /// Whether to sort in descending order.
+        #[named]
+        #[default(false)]
+        reverse: bool,
+    ) -> SourceResult<DataFrame> {
+        if by.is_empty() {
+            bail!(span, "expected at least one column to sort by");
+        }
+
+        let columns = by
+            .iter()
+            .map(|name| self.find(name))
+            .collect::<StrResult<Vec<_>>>()
+            .at(span)?;
+
+        let mut result = Ok(());
+        let mut indices = (0..self.len).collect::<Vec<_>>();
+        indices.sort_by(|&a, &b| {
+            for column in &columns {
+                let ordering = match (&column.values[a], &column.values[b]) {
+                    (Value::None, Value::None) => Ordering::Equal,
+                    (Value::None, _) => Ordering::Greater,
+                    (_, Value::None) => Ordering::Less,
+                    (a, b) => {
+                        let ordering = ops::compare(a, b).unwrap_or_else(|err| {
+                            if result.is_ok() {
+                                result = Err(err).at(span);
+                            }
+                            Ordering::Equal
+                        });
+                        if reverse {
+                            ordering.reverse()
+                        } else {
+                            ordering
+                        }
+                    }
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            Ordering::Equal
+        });
+
+        result.map(|_| self.take(&indices))
+    }
+
+    /// Groups the rows by the values in the given columns and aggregates the
+    /// other columns.
+    ///
+    /// The result has one row per distinct combination of values in the key
+    /// columns, in the order of their first appearance. Rows with `{none}` in
+    /// a key column form their own groups.
+    ///
+    /// ```example
+    /// #let sales = dataframe((
+    ///   region: ("North", "South", "North"),
+    ///   amount: (120, 80, 45),
+    /// ))
+    /// #sales
+    ///   .group-by("region", aggregate: (amount: "mean"))
+    ///   .rows()
+    /// ```
+    #[func]
+    pub fn group_by(
+        &self,
+        /// The callsite span.
+        span: Span,
+        /// The names of the key columns.
+        #[variadic]
+        keys: Vec<Str>,
+        /// How to aggregate other columns, as a dictionary from column names
+        /// to one of the following:
+        ///
+        /// - `{"count"}`: The number of values that are not `{none}`.
+        /// - `{"sum"}`: The sum of the values.
+        /// - `{"mean"}`: The mean of the values.
+        /// - `{"min"}`: The smallest value.
+        /// - `{"max"}`: The largest value.
+        /// - `{"first"}`: The first value.
+        /// - `{"last"}`: The last value.
+        ///
+        /// Values of `{none}` are skipped. Key columns cannot be aggregated.
+        #[named]
+        #[default]
+        aggregate: Dict,
+    ) -> SourceResult<DataFrame> {
+        let key_columns = keys
+            .iter()
+            .map(|name| self.find(name))
+            .collect::<StrResult<Vec<_>>>()
+            .at(span)?;
+
+        let mut groups = Vec::<Vec<usize>>::new();
+        let mut lookup = HashMap::<u128, usize>::new();
+        for i in 0..self.len {
+            let key =
+                key_columns.iter().map(|column| &column.values[i]).collect::<Vec<_>>();
+            let g = *lookup.entry(hash128(&key)).or_insert_with(|| {
+                groups.push(vec![]);
+                groups.len() - 1
+            });
+            groups[g].push(i);
+        }
+
+        let firsts = groups.iter().map(|group| group[0]).collect::<Vec<_>>();
+        let mut columns = key_columns
+            .iter()
+            .map(|column| column.take(&firsts))
+            .collect::<EcoVec<_>>();
+
+        for (name, op) in aggregate {
+            if keys.contains(&name) {
+                bail!(span, "cannot aggregate key column `{name}`");
+            }
+
+            let op = op.cast::<Aggregate>().at(span)?;
+            let column = self.find(&name).at(span)?;
+            let values = groups
+                .iter()
+                .map(|group| column.aggregate(op, group))
+                .collect::<StrResult<_>>()
+                .at(span)?;
+            columns.push(Column::new(name, values).at(span)?);
+        }
+
+        Ok(Self { columns, len: groups.len() })
+    }
+
+    /// Combines the rows of two data frames whose values in the given key
+    /// columns are equal.
+    ///
+    /// The result has the columns of this data frame followed by the other
+    /// data frame's columns, except for its key columns. Rows with `{none}`
+    /// in a key column never match.
+    ///
+    /// ```example
+    /// #let orders = dataframe((
+    ///   customer: (1, 2, 1),
+    ///   total: (30, 12, 8),
+    /// ))
+    /// #let customers = dataframe((
+    ///   customer: (1, 2),
+    ///   name: ("Ana", "Ben"),
+    /// ))
+    /// #orders.join(customers, "customer").column("name")
+    /// ```
+    #[func]
+    pub fn join(
+        &self,
+        /// The callsite span.
+        span: Span,
+        /// The data frame to join with.
+        other: DataFrame,
+        /// The name of the key column or an array of names.
+        on: JoinKeys,
+        /// Which rows to keep.
+        #[named]
+        #[default]
+        how: JoinKind,
+    ) -> SourceResult<DataFrame> {
+        let on = on.0;
+        for name in &on {
+            let (left, right) = (self.find(name).at(span)?, other.find(name).at(span)?);
+            if let (Some(a), Some(b)) = (left.ty, right.ty) {
+                if a != b {
+                    bail!(
+                        span,
+                        "key column `{name}` is {a} on the left but {b} on the right"
+                    );
+                }
+            }
+        }
+
+        let rest = other
+            .columns
+            .iter()
+            .filter(|column| !on.contains(&column.name))
+            .collect::<Vec<_>>();
+        for column in &rest {
+            if self.find(&column.name).is_ok() {
+                bail!(span, "column `{}` exists in both data frames", column.name);
+            }
+        }
+
+        let mut lookup = HashMap::<u128, Vec<usize>>::new();
+        for (i, key) in other.keys(&on).at(span)?.into_iter().enumerate() {
+            if let Some(key) = key {
+                lookup.entry(key).or_default().push(i);
+            }
+        }
+
+        let mut left = vec![];
+        let mut right = vec![];
+        for (i, key) in self.keys(&on).at(span)?.into_iter().enumerate() {
+            match key.and_then(|key| lookup.get(&key)) {
+                Some(matches) => {
+                    for &j in matches {
+                        left.push(i);
+                        right.push(Some(j));
+                    }
+                }
+                None if how == JoinKind::Left => {
+                    left.push(i);
+                    right.push(None);
+                }
+                None => {}
+            }
+        }
+
+        let mut joined = self.take(&left);
+        joined
+            .columns
+            .extend(rest.into_iter().map(|column| column.take_or_none(&right)));
+        Ok(joined)
+    }
+}
+
+impl Repr for DataFrame {
+    fn repr(&self) -> EcoString {
+        eco_format!(
+            "dataframe(rows: {}, columns: {})",
+            self.len,
+            repr::pretty_array_like(
+                &self
+                    .columns
+                    .iter()
+                    .map(|column| column.name.repr())
+                    .collect::<Vec<_>>(),
+                false,
+            )
+        )
+    }
+}
+
+cast! {
+    type DataFrame,
+}
+
+/// How to aggregate a column in a group.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+enum Aggregate {
+    /// The number of values.
+    Count,
+    /// The sum of the values.
+    Sum,
+    /// The mean of the values.
+    Mean,
+    /// The smallest value.
+    Min,
+    /// The largest value.
+    Max,
+    /// The first value.
+    First,
+    /// The last value.
+    Last,
+}
+
+/// The key columns of a join.
+pub struct JoinKeys(Vec<Str>);
+
+cast! {
+    JoinKeys,
+    self => self.0.into_value(),
+    v: Str => Self(vec![v]),
+    v: Array => {
+        let names = v.into_iter().map(Value::cast).collect::<StrResult<Vec<_>>>()?;
+        if names.is_empty() {
+            bail!("expected at least one key column");
+        }
+        Self(names)
+    },
+}
+
+/// Which rows a join keeps.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum JoinKind {
+    /// Only rows with a match in both data frames.
+    #[default]
+    Inner,
+    /// All rows of the left data frame. Columns from the right data frame are
+    /// `{none}` where there is no match.
+    Left,
+}
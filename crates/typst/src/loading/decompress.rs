@@ -0,0 +1,110 @@
+use std::io::Read;
+
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::foundations::{func, Bytes, Cast, Smart};
+use crate::syntax::Spanned;
+
+/// Decompresses bytes.
+///
+/// Supports the gzip, zlib, and Zstandard formats. By default, the format is
+/// detected automatically from the data.
+///
+/// Data loading functions like [`csv`]($csv) and [`json`]($json) also
+/// transparently decompress files whose names end in `.gz`, `.zz`, or `.zst`,
+/// so you can directly load `{"data.csv.gz"}`.
+///
+/// # Example
+/// ```example
+/// #let data = read("data.csv.gz", encoding: none)
+/// #csv.decode(decompress(data))
+/// ```
+#[func]
+pub fn decompress(
+    /// The compressed data.
+    data: Spanned<Bytes>,
+    /// The compression format of the data.
+    #[named]
+    #[default]
+    format: Smart<Compression>,
+) -> SourceResult<Bytes> {
+    let Spanned { v: data, span } = data;
+    let format = match format {
+        Smart::Custom(format) => format,
+        Smart::Auto => Compression::detect(&data)
+            .ok_or("failed to detect compression format")
+            .at(span)?,
+    };
+    format.decompress(&data).at(span)
+}
+
+/// A compression format.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Compression {
+    /// The gzip format.
+    Gzip,
+    /// The zlib format.
+    Zlib,
+    /// The Zstandard format.
+    Zstd,
+}
+
+impl Compression {
+    /// Determine the compression format from a file path's extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, ext) = path.rsplit_once('.')?;
+        Some(match ext.to_lowercase().as_str() {
+            "gz" => Self::Gzip,
+            "zz" => Self::Zlib,
+            "zst" => Self::Zstd,
+            _ => return None,
+        })
+    }
+
+    /// Detect the compression format from the data's magic bytes.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        match data {
+            [0x1f, 0x8b, ..] => Some(Self::Gzip),
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Self::Zstd),
+            [cmf, flg, ..]
+                if cmf & 0x0f == 8
+                    && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0 =>
+            {
+                Some(Self::Zlib)
+            }
+            _ => None,
+        }
+    }
+
+    /// The name of the format.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zlib => "zlib",
+            Self::Zstd => "Zstandard",
+        }
+    }
+
+    /// Decompress data in this format.
+    pub fn decompress(self, data: &[u8]) -> StrResult<Bytes> {
+        let mut buf = vec![];
+        let result = match self {
+            Self::Gzip => flate2::read::GzDecoder::new(data).read_to_end(&mut buf),
+            Self::Zlib => flate2::read::ZlibDecoder::new(data).read_to_end(&mut buf),
+            Self::Zstd => match ruzstd::StreamingDecoder::new(data) {
+                Ok(mut decoder) => decoder.read_to_end(&mut buf),
+                Err(err) => bail!("failed to decompress {} data ({err})", self.name()),
+            },
+        };
+
+        result
+            .map(|_| buf.into())
+            .map_err(|err| format_decompress_error(self, err))
+    }
+}
+
+/// Format the user-facing decompression error message.
+fn format_decompress_error(format: Compression, error: std::io::Error) -> EcoString {
+    eco_format!("failed to decompress {} data ({error})", format.name())
+}
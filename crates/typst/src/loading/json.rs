@@ -2,10 +2,9 @@ use ecow::{eco_format, EcoString};
 
 use crate::diag::{At, SourceResult};
 use crate::engine::Engine;
-use crate::foundations::{func, scope, Str, Value};
-use crate::loading::Readable;
+use crate::foundations::{func, scope, Str, Typed, Value};
+use crate::loading::{load_data, parse_datetimes, Readable};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from a JSON file.
 ///
@@ -17,6 +16,10 @@ use crate::World;
 ///
 /// The function returns a dictionary or an array, depending on the JSON file.
 ///
+/// Configuration files often use a relaxed flavor of JSON with comments and
+/// trailing commas (sometimes called JSONC). Such files can be loaded by
+/// setting `relaxed` to `{true}`.
+///
 /// The JSON files in the example contain objects with the keys `temperature`,
 /// `unit`, and `weather`.
 ///
@@ -50,11 +53,28 @@ pub fn json(
     engine: &mut Engine,
     /// Path to a JSON file.
     path: Spanned<EcoString>,
+    /// Whether to convert strings containing ISO 8601 dates and times, like
+    /// `{"2023-05-01"}` or `{"2023-05-01T12:30:00Z"}`, to
+    /// [datetimes]($datetime). Fractional seconds and UTC offsets are
+    /// discarded.
+    #[named]
+    #[default(false)]
+    datetimes: bool,
+    /// Whether to allow line comments (`//`), block comments (`/* */`), and
+    /// trailing commas in arrays and objects.
+    #[named]
+    #[default(false)]
+    relaxed: bool,
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
-    let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
-    json::decode(Spanned::new(Readable::Bytes(data), span))
+    let data = load_data(engine, &path, span)?;
+    json::decode(
+        engine,
+        Spanned::new(Readable::Bytes(data), span),
+        datetimes,
+        relaxed,
+        false,
+    )
 }
 
 #[scope]
@@ -62,13 +82,44 @@ impl json {
     /// Reads structured data from a JSON string/bytes.
     #[func(title = "Decode JSON")]
     pub fn decode(
+        /// The engine.
+        engine: &mut Engine,
         /// JSON data.
         data: Spanned<Readable>,
+        /// Whether to convert strings containing ISO 8601 dates and times to
+        /// datetimes. See the [`json`]($json) function for details.
+        #[named]
+        #[default(false)]
+        datetimes: bool,
+        /// Whether to allow comments and trailing commas.
+        #[named]
+        #[default(false)]
+        relaxed: bool,
+        /// Whether the data is in the typed serialization written by
+        /// [`json.encode`]($json.encode) with `typed` set to `{true}`. The
+        /// encoded values are restored with their original types.
+        #[named]
+        #[default(false)]
+        typed: bool,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        serde_json::from_slice(data.as_slice())
+        let relaxed_data;
+        let slice = if relaxed {
+            relaxed_data = strip_relaxed(data.as_slice());
+            &relaxed_data
+        } else {
+            data.as_slice()
+        };
+
+        let value = serde_json::from_slice(slice)
             .map_err(|err| eco_format!("failed to parse JSON ({err})"))
-            .at(span)
+            .at(span)?;
+        let value = if datetimes { parse_datetimes(value) } else { value };
+        if typed {
+            Typed::decode(engine, value, span)
+        } else {
+            Ok(value)
+        }
     }
 
     /// Encodes structured data into a JSON string.
@@ -80,15 +131,79 @@ impl json {
         #[named]
         #[default(true)]
         pretty: bool,
+        /// Whether to preserve the types of the encoded values.
+        ///
+        /// By default, values that have no counterpart in JSON, like lengths
+        /// or content, are encoded as strings holding their representation.
+        /// With `typed` set to `{true}`, they are instead encoded as objects
+        /// whose `type` key names their type, and dictionaries are wrapped so
+        /// that their own keys can't be mistaken for one. Such JSON can be
+        /// restored with [`json.decode`]($json.decode) by setting `typed` to
+        /// `{true}` there, too.
+        #[named]
+        #[default(false)]
+        typed: bool,
     ) -> SourceResult<Str> {
         let Spanned { v: value, span } = value;
-        if pretty {
-            serde_json::to_string_pretty(&value)
-        } else {
-            serde_json::to_string(&value)
+        match (pretty, typed) {
+            (true, true) => serde_json::to_string_pretty(&Typed(&value)),
+            (true, false) => serde_json::to_string_pretty(&value),
+            (false, true) => serde_json::to_string(&Typed(&value)),
+            (false, false) => serde_json::to_string(&value),
         }
         .map(|v| v.into())
         .map_err(|err| eco_format!("failed to encode value as JSON ({err})"))
         .at(span)
     }
 }
+
+/// Blank out comments and trailing commas in relaxed JSON.
+///
+/// Removed characters are replaced by spaces so that line and column numbers
+/// in error messages stay accurate.
+fn strip_relaxed(data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    let mut i = 0;
+    let mut last_comma = None;
+    while i < out.len() {
+        match out[i] {
+            b'"' => {
+                last_comma = None;
+                i += 1;
+                while i < out.len() && out[i] != b'"' {
+                    i += if out[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'/' if out.get(i + 1) == Some(&b'/') => {
+                while i < out.len() && out[i] != b'\n' {
+                    out[i] = b' ';
+                    i += 1;
+                }
+                continue;
+            }
+            b'/' if out.get(i + 1) == Some(&b'*') => {
+                let end = out[i + 2..]
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map_or(out.len(), |p| i + 2 + p + 2);
+                for c in &mut out[i..end] {
+                    if *c != b'\n' {
+                        *c = b' ';
+                    }
+                }
+                i = end;
+                continue;
+            }
+            b',' => last_comma = Some(i),
+            b']' | b'}' => {
+                if let Some(comma) = last_comma.take() {
+                    out[comma] = b' ';
+                }
+            }
+            c if c.is_ascii_whitespace() => {}
+            _ => last_comma = None,
+        }
+        i += 1;
+    }
+    out
+}
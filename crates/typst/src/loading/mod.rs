@@ -4,12 +4,19 @@
 mod cbor_;
 #[path = "csv.rs"]
 mod csv_;
+mod dataframe;
+#[path = "decompress.rs"]
+mod decompress_;
 #[path = "json.rs"]
 mod json_;
+#[path = "msgpack.rs"]
+mod msgpack_;
 #[path = "read.rs"]
 mod read_;
 #[path = "toml.rs"]
 mod toml_;
+#[path = "xlsx.rs"]
+mod xlsx_;
 #[path = "xml.rs"]
 mod xml_;
 #[path = "yaml.rs"]
@@ -17,13 +24,23 @@ mod yaml_;
 
 pub use self::cbor_::*;
 pub use self::csv_::*;
+pub use self::dataframe::*;
+pub use self::decompress_::*;
 pub use self::json_::*;
+pub use self::msgpack_::*;
 pub use self::read_::*;
 pub use self::toml_::*;
+pub use self::xlsx_::*;
 pub use self::xml_::*;
 pub use self::yaml_::*;
 
-use crate::foundations::{cast, category, Bytes, Category, Scope, Str};
+use crate::diag::{At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, category, Bytes, Category, Datetime, IntoValue, Scope, Str, Value,
+};
+use crate::syntax::Span;
+use crate::Load;
 
 /// Data loading from external files.
 ///
@@ -41,7 +58,11 @@ pub(super) fn define(global: &mut Scope) {
     global.define_func::<toml>();
     global.define_func::<yaml>();
     global.define_func::<cbor>();
+    global.define_func::<msgpack>();
     global.define_func::<xml>();
+    global.define_func::<xlsx>();
+    global.define_func::<decompress>();
+    global.define_type::<DataFrame>();
 }
 
 /// A value that can be read from a file.
@@ -80,3 +101,36 @@ impl From<Readable> for Bytes {
         }
     }
 }
+
+/// Load the data file at the given path.
+///
+/// Files with a `.gz`, `.zz`, or `.zst` extension are transparently
+/// decompressed.
+fn load_data(engine: &mut Engine, path: &str, span: Span) -> SourceResult<Bytes> {
+    let id = span.resolve_path(path).at(span)?;
+    let data = engine.world.load_file(id).at(span)?;
+    match Compression::from_path(path) {
+        Some(format) => format.decompress(&data).at(span),
+        None => Ok(data),
+    }
+}
+
+/// Convert all strings in a loaded value that contain ISO 8601 dates and times
+/// to datetimes.
+fn parse_datetimes(value: Value) -> Value {
+    match value {
+        Value::Str(string) => match Datetime::from_iso(&string) {
+            Some(datetime) => datetime.into_value(),
+            None => Value::Str(string),
+        },
+        Value::Array(array) => {
+            Value::Array(array.into_iter().map(parse_datetimes).collect())
+        }
+        Value::Dict(dict) => Value::Dict(
+            dict.into_iter()
+                .map(|(key, value)| (key, parse_datetimes(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
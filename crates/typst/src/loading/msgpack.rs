@@ -0,0 +1,54 @@
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{func, scope, Bytes, Value};
+use crate::loading::load_data;
+use crate::syntax::Spanned;
+
+/// Reads structured data from a MessagePack file.
+///
+/// The file must contain a valid MessagePack serialization. Maps will be
+/// converted into Typst dictionaries, and arrays will be converted into Typst
+/// arrays. Strings, binary data and booleans will be converted into the Typst
+/// equivalents, nil-values will be converted into `{none}`, and numbers will
+/// be converted to floats or integers depending on their encoding.
+#[func(scope, title = "MessagePack")]
+pub fn msgpack(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to a MessagePack file.
+    path: Spanned<EcoString>,
+) -> SourceResult<Value> {
+    let Spanned { v: path, span } = path;
+    let data = load_data(engine, &path, span)?;
+    msgpack::decode(Spanned::new(data, span))
+}
+
+#[scope]
+impl msgpack {
+    /// Reads structured data from MessagePack bytes.
+    #[func(title = "Decode MessagePack")]
+    pub fn decode(
+        /// MessagePack data.
+        data: Spanned<Bytes>,
+    ) -> SourceResult<Value> {
+        let Spanned { v: data, span } = data;
+        rmp_serde::from_slice(data.as_slice())
+            .map_err(|err| eco_format!("failed to parse MessagePack ({err})"))
+            .at(span)
+    }
+
+    /// Encode structured data into MessagePack bytes.
+    #[func(title = "Encode MessagePack")]
+    pub fn encode(
+        /// Value to be encoded.
+        value: Spanned<Value>,
+    ) -> SourceResult<Bytes> {
+        let Spanned { v: value, span } = value;
+        rmp_serde::to_vec_named(&value)
+            .map(|res| res.into())
+            .map_err(|err| eco_format!("failed to encode value as MessagePack ({err})"))
+            .at(span)
+    }
+}
@@ -1,15 +1,17 @@
 use ecow::EcoString;
 
-use crate::diag::{At, SourceResult};
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::foundations::{func, Cast};
+use crate::foundations::{func, Cast, Smart};
 use crate::loading::Readable;
 use crate::syntax::Spanned;
-use crate::World;
+use crate::Load;
 
 /// Reads plain text or data from a file.
 ///
 /// By default, the file will be read as UTF-8 and returned as a [string]($str).
+/// Files in other encodings can be read by specifying the `encoding`
+/// explicitly or by setting it to `{auto}`, which detects the encoding.
 ///
 /// If you specify `{encoding: none}`, this returns raw [bytes]($bytes) instead.
 ///
@@ -30,22 +32,27 @@ pub fn read(
     path: Spanned<EcoString>,
     /// The encoding to read the file with.
     ///
+    /// If set to `{auto}`, the encoding is detected from the file's byte
+    /// order mark or, if it has none, from its contents. Files that are
+    /// neither valid UTF-8 nor UTF-16 are read as Windows-1252.
+    ///
     /// If set to `{none}`, this function returns raw bytes.
     #[named]
-    #[default(Some(Encoding::Utf8))]
-    encoding: Option<Encoding>,
+    #[default(Some(Smart::Custom(Encoding::Utf8)))]
+    encoding: Option<Smart<Encoding>>,
 ) -> SourceResult<Readable> {
     let Spanned { v: path, span } = path;
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = engine.world.load_file(id).at(span)?;
     Ok(match encoding {
         None => Readable::Bytes(data),
-        Some(Encoding::Utf8) => Readable::Str(
-            std::str::from_utf8(&data)
-                .map_err(|_| "file is not valid utf-8")
-                .at(span)?
-                .into(),
-        ),
+        Some(Smart::Auto) => {
+            let (encoding, len) = Encoding::detect(&data);
+            Readable::Str(encoding.decode(&data[len..]).at(span)?.into())
+        }
+        Some(Smart::Custom(encoding)) => {
+            Readable::Str(encoding.decode(&data).at(span)?.into())
+        }
     })
 }
 
@@ -54,4 +61,98 @@ pub fn read(
 pub enum Encoding {
     /// The Unicode UTF-8 encoding.
     Utf8,
+    /// The Unicode UTF-16 encoding. The byte order is determined by the byte
+    /// order mark and defaults to little endian.
+    Utf16,
+    /// The Unicode UTF-16 encoding in little endian byte order.
+    #[string("utf16le")]
+    Utf16Le,
+    /// The Unicode UTF-16 encoding in big endian byte order.
+    #[string("utf16be")]
+    Utf16Be,
+    /// The ISO 8859-1 encoding, also known as Latin-1.
+    Latin1,
+    /// The Windows-1252 encoding, a superset of Latin-1 that is commonly used
+    /// by legacy Windows software.
+    #[string("windows-1252")]
+    Windows1252,
 }
+
+impl Encoding {
+    /// Detect the encoding of data, returning it alongside the length of the
+    /// byte order mark.
+    fn detect(data: &[u8]) -> (Self, usize) {
+        match data {
+            [0xef, 0xbb, 0xbf, ..] => (Self::Utf8, 3),
+            [0xff, 0xfe, ..] => (Self::Utf16Le, 2),
+            [0xfe, 0xff, ..] => (Self::Utf16Be, 2),
+            _ if std::str::from_utf8(data).is_ok() => (Self::Utf8, 0),
+            _ if data.len() % 2 == 0 && data.len() >= 2 => {
+                // UTF-16 text without a byte order mark is usually mostly
+                // ASCII, so every other byte is zero.
+                let zeros = |offset| {
+                    data.iter().skip(offset).step_by(2).filter(|&&b| b == 0).count()
+                };
+                let half = data.len() / 4;
+                if zeros(1) > half {
+                    (Self::Utf16Le, 0)
+                } else if zeros(0) > half {
+                    (Self::Utf16Be, 0)
+                } else {
+                    (Self::Windows1252, 0)
+                }
+            }
+            _ => (Self::Windows1252, 0),
+        }
+    }
+
+    /// Decode data in this encoding.
+    fn decode(self, data: &[u8]) -> StrResult<EcoString> {
+        Ok(match self {
+            Self::Utf8 => std::str::from_utf8(data)
+                .map_err(|_| "file is not valid utf-8")?
+                .into(),
+            Self::Utf16 => match data {
+                [0xfe, 0xff, rest @ ..] => Self::Utf16Be.decode(rest)?,
+                [0xff, 0xfe, rest @ ..] => Self::Utf16Le.decode(rest)?,
+                _ => Self::Utf16Le.decode(data)?,
+            },
+            Self::Utf16Le | Self::Utf16Be => {
+                if data.len() % 2 != 0 {
+                    bail!("file is not valid utf-16");
+                }
+
+                let units = data.chunks_exact(2).map(|pair| {
+                    let pair = [pair[0], pair[1]];
+                    if self == Self::Utf16Le {
+                        u16::from_le_bytes(pair)
+                    } else {
+                        u16::from_be_bytes(pair)
+                    }
+                });
+
+                char::decode_utf16(units)
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| "file is not valid utf-16")?
+            }
+            Self::Latin1 => data.iter().map(|&b| char::from(b)).collect(),
+            Self::Windows1252 => data
+                .iter()
+                .map(|&b| match b {
+                    0x80..=0x9f => WINDOWS_1252[usize::from(b - 0x80)],
+                    _ => char::from(b),
+                })
+                .collect(),
+        })
+    }
+}
+
+/// The characters for the bytes 0x80 to 0x9F in Windows-1252. Undefined bytes
+/// map to the corresponding C1 control characters.
+const WINDOWS_1252: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}',
+    '\u{2021}', '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}',
+    '\u{17D}', '\u{8F}', '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}',
+    '\u{2022}', '\u{2013}', '\u{2014}', '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}',
+    '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
@@ -3,9 +3,8 @@ use ecow::{eco_format, EcoString};
 use crate::diag::{At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{func, scope, Str, Value};
-use crate::loading::Readable;
+use crate::loading::{load_data, Readable};
 use crate::syntax::{is_newline, Spanned};
-use crate::World;
 
 /// Reads structured data from a TOML file.
 ///
@@ -35,8 +34,7 @@ pub fn toml(
     path: Spanned<EcoString>,
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
-    let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = load_data(engine, &path, span)?;
     toml::decode(Spanned::new(Readable::Bytes(data), span))
 }
 
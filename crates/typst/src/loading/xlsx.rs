@@ -0,0 +1,201 @@
+use std::io::Cursor;
+
+use calamine::{DataType, Reader, Xlsx};
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, func, scope, Array, Bytes, Datetime, IntoValue, Str, Value,
+};
+use crate::loading::load_data;
+use crate::syntax::Spanned;
+
+/// Reads structured data from an Excel spreadsheet.
+///
+/// The rows of a worksheet are read into an array of arrays. Text cells are
+/// converted into strings, numbers into integers or floats, booleans into
+/// booleans, and dates into [datetimes]($datetime). Empty cells and cells with
+/// errors become `{none}`.
+///
+/// If `header` is `{true}`, the first row is used for the column names and the
+/// remaining rows are returned as dictionaries, just like for
+/// [`csv`]($csv).
+///
+/// # Example
+/// ```example
+/// #let rows = xlsx("data.xlsx", sheet: "Q3")
+/// #table(
+///   columns: rows.first().len(),
+///   ..rows.flatten().map(cell => [#cell]),
+/// )
+/// ```
+#[func(scope, title = "XLSX")]
+pub fn xlsx(
+    /// The engine.
+    engine: &mut Engine,
+    /// Path to an XLSX file.
+    path: Spanned<EcoString>,
+    /// The worksheet to read, either by name or by zero-based index. Defaults
+    /// to the first worksheet.
+    #[named]
+    #[default]
+    sheet: Option<Sheet>,
+    /// Whether the first row contains the column names. If so, the remaining
+    /// rows are returned as dictionaries.
+    #[named]
+    #[default(false)]
+    header: bool,
+) -> SourceResult<Array> {
+    let Spanned { v: path, span } = path;
+    let data = load_data(engine, &path, span)?;
+    xlsx::decode(Spanned::new(data, span), sheet, header)
+}
+
+#[scope]
+impl xlsx {
+    /// Reads structured data from XLSX bytes.
+    #[func(title = "Decode XLSX")]
+    pub fn decode(
+        /// XLSX data.
+        data: Spanned<Bytes>,
+        /// The worksheet to read, either by name or by zero-based index.
+        #[named]
+        #[default]
+        sheet: Option<Sheet>,
+        /// Whether the first row contains the column names.
+        #[named]
+        #[default(false)]
+        header: bool,
+    ) -> SourceResult<Array> {
+        let Spanned { v: data, span } = data;
+        let mut workbook = Xlsx::new(Cursor::new(data.as_slice()))
+            .map_err(|err| eco_format!("failed to parse XLSX ({err})"))
+            .at(span)?;
+
+        let names = workbook.sheet_names().to_vec();
+        let name = match &sheet {
+            None => names.first(),
+            Some(Sheet::Name(name)) => names.iter().find(|n| n.as_str() == name.as_str()),
+            Some(Sheet::Index(i)) => names.get(*i),
+        };
+
+        let Some(name) = name else {
+            match sheet {
+                None => bail!(span, "spreadsheet does not contain any worksheets"),
+                Some(Sheet::Name(name)) => bail!(span, "worksheet `{name}` not found"),
+                Some(Sheet::Index(i)) => {
+                    bail!(span, "worksheet index out of bounds: {i}")
+                }
+            }
+        };
+
+        let range = workbook
+            .worksheet_range(name)
+            .ok_or("failed to parse XLSX (missing worksheet)")
+            .at(span)?
+            .map_err(|err| eco_format!("failed to parse XLSX ({err})"))
+            .at(span)?;
+
+        let mut rows = range.rows();
+        let columns: Option<Vec<Str>> = if header {
+            rows.next()
+                .map(|row| row.iter().map(|cell| eco_format!("{cell}").into()).collect())
+        } else {
+            None
+        };
+
+        let mut array = Array::new();
+        for row in rows {
+            let fields = row.iter().map(convert_cell);
+            let value = match &columns {
+                Some(columns) => Value::Dict(
+                    columns
+                        .iter()
+                        .cloned()
+                        .zip(fields.chain(std::iter::repeat(Value::None)))
+                        .collect(),
+                ),
+                None => Value::Array(fields.collect()),
+            };
+            array.push(value);
+        }
+
+        Ok(array)
+    }
+}
+
+/// A reference to a worksheet.
+pub enum Sheet {
+    /// A worksheet by name.
+    Name(Str),
+    /// A worksheet by zero-based index.
+    Index(usize),
+}
+
+cast! {
+    Sheet,
+    self => match self {
+        Self::Name(name) => name.into_value(),
+        Self::Index(i) => i.into_value(),
+    },
+    v: Str => Self::Name(v),
+    v: usize => Self::Index(v),
+}
+
+/// Convert a spreadsheet cell to a Typst value.
+fn convert_cell(cell: &DataType) -> Value {
+    match cell {
+        DataType::Int(v) => Value::Int(*v),
+        DataType::Float(v) => {
+            if v.fract() == 0.0 && v.abs() < i64::MAX as f64 {
+                Value::Int(*v as i64)
+            } else {
+                Value::Float(*v)
+            }
+        }
+        DataType::String(v) => Value::Str(v.as_str().into()),
+        DataType::Bool(v) => Value::Bool(*v),
+        DataType::DateTime(v) => match convert_serial_date(*v) {
+            Ok(datetime) => datetime.into_value(),
+            Err(_) => Value::Float(*v),
+        },
+        DataType::DateTimeIso(v) => match Datetime::from_iso(v) {
+            Some(datetime) => datetime.into_value(),
+            None => Value::Str(v.as_str().into()),
+        },
+        DataType::Duration(v) => Value::Float(*v),
+        DataType::DurationIso(v) => Value::Str(v.as_str().into()),
+        DataType::Error(_) | DataType::Empty => Value::None,
+    }
+}
+
+/// Convert an Excel serial date to a datetime.
+///
+/// Serial dates count the days since the end of 1899. Their fractional part is
+/// the time of day.
+fn convert_serial_date(serial: f64) -> StrResult<Datetime> {
+    if !serial.is_finite() || serial < 0.0 {
+        bail!("invalid serial date");
+    }
+
+    let days = serial.trunc() as i64;
+    let seconds = ((serial - serial.trunc()) * 86400.0).round() as i64;
+    let epoch = time::Date::from_calendar_date(1899, time::Month::December, 30)
+        .map_err(|_| "invalid serial date")?;
+    let date = epoch
+        .checked_add(time::Duration::days(days))
+        .ok_or("invalid serial date")?;
+
+    Ok(if days == 0 {
+        let time = time::Time::MIDNIGHT + time::Duration::seconds(seconds);
+        Datetime::Time(time)
+    } else if seconds == 0 {
+        Datetime::Date(date)
+    } else {
+        let datetime = time::PrimitiveDateTime::new(date, time::Time::MIDNIGHT)
+            .checked_add(time::Duration::seconds(seconds))
+            .ok_or("invalid serial date")?;
+        Datetime::Datetime(datetime)
+    })
+}
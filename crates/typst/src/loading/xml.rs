@@ -1,11 +1,10 @@
-use ecow::EcoString;
+use ecow::{eco_format, EcoString};
 
-use crate::diag::{format_xml_like_error, At, FileError, SourceResult};
+use crate::diag::{bail, format_xml_like_error, At, FileError, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{dict, func, scope, Array, Dict, IntoValue, Str, Value};
-use crate::loading::Readable;
+use crate::loading::{load_data, Readable};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from an XML file.
 ///
@@ -16,6 +15,12 @@ use crate::World;
 /// - `tag`: The name of the element as a string.
 /// - `attrs`: A dictionary of the element's attributes as strings.
 /// - `children`: An array of the element's child nodes.
+/// - `namespace`: The namespace URI of the element. This key is only present
+///   if the element is in a namespace.
+///
+/// Attributes in a namespace are prefixed with the prefix that is bound to
+/// their namespace, like `xlink:href`. To navigate large files, take a look at
+/// the [`xml.find`]($xml.find) function.
 ///
 /// The XML file in the example contains a root `news` tag with multiple
 /// `article` tags. Each article has a `title`, `author`, and `content` tag. The
@@ -63,8 +68,7 @@ pub fn xml(
     path: Spanned<EcoString>,
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
-    let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = load_data(engine, &path, span)?;
     xml::decode(Spanned::new(Readable::Bytes(data), span))
 }
 
@@ -84,6 +88,57 @@ impl xml {
             roxmltree::Document::parse(text).map_err(format_xml_error).at(span)?;
         Ok(convert_xml(document.root()))
     }
+
+    /// Finds all elements matching a path in decoded XML data.
+    ///
+    /// The path consists of tag names separated by slashes. Each tag name
+    /// selects the matching children of the elements selected so far. A `*`
+    /// matches any element and a double slash (`//`) descends into arbitrary
+    /// depths.
+    ///
+    /// ```example
+    /// #let data = xml("example.xml")
+    /// #for title in xml.find(data, "news/article/title") [
+    ///   - #title.children.first()
+    /// ]
+    /// ```
+    #[func(title = "Find in XML")]
+    pub fn find(
+        /// The decoded XML data to search in. Can be an element or an array of
+        /// nodes, like the one returned by [`xml`]($xml).
+        data: Value,
+        /// The path to search for, like `{"book/title"}`.
+        path: Str,
+    ) -> StrResult<Array> {
+        let mut selected = vec![data];
+        let mut descend = false;
+        for segment in path.split('/') {
+            if segment.is_empty() {
+                descend = true;
+                continue;
+            }
+
+            let mut next = vec![];
+            for node in &selected {
+                if descend {
+                    collect_descendants(node, segment, &mut next);
+                } else {
+                    next.extend(
+                        xml_children(node).filter(|child| matches_tag(child, segment)),
+                    );
+                }
+            }
+
+            selected = next;
+            descend = false;
+        }
+
+        if descend {
+            bail!("path must not end with a slash");
+        }
+
+        Ok(selected.into_iter().collect())
+    }
 }
 
 /// Convert an XML node to a Typst value.
@@ -100,14 +155,59 @@ fn convert_xml(node: roxmltree::Node) -> Value {
     let tag: Str = node.tag_name().name().into();
     let attrs: Dict = node
         .attributes()
-        .map(|attr| (attr.name().into(), attr.value().into_value()))
+        .map(|attr| {
+            let prefix = attr.namespace().and_then(|uri| node.lookup_prefix(uri));
+            let name = match prefix {
+                Some(prefix) => eco_format!("{prefix}:{}", attr.name()),
+                None => attr.name().into(),
+            };
+            (name.into(), attr.value().into_value())
+        })
         .collect();
 
-    Value::Dict(dict! {
+    let mut dict = dict! {
         "tag" => tag,
         "attrs" => attrs,
         "children" => children,
-    })
+    };
+
+    if let Some(namespace) = node.tag_name().namespace() {
+        dict.insert("namespace".into(), namespace.into_value());
+    }
+
+    Value::Dict(dict)
+}
+
+/// The child nodes of a decoded XML element or of an array of nodes.
+fn xml_children(node: &Value) -> impl Iterator<Item = Value> {
+    let children = match node {
+        Value::Array(array) => Some(array.clone()),
+        Value::Dict(dict) => match dict.get("children") {
+            Ok(Value::Array(array)) => Some(array.clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    children.into_iter().flatten()
+}
+
+/// Collect all descendants of a node whose tag matches.
+fn collect_descendants(node: &Value, tag: &str, out: &mut Vec<Value>) {
+    for child in xml_children(node) {
+        if matches_tag(&child, tag) {
+            out.push(child.clone());
+        }
+        collect_descendants(&child, tag, out);
+    }
+}
+
+/// Whether a decoded XML node is an element with the given tag.
+fn matches_tag(node: &Value, tag: &str) -> bool {
+    let Value::Dict(dict) = node else { return false };
+    match dict.get("tag") {
+        Ok(Value::Str(name)) => tag == "*" || name.as_str() == tag,
+        _ => false,
+    }
 }
 
 /// Format the user-facing XML error message.
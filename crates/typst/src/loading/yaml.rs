@@ -1,11 +1,12 @@
 use ecow::{eco_format, EcoString};
 
-use crate::diag::{At, SourceResult};
+use serde::Deserialize;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::foundations::{func, scope, Str, Value};
-use crate::loading::Readable;
+use crate::foundations::{func, scope, Array, Str, Value};
+use crate::loading::{load_data, parse_datetimes, Readable};
 use crate::syntax::Spanned;
-use crate::World;
 
 /// Reads structured data from a YAML file.
 ///
@@ -15,7 +16,12 @@ use crate::World;
 /// equivalents, null-values (`null`, `~` or empty ``) will be converted into
 /// `{none}`, and numbers will be converted to floats or integers depending on
 /// whether they are whole numbers. Custom YAML tags are ignored, though the
-/// loaded value will still be present.
+/// loaded value will still be present. Anchors, aliases, and merge keys
+/// (`<<`) are resolved.
+///
+/// A file can contain a stream of multiple documents separated by `---`. To
+/// load all of them, set `stream` to `{true}`, which results in an array of
+/// documents.
 ///
 /// The YAML files in the example contain objects with authors as keys,
 /// each with a sequence of their own submapping with the keys
@@ -42,11 +48,22 @@ pub fn yaml(
     engine: &mut Engine,
     /// Path to a YAML file.
     path: Spanned<EcoString>,
+    /// Whether to load all documents in the file as an array instead of a
+    /// single document.
+    #[named]
+    #[default(false)]
+    stream: bool,
+    /// Whether to convert strings containing ISO 8601 dates and times, like
+    /// `{"2023-05-01"}` or `{"2023-05-01T12:30:00Z"}`, to
+    /// [datetimes]($datetime). Fractional seconds and UTC offsets are
+    /// discarded.
+    #[named]
+    #[default(false)]
+    datetimes: bool,
 ) -> SourceResult<Value> {
     let Spanned { v: path, span } = path;
-    let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
-    yaml::decode(Spanned::new(Readable::Bytes(data), span))
+    let data = load_data(engine, &path, span)?;
+    yaml::decode(Spanned::new(Readable::Bytes(data), span), stream, datetimes)
 }
 
 #[scope]
@@ -56,10 +73,33 @@ impl yaml {
     pub fn decode(
         /// YAML data.
         data: Spanned<Readable>,
+        /// Whether to decode all documents in the data as an array instead of
+        /// a single document.
+        #[named]
+        #[default(false)]
+        stream: bool,
+        /// Whether to convert strings containing ISO 8601 dates and times to
+        /// datetimes. See the [`yaml`]($yaml) function for details.
+        #[named]
+        #[default(false)]
+        datetimes: bool,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        serde_yaml::from_slice(data.as_slice())
-            .map_err(|err| eco_format!("failed to parse YAML ({err})"))
+        if !stream {
+            let value = serde_yaml::from_slice(data.as_slice())
+                .map_err(format_yaml_error)
+                .at(span)?;
+            return convert_yaml(value, datetimes).at(span);
+        }
+
+        serde_yaml::Deserializer::from_slice(data.as_slice())
+            .map(|document| {
+                let value = serde_yaml::Value::deserialize(document)
+                    .map_err(format_yaml_error)?;
+                convert_yaml(value, datetimes)
+            })
+            .collect::<StrResult<Array>>()
+            .map(Value::Array)
             .at(span)
     }
 
@@ -76,3 +116,51 @@ impl yaml {
             .at(span)
     }
 }
+
+/// Convert a YAML value to a Typst value, resolving merge keys.
+fn convert_yaml(mut value: serde_yaml::Value, datetimes: bool) -> StrResult<Value> {
+    value.apply_merge().map_err(format_yaml_error)?;
+    let value = convert_yaml_node(value)?;
+    Ok(if datetimes { parse_datetimes(value) } else { value })
+}
+
+/// Convert a YAML node with resolved merge keys to a Typst value.
+fn convert_yaml_node(value: serde_yaml::Value) -> StrResult<Value> {
+    Ok(match value {
+        serde_yaml::Value::Null => Value::None,
+        serde_yaml::Value::Bool(v) => Value::Bool(v),
+        serde_yaml::Value::Number(v) => match v.as_i64() {
+            Some(int) => Value::Int(int),
+            None => Value::Float(v.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_yaml::Value::String(v) => Value::Str(v.into()),
+        serde_yaml::Value::Sequence(v) => {
+            Value::Array(v.into_iter().map(convert_yaml_node).collect::<StrResult<_>>()?)
+        }
+        serde_yaml::Value::Mapping(v) => Value::Dict(
+            v.into_iter()
+                .map(|(key, value)| {
+                    Ok((convert_yaml_key(key)?, convert_yaml_node(value)?))
+                })
+                .collect::<StrResult<_>>()?,
+        ),
+        serde_yaml::Value::Tagged(v) => convert_yaml_node(v.value)?,
+    })
+}
+
+/// Convert a YAML mapping key to a string.
+fn convert_yaml_key(key: serde_yaml::Value) -> StrResult<Str> {
+    Ok(match key {
+        serde_yaml::Value::Null => "null".into(),
+        serde_yaml::Value::Bool(v) => eco_format!("{v}").into(),
+        serde_yaml::Value::Number(v) => eco_format!("{v}").into(),
+        serde_yaml::Value::String(v) => v.into(),
+        serde_yaml::Value::Tagged(v) => convert_yaml_key(v.value)?,
+        _ => bail!("failed to parse YAML (mapping keys must be scalars)"),
+    })
+}
+
+/// Format the user-facing YAML error message.
+fn format_yaml_error(err: serde_yaml::Error) -> EcoString {
+    eco_format!("failed to parse YAML ({err})")
+}
@@ -5,6 +5,7 @@ use ttf_parser::gsub::{AlternateSubstitution, SingleSubstitution, SubstitutionSu
 use ttf_parser::math::MathValue;
 use ttf_parser::opentype_layout::LayoutTable;
 use ttf_parser::GlyphId;
+use unicode_bidi::{bidi_class, BidiClass};
 use unicode_math_class::MathClass;
 use unicode_segmentation::UnicodeSegmentation;
 
@@ -221,7 +222,7 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
                 fragments.push(GlyphFragment::new(self, c, span).into());
             }
             let frame = MathRow::new(fragments).into_frame(self);
-            FrameFragment::new(self, frame).into()
+            FrameFragment::new(self, frame).with_bidi(bidi_char(text)).into()
         } else {
             // Anything else is handled by Typst's standard text layout.
             let mut style = self.style;
@@ -281,7 +282,8 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
 
         Ok(FrameFragment::new(self, frame)
             .with_class(MathClass::Alphabetic)
-            .with_spaced(spaced))
+            .with_spaced(spaced)
+            .with_bidi(bidi_char(text)))
     }
 
     pub fn styles(&self) -> StyleChain {
@@ -321,6 +323,23 @@ impl<'a, 'b, 'v> MathContext<'a, 'b, 'v> {
     }
 }
 
+/// The first character of a text that determines its bidirectional placement
+/// in a row, that is, its first strong character or digit.
+fn bidi_char(text: &str) -> char {
+    text.chars()
+        .find(|&c| {
+            matches!(
+                bidi_class(c),
+                BidiClass::L
+                    | BidiClass::R
+                    | BidiClass::AL
+                    | BidiClass::EN
+                    | BidiClass::AN
+            )
+        })
+        .unwrap_or('\u{FFFC}')
+}
+
 pub(super) trait Scaled {
     fn scaled(self, ctx: &MathContext) -> Abs;
 }
@@ -1,20 +1,23 @@
 use std::num::NonZeroUsize;
 
+use unicode_math_class::MathClass;
+
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
     elem, Content, Finalize, Guard, NativeElement, Resolve, Show, Smart, StyleChain,
     Synthesize,
 };
-use crate::introspection::{Count, Counter, CounterUpdate, Locatable};
+use crate::introspection::{Count, Counter, CounterState, CounterUpdate, Locatable};
 use crate::layout::{
-    Abs, Align, AlignElem, Axes, Dir, Em, FixedAlign, Fragment, Layout, Point, Regions,
-    Size,
+    Abs, Align, AlignElem, Axes, Dir, Em, FixedAlign, Fragment, Frame, Layout, Point,
+    Regions, Size,
 };
 use crate::math::{LayoutMath, MathContext};
-use crate::model::{Numbering, Outlinable, ParElem, Refable, Supplement};
+use crate::model::{HeadingElem, Numbering, Outlinable, ParElem, Refable, Supplement};
+use crate::syntax::Span;
 use crate::text::{
-    families, variant, FontFamily, FontList, FontWeight, Lang, LocalName, Region,
+    families, variant, Font, FontFamily, FontList, FontWeight, Lang, LocalName, Region,
     TextElem,
 };
 use crate::util::{option_eq, NonZeroExt, Numeric};
@@ -65,6 +68,28 @@ pub struct EquationElem {
     /// ```
     pub numbering: Option<Numbering>,
 
+    /// The heading level at which the equation numbers reset.
+    ///
+    /// When set, equations are numbered within their section and the numbers
+    /// of the enclosing headings up to this level are put in front of the
+    /// equation's own number. With `{auto}`, the level is derived from the
+    /// numbering pattern: A pattern like `{"(1.1)"}` with two counting symbols
+    /// incorporates one heading level. Set this to `{none}` to number
+    /// equations consecutively throughout the document.
+    ///
+    /// ```example
+    /// #set heading(numbering: "1.")
+    /// #set math.equation(numbering: "(1.1)")
+    ///
+    /// = Basics
+    /// $ a + b = c $
+    /// $ c - b = a $
+    ///
+    /// = Products
+    /// $ a dot b = c $
+    /// ```
+    pub reset: Smart<Option<NonZeroUsize>>,
+
     /// A supplement for the equation.
     ///
     /// For references to equations, this is added before the referenced number.
@@ -83,9 +108,117 @@ pub struct EquationElem {
     /// ```
     pub supplement: Smart<Option<Supplement>>,
 
+    /// The penalty for breaking an inline equation after a relation, like
+    /// `{=}` or `{<}`.
+    ///
+    /// Long inline equations can break across lines after top-level relations
+    /// and binary operators. Higher penalties make such breaks less likely. A
+    /// penalty of `{50}` is as bad as hyphenating a word. Set this to `{none}`
+    /// to forbid breaking after relations.
+    ///
+    /// Inline equations only break with
+    /// [optimized line breaking]($par.linebreaks), where the penalties are
+    /// weighed against the other line break opportunities of the paragraph.
+    ///
+    /// ```example
+    /// #set page(width: 150pt)
+    /// #set par(linebreaks: "optimized")
+    /// #set math.equation(relation-penalty: 0)
+    ///
+    /// The sum is $a + b + c + d = x + y + z$.
+    /// ```
+    #[default(Some(500.0))]
+    pub relation_penalty: Option<f64>,
+
+    /// The penalty for breaking an inline equation after a binary operator,
+    /// like `{+}` or `{-}`.
+    ///
+    /// Works like the [`relation-penalty`]($math.equation.relation-penalty).
+    #[default(Some(700.0))]
+    pub binary_penalty: Option<f64>,
+
     /// The contents of the equation.
     #[required]
     pub body: Content,
+
+    /// The heading numbers followed by the equation's number within its
+    /// section, if the numbering resets at some heading level.
+    #[internal]
+    #[synthesized]
+    #[default(None)]
+    pub numbers: Option<CounterState>,
+}
+
+impl EquationElem {
+    /// Layout an inline equation into pieces between which a paragraph may
+    /// break lines.
+    pub fn layout_inline(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Vec<MathParItem>> {
+        let font = find_math_font(engine, styles, self.span())?;
+        let relation = self.relation_penalty(styles);
+        let binary = self.binary_penalty(styles);
+
+        let mut ctx = MathContext::new(engine, styles, regions, &font, false);
+        let row = ctx.layout_row(self)?;
+        let mut items = row.into_par_items(&ctx, |class| match class {
+            MathClass::Relation => relation,
+            MathClass::Binary => binary,
+            _ => None,
+        });
+
+        for item in &mut items {
+            let MathParItem::Frame(frame) = item else { continue };
+            fit_inline(frame, styles, &font);
+            frame.meta(styles, false);
+        }
+
+        Ok(items)
+    }
+
+    /// The heading level at which the numbering resets, if any.
+    fn reset_level(&self, styles: StyleChain) -> Option<NonZeroUsize> {
+        match self.reset(styles) {
+            Smart::Custom(level) => level,
+            Smart::Auto => match self.numbering(styles)? {
+                Numbering::Pattern(pattern) => {
+                    NonZeroUsize::new(pattern.pieces.len().saturating_sub(1))
+                }
+                Numbering::Func(_) => None,
+            },
+        }
+    }
+
+    /// Determine the numbers of this equation within its section.
+    fn section_numbers(
+        &self,
+        engine: &mut Engine,
+        level: NonZeroUsize,
+    ) -> SourceResult<CounterState> {
+        let location = self.location().unwrap();
+        let mut numbers = Counter::of(HeadingElem::elem()).at(engine, location)?.0;
+        numbers.resize(level.get(), 0);
+
+        // Continue from the previous equation if it is in the same section.
+        let selector = Self::elem().select().before(location.into(), false);
+        let previous = engine
+            .introspector
+            .query(&selector)
+            .iter()
+            .rev()
+            .find_map(|elem| elem.to::<Self>()?.numbers().clone());
+
+        let number = match previous.as_ref().and_then(|prev| prev.0.split_last()) {
+            Some((last, prefix)) if prefix == numbers.as_slice() => last + 1,
+            _ => 1,
+        };
+
+        numbers.push(number);
+        Ok(CounterState(numbers))
+    }
 }
 
 impl Synthesize for EquationElem {
@@ -103,8 +236,16 @@ impl Synthesize for EquationElem {
             }
         };
 
+        let numbers = match self.reset_level(styles) {
+            Some(level) if self.block(styles) && self.numbering(styles).is_some() => {
+                Some(self.section_numbers(engine, level)?)
+            }
+            _ => None,
+        };
+
         self.push_block(self.block(styles));
         self.push_numbering(self.numbering(styles));
+        self.push_numbers(numbers);
         self.push_supplement(Smart::Custom(Some(Supplement::Content(supplement))));
 
         Ok(())
@@ -147,18 +288,7 @@ impl Layout for EquationElem {
         const NUMBER_GUTTER: Em = Em::new(0.5);
 
         let block = self.block(styles);
-
-        // Find a math font.
-        let variant = variant(styles);
-        let world = engine.world;
-        let Some(font) = families(styles).find_map(|family| {
-            let id = world.book().select(family, variant)?;
-            let font = world.font(id)?;
-            let _ = font.ttf().tables().math?.constants?;
-            Some(font)
-        }) else {
-            bail!(self.span(), "current font does not support math");
-        };
+        let font = find_math_font(engine, styles, self.span())?;
 
         let mut ctx = MathContext::new(engine, styles, regions, &font, block);
         let mut frame = ctx.layout_frame(self)?;
@@ -200,16 +330,7 @@ impl Layout for EquationElem {
                 frame.push_frame(Point::new(x, y), counter)
             }
         } else {
-            let font_size = TextElem::size_in(styles);
-            let slack = ParElem::leading_in(styles) * 0.7;
-            let top_edge = TextElem::top_edge_in(styles).resolve(font_size, &font, None);
-            let bottom_edge =
-                -TextElem::bottom_edge_in(styles).resolve(font_size, &font, None);
-
-            let ascent = top_edge.max(frame.ascent() - slack);
-            let descent = bottom_edge.max(frame.descent() - slack);
-            frame.translate(Point::with_y(ascent - frame.baseline()));
-            frame.size_mut().y = ascent + descent;
+            fit_inline(&mut frame, styles, &font);
         }
 
         // Apply metadata.
@@ -219,11 +340,57 @@ impl Layout for EquationElem {
     }
 }
 
+/// Find a font that supports math.
+fn find_math_font(engine: &Engine, styles: StyleChain, span: Span) -> SourceResult<Font> {
+    let variant = variant(styles);
+    let world = engine.world;
+    let Some(font) = families(styles).find_map(|family| {
+        let id = world.book().select(family, variant)?;
+        let font = world.font(id)?;
+        let _ = font.ttf().tables().math?.constants?;
+        Some(font)
+    }) else {
+        bail!(span, "current font does not support math");
+    };
+    Ok(font)
+}
+
+/// Fit the frame of inline math to the text's top and bottom edges, allowing
+/// it to extend a bit into the leading.
+fn fit_inline(frame: &mut Frame, styles: StyleChain, font: &Font) {
+    let font_size = TextElem::size_in(styles);
+    let slack = ParElem::leading_in(styles) * 0.7;
+    let top_edge = TextElem::top_edge_in(styles).resolve(font_size, font, None);
+    let bottom_edge = -TextElem::bottom_edge_in(styles).resolve(font_size, font, None);
+
+    let ascent = top_edge.max(frame.ascent() - slack);
+    let descent = bottom_edge.max(frame.descent() - slack);
+    frame.translate(Point::with_y(ascent - frame.baseline()));
+    frame.size_mut().y = ascent + descent;
+}
+
+/// A piece of an inline equation laid out for a paragraph.
+#[derive(Debug, Clone)]
+pub enum MathParItem {
+    /// Spacing after which the line may be broken, with a penalty for doing
+    /// so.
+    Break(Abs, f64),
+    /// An unbreakable part of the equation.
+    Frame(Frame),
+}
+
 impl Count for EquationElem {
     fn update(&self) -> Option<CounterUpdate> {
-        (self.block(StyleChain::default())
-            && self.numbering(StyleChain::default()).is_some())
-        .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+        if !self.block(StyleChain::default())
+            || self.numbering(StyleChain::default()).is_none()
+        {
+            return None;
+        }
+
+        Some(match self.numbers() {
+            Some(numbers) => CounterUpdate::Set(numbers.clone()),
+            None => CounterUpdate::Step(NonZeroUsize::ONE),
+        })
     }
 }
 
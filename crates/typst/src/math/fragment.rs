@@ -10,8 +10,8 @@ use crate::introspection::{Meta, MetaElem};
 use crate::layout::{Abs, Corner, Em, Frame, FrameItem, Point, Size};
 use crate::math::{Limits, MathContext, MathStyle, Scaled};
 use crate::syntax::Span;
-use crate::text::{Font, Glyph, Lang, TextElem, TextItem};
-use crate::visualize::Paint;
+use crate::text::{Font, Glyph, Lang, Region, TextElem, TextItem};
+use crate::visualize::{FixedStroke, Paint};
 
 #[derive(Debug, Clone)]
 pub enum MathFragment {
@@ -129,6 +129,18 @@ impl MathFragment {
         }
     }
 
+    /// The character whose bidirectional class stands in for the fragment
+    /// when its row is reordered.
+    pub fn bidi(&self) -> char {
+        match self {
+            Self::Glyph(glyph) => glyph.c,
+            Self::Variant(variant) => variant.c,
+            Self::Frame(fragment) => fragment.bidi,
+            Self::Spacing(_) | Self::Space(_) => ' ',
+            _ => '\u{FFFC}',
+        }
+    }
+
     pub fn is_spaced(&self) -> bool {
         match self {
             MathFragment::Frame(frame) => {
@@ -193,7 +205,9 @@ pub struct GlyphFragment {
     pub c: char,
     pub font: Font,
     pub lang: Lang,
+    pub region: Option<Region>,
     pub fill: Paint,
+    pub stroke: Option<FixedStroke>,
     pub shift: Abs,
     pub width: Abs,
     pub ascent: Abs,
@@ -232,7 +246,13 @@ impl GlyphFragment {
             c,
             font: ctx.font.clone(),
             lang: TextElem::lang_in(ctx.styles()),
+            region: TextElem::region_in(ctx.styles()),
             fill: TextElem::fill_in(ctx.styles()).as_decoration(),
+            stroke: TextElem::stroke_in(ctx.styles()).map(|s| {
+                let mut stroke = s.unwrap_or_default();
+                stroke.paint = stroke.paint.as_decoration();
+                stroke
+            }),
             shift: TextElem::baseline_in(ctx.styles()),
             style: ctx.style,
             font_size: ctx.size,
@@ -305,7 +325,9 @@ impl GlyphFragment {
             font: self.font.clone(),
             size: self.font_size,
             fill: self.fill,
+            stroke: self.stroke,
             lang: self.lang,
+            region: self.region,
             text: self.c.into(),
             glyphs: vec![Glyph {
                 id: self.id.0,
@@ -386,6 +408,7 @@ pub struct FrameFragment {
     pub limits: Limits,
     pub spaced: bool,
     pub base_ascent: Abs,
+    pub bidi: char,
 }
 
 impl FrameFragment {
@@ -400,6 +423,7 @@ impl FrameFragment {
             limits: Limits::Never,
             spaced: false,
             base_ascent,
+            bidi: '\u{FFFC}',
         }
     }
 
@@ -418,6 +442,10 @@ impl FrameFragment {
     pub fn with_base_ascent(self, base_ascent: Abs) -> Self {
         Self { base_ascent, ..self }
     }
+
+    pub fn with_bidi(self, bidi: char) -> Self {
+        Self { bidi, ..self }
+    }
 }
 
 /// Look up the italics correction for a glyph.
@@ -1,7 +1,9 @@
 use unicode_math_class::MathClass;
 
 use crate::diag::SourceResult;
-use crate::foundations::{elem, func, Content, NativeElement, Resolve, Smart};
+use crate::foundations::{
+    cast, elem, func, Cast, Content, IntoValue, NativeElement, Resolve, Smart,
+};
 use crate::layout::{Abs, Em, Length, Rel};
 use crate::math::{GlyphFragment, LayoutMath, MathContext, MathFragment, Scaled};
 use crate::text::TextElem;
@@ -15,8 +17,28 @@ pub(super) const DELIM_SHORT_FALL: Em = Em::new(0.1);
 /// unmatched delimiters and to control the delimiter scaling more precisely.
 #[elem(title = "Left/Right", LayoutMath)]
 pub struct LrElem {
-    /// The size of the brackets, relative to the height of the wrapped content.
-    pub size: Smart<Rel<Length>>,
+    /// The size of the brackets.
+    ///
+    /// By default, the brackets grow with the wrapped content. A relative
+    /// length scales them relative to the height of the wrapped content
+    /// instead. Alternatively, one of the fixed size steps known from
+    /// traditional typesetting can be chosen, ignoring the content's height:
+    /// `{"normal"}`, `{"big"}`, `{"Big"}`, `{"bigg"}`, or `{"Bigg"}`.
+    ///
+    /// Since matched delimiters are scaled with this function, a set rule
+    /// applies a sizing policy to all of them. For instance,
+    /// `{set math.lr(size: "normal")}` turns off automatic growth.
+    ///
+    /// ```example
+    /// $ lr((x), size: "big")
+    ///   lr((x), size: "Big")
+    ///   lr((x), size: "bigg")
+    ///   lr((x), size: "Bigg") $
+    ///
+    /// #set math.lr(size: "normal")
+    /// $ (a / b) $
+    /// ```
+    pub size: Smart<LrSize>,
 
     /// The delimited content, including the delimiters.
     #[required]
@@ -51,11 +73,13 @@ impl LayoutMath for LrElem {
             .max()
             .unwrap_or_default();
 
-        let height = self
-            .size(ctx.styles())
-            .unwrap_or(Rel::one())
-            .resolve(ctx.styles())
-            .relative_to(2.0 * max_extent);
+        let height = match self.size(ctx.styles()) {
+            Smart::Auto => 2.0 * max_extent,
+            Smart::Custom(LrSize::Rel(rel)) => {
+                rel.resolve(ctx.styles()).relative_to(2.0 * max_extent)
+            }
+            Smart::Custom(LrSize::Step(step)) => step.height().scaled(ctx),
+        };
 
         match fragments.as_mut_slice() {
             [one] => scale(ctx, one, height, None),
@@ -72,6 +96,57 @@ impl LayoutMath for LrElem {
     }
 }
 
+/// The size of scaled delimiters.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub enum LrSize {
+    /// A size relative to the height of the wrapped content.
+    Rel(Rel<Length>),
+    /// A fixed size step.
+    Step(LrStep),
+}
+
+cast! {
+    LrSize,
+    self => match self {
+        Self::Rel(v) => v.into_value(),
+        Self::Step(v) => v.into_value(),
+    },
+    v: Rel<Length> => Self::Rel(v),
+    v: LrStep => Self::Step(v),
+}
+
+/// A fixed delimiter size step, like TeX's `\big` and friends.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum LrStep {
+    /// The delimiter's natural size.
+    Normal,
+    /// Slightly larger than the natural size.
+    #[string("big")]
+    Big,
+    /// Larger than `{"big"}`.
+    #[string("Big")]
+    BigCap,
+    /// Larger than `{"Big"}`.
+    #[string("bigg")]
+    Bigg,
+    /// The largest step.
+    #[string("Bigg")]
+    BiggCap,
+}
+
+impl LrStep {
+    /// The target height of delimiters of this size.
+    fn height(self) -> Em {
+        Em::new(match self {
+            Self::Normal => 0.0,
+            Self::Big => 1.2,
+            Self::BigCap => 1.8,
+            Self::Bigg => 2.4,
+            Self::BiggCap => 3.0,
+        })
+    }
+}
+
 /// Scale a math fragment to a height.
 fn scale(
     ctx: &mut MathContext,
@@ -109,9 +184,9 @@ fn scale(
 /// ```
 #[func]
 pub fn floor(
-    /// The size of the brackets, relative to the height of the wrapped content.
+    /// The size of the brackets. See [`lr`]($math.lr.size) for details.
     #[named]
-    size: Option<Smart<Rel<Length>>>,
+    size: Option<Smart<LrSize>>,
     /// The expression to floor.
     body: Content,
 ) -> Content {
@@ -125,9 +200,9 @@ pub fn floor(
 /// ```
 #[func]
 pub fn ceil(
-    /// The size of the brackets, relative to the height of the wrapped content.
+    /// The size of the brackets. See [`lr`]($math.lr.size) for details.
     #[named]
-    size: Option<Smart<Rel<Length>>>,
+    size: Option<Smart<LrSize>>,
     /// The expression to ceil.
     body: Content,
 ) -> Content {
@@ -141,9 +216,9 @@ pub fn ceil(
 /// ```
 #[func]
 pub fn round(
-    /// The size of the brackets, relative to the height of the wrapped content.
+    /// The size of the brackets. See [`lr`]($math.lr.size) for details.
     #[named]
-    size: Option<Smart<Rel<Length>>>,
+    size: Option<Smart<LrSize>>,
     /// The expression to round.
     body: Content,
 ) -> Content {
@@ -157,9 +232,9 @@ pub fn round(
 /// ```
 #[func]
 pub fn abs(
-    /// The size of the brackets, relative to the height of the wrapped content.
+    /// The size of the brackets. See [`lr`]($math.lr.size) for details.
     #[named]
-    size: Option<Smart<Rel<Length>>>,
+    size: Option<Smart<LrSize>>,
     /// The expression to take the absolute value of.
     body: Content,
 ) -> Content {
@@ -173,9 +248,9 @@ pub fn abs(
 /// ```
 #[func]
 pub fn norm(
-    /// The size of the brackets, relative to the height of the wrapped content.
+    /// The size of the brackets. See [`lr`]($math.lr.size) for details.
     #[named]
-    size: Option<Smart<Rel<Length>>>,
+    size: Option<Smart<LrSize>>,
     /// The expression to take the norm of.
     body: Content,
 ) -> Content {
@@ -186,7 +261,7 @@ fn delimited(
     body: Content,
     left: char,
     right: char,
-    size: Option<Smart<Rel<Length>>>,
+    size: Option<Smart<LrSize>>,
 ) -> Content {
     let mut elem = LrElem::new(Content::sequence([
         TextElem::packed(left),
@@ -199,6 +199,7 @@ pub fn module() -> Module {
     math.define_func::<inline>();
     math.define_func::<script>();
     math.define_func::<sscript>();
+    math.define_func::<cramped>();
 
     // Text operators, spacings, and symbols.
     op::define(&mut math);
@@ -1,12 +1,15 @@
 use std::iter::once;
 
+use ttf_parser::GlyphId;
+use unicode_bidi::{BidiInfo, Level as BidiLevel};
+use unicode_bidi_mirroring::get_mirrored;
 use unicode_math_class::MathClass;
 
 use crate::foundations::Resolve;
 use crate::layout::{Abs, AlignElem, Em, FixedAlign, Frame, Point, Size};
 use crate::math::{
-    alignments, spacing, AlignmentResult, FrameFragment, MathContext, MathFragment,
-    MathSize, Scaled,
+    alignments, spacing, AlignmentResult, FrameFragment, GlyphFragment, MathContext,
+    MathFragment, MathParItem, MathSize, Scaled,
 };
 use crate::model::ParElem;
 
@@ -146,7 +149,7 @@ impl MathRow {
         align: FixedAlign,
     ) -> Frame {
         if !self.iter().any(|frag| matches!(frag, MathFragment::Linebreak)) {
-            return self.into_line_frame(points, align);
+            return self.into_line_frame(ctx, points, align);
         }
 
         let leading = if ctx.style.size >= MathSize::Text {
@@ -165,7 +168,7 @@ impl MathRow {
         let mut frame = Frame::soft(Size::zero());
 
         for (i, row) in rows.into_iter().enumerate() {
-            let sub = row.into_line_frame(&points, align);
+            let sub = row.into_line_frame(ctx, &points, align);
             let size = frame.size_mut();
             if i > 0 {
                 size.y += leading;
@@ -183,7 +186,78 @@ impl MathRow {
         frame
     }
 
-    fn into_line_frame(self, points: &[Abs], align: FixedAlign) -> Frame {
+    /// Split the row into pieces that a paragraph can break across lines.
+    ///
+    /// Breaks are only possible after fragments for whose class `penalty`
+    /// yields a penalty, and never at the end of the row or in front of
+    /// another relation, operator or punctuation.
+    pub fn into_par_items(
+        self,
+        ctx: &MathContext,
+        penalty: impl Fn(MathClass) -> Option<f64>,
+    ) -> Vec<MathParItem> {
+        let ascent = self.ascent();
+        let descent = self.descent();
+        let finish = |fragments: Vec<MathFragment>| {
+            let mut frame = Self(fragments).into_line_frame(ctx, &[], FixedAlign::Start);
+            frame.translate(Point::with_y(ascent - frame.baseline()));
+            frame.size_mut().y = ascent + descent;
+            frame.set_baseline(ascent);
+            MathParItem::Frame(frame)
+        };
+
+        if self.iter().any(|frag| matches!(frag, MathFragment::Linebreak)) {
+            return vec![finish(self.0)];
+        }
+
+        let mut items = vec![];
+        let mut fragments = vec![];
+        let mut iter = self.0.into_iter().peekable();
+        while let Some(fragment) = iter.next() {
+            let class = fragment.class();
+            fragments.push(fragment);
+
+            let Some(penalty) = class.and_then(&penalty) else { continue };
+
+            // Take the spacing after the fragment into the break.
+            let mut space = Abs::zero();
+            let mut taken = vec![];
+            while let Some(MathFragment::Spacing(amount)) = iter.peek() {
+                space += *amount;
+                taken.extend(iter.next());
+            }
+
+            let breakable = iter.peek().map_or(false, |next| {
+                !matches!(
+                    next.class(),
+                    Some(
+                        MathClass::Relation | MathClass::Binary | MathClass::Punctuation
+                    )
+                )
+            });
+
+            if !breakable {
+                fragments.extend(taken);
+                continue;
+            }
+
+            items.push(finish(std::mem::take(&mut fragments)));
+            items.push(MathParItem::Break(space, penalty));
+        }
+
+        if !fragments.is_empty() || items.is_empty() {
+            items.push(finish(fragments));
+        }
+
+        items
+    }
+
+    fn into_line_frame(
+        self,
+        ctx: &MathContext,
+        points: &[Abs],
+        align: FixedAlign,
+    ) -> Frame {
         let ascent = self.ascent();
         let mut frame = Frame::soft(Size::new(Abs::zero(), ascent + self.descent()));
         frame.set_baseline(ascent);
@@ -224,7 +298,7 @@ impl MathRow {
         };
         let mut x = next_x().unwrap_or_default();
 
-        for fragment in self.0.into_iter() {
+        for fragment in reorder(ctx, self.0) {
             if matches!(fragment, MathFragment::Align) {
                 x = next_x().unwrap_or(x);
                 continue;
@@ -247,6 +321,92 @@ impl<T: Into<MathFragment>> From<T> for MathRow {
     }
 }
 
+/// Return the fragments of a line in visual order, determined by the Unicode
+/// Bidirectional Algorithm.
+///
+/// Formulas are always laid out from left to right, so the base direction is
+/// left-to-right regardless of the text direction and only right-to-left runs,
+/// like Hebrew or Arabic identifiers and the operators between them, are
+/// reversed. Mirrorable characters in such runs, like delimiters, are replaced
+/// by their mirrored counterparts. Alignment points stay in place and each
+/// column between them is reordered on its own.
+fn reorder(ctx: &MathContext, fragments: Vec<MathFragment>) -> Vec<MathFragment> {
+    let mut reordered = Vec::with_capacity(fragments.len());
+    let mut column = vec![];
+    for fragment in fragments {
+        if matches!(fragment, MathFragment::Align) {
+            reorder_column(ctx, &mut reordered, std::mem::take(&mut column));
+            reordered.push(fragment);
+        } else {
+            column.push(fragment);
+        }
+    }
+
+    reorder_column(ctx, &mut reordered, column);
+    reordered
+}
+
+/// Reorder the fragments of a single column into `reordered`.
+fn reorder_column(
+    ctx: &MathContext,
+    reordered: &mut Vec<MathFragment>,
+    column: Vec<MathFragment>,
+) {
+    // Each fragment is represented by a single character, like in a paragraph
+    // where inline objects are represented by the object replacement character.
+    let mut text = String::new();
+    let mut offsets = Vec::with_capacity(column.len());
+    for fragment in &column {
+        offsets.push(text.len());
+        text.push(fragment.bidi());
+    }
+
+    // The bidi crate doesn't like empty text. Columns without right-to-left
+    // text can be kept as they are.
+    let bidi = (!text.is_empty()).then(|| BidiInfo::new(&text, Some(BidiLevel::ltr())));
+    let Some(bidi) = bidi.filter(BidiInfo::has_rtl) else {
+        reordered.extend(column);
+        return;
+    };
+
+    let levels: Vec<BidiLevel> = offsets.iter().map(|&i| bidi.levels[i]).collect();
+    let mut column: Vec<_> = column.into_iter().map(Some).collect();
+    for i in BidiInfo::reorder_visual(&levels) {
+        let fragment = column[i].take().unwrap();
+        reordered.push(if levels[i].is_rtl() { mirror(ctx, fragment) } else { fragment });
+    }
+}
+
+/// Replace a glyph by the glyph of its mirrored character, like an opening
+/// parenthesis by a closing one. Stretched glyphs are stretched again to the
+/// same height.
+fn mirror(ctx: &MathContext, fragment: MathFragment) -> MathFragment {
+    match fragment {
+        MathFragment::Glyph(mut glyph) => {
+            let Some((c, id)) = mirrored(ctx, glyph.c) else { return glyph.into() };
+            glyph.c = c;
+            glyph.set_id(ctx, id);
+            glyph.into()
+        }
+        MathFragment::Variant(variant) => {
+            let Some((c, _)) = mirrored(ctx, variant.c) else { return variant.into() };
+            let mut stretched = GlyphFragment::new(ctx, c, variant.span)
+                .stretch_vertical(ctx, variant.frame.height(), Abs::zero());
+            stretched.class = variant.class;
+            stretched.frame.set_baseline(variant.frame.baseline());
+            stretched.into()
+        }
+        fragment => fragment,
+    }
+}
+
+/// The mirrored character and its glyph in the math font, if the character is
+/// mirrorable and the font has the glyph.
+fn mirrored(ctx: &MathContext, c: char) -> Option<(char, GlyphId)> {
+    let mirrored = get_mirrored(c)?;
+    Some((mirrored, ctx.ttf.glyph_index(mirrored)?))
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum LeftRightAlternator {
     Left,
@@ -215,6 +215,23 @@ pub fn sscript(
         .pack()
 }
 
+/// Forced cramped style in math, keeping the current size.
+///
+/// In cramped style, superscripts are raised less, like in denominators or
+/// under radicals. To leave cramped style again, use one of the size
+/// functions with `{cramped: false}`.
+///
+/// ```example
+/// $ x^2 + cramped(x^2) $
+/// ```
+#[func(title = "Cramped Style")]
+pub fn cramped(
+    /// The content to cramp.
+    body: Content,
+) -> Content {
+    MathStyleElem::new(body).with_cramped(Some(true)).pack()
+}
+
 /// A font variant in math.
 #[elem(LayoutMath)]
 pub struct MathStyleElem {
@@ -0,0 +1,121 @@
+use std::str::FromStr;
+
+use smallvec::smallvec;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Show, Smart, StyleChain};
+use crate::introspection::{Counter, CounterState, CounterUpdate};
+use crate::model::{HeadingElem, Numbering, NumberingPattern, Supplement};
+use crate::text::{Lang, LocalName, Region, TextElem};
+use crate::util::option_eq;
+
+/// A part of the document with its own heading numbering.
+///
+/// The appendix restarts the heading counter and switches the numbering and
+/// supplement of all headings in it. Since outlines and references take the
+/// numbering from each heading, they stay consistent with the rest of the
+/// document. The appendix is typically applied to the rest of the document
+/// with an everything show rule.
+///
+/// # Example
+/// ```example
+/// #set heading(numbering: "1.")
+/// #outline()
+///
+/// = Introduction
+/// The proofs are in @proofs.
+///
+/// #show: appendix
+///
+/// = Proofs <proofs>
+/// == Lemma
+/// ```
+///
+/// # Numbered parts
+/// The same mechanism works for other sections that are numbered on their
+/// own, like the parts of a book:
+///
+/// ```example
+/// #show: appendix.with(
+///   numbering: "I.",
+///   supplement: [Part],
+/// )
+///
+/// = Foundations
+/// ```
+#[elem(Show, LocalName)]
+pub struct AppendixElem {
+    /// How to number the headings in the appendix. Accepts a
+    /// [numbering pattern or function]($numbering).
+    #[borrowed]
+    #[default(Numbering::Pattern(NumberingPattern::from_str("A.1").unwrap()))]
+    pub numbering: Numbering,
+
+    /// A supplement for the headings in the appendix.
+    ///
+    /// When set to `{auto}`, the word "Appendix" in the current
+    /// [text language]($text.lang) is used. Otherwise, this works like the
+    /// heading's [supplement]($heading.supplement).
+    pub supplement: Smart<Option<Supplement>>,
+
+    /// The contents of the appendix.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for AppendixElem {
+    #[tracing::instrument(name = "AppendixElem::show", skip_all)]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let supplement = match self.supplement(styles) {
+            Smart::Auto => {
+                Some(Supplement::Content(TextElem::packed(Self::local_name_in(styles))))
+            }
+            Smart::Custom(supplement) => supplement,
+        };
+
+        // Headings in the appendix are numbered from the start.
+        let reset = Counter::of(HeadingElem::elem())
+            .update(CounterUpdate::Set(CounterState(smallvec![0])));
+
+        Ok(reset
+            + self
+                .body()
+                .clone()
+                .styled(HeadingElem::set_numbering(Some(self.numbering(styles).clone())))
+                .styled(HeadingElem::set_supplement(Smart::Custom(supplement))))
+    }
+}
+
+impl LocalName for AppendixElem {
+    fn local_name(lang: Lang, region: Option<Region>) -> &'static str {
+        match lang {
+            Lang::ALBANIAN => "Shtojca",
+            Lang::ARABIC => "ملحق",
+            Lang::BOKMÅL => "Vedlegg",
+            Lang::CHINESE if option_eq(region, "TW") => "附錄",
+            Lang::CHINESE => "附录",
+            Lang::CZECH => "Příloha",
+            Lang::DANISH => "Bilag",
+            Lang::DUTCH => "Bijlage",
+            Lang::FINNISH => "Liite",
+            Lang::FRENCH => "Annexe",
+            Lang::GERMAN => "Anhang",
+            Lang::GREEK => "Παράρτημα",
+            Lang::HUNGARIAN => "Függelék",
+            Lang::ITALIAN => "Appendice",
+            Lang::NYNORSK => "Vedlegg",
+            Lang::POLISH => "Dodatek",
+            Lang::PORTUGUESE => "Apêndice",
+            Lang::ROMANIAN => "Anexa",
+            Lang::RUSSIAN => "Приложение",
+            Lang::SLOVENIAN => "Dodatek",
+            Lang::SPANISH => "Apéndice",
+            Lang::SWEDISH => "Bilaga",
+            Lang::TURKISH => "Ek",
+            Lang::UKRAINIAN => "Додаток",
+            Lang::JAPANESE => "付録",
+            Lang::ENGLISH | _ => "Appendix",
+        }
+    }
+}
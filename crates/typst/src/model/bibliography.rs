@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
@@ -7,7 +7,7 @@ use std::path::Path;
 use std::sync::Arc;
 
 use comemo::{Prehashed, Tracked};
-use ecow::{eco_format, EcoString, EcoVec};
+use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use hayagriva::archive::ArchivedStyle;
 use hayagriva::io::BibLaTeXError;
 use hayagriva::{
@@ -23,7 +23,7 @@ use crate::diag::{bail, error, At, FileError, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::eval::{eval_string, EvalMode};
 use crate::foundations::{
-    cast, elem, ty, Args, Array, Bytes, CastInfo, Content, Finalize, FromValue,
+    cast, elem, ty, Args, Array, Bytes, CastInfo, Content, Dict, Finalize, FromValue,
     IntoValue, Label, NativeElement, Reflect, Repr, Scope, Show, Smart, Str, StyleChain,
     Synthesize, Type, Value,
 };
@@ -40,7 +40,7 @@ use crate::text::{
     FontStyle, Lang, LocalName, Region, SubElem, SuperElem, TextElem, WeightDelta,
 };
 use crate::util::{option_eq, NonZeroExt, PicoStr};
-use crate::World;
+use crate::{Load, World};
 
 /// A bibliography / reference listing.
 ///
@@ -125,6 +125,28 @@ pub struct BibliographyElem {
     #[default(CslStyle::from_name("ieee").unwrap())]
     pub style: CslStyle,
 
+    /// Annotations to display beneath the bibliography entries.
+    ///
+    /// - When set to `{none}`, no annotations are displayed. This is the
+    ///   default.
+    /// - When set to `{auto}`, the entries' `annote` fields are displayed, or
+    ///   their `note` fields if they have no `annote`. In BibLaTeX files, the
+    ///   `annotation` field is used.
+    /// - When set to a dictionary mapping citation keys to content, that
+    ///   content is displayed for the given entries, and the entries' fields
+    ///   for all others.
+    ///
+    /// ```example
+    /// #bibliography(
+    ///   "works.bib",
+    ///   full: true,
+    ///   annotations: (
+    ///     netwok: [A thorough study of pirate networks.],
+    ///   ),
+    /// )
+    /// ```
+    pub annotations: Option<Smart<Annotations>>,
+
     /// The loaded bibliography.
     #[internal]
     #[required]
@@ -142,6 +164,29 @@ pub struct BibliographyElem {
     pub region: Option<Region>,
 }
 
+/// Annotations for bibliography entries, keyed by citation key.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct Annotations(Vec<(Str, Content)>);
+
+impl Annotations {
+    /// The annotation for the entry with the given key.
+    fn get(&self, key: &str) -> Option<&Content> {
+        self.0.iter().find(|(k, _)| k.as_str() == key).map(|(_, v)| v)
+    }
+}
+
+cast! {
+    Annotations,
+    self => Value::Dict(
+        self.0.into_iter().map(|(k, v)| (k, v.into_value())).collect(),
+    ),
+    v: Dict => Self(
+        v.into_iter()
+            .map(|(k, v)| Ok((k, v.cast()?)))
+            .collect::<StrResult<_>>()?,
+    ),
+}
+
 /// A list of bibliography file paths.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
 pub struct BibliographyPaths(Vec<EcoString>);
@@ -171,9 +216,16 @@ impl BibliographyElem {
 
     /// Whether the bibliography contains the given key.
     pub fn has(engine: &Engine, key: impl Into<PicoStr>) -> bool {
+        Self::contains(engine.introspector, key)
+    }
+
+    /// Whether any bibliography in the document contains the given key.
+    pub fn contains(
+        introspector: Tracked<Introspector>,
+        key: impl Into<PicoStr>,
+    ) -> bool {
         let key = key.into();
-        engine
-            .introspector
+        introspector
             .query(&Self::elem().select())
             .iter()
             .any(|elem| elem.to::<Self>().unwrap().bibliography().has(key))
@@ -200,6 +252,7 @@ impl Synthesize for BibliographyElem {
     fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
         self.push_full(self.full(styles));
         self.push_style(self.style(styles));
+        self.push_annotations(self.annotations(styles));
         self.push_lang(TextElem::lang_in(styles));
         self.push_region(TextElem::region_in(styles));
         Ok(())
@@ -330,7 +383,7 @@ impl Bibliography {
             .iter()
             .map(|path| {
                 let id = span.resolve_path(path).at(span)?;
-                engine.world.file(id).at(span)
+                engine.world.load_file(id).at(span)
             })
             .collect::<SourceResult<Vec<Bytes>>>()?;
 
@@ -430,12 +483,24 @@ fn format_biblatex_error(path: &str, src: &str, errors: Vec<BibLaTeXError>) -> E
     eco_format!("failed to parse BibLaTeX file ({path}:{line}: {msg})")
 }
 
-/// A loaded CSL style.
+/// A loaded citation style.
+///
+/// This is either a CSL style or a [custom style](CitationStyle) provided by
+/// the world.
 #[ty]
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub struct CslStyle {
     name: Option<EcoString>,
-    style: Arc<Prehashed<citationberg::IndependentStyle>>,
+    style: StyleKind,
+}
+
+/// How a citation style is implemented.
+#[derive(Debug, Clone, PartialEq, Hash)]
+enum StyleKind {
+    /// A style defined in CSL and processed by hayagriva.
+    Csl(Arc<Prehashed<citationberg::IndependentStyle>>),
+    /// A style provided by the world.
+    Custom(CustomStyle),
 }
 
 impl CslStyle {
@@ -479,8 +544,13 @@ impl CslStyle {
 
         if ext == "csl" {
             let id = span.resolve_path(string)?;
-            let data = engine.world.file(id)?;
+            let data = engine.world.load_file(id)?;
             CslStyle::from_data(&data)
+        } else if let Some(custom) = engine.world.citation_style(string) {
+            Ok(CslStyle {
+                name: Some(string.into()),
+                style: StyleKind::Custom(custom),
+            })
         } else {
             CslStyle::from_name(string)
         }
@@ -492,7 +562,7 @@ impl CslStyle {
         match hayagriva::archive::ArchivedStyle::by_name(name).map(ArchivedStyle::get) {
             Some(citationberg::Style::Independent(style)) => Ok(Self {
                 name: Some(name.into()),
-                style: Arc::new(Prehashed::new(style)),
+                style: StyleKind::Csl(Arc::new(Prehashed::new(style))),
             }),
             _ => bail!("unknown style: `{name}`"),
         }
@@ -503,13 +573,140 @@ impl CslStyle {
     pub fn from_data(data: &Bytes) -> StrResult<Self> {
         let text = std::str::from_utf8(data.as_slice()).map_err(FileError::from)?;
         citationberg::IndependentStyle::from_xml(text)
-            .map(|style| Self { name: None, style: Arc::new(Prehashed::new(style)) })
+            .map(|style| Self {
+                name: None,
+                style: StyleKind::Csl(Arc::new(Prehashed::new(style))),
+            })
             .map_err(|err| eco_format!("failed to load CSL style ({err})"))
     }
 
-    /// Get the underlying independent style.
-    pub fn get(&self) -> &citationberg::IndependentStyle {
-        self.style.as_ref()
+    /// Get the underlying independent style or `None` if this is a custom
+    /// style.
+    pub fn get(&self) -> Option<&citationberg::IndependentStyle> {
+        match &self.style {
+            StyleKind::Csl(style) => Some(style),
+            StyleKind::Custom(_) => None,
+        }
+    }
+
+    /// Get the underlying custom style or `None` if this is a CSL style.
+    pub fn custom(&self) -> Option<&dyn CitationStyle> {
+        match &self.style {
+            StyleKind::Csl(_) => None,
+            StyleKind::Custom(custom) => Some(custom.get()),
+        }
+    }
+}
+
+/// A citation style that is implemented in Rust instead of CSL.
+///
+/// A [`World`] can provide such styles through [`World::citation_style`].
+/// They can then be selected by name with the `style` parameter of the
+/// bibliography, just like built-in styles. When the bibliography has a custom
+/// style, hayagriva's CSL processor is bypassed and the style sorts,
+/// disambiguates, and renders all citations and references of the document.
+pub trait CitationStyle: Send + Sync {
+    /// Sort the entries of the bibliography.
+    ///
+    /// The entries are passed in the order in which they are first cited,
+    /// followed by the uncited ones if the bibliography is `full`. By default,
+    /// this order is kept.
+    fn sort(&self, entries: &mut [&hayagriva::Entry]) {
+        let _ = entries;
+    }
+
+    /// The label by which an entry is identified in citations, e.g. its
+    /// authors and year.
+    ///
+    /// Entries with the same label are told apart by their
+    /// [disambiguator](CitationData::disambiguator). By default, entries have
+    /// no label and are thus never disambiguated.
+    fn label(&self, entry: &hayagriva::Entry) -> Option<EcoString> {
+        let _ = entry;
+        None
+    }
+
+    /// Render a group of adjacent citations.
+    fn citation(&self, items: &[CitationData]) -> Content;
+
+    /// Render an entry of the bibliography.
+    ///
+    /// Returns an optional prefix that is displayed in a separate column (e.g.
+    /// `[1]`) and the reference itself.
+    fn reference(&self, data: &ReferenceData) -> (Option<Content>, Content);
+
+    /// Whether the bibliography should have hanging indent.
+    fn hanging_indent(&self) -> bool {
+        false
+    }
+}
+
+/// A citation of an entry, as passed to a [`CitationStyle`].
+#[derive(Debug, Copy, Clone)]
+pub struct CitationData<'a> {
+    /// The cited entry.
+    pub entry: &'a hayagriva::Entry,
+    /// The one-based position of the entry in the bibliography.
+    pub number: usize,
+    /// The zero-based index of the entry among all entries with the same
+    /// label or `None` if its label is unique.
+    pub disambiguator: Option<usize>,
+    /// The citation's supplement.
+    pub supplement: Option<&'a Content>,
+    /// The form of the citation or `None` if it is hidden.
+    pub form: Option<CitationForm>,
+    /// Whether this is the first citation of the entry.
+    pub first: bool,
+    /// Whether the directly preceding citation refers to the same entry.
+    pub ibid: bool,
+}
+
+/// An entry of the bibliography, as passed to a [`CitationStyle`].
+#[derive(Debug, Copy, Clone)]
+pub struct ReferenceData<'a> {
+    /// The entry.
+    pub entry: &'a hayagriva::Entry,
+    /// The one-based position of the entry in the bibliography.
+    pub number: usize,
+    /// The zero-based index of the entry among all entries with the same
+    /// label or `None` if its label is unique.
+    pub disambiguator: Option<usize>,
+}
+
+/// A shared handle to a [`CitationStyle`].
+///
+/// Handles are compared by identity. A world should thus return the same
+/// handle for a name across compilations to benefit from caching.
+#[derive(Clone)]
+pub struct CustomStyle(Arc<dyn CitationStyle>);
+
+impl CustomStyle {
+    /// Create a new handle.
+    pub fn new(style: impl CitationStyle + 'static) -> Self {
+        Self(Arc::new(style))
+    }
+
+    /// Get the underlying style.
+    pub fn get(&self) -> &dyn CitationStyle {
+        self.0.as_ref()
+    }
+}
+
+impl Debug for CustomStyle {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad("CustomStyle(..)")
+    }
+}
+
+impl PartialEq for CustomStyle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Hash for CustomStyle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (Arc::as_ptr(&self.0) as *const () as usize).hash(state);
     }
 }
 
@@ -590,8 +787,14 @@ impl Works {
         introspector: Tracked<Introspector>,
     ) -> StrResult<Arc<Self>> {
         let mut generator = Generator::new(world, introspector)?;
-        let rendered = generator.drive();
-        let works = generator.display(&rendered)?;
+        let style = generator.bibliography.style(StyleChain::default());
+        let works = match &style.style {
+            StyleKind::Csl(style) => {
+                let rendered = generator.drive(style);
+                generator.display(&rendered)?
+            }
+            StyleKind::Custom(custom) => generator.drive_custom(custom.get()),
+        };
         Ok(Arc::new(works))
     }
 }
@@ -621,6 +824,13 @@ struct GroupInfo {
     span: Span,
     /// Whether the group should be displayed in a footnote.
     footnote: bool,
+    /// The form of the group's only citation, if it has just one.
+    form: Option<CitationForm>,
+    /// The prefix and suffix of the citation style's layout, with which
+    /// author-suppressed citations are enclosed.
+    enclosing: (EcoString, EcoString),
+    /// The language of the group, which determines how possessives are formed.
+    lang: Lang,
     /// Details about the groups citations.
     subinfos: SmallVec<[CiteInfo; 1]>,
 }
@@ -631,8 +841,8 @@ struct CiteInfo {
     key: Label,
     /// The citation's supplement.
     supplement: Option<Content>,
-    /// Whether this citation was hidden.
-    hidden: bool,
+    /// The citation's form or `None` if it was hidden.
+    form: Option<CitationForm>,
 }
 
 impl<'a> Generator<'a> {
@@ -654,12 +864,14 @@ impl<'a> Generator<'a> {
     }
 
     /// Drives hayagriva's citation driver.
-    fn drive(&mut self) -> hayagriva::Rendered {
+    fn drive(
+        &mut self,
+        bibliography_style: &citationberg::IndependentStyle,
+    ) -> hayagriva::Rendered {
         static LOCALES: Lazy<Vec<citationberg::Locale>> =
             Lazy::new(hayagriva::archive::locales);
 
         let database = self.bibliography.bibliography();
-        let bibliography_style = self.bibliography.style(StyleChain::default());
         let styles = Arena::new();
 
         // Process all citation groups.
@@ -697,8 +909,9 @@ impl<'a> Generator<'a> {
                     )
                 });
 
+                let form = child.form(StyleChain::default());
                 let mut hidden = false;
-                let special_form = match child.form(StyleChain::default()) {
+                let special_form = match form {
                     None => {
                         hidden = true;
                         None
@@ -708,10 +921,16 @@ impl<'a> Generator<'a> {
                     Some(CitationForm::Full) => Some(hayagriva::CitePurpose::Full),
                     Some(CitationForm::Author) => Some(hayagriva::CitePurpose::Author),
                     Some(CitationForm::Year) => Some(hayagriva::CitePurpose::Year),
+                    Some(CitationForm::SuppressAuthor) => {
+                        Some(hayagriva::CitePurpose::Year)
+                    }
+                    Some(CitationForm::Possessive) => {
+                        Some(hayagriva::CitePurpose::Author)
+                    }
                 };
 
                 normal &= special_form.is_none();
-                subinfos.push(CiteInfo { key, supplement, hidden });
+                subinfos.push(CiteInfo { key, supplement, form });
                 items.push(CitationItem::new(entry, locator, None, hidden, special_form));
             }
 
@@ -720,15 +939,36 @@ impl<'a> Generator<'a> {
                 continue;
             }
 
-            let style = match first.style(StyleChain::default()) {
-                Smart::Auto => &bibliography_style.style,
-                Smart::Custom(style) => styles.alloc(style.style),
-            };
+            let style: &citationberg::IndependentStyle =
+                match first.style(StyleChain::default()) {
+                    Smart::Auto => bibliography_style,
+                    Smart::Custom(CslStyle { style: StyleKind::Csl(style), .. }) => {
+                        styles.alloc(style)
+                    }
+                    Smart::Custom(CslStyle { style: StyleKind::Custom(_), .. }) => {
+                        self.failures.insert(
+                            location,
+                            Err(eco_vec![error!(
+                                first.span(),
+                                "custom citation styles can only be used for the \
+                                 whole bibliography"
+                            )]),
+                        );
+                        continue;
+                    }
+                };
 
             self.infos.push(GroupInfo {
                 location,
                 subinfos,
                 span: first.span(),
+                form: if children.len() == 1 {
+                    first.form(StyleChain::default())
+                } else {
+                    None
+                },
+                enclosing: enclosing(&style.citation.layout),
+                lang: *first.lang(),
                 footnote: normal
                     && style.settings.class == citationberg::StyleClass::Note,
             });
@@ -750,7 +990,7 @@ impl<'a> Generator<'a> {
             for entry in database.map.values() {
                 driver.citation(CitationRequest::new(
                     vec![CitationItem::new(entry, None, None, true, None)],
-                    bibliography_style.get(),
+                    bibliography_style,
                     Some(locale.clone()),
                     &LOCALES,
                     None,
@@ -759,7 +999,7 @@ impl<'a> Generator<'a> {
         }
 
         driver.finish(BibliographyRequest {
-            style: bibliography_style.get(),
+            style: bibliography_style,
             locale: Some(locale),
             locale_files: &LOCALES,
         })
@@ -801,12 +1041,26 @@ impl<'a> Generator<'a> {
                 link: &link,
             };
 
-            let content = if info.subinfos.iter().all(|sub| sub.hidden) {
+            let content = if info.subinfos.iter().all(|sub| sub.form.is_none()) {
                 Content::empty()
             } else {
                 let mut content =
                     renderer.display_elem_children(&citation.citation, &mut None);
 
+                match info.form {
+                    Some(CitationForm::SuppressAuthor) => {
+                        let (prefix, suffix) = &info.enclosing;
+                        content = TextElem::packed(prefix.clone())
+                            + content
+                            + TextElem::packed(suffix.clone());
+                    }
+                    Some(CitationForm::Possessive) => {
+                        let suffix = possessive(&content.plain_text(), info.lang);
+                        content += TextElem::packed(suffix);
+                    }
+                    _ => {}
+                }
+
                 if info.footnote {
                     content = FootnoteElem::with_content(content).pack();
                 }
@@ -865,15 +1119,210 @@ impl<'a> Generator<'a> {
             });
 
             // Render the main reference content.
-            let reference = renderer
+            let mut reference = renderer
                 .display_elem_children(&item.content, &mut prefix)
                 .backlinked(backlink);
 
+            // Render the annotation beneath the reference.
+            if let Some(annotation) = self.annotation(&item.key) {
+                reference += BlockElem::new().with_body(Some(annotation)).pack();
+            }
+
             output.push((prefix, reference));
         }
 
         Some(output)
     }
+
+    /// Formats the citations and references with a custom style instead of
+    /// hayagriva's driver.
+    fn drive_custom(&mut self, style: &dyn CitationStyle) -> Works {
+        let database = self.bibliography.bibliography();
+        let mut output = std::mem::take(&mut self.failures);
+
+        // Collect the cited entries in the order of their first citation.
+        let mut entries = vec![];
+        let mut seen = HashSet::new();
+        for elem in &self.groups {
+            let group = elem.to::<CiteGroup>().unwrap();
+            let location = group.location().unwrap();
+            let children = group.children();
+
+            // Groups should never be empty.
+            let Some(first) = children.first() else { continue };
+
+            let mut subinfos = SmallVec::with_capacity(children.len());
+            let mut errors = EcoVec::new();
+
+            if first.style(StyleChain::default()).is_custom() {
+                errors.push(error!(
+                    first.span(),
+                    "cannot change the style of a citation when the bibliography \
+                     uses a custom style"
+                ));
+            }
+
+            for child in children {
+                let key = *child.key();
+                let Some(entry) = database.map.get(&key.into_inner()) else {
+                    errors.push(error!(
+                        child.span(),
+                        "key `{}` does not exist in the bibliography",
+                        key.as_str()
+                    ));
+                    continue;
+                };
+
+                if seen.insert(entry.key()) {
+                    entries.push(entry);
+                }
+
+                let supplement = child.supplement(StyleChain::default());
+                let form = child.form(StyleChain::default());
+                subinfos.push(CiteInfo { key, supplement, form });
+            }
+
+            if !errors.is_empty() {
+                output.insert(location, Err(errors));
+                continue;
+            }
+
+            self.infos.push(GroupInfo {
+                location,
+                subinfos,
+                span: first.span(),
+                form: None,
+                footnote: false,
+            });
+        }
+
+        // Add everything else if we should print the whole bibliography.
+        if self.bibliography.full(StyleChain::default()) {
+            for entry in database.map.values() {
+                if seen.insert(entry.key()) {
+                    entries.push(entry);
+                }
+            }
+        }
+
+        style.sort(&mut entries);
+
+        // Entries whose labels collide are numbered in bibliography order.
+        let mut disambiguators = vec![None; entries.len()];
+        let mut labelled: HashMap<EcoString, Vec<usize>> = HashMap::new();
+        for (i, entry) in entries.iter().enumerate() {
+            if let Some(label) = style.label(entry) {
+                labelled.entry(label).or_default().push(i);
+            }
+        }
+        for indices in labelled.values().filter(|indices| indices.len() > 1) {
+            for (n, &i) in indices.iter().enumerate() {
+                disambiguators[i] = Some(n);
+            }
+        }
+
+        let numbers: HashMap<&str, usize> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (entry.key(), i))
+            .collect();
+
+        // The location of the bibliography. Each reference is assigned a
+        // variant of it, so that citations can link there.
+        let location = self.bibliography.location().unwrap();
+
+        // Render the citation groups.
+        let mut cited = HashSet::new();
+        let mut previous = None;
+        for info in &self.infos {
+            let mut items = Vec::with_capacity(info.subinfos.len());
+            for subinfo in &info.subinfos {
+                let key = subinfo.key.as_str();
+                let i = numbers[key];
+                items.push(CitationData {
+                    entry: entries[i],
+                    number: i + 1,
+                    disambiguator: disambiguators[i],
+                    supplement: subinfo.supplement.as_ref(),
+                    form: subinfo.form,
+                    first: cited.insert(key),
+                    ibid: previous == Some(key),
+                });
+                previous = Some(key);
+            }
+
+            let content = if items.iter().all(|item| item.form.is_none()) {
+                Content::empty()
+            } else {
+                let mut content = style.citation(&items).spanned(info.span);
+                if let [item] = items.as_slice() {
+                    let dest = Destination::Location(location.variant(item.number));
+                    content = content.linked(dest);
+                }
+                content
+            };
+
+            output.insert(info.location, Ok(content));
+        }
+
+        // Determine for each citation key where it first occured, so that we
+        // can link there.
+        let mut first_occurances = HashMap::new();
+        for info in &self.infos {
+            for subinfo in &info.subinfos {
+                first_occurances.entry(subinfo.key.as_str()).or_insert(info.location);
+            }
+        }
+
+        // Render the bibliography.
+        let span = self.bibliography.span();
+        let mut references = vec![];
+        for (i, entry) in entries.iter().enumerate() {
+            let backlink = location.variant(i + 1);
+            let data = ReferenceData {
+                entry,
+                number: i + 1,
+                disambiguator: disambiguators[i],
+            };
+
+            let (prefix, reference) = style.reference(&data);
+            let prefix = prefix.map(|prefix| {
+                let mut prefix = prefix.spanned(span);
+                if let Some(location) = first_occurances.get(entry.key()) {
+                    prefix = prefix.linked(Destination::Location(*location));
+                }
+                prefix.backlinked(backlink)
+            });
+
+            let mut reference = reference.spanned(span).backlinked(backlink);
+            if let Some(annotation) = self.annotation(entry.key()) {
+                reference += BlockElem::new().with_body(Some(annotation)).pack();
+            }
+
+            references.push((prefix, reference));
+        }
+
+        Works {
+            citations: output,
+            references: Some(references),
+            hanging_indent: style.hanging_indent(),
+        }
+    }
+
+    /// The annotation for the entry with the given key, if annotations are
+    /// enabled.
+    fn annotation(&self, key: &str) -> Option<Content> {
+        let annotations = self.bibliography.annotations(StyleChain::default())?;
+        if let Smart::Custom(annotations) = &annotations {
+            if let Some(annotation) = annotations.get(key) {
+                return Some(annotation.clone().spanned(self.bibliography.span()));
+            }
+        }
+
+        let entry = self.bibliography.bibliography().map.get(&PicoStr::new(key))?;
+        let field = entry.annote().or_else(|| entry.note())?;
+        Some(TextElem::packed(field.value.to_str()).spanned(self.bibliography.span()))
+    }
 }
 
 /// Renders hayagriva elements into content.
@@ -1042,6 +1491,44 @@ fn apply_formatting(mut content: Content, format: &hayagriva::Formatting) -> Con
     content
 }
 
+/// The prefix and suffix with which a style encloses its citations, e.g.
+/// parentheses for author-date styles and brackets for numeric ones.
+///
+/// Styles that do not enclose their citations, like note styles, fall back to
+/// parentheses.
+fn enclosing(layout: &citationberg::Layout) -> (EcoString, EcoString) {
+    match (&layout.prefix, &layout.suffix) {
+        (None, None) => ("(".into(), ")".into()),
+        (prefix, suffix) => (
+            prefix.as_deref().unwrap_or_default().into(),
+            suffix.as_deref().unwrap_or_default().into(),
+        ),
+    }
+}
+
+/// The suffix that turns a name into its possessive in the given language.
+///
+/// Languages without a possessive suffix, like French, still use the English
+/// rules, since there is no way to express the form otherwise.
+fn possessive(name: &str, lang: Lang) -> &'static str {
+    let last = name
+        .trim_end()
+        .chars()
+        .last()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let sibilant = matches!(last, 's' | 'x' | 'z' | 'ß');
+    match lang {
+        Lang::GERMAN | Lang::DANISH | Lang::SWEDISH | Lang::NYNORSK if sibilant => "’",
+        Lang::GERMAN | Lang::DANISH | Lang::SWEDISH | Lang::NYNORSK => "s",
+        Lang::DUTCH if sibilant => "’",
+        Lang::DUTCH if matches!(last, 'a' | 'e' | 'i' | 'o' | 'u' | 'y') => "’s",
+        Lang::DUTCH => "s",
+        _ if last == 's' => "’",
+        _ => "’s",
+    }
+}
+
 /// Create a locale code from language and optionally region.
 fn locale(lang: Lang, region: Option<Region>) -> citationberg::LocaleCode {
     let mut value = String::with_capacity(5);
@@ -66,6 +66,18 @@ pub struct CiteElem {
     /// If set to `{none}`, the cited work is included in the bibliography, but
     /// nothing will be displayed.
     ///
+    /// The author-only and author-suppressed forms can be combined to produce
+    /// sentences like "Smith’s study (2020) shows".
+    ///
+    /// ```example
+    /// #cite(<netwok>, form: "possessive")
+    /// study #cite(<netwok>, form: "suppress-author")
+    /// shows the outsized effects of
+    /// pirate life on the human psyche.
+    /// >>> #set text(0pt)
+    /// >>> #bibliography("works.bib", style: "apa")
+    /// ```
+    ///
     /// ```example
     /// #cite(<netwok>, form: "prose")
     /// show the outsized effects of
@@ -129,6 +141,16 @@ pub enum CitationForm {
     Author,
     /// Shows only the cited work's year.
     Year,
+    /// Shows only the cited work's year, enclosed like the style's citations
+    /// (e.g. in parentheses), for sentences that already name the author, like
+    /// "as Smith shows (2020)".
+    SuppressAuthor,
+    /// Shows the cited work's author(s) in the possessive, like "Smith’s".
+    ///
+    /// The suffix follows the [language]($text.lang) of the citation: In
+    /// English, it is "’s", or just "’" if the author ends with an "s", while
+    /// German and the Scandinavian languages append a plain "s".
+    Possessive,
 }
 
 /// A group of citations.
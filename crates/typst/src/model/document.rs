@@ -4,7 +4,8 @@ use ecow::EcoString;
 use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Args, Array, Construct, Content, Datetime, Smart, StyleChain, Value,
+    cast, elem, func, Args, Array, Cast, Construct, Content, Datetime, Smart, StyleChain,
+    Styles, Value,
 };
 use crate::introspection::ManualPageCounter;
 use crate::layout::{Frame, LayoutRoot, PageElem};
@@ -50,24 +51,74 @@ pub struct DocumentElem {
     /// PDF.
     pub date: Smart<Option<Datetime>>,
 
+    /// The export target the document is compiled for.
+    ///
+    /// This is normally set by the host, e.g. from the output format passed to
+    /// the CLI, and can be read with the [`target`]($target) function. Setting
+    /// it yourself lets you preview how the document looks for another target.
+    pub target: Target,
+
     /// The page runs.
     #[variadic]
     pub children: Vec<Prehashed<Content>>,
 }
 
+/// An export target for a document.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Target {
+    /// A PDF file.
+    #[default]
+    Pdf,
+    /// PNG images.
+    Png,
+    /// SVG images.
+    Svg,
+    /// An HTML page.
+    Html,
+    /// An EPUB publication.
+    Epub,
+}
+
+/// Determines the export target of the document.
+///
+/// Returns one of `{"pdf"}`, `{"png"}`, `{"svg"}`, `{"html"}`, or `{"epub"}`.
+/// This makes it possible to include content only in some outputs, e.g.
+/// clickable links in digital formats and printed URLs otherwise.
+///
+/// ```example
+/// #style(styles => {
+///   if target(styles) == "html" [
+///     Visit our #link("https://typst.app")[website].
+///   ] else [
+///     Visit typst.app.
+///   ]
+/// })
+/// ```
+#[func]
+pub fn target(
+    /// The styles in which to look up the target. Can be retrieved with the
+    /// [`style`]($style) function.
+    styles: Styles,
+) -> Target {
+    DocumentElem::target_in(StyleChain::new(&styles))
+}
+
 impl Construct for DocumentElem {
     fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
         bail!(args.span, "can only be used in set rules")
     }
 }
 
-impl LayoutRoot for DocumentElem {
-    /// Layout the document into a sequence of frames, one per page.
-    #[tracing::instrument(name = "DocumentElem::layout_root", skip_all)]
-    fn layout_root(
+impl DocumentElem {
+    /// Layout the document into a sequence of frames, one per page, handing
+    /// out each page to `emit` as soon as the run of pages it belongs to is
+    /// finished.
+    #[tracing::instrument(name = "DocumentElem::layout_pages", skip_all)]
+    pub fn layout_pages(
         &self,
         engine: &mut Engine,
         styles: StyleChain,
+        emit: &mut dyn FnMut(&Frame),
     ) -> SourceResult<Document> {
         tracing::info!("Document layout");
 
@@ -94,7 +145,10 @@ impl LayoutRoot for DocumentElem {
                 });
                 let fragment =
                     page.layout(engine, styles, &mut page_counter, extend_to)?;
-                pages.extend(fragment);
+                for frame in fragment {
+                    emit(&frame);
+                    pages.push(frame);
+                }
             } else {
                 bail!(child.span(), "unexpected document child");
             }
@@ -102,14 +156,27 @@ impl LayoutRoot for DocumentElem {
 
         Ok(Document {
             pages,
-            title: self.title(styles).map(|content| content.plain_text()),
-            author: self.author(styles).0,
-            keywords: self.keywords(styles).0,
-            date: self.date(styles),
+            info: DocumentInfo {
+                title: self.title(styles).map(|content| content.plain_text()),
+                author: self.author(styles).0,
+                keywords: self.keywords(styles).0,
+                date: self.date(styles),
+            },
         })
     }
 }
 
+impl LayoutRoot for DocumentElem {
+    /// Layout the document into a sequence of frames, one per page.
+    fn layout_root(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<Document> {
+        self.layout_pages(engine, styles, &mut |_| {})
+    }
+}
+
 /// A list of authors.
 #[derive(Debug, Default, Clone, PartialEq, Hash)]
 pub struct Author(Vec<EcoString>);
@@ -137,6 +204,13 @@ cast! {
 pub struct Document {
     /// The page frames.
     pub pages: Vec<Frame>,
+    /// The document's metadata.
+    pub info: DocumentInfo,
+}
+
+/// The metadata of a finished document.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct DocumentInfo {
     /// The document's title.
     pub title: Option<EcoString>,
     /// The document's author.
@@ -276,7 +276,7 @@ impl Layout for EnumElem {
             regions,
             styles,
             self.span(),
-        );
+        )?;
 
         Ok(layouter.layout(engine)?.fragment)
     }
@@ -13,7 +13,9 @@ use crate::foundations::{
 use crate::introspection::{
     Count, Counter, CounterKey, CounterUpdate, Locatable, Location,
 };
-use crate::layout::{Align, BlockElem, Em, HAlign, Length, PlaceElem, VAlign, VElem};
+use crate::layout::{
+    Align, BlockElem, Em, HAlign, Length, PlaceElem, Role, TagElem, VAlign, VElem,
+};
 use crate::model::{Numbering, NumberingPattern, Outlinable, Refable, Supplement};
 use crate::syntax::Spanned;
 use crate::text::{Lang, Region, TextElem};
@@ -314,9 +316,9 @@ impl Show for FigureElem {
             };
         }
 
-        // Wrap the contents in a block.
+        // Wrap the contents in a tagged block.
         realized = BlockElem::new()
-            .with_body(Some(realized))
+            .with_body(Some(TagElem::new(Role::Figure, realized).pack()))
             .pack()
             .aligned(Align::CENTER);
 
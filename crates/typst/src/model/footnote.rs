@@ -75,6 +75,19 @@ pub struct FootnoteElem {
     /// footnote this one should point to.
     #[required]
     pub body: FootnoteBody,
+
+    /// Whether this footnote is a repeated copy of another one, for instance
+    /// in an outline entry for a figure caption. Such footnotes are neither
+    /// displayed nor counted and produce no entry.
+    #[internal]
+    #[default(false)]
+    pub suppressed: bool,
+
+    /// Whether this footnote is listed in a block of notes below its
+    /// enclosing table instead of in the page's footnote area.
+    #[internal]
+    #[default(false)]
+    pub endnote: bool,
 }
 
 #[scope]
@@ -126,6 +139,8 @@ impl FootnoteElem {
 impl Synthesize for FootnoteElem {
     fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
         self.push_numbering(self.numbering(styles).clone());
+        self.push_suppressed(self.suppressed(styles));
+        self.push_endnote(self.endnote(styles));
         Ok(())
     }
 }
@@ -133,6 +148,10 @@ impl Synthesize for FootnoteElem {
 impl Show for FootnoteElem {
     #[tracing::instrument(name = "FootnoteElem::show", skip_all)]
     fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        if self.suppressed(styles) {
+            return Ok(Content::empty());
+        }
+
         Ok(engine.delayed(|engine| {
             let loc = self.declaration_location(engine).at(self.span())?;
             let numbering = self.numbering(styles);
@@ -148,7 +167,8 @@ impl Show for FootnoteElem {
 
 impl Count for FootnoteElem {
     fn update(&self) -> Option<CounterUpdate> {
-        (!self.is_ref()).then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+        (!self.is_ref() && !self.suppressed(StyleChain::default()))
+            .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
     }
 }
 
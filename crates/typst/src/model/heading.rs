@@ -7,7 +7,7 @@ use crate::foundations::{
     Synthesize,
 };
 use crate::introspection::{Count, Counter, CounterUpdate, Locatable};
-use crate::layout::{BlockElem, Em, HElem, VElem};
+use crate::layout::{BlockElem, Em, HElem, Role, TagElem, VElem};
 use crate::model::{Numbering, Outlinable, Refable, Supplement};
 use crate::text::{FontWeight, Lang, LocalName, Region, SpaceElem, TextElem, TextSize};
 use crate::util::{option_eq, NonZeroExt};
@@ -162,7 +162,8 @@ impl Show for HeadingElem {
                 + HElem::new(Em::new(0.3).into()).with_weak(true).pack()
                 + realized;
         }
-        Ok(BlockElem::new().with_body(Some(realized)).pack())
+        let tagged = TagElem::new(Role::Heading(self.level(styles)), realized).pack();
+        Ok(BlockElem::new().with_body(Some(tagged)).pack())
     }
 }
 
@@ -149,6 +149,12 @@ pub enum Destination {
     Position(Position),
     /// An unresolved link to a location in the document.
     Location(Location),
+    /// A link to a point on a page of another document.
+    ///
+    /// Documents that are compiled together with
+    /// [`compile_project`](crate::compile_project) can link to each other.
+    /// The index is that of the target document among them.
+    Document(usize, Position),
 }
 
 impl Repr for Destination {
@@ -163,6 +169,7 @@ cast! {
         Self::Url(v) => v.into_value(),
         Self::Position(v) => v.into_value(),
         Self::Location(v) => v.into_value(),
+        Self::Document(_, v) => v.into_value(),
     },
     v: EcoString => Self::Url(v),
     v: Position => Self::Position(v),
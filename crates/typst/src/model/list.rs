@@ -171,7 +171,7 @@ impl Layout for ListElem {
             regions,
             styles,
             self.span(),
-        );
+        )?;
 
         Ok(layouter.layout(engine)?.fragment)
     }
@@ -0,0 +1,143 @@
+use std::num::NonZeroUsize;
+
+use crate::diag::{bail, error, At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Content, Finalize, Func, LocatableSelector, NativeElement, Show, StyleChain,
+};
+use crate::layout::RepeatElem;
+use crate::model::{FootnoteElem, HeadingElem, Outlinable, OutlineEntry, ParbreakElem};
+use crate::text::{LinebreakElem, TextElem};
+use crate::util::NonZeroExt;
+
+/// A list of arbitrary elements in the document.
+///
+/// Like an [outline]($outline), a listing collects all elements matching a
+/// selector and displays them with their page numbers, linking each entry to
+/// its element. In contrast to the outline, the elements need not be
+/// outlinable: With the `entry` function, you decide how each of them is
+/// displayed. This makes it possible to list theorems, code listings,
+/// requirements, or anything else that can be queried.
+///
+/// Each entry is an [`outline.entry`]($outline.entry), so show rules for
+/// outline entries also apply to listings.
+///
+/// # Example
+/// ```example
+/// #let req(id, body) = figure(
+///   body,
+///   kind: "req",
+///   supplement: [Requirement],
+///   caption: id,
+/// )
+///
+/// #listing(
+///   figure.where(kind: "req"),
+///   title: [Requirements],
+///   entry: it => [#it.caption.body],
+/// )
+///
+/// #req[R1][The system shall start.]
+/// #req[R2][The system shall stop.]
+/// ```
+#[elem(Show, Finalize)]
+pub struct ListingElem {
+    /// The elements to list.
+    ///
+    /// The selector must match [locatable]($locate) elements, such as
+    /// headings, figures, equations, or metadata.
+    #[required]
+    pub target: LocatableSelector,
+
+    /// The title of the listing. When set to `{none}`, the listing does not
+    /// have a title.
+    pub title: Option<Content>,
+
+    /// How to display an element in its entry.
+    ///
+    /// The function receives the element and returns the content to display
+    /// before the page number. If it returns `{none}`, the element is left out
+    /// of the listing.
+    ///
+    /// When this is `{none}`, outlinable elements are displayed as in an
+    /// outline and other elements with their `body` field.
+    pub entry: Option<Func>,
+
+    /// Content to fill the space between an entry and its page number. Can be
+    /// set to `none` to disable filling.
+    #[default(Some(RepeatElem::new(TextElem::packed(".")).pack()))]
+    pub fill: Option<Content>,
+}
+
+impl Show for ListingElem {
+    #[tracing::instrument(name = "ListingElem::show", skip_all)]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::new().pack()];
+        if let Some(title) = self.title(styles) {
+            seq.push(HeadingElem::new(title).with_level(NonZeroUsize::ONE).pack());
+        }
+
+        let entry = self.entry(styles);
+        let elems = engine.introspector.query(&self.target().0);
+        for elem in &elems {
+            let elem = elem.clone().into_inner();
+            let body = match &entry {
+                Some(func) => func
+                    .call(engine, [elem.clone()])?
+                    .cast::<Option<Content>>()
+                    .at(self.span())?,
+                None => self.default_body(engine, &elem)?,
+            };
+
+            let Some(body) = body else { continue };
+            let level = elem
+                .with::<dyn Outlinable>()
+                .map_or(NonZeroUsize::ONE, |outlinable| outlinable.level());
+
+            let entry =
+                OutlineEntry::locate(engine, level, elem, body, self.fill(styles))?;
+
+            // Footnotes in the entry were already placed at the listed
+            // element, so they must not be repeated here.
+            seq.push(entry.pack().styled(FootnoteElem::set_suppressed(true)));
+            seq.push(LinebreakElem::new().pack());
+        }
+
+        seq.push(ParbreakElem::new().pack());
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl ListingElem {
+    /// The body of an element's entry if no `entry` function is given.
+    fn default_body(
+        &self,
+        engine: &mut Engine,
+        elem: &Content,
+    ) -> SourceResult<Option<Content>> {
+        if let Some(outlinable) = elem.with::<dyn Outlinable>() {
+            if let Some(body) = outlinable.outline(engine)? {
+                return Ok(Some(body));
+            }
+        }
+
+        match elem.field_by_name("body") {
+            Ok(body) => Ok(Some(body.display())),
+            Err(_) => bail!(error!(
+                self.span(),
+                "cannot list {} without an entry function",
+                elem.func().name()
+            )
+            .with_hint("specify how to display the element with `entry`")),
+        }
+    }
+}
+
+impl Finalize for ListingElem {
+    fn finalize(&self, realized: Content, _: StyleChain) -> Content {
+        realized
+            .styled(HeadingElem::set_outlined(false))
+            .styled(HeadingElem::set_numbering(None))
+    }
+}
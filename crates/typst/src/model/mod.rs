@@ -1,5 +1,6 @@
 //! Structuring elements that define the document model.
 
+mod appendix;
 mod bibliography;
 mod cite;
 mod document;
@@ -11,16 +12,20 @@ mod footnote;
 mod heading;
 mod link;
 mod list;
+mod listing;
 #[path = "numbering.rs"]
 mod numbering_;
 mod outline;
 mod par;
 mod quote;
 mod reference;
+mod review;
 mod strong;
 mod table;
 mod terms;
+mod theorem;
 
+pub use self::appendix::*;
 pub use self::bibliography::*;
 pub use self::cite::*;
 pub use self::document::*;
@@ -31,14 +36,17 @@ pub use self::footnote::*;
 pub use self::heading::*;
 pub use self::link::*;
 pub use self::list::*;
+pub use self::listing::*;
 pub use self::numbering_::*;
 pub use self::outline::*;
 pub use self::par::*;
 pub use self::quote::*;
 pub use self::reference::*;
+pub use self::review::*;
 pub use self::strong::*;
 pub use self::table::*;
 pub use self::terms::*;
+pub use self::theorem::*;
 
 use crate::foundations::{category, Category, Scope};
 
@@ -54,13 +62,20 @@ pub static MODEL: Category;
 pub fn define(global: &mut Scope) {
     global.category(MODEL);
     global.define_elem::<DocumentElem>();
+    global.define_func::<target>();
     global.define_elem::<RefElem>();
     global.define_elem::<LinkElem>();
     global.define_elem::<OutlineElem>();
+    global.define_elem::<ListingElem>();
     global.define_elem::<HeadingElem>();
+    global.define_elem::<AppendixElem>();
     global.define_elem::<FigureElem>();
+    global.define_elem::<TheoremElem>();
     global.define_elem::<FootnoteElem>();
     global.define_elem::<QuoteElem>();
+    global.define_elem::<InsElem>();
+    global.define_elem::<DelElem>();
+    global.define_elem::<CommentElem>();
     global.define_elem::<CiteElem>();
     global.define_elem::<BibliographyElem>();
     global.define_elem::<EnumElem>();
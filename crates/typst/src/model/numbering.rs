@@ -4,11 +4,11 @@ use std::str::FromStr;
 use chinese_number::{ChineseCase, ChineseCountMethod, ChineseVariant, NumberToChinese};
 use ecow::{eco_format, EcoString, EcoVec};
 
-use crate::diag::SourceResult;
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
-use crate::foundations::{cast, func, Func, Str, Value};
+use crate::foundations::{cast, func, scope, Func, Str, Value};
 use crate::layout::{PdfPageLabel, PdfPageLabelStyle};
-use crate::text::Case;
+use crate::text::{Case, Lang};
 
 /// Applies a numbering to a sequence of numbers.
 ///
@@ -33,7 +33,15 @@ use crate::text::Case;
 ///   1, 2, 3,
 /// )
 /// ```
-#[func]
+///
+/// The helpers in the numbering function's scope convert individual numbers
+/// directly, without a pattern or counter.
+///
+/// ```example
+/// #numbering.roman(1994, upper: true) \
+/// #numbering.words(21, ordinal: true)
+/// ```
+#[func(scope)]
 pub fn numbering(
     /// The engine.
     engine: &mut Engine,
@@ -71,6 +79,87 @@ pub fn numbering(
     numbering.apply(engine, &numbers)
 }
 
+#[scope]
+impl numbering {
+    /// Converts a number into a roman numeral.
+    ///
+    /// This is the same as applying the numbering pattern `{"i"}` or `{"I"}`.
+    ///
+    /// ```example
+    /// #numbering.roman(14) \
+    /// #numbering.roman(2024, upper: true)
+    /// ```
+    #[func]
+    pub fn roman(
+        /// The number to convert. Must be positive.
+        number: usize,
+        /// Whether to use uppercase letters.
+        #[named]
+        #[default(false)]
+        upper: bool,
+    ) -> Str {
+        NumberingKind::Roman.apply(number, case(upper)).into()
+    }
+
+    /// Converts a number into a sequence of letters.
+    ///
+    /// After `z` comes `aa`, like for the columns of a spreadsheet. This is
+    /// the same as applying the numbering pattern `{"a"}` or `{"A"}`.
+    ///
+    /// ```example
+    /// #numbering.letter(3) \
+    /// #numbering.letter(28, upper: true)
+    /// ```
+    #[func]
+    pub fn letter(
+        /// The number to convert. Must be positive.
+        number: usize,
+        /// Whether to use uppercase letters.
+        #[named]
+        #[default(false)]
+        upper: bool,
+    ) -> Str {
+        NumberingKind::Letter.apply(number, case(upper)).into()
+    }
+
+    /// Spells out a number in words.
+    ///
+    /// Supports cardinal and ordinal numbers up to 999 999 999 999 in English,
+    /// German, and French.
+    ///
+    /// ```example
+    /// #numbering.words(42) \
+    /// #numbering.words(3, ordinal: true) \
+    /// #numbering.words(1984, locale: "de") \
+    /// #numbering.words(80, locale: "fr")
+    /// ```
+    #[func]
+    pub fn words(
+        /// The number to spell out.
+        number: i64,
+        /// The language to spell the number in.
+        #[named]
+        #[default(Lang::ENGLISH)]
+        locale: Lang,
+        /// Whether to spell out the ordinal instead of the cardinal number,
+        /// e.g. "third" instead of "three". Ordinals must be positive.
+        #[named]
+        #[default(false)]
+        ordinal: bool,
+    ) -> StrResult<Str> {
+        spell(number, locale, ordinal).map(Into::into)
+    }
+}
+
+/// The case of a numbering helper's output.
+fn case(upper: bool) -> Case {
+    if upper {
+        Case::Upper
+    } else {
+        Case::Lower
+    }
+}
+
 /// How to number a sequence of things.
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum Numbering {
@@ -556,3 +645,375 @@ fn zeroless<const N_DIGITS: usize>(
     }
     cs.into_iter().rev().collect()
 }
+
+/// The largest number that can be spelled out.
+const SPELL_MAX: u64 = 999_999_999_999;
+
+/// Spell out a number in the given language.
+fn spell(number: i64, lang: Lang, ordinal: bool) -> StrResult<EcoString> {
+    let minus = match lang {
+        Lang::ENGLISH | Lang::GERMAN => "minus",
+        Lang::FRENCH => "moins",
+        _ => bail!("cannot spell out numbers in this language"),
+    };
+
+    if ordinal && number < 1 {
+        bail!("number must be positive for ordinals");
+    }
+
+    let n = number.unsigned_abs();
+    if n > SPELL_MAX {
+        bail!("number is too large to spell out");
+    }
+
+    let words = match (lang, ordinal) {
+        (Lang::GERMAN, false) => german_cardinal(n),
+        (Lang::GERMAN, true) => german_ordinal(n),
+        (Lang::FRENCH, false) => french_cardinal(n),
+        (Lang::FRENCH, true) => french_ordinal(n),
+        (_, false) => english_cardinal(n),
+        (_, true) => english_ordinal(n),
+    };
+
+    Ok(if number < 0 { eco_format!("{minus} {words}") } else { words })
+}
+
+/// Split a number into its non-zero groups of three digits, from the highest
+/// to the lowest, together with their scale (0 for the units, 1 for the
+/// thousands, 2 for the millions, and so on).
+fn groups(mut n: u64) -> Vec<(u64, usize)> {
+    let mut groups = vec![];
+    let mut scale = 0;
+    while n > 0 {
+        if n % 1000 != 0 {
+            groups.push((n % 1000, scale));
+        }
+        n /= 1000;
+        scale += 1;
+    }
+    groups.reverse();
+    groups
+}
+
+/// Split a spelled-out number in front of its last word.
+fn split_last(words: &str) -> (&str, &str) {
+    let at = words.rfind(|c| c == ' ' || c == '-').map_or(0, |i| i + 1);
+    words.split_at(at)
+}
+
+const EN_ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const EN_TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const EN_SCALES: [&str; 4] = ["", "thousand", "million", "billion"];
+
+/// Spell out an English cardinal number, e.g. "one hundred twenty-three".
+fn english_cardinal(n: u64) -> EcoString {
+    if n == 0 {
+        return EN_ONES[0].into();
+    }
+
+    let mut parts: Vec<EcoString> = vec![];
+    for (group, scale) in groups(n) {
+        let (hundreds, rest) = ((group / 100) as usize, (group % 100) as usize);
+        if hundreds > 0 {
+            parts.push(eco_format!("{} hundred", EN_ONES[hundreds]));
+        }
+        if rest >= 20 && rest % 10 > 0 {
+            parts.push(eco_format!("{}-{}", EN_TENS[rest / 10], EN_ONES[rest % 10]));
+        } else if rest >= 20 {
+            parts.push(EN_TENS[rest / 10].into());
+        } else if rest > 0 {
+            parts.push(EN_ONES[rest].into());
+        }
+        if scale > 0 {
+            parts.push(EN_SCALES[scale].into());
+        }
+    }
+
+    parts.join(" ").into()
+}
+
+/// Spell out an English ordinal number, e.g. "one hundred twenty-third".
+fn english_ordinal(n: u64) -> EcoString {
+    let cardinal = english_cardinal(n);
+    let (head, last) = split_last(&cardinal);
+    match last {
+        "one" => eco_format!("{head}first"),
+        "two" => eco_format!("{head}second"),
+        "three" => eco_format!("{head}third"),
+        "five" => eco_format!("{head}fifth"),
+        "eight" => eco_format!("{head}eighth"),
+        "nine" => eco_format!("{head}ninth"),
+        "twelve" => eco_format!("{head}twelfth"),
+        _ => match last.strip_suffix('y') {
+            Some(stem) => eco_format!("{head}{stem}ieth"),
+            None => eco_format!("{head}{last}th"),
+        },
+    }
+}
+
+const DE_ONES: [&str; 20] = [
+    "null",
+    "eins",
+    "zwei",
+    "drei",
+    "vier",
+    "fünf",
+    "sechs",
+    "sieben",
+    "acht",
+    "neun",
+    "zehn",
+    "elf",
+    "zwölf",
+    "dreizehn",
+    "vierzehn",
+    "fünfzehn",
+    "sechzehn",
+    "siebzehn",
+    "achtzehn",
+    "neunzehn",
+];
+
+const DE_TENS: [&str; 10] = [
+    "", "", "zwanzig", "dreißig", "vierzig", "fünfzig", "sechzig", "siebzig", "achtzig",
+    "neunzig",
+];
+
+const DE_SCALES: [(&str, &str); 2] =
+    [("Million", "Millionen"), ("Milliarde", "Milliarden")];
+
+/// Spell out a German cardinal number, e.g. "einhundertdreiundzwanzig".
+fn german_cardinal(n: u64) -> EcoString {
+    if n == 0 {
+        return DE_ONES[0].into();
+    }
+    german_parts(n).join(" ").into()
+}
+
+/// Spell out a German ordinal number, e.g. "einhundertdreiundzwanzigste".
+fn german_ordinal(n: u64) -> EcoString {
+    // Ordinals below twenty have their own forms, all others append "ste" to
+    // the cardinal number.
+    let rest = n % 100;
+    let (base, suffix): (u64, EcoString) = match rest {
+        1 => (n - 1, "erste".into()),
+        3 => (n - 3, "dritte".into()),
+        7 => (n - 7, "siebte".into()),
+        8 => (n - 8, "achte".into()),
+        2..=19 => (n - rest, eco_format!("{}te", DE_ONES[rest as usize])),
+        _ => (n, "ste".into()),
+    };
+
+    let mut parts = german_parts(base);
+
+    // A trailing million or billion becomes part of the ordinal's word, as in
+    // "zweimillionste".
+    if base % 1_000_000 == 0 {
+        if let Some(&(group, scale)) = groups(base).last() {
+            parts.pop();
+            let (one, _) = DE_SCALES[scale - 2];
+            parts.push(eco_format!("{}{}", german_compound(group), one.to_lowercase()));
+        }
+    }
+
+    match parts.last_mut() {
+        Some(last) => last.push_str(&suffix),
+        None => parts.push(suffix),
+    }
+
+    parts.join(" ").into()
+}
+
+/// Spell out a German number as words, where the millions and billions are
+/// separate words and the rest of the number is a single one.
+fn german_parts(n: u64) -> Vec<EcoString> {
+    let mut parts = vec![];
+    let mut word = EcoString::new();
+    for (group, scale) in groups(n) {
+        if scale >= 2 {
+            let (one, many) = DE_SCALES[scale - 2];
+            parts.push(if group == 1 {
+                eco_format!("eine {one}")
+            } else {
+                eco_format!("{} {many}", german_compound(group))
+            });
+        } else if scale == 1 {
+            word.push_str(&german_compound(group));
+            word.push_str("tausend");
+        } else {
+            word.push_str(&german_below_thousand(group));
+        }
+    }
+
+    if !word.is_empty() {
+        parts.push(word);
+    }
+
+    parts
+}
+
+/// Spell out a German number below one thousand.
+fn german_below_thousand(n: u64) -> EcoString {
+    let (hundreds, rest) = ((n / 100) as usize, (n % 100) as usize);
+    let mut word = EcoString::new();
+    if hundreds > 0 {
+        word.push_str(german_unit(hundreds));
+        word.push_str("hundert");
+    }
+    if rest >= 20 {
+        if rest % 10 > 0 {
+            word.push_str(german_unit(rest % 10));
+            word.push_str("und");
+        }
+        word.push_str(DE_TENS[rest / 10]);
+    } else if rest > 0 {
+        word.push_str(DE_ONES[rest]);
+    }
+    word
+}
+
+/// Spell out a German number below one thousand that precedes another word,
+/// where a trailing "eins" becomes "ein".
+fn german_compound(n: u64) -> EcoString {
+    let mut word = german_below_thousand(n);
+    if word.ends_with("eins") {
+        word.pop();
+    }
+    word
+}
+
+/// The form of a German unit inside a compound word.
+fn german_unit(n: usize) -> &'static str {
+    if n == 1 {
+        "ein"
+    } else {
+        DE_ONES[n]
+    }
+}
+
+const FR_ONES: [&str; 17] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+    "dix", "onze", "douze", "treize", "quatorze", "quinze", "seize",
+];
+
+const FR_TENS: [&str; 7] =
+    ["", "dix", "vingt", "trente", "quarante", "cinquante", "soixante"];
+
+const FR_SCALES: [(&str, &str); 2] = [("million", "millions"), ("milliard", "milliards")];
+
+/// Spell out a French cardinal number, e.g. "cent vingt-trois".
+fn french_cardinal(n: u64) -> EcoString {
+    if n == 0 {
+        return FR_ONES[0].into();
+    }
+
+    let mut parts = vec![];
+    for (group, scale) in groups(n) {
+        match scale {
+            0 => parts.push(french_below_thousand(group, true)),
+            1 => {
+                // "Mille" is invariable and takes no "un".
+                if group > 1 {
+                    parts.push(french_below_thousand(group, false));
+                }
+                parts.push("mille".into());
+            }
+            _ => {
+                let (one, many) = FR_SCALES[scale - 2];
+                parts.push(french_below_thousand(group, true));
+                parts.push(if group == 1 { one } else { many }.into());
+            }
+        }
+    }
+
+    parts.join(" ").into()
+}
+
+/// Spell out a French ordinal number, e.g. "cent vingt-troisième".
+fn french_ordinal(n: u64) -> EcoString {
+    if n == 1 {
+        return "premier".into();
+    }
+
+    let cardinal = french_cardinal(n);
+    let (head, last) = split_last(&cardinal);
+    let last = match last {
+        "cents" | "vingts" | "millions" | "milliards" => &last[..last.len() - 1],
+        _ => last,
+    };
+    let stem = match last {
+        "cinq" => "cinqu",
+        "neuf" => "neuv",
+        _ => last.strip_suffix('e').unwrap_or(last),
+    };
+    eco_format!("{head}{stem}ième")
+}
+
+/// Spell out a French number below one thousand.
+///
+/// The "s" of "cents" and "quatre-vingts" is only kept if `plural` is true,
+/// i.e. when no number and no "mille" follows.
+fn french_below_thousand(n: u64, plural: bool) -> EcoString {
+    let (hundreds, rest) = ((n / 100) as usize, n % 100);
+    let mut word = EcoString::new();
+    if hundreds > 0 {
+        if hundreds > 1 {
+            word.push_str(FR_ONES[hundreds]);
+            word.push(' ');
+        }
+        word.push_str("cent");
+        if hundreds > 1 && rest == 0 && plural {
+            word.push('s');
+        }
+        if rest > 0 {
+            word.push(' ');
+        }
+    }
+    if rest == 80 && !plural {
+        word.push_str("quatre-vingt");
+    } else if rest > 0 {
+        word.push_str(&french_below_hundred(rest));
+    }
+    word
+}
+
+/// Spell out a French number below one hundred.
+fn french_below_hundred(n: u64) -> EcoString {
+    let (tens, unit) = ((n / 10) as usize, (n % 10) as usize);
+    match n {
+        0..=16 => FR_ONES[n as usize].into(),
+        17..=19 => eco_format!("dix-{}", FR_ONES[unit]),
+        20..=69 if unit == 0 => FR_TENS[tens].into(),
+        20..=69 if unit == 1 => eco_format!("{} et un", FR_TENS[tens]),
+        20..=69 => eco_format!("{}-{}", FR_TENS[tens], FR_ONES[unit]),
+        71 => "soixante et onze".into(),
+        70..=79 => eco_format!("soixante-{}", french_below_hundred(n - 60)),
+        80 => "quatre-vingts".into(),
+        _ => eco_format!("quatre-vingt-{}", french_below_hundred(n - 80)),
+    }
+}
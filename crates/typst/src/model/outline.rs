@@ -9,7 +9,9 @@ use crate::foundations::{
 };
 use crate::introspection::{Counter, CounterKey, Locatable};
 use crate::layout::{BoxElem, Fr, HElem, HideElem, Length, Rel, RepeatElem, Spacing};
-use crate::model::{Destination, HeadingElem, NumberingPattern, ParbreakElem, Refable};
+use crate::model::{
+    Destination, FootnoteElem, HeadingElem, NumberingPattern, ParbreakElem, Refable,
+};
 use crate::syntax::Span;
 use crate::text::{Lang, LinebreakElem, LocalName, Region, SpaceElem, TextElem};
 use crate::util::{option_eq, NonZeroExt};
@@ -233,7 +235,10 @@ impl Show for OutlineElem {
             OutlineIndent::apply(indent, engine, &ancestors, &mut seq, self.span())?;
 
             // Add the overridable outline entry, followed by a line break.
-            seq.push(entry.pack());
+            // Footnotes in the entry were already placed at the outlined
+            // element, so they must not be repeated here. Most of them are
+            // stripped from the entry's body, the rest is suppressed.
+            seq.push(entry.pack().styled(FootnoteElem::set_suppressed(true)));
             seq.push(LinebreakElem::new().pack());
 
             ancestors.push(elem);
@@ -469,6 +474,20 @@ impl OutlineEntry {
             return Ok(None);
         };
 
+        let body = strip_footnotes(&body);
+        let level = outlinable.level();
+        Self::locate(engine, level, elem, body, fill).map(Some)
+    }
+
+    /// Creates an entry for a located element, determining the number of the
+    /// page it is on.
+    pub(crate) fn locate(
+        engine: &mut Engine,
+        level: NonZeroUsize,
+        elem: Content,
+        body: Content,
+        fill: Option<Content>,
+    ) -> SourceResult<Self> {
         let location = elem.location().unwrap();
         let page_numbering = engine
             .introspector
@@ -480,7 +499,23 @@ impl OutlineEntry {
             .at(engine, location)?
             .display(engine, &page_numbering)?;
 
-        Ok(Some(Self::new(outlinable.level(), elem, body, fill, page)))
+        Ok(Self::new(level, elem, body, fill, page))
+    }
+}
+
+/// Remove the footnotes from the body of an outline entry.
+///
+/// Only footnotes at the top level of the body or nested in styled content
+/// are removed, as other elements can't be rebuilt generically.
+fn strip_footnotes(content: &Content) -> Content {
+    if content.is::<FootnoteElem>() {
+        Content::empty()
+    } else if let Some(children) = content.to_sequence() {
+        Content::sequence(children.map(|child| strip_footnotes(child)))
+    } else if let Some((child, styles)) = content.to_styled() {
+        strip_footnotes(child).styled_with_map(styles.clone())
+    } else {
+        content.clone()
     }
 }
 
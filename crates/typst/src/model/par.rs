@@ -1,12 +1,17 @@
 use comemo::Prehashed;
 
-use crate::diag::SourceResult;
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Args, Cast, Construct, Content, NativeElement, Set, Smart, StyleChain,
-    Unlabellable,
+    cast, dict, elem, scope, Args, Cast, Construct, Content, Dict, IntoValue,
+    NativeElement, Resolve, Set, Smart, StyleChain, Styles, Unlabellable, Value,
 };
-use crate::layout::{Em, Fragment, Length, Size};
+use crate::introspection::{Locatable, Meta, MetaElem};
+use crate::layout::{
+    locate_tag, Align, Dir, Em, Fragment, HAlign, Length, Ratio, Role, Size,
+};
+use crate::model::{FigureElem, HeadingElem, Numbering};
+use crate::text::TextElem;
 
 /// Arranges text, spacing and inline-level elements into a paragraph.
 ///
@@ -33,7 +38,7 @@ use crate::layout::{Em, Fragment, Length, Size};
 /// let $a$ be the smallest of the
 /// three integers. Then, we ...
 /// ```
-#[elem(title = "Paragraph", Construct)]
+#[elem(scope, title = "Paragraph", Construct)]
 pub struct ParElem {
     /// The spacing between lines.
     #[resolve]
@@ -41,6 +46,26 @@ pub struct ParElem {
     #[default(Em::new(0.65).into())]
     pub leading: Length,
 
+    /// How to determine the height of lines.
+    ///
+    /// - `{auto}`: Lines are as high as their content, as determined by the
+    ///   text's [top]($text.top-edge) and [bottom edges]($text.bottom-edge),
+    ///   and separated by the [`leading`]($par.leading).
+    /// - `{"normal"}`: Lines are as high as the ascender, descender, and line
+    ///   gap metrics of their fonts, like with `line-height: normal` in CSS.
+    ///   The leading is ignored.
+    /// - A length: Consecutive baselines are always exactly this far apart,
+    ///   even if the font size changes within a line. The leading is ignored.
+    ///
+    /// ```example
+    /// #set par(line-height: 1.5em)
+    /// Baselines stay evenly spaced,
+    /// even with #text(1.5em)[larger]
+    /// text in between.
+    /// ```
+    #[ghost]
+    pub line_height: Smart<LineHeight>,
+
     /// Whether to justify text in its line.
     ///
     /// Hyphenation will be enabled for justified paragraphs if the
@@ -54,6 +79,30 @@ pub struct ParElem {
     #[default(false)]
     pub justify: bool,
 
+    /// How to treat the last line of a justified paragraph.
+    ///
+    /// This also applies to lines before a forced [line break]($linebreak),
+    /// unless the break is justified itself.
+    ///
+    /// - `{false}`: The line is placed according to the current alignment.
+    /// - `{true}`: The line is justified like all other lines.
+    /// - `{"center"}`: The line is centered.
+    /// - A ratio: The line is justified if it fills at least this share of the
+    ///   available width and placed according to the alignment otherwise.
+    ///
+    /// Has no effect if the paragraph isn't [justified]($par.justify).
+    ///
+    /// ```example
+    /// #set page(width: 160pt)
+    /// #set par(justify: true, justify-last: "center")
+    /// These lines are justified, except
+    /// for the last one, which is centered
+    /// like in a book's dedication.
+    /// ```
+    #[ghost]
+    #[default(JustifyLast::Align)]
+    pub justify_last: JustifyLast,
+
     /// How to determine line breaks.
     ///
     /// When this property is set to `{auto}`, its default value, optimized line
@@ -88,8 +137,38 @@ pub struct ParElem {
     /// the [paragraph spacing]($block.spacing) to the [`leading`] when
     /// using this property (e.g. using
     /// `[#show par: set block(spacing: 0.65em)]`).
+    ///
+    /// Instead of a length, you can pass a dictionary with the indent's
+    /// `amount` and rules for when it applies:
+    /// - `after-heading`: Whether to also indent paragraphs directly following
+    ///   a heading. Defaults to `{false}`.
+    /// - `after-figure`: Whether to also indent paragraphs directly following
+    ///   a figure. Defaults to `{false}`.
+    /// - `same-style`: Whether to only indent paragraphs that follow a
+    ///   paragraph with the same styles. Defaults to `{false}`.
+    /// - `all`: Whether to indent all paragraphs, including the first one in a
+    ///   block or on the page and those following headings or figures. This
+    ///   overrides the other rules. Defaults to `{false}`.
+    ///
+    /// Even with `all`, content that makes up a single paragraph of its own,
+    /// like a table cell, a list item, a caption, or a footnote, is not
+    /// indented. In such containers, only paragraphs that are separated from
+    /// others by a paragraph break are indented. Paragraphs directly on the
+    /// page are always indented.
+    ///
+    /// ```example
+    /// #set par(first-line-indent: (
+    ///   amount: 1em,
+    ///   after-heading: true,
+    /// ))
+    ///
+    /// = Introduction
+    /// This paragraph is indented.
+    ///
+    /// And so is this one.
+    /// ```
     #[ghost]
-    pub first_line_indent: Length,
+    pub first_line_indent: FirstLineIndent,
 
     /// The indent all but the first line of a paragraph should have.
     #[ghost]
@@ -105,6 +184,19 @@ pub struct ParElem {
     #[internal]
     #[variadic]
     pub children: Vec<Prehashed<Content>>,
+
+    /// Whether the paragraph stands on its own rather than making up the
+    /// body of a container. Determined during realization and used to decide
+    /// whether the paragraph is indented when all paragraphs should be.
+    #[internal]
+    #[default(false)]
+    pub standalone: bool,
+}
+
+#[scope]
+impl ParElem {
+    #[elem]
+    type ParLine;
 }
 
 impl Construct for ParElem {
@@ -133,17 +225,165 @@ impl ParElem {
         region: Size,
         expand: bool,
     ) -> SourceResult<Fragment> {
-        crate::layout::layout_inline(
+        let mut fragment = crate::layout::layout_inline(
             self.children(),
             engine,
             styles,
             consecutive,
             region,
             expand,
+        )?;
+
+        if let Some(tag) = locate_tag(engine, Role::Paragraph, self.span()) {
+            for frame in &mut fragment {
+                frame.tag(tag.clone());
+            }
+        }
+
+        Ok(fragment)
+    }
+}
+
+/// A line of a paragraph.
+///
+/// This element is used in set rules to number the lines of paragraphs, as
+/// is customary for legal documents and manuscript submissions. The numbers
+/// are placed in the page's margin, next to the baseline of each line.
+///
+/// Each numbered line is marked with a `par.line` element, so numbered lines
+/// can be found with [`query`]($query). The element can't be constructed
+/// manually.
+///
+/// ```example
+/// >>> #set page(width: 160pt, margin: (left: 32pt, rest: 12pt))
+/// #set par.line(numbering: "1")
+///
+/// Line numbers make it easy to
+/// refer to a specific passage of
+/// a text, for example in a review
+/// or in court.
+/// ```
+#[elem(name = "line", title = "Paragraph Line", Construct, Locatable)]
+pub struct ParLine {
+    /// How to number the lines. Accepts a
+    /// [numbering pattern or function]($numbering).
+    ///
+    /// Lines are only numbered if this is set.
+    pub numbering: Option<Numbering>,
+
+    /// The margin in which to place the numbers: `{start}` or `{end}` of the
+    /// text direction, or `{left}` or `{right}`.
+    ///
+    /// On pages with multiple columns, this is ignored: The numbers of the
+    /// first column are placed in the left margin, those of the last column in
+    /// the right margin, and those of any columns in between in the gutter
+    /// before them. Lines in headers and footers are never numbered.
+    ///
+    /// ```example
+    /// >>> #set page(width: 160pt, margin: (right: 32pt, rest: 12pt))
+    /// #set par.line(numbering: "i", number-margin: right)
+    ///
+    /// These lines are numbered in
+    /// the right margin.
+    /// ```
+    #[default(NumberMargin::Start)]
+    pub number_margin: NumberMargin,
+
+    /// The distance between the numbers and the text area.
+    #[resolve]
+    #[default(Em::new(1.0).into())]
+    pub number_clearance: Length,
+
+    /// When to restart the numbering.
+    ///
+    /// - `{"document"}`: The lines are numbered continuously throughout the
+    ///   document.
+    /// - `{"page"}`: The numbering restarts on each page.
+    #[default(LineNumberingScope::Document)]
+    pub numbering_scope: LineNumberingScope,
+}
+
+impl Construct for ParLine {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "can only be used in set rules")
+    }
+}
+
+impl ParLine {
+    /// Create the marker for a numbered line of a paragraph with the given
+    /// styles, if its lines are numbered.
+    ///
+    /// The marker has all its fields set, with the margin resolved to `left`
+    /// or `right`, so that the page can number the line without the
+    /// paragraph's styles.
+    pub fn marker(styles: StyleChain) -> Option<Self> {
+        let numbering = Self::numbering_in(styles)?;
+        let margin = Self::number_margin_in(styles).resolve(styles);
+        Some(
+            Self::new()
+                .with_numbering(Some(numbering))
+                .with_number_margin(margin)
+                .with_number_clearance(Self::number_clearance_in(styles).into())
+                .with_numbering_scope(Self::numbering_scope_in(styles)),
         )
     }
 }
 
+/// The margin in which line numbers are placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NumberMargin {
+    /// The margin at the start of the text direction.
+    Start,
+    /// The margin at the end of the text direction.
+    End,
+    /// The left margin.
+    Left,
+    /// The right margin.
+    Right,
+}
+
+impl Resolve for NumberMargin {
+    type Output = Self;
+
+    /// Resolve `start` and `end` to `left` or `right`.
+    fn resolve(self, styles: StyleChain) -> Self::Output {
+        let rtl = TextElem::dir_in(styles) == Dir::RTL;
+        match self {
+            Self::Start if rtl => Self::Right,
+            Self::End if rtl => Self::Left,
+            Self::Start => Self::Left,
+            Self::End => Self::Right,
+            side => side,
+        }
+    }
+}
+
+cast! {
+    NumberMargin,
+    self => Align::H(match self {
+        Self::Start => HAlign::Start,
+        Self::End => HAlign::End,
+        Self::Left => HAlign::Left,
+        Self::Right => HAlign::Right,
+    }).into_value(),
+    align: HAlign => match align {
+        HAlign::Start => Self::Start,
+        HAlign::End => Self::End,
+        HAlign::Left => Self::Left,
+        HAlign::Right => Self::Right,
+        HAlign::Center => bail!("expected `start`, `left`, `right`, or `end`, found `center`"),
+    },
+}
+
+/// When to restart the numbering of paragraph lines.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum LineNumberingScope {
+    /// Number the lines continuously throughout the document.
+    Document,
+    /// Restart the numbering on each page.
+    Page,
+}
+
 /// How to determine line breaks in a paragraph.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum Linebreaks {
@@ -156,6 +396,139 @@ pub enum Linebreaks {
     Optimized,
 }
 
+/// How to determine the height of lines in a paragraph.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub enum LineHeight {
+    /// Use the fonts' vertical metrics.
+    Normal,
+    /// Place baselines at a fixed distance.
+    Fixed(Length),
+}
+
+cast! {
+    LineHeight,
+    self => match self {
+        Self::Normal => "normal".into_value(),
+        Self::Fixed(v) => v.into_value(),
+    },
+    "normal" => Self::Normal,
+    v: Length => Self::Fixed(v),
+}
+
+/// The first-line indent of paragraphs and when it applies.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Hash)]
+pub struct FirstLineIndent {
+    /// The amount of the indent.
+    pub amount: Length,
+    /// Whether to indent paragraphs following a heading.
+    pub after_heading: bool,
+    /// Whether to indent paragraphs following a figure.
+    pub after_figure: bool,
+    /// Whether to only indent paragraphs following a paragraph with the same
+    /// styles.
+    pub same_style: bool,
+    /// Whether to indent all paragraphs, no matter what precedes them.
+    pub all: bool,
+}
+
+impl FirstLineIndent {
+    /// Whether the indent applies to a paragraph with the given style hash
+    /// that follows the given block. With `all`, it applies to all
+    /// [standalone](ParElem::standalone) paragraphs.
+    pub fn applies(
+        &self,
+        preceding: PrecedingBlock,
+        hash: u128,
+        standalone: bool,
+    ) -> bool {
+        (self.all && standalone)
+            || match preceding {
+                PrecedingBlock::Par(prev) => !self.same_style || prev == hash,
+                PrecedingBlock::Heading => self.after_heading,
+                PrecedingBlock::Figure => self.after_figure,
+                PrecedingBlock::Other => false,
+            }
+    }
+}
+
+cast! {
+    FirstLineIndent,
+    self => dict! {
+        "amount" => self.amount.into_value(),
+        "after-heading" => self.after_heading.into_value(),
+        "after-figure" => self.after_figure.into_value(),
+        "same-style" => self.same_style.into_value(),
+        "all" => self.all.into_value(),
+    }.into_value(),
+    amount: Length => Self { amount, ..Default::default() },
+    mut dict: Dict => {
+        let mut flag = |key: &str| -> StrResult<bool> {
+            Ok(dict.take(key).ok().map(Value::cast).transpose()?.unwrap_or(false))
+        };
+        let after_heading = flag("after-heading")?;
+        let after_figure = flag("after-figure")?;
+        let same_style = flag("same-style")?;
+        let all = flag("all")?;
+        let amount = dict.take("amount")?.cast()?;
+        dict.finish(&["amount", "after-heading", "after-figure", "same-style", "all"])?;
+        Self { amount, after_heading, after_figure, same_style, all }
+    },
+}
+
+/// The block that precedes a paragraph in a flow, which determines whether the
+/// paragraph's first line is indented.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PrecedingBlock {
+    /// A paragraph with the given style hash.
+    Par(u128),
+    /// A heading.
+    Heading,
+    /// A figure.
+    Figure,
+    /// Nothing or any other block.
+    Other,
+}
+
+impl PrecedingBlock {
+    /// Determine the kind of a block from the metadata that realization
+    /// attached to its local styles.
+    pub fn located(local: &Styles) -> Option<Self> {
+        MetaElem::data_in(StyleChain::new(local)).into_iter().find_map(
+            |meta| match meta {
+                Meta::Elem(elem) if elem.is::<HeadingElem>() => Some(Self::Heading),
+                Meta::Elem(elem) if elem.is::<FigureElem>() => Some(Self::Figure),
+                _ => None,
+            },
+        )
+    }
+}
+
+/// How to treat the last line of a justified paragraph.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum JustifyLast {
+    /// Place the line according to the alignment.
+    Align,
+    /// Justify the line.
+    Justify,
+    /// Center the line.
+    Center,
+    /// Justify the line if it fills at least this share of the width.
+    Threshold(Ratio),
+}
+
+cast! {
+    JustifyLast,
+    self => match self {
+        Self::Align => false.into_value(),
+        Self::Justify => true.into_value(),
+        Self::Center => "center".into_value(),
+        Self::Threshold(ratio) => ratio.into_value(),
+    },
+    v: bool => if v { Self::Justify } else { Self::Align },
+    "center" => Self::Center,
+    v: Ratio => Self::Threshold(v),
+}
+
 /// A paragraph break.
 ///
 /// This starts a new paragraph. Especially useful when used within code like
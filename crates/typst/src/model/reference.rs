@@ -1,10 +1,11 @@
 use ecow::eco_format;
 
-use crate::diag::{bail, At, Hint, SourceResult};
+use crate::diag::{bail, At, Hint, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Content, Func, IntoValue, Label, NativeElement, Show, Smart, StyleChain,
-    Synthesize,
+    cast, elem, Cast, CastInfo, Content, Dict, Element, Fold, FromValue, Func, IntoValue,
+    Label, LabelNamespaceElem, NativeElement, Reflect, Selector, Show, Smart, Str,
+    StyleChain, Synthesize, Value,
 };
 use crate::introspection::{Counter, Locatable};
 use crate::math::EquationElem;
@@ -62,6 +63,11 @@ use crate::text::TextElem;
 /// To customize the supplement, add content in square brackets after the
 /// reference: `[@intro[Chapter]]`.
 ///
+/// Labels of files that were included with `{include "ch1.typ" as ch1}` live
+/// in the `ch1` namespace. From outside of the chapter, they are referenced
+/// with their qualified name, e.g. `[@ch1.intro]`. Within the chapter, the
+/// short name `[@intro]` is resolved in the chapter's namespace first.
+///
 /// # Customization
 /// If you write a show rule for references, you can access the referenced
 /// element through the `element` field of the reference. The `element` may
@@ -124,8 +130,40 @@ pub struct RefElem {
     /// in @intro[Part], it is done
     /// manually.
     /// ```
-    #[borrowed]
-    pub supplement: Smart<Option<Supplement>>,
+    ///
+    /// The supplement can also be configured per kind of referenced element
+    /// with a dictionary keyed by the name of the element function. Elements
+    /// without an entry use their default supplement. The key `cite` applies
+    /// to citations.
+    ///
+    /// ```example
+    /// #set math.equation(numbering: "(1)")
+    /// #set ref(supplement: (equation: [Eq.]))
+    ///
+    /// $ e^(i pi) + 1 = 0 $ <euler>
+    /// See @euler.
+    /// ```
+    #[fold]
+    pub supplement: ByTarget<Smart<Option<Supplement>>>,
+
+    /// How to display the referenced number.
+    ///
+    /// Like the supplement, this can be configured per kind of referenced
+    /// element with a dictionary keyed by the name of the element function.
+    ///
+    /// ```example
+    /// #set math.equation(numbering: "(1)")
+    /// #set ref(
+    ///   form: (equation: "full"),
+    ///   supplement: (equation: [Eq.]),
+    /// )
+    ///
+    /// #figure(rect[A], caption: [A box]) <box>
+    /// $ a^2 + b^2 = c^2 $ <pythagoras>
+    /// See @box and @pythagoras.
+    /// ```
+    #[fold]
+    pub form: ByTarget<RefForm>,
 
     /// A synthesized citation.
     #[synthesized]
@@ -142,11 +180,21 @@ impl Synthesize for RefElem {
         engine: &mut Engine,
         styles: StyleChain,
     ) -> SourceResult<()> {
+        // Resolve the target against the label namespaces of the reference,
+        // falling back to less specific labels.
+        let target = *self.target();
+        let target = LabelNamespaceElem::candidates(target, styles)
+            .find(|&label| {
+                BibliographyElem::has(engine, label)
+                    || !engine.introspector.query(&Selector::Label(label)).is_empty()
+            })
+            .unwrap_or(target);
+        self.push_target(target);
+
         let citation = self.to_citation(engine, styles)?;
         self.push_citation(Some(citation));
         self.push_element(None);
 
-        let target = *self.target();
         if !BibliographyElem::has(engine, target) {
             if let Ok(elem) = engine.introspector.query_label(target) {
                 self.push_element(Some(elem.into_inner()));
@@ -213,12 +261,19 @@ impl Show for RefElem {
                 ))
                 .at(span)?;
 
+            let form = self.form(styles).get(elem.func());
+            let numbering = match form {
+                RefForm::Full => numbering,
+                RefForm::Normal | RefForm::Number => numbering.trimmed(),
+            };
+
             let numbers = refable
                 .counter()
                 .at(engine, elem.location().unwrap())?
-                .display(engine, &numbering.trimmed())?;
+                .display(engine, &numbering)?;
 
-            let supplement = match self.supplement(styles).as_ref() {
+            let supplement = match self.supplement(styles).get(elem.func()) {
+                _ if form == RefForm::Number => Content::empty(),
                 Smart::Auto => refable.supplement(),
                 Smart::Custom(None) => Content::empty(),
                 Smart::Custom(Some(supplement)) => {
@@ -246,7 +301,7 @@ impl RefElem {
         let mut elem = CiteElem::new(*self.target());
         elem.set_location(self.location().unwrap());
         elem.synthesize(engine, styles)?;
-        elem.push_supplement(match self.supplement(styles).clone() {
+        elem.push_supplement(match self.supplement(styles).get(CiteElem::elem()) {
             Smart::Custom(Some(Supplement::Content(content))) => Some(content),
             _ => None,
         });
@@ -255,6 +310,123 @@ impl RefElem {
     }
 }
 
+/// How to display the number of a referenced element.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum RefForm {
+    /// The supplement followed by the number, without the prefix and suffix
+    /// of the numbering pattern (e.g. "Equation 1").
+    #[default]
+    Normal,
+    /// The supplement followed by the number as it is displayed at the
+    /// element (e.g. "Equation (1)").
+    Full,
+    /// Only the number, without supplement and without the prefix and suffix
+    /// of the numbering pattern (e.g. "1").
+    Number,
+}
+
+/// A value that can be configured per kind of referenced element.
+///
+/// Set rules fold: a dictionary only overrides the targets it names and keeps
+/// the settings of outer set rules for all others, while a bare value
+/// applies to all targets.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct ByTarget<T> {
+    /// Values keyed by the names of element functions.
+    entries: Vec<(Str, T)>,
+    /// The value for targets without an entry.
+    fallback: Option<T>,
+}
+
+impl<T> ByTarget<T> {
+    /// The same value for all targets.
+    pub fn all(value: T) -> Self {
+        Self { entries: vec![], fallback: Some(value) }
+    }
+}
+
+impl<T: Default + Clone> ByTarget<T> {
+    /// The value for a target of the given element function.
+    pub fn get(&self, func: Element) -> T {
+        self.entries
+            .iter()
+            .find(|(name, _)| name.as_str() == func.name())
+            .map(|(_, value)| value.clone())
+            .or_else(|| self.fallback.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl<T> Default for ByTarget<T> {
+    fn default() -> Self {
+        Self { entries: vec![], fallback: None }
+    }
+}
+
+impl<T> Fold for ByTarget<T> {
+    type Output = Self;
+
+    fn fold(mut self, outer: Self::Output) -> Self::Output {
+        if self.fallback.is_some() {
+            return self;
+        }
+
+        for (name, value) in outer.entries {
+            if !self.entries.iter().any(|(n, _)| *n == name) {
+                self.entries.push((name, value));
+            }
+        }
+
+        self.fallback = outer.fallback;
+        self
+    }
+}
+
+impl<T: Reflect> Reflect for ByTarget<T> {
+    fn input() -> CastInfo {
+        T::input() + Dict::input()
+    }
+
+    fn output() -> CastInfo {
+        T::output() + Dict::output()
+    }
+
+    fn castable(value: &Value) -> bool {
+        Dict::castable(value) || T::castable(value)
+    }
+}
+
+impl<T: IntoValue + Default> IntoValue for ByTarget<T> {
+    fn into_value(self) -> Value {
+        if self.entries.is_empty() {
+            return self.fallback.unwrap_or_default().into_value();
+        }
+
+        Value::Dict(
+            self.entries
+                .into_iter()
+                .map(|(name, v)| (name, v.into_value()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: FromValue> FromValue for ByTarget<T> {
+    fn from_value(value: Value) -> StrResult<Self> {
+        match value {
+            Value::Dict(dict) => Ok(Self {
+                entries: dict
+                    .into_iter()
+                    .map(|(name, v)| Ok((name, T::from_value(v)?)))
+                    .collect::<StrResult<_>>()?,
+                fallback: None,
+            }),
+            v if T::castable(&v) => Ok(Self::all(T::from_value(v)?)),
+            v => Err(Self::error(&v)),
+        }
+    }
+}
+
 /// Additional content for a reference.
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum Supplement {
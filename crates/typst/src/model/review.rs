@@ -0,0 +1,144 @@
+use ecow::EcoString;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Behave, Behaviour, Content, Datetime, NativeElement, Show, StyleChain,
+    Synthesize,
+};
+use crate::introspection::Locatable;
+use crate::text::{StrikeElem, TextElem, UnderlineElem};
+use crate::visualize::Color;
+
+/// Marks content as inserted in a revision.
+///
+/// Insertions are underlined and colored green by default. To accept all
+/// insertions, show them as they are: `{show ins: it => it.body}`.
+///
+/// Insertions, [deletions]($del), and [comments]($comment) can be listed
+/// with their positions through the `typst review` command.
+///
+/// # Example
+/// ```example
+/// The results are
+/// #ins(author: "Ana")[very]
+/// promising.
+/// ```
+#[elem(title = "Insertion", Locatable, Synthesize, Show)]
+pub struct InsElem {
+    /// The author of the insertion.
+    pub author: Option<EcoString>,
+
+    /// When the insertion was made.
+    pub date: Option<Datetime>,
+
+    /// The inserted content.
+    #[required]
+    pub body: Content,
+}
+
+impl Synthesize for InsElem {
+    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        self.push_author(self.author(styles));
+        self.push_date(self.date(styles));
+        Ok(())
+    }
+}
+
+impl Show for InsElem {
+    #[tracing::instrument(name = "InsElem::show", skip_all)]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(UnderlineElem::new(self.body().clone())
+            .pack()
+            .styled(TextElem::set_fill(Color::GREEN.into())))
+    }
+}
+
+/// Marks content as deleted in a revision.
+///
+/// Deletions are struck through and colored red by default. To accept all
+/// deletions, hide them: `{show del: none}`.
+///
+/// # Example
+/// ```example
+/// The results are
+/// #del(author: "Ana")[not]
+/// promising.
+/// ```
+#[elem(title = "Deletion", Locatable, Synthesize, Show)]
+pub struct DelElem {
+    /// The author of the deletion.
+    pub author: Option<EcoString>,
+
+    /// When the deletion was made.
+    pub date: Option<Datetime>,
+
+    /// The deleted content.
+    #[required]
+    pub body: Content,
+}
+
+impl Synthesize for DelElem {
+    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        self.push_author(self.author(styles));
+        self.push_date(self.date(styles));
+        Ok(())
+    }
+}
+
+impl Show for DelElem {
+    #[tracing::instrument(name = "DelElem::show", skip_all)]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(StrikeElem::new(self.body().clone())
+            .pack()
+            .styled(TextElem::set_fill(Color::RED.into())))
+    }
+}
+
+/// A review comment.
+///
+/// Comments are invisible in the document. They are anchored at the position
+/// where they are placed and can be displayed with a show rule or listed
+/// through the `typst review` command.
+///
+/// # Example
+/// ```example
+/// #show comment: it => text(blue)[
+///   (#it.author: #it.body)
+/// ]
+///
+/// The sky is green.
+/// #comment(author: "Ana")[Really?]
+/// ```
+#[elem(Locatable, Synthesize, Behave, Show)]
+pub struct CommentElem {
+    /// The author of the comment.
+    pub author: Option<EcoString>,
+
+    /// When the comment was made.
+    pub date: Option<Datetime>,
+
+    /// The text of the comment.
+    #[required]
+    pub body: Content,
+}
+
+impl Synthesize for CommentElem {
+    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        self.push_author(self.author(styles));
+        self.push_date(self.date(styles));
+        Ok(())
+    }
+}
+
+impl Show for CommentElem {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+impl Behave for CommentElem {
+    fn behaviour(&self) -> Behaviour {
+        Behaviour::Invisible
+    }
+}
@@ -1,15 +1,27 @@
-use crate::diag::{At, SourceResult, StrResult};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+
+use ecow::eco_format;
+use smallvec::smallvec;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
+use crate::eval::ops;
 use crate::foundations::{
-    elem, Array, CastInfo, Content, FromValue, Func, IntoValue, NativeElement, Reflect,
-    Smart, StyleChain, Value,
+    elem, scope, Args, Array, CastInfo, Content, Dict, FromValue, Func, IntoValue,
+    NativeElement, Reflect, Resolve, Set, Smart, Str, StyleChain, Value,
 };
+use crate::introspection::Meta;
 use crate::layout::{
-    Abs, Align, AlignElem, Axes, Fragment, FrameItem, GridLayouter, Layout, Length,
-    Point, Regions, Rel, Sides, Size, TrackSizings,
+    line_segments, locate_tag, place_cells, Abs, Align, AlignElem, Axes, CellPosition,
+    Fragment, Frame, FrameItem, GridCell, GridLayouter, Layout, Length, Point, Regions,
+    Rel, Role, RowPiece, RuleInset, Sides, Size, Sizing, TagElem, TrackSizings,
 };
-use crate::model::Figurable;
-use crate::text::{Lang, LocalName, Region};
+use crate::model::{Figurable, FootnoteElem, FootnoteEntry, StrongElem};
+use crate::syntax::{Span, Spanned};
+use crate::text::{Lang, LocalName, Region, TextElem};
+use crate::util::NonZeroExt;
 use crate::visualize::{Geometry, Paint, Stroke};
 
 /// A table of items.
@@ -42,7 +54,7 @@ use crate::visualize::{Geometry, Paint, Stroke};
 ///   [$a$: edge length]
 /// )
 /// ```
-#[elem(Layout, LocalName, Figurable)]
+#[elem(scope, Layout, LocalName, Figurable)]
 pub struct TableElem {
     /// The column sizes. See the [grid documentation]($grid) for more
     /// information on track sizing.
@@ -97,6 +109,36 @@ pub struct TableElem {
     #[borrowed]
     pub fill: Celled<Option<Paint>>,
 
+    /// How to fill alternating bands of rows or columns.
+    ///
+    /// This can be a paint, in which case every other row is filled, or a
+    /// dictionary with the following keys:
+    /// - `fill`: The paint for the striped bands.
+    /// - `bands`: Whether to stripe `{"rows"}` (the default) or
+    ///   `{"columns"}`.
+    /// - `size`: How many rows or columns make up a band. Defaults to `{1}`.
+    /// - `offset`: How many leading rows or columns, like a header, to
+    ///   exclude from the banding. Defaults to `{0}`.
+    ///
+    /// The first band is left unfilled and every second band after it is
+    /// filled. Stripes are drawn below the cells' [`fill`]($table.fill), so
+    /// that the two can be combined.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   fill: (_, row) => if row == 0 { aqua },
+    ///   stripe: (fill: luma(230), size: 2, offset: 1),
+    ///   [*Name*], [*Q1*], [*Q2*],
+    ///   [Anna], [10], [12],
+    ///   [Ben], [8], [9],
+    ///   [Cleo], [11], [14],
+    ///   [Dan], [7], [6],
+    /// )
+    /// ```
+    #[borrowed]
+    pub stripe: Option<Stripe>,
+
     /// How to align the cells' content.
     ///
     /// This can either be a single alignment, an array of alignments
@@ -127,6 +169,30 @@ pub struct TableElem {
     #[default(Some(Stroke::default()))]
     pub stroke: Option<Stroke>,
 
+    /// How to [stroke]($stroke) the gutters between rows and columns. See the
+    /// [grid documentation]($grid.rule) for more information on gutter rules.
+    ///
+    /// Combined with `{stroke: none}`, this allows for minimal table styles.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   stroke: none,
+    ///   gutter: 4pt,
+    ///   rule: 0.5pt + gray,
+    ///   [*Name*], [*Q1*], [*Q2*],
+    ///   [Anna], [10], [12],
+    ///   [Ben], [8], [9],
+    /// )
+    /// ```
+    #[resolve]
+    #[fold]
+    pub rule: Option<Stroke>,
+
+    /// How far the gutter rules are inset from the edges of the table. See the
+    /// [grid documentation]($grid.rule-inset) for more information.
+    pub rule_inset: RuleInset,
+
     /// How much to pad the cells' content.
     ///
     /// ```example
@@ -150,11 +216,214 @@ pub struct TableElem {
     #[default(Sides::splat(Abs::pt(5.0).into()))]
     pub inset: Sides<Option<Rel<Length>>>,
 
+    /// In which columns vertically adjacent cells with equal content should be
+    /// merged into a single cell.
+    ///
+    /// Only the first cell of each run of equal cells shows its content and
+    /// the lines between the merged cells are omitted. This is common in
+    /// pivot-style reports, where one value applies to multiple rows.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   merge-equal: (columns: (0,)),
+    ///   [*Region*], [*Quarter*], [*Sales*],
+    ///   [North], [Q1], [10],
+    ///   [North], [Q2], [12],
+    ///   [South], [Q1], [7],
+    ///   [South], [Q2], [9],
+    /// )
+    /// ```
+    #[borrowed]
+    pub merge_equal: Option<MergeEqual>,
+
+    /// Whether to list the footnotes in the table's cells in a block of notes
+    /// right below the table instead of in the page's footnote area.
+    ///
+    /// The notes share their numbering with the other footnotes of the
+    /// document.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 2,
+    ///   notes: true,
+    ///   [Apples #footnote[Organic.]], [3],
+    ///   [Pears], [5],
+    /// )
+    /// ```
+    #[default(false)]
+    pub notes: bool,
+
     /// The contents of the table cells.
+    ///
+    /// To let a cell span multiple columns or rows, wrap it in a
+    /// [`table.cell`]($grid.cell).
     #[variadic]
     pub children: Vec<Content>,
 }
 
+#[scope]
+impl TableElem {
+    #[elem]
+    type GridCell;
+
+    /// Builds a table from structured data.
+    ///
+    /// Each row of the table is given as a dictionary mapping column names to
+    /// values, like the rows returned by [`csv`]($csv) with `header: true`.
+    /// Missing values are left empty.
+    ///
+    /// Any further arguments, like `stroke` or `fill`, are applied to the
+    /// table.
+    ///
+    /// ```example
+    /// #let fruits = (
+    ///   (name: "Apples", count: 12, price: 0.5),
+    ///   (name: "Bananas", count: 6, price: 0.25),
+    ///   (name: "Cherries", count: 200, price: 0.02),
+    /// )
+    ///
+    /// #table.from(
+    ///   fruits,
+    ///   columns: ("name", "count"),
+    ///   format: (count: v => align(right, str(v))),
+    ///   sort: "count",
+    ///   descending: true,
+    ///   totals: ("count",),
+    ///   stroke: 0.5pt,
+    /// )
+    /// ```
+    #[func]
+    pub fn from(
+        /// The engine.
+        engine: &mut Engine,
+        /// The remaining arguments, which are applied to the table.
+        args: &mut Args,
+        /// The rows of the table.
+        data: Vec<Dict>,
+        /// The names of the columns to show, in order. Defaults to the keys of
+        /// the first row.
+        #[named]
+        #[default]
+        columns: Smart<Vec<Str>>,
+        /// Whether to show the column names in a header row.
+        #[named]
+        #[default(true)]
+        header: bool,
+        /// How to format the values of specific columns. Maps column names to
+        /// functions that receive a value and return content. Values in other
+        /// columns are displayed as is.
+        #[named]
+        #[default]
+        format: Dict,
+        /// The name of the column to sort the rows by. If `{none}`, the rows
+        /// keep their original order.
+        #[named]
+        #[default]
+        sort: Option<Spanned<Str>>,
+        /// Whether to sort the rows in descending instead of ascending order.
+        #[named]
+        #[default(false)]
+        descending: bool,
+        /// The names of columns whose values should be summed up in a final
+        /// totals row.
+        #[named]
+        #[default(Spanned::new(vec![], Span::detached()))]
+        totals: Spanned<Vec<Str>>,
+    ) -> SourceResult<Content> {
+        let span = args.span;
+        let styles = TableElem::set(engine, args)?;
+        args.take().finish()?;
+
+        let columns = columns.unwrap_or_else(|| {
+            data.first()
+                .map(|row| row.iter().map(|(key, _)| key.clone()).collect())
+                .unwrap_or_default()
+        });
+
+        let mut rows = data;
+        if let Some(Spanned { v: key, span }) = &sort {
+            let none = Value::None;
+            let mut result = Ok(());
+            rows.sort_by(|a, b| {
+                let a = a.get(key).unwrap_or(&none);
+                let b = b.get(key).unwrap_or(&none);
+                let ordering = ops::compare(a, b).unwrap_or_else(|err| {
+                    if result.is_ok() {
+                        result = Err(err).at(*span);
+                    }
+                    Ordering::Equal
+                });
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+            result?;
+        }
+
+        let mut cell = |key: &Str, value: Value| -> SourceResult<Content> {
+            Ok(match format.get(key) {
+                Ok(formatter) => {
+                    let func = formatter.clone().cast::<Func>().at(span)?;
+                    func.call(engine, [value])?.display()
+                }
+                Err(_) => value.display(),
+            })
+        };
+
+        let mut cells = vec![];
+        if header {
+            for key in &columns {
+                cells.push(StrongElem::new(TextElem::packed(key.clone())).pack());
+            }
+        }
+
+        for row in &rows {
+            for key in &columns {
+                let value = row.get(key).cloned().unwrap_or_default();
+                cells.push(cell(key, value)?);
+            }
+        }
+
+        if !totals.v.is_empty() {
+            for key in &totals.v {
+                if !columns.contains(key) {
+                    bail!(totals.span, "cannot total unknown column `{key}`");
+                }
+            }
+
+            for key in &columns {
+                if !totals.v.contains(key) {
+                    cells.push(Content::empty());
+                    continue;
+                }
+
+                let mut sum = Value::None;
+                for value in rows.iter().filter_map(|row| row.get(key).ok()) {
+                    sum = match sum {
+                        Value::None => value.clone(),
+                        sum => ops::add(sum, value.clone())
+                            .map_err(|err| {
+                                eco_format!("cannot total column `{key}` ({err})")
+                            })
+                            .at(totals.span)?,
+                    };
+                }
+                cells.push(cell(key, sum)?);
+            }
+        }
+
+        let sizings = TrackSizings(smallvec![Sizing::Auto; columns.len().max(1)]);
+        Ok(TableElem::new(cells)
+            .with_columns(sizings)
+            .spanned(span)
+            .pack()
+            .styled_with_map(styles))
+    }
+}
+
 impl Layout for TableElem {
     #[tracing::instrument(name = "TableElem::layout", skip_all)]
     fn layout(
@@ -173,34 +442,59 @@ impl Layout for TableElem {
         let tracks = Axes::new(columns.0.as_slice(), rows.0.as_slice());
         let gutter = Axes::new(column_gutter.0.as_slice(), row_gutter.0.as_slice());
         let cols = tracks.x.len().max(1);
+        let positions = place_cells(self.children(), cols, styles)?;
+        let merged = self
+            .merge_equal(styles)
+            .as_ref()
+            .map(|merge| merge.find(self.children(), &positions))
+            .unwrap_or_default();
+
+        let notes = self.notes(styles);
         let cells: Vec<_> = self
             .children()
             .iter()
+            .zip(&positions)
             .enumerate()
-            .map(|(i, child)| {
-                let mut child = child.clone().padded(inset);
+            .map(|(i, (child, pos))| {
+                // The inset and alignment apply to the body of a spanning
+                // cell, so that the layouter still sees its spans.
+                let spanning = child.to::<GridCell>();
+                let mut body = if merged.contains(&i) {
+                    Content::empty().padded(inset)
+                } else {
+                    spanning.map_or(child, |cell| cell.body()).clone().padded(inset)
+                };
+
+                if let Smart::Custom(alignment) = align.resolve(engine, pos.x, pos.y)? {
+                    body = body.styled(AlignElem::set_alignment(alignment));
+                }
 
-                let x = i % cols;
-                let y = i / cols;
-                if let Smart::Custom(alignment) = align.resolve(engine, x, y)? {
-                    child = child.styled(AlignElem::set_alignment(alignment));
+                if notes {
+                    body = body.styled(FootnoteElem::set_endnote(true));
                 }
 
-                Ok(child)
+                let body = TagElem::new(Role::TableCell { row: pos.y }, body).pack();
+                Ok(match spanning {
+                    Some(cell) => cell.clone().with_body(body).pack(),
+                    None => body,
+                })
             })
             .collect::<SourceResult<_>>()?;
 
         let fill = self.fill(styles);
+        let stripe = self.stripe(styles);
         let stroke = self.stroke(styles).map(Stroke::unwrap_or_default);
+        let has_gutter = gutter.any(|tracks| !tracks.is_empty());
 
         // Prepare grid layout by unifying content and gutter tracks.
         let layouter =
-            GridLayouter::new(tracks, gutter, &cells, regions, styles, self.span());
+            GridLayouter::new(tracks, gutter, &cells, regions, styles, self.span())?;
 
         // Measure the columns and layout the grid row-by-row.
         let mut layout = layouter.layout(engine)?;
 
         // Add lines and backgrounds.
+        let spans = &layout.spans;
         for (frame, rows) in layout.fragment.iter_mut().zip(&layout.rows) {
             if layout.cols.is_empty() || rows.is_empty() {
                 continue;
@@ -211,48 +505,316 @@ impl Layout for TableElem {
                 let thickness = stroke.thickness;
                 let half = thickness / 2.0;
 
-                // Render horizontal lines.
-                for offset in points(rows.iter().map(|piece| piece.height)) {
-                    let target = Point::with_x(frame.width() + thickness);
-                    let hline = Geometry::Line(target).stroked(stroke.clone());
-                    frame.prepend(
-                        Point::new(-half, offset),
-                        FrameItem::Shape(hline, self.span()),
-                    );
+                // Render horizontal lines. Lines above cells that are merged
+                // into the cell above them and lines through cells spanning
+                // multiple rows are omitted.
+                let offsets = points(rows.iter().map(|piece| piece.height));
+                for (k, offset) in offsets.enumerate() {
+                    let is_merged = |x: usize| {
+                        let Some(row) = rows.get(k).filter(|_| k > 0) else {
+                            return false;
+                        };
+                        if spans.at(x, row.y).is_some_and(|cell| cell.y < row.y) {
+                            return true;
+                        }
+                        if has_gutter && x % 2 == 1 {
+                            return false;
+                        }
+                        // A gutter row belongs to the content row below it.
+                        let y = if has_gutter { row.y + row.y % 2 } else { row.y };
+                        spans.at(x, y).is_some_and(|cell| {
+                            cell.y == y && merged.contains(&cell.index)
+                        })
+                    };
+
+                    let mut dx = Abs::zero();
+                    let mut start = None;
+                    for (x, &col) in layout.cols.iter().enumerate() {
+                        if is_merged(x) {
+                            if let Some(start) = start.take() {
+                                let hline = Geometry::Line(Point::with_x(dx - start))
+                                    .stroked(stroke.clone());
+                                frame.prepend(
+                                    Point::new(start, offset),
+                                    FrameItem::Shape(hline, self.span()),
+                                );
+                            }
+                        } else if start.is_none() {
+                            start = Some(if x == 0 { -half } else { dx });
+                        }
+                        dx += col;
+                    }
+
+                    if let Some(start) = start {
+                        let target = Point::with_x(frame.width() + half - start);
+                        let hline = Geometry::Line(target).stroked(stroke.clone());
+                        frame.prepend(
+                            Point::new(start, offset),
+                            FrameItem::Shape(hline, self.span()),
+                        );
+                    }
                 }
 
-                // Render vertical lines.
-                for offset in points(layout.cols.iter().copied()) {
-                    let target = Point::with_y(frame.height() + thickness);
-                    let vline = Geometry::Line(target).stroked(stroke.clone());
-                    frame.prepend(
-                        Point::new(offset, -half),
-                        FrameItem::Shape(vline, self.span()),
-                    );
+                // Render vertical lines. Lines through cells spanning
+                // multiple columns are omitted.
+                let total: Abs = rows.iter().map(|piece| piece.height).sum();
+                for (x, offset) in points(layout.cols.iter().copied()).enumerate() {
+                    let is_spanned = |row: &RowPiece| {
+                        spans.at(x, row.y).is_some_and(|cell| cell.x < x)
+                    };
+
+                    let tracks = rows.iter().map(|row| (row.height, is_spanned(row)));
+                    for (start, end) in line_segments(tracks) {
+                        let start = if start.is_zero() { -half } else { start };
+                        let end = if end == total { frame.height() + half } else { end };
+                        let vline = Geometry::Line(Point::with_y(end - start))
+                            .stroked(stroke.clone());
+                        frame.prepend(
+                            Point::new(offset, start),
+                            FrameItem::Shape(vline, self.span()),
+                        );
+                    }
                 }
             }
 
-            // Render cell backgrounds.
+            // Render cell backgrounds and stripes below them. Cells spanning
+            // multiple tracks are filled as a whole from their first track in
+            // the region.
             let mut dx = Abs::zero();
             for (x, &col) in layout.cols.iter().enumerate() {
                 let mut dy = Abs::zero();
                 for row in rows {
-                    if let Some(fill) = fill.resolve(engine, x, row.y)? {
-                        let pos = Point::new(dx, dy);
-                        let size = Size::new(col, row.height);
+                    let pos = Point::new(dx, dy);
+                    dy += row.height;
+
+                    let (x, y, size) = match spans.at(x, row.y) {
+                        Some(cell) if cell.colspan > 1 || cell.rowspan > 1 => {
+                            let tracks = cell.y..cell.y + cell.rowspan;
+                            let mut pieces =
+                                rows.iter().filter(|piece| tracks.contains(&piece.y));
+                            if x != cell.x
+                                || pieces.next().map(|piece| piece.y) != Some(row.y)
+                            {
+                                continue;
+                            }
+
+                            let width = layout.cols[x..x + cell.colspan].iter().sum();
+                            let height = row.height
+                                + pieces.map(|piece| piece.height).sum::<Abs>();
+                            (cell.x, cell.y, Size::new(width, height))
+                        }
+                        _ => (x, row.y, Size::new(col, row.height)),
+                    };
+
+                    if let Some(fill) = fill.resolve(engine, x, y)? {
                         let rect = Geometry::Rect(size).filled(fill);
                         frame.prepend(pos, FrameItem::Shape(rect, self.span()));
                     }
-                    dy += row.height;
+                    if let Some(stripe) = stripe {
+                        if let Some(fill) = stripe.resolve(x, y, has_gutter) {
+                            let rect = Geometry::Rect(size).filled(fill);
+                            frame.prepend(pos, FrameItem::Shape(rect, self.span()));
+                        }
+                    }
                 }
                 dx += col;
             }
         }
 
+        // Draw rules in the gutters.
+        if let Some(rule) = self.rule(styles) {
+            let inset = self.rule_inset(styles);
+            layout.stroke_gutters(
+                &rule.unwrap_or_default(),
+                inset.start.resolve(styles),
+                inset.end.resolve(styles),
+                self.span(),
+            );
+        }
+
+        if notes {
+            layout_notes(engine, styles, &mut layout.fragment)?;
+        }
+
+        if let Some(tag) = locate_tag(engine, Role::Table, self.span()) {
+            for frame in &mut layout.fragment {
+                frame.tag(tag.clone());
+            }
+        }
+
         Ok(layout.fragment)
     }
 }
 
+/// Lay out the entries of the footnotes in a table's cells below its last
+/// frame.
+fn layout_notes(
+    engine: &mut Engine,
+    styles: StyleChain,
+    fragment: &mut Fragment,
+) -> SourceResult<()> {
+    let mut notes = vec![];
+    for frame in fragment.iter() {
+        find_notes(&mut notes, frame);
+    }
+
+    let Some(last) = fragment.iter_mut().last() else { return Ok(()) };
+    if notes.is_empty() {
+        return Ok(());
+    }
+
+    let gap = FootnoteEntry::gap_in(styles);
+    let pod = Regions::one(Size::new(last.width(), Abs::inf()), Axes::new(true, false));
+    let mut y = last.height() + FootnoteEntry::clearance_in(styles);
+    for (i, note) in notes.into_iter().enumerate() {
+        if i > 0 {
+            y += gap;
+        }
+
+        let frame = FootnoteEntry::new(note).pack().layout(engine, styles, pod)?;
+        let frame = frame.into_frame();
+        let height = frame.height();
+        last.push_frame(Point::with_y(y), frame);
+        y += height;
+    }
+
+    last.size_mut().y = y;
+    Ok(())
+}
+
+/// Find the footnotes in a frame that are listed below their table.
+fn find_notes(notes: &mut Vec<FootnoteElem>, frame: &Frame) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => find_notes(notes, &group.frame),
+            FrameItem::Meta(Meta::Elem(content), _) => {
+                let Some(note) = content.to::<FootnoteElem>() else { continue };
+                if note.endnote(StyleChain::default())
+                    && !note.is_ref()
+                    && !notes.iter().any(|other| other.location() == note.location())
+                {
+                    notes.push(note.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Alternating bands of filled rows or columns in a table.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Stripe {
+    /// The paint for the striped bands.
+    pub fill: Paint,
+    /// Whether to stripe rows or columns.
+    pub bands: Bands,
+    /// How many rows or columns make up a band.
+    pub size: NonZeroUsize,
+    /// How many leading rows or columns are excluded from the banding.
+    pub offset: usize,
+}
+
+impl Stripe {
+    /// The stripe paint for the cell at the given track indices, if any.
+    fn resolve(&self, x: usize, y: usize, has_gutter: bool) -> Option<Paint> {
+        let i = match self.bands {
+            Bands::Rows => y,
+            Bands::Columns => x,
+        };
+
+        // Gutter tracks are never striped.
+        if has_gutter && (x % 2 == 1 || y % 2 == 1) {
+            return None;
+        }
+
+        let i = if has_gutter { i / 2 } else { i };
+        let band = i.checked_sub(self.offset)? / self.size.get();
+        (band % 2 == 1).then(|| self.fill.clone())
+    }
+}
+
+/// Whether to stripe the rows or columns of a table.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Bands {
+    /// Stripe alternating rows.
+    Rows,
+    /// Stripe alternating columns.
+    Columns,
+}
+
+cast! {
+    Stripe,
+    self => dict! {
+        "fill" => self.fill.into_value(),
+        "bands" => self.bands.into_value(),
+        "size" => self.size.into_value(),
+        "offset" => self.offset.into_value(),
+    }.into_value(),
+    fill: Paint => Self {
+        fill,
+        bands: Bands::Rows,
+        size: NonZeroUsize::ONE,
+        offset: 0,
+    },
+    mut dict: Dict => {
+        let fill = dict.take("fill")?.cast()?;
+        let bands = dict.take("bands").ok().map(Value::cast).transpose()?;
+        let size = dict.take("size").ok().map(Value::cast).transpose()?;
+        let offset = dict.take("offset").ok().map(Value::cast).transpose()?;
+        dict.finish(&["fill", "bands", "size", "offset"])?;
+        Self {
+            fill,
+            bands: bands.unwrap_or(Bands::Rows),
+            size: size.unwrap_or(NonZeroUsize::ONE),
+            offset: offset.unwrap_or(0),
+        }
+    },
+}
+
+/// Which columns of a table should have equal adjacent cells merged.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct MergeEqual {
+    /// The indices of the columns.
+    pub columns: Vec<usize>,
+}
+
+impl MergeEqual {
+    /// Find the indices of all cells that are merged into the cell above them.
+    ///
+    /// Cells spanning multiple columns or rows are never merged.
+    fn find(&self, cells: &[Content], positions: &[CellPosition]) -> HashSet<usize> {
+        let single: HashMap<(usize, usize), usize> = positions
+            .iter()
+            .enumerate()
+            .filter(|(_, pos)| pos.colspan == 1 && pos.rowspan == 1)
+            .map(|(i, pos)| ((pos.x, pos.y), i))
+            .collect();
+
+        let mut merged = HashSet::new();
+        for (&(x, y), &i) in &single {
+            if y == 0 || !self.columns.contains(&x) {
+                continue;
+            }
+            if let Some(&above) = single.get(&(x, y - 1)) {
+                if cells[i] == cells[above] {
+                    merged.insert(i);
+                }
+            }
+        }
+        merged
+    }
+}
+
+cast! {
+    MergeEqual,
+    self => dict! { "columns" => self.columns.into_value() }.into_value(),
+    mut dict: Dict => {
+        let columns = dict.take("columns")?.cast()?;
+        dict.finish(&["columns"])?;
+        Self { columns }
+    },
+}
+
 /// Turn an iterator of extents into an iterator of offsets before, in between,
 /// and after the extents, e.g. [10mm, 5mm] -> [0mm, 10mm, 15mm].
 fn points(extents: impl IntoIterator<Item = Abs>) -> impl Iterator<Item = Abs> {
@@ -0,0 +1,255 @@
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use ecow::EcoString;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, select_where, Content, NativeElement, Show, Smart, StyleChain, Synthesize,
+};
+use crate::introspection::{Count, Counter, CounterKey, CounterUpdate, Locatable};
+use crate::layout::BlockElem;
+use crate::model::{
+    Numbering, NumberingPattern, Outlinable, Refable, StrongElem, Supplement,
+};
+use crate::text::{Lang, LocalName, Region, SpaceElem, TextElem};
+use crate::util::NonZeroExt;
+
+/// A numbered environment, like a theorem, lemma, or definition.
+///
+/// The environment's `kind` determines its supplement and its counter. By
+/// default, each kind is numbered independently. Kinds that should share a
+/// counter, like theorems and lemmas, can be put into the same `group`. To
+/// style a kind differently, use a show rule with a
+/// [`where`]($function.where) selector.
+///
+/// Like figures, environments can be labelled and then referenced.
+///
+/// # Example
+/// ```example
+/// #let lemma = theorem.with(kind: "lemma", group: "theorem")
+/// #let definition = theorem.with(kind: "definition")
+/// #show theorem.where(kind: "theorem"): set text(style: "italic")
+///
+/// #definition[
+///   A prime is a natural number with exactly two divisors.
+/// ]
+///
+/// #lemma[
+///   Every natural number greater than one has a prime factor.
+/// ] <factor>
+///
+/// #theorem(title: [Euclid])[
+///   There are infinitely many primes.
+/// ]
+///
+/// The proof builds on @factor.
+/// ```
+#[elem(Locatable, Synthesize, Count, Show, Refable, Outlinable, LocalName)]
+pub struct TheoremElem {
+    /// The kind of environment, like `{"theorem"}`, `{"lemma"}`, or
+    /// `{"definition"}`.
+    #[default("theorem".into())]
+    pub kind: EcoString,
+
+    /// The group whose counter numbers the environment.
+    ///
+    /// Environments in the same group share a counter. If set to `{auto}`,
+    /// the group is the environment's kind, so that each kind is numbered
+    /// independently.
+    ///
+    /// ```example
+    /// #theorem[First.]
+    /// #theorem(kind: "corollary", group: "theorem")[Second.]
+    /// #theorem(kind: "remark")[Third.]
+    /// ```
+    pub group: Smart<EcoString>,
+
+    /// The title of the environment, which is displayed in parentheses after
+    /// its number.
+    pub title: Option<Content>,
+
+    /// The environment's supplement.
+    ///
+    /// If set to `{auto}`, the supplement of theorems is the word "Theorem"
+    /// in the text language and that of other kinds is the capitalized kind.
+    /// If a function is specified, it is passed the environment's body and
+    /// should return content.
+    ///
+    /// ```example
+    /// #theorem(kind: "lemme", supplement: [Lemme])[
+    ///   Tout nombre premier est impair ou égal à deux.
+    /// ]
+    /// ```
+    #[borrowed]
+    pub supplement: Smart<Option<Supplement>>,
+
+    /// How to number the environment. Accepts a
+    /// [numbering pattern or function]($numbering).
+    #[default(Some(NumberingPattern::from_str("1").unwrap().into()))]
+    pub numbering: Option<Numbering>,
+
+    /// The contents of the environment.
+    #[required]
+    pub body: Content,
+
+    /// Convenience field to get access to the counter for this environment.
+    ///
+    /// The counter only depends on the group:
+    /// `{counter(theorem.where(group: group))}`.
+    #[synthesized]
+    pub counter: Option<Counter>,
+}
+
+impl Synthesize for TheoremElem {
+    fn synthesize(
+        &mut self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        let kind = self.kind(styles);
+        let group = self.group(styles).unwrap_or_else(|| kind.clone());
+
+        let supplement = match self.supplement(styles).as_ref() {
+            Smart::Auto if kind == "theorem" => {
+                Some(TextElem::packed(Self::local_name_in(styles)))
+            }
+            Smart::Auto => Some(TextElem::packed(capitalize(&kind))),
+            Smart::Custom(None) => None,
+            Smart::Custom(Some(supplement)) => {
+                Some(supplement.resolve(engine, [self.body().clone()])?)
+            }
+        };
+
+        let counter = Counter::new(CounterKey::Selector(
+            select_where!(Self, Group => group.clone()),
+        ));
+
+        self.push_kind(kind);
+        self.push_group(Smart::Custom(group));
+        self.push_title(self.title(styles));
+        self.push_supplement(Smart::Custom(supplement.map(Supplement::Content)));
+        self.push_numbering(self.numbering(styles));
+        self.push_counter(Some(counter));
+
+        Ok(())
+    }
+}
+
+impl Show for TheoremElem {
+    #[tracing::instrument(name = "TheoremElem::show", skip_all)]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut head = self.head(engine)?;
+        if let Some(title) = self.title(styles) {
+            head += TextElem::packed(" (") + title + TextElem::packed(")");
+        }
+
+        let realized = StrongElem::new(head + TextElem::packed('.')).pack()
+            + SpaceElem::new().pack()
+            + self.body().clone();
+
+        Ok(BlockElem::new().with_body(Some(realized)).pack())
+    }
+}
+
+impl TheoremElem {
+    /// The supplement followed by the number.
+    fn head(&self, engine: &mut Engine) -> SourceResult<Content> {
+        let mut head = Refable::supplement(self);
+        if let (Some(counter), Some(numbering)) =
+            (self.counter(), self.numbering(StyleChain::default()))
+        {
+            let location = self.location().unwrap();
+            let numbers = counter.at(engine, location)?.display(engine, &numbering)?;
+            if !head.is_empty() {
+                head += TextElem::packed('\u{a0}');
+            }
+            head += numbers;
+        }
+        Ok(head)
+    }
+}
+
+impl Count for TheoremElem {
+    fn update(&self) -> Option<CounterUpdate> {
+        self.numbering(StyleChain::default())
+            .is_some()
+            .then(|| CounterUpdate::Step(NonZeroUsize::ONE))
+    }
+}
+
+impl Refable for TheoremElem {
+    fn supplement(&self) -> Content {
+        // After synthesis, this should always be custom content.
+        match self.supplement(StyleChain::default()).as_ref() {
+            Smart::Custom(Some(Supplement::Content(content))) => content.clone(),
+            _ => Content::empty(),
+        }
+    }
+
+    fn counter(&self) -> Counter {
+        self.counter().clone().unwrap_or_else(|| Counter::of(Self::elem()))
+    }
+
+    fn numbering(&self) -> Option<Numbering> {
+        self.numbering(StyleChain::default())
+    }
+}
+
+impl Outlinable for TheoremElem {
+    fn outline(&self, engine: &mut Engine) -> SourceResult<Option<Content>> {
+        let mut realized = self.head(engine)?;
+        if let Some(title) = self.title(StyleChain::default()) {
+            if !realized.is_empty() {
+                realized += TextElem::packed(": ");
+            }
+            realized += title;
+        }
+
+        Ok((!realized.is_empty()).then_some(realized))
+    }
+}
+
+impl LocalName for TheoremElem {
+    fn local_name(lang: Lang, _: Option<Region>) -> &'static str {
+        match lang {
+            Lang::ALBANIAN => "Teorema",
+            Lang::ARABIC => "مبرهنة",
+            Lang::BOKMÅL => "Teorem",
+            Lang::CHINESE => "定理",
+            Lang::CZECH => "Věta",
+            Lang::DANISH => "Sætning",
+            Lang::DUTCH => "Stelling",
+            Lang::FILIPINO => "Teorema",
+            Lang::FINNISH => "Lause",
+            Lang::FRENCH => "Théorème",
+            Lang::GERMAN => "Satz",
+            Lang::GREEK => "Θεώρημα",
+            Lang::HUNGARIAN => "Tétel",
+            Lang::ITALIAN => "Teorema",
+            Lang::NYNORSK => "Teorem",
+            Lang::POLISH => "Twierdzenie",
+            Lang::PORTUGUESE => "Teorema",
+            Lang::ROMANIAN => "Teorema",
+            Lang::RUSSIAN => "Теорема",
+            Lang::SLOVENIAN => "Izrek",
+            Lang::SPANISH => "Teorema",
+            Lang::SWEDISH => "Sats",
+            Lang::TURKISH => "Teorem",
+            Lang::UKRAINIAN => "Теорема",
+            Lang::VIETNAMESE => "Định lý",
+            Lang::JAPANESE => "定理",
+            Lang::ENGLISH | _ => "Theorem",
+        }
+    }
+}
+
+/// Capitalize the first letter of a kind for use as a supplement.
+fn capitalize(kind: &str) -> EcoString {
+    let mut chars = kind.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => EcoString::new(),
+    }
+}
@@ -0,0 +1,194 @@
+//! Compilation against worlds that load files asynchronously.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+use comemo::Prehashed;
+use ecow::EcoString;
+
+use crate::diag::{FileError, FileResult, SourceResult};
+use crate::eval::Tracer;
+use crate::foundations::{Bytes, Datetime};
+use crate::model::Document;
+use crate::syntax::{FileId, PackageSpec, Source};
+use crate::text::{Font, FontBook};
+use crate::{Library, World};
+
+/// A future returned by an [`AsyncWorld`].
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// An environment in which typesetting occurs and whose files are loaded
+/// asynchronously.
+///
+/// This is like [`World`], except that source files and other files are
+/// returned as futures. Hosts that fetch files over the network, like web
+/// applications or language servers backed by remote file systems and package
+/// registries, can implement this trait instead of blocking in [`World::file`].
+/// A document can then be compiled with [`compile_async`] or, for finer
+/// control, through a [`Prefetch`].
+pub trait AsyncWorld {
+    /// The standard library.
+    fn library(&self) -> &Prehashed<Library>;
+
+    /// Metadata about all known fonts.
+    fn book(&self) -> &Prehashed<FontBook>;
+
+    /// Access the main source file.
+    fn main(&self) -> Source;
+
+    /// Load the specified source file.
+    fn source(&self, id: FileId) -> BoxFuture<'_, FileResult<Source>>;
+
+    /// Load the specified file.
+    fn file(&self, id: FileId) -> BoxFuture<'_, FileResult<Bytes>>;
+
+    /// Try to access the font with the given index in the font book.
+    fn font(&self, index: usize) -> Option<Font>;
+
+    /// Get the current date. See [`World::today`] for details.
+    fn today(&self, offset: Option<i64>) -> Option<Datetime>;
+
+    /// A list of all available packages and optionally descriptions for them.
+    ///
+    /// This function is optional to implement.
+    fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
+        &[]
+    }
+}
+
+/// Compile the main source file of an asynchronous world.
+///
+/// Compiles against a [`Prefetch`] of the world until no more files are
+/// missing. Each time the compiler asks for files that aren't loaded yet, they
+/// are loaded and the compilation is repeated. Thanks to the compiler's
+/// caching, a repetition only redoes the work that depends on the new files.
+///
+/// The tracer receives the warnings of the last compilation.
+pub async fn compile_async(
+    world: &dyn AsyncWorld,
+    tracer: &mut Tracer,
+) -> SourceResult<Document> {
+    let prefetch = Prefetch::new(world);
+    loop {
+        let mut attempt = tracer.clone();
+        let result = crate::compile(&prefetch, &mut attempt);
+        if !prefetch.is_pending() {
+            *tracer = attempt;
+            return result;
+        }
+
+        prefetch.fetch().await;
+    }
+}
+
+/// Adapts an [`AsyncWorld`] into a [`World`].
+///
+/// A prefetch serves files that were already loaded and notes all other
+/// requested files, failing their access for now. After a compilation or an
+/// IDE query, the noted files can be loaded with [`fetch`](Self::fetch), so
+/// that the next attempt gets further. This way, the compiler never waits for
+/// I/O.
+pub struct Prefetch<'a> {
+    world: &'a dyn AsyncWorld,
+    main: Source,
+    sources: Mutex<HashMap<FileId, FileResult<Source>>>,
+    files: Mutex<HashMap<FileId, FileResult<Bytes>>>,
+    pending: Mutex<Vec<Request>>,
+}
+
+/// A file that was requested, but isn't loaded yet.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Request {
+    Source(FileId),
+    File(FileId),
+}
+
+impl<'a> Prefetch<'a> {
+    /// Create a prefetch for a world, with no files loaded yet.
+    pub fn new(world: &'a dyn AsyncWorld) -> Self {
+        Self {
+            world,
+            main: world.main(),
+            sources: Mutex::new(HashMap::new()),
+            files: Mutex::new(HashMap::new()),
+            pending: Mutex::new(vec![]),
+        }
+    }
+
+    /// Whether files were requested that aren't loaded yet.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.lock().unwrap().is_empty()
+    }
+
+    /// Load all files that were requested, but aren't loaded yet.
+    pub async fn fetch(&self) {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        for request in pending {
+            match request {
+                Request::Source(id) => {
+                    let result = self.world.source(id).await;
+                    self.sources.lock().unwrap().insert(id, result);
+                }
+                Request::File(id) => {
+                    let result = self.world.file(id).await;
+                    self.files.lock().unwrap().insert(id, result);
+                }
+            }
+        }
+    }
+
+    /// Note a file that isn't loaded yet and produce the error for it.
+    fn request(&self, request: Request) -> FileError {
+        let mut pending = self.pending.lock().unwrap();
+        if !pending.contains(&request) {
+            pending.push(request);
+        }
+        FileError::Other(Some("file is not loaded yet".into()))
+    }
+}
+
+impl World for Prefetch<'_> {
+    fn library(&self) -> &Prehashed<Library> {
+        self.world.library()
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        self.world.book()
+    }
+
+    fn main(&self) -> Source {
+        self.main.clone()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        if id == self.main.id() {
+            return Ok(self.main.clone());
+        }
+
+        match self.sources.lock().unwrap().get(&id) {
+            Some(result) => result.clone(),
+            None => Err(self.request(Request::Source(id))),
+        }
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        match self.files.lock().unwrap().get(&id) {
+            Some(result) => result.clone(),
+            None => Err(self.request(Request::File(id))),
+        }
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.world.font(index)
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        self.world.today(offset)
+    }
+
+    fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
+        self.world.packages()
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use crate::diag::SourceResult;
+use crate::foundations::Content;
+use crate::util::hash128;
+
+/// A transformation that runs over the content tree between evaluation and
+/// layout.
+///
+/// Hooks allow embedders to transform documents without patching the
+/// compiler, for example to redact content or to inject watermarks. They are
+/// registered in the [`Library`](crate::Library) and applied in order to the
+/// content of each document before it is laid out.
+///
+/// Hooks are identified by their name and key: Two hooks with the same name
+/// and key must perform the same transformation. Otherwise, incremental
+/// compilation may produce stale results. A hook whose transformation depends
+/// on configuration, like the list of words to redact, should thus include
+/// that configuration in its [key](Self::with_key).
+#[derive(Clone)]
+pub struct RealizeHook {
+    name: &'static str,
+    key: u128,
+    func: Arc<dyn Fn(Content) -> SourceResult<Content> + Send + Sync>,
+}
+
+impl RealizeHook {
+    /// Create a new hook with a unique name from a transformation function.
+    pub fn new<F>(name: &'static str, func: F) -> Self
+    where
+        F: Fn(Content) -> SourceResult<Content> + Send + Sync + 'static,
+    {
+        Self { name, key: 0, func: Arc::new(func) }
+    }
+
+    /// Distinguish the hook from others with the same name by a key that
+    /// identifies its configuration.
+    pub fn with_key(mut self, key: impl Hash) -> Self {
+        self.key = hash128(&key);
+        self
+    }
+
+    /// The hook's name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Apply the hook to a document's content.
+    pub fn apply(&self, content: Content) -> SourceResult<Content> {
+        (self.func)(content)
+    }
+}
+
+impl Debug for RealizeHook {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "RealizeHook({})", self.name)
+    }
+}
+
+impl Hash for RealizeHook {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.key.hash(state);
+    }
+}
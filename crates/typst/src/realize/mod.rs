@@ -1,8 +1,10 @@
 //! Realization of content.
 
 mod behave;
+mod hook;
 
 pub use self::behave::BehavedBuilder;
+pub use self::hook::RealizeHook;
 
 use std::borrow::Cow;
 use std::mem;
@@ -10,13 +12,14 @@ use std::mem;
 use smallvec::smallvec;
 use typed_arena::Arena;
 
-use crate::diag::{bail, error, SourceResult};
+use crate::diag::{bail, error, SourceResult, Trace, Tracepoint};
 use crate::engine::Engine;
+use crate::eval::StyleOrigin;
 use crate::foundations::{
-    Content, Finalize, Guard, NativeElement, Recipe, Selector, Show, StyleChain,
-    StyleVecBuilder, Styles, Synthesize,
+    Content, Finalize, Guard, LabelNamespaceElem, NativeElement, Recipe, Selector, Show,
+    StyleChain, StyleVecBuilder, Styles, Synthesize,
 };
-use crate::introspection::{Locatable, Meta, MetaElem};
+use crate::introspection::{Locatable, Location, Meta, MetaElem};
 use crate::layout::{
     AlignElem, BlockElem, BoxElem, ColbreakElem, FlowElem, HElem, Layout, LayoutRoot,
     PageElem, PagebreakElem, Parity, PlaceElem, VElem,
@@ -30,8 +33,8 @@ use crate::syntax::Span;
 use crate::text::{LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
 use crate::util::hash128;
 use crate::visualize::{
-    CircleElem, EllipseElem, ImageElem, LineElem, PathElem, PolygonElem, RectElem,
-    SquareElem,
+    CircleElem, ConnectElem, EllipseElem, ImageElem, LineElem, PathElem, PolygonElem,
+    RectElem, SquareElem,
 };
 
 /// Realize into an element that is capable of root-level layout.
@@ -100,7 +103,8 @@ pub fn applicable(target: &Content, styles: StyleChain) -> bool {
     let mut n = styles.recipes().count();
 
     // Find out whether any recipe matches and is unguarded.
-    for recipe in styles.recipes() {
+    for (recipe, outer) in styles.recipes_with_outer() {
+        let recipe = LabelNamespaceElem::qualify_recipe(recipe, outer);
         if recipe.applicable(target) && !target.is_guarded(Guard::Nth(n)) {
             return true;
         }
@@ -116,9 +120,26 @@ pub fn realize(
     target: &Content,
     styles: StyleChain,
 ) -> SourceResult<Option<Content>> {
+    Ok(realize_with_rule(engine, target, styles)?.map(|(realized, _)| realized))
+}
+
+/// Apply the show rules in the given style chain to a target and also return
+/// the span of the show rule that generated the content, if any.
+fn realize_with_rule(
+    engine: &mut Engine,
+    target: &Content,
+    styles: StyleChain,
+) -> SourceResult<Option<(Content, Option<Span>)>> {
     // Pre-process.
     if target.needs_preparation() {
         let mut elem = target.clone();
+        if let Some(label) = target.label() {
+            let qualified = LabelNamespaceElem::qualify(label, styles);
+            if qualified != label {
+                elem = elem.labelled(qualified);
+            }
+        }
+
         if target.can::<dyn Locatable>() || target.label().is_some() {
             let location = engine.locator.locate(hash128(target));
             elem.set_location(location);
@@ -130,16 +151,23 @@ pub fn realize(
 
         elem.mark_prepared();
 
-        if elem.location().is_some() {
+        if let Some(location) = elem.location() {
             let span = elem.span();
             let meta = Meta::Elem(elem.clone());
-            return Ok(Some(
-                (elem + MetaElem::new().pack().spanned(span))
-                    .styled(MetaElem::set_data(smallvec![meta])),
-            ));
+            let mut marker = MetaElem::new().pack().spanned(span);
+
+            // Record the innermost enclosing element so that the introspector
+            // can reconstruct the nesting of elements.
+            if let Some(parent) = enclosing(styles) {
+                let nesting = Meta::Parent { child: location, parent };
+                marker = marker.styled(MetaElem::set_data(smallvec![nesting]));
+            }
+
+            let marked = (elem + marker).styled(MetaElem::set_data(smallvec![meta]));
+            return Ok(Some((marked, None)));
         }
 
-        return Ok(Some(elem));
+        return Ok(Some((elem, None)));
     }
 
     // Find out how many recipes there are.
@@ -147,11 +175,17 @@ pub fn realize(
 
     // Find an applicable recipe.
     let mut realized = None;
-    for recipe in styles.recipes() {
+    let mut rule = None;
+    for (recipe, outer) in styles.recipes_with_outer() {
+        let recipe = LabelNamespaceElem::qualify_recipe(recipe, outer);
         let guard = Guard::Nth(n);
         if recipe.applicable(target) && !target.is_guarded(guard) {
-            if let Some(content) = try_apply(engine, target, recipe, guard)? {
+            if let Some(content) = try_apply(engine, target, &recipe, guard)? {
+                if engine.tracer.inspected_elem() == Some(target.span()) {
+                    engine.tracer.origin(StyleOrigin::Show { span: recipe.span });
+                }
                 realized = Some(content);
+                rule = Some(recipe.span);
                 break;
             }
         }
@@ -175,7 +209,59 @@ pub fn realize(
         }
     }
 
-    Ok(realized)
+    // The content that an inspected element is realized into inherits its
+    // styles, so the set rules for that content affect the element, too.
+    if let Some(realized) = &realized {
+        if engine.tracer.inspected_elem() == Some(target.span()) {
+            trace_set_rules(engine, realized, styles);
+        }
+    }
+
+    // Record where the content was generated so that inverse search can
+    // point to both the element and the show rule. This is only done on
+    // request since it adds metadata to the frames for every application of
+    // a show rule.
+    if let Some(rule) = rule.filter(|_| engine.tracer.records_origins()) {
+        let origin = Meta::Origin { call: target.span(), rule };
+        realized =
+            realized.map(|content| content.styled(MetaElem::set_data(smallvec![origin])));
+    }
+
+    Ok(realized.map(|realized| (realized, rule)))
+}
+
+/// Trace the set rules that affect the properties of an inspected element.
+///
+/// Besides the rules for the element itself, these are the rules for all
+/// elements nested in it, like the text in a heading's body, as these inherit
+/// the element's styles. Outer rules for a property are traced, too, as
+/// folded properties combine them with the inner ones.
+fn trace_set_rules(engine: &mut Engine, content: &Content, styles: StyleChain) {
+    let mut elems = Vec::new();
+    content.traverse(&mut |child| {
+        let elem = child.func();
+        if !elems.contains(&elem) {
+            elems.push(elem);
+        }
+    });
+
+    for elem in elems {
+        for (id, span) in styles.set_rules(elem) {
+            if let Some(field) = elem.field_name(id) {
+                engine.tracer.origin(StyleOrigin::Set { elem, field, span });
+            }
+        }
+    }
+}
+
+/// The location of the innermost located element the styles belong to.
+///
+/// Synthetic locations (like those of backlinks) are skipped.
+fn enclosing(styles: StyleChain) -> Option<Location> {
+    MetaElem::data_in(styles).into_iter().find_map(|meta| match meta {
+        Meta::Elem(elem) => elem.location().filter(|loc| loc.variant == 0),
+        _ => None,
+    })
 }
 
 /// Try to apply a recipe to the target.
@@ -248,7 +334,8 @@ fn try_apply(
             | Selector::Location(_)
             | Selector::Can(_)
             | Selector::Before { .. }
-            | Selector::After { .. },
+            | Selector::After { .. }
+            | Selector::Within { .. },
         ) => Ok(None),
 
         None => Ok(None),
@@ -305,7 +392,11 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
                 self.scratch.content.alloc(EquationElem::new(content.clone()).pack());
         }
 
-        if let Some(realized) = realize(self.engine, content, styles)? {
+        if self.engine.tracer.inspected_elem() == Some(content.span()) {
+            trace_set_rules(self.engine, content, styles);
+        }
+
+        if let Some((realized, rule)) = realize_with_rule(self.engine, content, styles)? {
             self.engine.route.increase();
             if self.engine.route.exceeding() {
                 bail!(error!(content.span(), "maximum show rule depth exceeded")
@@ -315,7 +406,11 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
                     ));
             }
             let stored = self.scratch.content.alloc(realized);
-            let v = self.accept(stored, styles);
+            let mut v = self.accept(stored, styles);
+            if rule.is_some() {
+                let point = || Tracepoint::Show(content.func().name().into());
+                v = v.trace(self.engine.world, point, content.span());
+            }
             self.engine.route.decrease();
             return v;
         }
@@ -351,6 +446,10 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
             return Ok(());
         }
 
+        if content.is::<ParbreakElem>() && !self.par.0.is_empty() {
+            self.par.1 = true;
+        }
+
         self.interrupt_par()?;
 
         if self.flow.accept(content, styles) {
@@ -454,7 +553,10 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
     fn interrupt_par(&mut self) -> SourceResult<()> {
         self.interrupt_list()?;
         if !self.par.0.is_empty() {
-            let (par, styles) = mem::take(&mut self.par).finish();
+            // Paragraphs on the page stand on their own. In containers, only
+            // those delimited by a paragraph break do.
+            let standalone = self.doc.is_some() || self.flow.1 || self.par.1;
+            let (par, styles) = mem::take(&mut self.par).finish(standalone);
             let stored = self.scratch.content.alloc(par);
             self.accept(stored, styles)?;
         }
@@ -548,6 +650,7 @@ impl<'a> FlowBuilder<'a> {
             || content.is::<ColbreakElem>()
             || content.is::<MetaElem>()
             || content.is::<PlaceElem>()
+            || content.is::<ConnectElem>()
         {
             self.0.push(Cow::Borrowed(content), styles);
             return true;
@@ -586,9 +689,9 @@ impl<'a> FlowBuilder<'a> {
     }
 }
 
-/// Accepts paragraph content.
+/// Accepts paragraph content and tracks whether a paragraph break follows it.
 #[derive(Default)]
-struct ParBuilder<'a>(BehavedBuilder<'a>);
+struct ParBuilder<'a>(BehavedBuilder<'a>, bool);
 
 impl<'a> ParBuilder<'a> {
     fn accept(&mut self, content: &'a Content, styles: StyleChain<'a>) -> bool {
@@ -612,7 +715,7 @@ impl<'a> ParBuilder<'a> {
         false
     }
 
-    fn finish(self) -> (Content, StyleChain<'a>) {
+    fn finish(self, standalone: bool) -> (Content, StyleChain<'a>) {
         let (children, shared) = self.0.finish();
 
         // Find the first span that isn't detached.
@@ -622,7 +725,9 @@ impl<'a> ParBuilder<'a> {
             .find(|span| !span.is_detached())
             .unwrap_or_else(Span::detached);
 
-        (ParElem::new(children.to_vec()).spanned(span).pack(), shared)
+        let mut par = ParElem::new(children.to_vec());
+        par.push_standalone(standalone);
+        (par.spanned(span).pack(), shared)
     }
 }
 
@@ -6,8 +6,8 @@ use std::sync::Arc;
 use ecow::{eco_format, EcoString};
 use serde::{Serialize, Serializer};
 
-use crate::diag::{bail, SourceResult, StrResult};
-use crate::foundations::{cast, func, scope, ty, Array};
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::foundations::{cast, func, scope, ty, Array, Dict, Module, Scope, Str, Value};
 use crate::syntax::{Span, Spanned};
 
 #[doc(inline)]
@@ -206,6 +206,55 @@ impl Symbol {
         }
         Ok(Symbol::runtime(list.into_boxed_slice()))
     }
+
+    /// Create a module of symbols from a table, for example one loaded from a
+    /// data file.
+    ///
+    /// Each entry maps a symbol name to either a single character, a Unicode
+    /// codepoint, or a dictionary from modifiers to characters or codepoints.
+    /// The empty modifier `{""}` denotes the variant without modifiers.
+    ///
+    /// Modules cannot be changed, so the symbols are not added to the built-in
+    /// `sym` module. Instead, a package can export the resulting module and its
+    /// users can import it like any other module.
+    ///
+    /// ```example
+    /// #let chem = symbol.module("chem", (
+    ///   bond: ("": "–", double: "=", triple: "≡"),
+    ///   eq: 0x21CC,
+    /// ))
+    ///
+    /// #chem.bond.triple \
+    /// $A chem.eq B$
+    /// ```
+    #[func]
+    pub fn module(
+        /// The name of the module.
+        name: Str,
+        /// The symbols, keyed by name.
+        table: Spanned<Dict>,
+    ) -> SourceResult<Module> {
+        let Spanned { v: table, span } = table;
+        let mut scope = Scope::new();
+        for (name, value) in table {
+            let symbol = match value {
+                Value::Dict(variants) => {
+                    let mut list = Vec::new();
+                    for (modifiers, value) in variants {
+                        let TableChar(c) = value.cast().at(span)?;
+                        list.push((modifiers.into(), c));
+                    }
+                    if list.is_empty() {
+                        bail!(span, "symbol `{name}` has no variants");
+                    }
+                    Symbol::runtime(list.into_boxed_slice())
+                }
+                value => Symbol::single(value.cast::<TableChar>().at(span)?.0),
+            };
+            scope.define(name, symbol);
+        }
+        Ok(Module::new(name, scope))
+    }
 }
 
 impl Display for Symbol {
@@ -273,6 +322,15 @@ cast! {
     },
 }
 
+/// A character in a symbol table, given as a string or a codepoint.
+struct TableChar(char);
+
+cast! {
+    TableChar,
+    c: char => Self(c),
+    v: u32 => Self(char::from_u32(v).ok_or("invalid codepoint")?),
+}
+
 /// Iterator over variants.
 enum Variants<'a> {
     Single(std::option::IntoIter<char>),
@@ -158,6 +158,9 @@ pub struct FontMetrics {
     pub x_height: Em,
     /// The distance from the baseline to the typographic descender.
     pub descender: Em,
+    /// The recommended gap between the descender of one line and the
+    /// ascender of the next.
+    pub line_gap: Em,
     /// Recommended metrics for a strikethrough line.
     pub strikethrough: LineMetrics,
     /// Recommended metrics for an underline.
@@ -176,6 +179,7 @@ impl FontMetrics {
         let cap_height = ttf.capital_height().filter(|&h| h > 0).map_or(ascender, to_em);
         let x_height = ttf.x_height().filter(|&h| h > 0).map_or(ascender, to_em);
         let descender = to_em(ttf.typographic_descender().unwrap_or(ttf.descender()));
+        let line_gap = to_em(ttf.typographic_line_gap().unwrap_or(ttf.line_gap()));
         let strikeout = ttf.strikeout_metrics();
         let underline = ttf.underline_metrics();
 
@@ -204,6 +208,7 @@ impl FontMetrics {
             cap_height,
             x_height,
             descender,
+            line_gap,
             strikethrough,
             underline,
             overline,
@@ -5,8 +5,8 @@ use ecow::EcoString;
 
 use crate::layout::{Abs, Em};
 use crate::syntax::Span;
-use crate::text::{Font, Lang};
-use crate::visualize::Paint;
+use crate::text::{Font, Lang, Region};
+use crate::visualize::{FixedStroke, Paint};
 
 /// A run of shaped text.
 #[derive(Clone, Eq, PartialEq, Hash)]
@@ -17,8 +17,12 @@ pub struct TextItem {
     pub size: Abs,
     /// Glyph color.
     pub fill: Paint,
+    /// Glyph outline stroke, if any.
+    pub stroke: Option<FixedStroke>,
     /// The natural language of the text.
     pub lang: Lang,
+    /// The region of the text, if any.
+    pub region: Option<Region>,
     /// The item's plain text.
     pub text: EcoString,
     /// The glyphs.
@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+use ecow::EcoString;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::layout::{Frame, FrameItem, Point, Size, Transform};
+use crate::text::TextItem;
+
+/// The selectable text of a page.
+///
+/// Contains the page's text alongside bounding boxes of its glyphs and words
+/// in page coordinates. This allows viewers to implement text selection and
+/// search highlighting on top of rendered pages.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub struct TextLayer {
+    /// The text of the page. Lines and pieces of text that don't directly
+    /// follow each other, like those of neighbouring columns, are separated
+    /// by newlines.
+    pub text: EcoString,
+    /// The boxes of the page's glyph clusters in the order in which they
+    /// appear on the page.
+    pub glyphs: Vec<TextBox>,
+    /// The boxes of the page's words in reading order.
+    pub words: Vec<TextBox>,
+}
+
+/// The bounding box of a piece of text on a page.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct TextBox {
+    /// The byte range of the piece in the text of the layer.
+    pub range: Range<usize>,
+    /// The top-left corner of the box.
+    pub pos: Point,
+    /// The size of the box.
+    pub size: Size,
+}
+
+impl TextLayer {
+    /// Extract the text layer of a page.
+    pub fn new(page: &Frame) -> Self {
+        let mut layer = Self::default();
+        layer.extract(page, Transform::identity(), &mut None);
+
+        let mut words: Vec<(Range<usize>, Option<(Point, Point)>)> = layer
+            .text
+            .unicode_word_indices()
+            .map(|(offset, word)| (offset..offset + word.len(), None))
+            .collect();
+
+        for glyph in &layer.glyphs {
+            let i = words.partition_point(|(range, _)| range.end <= glyph.range.start);
+            let Some((range, bounds)) = words.get_mut(i) else { continue };
+            if range.start >= glyph.range.end {
+                continue;
+            }
+
+            let (min, max) = (glyph.pos, glyph.pos + glyph.size.to_point());
+            *bounds = Some(match *bounds {
+                Some((a, b)) => (a.min(min), b.max(max)),
+                None => (min, max),
+            });
+        }
+
+        let words = words
+            .into_iter()
+            .filter_map(|(range, bounds)| {
+                let (min, max) = bounds?;
+                Some(TextBox { range, pos: min, size: (max - min).to_size() })
+            })
+            .collect();
+
+        layer.words = words;
+        layer
+    }
+
+    /// Extract the glyphs of a frame, given where the previous text item
+    /// ended.
+    fn extract(&mut self, frame: &Frame, ts: Transform, end: &mut Option<Point>) {
+        for (pos, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => {
+                    let ts = ts
+                        .pre_concat(Transform::translate(pos.x, pos.y))
+                        .pre_concat(group.transform);
+                    self.extract(&group.frame, ts, end);
+                }
+                FrameItem::Text(text) => {
+                    let start = pos.transform(ts);
+                    if end.map_or(false, |end| {
+                        !end.x.approx_eq(start.x) || !end.y.approx_eq(start.y)
+                    }) {
+                        self.text.push('\n');
+                    }
+                    *end = Some((*pos + Point::with_x(text.width())).transform(ts));
+                    self.push(text, *pos, ts);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Add the glyphs of a text item at the given position.
+    fn push(&mut self, text: &TextItem, pos: Point, ts: Transform) {
+        let base = self.text.len();
+        let metrics = text.font.metrics();
+        let top = pos.y - metrics.ascender.at(text.size);
+        let bottom = pos.y - metrics.descender.at(text.size);
+
+        let mut x = pos.x;
+        for glyph in &text.glyphs {
+            let left = x + glyph.x_offset.at(text.size);
+            let right = left + glyph.x_advance.at(text.size);
+            x += glyph.x_advance.at(text.size);
+
+            let corners = [
+                Point::new(left, top),
+                Point::new(right, top),
+                Point::new(left, bottom),
+                Point::new(right, bottom),
+            ]
+            .map(|point| point.transform(ts));
+
+            let mut min = corners.into_iter().reduce(Point::min).unwrap();
+            let mut max = corners.into_iter().reduce(Point::max).unwrap();
+
+            // Glyphs of the same cluster share a box.
+            let range = base + glyph.range().start..base + glyph.range().end;
+            if let Some(prev) = self.glyphs.last_mut().filter(|prev| prev.range == range)
+            {
+                min = min.min(prev.pos);
+                max = max.max(prev.pos + prev.size.to_point());
+                prev.pos = min;
+                prev.size = (max - min).to_size();
+            } else {
+                self.glyphs.push(TextBox {
+                    range,
+                    pos: min,
+                    size: (max - min).to_size(),
+                });
+            }
+        }
+
+        self.text.push_str(&text.text);
+    }
+}
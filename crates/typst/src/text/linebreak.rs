@@ -1,4 +1,5 @@
-use crate::foundations::{elem, Behave, Behaviour};
+use crate::foundations::{elem, func, Behave, Behaviour, Content};
+use crate::text::TextElem;
 
 /// Inserts a line break.
 ///
@@ -41,3 +42,59 @@ impl Behave for LinebreakElem {
         Behaviour::Destructive
     }
 }
+
+/// Prevents line breaks within content.
+///
+/// The content is kept on one line, even if it contains spaces or could be
+/// hyphenated. Lines can still break before and after it.
+///
+/// # Example
+/// ```example
+/// #set page(width: 120pt)
+/// The meeting starts at
+/// #nobreak[10:30 a.m.] in
+/// room #nobreak[B 204].
+/// ```
+#[func(title = "No Break")]
+pub fn nobreak(
+    /// The content to keep on one line.
+    body: Content,
+) -> Content {
+    body.styled(TextElem::set_nobreak(true))
+}
+
+/// Inserts a soft hyphen.
+///
+/// A soft hyphen marks a position where a word may be hyphenated. It is
+/// invisible unless the line is broken there, in which case a hyphen is
+/// shown. This works even if [hyphenation]($text.hyphenate) is turned off.
+///
+/// # Example
+/// ```example
+/// #set page(width: 80pt)
+/// Extra#shy()ordinarily
+/// long#shy()winded words.
+/// ```
+///
+/// # Syntax
+/// This function also has dedicated syntax: Write `-?` to insert a soft
+/// hyphen.
+#[func(title = "Soft Hyphen")]
+pub fn shy() -> Content {
+    TextElem::packed('\u{ad}')
+}
+
+/// Inserts a word joiner.
+///
+/// A word joiner is an invisible character that prevents a line break at its
+/// position, for example, between a word and a following dash.
+///
+/// # Example
+/// ```example
+/// #set page(width: 100pt)
+/// The first#wj()---and only---attempt.
+/// ```
+#[func(title = "Word Joiner")]
+pub fn wj() -> Content {
+    TextElem::packed('\u{2060}')
+}
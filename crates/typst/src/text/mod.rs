@@ -5,10 +5,12 @@ mod deco;
 mod font;
 mod item;
 mod lang;
+mod layer;
 mod linebreak;
 #[path = "lorem.rs"]
 mod lorem_;
 mod raw;
+mod run;
 mod shift;
 #[path = "smallcaps.rs"]
 mod smallcaps_;
@@ -20,9 +22,11 @@ pub use self::deco::*;
 pub use self::font::*;
 pub use self::item::*;
 pub use self::lang::*;
+pub use self::layer::*;
 pub use self::linebreak::*;
 pub use self::lorem_::*;
 pub use self::raw::*;
+pub use self::run::*;
 pub use self::shift::*;
 pub use self::smallcaps_::*;
 pub use self::smartquote::*;
@@ -34,16 +38,16 @@ use ecow::{eco_format, EcoString};
 use rustybuzz::{Feature, Tag};
 use ttf_parser::Rect;
 
-use crate::diag::{bail, error, SourceResult, StrResult};
+use crate::diag::{bail, error, warning, SourceResult, StrResult, WarningCategory};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, category, elem, Args, Array, Cast, Category, Construct, Content, Dict, Fold,
     NativeElement, Never, PlainText, Repr, Resolve, Scope, Set, Smart, StyleChain, Value,
 };
 use crate::layout::{Abs, Axis, Dir, Length, Rel};
-use crate::model::ParElem;
+use crate::model::{LineHeight, ParElem};
 use crate::syntax::Spanned;
-use crate::visualize::{Color, Paint, RelativeTo};
+use crate::visualize::{Color, Paint, RelativeTo, Stroke};
 
 /// Text styling.
 ///
@@ -68,6 +72,9 @@ pub(super) fn define(global: &mut Scope) {
     global.define_func::<upper>();
     global.define_func::<smallcaps>();
     global.define_func::<lorem>();
+    global.define_func::<nobreak>();
+    global.define_func::<shy>();
+    global.define_func::<wj>();
 }
 
 /// Customizes the look and layout of text in a variety of ways.
@@ -93,6 +100,7 @@ pub struct TextElem {
     /// until it finds a font that has the necessary glyphs. In the example
     /// below, the font `Inria Serif` is preferred, but since it does not
     /// contain Arabic glyphs, the arabic text uses `Noto Sans Arabic` instead.
+    /// Families that aren't available at all produce a warning.
     ///
     /// The collection of available fonts differs by platform:
     ///
@@ -117,6 +125,21 @@ pub struct TextElem {
     /// This is Latin. \
     /// هذا عربي.
     /// ```
+    #[parse({
+        let font_list: Option<Spanned<FontList>> = args.named("font")?;
+        if let Some(Spanned { v: list, span }) = &font_list {
+            let book = engine.world.book();
+            for family in list {
+                if book.select_family(family.as_str()).next().is_none() {
+                    engine.tracer.warn(
+                        warning!(*span, "unknown font family: {}", family.as_str())
+                            .with_category(WarningCategory::UnknownFont),
+                    );
+                }
+            }
+        }
+        font_list.map(|list| list.v)
+    })]
     #[default(FontList(vec![FontFamily::new("Linux Libertine")]))]
     #[borrowed]
     #[ghost]
@@ -219,28 +242,57 @@ pub struct TextElem {
 
     /// The glyph fill paint.
     ///
+    /// Gradients and patterns are relative to the closest ancestor container
+    /// by default. If their [relativeness]($gradient.relative) is set to
+    /// `{"self"}`, each glyph is painted individually instead, and a gradient
+    /// or pattern [stroke]($text.stroke) then also spans just its glyph. Since
+    /// a [pattern]($pattern) can contain arbitrary content, this also allows
+    /// filling text with an image.
+    ///
     /// ```example
     /// #set text(fill: red)
     /// This text is red.
+    ///
+    /// #set text(size: 20pt, fill: gradient.linear(
+    ///   red, blue, relative: "self",
+    /// ))
+    /// Glyph by glyph.
+    /// ```
+    #[parse(args.named_or_find("fill")?)]
+    #[default(Color::BLACK.into())]
+    #[ghost]
+    pub fill: Paint,
+
+    /// How to [stroke]($stroke) the glyph outlines.
+    ///
+    /// The stroke is drawn on top of the fill. To draw only the outlines, set
+    /// the fill to a transparent color.
+    ///
+    /// ```example
+    /// #set text(size: 28pt, weight: "bold")
+    /// #text(stroke: 1pt + red)[Outlined]
+    /// #text(fill: white, stroke: 0.5pt + black)[Hollow]
     /// ```
     #[parse({
-        let paint: Option<Spanned<Paint>> = args.named_or_find("fill")?;
-        if let Some(paint) = &paint {
-            if paint.v.relative() == Smart::Custom(RelativeTo::Self_) {
+        let stroke: Option<Spanned<Option<Stroke>>> = args.named("stroke")?;
+        if let Some(Spanned { v: Some(stroke), span }) = &stroke {
+            let relative = stroke.paint.as_ref().map_or(Smart::Auto, Paint::relative);
+            if relative == Smart::Custom(RelativeTo::Self_) {
                 bail!(
                     error!(
-                        paint.span,
+                        *span,
                         "gradients and patterns on text must be relative to the parent"
                     )
-                    .with_hint("make sure to set `relative: auto` on your text fill")
+                    .with_hint("make sure to set `relative: auto` on your text stroke")
                 );
             }
         }
-        paint.map(|paint| paint.v)
+        stroke.map(|stroke| stroke.v)
     })]
-    #[default(Color::BLACK.into())]
+    #[resolve]
+    #[fold]
     #[ghost]
-    pub fill: Paint,
+    pub stroke: Option<Stroke>,
 
     /// The amount of space that should be added between characters.
     ///
@@ -345,6 +397,29 @@ pub struct TextElem {
     #[ghost]
     pub bottom_edge: BottomEdge,
 
+    /// How much height the text takes up in the lines of a paragraph.
+    ///
+    /// - `{auto}`: The text uses the paragraph's
+    ///   [line height]($par.line-height).
+    /// - `{"normal"}`: The text takes up the ascender, descender, and line gap
+    ///   metrics of its font.
+    /// - A length: The text takes up this much height, distributed evenly
+    ///   above its font's ascender and below its descender, like an inline
+    ///   element's `line-height` in CSS.
+    ///
+    /// Lines grow to fit their tallest text, so this can make lines taller
+    /// than the paragraph's line height, but never shorter. If the paragraph
+    /// has a fixed line height, its baselines are kept in place and the line
+    /// height of the text is ignored.
+    ///
+    /// ```example
+    /// #set par(line-height: "normal")
+    /// Lines fit their text, but
+    /// #text(line-height: 2em)[this part] makes its line taller.
+    /// ```
+    #[ghost]
+    pub line_height: Smart<LineHeight>,
+
     /// An [ISO 639-1/2/3 language code.](https://en.wikipedia.org/wiki/ISO_639)
     ///
     /// Setting the correct language affects various parts of Typst:
@@ -640,6 +715,12 @@ pub struct TextElem {
     #[default(false)]
     #[ghost]
     pub smallcaps: bool,
+
+    /// Whether line breaks are forbidden within the text.
+    #[internal]
+    #[default(false)]
+    #[ghost]
+    pub nobreak: bool,
 }
 
 impl TextElem {
@@ -24,7 +24,7 @@ use crate::text::{
 };
 use crate::util::option_eq;
 use crate::visualize::Color;
-use crate::{syntax, World};
+use crate::{syntax, Load};
 
 // Shorthand for highlighter closures.
 type StyleFn<'a> = &'a mut dyn FnMut(&LinkedNode, Range<usize>, synt::Style) -> Content;
@@ -691,7 +691,7 @@ fn parse_syntaxes(
         .iter()
         .map(|path| {
             let id = span.resolve_path(path).at(span)?;
-            engine.world.file(id).at(span)
+            engine.world.load_file(id).at(span)
         })
         .collect::<SourceResult<Vec<Bytes>>>()?;
 
@@ -723,7 +723,7 @@ fn parse_theme(
 
     // Load theme file.
     let id = span.resolve_path(&path).at(span)?;
-    let data = engine.world.file(id).at(span)?;
+    let data = engine.world.load_file(id).at(span)?;
 
     // Check that parsing works.
     let _ = load_theme(&path, &data).at(span)?;
@@ -0,0 +1,118 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+
+use crate::layout::{Abs, Frame, FrameItem, Transform};
+use crate::syntax::Span;
+use crate::text::{Lang, Region, TextItem};
+
+/// A run of laid out text in a single language.
+///
+/// Text runs are useful to integrate external tools like spellcheckers and
+/// grammar checkers: They contain the text as it appears on the pages,
+/// annotated with its language and with the source locations each part of it
+/// originates from.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TextRun {
+    /// The run's text.
+    pub text: EcoString,
+    /// The language of the text.
+    pub lang: Lang,
+    /// The region of the text, if any.
+    pub region: Option<Region>,
+    /// The page the run is on.
+    pub page: NonZeroUsize,
+    /// Maps from byte offsets in the run's text to the source locations of
+    /// the text starting at these offsets. Sorted by offset.
+    pub spans: Vec<(usize, Span, u16)>,
+}
+
+impl TextRun {
+    /// Collect the text runs of the given pages in reading order.
+    ///
+    /// Consecutive text items on the same baseline and with the same
+    /// language and region are merged into one run. Each line of text starts
+    /// a new run.
+    pub fn collect(pages: &[Frame]) -> Vec<Self> {
+        let mut runs = vec![];
+        for (i, frame) in pages.iter().enumerate() {
+            let page = NonZeroUsize::new(1 + i).unwrap();
+            let mut builder = RunBuilder { runs: &mut runs, page, baseline: None };
+            builder.extract(frame, Transform::identity());
+        }
+        runs
+    }
+
+    /// The source location of the text at the given byte offset.
+    pub fn span(&self, offset: usize) -> Option<(Span, u16)> {
+        let i = match self.spans.binary_search_by_key(&offset, |&(o, ..)| o) {
+            Ok(i) => i,
+            Err(i) => i.checked_sub(1)?,
+        };
+        let (_, span, start) = self.spans[i];
+        Some((span, start))
+    }
+}
+
+/// Accumulates text runs for a page.
+struct RunBuilder<'a> {
+    runs: &'a mut Vec<TextRun>,
+    page: NonZeroUsize,
+    baseline: Option<Abs>,
+}
+
+impl RunBuilder<'_> {
+    /// Extract the text runs from a frame.
+    fn extract(&mut self, frame: &Frame, ts: Transform) {
+        for (pos, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => {
+                    let ts = ts
+                        .pre_concat(Transform::translate(pos.x, pos.y))
+                        .pre_concat(group.transform);
+                    self.extract(&group.frame, ts);
+                }
+                FrameItem::Text(text) => {
+                    self.push(text, pos.transform(ts).y);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Add a text item, either to the last run or to a new one.
+    fn push(&mut self, text: &TextItem, baseline: Abs) {
+        let continues = self.baseline.map_or(false, |prev| prev.approx_eq(baseline))
+            && self.runs.last().map_or(false, |run| {
+                run.page == self.page
+                    && run.lang == text.lang
+                    && run.region == text.region
+            });
+
+        if !continues {
+            self.runs.push(TextRun {
+                text: EcoString::new(),
+                lang: text.lang,
+                region: text.region,
+                page: self.page,
+                spans: vec![],
+            });
+        }
+
+        self.baseline = Some(baseline);
+
+        let run = self.runs.last_mut().unwrap();
+        let base = run.text.len();
+        for glyph in &text.glyphs {
+            let (span, start) = glyph.span;
+            if !span.is_detached() {
+                run.spans.push((base + glyph.range().start, span, start));
+            }
+        }
+
+        // Glyphs of right-to-left text are in visual order.
+        run.spans.sort_by_key(|&(offset, ..)| offset);
+        run.spans.dedup_by_key(|&mut (offset, ..)| offset);
+        run.text.push_str(&text.text);
+    }
+}
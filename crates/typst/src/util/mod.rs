@@ -1,6 +1,7 @@
 //! Utilities.
 
 pub mod fat;
+pub mod persist;
 
 #[macro_use]
 mod macros;
@@ -0,0 +1,40 @@
+//! Persisting expensive results across processes.
+//!
+//! Layout is memoized in memory and its results reference fonts and images
+//! that only live in the current process, so layouted frames cannot outlive
+//! it. The results of shaping text and of decoding raster images, however,
+//! are plain data that is fully determined by its inputs. The compiler
+//! consults the [store](crate::World::store) of the world before computing
+//! them and saves them into it afterwards, so that batch builds and CI runs
+//! can warm-start.
+
+use std::hash::{Hash, Hasher};
+
+/// A store for expensive results that outlives the process, like an on-disk
+/// cache.
+///
+/// Entries are identified by a kind (e.g. `"shape"`) and the hash of their
+/// inputs. Since the encoding of entries may change between versions of the
+/// compiler, stores that are shared between versions must take the version
+/// into account.
+pub trait Store: Send + Sync {
+    /// Load the entry of the given kind with the given key, if any.
+    fn load(&self, kind: &str, key: u128) -> Option<Vec<u8>>;
+
+    /// Save an entry of the given kind with the given key.
+    ///
+    /// Since the store is only an optimization, failing to save an entry is
+    /// not an error.
+    fn save(&self, kind: &str, key: u128, data: &[u8]);
+}
+
+/// Stores are hashed by their address.
+///
+/// The results of the compiler don't depend on the store, so this only
+/// decides whether work that was memoized with one store is reused with
+/// another one without consulting it.
+impl Hash for dyn Store + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self as *const Self).cast::<()>().hash(state);
+    }
+}
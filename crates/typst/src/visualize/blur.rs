@@ -0,0 +1,53 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, StyleChain};
+use crate::layout::{Abs, Axes, Fragment, Layout, Length, Regions};
+
+/// Blurs content without affecting layout.
+///
+/// The blur extends beyond the bounds of the content, but the layout acts as
+/// if the content was not blurred. In PDF export, blurred content is
+/// rasterized because PDF cannot express blurs natively.
+///
+/// # Example
+/// ```example
+/// #stack(
+///   dir: ltr,
+///   spacing: 12pt,
+///   text(24pt)[Sharp],
+///   blur(2pt, text(24pt)[Blurry]),
+/// )
+/// ```
+#[elem(Layout)]
+pub struct BlurElem {
+    /// The blur radius, that is, the standard deviation of the Gaussian blur.
+    ///
+    /// ```example
+    /// #for radius in (0pt, 1pt, 2pt, 4pt) {
+    ///   box(blur(radius, circle(radius: 10pt, fill: blue)))
+    /// }
+    /// ```
+    #[positional]
+    #[resolve]
+    #[default(Abs::pt(2.0).into())]
+    pub radius: Length,
+
+    /// The content to blur.
+    #[required]
+    pub body: Content,
+}
+
+impl Layout for BlurElem {
+    #[tracing::instrument(name = "BlurElem::layout", skip_all)]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let pod = Regions::one(regions.base(), Axes::splat(false));
+        let mut frame = self.body().layout(engine, styles, pod)?.into_frame();
+        frame.blur(self.radius(styles));
+        Ok(Fragment::frame(frame))
+    }
+}
@@ -0,0 +1,1119 @@
+//! Charts from arrays of data.
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use ecow::EcoString;
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    array, cast, dict, elem, repr, Array, Content, Datetime, Dict, Duration, IntoValue,
+    Module, NativeElement, Scope, Show, Smart, StyleChain, Value,
+};
+use crate::layout::{
+    Abs, Axes, BoxElem, Em, Fragment, Frame, FrameItem, Layout, Length, PlaceElem, Point,
+    Ratio, Regions, Rel, Size, Sizing,
+};
+use crate::syntax::Span;
+use crate::text::TextElem;
+use crate::util::{Numeric, Scalar};
+use crate::visualize::{
+    Color, FixedStroke, Geometry, Paint, Path, PathElem, PathVertex, Shape, Stroke,
+};
+
+/// A module with charts for everyday data.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define_elem::<BarChartElem>();
+    scope.define_elem::<LineChartElem>();
+    scope.define_elem::<PieChartElem>();
+    scope.define_elem::<GanttChartElem>();
+    scope.define_elem::<SparklineElem>();
+    scope.define_elem::<BulletElem>();
+    Module::new("chart", scope)
+}
+
+/// The space between axes, labels, and legends.
+const PAD: Abs = Abs::raw(4.0);
+
+/// The side length of a color swatch in a legend.
+const SWATCH: Abs = Abs::raw(8.0);
+
+/// A bar chart.
+///
+/// Each bar is given as a pair of a label and a value or as a plain value.
+/// The bars start at zero, so negative values point downward.
+///
+/// # Example
+/// ```example
+/// #chart.bar(
+///   height: 80pt,
+///   ("Q1", 12), ("Q2", 18),
+///   ("Q3", 9), ("Q4", 15),
+/// )
+/// ```
+#[elem(name = "bar", title = "Bar Chart", Layout)]
+pub struct BarChartElem {
+    /// The width of the chart, including its axes.
+    #[resolve]
+    #[default(Rel::one())]
+    pub width: Rel<Length>,
+
+    /// The height of the chart, including its axes.
+    #[resolve]
+    #[default(Abs::pt(120.0).into())]
+    pub height: Length,
+
+    /// How to fill the bars.
+    #[default(Color::BLUE.into())]
+    pub fill: Paint,
+
+    /// The bars.
+    #[variadic]
+    pub data: Vec<Datum>,
+}
+
+impl Layout for BarChartElem {
+    #[tracing::instrument(name = "BarChartElem::layout", skip_all)]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let size = Size::new(
+            self.width(styles).relative_to(regions.base().x),
+            self.height(styles),
+        );
+        check_size(size, self.span())?;
+
+        let data = self.data();
+        let values = data.iter().map(|datum| datum.value.get());
+        let min = values.clone().fold(0.0, f64::min);
+        let max = values.fold(0.0, f64::max);
+        let scale = Scale::new(min, max).at(self.span())?;
+
+        let count = data.len() as f64;
+        let labels = data
+            .iter()
+            .enumerate()
+            .filter_map(|(i, datum)| {
+                Some(((i as f64 + 0.5) / count, datum.label.clone()?))
+            })
+            .collect::<Vec<_>>();
+
+        let (mut frame, origin, plot) = axes(engine, styles, size, &scale, &labels)?;
+        let slot = plot.x / count;
+        let zero = plot.y * scale.fraction(0.0);
+        let fill = self.fill(styles);
+        for (i, datum) in data.iter().enumerate() {
+            let value = plot.y * scale.fraction(datum.value.get());
+            let bar = Size::new(slot * 0.6, (value - zero).abs());
+            let pos = origin
+                + Point::new(slot * i as f64 + slot * 0.2, plot.y - value.max(zero));
+            let shape = Geometry::Rect(bar).filled(fill.clone());
+            frame.push(pos, FrameItem::Shape(shape, self.span()));
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// A line chart.
+///
+/// Each series is an array of values, which are spaced evenly, or an array of
+/// `(x, y)` pairs. To show a series in the legend, give it as a dictionary
+/// with a `label` and the `data`.
+///
+/// # Example
+/// ```example
+/// #chart.line(
+///   height: 80pt,
+///   (label: "Revenue", data: (3, 5, 4, 7, 8)),
+///   (label: "Costs", data: (2, 3, 3, 4, 4)),
+/// )
+/// ```
+#[elem(name = "line", title = "Line Chart", Layout)]
+pub struct LineChartElem {
+    /// The width of the chart, including its axes and legend.
+    #[resolve]
+    #[default(Rel::one())]
+    pub width: Rel<Length>,
+
+    /// The height of the chart, including its axes.
+    #[resolve]
+    #[default(Abs::pt(120.0).into())]
+    pub height: Length,
+
+    /// The colors of the series, which are used in turn.
+    #[default(default_colors())]
+    pub colors: Vec<Paint>,
+
+    /// The series.
+    #[variadic]
+    pub series: Vec<Series>,
+}
+
+impl Layout for LineChartElem {
+    #[tracing::instrument(name = "LineChartElem::layout", skip_all)]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let size = Size::new(
+            self.width(styles).relative_to(regions.base().x),
+            self.height(styles),
+        );
+        check_size(size, self.span())?;
+
+        let colors = self.colors(styles);
+        if colors.is_empty() {
+            bail!(self.span(), "chart must have at least one color");
+        }
+
+        let series = self.series();
+        let color = |i: usize| colors[i % colors.len()].clone();
+        let entries = series
+            .iter()
+            .enumerate()
+            .filter_map(|(i, series)| Some((color(i), series.label.clone()?)))
+            .collect::<Vec<_>>();
+        let legend = legend(engine, styles, &entries)?;
+        let space = legend.as_ref().map_or(Abs::zero(), |legend| legend.width() + PAD);
+
+        let points = series.iter().flat_map(|series| &series.points);
+        let bounds = |get: fn(&Axes<Scalar>) -> f64| {
+            let values = points.clone().map(get);
+            let min = values.clone().fold(f64::INFINITY, f64::min);
+            let max = values.fold(f64::NEG_INFINITY, f64::max);
+            if min <= max {
+                Scale::new(min, max)
+            } else {
+                Scale::new(0.0, 1.0)
+            }
+        };
+        let x = bounds(|point| point.x.get()).at(self.span())?;
+        let y = bounds(|point| point.y.get()).at(self.span())?;
+
+        let labels = x
+            .ticks
+            .iter()
+            .map(|&tick| (x.fraction(tick), TextElem::packed(x.format(tick))))
+            .collect::<Vec<_>>();
+
+        let area = Size::new(size.x - space, size.y);
+        let (plot_frame, origin, plot) = axes(engine, styles, area, &y, &labels)?;
+        let mut frame = Frame::soft(size);
+        frame.push_frame(Point::zero(), plot_frame);
+
+        for (i, series) in series.iter().enumerate() {
+            let mut path = Path::new();
+            for (k, point) in series.points.iter().enumerate() {
+                let pos = origin
+                    + Point::new(
+                        plot.x * x.fraction(point.x.get()),
+                        plot.y * (1.0 - y.fraction(point.y.get())),
+                    );
+                if k == 0 {
+                    path.move_to(pos);
+                } else {
+                    path.line_to(pos);
+                }
+            }
+
+            let stroke = FixedStroke {
+                paint: color(i),
+                thickness: Abs::pt(1.5),
+                ..Default::default()
+            };
+            let shape = Shape {
+                geometry: Geometry::Path(path),
+                stroke: Some(stroke),
+                fill: None,
+            };
+            frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+        }
+
+        if let Some(legend) = legend {
+            let pos = Point::new(
+                size.x - legend.width(),
+                origin.y + (plot.y - legend.height()) / 2.0,
+            );
+            frame.push_frame(pos, legend);
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// A pie chart.
+///
+/// Each slice is given as a pair of a label and a value or as a plain value.
+/// The slices start at the top and go clockwise. Labelled slices are listed
+/// in a legend next to the pie.
+///
+/// # Example
+/// ```example
+/// #chart.pie(
+///   size: 60pt,
+///   ("Rent", 1200),
+///   ("Food", 450),
+///   ("Other", 300),
+/// )
+/// ```
+#[elem(name = "pie", title = "Pie Chart", Layout)]
+pub struct PieChartElem {
+    /// The diameter of the pie.
+    #[resolve]
+    #[default(Abs::pt(100.0).into())]
+    pub size: Length,
+
+    /// The colors of the slices, which are used in turn.
+    #[default(default_colors())]
+    pub colors: Vec<Paint>,
+
+    /// The slices.
+    #[variadic]
+    pub data: Vec<Datum>,
+}
+
+impl Layout for PieChartElem {
+    #[tracing::instrument(name = "PieChartElem::layout", skip_all)]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Fragment> {
+        let diameter = self.size(styles);
+        let colors = self.colors(styles);
+        if colors.is_empty() {
+            bail!(self.span(), "chart must have at least one color");
+        }
+
+        let data = self.data();
+        if data.iter().any(|datum| datum.value.get() < 0.0) {
+            bail!(self.span(), "pie chart values must not be negative");
+        }
+
+        let color = |i: usize| colors[i % colors.len()].clone();
+        let entries = data
+            .iter()
+            .enumerate()
+            .filter_map(|(i, datum)| Some((color(i), datum.label.clone()?)))
+            .collect::<Vec<_>>();
+        let legend = legend(engine, styles, &entries)?;
+
+        let size = match &legend {
+            Some(legend) => Size::new(
+                diameter + PAD * 2.0 + legend.width(),
+                diameter.max(legend.height()),
+            ),
+            None => Size::splat(diameter),
+        };
+
+        let mut frame = Frame::soft(size);
+        let center = Point::new(diameter / 2.0, size.y / 2.0);
+        let total: f64 = data.iter().map(|datum| datum.value.get()).sum();
+        let mut angle = -FRAC_PI_2;
+        for (i, datum) in data.iter().enumerate() {
+            if total <= 0.0 || datum.value.get() == 0.0 {
+                continue;
+            }
+
+            let sweep = TAU * datum.value.get() / total;
+            let path = slice(center, diameter / 2.0, angle, sweep);
+            let stroke = FixedStroke {
+                paint: Color::WHITE.into(),
+                thickness: Abs::pt(0.5),
+                ..Default::default()
+            };
+            let shape = Shape {
+                geometry: Geometry::Path(path),
+                stroke: Some(stroke),
+                fill: Some(color(i)),
+            };
+            frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+            angle += sweep;
+        }
+
+        if let Some(legend) = legend {
+            let pos = Point::new(diameter + PAD * 2.0, (size.y - legend.height()) / 2.0);
+            frame.push_frame(pos, legend);
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// A Gantt chart or timeline.
+///
+/// Each task is given as an array of a label, a start, and an end. A task
+/// without an end is a milestone, which is marked with a diamond, so that a
+/// chart of only milestones is a timeline. The start and end can be numbers or
+/// [dates]($datetime), but all tasks must use the same kind.
+///
+/// # Example
+/// ```example
+/// #chart.gantt(
+///   ("Design", 0, 3),
+///   ("Build", 2, 7),
+///   ("Test", 5, 8),
+///   ("Launch", 8),
+/// )
+/// ```
+#[elem(name = "gantt", title = "Gantt Chart", Layout)]
+pub struct GanttChartElem {
+    /// The width of the chart, including its labels.
+    #[resolve]
+    #[default(Rel::one())]
+    pub width: Rel<Length>,
+
+    /// The height of each task's row.
+    #[resolve]
+    #[default(Abs::pt(16.0).into())]
+    pub row_height: Length,
+
+    /// How to fill the bars and milestones.
+    #[default(Color::BLUE.into())]
+    pub fill: Paint,
+
+    /// The tasks, from top to bottom.
+    #[variadic]
+    pub tasks: Vec<Task>,
+}
+
+impl Layout for GanttChartElem {
+    #[tracing::instrument(name = "GanttChartElem::layout", skip_all)]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let width = self.width(styles).relative_to(regions.base().x);
+        check_size(Size::new(width, Abs::zero()), self.span())?;
+
+        // Map dates to days since the earliest one.
+        let tasks = self.tasks();
+        let mut reference: Option<Datetime> = None;
+        let mut numbers = false;
+        for task in tasks {
+            for moment in std::iter::once(task.start).chain(task.end) {
+                match moment {
+                    Moment::Value(_) => numbers = true,
+                    Moment::Date(date) if reference.map_or(true, |min| date < min) => {
+                        reference = Some(date)
+                    }
+                    Moment::Date(_) => {}
+                }
+            }
+        }
+
+        if numbers && reference.is_some() {
+            bail!(self.span(), "cannot mix numbers and dates in a gantt chart");
+        }
+
+        let position = |moment: Moment| -> SourceResult<f64> {
+            Ok(match moment {
+                Moment::Value(value) => value.get(),
+                Moment::Date(date) => {
+                    (date - reference.unwrap_or(date)).at(self.span())?.days()
+                }
+            })
+        };
+
+        let mut spans = vec![];
+        for task in tasks {
+            let start = position(task.start)?;
+            let end = task.end.map(position).transpose()?;
+            if end.map_or(false, |end| end < start) {
+                bail!(self.span(), "task must not end before it starts");
+            }
+            spans.push((start, end));
+        }
+
+        let min = spans.iter().map(|&(start, _)| start).fold(f64::INFINITY, f64::min);
+        let max = spans
+            .iter()
+            .map(|&(start, end)| end.unwrap_or(start))
+            .fold(f64::NEG_INFINITY, f64::max);
+        let (min, max) = if min <= max { (min, max) } else { (0.0, 1.0) };
+        let scale = Scale::new(min, max).at(self.span())?;
+
+        // Dates are only labelled at whole days.
+        let ticks = scale
+            .ticks
+            .iter()
+            .filter(|tick| reference.is_none() || tick.fract() == 0.0)
+            .map(|&tick| {
+                let text = match reference {
+                    Some(reference) => {
+                        let offset = time::Duration::seconds_f64(tick * 86400.0);
+                        (reference + Duration::from(offset))
+                            .display(Smart::Auto)
+                            .at(self.span())?
+                    }
+                    None => scale.format(tick),
+                };
+                Ok((tick, natural(engine, styles, &TextElem::packed(text))?))
+            })
+            .collect::<SourceResult<Vec<_>>>()?;
+        let labels = tasks
+            .iter()
+            .map(|task| natural(engine, styles, &task.label))
+            .collect::<SourceResult<Vec<_>>>()?;
+
+        // Leave room for the task labels on the left and the tick labels,
+        // which are centered on their ticks, below the plot.
+        let first = ticks.first().map_or(Abs::zero(), |(_, frame)| frame.width() / 2.0);
+        let last = ticks.last().map_or(Abs::zero(), |(_, frame)| frame.width() / 2.0);
+        let left = (labels.iter().map(Frame::width).fold(Abs::zero(), Abs::max) + PAD)
+            .max(first);
+        let bottom = ticks
+            .iter()
+            .map(|(_, frame)| frame.height())
+            .fold(Abs::zero(), Abs::max)
+            + PAD;
+        let row = self.row_height(styles);
+        let plot =
+            Size::new((width - left - last).max(Abs::zero()), row * tasks.len() as f64);
+
+        let mut frame = Frame::soft(Size::new(width, plot.y + bottom));
+        let grid = FixedStroke {
+            paint: Color::SILVER.into(),
+            thickness: Abs::pt(0.5),
+            ..Default::default()
+        };
+        for (tick, label) in ticks {
+            let x = left + plot.x * scale.fraction(tick);
+            let line = Geometry::Line(Point::with_y(plot.y)).stroked(grid.clone());
+            frame.push(Point::with_x(x), FrameItem::Shape(line, Span::detached()));
+            let pos = Point::new(x - label.width() / 2.0, plot.y + PAD);
+            frame.push_frame(pos, label);
+        }
+
+        let fill = self.fill(styles);
+        let size = row * 0.6;
+        for (i, ((start, end), label)) in spans.into_iter().zip(labels).enumerate() {
+            let y = row * i as f64;
+            let pos =
+                Point::new(left - PAD - label.width(), y + (row - label.height()) / 2.0);
+            frame.push_frame(pos, label);
+
+            let x = left + plot.x * scale.fraction(start);
+            let center = y + row / 2.0;
+            match end {
+                Some(end) => {
+                    let length = plot.x * (scale.fraction(end) - scale.fraction(start));
+                    let bar =
+                        Geometry::Rect(Size::new(length, size)).filled(fill.clone());
+                    let pos = Point::new(x, center - size / 2.0);
+                    frame.push(pos, FrameItem::Shape(bar, self.span()));
+                }
+                None => {
+                    let mark = Geometry::Path(diamond(size)).filled(fill.clone());
+                    frame
+                        .push(Point::new(x, center), FrameItem::Shape(mark, self.span()));
+                }
+            }
+        }
+
+        let axis = FixedStroke { thickness: Abs::pt(0.5), ..Default::default() };
+        let corner = Point::new(left, plot.y);
+        for delta in [Point::with_y(-plot.y), Point::with_x(plot.x)] {
+            let line = Geometry::Line(delta).stroked(axis.clone());
+            frame.push(corner, FrameItem::Shape(line, Span::detached()));
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// A small line chart that fits into a line of text.
+///
+/// A sparkline shows the trend of a series of values without axes or labels.
+/// Its size is relative to the surrounding text, so it can be used in
+/// paragraphs and table cells.
+///
+/// # Example
+/// ```example
+/// Visitors this week:
+/// #chart.sparkline((3, 5, 4, 8, 6, 9, 12))
+/// ```
+#[elem(name = "sparkline", title = "Sparkline", Show)]
+pub struct SparklineElem {
+    /// The width of the sparkline.
+    #[default(Em::new(4.0).into())]
+    pub width: Length,
+
+    /// The height of the sparkline.
+    #[default(Em::new(0.8).into())]
+    pub height: Length,
+
+    /// How to [stroke]($stroke) the line.
+    pub stroke: Stroke,
+
+    /// The values, which are spaced evenly.
+    #[required]
+    pub values: Vec<Finite>,
+}
+
+impl Show for SparklineElem {
+    #[tracing::instrument(name = "SparklineElem::show", skip_all)]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let values = self.values();
+        let min = values.iter().map(|v| v.get()).fold(f64::INFINITY, f64::min);
+        let max = values.iter().map(|v| v.get()).fold(f64::NEG_INFINITY, f64::max);
+        let last = values.len().saturating_sub(1).max(1) as f64;
+        let vertices = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let y = if max > min { (max - v.get()) / (max - min) } else { 0.5 };
+                vertex(i as f64 / last, y)
+            })
+            .collect();
+
+        let line = PathElem::new(vertices)
+            .with_stroke(Smart::Custom(Some(self.stroke(styles))))
+            .pack();
+        Ok(inline(self.width(styles), self.height(styles), line))
+    }
+}
+
+/// A bar that compares a value with a target and fits into a line of text.
+///
+/// The bar fills its track in proportion to the value's share of the
+/// maximum. The target, if any, is marked with a vertical line. Like a
+/// [sparkline]($chart.sparkline), it is sized relative to the surrounding
+/// text.
+///
+/// # Example
+/// ```example
+/// Sales: #chart.bullet(72, target: 80) \
+/// Costs: #chart.bullet(35, target: 30)
+/// ```
+#[elem(name = "bullet", title = "Bullet Bar", Show)]
+pub struct BulletElem {
+    /// The width of the bar's track.
+    #[default(Em::new(4.0).into())]
+    pub width: Length,
+
+    /// The height of the bar.
+    #[default(Em::new(0.6).into())]
+    pub height: Length,
+
+    /// The value at which the track ends.
+    #[default(Finite(Scalar::new(100.0)))]
+    pub max: Finite,
+
+    /// The value to compare with.
+    pub target: Option<Finite>,
+
+    /// How to fill the bar.
+    #[default(Color::BLUE.into())]
+    pub fill: Paint,
+
+    /// How to fill the track behind the bar.
+    #[default(Color::SILVER.into())]
+    pub track: Paint,
+
+    /// The value to show.
+    #[required]
+    pub value: Finite,
+}
+
+impl Show for BulletElem {
+    #[tracing::instrument(name = "BulletElem::show", skip_all)]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let max = self.max(styles).get();
+        let share = |value: Finite| {
+            if max > 0.0 {
+                (value.get() / max).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+
+        let rect = |width: f64, fill: Paint| {
+            let vertices = vec![
+                vertex(0.0, 0.0),
+                vertex(width, 0.0),
+                vertex(width, 1.0),
+                vertex(0.0, 1.0),
+            ];
+            PlaceElem::new(
+                PathElem::new(vertices).with_closed(true).with_fill(Some(fill)).pack(),
+            )
+            .pack()
+        };
+
+        let mut body =
+            rect(1.0, self.track(styles)) + rect(share(*self.value()), self.fill(styles));
+        if let Some(target) = self.target(styles) {
+            let x = share(target);
+            let marker = PathElem::new(vec![vertex(x, -0.2), vertex(x, 1.2)]).pack();
+            body += PlaceElem::new(marker).pack();
+        }
+
+        Ok(inline(self.width(styles), self.height(styles), body))
+    }
+}
+
+/// A path vertex at the given fractions of the available space.
+fn vertex(x: f64, y: f64) -> PathVertex {
+    PathVertex::Vertex(Axes::new(Ratio::new(x).into(), Ratio::new(y).into()))
+}
+
+/// Wrap a micro-chart into a box so that it flows with the surrounding text.
+fn inline(width: Length, height: Length, body: Content) -> Content {
+    BoxElem::new()
+        .with_width(Sizing::Rel(width.into()))
+        .with_height(Smart::Custom(height.into()))
+        .with_body(Some(body))
+        .pack()
+}
+
+/// A finite number in a chart.
+///
+/// Casting checks finiteness on the float, since a [`Scalar`] would already
+/// have turned `NaN` into zero.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub struct Finite(Scalar);
+
+impl Finite {
+    /// The number.
+    pub fn get(self) -> f64 {
+        self.0.get()
+    }
+}
+
+cast! {
+    Finite,
+    self => self.get().into_value(),
+    v: f64 => Self(finite(v)?),
+}
+
+/// A value in a chart, optionally with a label.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Datum {
+    /// The label of the value.
+    pub label: Option<Content>,
+    /// The value.
+    pub value: Scalar,
+}
+
+cast! {
+    Datum,
+    self => match self.label {
+        Some(label) => array![label, self.value.get()].into_value(),
+        None => self.value.get().into_value(),
+    },
+    value: f64 => Self { label: None, value: finite(value)? },
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(label), Some(value), None) => Self {
+                label: Some(label.display()),
+                value: finite(value.cast()?)?,
+            },
+            _ => bail!("datum must be a value or a pair of a label and a value"),
+        }
+    },
+}
+
+/// A task in a Gantt chart.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Task {
+    /// The label of the task.
+    pub label: Content,
+    /// When the task starts.
+    pub start: Moment,
+    /// When the task ends, or `None` for a milestone.
+    pub end: Option<Moment>,
+}
+
+cast! {
+    Task,
+    self => {
+        let mut array = array![self.label, self.start];
+        if let Some(end) = self.end {
+            array.push(end.into_value());
+        }
+        array.into_value()
+    },
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next(), iter.next()) {
+            (Some(label), Some(start), end, None) => Self {
+                label: label.display(),
+                start: start.cast()?,
+                end: end.map(Value::cast).transpose()?,
+            },
+            _ => bail!("task must be an array of a label, a start, and an optional end"),
+        }
+    },
+}
+
+/// A point on the time axis of a Gantt chart.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub enum Moment {
+    /// A plain number.
+    Value(Scalar),
+    /// A date.
+    Date(Datetime),
+}
+
+cast! {
+    Moment,
+    self => match self {
+        Self::Value(value) => value.get().into_value(),
+        Self::Date(date) => date.into_value(),
+    },
+    value: f64 => Self::Value(finite(value)?),
+    date: Datetime => Self::Date(date),
+}
+
+/// A series of points in a line chart, optionally with a label.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Series {
+    /// The label of the series.
+    pub label: Option<Content>,
+    /// The points of the series.
+    pub points: Vec<Axes<Scalar>>,
+}
+
+cast! {
+    Series,
+    self => {
+        let data = self
+            .points
+            .into_iter()
+            .map(|point| array![point.x.get(), point.y.get()].into_value())
+            .collect::<Array>();
+        match self.label {
+            Some(label) => dict! { "label" => label, "data" => data }.into_value(),
+            None => data.into_value(),
+        }
+    },
+    array: Array => Self { label: None, points: points(array)? },
+    mut dict: Dict => {
+        let label = dict.take("label").ok().map(Value::display);
+        let data = dict.take("data")?.cast()?;
+        dict.finish(&["label", "data"])?;
+        Self { label, points: points(data)? }
+    },
+}
+
+/// Convert the data of a series into points.
+fn points(data: Array) -> StrResult<Vec<Axes<Scalar>>> {
+    data.into_iter()
+        .enumerate()
+        .map(|(i, value)| match value {
+            Value::Array(pair) => {
+                let mut iter = pair.into_iter();
+                match (iter.next(), iter.next(), iter.next()) {
+                    (Some(x), Some(y), None) => {
+                        Ok(Axes::new(finite(x.cast()?)?, finite(y.cast()?)?))
+                    }
+                    _ => bail!("point must be a pair of an x and a y value"),
+                }
+            }
+            value => Ok(Axes::new(Scalar::new(i as f64), finite(value.cast()?)?)),
+        })
+        .collect()
+}
+
+/// Ensure that a chart value is finite.
+fn finite(value: f64) -> StrResult<Scalar> {
+    if !value.is_finite() {
+        bail!("chart values must be finite");
+    }
+    Ok(Scalar::new(value))
+}
+
+/// The colors that charts use by default.
+fn default_colors() -> Vec<Paint> {
+    [Color::BLUE, Color::ORANGE, Color::GREEN, Color::RED, Color::PURPLE, Color::EASTERN]
+        .into_iter()
+        .map(Paint::from)
+        .collect()
+}
+
+/// Ensure that a chart has a finite size.
+fn check_size(size: Size, span: Span) -> SourceResult<()> {
+    if !size.is_finite() {
+        bail!(span, "cannot create chart with infinite size");
+    }
+    Ok(())
+}
+
+/// A linear mapping from values to an axis with evenly spaced ticks.
+struct Scale {
+    /// The smallest value on the axis.
+    min: f64,
+    /// The largest value on the axis.
+    max: f64,
+    /// The values at which the axis has ticks.
+    ticks: Vec<f64>,
+    /// The number of fractional digits of the tick labels.
+    decimals: u8,
+}
+
+impl Scale {
+    /// Create a scale with round ticks that covers the given range.
+    ///
+    /// Fails if the range is too large to be divided into ticks.
+    fn new(min: f64, max: f64) -> StrResult<Self> {
+        // Widen an empty range relative to the magnitude of its values, so
+        // that it doesn't stay empty for very large ones.
+        let (min, max) = if min < max {
+            (min, max)
+        } else {
+            let delta = (min.abs() / 10.0).max(1.0);
+            (min - delta, max + delta)
+        };
+
+        // Aim for about five ticks with a step of 1, 2, or 5 times a power of
+        // ten.
+        let raw = (max - min) / 5.0;
+        let magnitude = 10_f64.powf(raw.log10().floor());
+        let step = magnitude
+            * match raw / magnitude {
+                r if r <= 1.0 => 1.0,
+                r if r <= 2.0 => 2.0,
+                r if r <= 5.0 => 5.0,
+                _ => 10.0,
+            };
+
+        let start = (min / step).floor();
+        let end = (max / step).ceil();
+        if !step.is_normal() || !(start * step).is_finite() || !(end * step).is_finite() {
+            bail!("chart values span too large a range");
+        }
+
+        let (start, end) = (start as i64, end as i64);
+        Ok(Self {
+            min: start as f64 * step,
+            max: end as f64 * step,
+            ticks: (start..=end).map(|i| i as f64 * step).collect(),
+            decimals: (-step.log10().floor()).max(0.0) as u8,
+        })
+    }
+
+    /// Where a value lies on the axis, from zero at the start to one at the
+    /// end.
+    fn fraction(&self, value: f64) -> f64 {
+        (value - self.min) / (self.max - self.min)
+    }
+
+    /// Format a tick value.
+    fn format(&self, value: f64) -> EcoString {
+        repr::format_float(value, Some(self.decimals), "")
+    }
+}
+
+/// Lay out content at its natural size.
+fn natural(
+    engine: &mut Engine,
+    styles: StyleChain,
+    body: &Content,
+) -> SourceResult<Frame> {
+    let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+    Ok(body.layout(engine, styles, pod)?.into_frame())
+}
+
+/// Lay out the axes of a chart with the y-axis showing the scale and labels
+/// along the x-axis at the given fractions of its length.
+///
+/// Returns the frame along with the position and size of the plot area
+/// between the axes.
+fn axes(
+    engine: &mut Engine,
+    styles: StyleChain,
+    size: Size,
+    scale: &Scale,
+    labels: &[(f64, Content)],
+) -> SourceResult<(Frame, Point, Size)> {
+    let ticks = scale
+        .ticks
+        .iter()
+        .map(|&tick| natural(engine, styles, &TextElem::packed(scale.format(tick))))
+        .collect::<SourceResult<Vec<_>>>()?;
+    let labels = labels
+        .iter()
+        .map(|(at, label)| Ok((*at, natural(engine, styles, label)?)))
+        .collect::<SourceResult<Vec<_>>>()?;
+
+    // Leave room for the tick labels, which are centered on their ticks, and
+    // the labels below the x-axis.
+    let top = ticks.last().map_or(Abs::zero(), |frame| frame.height() / 2.0);
+    let left = ticks.iter().map(Frame::width).fold(Abs::zero(), Abs::max) + PAD;
+    let bottom = labels
+        .iter()
+        .map(|(_, frame)| frame.height())
+        .fold(Abs::zero(), Abs::max)
+        + PAD;
+    let right = labels
+        .iter()
+        .map(|(at, frame)| frame.width() / 2.0 - size.x * (1.0 - at))
+        .fold(Abs::zero(), Abs::max);
+
+    let origin = Point::new(left, top);
+    let plot = Size::new(
+        (size.x - left - right).max(Abs::zero()),
+        (size.y - top - bottom).max(Abs::zero()),
+    );
+
+    let mut frame = Frame::soft(size);
+    let grid = FixedStroke {
+        paint: Color::SILVER.into(),
+        thickness: Abs::pt(0.5),
+        ..Default::default()
+    };
+    for (tick, label) in scale.ticks.iter().zip(ticks) {
+        let y = origin.y + plot.y * (1.0 - scale.fraction(*tick));
+        let line = Geometry::Line(Point::with_x(plot.x)).stroked(grid.clone());
+        frame.push(Point::new(origin.x, y), FrameItem::Shape(line, Span::detached()));
+        let pos = Point::new(left - PAD - label.width(), y - label.height() / 2.0);
+        frame.push_frame(pos, label);
+    }
+
+    for (at, label) in labels {
+        let x = origin.x + plot.x * at - label.width() / 2.0;
+        frame.push_frame(Point::new(x, origin.y + plot.y + PAD), label);
+    }
+
+    let axis = FixedStroke { thickness: Abs::pt(0.5), ..Default::default() };
+    let corner = origin + Point::with_y(plot.y);
+    for delta in [Point::with_y(-plot.y), Point::with_x(plot.x)] {
+        let line = Geometry::Line(delta).stroked(axis.clone());
+        frame.push(corner, FrameItem::Shape(line, Span::detached()));
+    }
+
+    Ok((frame, origin, plot))
+}
+
+/// Lay out a legend with a color swatch next to each label, or nothing if
+/// there are no labels.
+fn legend(
+    engine: &mut Engine,
+    styles: StyleChain,
+    entries: &[(Paint, Content)],
+) -> SourceResult<Option<Frame>> {
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    let labels = entries
+        .iter()
+        .map(|(_, label)| natural(engine, styles, label))
+        .collect::<SourceResult<Vec<_>>>()?;
+
+    let rows = labels
+        .iter()
+        .map(|label| label.height().max(SWATCH))
+        .collect::<Vec<_>>();
+    let width = labels.iter().map(Frame::width).fold(Abs::zero(), Abs::max);
+    let height = rows.iter().copied().sum::<Abs>() + PAD * (rows.len() - 1) as f64;
+
+    let mut frame = Frame::soft(Size::new(SWATCH + PAD + width, height));
+    let mut y = Abs::zero();
+    for (((paint, _), label), row) in entries.iter().zip(labels).zip(rows) {
+        let swatch = Geometry::Rect(Size::splat(SWATCH)).filled(paint.clone());
+        let pos = Point::new(Abs::zero(), y + (row - SWATCH) / 2.0);
+        frame.push(pos, FrameItem::Shape(swatch, Span::detached()));
+        let pos = Point::new(SWATCH + PAD, y + (row - label.height()) / 2.0);
+        frame.push_frame(pos, label);
+        y += row + PAD;
+    }
+
+    Ok(Some(frame))
+}
+
+/// Create a diamond with the given width and height around the origin.
+fn diamond(size: Abs) -> Path {
+    let half = size / 2.0;
+    let mut path = Path::new();
+    path.move_to(Point::with_y(-half));
+    path.line_to(Point::with_x(half));
+    path.line_to(Point::with_y(half));
+    path.line_to(Point::with_x(-half));
+    path.close_path();
+    path
+}
+
+/// Create a pie slice that starts at the given angle and sweeps clockwise.
+fn slice(center: Point, radius: Abs, start: f64, sweep: f64) -> Path {
+    let at = |angle: f64| center + Point::new(radius * angle.cos(), radius * angle.sin());
+    let tangent = |angle: f64| Point::new(-radius * angle.sin(), radius * angle.cos());
+
+    let mut path = Path::new();
+    path.move_to(center);
+    path.line_to(at(start));
+
+    // Approximate the arc with one cubic curve per quarter turn at most.
+    let pieces = (sweep / FRAC_PI_2).ceil().max(1.0);
+    let step = sweep / pieces;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    for i in 0..pieces as usize {
+        let a = start + step * i as f64;
+        let b = a + step;
+        path.cubic_to(at(a) + tangent(a) * k, at(b) - tangent(b) * k, at(b));
+    }
+
+    path.close_path();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_covers_range_with_round_ticks() {
+        let scale = Scale::new(-3.0, 18.0).unwrap();
+        assert_eq!(scale.ticks, [-5.0, 0.0, 5.0, 10.0, 15.0, 20.0]);
+        assert_eq!((scale.min, scale.max), (-5.0, 20.0));
+        assert_eq!(scale.decimals, 0);
+
+        let scale = Scale::new(0.0, 0.3).unwrap();
+        assert_eq!(scale.ticks.len(), 4);
+        assert_eq!(scale.decimals, 1);
+    }
+
+    #[test]
+    fn test_scale_widens_empty_ranges() {
+        #[track_caller]
+        fn test(value: f64) {
+            let scale = Scale::new(value, value).unwrap();
+            assert!(scale.min < value && value < scale.max);
+            assert!(scale.ticks.len() > 1);
+            assert!(scale.ticks.iter().all(|tick| tick.is_finite()));
+            assert!((0.0..=1.0).contains(&scale.fraction(value)));
+        }
+
+        test(0.0);
+        test(-4.0);
+        test(1e20);
+        test(-1e300);
+    }
+
+    #[test]
+    fn test_scale_rejects_ranges_that_are_too_large() {
+        #[track_caller]
+        fn test(min: f64, max: f64) {
+            assert_eq!(
+                Scale::new(min, max).map(|scale| scale.ticks),
+                Err("chart values span too large a range".into()),
+            );
+        }
+
+        test(-1e308, 1e308);
+        test(0.0, f64::MAX);
+        test(f64::MAX, f64::MAX);
+    }
+}
@@ -0,0 +1,349 @@
+use crate::diag::{At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, Behave, Behaviour, Cast, Label, NativeElement, StyleChain,
+};
+use crate::introspection::Locatable;
+use crate::layout::{
+    Abs, Bounds, Fragment, Frame, FrameItem, Layout, Point, Regions, Size, Transform,
+};
+use crate::util::Numeric;
+use crate::visualize::{FixedStroke, Geometry, Path, Shape, Stroke};
+
+/// A connector between two labelled elements.
+///
+/// The connector looks up where both elements ended up in the document and
+/// draws a line from the border of the first one to the border of the second
+/// one. Orthogonal and curved connectors are routed around other labelled
+/// elements on the page. Like [placed]($place) content, it doesn't take up any
+/// space, so it can go anywhere on the page where both elements are, even into
+/// rotated or scaled content. Connectors to elements on other pages are not
+/// drawn.
+///
+/// # Example
+/// ```example
+/// #set rect(inset: 6pt)
+/// #grid(
+///   columns: 3,
+///   column-gutter: 24pt,
+///   row-gutter: 16pt,
+///   [#rect[Start] <start>],
+///   [],
+///   [#rect[Check] <check>],
+///   [],
+///   [#rect[End] <end>],
+/// )
+///
+/// #connect(<start>, <check>)
+/// #connect(<check>, <end>, style: "curved")
+/// ```
+#[elem(Locatable, Layout, Behave)]
+pub struct ConnectElem {
+    /// The label of the element where the connector starts.
+    #[required]
+    #[parse(
+        let from = args.named_or_find("from")?;
+        let to = args.named_or_find("to")?;
+        match from {
+            Some(from) => from,
+            None => args.expect("from")?,
+        }
+    )]
+    pub from: Label,
+
+    /// The label of the element where the connector ends.
+    #[required]
+    #[parse(match to {
+        Some(to) => to,
+        None => args.expect("to")?,
+    })]
+    pub to: Label,
+
+    /// How to route the connector.
+    ///
+    /// ```example
+    /// #set rect(inset: 6pt)
+    /// #stack(
+    ///   dir: ltr,
+    ///   spacing: 24pt,
+    ///   [#rect[A] <a>],
+    ///   move(dy: 24pt)[#rect[B] <b>],
+    /// )
+    ///
+    /// #connect(<a>, <b>, style: "orthogonal")
+    /// #connect(<a>, <b>, style: "curved", stroke: blue)
+    /// #connect(<a>, <b>, style: "straight", stroke: red)
+    /// ```
+    pub style: ConnectStyle,
+
+    /// How to [stroke]($stroke) the connector.
+    #[resolve]
+    #[fold]
+    pub stroke: Stroke,
+
+    /// Whether to draw an arrowhead where the connector ends.
+    #[default(true)]
+    pub arrow: bool,
+}
+
+impl Layout for ConnectElem {
+    #[tracing::instrument(name = "ConnectElem::layout", skip_all)]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        _: Regions,
+    ) -> SourceResult<Fragment> {
+        let mut frame = Frame::soft(Size::zero());
+
+        // The anchors are only known after the first layout iteration.
+        let anchors = engine.delayed(|engine| {
+            let bounds = |label: Label| -> SourceResult<Bounds> {
+                let elem = engine.introspector.query_label(label).at(self.span())?;
+                Ok(engine.introspector.bounds(elem.location().unwrap()))
+            };
+            Ok(Some((bounds(*self.from())?, bounds(*self.to())?)))
+        });
+
+        let location = self.location().unwrap();
+        let here = engine.introspector.position(location);
+        let Some((from, to)) = anchors else { return Ok(Fragment::frame(frame)) };
+        if from.page != here.page || to.page != here.page {
+            return Ok(Fragment::frame(frame));
+        }
+
+        // Other labelled elements on the page are in the way, unless they
+        // enclose one of the connected elements.
+        let encloses = |outer: &Bounds, inner: &Bounds| {
+            outer.point.x <= inner.point.x
+                && outer.point.y <= inner.point.y
+                && inner.point.x + inner.size.x <= outer.point.x + outer.size.x
+                && inner.point.y + inner.size.y <= outer.point.y + outer.size.y
+        };
+        let obstacles: Vec<Bounds> = engine
+            .introspector
+            .labelled_bounds()
+            .into_iter()
+            .map(|(_, bounds)| bounds)
+            .filter(|bounds| {
+                bounds.page == here.page
+                    && !bounds.size.x.is_zero()
+                    && !bounds.size.y.is_zero()
+                    && !encloses(bounds, &from)
+                    && !encloses(bounds, &to)
+            })
+            .collect();
+
+        // The route is found in page coordinates and then drawn in the
+        // coordinates of the connector, which may be transformed.
+        let ts = engine
+            .introspector
+            .transform(location)
+            .invert()
+            .unwrap_or(Transform::identity());
+
+        let stroke = self.stroke(styles).unwrap_or_default();
+        let route = Route::new(self.style(styles), from, to, &obstacles);
+        let path = route.path(ts, self.arrow(styles), &stroke);
+        let shape = Shape {
+            geometry: Geometry::Path(path),
+            stroke: Some(stroke),
+            fill: None,
+        };
+
+        frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+        frame.meta(styles, false);
+        Ok(Fragment::frame(frame))
+    }
+}
+
+impl Behave for ConnectElem {
+    fn behaviour(&self) -> Behaviour {
+        Behaviour::Ignorant
+    }
+}
+
+/// How a connector is routed between two elements.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ConnectStyle {
+    /// A direct line between the borders of the elements. It is not routed
+    /// around other elements.
+    Straight,
+    /// Horizontal and vertical segments that leave and enter the elements
+    /// at the middle of their sides and go around other labelled elements.
+    #[default]
+    Orthogonal,
+    /// A smooth curve that follows the orthogonal route.
+    Curved,
+}
+
+/// The distance that routes keep from the elements they go around.
+const GAP: Abs = Abs::raw(4.0);
+
+/// The points through which a connector passes.
+struct Route {
+    /// Whether the route is curved.
+    curved: bool,
+    /// Where the connector starts.
+    start: Point,
+    /// The corners between start and end. A curved route uses them as
+    /// control points.
+    via: Vec<Point>,
+    /// Where the connector ends.
+    end: Point,
+}
+
+impl Route {
+    /// Route a connector between two elements around the `obstacles`.
+    fn new(style: ConnectStyle, from: Bounds, to: Bounds, obstacles: &[Bounds]) -> Self {
+        let (a, b) = (from.center(), to.center());
+        if style == ConnectStyle::Straight {
+            return Self {
+                curved: false,
+                start: from.boundary(b),
+                via: vec![],
+                end: to.boundary(a),
+            };
+        }
+
+        // Leave and enter through the sides that face each other along the
+        // axis on which the elements are further apart.
+        let horizontal = (b.x - a.x).abs() >= (b.y - a.y).abs();
+        let side = |bounds: Bounds, toward: Point| {
+            let center = bounds.center();
+            let half = bounds.size.to_point() / 2.0;
+            if horizontal {
+                let sign = if toward.x >= center.x { 1.0 } else { -1.0 };
+                Point::new(center.x + half.x * sign, center.y)
+            } else {
+                let sign = if toward.y >= center.y { 1.0 } else { -1.0 };
+                Point::new(center.x, center.y + half.y * sign)
+            }
+        };
+
+        let start = side(from, b);
+        let end = side(to, a);
+
+        // Find the route as if the connector ran horizontally. Swapping the
+        // axes maps vertical connectors there and back.
+        let flip = |p: Point| if horizontal { p } else { Point::new(p.y, p.x) };
+        let gap = Point::new(GAP, GAP);
+        let boxes: Vec<(Point, Point)> = obstacles
+            .iter()
+            .map(|bounds| {
+                let p = flip(bounds.point);
+                let q = flip(bounds.point + bounds.size.to_point());
+                (p.min(q) - gap, p.max(q) + gap)
+            })
+            .collect();
+        let via = route(flip(start), flip(end), &boxes).into_iter().map(flip).collect();
+
+        Self {
+            curved: style == ConnectStyle::Curved,
+            start,
+            via,
+            end,
+        }
+    }
+
+    /// Build the connector's path, transforming the route's points with `ts`.
+    fn path(&self, ts: Transform, arrow: bool, stroke: &FixedStroke) -> Path {
+        let start = self.start.transform(ts);
+        let via: Vec<Point> = self.via.iter().map(|point| point.transform(ts)).collect();
+        let end = self.end.transform(ts);
+
+        let mut path = Path::new();
+        path.move_to(start);
+        match via.as_slice() {
+            &[c1, c2] if self.curved => path.cubic_to(c1, c2, end),
+            &[c1, c2, c3, c4] if self.curved => {
+                // Two curves that meet smoothly in the middle of the lane.
+                let mid = (c2 + c3) / 2.0;
+                path.cubic_to(c1, c2, mid);
+                path.cubic_to(c3, c4, end);
+            }
+            _ => {
+                for &point in &via {
+                    path.line_to(point);
+                }
+                path.line_to(end);
+            }
+        }
+
+        // The arrowhead points along the last segment or, for curves, along
+        // the tangent at the end.
+        let before = via.last().copied().unwrap_or(start);
+        let direction = end - before;
+        let length = direction.hypot();
+        if arrow && !length.is_zero() {
+            let size = Abs::pt(4.0) + stroke.thickness * 2.0;
+            let back = direction * (size / length);
+            let normal = Point::new(-back.y, back.x) / 2.0;
+            path.move_to(end - back + normal);
+            path.line_to(end);
+            path.line_to(end - back - normal);
+        }
+
+        path
+    }
+}
+
+/// Find the corners of an orthogonal route from `start` to `end` that leaves
+/// and enters horizontally and doesn't cross any of the boxes.
+///
+/// A route with a single vertical segment between the elements is preferred,
+/// as close to the middle as possible. If the boxes block all of these, the
+/// route detours through a horizontal lane along one of the boxes. If no
+/// route is free, the one through the middle is taken.
+fn route(start: Point, end: Point, boxes: &[(Point, Point)]) -> Vec<Point> {
+    let free = |via: &[Point]| {
+        let points: Vec<Point> = std::iter::once(start)
+            .chain(via.iter().copied())
+            .chain([end])
+            .collect();
+        points
+            .windows(2)
+            .all(|segment| boxes.iter().all(|b| !crosses(segment[0], segment[1], b)))
+    };
+
+    let mid = (start + end) / 2.0;
+    let (lo, hi) = (start.x.min(end.x), start.x.max(end.x));
+    let mut xs = vec![mid.x];
+    xs.extend(
+        boxes
+            .iter()
+            .flat_map(|&(min, max)| [min.x, max.x])
+            .filter(|x| (lo..=hi).contains(x)),
+    );
+    xs.sort_by_key(|&x| (x - mid.x).abs());
+    for x in xs {
+        let via = [Point::new(x, start.y), Point::new(x, end.y)];
+        if free(&via) {
+            return via.to_vec();
+        }
+    }
+
+    let sign = if end.x >= start.x { 1.0 } else { -1.0 };
+    let (x1, x2) = (start.x + GAP * sign, end.x - GAP * sign);
+    let mut ys: Vec<Abs> = boxes.iter().flat_map(|&(min, max)| [min.y, max.y]).collect();
+    ys.sort_by_key(|&y| (y - mid.y).abs());
+    for y in ys {
+        let via = [
+            Point::new(x1, start.y),
+            Point::new(x1, y),
+            Point::new(x2, y),
+            Point::new(x2, end.y),
+        ];
+        if free(&via) {
+            return via.to_vec();
+        }
+    }
+
+    vec![Point::new(mid.x, start.y), Point::new(mid.x, end.y)]
+}
+
+/// Whether an axis-aligned segment crosses the inside of a box.
+fn crosses(a: Point, b: Point, (min, max): &(Point, Point)) -> bool {
+    let (lo, hi) = (a.min(b), a.max(b));
+    hi.x > min.x && lo.x < max.x && hi.y > min.y && lo.y < max.y
+}
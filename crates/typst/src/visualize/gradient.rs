@@ -44,10 +44,10 @@ use crate::visualize::{Color, ColorSpace, WeightedColor};
 /// )
 /// ```
 ///
-/// Gradients are also supported on text, but only when setting the
-/// [relativeness]($gradient.relative) to either `{auto}` (the default value) or
-/// `{"parent"}`. To create word-by-word or glyph-by-glyph gradients, you can
-/// wrap the words or characters of your text in [boxes]($box) manually or
+/// Gradients are also supported on text. By default, they span the closest
+/// ancestor container. When the [relativeness]($gradient.relative) is set to
+/// `{"self"}`, each glyph gets its own gradient instead. To create word-by-word
+/// gradients, you can wrap the words of your text in [boxes]($box) manually or
 /// through a [show rule]($styling/#show-rules).
 ///
 /// ```example
@@ -29,7 +29,7 @@ use crate::syntax::Spanned;
 use crate::text::{families, Lang, LocalName, Region};
 use crate::util::{option_eq, Numeric};
 use crate::visualize::Path;
-use crate::World;
+use crate::{Load, World};
 
 /// A raster or vector graphic.
 ///
@@ -59,7 +59,7 @@ pub struct ImageElem {
         let Spanned { v: path, span } =
             args.expect::<Spanned<EcoString>>("path to image file")?;
         let id = span.resolve_path(&path).at(span)?;
-        let data = engine.world.file(id).at(span)?;
+        let data = engine.world.load_file(id).at(span)?;
         path
     )]
     #[borrowed]
@@ -327,7 +327,7 @@ impl Image {
     ) -> StrResult<Self> {
         let kind = match format {
             ImageFormat::Raster(format) => {
-                ImageKind::Raster(RasterImage::new(data, format)?)
+                ImageKind::Raster(RasterImage::new(data, format, None)?)
             }
             ImageFormat::Vector(VectorFormat::Svg) => {
                 ImageKind::Svg(SvgImage::new(data)?)
@@ -348,7 +348,7 @@ impl Image {
     ) -> StrResult<Self> {
         let kind = match format {
             ImageFormat::Raster(format) => {
-                ImageKind::Raster(RasterImage::new(data, format)?)
+                ImageKind::Raster(RasterImage::new(data, format, world.store())?)
             }
             ImageFormat::Vector(VectorFormat::Svg) => {
                 ImageKind::Svg(SvgImage::with_fonts(data, world, families)?)
@@ -11,6 +11,8 @@ use image::{guess_format, ImageDecoder, ImageResult};
 
 use crate::diag::{bail, StrResult};
 use crate::foundations::{Bytes, Cast};
+use crate::util::hash128;
+use crate::util::persist::Store;
 
 /// A decoded raster image.
 #[derive(Clone, Hash)]
@@ -26,8 +28,15 @@ struct Repr {
 
 impl RasterImage {
     /// Decode a raster image.
+    ///
+    /// If a store is given, the decoded pixels are loaded from it or saved
+    /// into it.
     #[comemo::memoize]
-    pub fn new(data: Bytes, format: RasterFormat) -> StrResult<Self> {
+    pub fn new(
+        data: Bytes,
+        format: RasterFormat,
+        store: Option<&dyn Store>,
+    ) -> StrResult<Self> {
         fn decode_with<'a, T: ImageDecoder<'a>>(
             decoder: ImageResult<T>,
         ) -> ImageResult<(image::DynamicImage, Option<Vec<u8>>)> {
@@ -38,6 +47,15 @@ impl RasterImage {
             Ok((dynamic, icc))
         }
 
+        // Reuse the pixels decoded by a previous process if possible.
+        let persist = store.map(|store| (store, hash128(&(&data, format))));
+        if let Some((dynamic, icc)) = persist
+            .and_then(|(store, key)| store.load("image", key))
+            .and_then(|persisted| decode_persisted(&persisted))
+        {
+            return Ok(Self(Arc::new(Repr { data, format, dynamic, icc })));
+        }
+
         let cursor = io::Cursor::new(&data);
         let (dynamic, icc) = match format {
             RasterFormat::Jpg => decode_with(JpegDecoder::new(cursor)),
@@ -46,6 +64,12 @@ impl RasterImage {
         }
         .map_err(format_image_error)?;
 
+        if let Some((store, key)) = persist {
+            if let Some(persisted) = encode_persisted(&dynamic, icc.as_deref()) {
+                store.save("image", key, &persisted);
+            }
+        }
+
         Ok(Self(Arc::new(Repr { data, format, dynamic, icc })))
     }
 
@@ -88,6 +112,64 @@ impl Hash for Repr {
     }
 }
 
+/// Encode decoded pixels for persisting them.
+///
+/// Only images with eight bits per channel are persisted, other ones are
+/// decoded anew in each process.
+fn encode_persisted(
+    dynamic: &image::DynamicImage,
+    icc: Option<&[u8]>,
+) -> Option<Vec<u8>> {
+    let kind = match dynamic {
+        image::DynamicImage::ImageLuma8(_) => 0,
+        image::DynamicImage::ImageLumaA8(_) => 1,
+        image::DynamicImage::ImageRgb8(_) => 2,
+        image::DynamicImage::ImageRgba8(_) => 3,
+        _ => return None,
+    };
+
+    let icc = icc.unwrap_or_default();
+    let pixels = dynamic.as_bytes();
+    let mut data = Vec::with_capacity(13 + icc.len() + pixels.len());
+    data.push(kind);
+    data.extend(dynamic.width().to_le_bytes());
+    data.extend(dynamic.height().to_le_bytes());
+    data.extend((icc.len() as u32).to_le_bytes());
+    data.extend(icc);
+    data.extend(pixels);
+    Some(data)
+}
+
+/// Decode pixels persisted with [`encode_persisted`], returning `None` if the
+/// data is malformed.
+fn decode_persisted(data: &[u8]) -> Option<(image::DynamicImage, Option<Vec<u8>>)> {
+    let word = |i: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(data.get(1 + 4 * i..5 + 4 * i)?.try_into().ok()?))
+    };
+
+    let kind = *data.first()?;
+    let (width, height, icc_len) = (word(0)?, word(1)?, word(2)? as usize);
+    let icc = data.get(13..13 + icc_len)?;
+    let pixels = data.get(13 + icc_len..)?.to_vec();
+    let dynamic = match kind {
+        0 => image::DynamicImage::ImageLuma8(image::ImageBuffer::from_raw(
+            width, height, pixels,
+        )?),
+        1 => image::DynamicImage::ImageLumaA8(image::ImageBuffer::from_raw(
+            width, height, pixels,
+        )?),
+        2 => image::DynamicImage::ImageRgb8(image::ImageBuffer::from_raw(
+            width, height, pixels,
+        )?),
+        3 => image::DynamicImage::ImageRgba8(image::ImageBuffer::from_raw(
+            width, height, pixels,
+        )?),
+        _ => return None,
+    };
+
+    Some((dynamic, (!icc.is_empty()).then(|| icc.to_vec())))
+}
+
 /// A raster graphics format.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum RasterFormat {
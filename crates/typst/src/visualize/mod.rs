@@ -1,6 +1,10 @@
 //! Drawing and visualization.
 
+pub mod chart;
+
+mod blur;
 mod color;
+mod connect;
 mod gradient;
 mod image;
 mod line;
@@ -11,7 +15,9 @@ mod polygon;
 mod shape;
 mod stroke;
 
+pub use self::blur::*;
 pub use self::color::*;
+pub use self::connect::*;
 pub use self::gradient::*;
 pub use self::image::*;
 pub use self::line::*;
@@ -47,4 +53,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<CircleElem>();
     global.define_elem::<PolygonElem>();
     global.define_elem::<PathElem>();
+    global.define_elem::<ConnectElem>();
+    global.define_elem::<BlurElem>();
+    global.define_module(chart::module());
 }
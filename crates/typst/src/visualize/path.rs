@@ -1,13 +1,21 @@
-use kurbo::{CubicBez, ParamCurveExtrema};
+use std::f64::consts::PI;
 
-use crate::diag::{bail, SourceResult};
+use kurbo::{
+    BezPath, CubicBez, ParamCurve, ParamCurveArclen, ParamCurveDeriv, ParamCurveExtrema,
+    PathSeg,
+};
+
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    array, cast, elem, Array, NativeElement, Reflect, Resolve, Smart, StyleChain,
+    array, cast, elem, func, scope, Array, Content, NativeElement, Reflect, Resolve,
+    Smart, StyleChain,
 };
 use crate::layout::{
-    Abs, Axes, Fragment, Frame, FrameItem, Layout, Length, Point, Regions, Rel, Size,
+    Abs, Angle, Axes, Fragment, Frame, FrameItem, Layout, Length, Point, Ratio, Regions,
+    Rel, Size,
 };
+use crate::util::Numeric;
 use crate::visualize::{FixedStroke, Geometry, Paint, Shape, Stroke};
 
 use PathVertex::{AllControlPoints, MirroredControlPoint, Vertex};
@@ -25,7 +33,25 @@ use PathVertex::{AllControlPoints, MirroredControlPoint, Vertex};
 ///   ((50%, 0pt), (40pt, 0pt)),
 /// )
 /// ```
-#[elem(Layout)]
+///
+/// # Measuring
+/// The functions in the path's scope measure a path, for example to place
+/// labels or markers along it.
+///
+/// ```example
+/// #let curve = path(
+///   stroke: blue,
+///   (0pt, 40pt),
+///   ((80pt, 0pt), (-30pt, 0pt)),
+/// )
+/// #curve
+/// #place(
+///   dx: path.point(curve, 50%).at(0),
+///   dy: path.point(curve, 50%).at(1) - 40pt,
+///   rotate(path.tangent(curve, 50%), origin: top + left)[Halfway],
+/// )
+/// ```
+#[elem(scope, Layout)]
 pub struct PathElem {
     /// How to fill the path.
     ///
@@ -51,6 +77,24 @@ pub struct PathElem {
     #[default(false)]
     pub closed: bool,
 
+    /// How much to round the corners between straight segments of the path.
+    ///
+    /// Corners next to curved segments are kept as is. Radii that are too
+    /// large for the adjacent segments are reduced automatically.
+    ///
+    /// ```example
+    /// #path(
+    ///   stroke: blue,
+    ///   radius: 8pt,
+    ///   (0pt, 30pt),
+    ///   (30pt, 0pt),
+    ///   (60pt, 30pt),
+    ///   (90pt, 0pt),
+    /// )
+    /// ```
+    #[resolve]
+    pub radius: Length,
+
     /// The vertices of the path.
     ///
     /// Each vertex can be defined in 3 ways:
@@ -84,24 +128,127 @@ impl Layout for PathElem {
                 .to_point()
         };
 
+        let Some((path, size)) = self.geometry(styles, resolve) else {
+            return Ok(Fragment::frame(Frame::soft(Size::zero())));
+        };
+
+        // Prepare fill and stroke.
+        let fill = self.fill(styles);
+        let stroke = match self.stroke(styles) {
+            Smart::Auto if fill.is_none() => Some(FixedStroke::default()),
+            Smart::Auto => None,
+            Smart::Custom(stroke) => stroke.map(Stroke::unwrap_or_default),
+        };
+
+        let mut frame = Frame::soft(size);
+        let shape = Shape { geometry: Geometry::Path(path), stroke, fill };
+        frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+#[scope]
+impl PathElem {
+    /// Measures the length of a path.
+    ///
+    /// ```example
+    /// #path.length(path((0pt, 0pt), (30pt, 40pt)))
+    /// ```
+    #[func]
+    pub fn length(
+        /// The path to measure. Its coordinates must be absolute lengths.
+        path: Content,
+    ) -> StrResult<Length> {
+        let length = segments(&path)?.iter().map(|seg| seg.arclen(ACCURACY)).sum();
+        Ok(Abs::raw(length).into())
+    }
+
+    /// Finds the point at a position along a path.
+    ///
+    /// Returns the point's coordinates as an array of two lengths.
+    ///
+    /// ```example
+    /// #let p = path((0pt, 0pt), (40pt, 0pt), (40pt, 40pt))
+    /// #path.point(p, 25%) \
+    /// #path.point(p, 100%)
+    /// ```
+    #[func]
+    pub fn point(
+        /// The path to query. Its coordinates must be absolute lengths.
+        path: Content,
+        /// The position along the path, as a fraction of its length.
+        at: Ratio,
+    ) -> StrResult<Axes<Length>> {
+        let (seg, t) = locate(&path, at)?;
+        let point = seg.eval(t);
+        Ok(Axes::new(Abs::raw(point.x).into(), Abs::raw(point.y).into()))
+    }
+
+    /// Finds the direction of a path at a position along it.
+    ///
+    /// Returns the angle between the horizontal axis and the path's tangent,
+    /// which can be passed to [`rotate`]($rotate) to align content with the
+    /// path.
+    ///
+    /// ```example
+    /// #let p = path((0pt, 0pt), (40pt, 0pt), (40pt, 40pt))
+    /// #path.tangent(p, 25%) \
+    /// #path.tangent(p, 75%)
+    /// ```
+    #[func]
+    pub fn tangent(
+        /// The path to query. Its coordinates must be absolute lengths.
+        path: Content,
+        /// The position along the path, as a fraction of its length.
+        at: Ratio,
+    ) -> StrResult<Angle> {
+        let (seg, t) = locate(&path, at)?;
+        let mut dir = match seg {
+            PathSeg::Line(line) => line.deriv().eval(t),
+            PathSeg::Quad(quad) => quad.deriv().eval(t),
+            PathSeg::Cubic(cubic) => cubic.deriv().eval(t),
+        }
+        .to_vec2();
+
+        // The derivative vanishes where a control point coincides with its
+        // vertex, so we look at the neighbourhood instead.
+        if dir.hypot() < 1e-9 {
+            dir = seg.eval((t + 1e-3).min(1.0)) - seg.eval((t - 1e-3).max(0.0));
+        }
+
+        Ok(Angle::rad(dir.y.atan2(dir.x)))
+    }
+}
+
+impl PathElem {
+    /// Build the path's geometry, with coordinates resolved by `resolve`, and
+    /// compute the size of its frame.
+    ///
+    /// Returns `None` if the path has no vertices.
+    fn geometry(
+        &self,
+        styles: StyleChain,
+        resolve: impl Fn(Axes<Rel<Length>>) -> Point,
+    ) -> Option<(Path, Size)> {
         let vertices = self.vertices();
         let points: Vec<Point> = vertices.iter().map(|c| resolve(c.vertex())).collect();
-
-        let mut size = Size::zero();
         if points.is_empty() {
-            return Ok(Fragment::frame(Frame::soft(size)));
+            return None;
         }
 
-        // Only create a path if there are more than zero points.
-        // Construct a closed path given all points.
-        let mut path = Path::new();
-        path.move_to(points[0]);
-
+        // Compute the control points of all segments and the size of the
+        // frame.
+        let mut size = Size::zero();
+        let mut segments = vec![];
         let mut add_cubic =
             |from_point: Point, to_point: Point, from: PathVertex, to: PathVertex| {
                 let from_control_point = resolve(from.control_point_from()) + from_point;
                 let to_control_point = resolve(to.control_point_to()) + to_point;
-                path.cubic_to(from_control_point, to_control_point, to_point);
+                let straight =
+                    from_control_point == from_point && to_control_point == to_point;
+                segments
+                    .push((!straight).then_some((from_control_point, to_control_point)));
 
                 let p0 = kurbo::Point::new(from_point.x.to_raw(), from_point.y.to_raw());
                 let p1 = kurbo::Point::new(
@@ -127,29 +274,85 @@ impl Layout for PathElem {
             add_cubic(from_point, to_point, from, to);
         }
 
-        if self.closed(styles) {
+        let closed = self.closed(styles);
+        if closed {
             let from = *vertices.last().unwrap(); // We checked that we have at least one element.
             let to = vertices[0];
             let from_point = *points.last().unwrap();
             let to_point = points[0];
 
             add_cubic(from_point, to_point, from, to);
-            path.close_path();
         }
 
-        // Prepare fill and stroke.
-        let fill = self.fill(styles);
-        let stroke = match self.stroke(styles) {
-            Smart::Auto if fill.is_none() => Some(FixedStroke::default()),
-            Smart::Auto => None,
-            Smart::Custom(stroke) => stroke.map(Stroke::unwrap_or_default),
-        };
+        let path = Path::rounded(&points, &segments, closed, self.radius(styles));
+        Some((path, size))
+    }
+}
 
-        let mut frame = Frame::soft(size);
-        let shape = Shape { geometry: Geometry::Path(path), stroke, fill };
-        frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
+/// The accuracy with which path lengths are measured, in raw units.
+const ACCURACY: f64 = 1e-6;
 
-        Ok(Fragment::frame(frame))
+/// Split a path into its segments for measuring.
+///
+/// As there are no regions or styles to resolve against, all coordinates must
+/// be absolute and properties are only taken from the path itself, not from
+/// set rules.
+fn segments(path: &Content) -> StrResult<Vec<PathSeg>> {
+    let Some(elem) = path.to::<PathElem>() else {
+        bail!("expected path, found {}", path.func().name());
+    };
+
+    let absolute = |rel: Rel<Length>| rel.rel.is_zero() && rel.abs.em.is_zero();
+    for vertex in elem.vertices() {
+        for axes in
+            [vertex.vertex(), vertex.control_point_from(), vertex.control_point_to()]
+        {
+            if !absolute(axes.x) || !absolute(axes.y) {
+                bail!("cannot measure a path with relative coordinates");
+            }
+        }
+    }
+
+    let resolve = |axes: Axes<Rel<Length>>| Point::new(axes.x.abs.abs, axes.y.abs.abs);
+    let Some((path, _)) = elem.geometry(StyleChain::default(), resolve) else {
+        return Ok(vec![]);
+    };
+
+    let point = |p: &Point| kurbo::Point::new(p.x.to_raw(), p.y.to_raw());
+    let mut bez = BezPath::new();
+    for item in &path.0 {
+        match item {
+            PathItem::MoveTo(p) => bez.move_to(point(p)),
+            PathItem::LineTo(p) => bez.line_to(point(p)),
+            PathItem::CubicTo(c1, c2, p) => bez.curve_to(point(c1), point(c2), point(p)),
+            PathItem::ClosePath => bez.close_path(),
+        }
+    }
+
+    Ok(bez.segments().collect())
+}
+
+/// Find the segment that contains the position `at` along a path and the
+/// segment's curve parameter at that position.
+fn locate(path: &Content, at: Ratio) -> StrResult<(PathSeg, f64)> {
+    if !(0.0..=1.0).contains(&at.get()) {
+        bail!("position must be between 0% and 100%");
+    }
+
+    let segments = segments(path)?;
+    let lengths: Vec<f64> = segments.iter().map(|seg| seg.arclen(ACCURACY)).collect();
+    let mut remaining = at.get() * lengths.iter().sum::<f64>();
+    for (&seg, &length) in segments.iter().zip(&lengths) {
+        if remaining <= length {
+            let t = if length > 0.0 { seg.inv_arclen(remaining, ACCURACY) } else { 0.0 };
+            return Ok((seg, t));
+        }
+        remaining -= length;
+    }
+
+    match segments.last() {
+        Some(&seg) => Ok((seg, 1.0)),
+        None => bail!("path must have at least two distinct points"),
     }
 }
 
@@ -247,6 +450,68 @@ impl Path {
         path
     }
 
+    /// Create a path through the given points whose corners between straight
+    /// segments are rounded with the given radius.
+    ///
+    /// Each segment connects a point with the next one and is either straight
+    /// or a cubic Bezier curve with the given control points. If the path is
+    /// closed, there is one more segment that connects the last point with the
+    /// first one.
+    pub fn rounded(
+        points: &[Point],
+        segments: &[Option<(Point, Point)>],
+        closed: bool,
+        radius: Abs,
+    ) -> Self {
+        let mut path = Self::new();
+        let Some(&first) = points.first() else { return path };
+        let n = points.len();
+
+        let corners: Vec<_> = (0..n)
+            .map(|i| {
+                let prev = if i > 0 { Some(i - 1) } else { closed.then_some(n - 1) };
+                let next = segments.get(i).map(|_| (i + 1) % n);
+                let (prev, next) = (prev?, next?);
+                if segments[prev].is_some() || segments[i].is_some() {
+                    return None;
+                }
+                round_corner(points[prev], points[i], points[next], radius)
+            })
+            .collect();
+
+        match corners[0] {
+            Some([start, c1, c2, end]) => {
+                path.move_to(start);
+                path.cubic_to(c1, c2, end);
+            }
+            None => path.move_to(first),
+        }
+
+        for (i, segment) in segments.iter().enumerate() {
+            let j = (i + 1) % n;
+            let target = corners[j].map_or(points[j], |[start, ..]| start);
+            match segment {
+                // The closing line is added by closing the path.
+                None if closed && j == 0 => {}
+                None => path.line_to(target),
+                Some((c1, c2)) => path.cubic_to(*c1, *c2, target),
+            }
+
+            // The first corner was already added at the start.
+            if j != 0 {
+                if let Some([_, c1, c2, end]) = corners[j] {
+                    path.cubic_to(c1, c2, end);
+                }
+            }
+        }
+
+        if closed {
+            path.close_path();
+        }
+
+        path
+    }
+
     /// Push a [`MoveTo`](PathItem::MoveTo) item.
     pub fn move_to(&mut self, p: Point) {
         self.0.push(PathItem::MoveTo(p));
@@ -313,3 +578,44 @@ impl Path {
         Size::new(max_x - min_x, max_y - min_y)
     }
 }
+
+/// Compute the Bezier curve that rounds the corner between the straight lines
+/// from `prev` to `corner` and from `corner` to `next`.
+///
+/// Returns the start point, the two control points, and the end point of the
+/// curve, or `None` if the corner doesn't need to be rounded.
+fn round_corner(
+    prev: Point,
+    corner: Point,
+    next: Point,
+    radius: Abs,
+) -> Option<[Point; 4]> {
+    let a = prev - corner;
+    let b = next - corner;
+    let (la, lb) = (a.hypot().to_raw(), b.hypot().to_raw());
+    if radius.to_raw() <= 0.0 || la <= 0.0 || lb <= 0.0 {
+        return None;
+    }
+
+    // The unit vectors pointing from the corner to its neighbours and the
+    // interior angle between them.
+    let (ua, ub) = (a / la, b / lb);
+    let cos = ua.x.to_raw() * ub.x.to_raw() + ua.y.to_raw() * ub.y.to_raw();
+    let angle = cos.clamp(-1.0, 1.0).acos();
+    if angle < 1e-6 || PI - angle < 1e-6 {
+        return None;
+    }
+
+    // The distance from the corner to the points where the arc touches the
+    // lines. Limiting it to half the lines' lengths ensures that adjacent
+    // corners don't overlap.
+    let half_tan = (angle / 2.0).tan();
+    let dist = (radius.to_raw() / half_tan).min(la / 2.0).min(lb / 2.0);
+    let radius = dist * half_tan;
+
+    // Approximate the circular arc with a cubic Bezier curve.
+    let handle = 4.0 / 3.0 * ((PI - angle) / 4.0).tan() * radius;
+    let start = corner + ua * dist;
+    let end = corner + ub * dist;
+    Some([start, start - ua * handle, end - ub * handle, end])
+}
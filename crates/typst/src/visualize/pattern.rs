@@ -36,11 +36,11 @@ use crate::World;
 /// #rect(fill: pat, width: 100%, height: 100%, stroke: 1pt)
 /// ```
 ///
-/// Patterns are also supported on text, but only when setting the
-/// [relativeness]($pattern.relative) to either `{auto}` (the default value) or
-/// `{"parent"}`. To create word-by-word or glyph-by-glyph patterns, you can
-/// wrap the words or characters of your text in [boxes]($box) manually or
-/// through a [show rule]($styling/#show-rules).
+/// Patterns are also supported on text. By default, they span the closest
+/// ancestor container. When the [relativeness]($pattern.relative) is set to
+/// `{"self"}`, the pattern starts anew for each glyph instead. As a pattern's
+/// body can be any content, this also makes it possible to fill text with an
+/// [image]($image).
 ///
 /// ```example
 /// #let pat = pattern(
@@ -45,6 +45,25 @@ pub struct PolygonElem {
     #[fold]
     pub stroke: Smart<Option<Stroke>>,
 
+    /// How much to round the polygon's corners.
+    ///
+    /// Radii that are too large for the adjacent edges are reduced
+    /// automatically.
+    ///
+    /// ```example
+    /// #polygon(
+    ///   fill: blue.lighten(80%),
+    ///   radius: 6pt,
+    ///   (0pt, 0pt),
+    ///   (80pt, 0pt),
+    ///   (100pt, 20pt),
+    ///   (80pt, 40pt),
+    ///   (0pt, 40pt),
+    /// )
+    /// ```
+    #[resolve]
+    pub radius: Length,
+
     /// The vertices of the polygon. Each point is specified as an array of two
     /// [relative lengths]($relative).
     #[variadic]
@@ -159,12 +178,8 @@ impl Layout for PolygonElem {
         };
 
         // Construct a closed path given all points.
-        let mut path = Path::new();
-        path.move_to(points[0]);
-        for &point in &points[1..] {
-            path.line_to(point);
-        }
-        path.close_path();
+        let segments = vec![None; points.len()];
+        let path = Path::rounded(&points, &segments, true, self.radius(styles));
 
         let shape = Shape { geometry: Geometry::Path(path), stroke, fill };
         frame.push(Point::zero(), FrameItem::Shape(shape, self.span()));
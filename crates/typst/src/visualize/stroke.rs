@@ -11,9 +11,9 @@ use crate::visualize::{Color, Gradient, Paint, Pattern};
 
 /// Defines how to draw a line.
 ///
-/// A stroke has a _paint_ (a solid color or gradient), a _thickness,_ a line
-/// _cap,_ a line _join,_ a _miter limit,_ and a _dash_ pattern. All of these
-/// values are optional and have sensible defaults.
+/// A stroke has a _paint_ (a solid color, gradient, or pattern), a
+/// _thickness,_ a line _cap,_ a line _join,_ a _miter limit,_ and a _dash_
+/// pattern. All of these values are optional and have sensible defaults.
 ///
 /// # Example
 /// ```example
@@ -0,0 +1,2057 @@
+//! Tests for the Rust API of the compiler.
+//!
+//! The tests in `typ` cover the language and layout. The ones here cover
+//! entry points that can only be reached from Rust, like compiling several
+//! documents at once.
+
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Wake, Waker};
+
+use comemo::Prehashed;
+use ecow::{EcoString, EcoVec};
+use typst::diag::{
+    FileError, FileResult, Lookup, Severity, SourceDiagnostic, StrResult, WarningCategory,
+};
+use typst::engine::CompilationCache;
+use typst::eval::{StyleOrigin, Tracer};
+use typst::foundations::{dict, Bytes, Content, Datetime, Dict, Label, Selector, Value};
+use typst::introspection::{Introspector, Meta};
+use typst::layout::{Abs, Bounds, Frame, FrameItem, Point, Size, Transform};
+use typst::model::{
+    CitationData, CitationStyle, CustomStyle, Destination, Document, DocumentInfo,
+    ReferenceData,
+};
+use typst::realize::RealizeHook;
+use typst::syntax::{FileId, LinkedNode, Source, SyntaxKind, VirtualPath};
+use typst::text::{Font, FontBook, FontVariant, TextElem, TextLayer, TextRun};
+use typst::util::hash128;
+use typst::util::persist::Store;
+use typst::visualize::{
+    Color, Geometry, Image, ImageFormat, PathItem, RasterFormat, Shape,
+};
+use typst::{AsyncWorld, BoxFuture, Library, Prefetch, World, WorldExt};
+use typst_ide::{origins_from_click, Jump, Origin};
+use typst_pdf::{ImageOptions, PdfExporter, PdfStandard};
+use walkdir::WalkDir;
+use zip::{CompressionMethod, ZipArchive};
+
+const FONT_DIR: &str = "../assets/fonts";
+const ASSET_DIR: &str = "../assets";
+
+/// A world with in-memory sources.
+struct ApiWorld {
+    main: FileId,
+    library: Prehashed<Library>,
+    sources: HashMap<FileId, Source>,
+    styles: HashMap<&'static str, CustomStyle>,
+    missing: Mutex<Vec<Lookup>>,
+    store: Option<Box<dyn Store>>,
+}
+
+impl ApiWorld {
+    /// Create a world whose main file has the given text.
+    fn new(text: &str) -> Self {
+        let main = id("main.typ");
+        let mut sources = HashMap::new();
+        sources.insert(main, Source::new(main, text.into()));
+        Self {
+            main,
+            library: Prehashed::new(Library::build()),
+            sources,
+            styles: HashMap::new(),
+            missing: Mutex::new(vec![]),
+            store: None,
+        }
+    }
+
+    /// Replace a range of the main file's text.
+    fn edit(&mut self, replace: std::ops::Range<usize>, with: &str) {
+        self.sources.get_mut(&self.main).unwrap().edit(replace, with);
+    }
+
+    /// Add another source file.
+    fn with(mut self, path: &str, text: &str) -> Self {
+        let id = id(path);
+        self.sources.insert(id, Source::new(id, text.into()));
+        self
+    }
+
+    /// Set the inputs that the main file sees as `sys.inputs`.
+    fn with_inputs(mut self, inputs: Dict) -> Self {
+        let mut library = Library::build();
+        library.set_inputs(inputs);
+        self.library = Prehashed::new(library);
+        self
+    }
+
+    /// Provide a custom citation style.
+    fn with_style(
+        mut self,
+        name: &'static str,
+        style: impl CitationStyle + 'static,
+    ) -> Self {
+        self.styles.insert(name, CustomStyle::new(style));
+        self
+    }
+
+    /// Persist shaping and image decoding results in a store.
+    fn with_store(mut self, store: impl Store + 'static) -> Self {
+        self.store = Some(Box::new(store));
+        self
+    }
+}
+
+impl World for ApiWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &fonts().0
+    }
+
+    fn main(&self) -> Source {
+        self.sources[&self.main].clone()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        let path = id.vpath().as_rootless_path();
+        self.sources
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| FileError::NotFound(path.into()))
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        let path = id.vpath().as_rootless_path();
+        fs::read(Path::new(ASSET_DIR).join(path))
+            .map(Bytes::from)
+            .map_err(|err| FileError::from_io(err, path))
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        fonts().1.get(index).cloned()
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        Some(Datetime::from_ymd(1970, 1, 1).unwrap())
+    }
+
+    fn missing(&self, lookup: &Lookup) {
+        self.missing.lock().unwrap().push(lookup.clone());
+    }
+
+    fn citation_style(&self, name: &str) -> Option<CustomStyle> {
+        self.styles.get(name).cloned()
+    }
+
+    fn store(&self) -> Option<&dyn Store> {
+        self.store.as_deref()
+    }
+}
+
+/// The fonts of the test environment, loaded just once for all tests.
+fn fonts() -> &'static (Prehashed<FontBook>, Vec<Font>) {
+    static FONTS: OnceLock<(Prehashed<FontBook>, Vec<Font>)> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        let mut fonts = vec![];
+        for entry in WalkDir::new(FONT_DIR)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|entry| entry.file_type().is_file())
+        {
+            let data = fs::read(entry.path()).unwrap();
+            fonts.extend(Font::iter(data.into()));
+        }
+        (Prehashed::new(FontBook::from_fonts(&fonts)), fonts)
+    })
+}
+
+/// Compile the main file of a world, panicking on errors.
+fn compile(world: &ApiWorld) -> Document {
+    let mut tracer = Tracer::new();
+    typst::compile(world, &mut tracer).expect("compilation failed")
+}
+
+/// The ID of a file in the project root.
+fn id(path: &str) -> FileId {
+    FileId::new(None, VirtualPath::new(path))
+}
+
+/// The values of all metadata elements with the given label.
+fn metadata(document: &Document, label: &str) -> Vec<Value> {
+    Introspector::new(&document.pages)
+        .query(&Selector::Label(Label::new(label)))
+        .into_iter()
+        .filter_map(|elem| elem.get_by_name("value"))
+        .collect()
+}
+
+/// The destinations of all links in a frame.
+fn links(frame: &Frame) -> Vec<Destination> {
+    let mut dests = vec![];
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => dests.extend(links(&group.frame)),
+            FrameItem::Meta(Meta::Link(dest), _) => dests.push(dest.clone()),
+            _ => {}
+        }
+    }
+    dests
+}
+
+#[test]
+fn test_compile_project_positions_are_per_document() {
+    let world = ApiWorld::new("")
+        .with(
+            "a.typ",
+            "#set heading(numbering: \"1.\")\n\
+             = First\n\
+             #pagebreak()\n\
+             = Second <second>",
+        )
+        .with(
+            "b.typ",
+            "#pagebreak()\n\
+             #locate(loc => [#metadata(loc.page()) <page>])\n\
+             See @second.",
+        );
+
+    let mut tracer = Tracer::new();
+    let documents =
+        typst::compile_project(&world, &[id("a.typ"), id("b.typ")], &mut tracer)
+            .expect("compilation failed");
+
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[0].pages.len(), 2);
+    assert_eq!(documents[1].pages.len(), 2);
+
+    // The page number is relative to the second document.
+    assert_eq!(metadata(&documents[1], "page"), [Value::Int(2)]);
+
+    // The reference links to the second page of the first document.
+    let dests: Vec<_> = documents[1].pages.iter().flat_map(links).collect();
+    let [Destination::Document(0, pos)] = dests.as_slice() else {
+        panic!("expected a link into the first document, found {dests:?}");
+    };
+    assert_eq!(pos.page.get(), 2);
+}
+
+#[test]
+fn test_project_links_open_other_files() {
+    let world = ApiWorld::new("")
+        .with("a.typ", "#pagebreak()\n= Target <target>")
+        .with("b.typ", "#link(<target>)[Go]");
+
+    let mut tracer = Tracer::new();
+    let documents =
+        typst::compile_project(&world, &[id("a.typ"), id("b.typ")], &mut tracer)
+            .expect("compilation failed");
+
+    let export = |project: &[(EcoString, &Document)]| {
+        let mut exporter = PdfExporter::new().with_project(project);
+        for frame in &documents[1].pages {
+            exporter.push_page(frame);
+        }
+        exporter.finish(&DocumentInfo::default(), None, None).unwrap()
+    };
+
+    // The link opens the second page of the first document's file.
+    let pdf = export(&[("a.pdf".into(), &documents[0]), ("b.pdf".into(), &documents[1])]);
+    assert!(find(&pdf, b"/S /GoToR").is_some());
+    assert!(find(&pdf, b"/F (a.pdf)").is_some());
+    assert!(find(&pdf, b"/D [1 /XYZ").is_some());
+
+    // Without the files of the project, there is no dead link.
+    let pdf = export(&[]);
+    assert!(find(&pdf, b"/Subtype /Link").is_none());
+}
+
+/// Whether two frames share their items, i.e. one is a reused copy of the
+/// other.
+fn reused(a: &Frame, b: &Frame) -> bool {
+    std::ptr::eq(a.items().as_slice(), b.items().as_slice())
+}
+
+#[test]
+fn test_unchanged_page_runs_are_reused_across_edits() {
+    let text = "#set heading(numbering: \"1.\")\n\
+                = One\n\
+                #pagebreak()\n\
+                #include \"two.typ\"\n\
+                #pagebreak()\n\
+                Three";
+    let mut world = ApiWorld::new(text).with("two.typ", "= Two\nReused run.");
+    let first = compile(&world);
+    assert!(self::text(&first.pages[1]).contains("2."));
+
+    // Editing the last page run leaves the second one as it was.
+    let end = text.len();
+    world.edit(end..end, ", edited");
+    let second = compile(&world);
+    assert!(self::text(&second.pages[2]).contains("edited"));
+    assert!(reused(&first.pages[1], &second.pages[1]));
+
+    // A heading on the first page changes the counter at the start of the
+    // second run, which must thus be laid out again.
+    let start = text.find("= One").unwrap();
+    world.edit(start..start, "= Extra\n");
+    let third = compile(&world);
+    assert!(!reused(&second.pages[1], &third.pages[1]));
+    assert!(self::text(&third.pages[1]).contains("3."));
+}
+
+#[test]
+fn test_text_runs_split_by_language_and_line() {
+    let text = "Hello #text(lang: \"de\", region: \"ch\")[Welt] again\n\nNext";
+    let world = ApiWorld::new(text);
+    let runs = TextRun::collect(&compile(&world).pages);
+
+    let summary: Vec<_> = runs
+        .iter()
+        .map(|run| (run.text.trim(), run.lang.as_str(), run.region.map(|r| r.as_str())))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("Hello", "en", None),
+            ("Welt", "de", Some("CH")),
+            ("again", "en", None),
+            ("Next", "en", None),
+        ]
+    );
+
+    // Each run maps back to where its text comes from.
+    for run in &runs {
+        let (i, span, start) = run.spans[0];
+        assert_eq!(run.span(i), Some((span, start)));
+        let offset = world.range(span).unwrap().start + usize::from(start);
+        assert!(text[offset..].starts_with(&run.text[i..]));
+    }
+}
+
+#[test]
+fn test_pdf_actual_text_for_ligatures_and_hyphens() {
+    let world = ApiWorld::new(
+        "#set page(width: 40pt)\n\
+         #set text(hyphenate: true)\n\
+         confirmation",
+    );
+
+    let document = compile(&world);
+    assert!(self::text(&document.pages[0]).contains('-'));
+
+    let pdf = typst_pdf::pdf(&document, None, None);
+    let content = pdf_streams(&pdf).concat();
+
+    // The ligature stands for the two letters it replaces and the inserted
+    // hyphen for no text at all, while plain glyphs need no actual text.
+    assert!(content.contains("/ActualText (fi)"));
+    assert!(content.contains("/ActualText ()"));
+    assert!(!content.contains("/ActualText (c"));
+}
+
+#[test]
+fn test_text_layer_boxes_glyphs_and_words() {
+    let world = ApiWorld::new(
+        "#set page(width: 200pt, height: 100pt, margin: 10pt)\n\
+         #set text(size: 10pt)\n\
+         first line\n\n\
+         fine",
+    );
+
+    let layer = TextLayer::new(&compile(&world).pages[0]);
+    assert_eq!(layer.text, "first line\nfine");
+
+    // The ligature of "fi" is one cluster with one box.
+    let clusters: Vec<_> =
+        layer.glyphs.iter().map(|g| &layer.text[g.range.clone()]).collect();
+    assert_eq!(clusters[..3], ["fi", "r", "s"]);
+    assert!(layer.glyphs[0].pos.x.approx_eq(Abs::pt(10.0)));
+    assert!(layer.glyphs[1].pos.x > layer.glyphs[0].pos.x);
+
+    // Each word box spans the boxes of its glyphs.
+    let words: Vec<_> =
+        layer.words.iter().map(|w| &layer.text[w.range.clone()]).collect();
+    assert_eq!(words, ["first", "line", "fine"]);
+    let first = &layer.words[0];
+    let t = &layer.glyphs[3];
+    assert_eq!(&layer.text[t.range.clone()], "t");
+    assert!(first.pos.x.approx_eq(Abs::pt(10.0)));
+    assert!((first.pos.x + first.size.x).approx_eq(t.pos.x + t.size.x));
+    assert!(layer.words[2].pos.y > first.pos.y + first.size.y / 2.0);
+}
+
+#[test]
+fn test_text_layer_separates_columns() {
+    let world = ApiWorld::new(
+        "#set page(width: 200pt, height: 100pt, margin: 10pt)\n\
+         #columns(2)[left #colbreak() right]",
+    );
+
+    let layer = TextLayer::new(&compile(&world).pages[0]);
+    assert_eq!(layer.text, "left\nright");
+    assert_eq!(layer.words.len(), 2);
+    assert!(layer.words[0].pos.y.approx_eq(layer.words[1].pos.y));
+    assert!(layer.words[1].pos.x > Abs::pt(100.0));
+}
+
+/// The set rules that style the element created by the syntax node of the
+/// given kind at the first occurrence of a needle in the main file, each
+/// formatted as the property's path and the rule's text.
+fn set_origins(world: &ApiWorld, needle: &str, kind: SyntaxKind) -> Vec<String> {
+    let source = world.main();
+    let offset = source.text().find(needle).unwrap();
+    let root = LinkedNode::new(source.root());
+    let mut node = root.leaf_at(offset + 1).unwrap();
+    while node.kind() != kind {
+        node = node.parent().unwrap().clone();
+    }
+
+    let mut tracer = Tracer::new();
+    tracer.inspect_origins(node.span());
+    typst::compile(world, &mut tracer).expect("compilation failed");
+    tracer
+        .origins()
+        .into_iter()
+        .filter_map(|origin| match origin {
+            StyleOrigin::Set { elem, field, span } => {
+                let rule = &source.text()[world.range(span).unwrap()];
+                Some(format!("{}.{field}: {rule}", elem.name()))
+            }
+            StyleOrigin::Show { .. } => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_style_origins_include_inherited_rules() {
+    let world = ApiWorld::new(
+        "#set text(red)\n\
+         #set list(indent: 1em)\n\
+         #set block(above: 2em)\n\
+         #set heading(numbering: \"1.\")\n\
+         #set text(features: (\"smcp\",))\n\
+         = Intro\n\
+         #[\n\
+           #set text(blue)\n\
+           = Outro\n\
+         ]",
+    );
+
+    // The heading's own rule, the rules for the text in its body, which fold
+    // into its features, and the rule for the block it is shown as. The list
+    // rule doesn't reach it.
+    let mut origins = set_origins(&world, "= Intro", SyntaxKind::Heading);
+    origins.sort();
+    assert_eq!(
+        origins,
+        [
+            "block.above: set block(above: 2em)",
+            "heading.numbering: set heading(numbering: \"1.\")",
+            "text.features: set text(features: (\"smcp\",))",
+            "text.fill: set text(red)",
+        ]
+    );
+
+    // Inner rules are traced alongside the outer ones they override.
+    let origins = set_origins(&world, "= Outro", SyntaxKind::Heading);
+    assert!(origins.contains(&"text.fill: set text(red)".into()));
+    assert!(origins.contains(&"text.fill: set text(blue)".into()));
+}
+
+#[test]
+fn test_realize_hooks_are_identified_by_name_and_key() {
+    // A hook that replaces the whole document with a configured word.
+    let hook = |word: &'static str| {
+        RealizeHook::new("replace", move |_| Ok(TextElem::packed(word))).with_key(word)
+    };
+    let world = |hook| {
+        let mut world = ApiWorld::new("Original");
+        let mut library = Library::build();
+        library.hooks.push(hook);
+        world.library = Prehashed::new(library);
+        world
+    };
+
+    assert_eq!(hash128(&hook("A")), hash128(&hook("A")));
+    assert_ne!(hash128(&hook("A")), hash128(&hook("B")));
+    assert_eq!(text(&compile(&world(hook("A"))).pages[0]), "A");
+    assert_eq!(text(&compile(&world(hook("B"))).pages[0]), "B");
+}
+
+#[test]
+fn test_dashed_paint_strokes_in_all_exporters() {
+    let world = ApiWorld::new(
+        "#set page(width: 100pt, height: 20pt, margin: 0pt)\n\
+         #let dashed(paint) = (paint: paint, thickness: 4pt, dash: (10pt, 10pt))\n\
+         #let pat = pattern(size: (4pt, 4pt), square(size: 4pt, fill: red))\n\
+         #let grad = gradient.linear(red, blue)\n\
+         #place(dy: 5pt, line(length: 100%, stroke: dashed(grad)))\n\
+         #place(dy: 15pt, line(length: 100%, stroke: dashed(pat)))",
+    );
+
+    let document = compile(&world);
+    let frame = &document.pages[0];
+
+    // Both lines are dashed: painted in the first ten points of each
+    // twenty-point period and empty in the second ten.
+    let pixmap = typst_render::render(frame, 1.0, Color::WHITE);
+    let painted = |x: u32, y: u32| {
+        let pixel = pixmap.pixel(x, y).unwrap();
+        (pixel.red(), pixel.green(), pixel.blue()) != (255, 255, 255)
+    };
+    for y in [5, 15] {
+        assert!(painted(5, y) && painted(25, y), "dash missing in line at {y}pt");
+        assert!(!painted(15, y) && !painted(35, y), "gap missing in line at {y}pt");
+    }
+
+    // The SVG references the paint server and keeps the dash array.
+    let svg = typst_svg::svg(frame);
+    assert_eq!(svg.matches("stroke=\"url(#").count(), 2);
+    assert_eq!(svg.matches("stroke-dasharray=\"10 10\"").count(), 2);
+
+    // The PDF export handles both paints.
+    let pdf = typst_pdf::pdf(&document, Some("dashed-strokes"), None);
+    assert!(pdf.starts_with(b"%PDF-"));
+}
+
+#[test]
+fn test_blur_spreads_only_around_content() {
+    let world = ApiWorld::new(
+        "#set page(width: 100pt, height: 100pt, margin: 0pt)\n\
+         #place(dx: 40pt, dy: 40pt, blur(2pt, rect(width: 20pt, height: 20pt, fill: black)))",
+    );
+
+    let document = compile(&world);
+    let pixmap = typst_render::render(&document.pages[0], 1.0, Color::WHITE);
+    let luma = |x: u32, y: u32| pixmap.pixel(x, y).unwrap().red();
+
+    // The center stays dark, the edges are soft, and the blur fades out
+    // within a few standard deviations.
+    assert!(luma(50, 50) < 20);
+    assert!((20..235).contains(&luma(40, 50)));
+    assert!((20..235).contains(&luma(50, 60)));
+    assert!(luma(37, 50) > luma(40, 50));
+    assert_eq!(luma(30, 50), 255);
+    assert_eq!(luma(5, 5), 255);
+}
+
+#[test]
+fn test_blur_in_pdf_uses_rasterizer() {
+    fn rasterize(frame: &Frame, pixel_per_pt: f32) -> Option<Image> {
+        let pixmap =
+            typst_render::render(frame, pixel_per_pt, Color::WHITE.with_alpha(0.0));
+        let data = pixmap.encode_png().ok()?;
+        Image::new(data.into(), ImageFormat::Raster(RasterFormat::Png), None).ok()
+    }
+
+    let world = ApiWorld::new("#blur(rect(fill: black))");
+    let document = compile(&world);
+    let export = |mut exporter: PdfExporter| {
+        exporter.push_page(&document.pages[0]);
+        let pdf = exporter.finish(&document.info, Some("blur"), None).unwrap();
+        String::from_utf8_lossy(&pdf).contains("/Subtype /Image")
+    };
+
+    // With a rasterizer, the blurred content becomes an image. Without one,
+    // it is written as vector content without the blur.
+    assert!(export(PdfExporter::new().with_rasterizer(rasterize)));
+    assert!(!export(PdfExporter::new()));
+}
+
+/// The number of lines in a frame, like the footnote separator.
+fn lines(frame: &Frame) -> usize {
+    frame
+        .items()
+        .map(|(_, item)| match item {
+            FrameItem::Group(group) => lines(&group.frame),
+            FrameItem::Shape(shape, _) => {
+                matches!(shape.geometry, Geometry::Line(_)) as usize
+            }
+            _ => 0,
+        })
+        .sum()
+}
+
+/// The points of all paths in a frame, in page coordinates.
+fn paths(frame: &Frame, ts: Transform) -> Vec<Vec<Point>> {
+    let mut out = vec![];
+    for (pos, item) in frame.items() {
+        let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+        match item {
+            FrameItem::Group(group) => {
+                out.extend(paths(&group.frame, ts.pre_concat(group.transform)))
+            }
+            FrameItem::Shape(shape, _) => {
+                let Geometry::Path(path) = &shape.geometry else { continue };
+                let points = path.0.iter().map(|item| match *item {
+                    PathItem::MoveTo(p) | PathItem::LineTo(p) => p,
+                    PathItem::CubicTo(_, _, p) => p,
+                    PathItem::ClosePath => Point::zero(),
+                });
+                out.push(points.map(|p| p.transform(ts)).collect());
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// The bounding box of the element with the given label.
+fn bounds(document: &Document, label: &str) -> Bounds {
+    let introspector = Introspector::new(&document.pages);
+    let elem = introspector.query_label(Label::new(label)).unwrap();
+    introspector.bounds(elem.location().unwrap())
+}
+
+#[test]
+fn test_connector_goes_around_elements_in_the_way() {
+    let world = ApiWorld::new(
+        "#stack(\n\
+           dir: ltr,\n\
+           spacing: 20pt,\n\
+           [#rect(width: 10pt)[A] <a>],\n\
+           [#rect(width: 10pt)[B] <b>],\n\
+           [#rect(width: 10pt)[C] <c>],\n\
+         )\n\
+         #connect(<a>, <c>, arrow: false)",
+    );
+
+    let document = compile(&world);
+    let b = bounds(&document, "b");
+    let paths = paths(&document.pages[0], Transform::identity());
+    assert_eq!(paths.len(), 1);
+
+    // The direct route through B is blocked, so the connector leaves A, goes
+    // over or under B, and enters C.
+    let points = &paths[0];
+    assert_eq!(points.len(), 6);
+    for segment in points.windows(2) {
+        let (lo, hi) = (segment[0].min(segment[1]), segment[0].max(segment[1]));
+        assert!(
+            hi.x <= b.point.x
+                || lo.x >= b.point.x + b.size.x
+                || hi.y <= b.point.y
+                || lo.y >= b.point.y + b.size.y,
+            "segment {segment:?} crosses {b:?}",
+        );
+    }
+}
+
+#[test]
+fn test_connector_in_transformed_content_ends_on_elements() {
+    for transform in ["rotate(90deg)", "scale(50%)", "move(dx: 10pt, dy: 5pt)"] {
+        let world = ApiWorld::new(&format!(
+            "#rect[A] <a>\n\
+             #v(20pt)\n\
+             #pad(left: 40pt)[#rect[B] <b>]\n\
+             #{transform}[#connect(<a>, <b>, style: \"straight\", arrow: false)]",
+        ));
+
+        let document = compile(&world);
+        let (a, b) = (bounds(&document, "a"), bounds(&document, "b"));
+        let paths = paths(&document.pages[0], Transform::identity());
+        assert_eq!(paths.len(), 1, "{transform}");
+
+        // The connector is drawn in the transformed content, but its ends
+        // are on the borders of the elements on the page.
+        let close = |p: Point, q: Point| (p - q).hypot() < Abs::pt(0.01);
+        let (start, end) = (paths[0][0], paths[0][1]);
+        assert!(close(start, a.boundary(b.center())), "{transform}: {start:?}");
+        assert!(close(end, b.boundary(a.center())), "{transform}: {end:?}");
+    }
+}
+
+#[test]
+fn test_float_with_footnotes_moves_to_next_page_cleanly() {
+    // The float itself fits on the first page, but its footnote doesn't. Both
+    // move to the second page and the first page is left as if the float had
+    // never been tried there: The text after the float still fits and its
+    // footnote gets a separator.
+    let world = ApiWorld::new(
+        "#set page(width: 200pt, height: 200pt, margin: 20pt)\n\
+         A\n\
+         #figure(\n\
+           placement: auto,\n\
+           rect(height: 100pt),\n\
+           caption: [Float #footnote[#lorem(40)]],\n\
+         )\n\
+         After #footnote[Later]\n",
+    );
+
+    let document = compile(&world);
+    assert_eq!(document.pages.len(), 2);
+    let (first, second) = (text(&document.pages[0]), text(&document.pages[1]));
+    assert!(first.contains("After") && first.contains("Later"));
+    assert!(!first.contains("Float") && !first.contains("Lorem"));
+    assert!(second.contains("Float") && second.contains("Lorem"));
+    assert_eq!(lines(&document.pages[0]), 1);
+    assert_eq!(lines(&document.pages[1]), 1);
+}
+
+#[test]
+fn test_persisted_shaping_and_decoding_results_are_reused() {
+    /// A store that keeps its entries in memory and records the hits.
+    #[derive(Default)]
+    struct MemoryStore {
+        entries: Mutex<HashMap<(String, u128), Vec<u8>>>,
+        hits: Mutex<Vec<(String, u128)>>,
+    }
+
+    impl Store for Arc<MemoryStore> {
+        fn load(&self, kind: &str, key: u128) -> Option<Vec<u8>> {
+            let data = self.entries.lock().unwrap().get(&(kind.into(), key)).cloned();
+            if data.is_some() {
+                self.hits.lock().unwrap().push((kind.into(), key));
+            }
+            data
+        }
+
+        fn save(&self, kind: &str, key: u128, data: &[u8]) {
+            self.entries.lock().unwrap().insert((kind.into(), key), data.to_vec());
+        }
+    }
+
+    let store = Arc::new(MemoryStore::default());
+
+    // The first compilation fills the store.
+    let text = "#image(\"/files/tiger.jpg\", width: 50pt)\nPersisted glyphs.";
+    let data =
+        Bytes::from(fs::read(Path::new(ASSET_DIR).join("files/tiger.jpg")).unwrap());
+    let image_key = (String::from("image"), hash128(&(&data, RasterFormat::Jpg)));
+
+    let world = ApiWorld::new(text).with_store(store.clone());
+    let first = typst_render::render(&compile(&world).pages[0], 1.0, Color::WHITE);
+    let entries = store.entries.lock().unwrap();
+    assert!(entries.contains_key(&image_key));
+    assert!(entries.keys().any(|(kind, _)| kind == "shape"));
+    drop(entries);
+
+    // The second world's store is another handle to the same entries. Work
+    // memoized with the first store isn't reused with it, so the second
+    // compilation has to go through the store. It produces the same output.
+    let world = ApiWorld::new(text).with_store(store.clone());
+    let second = typst_render::render(&compile(&world).pages[0], 1.0, Color::WHITE);
+    let hits = store.hits.lock().unwrap().clone();
+    assert!(hits.contains(&image_key));
+    assert!(hits.iter().any(|(kind, _)| kind == "shape"));
+    assert_eq!(first.data(), second.data());
+}
+
+#[test]
+fn test_pdf_fonts_are_subset_independently() {
+    let world = ApiWorld::new(
+        "#text(font: \"Linux Libertine\")[Serif]\n\
+         #text(font: \"DejaVu Sans Mono\")[mono]",
+    );
+    let pdf = typst_pdf::pdf(&compile(&world), None, None);
+    let streams = pdf_stream_data(&pdf);
+    let subsets: Vec<_> = streams
+        .iter()
+        .filter_map(|data| ttf_parser::Face::parse(data, 0).ok())
+        .collect();
+    assert_eq!(subsets.len(), 2);
+
+    // Each font is embedded with the glyphs of its own text, no matter in
+    // which order the subsets were computed.
+    for (family, used, unused) in
+        [("Linux Libertine", 'S', 'm'), ("DejaVu Sans Mono", 'm', 'S')]
+    {
+        let font = fonts()
+            .1
+            .iter()
+            .find(|font| {
+                font.info().family == family
+                    && font.info().variant == FontVariant::default()
+            })
+            .unwrap()
+            .ttf();
+        let glyph = |c| font.glyph_index(c).unwrap();
+        let subset = subsets
+            .iter()
+            .find(|subset| subset.number_of_glyphs() == font.number_of_glyphs())
+            .unwrap();
+        assert!(subset.glyph_bounding_box(glyph(used)).is_some(), "{family}");
+        assert!(subset.glyph_bounding_box(glyph(unused)).is_none(), "{family}");
+    }
+}
+
+#[test]
+fn test_rendered_glyphs_are_reused_at_whole_pixel_offsets() {
+    let render = |x: f64| {
+        let world = ApiWorld::new(&format!(
+            "#set page(width: 60pt, height: 30pt, margin: 0pt)\n\
+             #place(dx: {x}pt, dy: 2.5pt, text(20pt)[A])"
+        ));
+        typst_render::render(&compile(&world).pages[0], 1.0, Color::WHITE)
+    };
+
+    // Both glyphs are at the same subpixel offset, so they share their
+    // rasterization, which is drawn shifted by exactly 20 pixels.
+    let (a, b) = (render(10.25), render(30.25));
+    assert_ne!(a.data(), b.data());
+    for y in 0..30 {
+        for x in 0..40 {
+            assert_eq!(a.pixel(x, y), b.pixel(x + 20, y), "{x}, {y}");
+        }
+    }
+}
+
+#[test]
+fn test_pdf_images_are_downsampled_and_recompressed() {
+    // The 1024x670 pixel image is placed two inches wide.
+    let world = ApiWorld::new(
+        "#set page(width: 3in, height: 3in, margin: 0pt)\n\
+         #image(\"/files/tiger.jpg\", width: 2in)",
+    );
+    let document = compile(&world);
+    let export = |max_ppi, jpeg_quality| {
+        let options = ImageOptions { max_ppi, jpeg_quality };
+        let mut exporter = PdfExporter::new().with_image_options(options);
+        exporter.push_page(&document.pages[0]);
+        exporter.finish(&document.info, Some("images"), None).unwrap()
+    };
+    let width = |pdf: &[u8]| {
+        let rest = &pdf[find(pdf, b"/Width ").unwrap() + 7..];
+        let end = rest.iter().position(|b| !b.is_ascii_digit()).unwrap();
+        std::str::from_utf8(&rest[..end]).unwrap().parse::<u32>().unwrap()
+    };
+
+    // Images are only downsampled if their resolution exceeds the limit.
+    assert_eq!(width(&export(None, None)), 1024);
+    assert_eq!(width(&export(Some(1000.0), None)), 1024);
+    assert!((200..=201).contains(&width(&export(Some(100.0), None))));
+
+    // A lower quality yields a smaller image.
+    assert!(export(None, Some(10)).len() < export(None, Some(95)).len());
+}
+
+#[test]
+fn test_pdf_exporter_matches_whole_document_export() {
+    let world = ApiWorld::new(
+        "#set document(title: \"Streamed\", author: \"Jane\", date: none)\n\
+         First page\n\
+         #pagebreak()\n\
+         Second page",
+    );
+    let document = compile(&world);
+    assert_eq!(document.pages.len(), 2);
+
+    // Pushing the pages one by one and dropping them afterwards produces
+    // the same file as exporting the whole document.
+    let mut exporter = PdfExporter::new();
+    for frame in document.pages.clone() {
+        exporter.push_page(&frame);
+    }
+    let streamed = exporter.finish(&document.info, Some("streamed"), None).unwrap();
+    assert_eq!(streamed, typst_pdf::pdf(&document, Some("streamed"), None));
+
+    // The metadata comes from the info, not from the pushed pages.
+    let text = String::from_utf8_lossy(&streamed);
+    assert!(text.contains("/Title (Streamed)"));
+    assert!(text.contains("/Author (Jane)"));
+    assert!(text.contains("/Count 2"));
+}
+
+#[test]
+fn test_pdf_exporter_without_pages_or_metadata() {
+    let document = compile(&ApiWorld::new("Only page"));
+
+    // Metadata can be supplied independently of any compiled document.
+    let info = DocumentInfo {
+        title: Some("Detached".into()),
+        ..Default::default()
+    };
+    let mut exporter = PdfExporter::new();
+    exporter.push_page(&document.pages[0]);
+    let pdf = exporter.finish(&info, None, None).unwrap();
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("/Title (Detached)"));
+    assert!(text.contains("/DisplayDocTitle true"));
+
+    // An exporter without pages still produces a file with an empty page tree.
+    let empty = PdfExporter::new()
+        .finish(&DocumentInfo::default(), None, None)
+        .unwrap();
+    assert!(String::from_utf8_lossy(&empty).contains("/Count 0"));
+}
+
+/// Compile the main file of a world with tags and export it as PDF/UA.
+fn tagged_pdf(world: &ApiWorld) -> StrResult<String> {
+    let mut tracer = Tracer::new();
+    tracer.record_tags();
+    let document = typst::compile(world, &mut tracer).expect("compilation failed");
+    let mut exporter = PdfExporter::new().with_standard(PdfStandard::Ua1);
+    for frame in &document.pages {
+        exporter.push_page(frame);
+    }
+    let pdf = exporter.finish(&document.info, None, None)?;
+    Ok(String::from_utf8_lossy(&pdf).into_owned())
+}
+
+/// Whether a frame contains tagged groups.
+fn has_tags(frame: &Frame) -> bool {
+    frame.items().any(|(_, item)| match item {
+        FrameItem::Group(group) => group.tag.is_some() || has_tags(&group.frame),
+        _ => false,
+    })
+}
+
+#[test]
+fn test_frames_are_only_tagged_on_request() {
+    let world = ApiWorld::new("= Intro\nHello");
+    assert!(!has_tags(&compile(&world).pages[0]));
+
+    let mut tracer = Tracer::new();
+    tracer.record_tags();
+    let document = typst::compile(&world, &mut tracer).expect("compilation failed");
+    assert!(has_tags(&document.pages[0]));
+
+    // Without PDF/UA, the file has no structure tree, even for tagged frames.
+    let pdf = typst_pdf::pdf(&document, None, None);
+    assert!(!String::from_utf8_lossy(&pdf).contains("/StructTreeRoot"));
+}
+
+#[test]
+fn test_pdf_ua_structure_tree() {
+    let world = ApiWorld::new(
+        "#set document(title: \"Tagged\")\n\
+         = Intro\n\
+         Hello #link(\"https://example.com\")[there].",
+    );
+    let pdf = tagged_pdf(&world).unwrap();
+
+    // The structure elements in the order they are written.
+    let roles: Vec<&str> = pdf
+        .split("/Type /StructElem")
+        .skip(1)
+        .filter_map(|elem| elem.split("/S /").nth(1)?.split_whitespace().next())
+        .collect();
+    assert_eq!(roles, ["H1", "P", "Link"]);
+
+    // The link annotation is referenced from its structure element and
+    // the file declares its conformance.
+    assert!(pdf.contains("/Type /OBJR"));
+    assert!(pdf.contains("/StructParent 1"));
+    assert!(pdf.contains("/Tabs /S"));
+    assert!(pdf.contains("<pdfuaid:part>1</pdfuaid:part>"));
+    assert!(pdf.contains("/Marked true"));
+}
+
+#[test]
+fn test_pdf_ua_validation() {
+    let error = |world: ApiWorld| tagged_pdf(&world).unwrap_err();
+    assert_eq!(
+        error(ApiWorld::new("Hello")),
+        "PDF/UA-1 requires the document to have a title",
+    );
+    assert_eq!(
+        error(ApiWorld::new(
+            "#set document(title: \"Image\")\n#image(\"/files/tiger.jpg\", width: 10pt)"
+        )),
+        "PDF/UA-1 requires all images to have an alternative description",
+    );
+
+    // Frames compiled without tags can't be exported as PDF/UA.
+    let world = ApiWorld::new("#set document(title: \"Untagged\")\nHello");
+    let document = compile(&world);
+    let mut exporter = PdfExporter::new().with_standard(PdfStandard::Ua1);
+    exporter.push_page(&document.pages[0]);
+    assert_eq!(
+        exporter.finish(&document.info, None, None).unwrap_err(),
+        "PDF/UA-1 requires a tagged document, but the document was compiled without recording tags",
+    );
+}
+
+/// Export a document as PDF/A-2b.
+fn pdfa(document: &Document) -> StrResult<Vec<u8>> {
+    let mut exporter = PdfExporter::new().with_standard(PdfStandard::A2b);
+    for frame in &document.pages {
+        exporter.push_page(frame);
+    }
+    exporter.finish(&document.info, None, None)
+}
+
+/// The decompressed streams of a PDF file that hold text.
+fn pdf_streams(pdf: &[u8]) -> Vec<String> {
+    pdf_stream_data(pdf)
+        .iter()
+        .map(|data| String::from_utf8_lossy(data).into_owned())
+        .collect()
+}
+
+/// The decompressed data of all compressed streams of a PDF file.
+fn pdf_stream_data(pdf: &[u8]) -> Vec<Vec<u8>> {
+    let mut streams = vec![];
+    let mut rest = pdf;
+    while let Some(start) = find(rest, b"stream\n") {
+        rest = &rest[start + 7..];
+        let Some(end) = find(rest, b"\nendstream") else { break };
+        if let Ok(data) = miniz_oxide::inflate::decompress_to_vec_zlib(&rest[..end]) {
+            streams.push(data);
+        }
+        rest = &rest[end..];
+    }
+    streams
+}
+
+/// The position of a byte string in another one.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[test]
+fn test_pdfa_converts_cmyk_colors_to_srgb() {
+    let document = compile(&ApiWorld::new(
+        "#rect(fill: cmyk(10%, 20%, 30%, 40%), stroke: cmyk(0%, 0%, 0%, 100%))",
+    ));
+    let uses_cmyk = |pdf: &[u8]| {
+        pdf_streams(pdf).iter().any(|stream| {
+            stream
+                .lines()
+                .any(|line| line.ends_with(" k") || line.ends_with(" K"))
+        })
+    };
+
+    assert!(uses_cmyk(&typst_pdf::pdf(&document, None, None)));
+    let pdf = pdfa(&document).unwrap();
+    assert!(!uses_cmyk(&pdf));
+    assert!(pdf_streams(&pdf).iter().any(|stream| stream.contains("/srgb cs")));
+}
+
+#[test]
+fn test_pdfa_validation() {
+    let error = |text: &str| pdfa(&compile(&ApiWorld::new(text))).unwrap_err();
+    assert_eq!(
+        error("#rect(fill: gradient.linear(red, blue, space: cmyk))"),
+        "PDF/A-2b forbids gradients in the CMYK color space, as it only allows sRGB colors",
+    );
+    assert_eq!(
+        error("#set text(font: \"PT Sans\", fallback: false)\n\u{1200}"),
+        "PDF/A-2b forbids the `.notdef` glyph, but font `PT Sans` is missing some characters of the text",
+    );
+
+    // Gradients in other color spaces are fine.
+    let document = compile(&ApiWorld::new("#rect(fill: gradient.linear(red, blue))"));
+    assert!(pdfa(&document).is_ok());
+}
+
+#[test]
+fn test_origins_from_click_are_recorded_on_request() {
+    let world = ApiWorld::new(
+        "#set page(width: 100pt, height: 100pt, margin: 0pt)\n\
+         #show <x>: it => scale(x: 300%, origin: left + top, rect(width: 10pt))\n\
+         #[Target] <x>",
+    );
+    let click = |x: f64| Point::new(Abs::pt(x), Abs::pt(5.0));
+
+    // Without a request, the frames don't carry origins.
+    let document = compile(&world);
+    assert!(origins_from_click(&world, &document.pages[0], click(5.0)).is_empty());
+
+    let mut tracer = Tracer::new();
+    tracer.record_origins();
+    let document = typst::compile(&world, &mut tracer).expect("compilation failed");
+    let frame = &document.pages[0];
+
+    // The rectangle is scaled to thrice its width, so a click beyond its
+    // untransformed width still hits it, but one beyond the scaled width
+    // doesn't.
+    let origins = origins_from_click(&world, frame, click(25.0));
+    let [Origin {
+        call: Some(Jump::Source(_, call)),
+        rule: Some(Jump::Source(_, rule)),
+    }] = origins.as_slice()
+    else {
+        panic!("expected a single origin, found {origins:?}");
+    };
+    let text = world.main().text().to_string();
+    assert!(text[*call..].starts_with("[Target]"));
+    assert!(text[*rule..].starts_with("it => scale"));
+    assert!(origins_from_click(&world, frame, click(35.0)).is_empty());
+}
+
+#[test]
+fn test_world_is_told_about_missing_resources() {
+    // A family that doesn't exist and one that lacks the requested weight.
+    let world = ApiWorld::new(
+        "#text(font: \"Nonexistent Sans\")[A]\n\
+         #text(font: \"Linux Libertine\", weight: \"black\")[B]\n\
+         #text(font: \"Linux Libertine\")[C]",
+    );
+    compile(&world);
+    let fonts: Vec<_> = world
+        .missing
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|lookup| match lookup {
+            Lookup::Font { family, similar, .. } => Some((family.clone(), *similar)),
+            _ => None,
+        })
+        .collect();
+    let expected = [("nonexistent sans".into(), false), ("linux libertine".into(), true)];
+    assert!(expected.iter().all(|font| fonts.contains(font)));
+    assert!(fonts.iter().all(|font| expected.contains(font)), "{fonts:?}");
+
+    // A missing file is reported and still results in an error.
+    let world = ApiWorld::new("#include \"chapter.typ\"");
+    let mut tracer = Tracer::new();
+    assert!(typst::compile(&world, &mut tracer).is_err());
+    let missing = world.missing.lock().unwrap();
+    assert!(missing.iter().any(|lookup| matches!(
+        lookup,
+        Lookup::File { id, error: FileError::NotFound(_) } if *id == self::id("chapter.typ")
+    )));
+}
+
+#[test]
+fn test_introspector_update_reports_changes() {
+    let first = compile(&ApiWorld::new("= A <a>\n#pagebreak()\n= B <b>"));
+    let second = compile(&ApiWorld::new("= A <a>\n#pagebreak()\n= C <c>"));
+    let mut introspector = Introspector::new(&first.pages);
+
+    // The same pages leave the introspector as it is.
+    assert!(!introspector.update(&first.pages));
+    assert_eq!(introspector.query(&Selector::Label(Label::new("b"))).len(), 1);
+
+    // A changed page is extracted again and queries see the new version.
+    assert!(introspector.update(&second.pages));
+    assert!(introspector.query(&Selector::Label(Label::new("b"))).is_empty());
+    assert_eq!(introspector.query(&Selector::Label(Label::new("c"))).len(), 1);
+    assert_eq!(introspector.query(&Selector::Label(Label::new("a"))).len(), 1);
+
+    // Dropping a page is a change, too.
+    assert!(introspector.update(&second.pages[..1]));
+    assert!(introspector.query(&Selector::Label(Label::new("c"))).is_empty());
+}
+
+#[test]
+fn test_incremental_compilation_matches_full_compilation() {
+    let text = "#set heading(numbering: \"1.\")\n\
+                = Intro <intro>\n\
+                See @outro on page #locate(loc => query(<outro>, loc).first().location().page()).\n\
+                #pagebreak()\n\
+                = Outro <outro>\n\
+                Back to @intro.\n";
+    let mut world = ApiWorld::new(text);
+    let cache = CompilationCache::new();
+
+    // Each edit is checked against a compilation from scratch. The edits
+    // change counters, references, and the number of pages.
+    let edits: &[(&str, &str)] = &[
+        ("= Intro", "= Prelude\n= Intro"),
+        ("#pagebreak()", "#pagebreak()\n#pagebreak()"),
+        ("Back to", "Way back to"),
+        ("#pagebreak()\n#pagebreak()", ""),
+    ];
+
+    let check = |world: &ApiWorld| {
+        let mut tracer = Tracer::new();
+        let incremental = typst::compile_incremental(world, &mut tracer, &cache)
+            .expect("incremental compilation failed");
+        let full = compile(world);
+        assert_eq!(incremental.pages.len(), full.pages.len());
+        assert_eq!(hash128(&incremental.pages), hash128(&full.pages));
+    };
+
+    check(&world);
+    for &(find, replace) in edits {
+        let text = world.main().text().to_string();
+        let start = text.find(find).unwrap();
+        world.edit(start..start + find.len(), replace);
+        check(&world);
+    }
+}
+
+#[test]
+fn test_incremental_compilation_with_cache_of_other_document() {
+    let cache = CompilationCache::new();
+    let mut tracer = Tracer::new();
+    let first = ApiWorld::new("= A\n#pagebreak()\n= B\n#counter(heading).display()");
+    let second = ApiWorld::new("#counter(heading).display()\n= C");
+    typst::compile_incremental(&first, &mut tracer, &cache).unwrap();
+    let incremental = typst::compile_incremental(&second, &mut tracer, &cache).unwrap();
+    assert_eq!(hash128(&incremental.pages), hash128(&compile(&second).pages));
+
+    // A cleared cache behaves like a fresh one.
+    cache.clear();
+    let incremental = typst::compile_incremental(&first, &mut tracer, &cache).unwrap();
+    assert_eq!(hash128(&incremental.pages), hash128(&compile(&first).pages));
+}
+
+#[test]
+fn test_streaming_compilation_emits_changed_pages_per_iteration() {
+    let world = ApiWorld::new(
+        "Of #locate(loc => counter(page).final(loc).first()) pages\n\
+         #pagebreak()\n\
+         B\n\
+         #pagebreak()\n\
+         C\n",
+    );
+    let mut emitted = vec![];
+    let mut tracer = Tracer::new();
+    let document = typst::compile_streaming(&world, &mut tracer, &mut |i, page| {
+        emitted.push((i, text(page)));
+    })
+    .unwrap();
+
+    // All pages are emitted in the first iteration. Afterwards, only the first
+    // page, which depends on the final page count, changes.
+    assert_eq!(
+        emitted,
+        [
+            (0, "Of 1 pages".to_string()),
+            (1, "B".to_string()),
+            (2, "C".to_string()),
+            (0, "Of 3 pages".to_string()),
+        ]
+    );
+    assert_eq!(hash128(&document.pages), hash128(&compile(&world).pages));
+}
+
+#[test]
+fn test_streaming_compilation_emits_finished_pages_eagerly() {
+    // The second run of pages fails to layout. The first one was already
+    // finished at that point and is emitted nonetheless.
+    let world = ApiWorld::new(
+        "A\n\
+         #set page(width: 100pt)\n\
+         #place(float: true, horizon)[B]\n",
+    );
+    let mut emitted = vec![];
+    let mut tracer = Tracer::new();
+    let result = typst::compile_streaming(&world, &mut tracer, &mut |i, page| {
+        emitted.push((i, text(page)));
+    });
+    assert!(result.is_err());
+    assert_eq!(emitted, [(0, "A".to_string())]);
+}
+
+#[test]
+fn test_compile_variants_see_their_inputs() {
+    let text = "Task\n\
+                #if sys.inputs.at(\"solutions\", default: \"no\") == \"yes\" [with solution]\n\
+                #sys.inputs.at(\"lang\", default: \"en\")\n";
+    let world = ApiWorld::new(text);
+    let variants = [
+        dict! {},
+        dict! { "solutions" => "yes" },
+        dict! { "solutions" => "yes", "lang" => "de" },
+    ];
+
+    let mut tracer = Tracer::new();
+    let documents = typst::compile_variants(&world, &mut tracer, &variants).unwrap();
+    let texts: Vec<_> = documents.iter().map(|doc| text(&doc.pages[0])).collect();
+    assert_eq!(texts, ["Task en", "Task with solution en", "Task with solution de"]);
+
+    // Each variant is the same as a separate compilation with its inputs.
+    for (document, inputs) in documents.iter().zip(variants) {
+        let single = compile(&ApiWorld::new(text).with_inputs(inputs));
+        assert_eq!(hash128(&document.pages), hash128(&single.pages));
+    }
+}
+
+#[test]
+fn test_compile_variants_fail_with_first_failing_variant() {
+    let world = ApiWorld::new(
+        "#let fail = sys.inputs.at(\"fail\", default: none)\n\
+         #if fail != none { panic(fail) }\n",
+    );
+    let variants = [dict! {}, dict! { "fail" => "b" }, dict! { "fail" => "c" }];
+    let mut tracer = Tracer::new();
+    let errors = typst::compile_variants(&world, &mut tracer, &variants).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "panicked with: \"b\"");
+    assert!(typst::compile_variants(&world, &mut tracer, &variants[..1]).is_ok());
+}
+
+/// Export a world into HTML, returning the page and the warnings.
+fn html(world: &ApiWorld) -> (String, Vec<EcoString>) {
+    let document = compile(world);
+    let mut tracer = Tracer::new();
+    let html = typst_html::html(world, &document, &mut tracer).expect("export failed");
+    let warnings = tracer.warnings().into_iter().map(|w| w.message).collect();
+    (html, warnings)
+}
+
+#[test]
+fn test_html_uses_introspections_of_document() {
+    let (html, _) = html(&ApiWorld::new(
+        "#set heading(numbering: \"1\")\n\
+         = Intro\n\
+         = Method <method>\n\
+         See @method on page #locate(loc => loc.page()), \
+         chapter #counter(heading).display().",
+    ));
+    assert!(html.contains("<h1 id=\"method\">Method</h1>"));
+    assert!(html.contains("See Section\u{a0}2 on page 1, chapter 2."), "{html}");
+}
+
+#[test]
+fn test_html_applies_show_rules() {
+    let (html, _) = html(&ApiWorld::new(
+        "#show heading: it => [Chapter: #it.body]\n\
+         #show \"cat\": \"dog\"\n\
+         = Intro\n\n\
+         The cat sleeps. #box[boxed] #emph[a cat]",
+    ));
+    assert!(!html.contains("<h1"));
+    assert!(html.contains("<p>Chapter: Intro</p>"), "{html}");
+    assert!(html.contains("<p>The dog sleeps. boxed <em>a dog</em></p>"), "{html}");
+}
+
+#[test]
+fn test_html_reports_warnings() {
+    let (_, warnings) = html(&ApiWorld::new("Stars: **"));
+    assert!(warnings.iter().any(|message| message == "no text within stars"));
+}
+
+#[test]
+fn test_html_places_spanning_table_cells() {
+    let (html, _) = html(&ApiWorld::new(
+        "#table(\n\
+           columns: 3,\n\
+           table.cell(rowspan: 2)[R], table.cell(colspan: 2)[S],\n\
+           [a], [b],\n\
+           [c], table.cell(colspan: 2, rowspan: 2)[D],\n\
+           [e],\n\
+         )",
+    ));
+    let expected = "<table>\n\
+                    <tr><td rowspan=\"2\">R</td><td colspan=\"2\">S</td></tr>\n\
+                    <tr><td>a</td><td>b</td></tr>\n\
+                    <tr><td>c</td><td colspan=\"2\" rowspan=\"2\">D</td></tr>\n\
+                    <tr><td>e</td></tr>\n\
+                    </table>";
+    assert!(html.contains(expected), "{html}");
+}
+
+/// Export a world into an EPUB publication, returning its files in order.
+fn epub(world: &ApiWorld) -> (Document, Vec<(String, CompressionMethod, Vec<u8>)>) {
+    let document = compile(world);
+    let mut tracer = Tracer::new();
+    let data =
+        typst_html::epub(world, &document, &mut tracer, None).expect("export failed");
+    let mut archive = ZipArchive::new(Cursor::new(data)).unwrap();
+    let files = (0..archive.len())
+        .map(|i| {
+            let mut file = archive.by_index(i).unwrap();
+            let mut data = vec![];
+            file.read_to_end(&mut data).unwrap();
+            (file.name().to_string(), file.compression(), data)
+        })
+        .collect();
+    (document, files)
+}
+
+#[test]
+fn test_epub_splits_chapters_and_links_between_them() {
+    let (document, files) = epub(&ApiWorld::new(
+        "#set document(title: \"Book\")\n\
+         #set heading(numbering: \"1.\")\n\
+         = Intro <intro>\n\
+         See #link(<method>)[the method].\n\
+         = Method <method>\n\
+         Back to #link(<intro>)[the intro].\n\
+         == Details\n",
+    ));
+    let file = |name: &str| {
+        let (_, _, data) = files.iter().find(|(path, _, _)| path == name).unwrap();
+        String::from_utf8_lossy(data).into_owned()
+    };
+
+    // The mimetype comes first and is stored uncompressed.
+    let (name, compression, data) = &files[0];
+    assert_eq!(name, "mimetype");
+    assert_eq!(*compression, CompressionMethod::Stored);
+    assert_eq!(data, b"application/epub+zip");
+
+    // Each top-level heading starts a chapter and links to a label in another
+    // chapter point to that chapter's file.
+    let first = file("EPUB/chapter-1.xhtml");
+    let second = file("EPUB/chapter-2.xhtml");
+    assert!(first.contains("<h1 id=\"intro\">1. Intro</h1>"), "{first}");
+    assert!(first.contains("<a href=\"chapter-2.xhtml#method\">the method</a>"));
+    assert!(!first.contains("Details"));
+    assert!(second.contains("<h1 id=\"method\">2. Method</h1>"), "{second}");
+    assert!(second.contains("<a href=\"chapter-1.xhtml#intro\">the intro</a>"));
+    assert!(second.contains("2.1. Details"));
+    assert!(!files.iter().any(|(path, _, _)| path == "EPUB/chapter-3.xhtml"));
+
+    // The navigation nests the numbered headings.
+    let nav = file("EPUB/nav.xhtml");
+    assert!(nav.contains("<li><a href=\"chapter-1.xhtml#intro\">1. Intro</a></li>"));
+    assert!(nav.contains("<li><a href=\"chapter-2.xhtml#method\">2. Method</a>\n<ol>"));
+    assert!(nav.contains("2.1. Details</a></li>\n</ol>\n</li>"), "{nav}");
+    assert!(file("EPUB/content.opf").contains("properties=\"nav\""));
+
+    // The fonts are subsetted.
+    fn font(frame: &Frame) -> Option<Font> {
+        frame.items().find_map(|(_, item)| match item {
+            FrameItem::Group(group) => font(&group.frame),
+            FrameItem::Text(text) => Some(text.font.clone()),
+            _ => None,
+        })
+    }
+    let font = font(&document.pages[0]).unwrap();
+    let (_, _, embedded) = files
+        .iter()
+        .find(|(path, _, _)| path.starts_with("EPUB/fonts/1."))
+        .unwrap();
+    assert!(embedded.len() < font.data().len());
+}
+
+/// Compile a world and return the warnings about unknown fonts.
+fn font_warnings(world: &ApiWorld) -> Vec<SourceDiagnostic> {
+    let mut tracer = Tracer::new();
+    typst::compile(world, &mut tracer).expect("compilation failed");
+    tracer
+        .warnings()
+        .into_iter()
+        .filter(|warning| warning.message.starts_with("unknown font family"))
+        .collect()
+}
+
+#[test]
+fn test_frequent_warnings_are_grouped() {
+    // Twelve places with the same problem are merged into the first one,
+    // which keeps three further spans. The other problem stays separate.
+    let text = "#text(font: \"Nonexistent\")[A]\n".repeat(12)
+        + &"#text(font: \"Missing\")[B]\n".repeat(2);
+    let world = ApiWorld::new(&text);
+    let warnings = font_warnings(&world);
+    assert_eq!(warnings.len(), 3);
+    let group = &warnings[0];
+    assert_eq!(group.message, "unknown font family: nonexistent");
+    assert_eq!(group.count, 12);
+    let lines: Vec<usize> = std::iter::once(group.span)
+        .chain(group.repeats.iter().copied())
+        .map(|span| {
+            let source = world.main();
+            source.byte_to_line(source.range(span).unwrap().start).unwrap()
+        })
+        .collect();
+    assert_eq!(lines, [0, 1, 2, 3]);
+    for warning in &warnings[1..] {
+        assert_eq!(warning.message, "unknown font family: missing");
+        assert_eq!(warning.count, 1);
+        assert!(warning.repeats.is_empty());
+    }
+
+    // Below the threshold, diagnostics aren't grouped.
+    let world = ApiWorld::new(&"#text(font: \"Nonexistent\")[A]\n".repeat(9));
+    let warnings = font_warnings(&world);
+    assert_eq!(warnings.len(), 9);
+    assert!(warnings.iter().all(|warning| warning.count == 1));
+
+    // The same problem in the same place counts just once.
+    let world = ApiWorld::new("#for i in range(12) [#text(font: \"Nonexistent\")[#i]]");
+    let warnings = font_warnings(&world);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].count, 1);
+    assert!(warnings[0].repeats.is_empty());
+}
+
+/// Run a query against a world.
+fn query(world: &ApiWorld, selector: &str, field: Option<&str>) -> Vec<Value> {
+    let mut tracer = Tracer::new();
+    typst::query(world, &mut tracer, selector, field).expect("query failed")
+}
+
+#[test]
+fn test_query_returns_elements_and_fields() {
+    let world = ApiWorld::new(
+        "= Intro <intro>\n\
+         == Details\n\
+         #figure(table[A], caption: [Numbers]) <numbers>\n\
+         #metadata((version: 2)) <meta>",
+    );
+
+    let headings = query(&world, "heading", None);
+    let titles: Vec<_> = headings
+        .iter()
+        .map(|value| match value {
+            Value::Content(content) => content.plain_text(),
+            _ => panic!("expected content"),
+        })
+        .collect();
+    assert_eq!(titles, ["Intro", "Details"]);
+
+    assert_eq!(query(&world, "heading", Some("level")), [Value::Int(1), Value::Int(2)]);
+    assert_eq!(query(&world, "heading.where(level: 2)", Some("level")), [Value::Int(2)]);
+    assert_eq!(query(&world, "<intro>", Some("level")), [Value::Int(1)]);
+    assert_eq!(query(&world, "figure.where(kind: table)", None).len(), 1);
+
+    // Elements without the field are skipped.
+    assert_eq!(query(&world, "selector(heading).or(<meta>)", Some("level")).len(), 2);
+
+    let values = query(&world, "<meta>", Some("value"));
+    assert_eq!(values.len(), 1);
+    let Value::Dict(dict) = &values[0] else { panic!("expected dictionary") };
+    assert_eq!(dict.get("version"), Ok(&Value::Int(2)));
+}
+
+#[test]
+fn test_query_fails_for_invalid_selectors() {
+    let world = ApiWorld::new("= Intro");
+    let mut tracer = Tracer::new();
+    let errors = typst::query(&world, &mut tracer, "regex(\"a\")", None).unwrap_err();
+    assert_eq!(errors[0].message, "text is not locatable");
+
+    let errors = typst::query(&world, &mut tracer, "heading(", None).unwrap_err();
+    assert!(!errors.is_empty());
+
+    // Compilation errors are reported as such.
+    let world = ApiWorld::new("#panic()");
+    assert!(typst::query(&world, &mut tracer, "heading", None).is_err());
+}
+
+#[test]
+fn test_audit_reports_label_problems_with_spans() {
+    let world = ApiWorld::new(
+        "#show ref: it => \"@\" + str(it.target)\n\
+         = Intro <intro>\n\
+         = Again <intro>\n\
+         See @intro and @missing.",
+    );
+    let text = world.main().text().to_string();
+    let spanned = |diags: EcoVec<SourceDiagnostic>| {
+        diags
+            .into_iter()
+            .map(|diag| {
+                assert_eq!(diag.severity, Severity::Error);
+                (diag.message, &text[world.range(diag.span).unwrap()])
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // The show rule deliberately replaces the references, so they aren't
+    // reported by default.
+    let mut tracer = Tracer::new();
+    let document = typst::compile(&world, &mut tracer).unwrap();
+    assert!(tracer.warnings().is_empty());
+
+    // The second heading with the label is the duplicate.
+    let diags = spanned(typst::audit(&document));
+    let [(duplicate, heading)] = diags.as_slice() else {
+        panic!("expected one diagnostic, found {diags:?}");
+    };
+    assert_eq!(duplicate, "label `<intro>` occurs multiple times in the document");
+    assert!(heading.starts_with("= Again"));
+
+    // A strict build reports the references that the show rule replaced.
+    let mut tracer = Tracer::new();
+    tracer.promote(WarningCategory::UnresolvedReference);
+    let diags = spanned(typst::compile(&world, &mut tracer).unwrap_err());
+    assert_eq!(
+        diags,
+        [
+            ("label `<intro>` occurs multiple times in the document".into(), "@intro"),
+            ("label `<missing>` does not exist in the document".into(), "@missing"),
+        ]
+    );
+
+    // A document whose references resolve passes both checks.
+    let world =
+        ApiWorld::new("#set heading(numbering: \"1.\")\n= Intro <intro>\nSee @intro.");
+    let mut tracer = Tracer::new();
+    tracer.promote(WarningCategory::UnresolvedReference);
+    assert!(typst::audit(&typst::compile(&world, &mut tracer).unwrap()).is_empty());
+}
+
+/// The text of all text items in a frame, in order.
+fn text(frame: &Frame) -> String {
+    let mut text = String::new();
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => text.push_str(&self::text(&group.frame)),
+            FrameItem::Text(item) => text.push_str(&item.text),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// A citation style that identifies entries by the length of their key.
+///
+/// Citations show the number of the entry, a letter for disambiguation, and
+/// markers for first citations (`*`) and repeated ones (`~`).
+struct KeyLengthStyle;
+
+impl CitationStyle for KeyLengthStyle {
+    fn sort(&self, entries: &mut [&hayagriva::Entry]) {
+        entries.sort_by_key(|entry| entry.key().to_string());
+    }
+
+    fn label(&self, entry: &hayagriva::Entry) -> Option<EcoString> {
+        Some(entry.key().len().to_string().into())
+    }
+
+    fn citation(&self, items: &[CitationData]) -> Content {
+        let text: Vec<String> = items
+            .iter()
+            .map(|item| {
+                let letter = item.disambiguator.map(|i| (b'a' + i as u8) as char);
+                format!(
+                    "{}{}{}{}",
+                    item.number,
+                    letter.map(String::from).unwrap_or_default(),
+                    if item.first { "*" } else { "" },
+                    if item.ibid { "~" } else { "" },
+                )
+            })
+            .collect();
+        TextElem::packed(text.join(","))
+    }
+
+    fn reference(&self, data: &ReferenceData) -> (Option<Content>, Content) {
+        let prefix = TextElem::packed(format!("[{}]", data.number));
+        (Some(prefix), TextElem::packed(data.entry.key()))
+    }
+}
+
+#[test]
+fn test_custom_citation_style_sorts_disambiguates_and_renders() {
+    let world = ApiWorld::new(
+        "A @quark. B @netwok. C @arrgh @quark. D @quark.\n\
+         #bibliography(\"/files/works.bib\", style: \"key-length\", title: none)",
+    )
+    .with_style("key-length", KeyLengthStyle);
+    let text = text(&compile(&world).pages[0]);
+
+    // The entries are sorted by key. `arrgh` and `quark` have the same label
+    // and are thus disambiguated.
+    assert!(text.contains("A 3b*. B 2*. C 1a*,3b. D 3b~."), "{text}");
+    assert!(text.contains("[1]arrgh[2]netwok[3]quark"), "{text}");
+}
+
+#[test]
+fn test_custom_citation_style_applies_to_whole_document() {
+    let world = ApiWorld::new(
+        "#cite(<quark>, style: \"apa\")\n\
+         #bibliography(\"/files/works.bib\", style: \"key-length\")",
+    )
+    .with_style("key-length", KeyLengthStyle);
+    let errors = typst::compile(&world, &mut Tracer::new()).unwrap_err();
+    assert_eq!(
+        errors[0].message,
+        "cannot change the style of a citation when the bibliography uses a custom style",
+    );
+
+    // Without the world providing it, the style is unknown.
+    let world =
+        ApiWorld::new("#bibliography(\"/files/works.bib\", style: \"key-length\")");
+    let errors = typst::compile(&world, &mut Tracer::new()).unwrap_err();
+    assert_eq!(errors[0].message, "unknown style: `key-length`");
+}
+
+/// An asynchronous world that serves the files of an API world and records
+/// which files it was asked for.
+struct AsyncApiWorld {
+    world: ApiWorld,
+    loads: Mutex<Vec<FileId>>,
+}
+
+impl AsyncApiWorld {
+    fn new(world: ApiWorld) -> Self {
+        Self { world, loads: Mutex::new(vec![]) }
+    }
+
+    /// The files that were loaded, in order.
+    fn loads(&self) -> Vec<FileId> {
+        self.loads.lock().unwrap().clone()
+    }
+}
+
+impl AsyncWorld for AsyncApiWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        self.world.library()
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        self.world.book()
+    }
+
+    fn main(&self) -> Source {
+        self.world.main()
+    }
+
+    fn source(&self, id: FileId) -> BoxFuture<'_, FileResult<Source>> {
+        self.loads.lock().unwrap().push(id);
+        Box::pin(std::future::ready(self.world.source(id)))
+    }
+
+    fn file(&self, id: FileId) -> BoxFuture<'_, FileResult<Bytes>> {
+        self.loads.lock().unwrap().push(id);
+        Box::pin(std::future::ready(self.world.file(id)))
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.world.font(index)
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        self.world.today(offset)
+    }
+}
+
+/// Run a future to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn test_compile_async_loads_files_in_rounds() {
+    let world = ApiWorld::new(
+        "#import \"other.typ\": tiger\n\
+         #set text(font: \"Nonexistent\")\n\
+         #tiger",
+    )
+    .with("other.typ", "#let tiger = image(\"/files/tiger.jpg\", width: 10pt)");
+    let expected = hash128(&compile(&world).pages);
+
+    // The image is only requested once the module that uses it was loaded,
+    // and each file is loaded just once.
+    let world = AsyncApiWorld::new(world);
+    let mut tracer = Tracer::new();
+    let document = block_on(typst::compile_async(&world, &mut tracer)).unwrap();
+    assert_eq!(hash128(&document.pages), expected);
+    assert_eq!(world.loads(), [id("other.typ"), id("files/tiger.jpg")]);
+
+    // Only the warnings of the last attempt are kept.
+    let warnings = tracer.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].message, "unknown font family: nonexistent");
+}
+
+#[test]
+fn test_compile_async_fails_for_missing_files() {
+    let world = AsyncApiWorld::new(ApiWorld::new("#include \"missing.typ\""));
+    let errors = block_on(typst::compile_async(&world, &mut Tracer::new())).unwrap_err();
+    assert!(errors[0].message.starts_with("file not found"), "{}", errors[0].message);
+    assert_eq!(world.loads(), [id("missing.typ")]);
+}
+
+#[test]
+fn test_prefetch_serves_fetched_files() {
+    let world = AsyncApiWorld::new(
+        ApiWorld::new("#include \"chapter.typ\"").with("chapter.typ", "= Chapter"),
+    );
+    let prefetch = Prefetch::new(&world);
+    assert!(!prefetch.is_pending());
+
+    // The first attempt notes the missing file instead of waiting for it.
+    let errors = typst::compile(&prefetch, &mut Tracer::new()).unwrap_err();
+    assert_eq!(errors[0].message, "failed to load file (file is not loaded yet)");
+    assert!(prefetch.is_pending());
+    assert!(world.loads().is_empty());
+
+    block_on(prefetch.fetch());
+    assert!(!prefetch.is_pending());
+    assert_eq!(world.loads(), [id("chapter.typ")]);
+    let document = typst::compile(&prefetch, &mut Tracer::new()).unwrap();
+    assert!(text(&document.pages[0]).contains("Chapter"));
+}
+
+/// The text of a frame's text items, ordered by their horizontal position.
+fn visual_text(frame: &Frame) -> String {
+    fn collect(frame: &Frame, offset: Abs, items: &mut Vec<(Abs, EcoString)>) {
+        for (pos, item) in frame.items() {
+            let x = offset + pos.x;
+            match item {
+                FrameItem::Group(group) => collect(&group.frame, x, items),
+                FrameItem::Text(text) => items.push((x, text.text.clone())),
+                _ => {}
+            }
+        }
+    }
+
+    let mut items = vec![];
+    collect(frame, Abs::zero(), &mut items);
+    items.sort_by_key(|&(x, _)| x);
+    items.into_iter().map(|(_, text)| text.to_string()).collect()
+}
+
+#[test]
+fn test_math_reverses_right_to_left_runs() {
+    let visual = |text: &str| visual_text(&compile(&ApiWorld::new(text)).pages[0]);
+
+    // Right-to-left identifiers and the operators between them are reversed,
+    // independently of the text direction.
+    assert_eq!(visual("#text(lang: \"he\")[$ א + ב = 2 $]"), "2=ב+א");
+    assert_eq!(visual("#text(lang: \"de\")[$ א + ב = 2 $]"), "2=ב+א");
+
+    // Formulas without right-to-left text stay as they are, even in
+    // right-to-left text.
+    assert_eq!(visual("#set text(dir: rtl)\n$ x + 1 = y $"), "x+1=y");
+    assert_eq!(visual("#set text(lang: \"ar\")\n$ f(x) < 2 $"), "f(x)<2");
+
+    // Each column between alignment points is reordered on its own.
+    assert_eq!(visual("$ א &= ב + ג $"), "א=ג+ב");
+}
+
+#[test]
+fn test_math_mirrors_delimiters_in_right_to_left_runs() {
+    let visual = |text: &str| visual_text(&compile(&ApiWorld::new(text)).pages[0]);
+
+    // The parenthesis is replaced by the glyph of its mirrored character
+    // instead of being flipped.
+    assert_eq!(visual("$ س ( ص $"), "ص)س");
+    assert_eq!(visual("$ x ( y $"), "x(y");
+}
+
+/// The horizontal position of the first text run containing the needle.
+fn text_x(frame: &Frame, needle: &str) -> Option<Abs> {
+    frame.items().find_map(|(pos, item)| match item {
+        FrameItem::Group(group) => text_x(&group.frame, needle).map(|x| pos.x + x),
+        FrameItem::Text(text) if text.text.contains(needle) => Some(pos.x),
+        _ => None,
+    })
+}
+
+#[test]
+fn test_indenting_all_paragraphs_skips_single_paragraph_containers() {
+    let body = "Page.\n\n\
+                = Heading\n\
+                After heading.\n\n\
+                #block[Single.]\n\n\
+                #block[First.\n\nSecond.]\n\n\
+                - Item.\n\n\
+                #table[Cell.]\n\n\
+                #figure(rect(), caption: [Caption.])\n\n\
+                Note#footnote[Footnote.]";
+    let x = |indent: &str, needle: &str| {
+        let text = format!("#set par(first-line-indent: {indent})\n{body}");
+        let document = compile(&ApiWorld::new(&text));
+        text_x(&document.pages[0], needle).unwrap()
+    };
+
+    // Compare against the layout without any indent, so that only the
+    // indent itself is measured.
+    let indent = |needle: &str| x("(amount: 12pt, all: true)", needle) - x("0pt", needle);
+
+    for needle in ["Page.", "After heading.", "First.", "Second.", "Note"] {
+        assert!(indent(needle).approx_eq(Abs::pt(12.0)), "{needle}");
+    }
+
+    for needle in ["Heading", "Single.", "Item.", "Cell.", "Caption.", "Footnote."] {
+        assert!(indent(needle).approx_eq(Abs::zero()), "{needle}");
+    }
+}
+
+/// The shapes in a frame with their positions, ignoring transforms.
+fn shapes(frame: &Frame) -> Vec<(Point, Shape)> {
+    let mut out = vec![];
+    for (pos, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => out.extend(
+                shapes(&group.frame).into_iter().map(|(p, shape)| (*pos + p, shape)),
+            ),
+            FrameItem::Shape(shape, _) => out.push((*pos, shape.clone())),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// The positions and sizes of the rectangles with the given fill.
+fn rects(shapes: &[(Point, Shape)], fill: Color) -> Vec<(Point, Size)> {
+    shapes
+        .iter()
+        .filter(|(_, shape)| shape.fill == Some(fill.into()))
+        .filter_map(|(pos, shape)| match shape.geometry {
+            Geometry::Rect(size) => Some((*pos, size)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The number of lines with the given stroke paint.
+fn strokes(shapes: &[(Point, Shape)], paint: Color) -> usize {
+    shapes
+        .iter()
+        .filter(|(_, shape)| matches!(shape.geometry, Geometry::Line(_)))
+        .filter(|(_, shape)| {
+            shape
+                .stroke
+                .as_ref()
+                .map_or(false, |stroke| stroke.paint == paint.into())
+        })
+        .count()
+}
+
+#[test]
+fn test_bar_chart_draws_axes_ticks_and_negative_bars() {
+    let world = ApiWorld::new(
+        "#chart.bar(\n\
+           width: 200pt,\n\
+           height: 100pt,\n\
+           (\"Q1\", 12), (\"Q2\", 18), (\"Q3\", 9), (\"Q4\", -3),\n\
+         )",
+    );
+
+    let document = compile(&world);
+    let page = &document.pages[0];
+    let shapes = shapes(page);
+
+    // The scale covers the values with round ticks, each of which gets a
+    // label and a grid line. The bar labels follow below the plot.
+    assert_eq!(text(page), "\u{2212}505101520Q1Q2Q3Q4");
+    assert_eq!(strokes(&shapes, Color::SILVER), 6);
+    assert_eq!(strokes(&shapes, Color::BLACK), 2);
+
+    // All bars start at the zero line, so the negative one hangs below it.
+    let bars = rects(&shapes, Color::BLUE);
+    assert_eq!(bars.len(), 4);
+    let zero = bars[0].0.y + bars[0].1.y;
+    assert!((bars[1].0.y + bars[1].1.y).approx_eq(zero));
+    assert!(bars[3].0.y.approx_eq(zero));
+    assert!((bars[1].1.y / bars[0].1.y - 1.5).abs() < 1e-6);
+    assert!((bars[3].1.y / bars[0].1.y - 0.25).abs() < 1e-6);
+}
+
+#[test]
+fn test_line_chart_lists_labelled_series_in_legend() {
+    let world = ApiWorld::new(
+        "#chart.line(\n\
+           width: 200pt,\n\
+           height: 100pt,\n\
+           (label: \"Revenue\", data: (3, 5, 4, 7, 8)),\n\
+           (label: \"Costs\", data: (2, 3, 3, 4, 4)),\n\
+           (1, 1, 2),\n\
+         )",
+    );
+
+    let document = compile(&world);
+    let page = &document.pages[0];
+    let shapes = shapes(page);
+    assert!(text(page).ends_with("RevenueCosts"));
+
+    // Only the labelled series have a swatch, which is right of all lines.
+    let swatches = [rects(&shapes, Color::BLUE), rects(&shapes, Color::ORANGE)];
+    assert!(swatches.iter().all(|swatch| swatch.len() == 1));
+    assert!(rects(&shapes, Color::GREEN).is_empty());
+
+    let paths = paths(page, Transform::identity());
+    assert_eq!(paths.len(), 3);
+    let right = paths.iter().flatten().map(|point| point.x).max().unwrap();
+    assert!(swatches.iter().all(|swatch| swatch[0].0.x > right));
+}
+
+#[test]
+fn test_pie_chart_skips_empty_slices() {
+    let world = ApiWorld::new(
+        "#chart.pie((\"Rent\", 1200), (\"Food\", 0), 450, (\"Other\", 300))",
+    );
+
+    // Every labelled slice is in the legend, but only the non-empty ones
+    // are drawn.
+    let document = compile(&world);
+    let page = &document.pages[0];
+    let shapes = shapes(page);
+    assert_eq!(text(page), "RentFoodOther");
+    assert_eq!(paths(page, Transform::identity()).len(), 3);
+    for color in [Color::BLUE, Color::ORANGE, Color::RED] {
+        assert_eq!(rects(&shapes, color).len(), 1);
+    }
+}
+
+#[test]
+fn test_gantt_chart_places_tasks_and_milestones_on_time_axis() {
+    let world = ApiWorld::new(
+        "#chart.gantt(width: 200pt, (\"A\", 0, 4), (\"B\", 2, 6), (\"C\", 6))",
+    );
+
+    let document = compile(&world);
+    let page = &document.pages[0];
+    let shapes = shapes(page);
+    assert_eq!(text(page), "0246ABC");
+    assert_eq!(strokes(&shapes, Color::SILVER), 4);
+
+    // The second task starts halfway through the first one and lasts as
+    // long, one row further down.
+    let bars = rects(&shapes, Color::BLUE);
+    assert_eq!(bars.len(), 2);
+    let ((a, a_size), (b, b_size)) = (bars[0], bars[1]);
+    assert!(b.x.approx_eq(a.x + a_size.x / 2.0));
+    assert!(b_size.x.approx_eq(a_size.x));
+    assert!((b.y - a.y).approx_eq(Abs::pt(16.0)));
+
+    // The milestone's diamond is centered where the second task ends.
+    let milestones = paths(page, Transform::identity());
+    assert_eq!(milestones.len(), 1);
+    assert!(milestones[0][0].x.approx_eq(b.x + b_size.x));
+}
+
+#[test]
+fn test_gantt_chart_labels_dates() {
+    let world = ApiWorld::new(
+        "#let day(n) = datetime(year: 2023, month: 1, day: n)\n\
+         #chart.gantt(width: 400pt, (\"A\", day(1), day(5)), (\"B\", day(11)))",
+    );
+
+    let document = compile(&world);
+    let page = &document.pages[0];
+    assert_eq!(
+        text(page),
+        "2023-01-012023-01-032023-01-052023-01-072023-01-092023-01-11AB",
+    );
+
+    // The milestone is ten days after the start of the four-day task.
+    let (bar, size) = rects(&shapes(page), Color::BLUE)[0];
+    let milestone = &paths(page, Transform::identity())[0];
+    assert!((milestone[0].x - bar.x).approx_eq(size.x * 2.5));
+}
+
+#[test]
+fn test_sparkline_scales_with_text_and_values() {
+    let world = ApiWorld::new("#set text(size: 20pt)\n#chart.sparkline((0, 10, 5))");
+    let document = compile(&world);
+    let paths = paths(&document.pages[0], Transform::identity());
+    assert_eq!(paths.len(), 1);
+
+    // The sparkline is 4em wide and 0.8em high, with the smallest value at
+    // the bottom and the largest at the top.
+    let points = &paths[0];
+    let close = |p: Point, x: f64, y: f64| {
+        (p - Point::new(Abs::pt(x), Abs::pt(y))).hypot() < Abs::pt(0.01)
+    };
+    assert!(close(points[1] - points[0], 40.0, -16.0));
+    assert!(close(points[2] - points[0], 80.0, -8.0));
+}
+
+#[test]
+fn test_bullet_fills_share_of_track_and_marks_target() {
+    let world = ApiWorld::new("#chart.bullet(30, target: 50, max: 60)");
+    let document = compile(&world);
+    let paths = paths(&document.pages[0], Transform::identity());
+    assert_eq!(paths.len(), 3);
+
+    // The track is 4em wide, the bar fills half of it, and the target is
+    // marked at five sixths.
+    let (track, bar, target) = (&paths[0], &paths[1], &paths[2]);
+    assert!((track[1].x - track[0].x).approx_eq(Abs::pt(44.0)));
+    assert!((bar[1].x - bar[0].x).approx_eq(Abs::pt(22.0)));
+    assert!((target[0].x - track[0].x).approx_eq(Abs::pt(44.0 * 5.0 / 6.0)));
+}
@@ -3,6 +3,7 @@ use iai::{black_box, main, Iai};
 use typst::diag::FileResult;
 use typst::eval::Tracer;
 use typst::foundations::{Bytes, Datetime};
+use typst::introspection::Introspector;
 use typst::syntax::{FileId, Source};
 use typst::text::{Font, FontBook};
 use typst::visualize::Color;
@@ -57,11 +58,18 @@ fn bench_edit(iai: &mut Iai) {
 
 fn bench_eval(iai: &mut Iai) {
     let world = BenchWorld::new();
+    let introspector = Introspector::default();
     let route = typst::engine::Route::default();
     let mut tracer = typst::eval::Tracer::new();
     iai.run(|| {
-        typst::eval::eval(world.track(), route.track(), tracer.track_mut(), &world.source)
-            .unwrap()
+        typst::eval::eval(
+            world.track(),
+            introspector.track(),
+            route.track(),
+            tracer.track_mut(),
+            &world.source,
+        )
+        .unwrap()
     });
 }
 
@@ -21,7 +21,7 @@ use typst::eval::Tracer;
 use typst::foundations::{
     eco_format, func, Bytes, Datetime, NoneValue, Repr, Smart, Value,
 };
-use typst::introspection::Meta;
+use typst::introspection::{Introspector, Meta};
 use typst::layout::{Abs, Frame, FrameItem, Margin, PageElem, Transform};
 use typst::model::Document;
 use typst::syntax::{FileId, PackageVersion, Source, SyntaxNode, VirtualPath};
@@ -535,11 +535,18 @@ fn test_part(
 
     if world.print.model {
         let world = (world as &dyn World).track();
+        let introspector = Introspector::default();
         let route = typst::engine::Route::default();
         let mut tracer = typst::eval::Tracer::new();
 
-        let module =
-            typst::eval::eval(world, route.track(), tracer.track_mut(), &source).unwrap();
+        let module = typst::eval::eval(
+            world,
+            introspector.track(),
+            route.track(),
+            tracer.track_mut(),
+            &source,
+        )
+        .unwrap();
         writeln!(output, "Model:\n{:#?}\n", module.content()).unwrap();
     }
 
@@ -0,0 +1,26 @@
+// Test content traversal.
+// Ref: false
+
+---
+#test([a *b*].nodes(), ([a], [ ], strong[b]))
+#test(strong[b].nodes(), ([b],))
+#test([a].nodes(), ())
+#test(list[a][b].nodes().len(), 2)
+
+---
+// The method doesn't shadow the `children` field of elements.
+#let it = list[a][b]
+#test(it.children.len(), 2)
+#test(it.children, it.nodes())
+#test(terms.item[a][b].nodes(), ([a], [b]))
+
+---
+#let body = [
+  = Intro
+  Some *strong* and _emphasized *nested*_ text.
+]
+
+#test(body.find(strong).map(it => it.body), ([strong], [nested]))
+#test(body.find(heading.where(level: 1)).len(), 1)
+#test(body.find(heading.where(level: 2)), ())
+#test(body.find(<missing>), ())
@@ -0,0 +1,28 @@
+// Test label namespaces for included files.
+// Ref: false
+
+---
+#set heading(numbering: "1.")
+
+= Introduction <intro>
+#include "modules/labelled.typ" as ch1
+#include "modules/labelled.typ" as ch2
+
+See @intro, @ch1.details, and @ch2.details.
+
+#locate(loc => {
+  test(query(<note>, loc), ())
+  test(query(<ch1.note>, loc).first().value, "inner")
+  test(query(<ch2.note>, loc).len(), 1)
+  test(query(<ch1.details>, loc).first().body, [Details])
+})
+
+---
+// Test that show rules on labels apply within their namespace.
+#show <details>: it => [#metadata("outer") <outer> #it]
+#include "modules/labelled-show.typ" as ch1
+
+#locate(loc => {
+  test(query(<ch1.shown>, loc).len(), 1)
+  test(query(<outer>, loc), ())
+})
@@ -0,0 +1,6 @@
+// A file to include in label namespace tests with show rules.
+// Ref: false
+
+#show <details>: it => [#metadata("shown") <shown> #it]
+
+= Details <details>
@@ -0,0 +1,8 @@
+// A file to include in label namespace tests.
+// Ref: false
+
+#set heading(numbering: "1.")
+
+= Details <details>
+As described in @details.
+#metadata("inner") <note>
@@ -34,3 +34,24 @@
 
 // Error: 2-16 plugin panicked: wasm `unreachable` instruction executed
 #p.will_panic()
+
+---
+// Test reading arguments and sending results in chunks.
+#let p = plugin("/files/plugin-host.wasm")
+#test(p.echo_chunked(bytes("Hello in chunks")), bytes("Hello in chunks"))
+#test(p.echo_chunked(bytes("")), bytes(""))
+
+---
+// Test reading files the plugin was granted access to.
+#let p = plugin("/files/plugin-host.wasm", files: ("data.txt": "/files/hello.txt"))
+#test(p.read_file_chunked(), read("/files/hello.txt", encoding: none))
+
+---
+#let p = plugin("/files/plugin-host.wasm", files: ("other.txt": "/files/hello.txt"))
+
+// Error: 2-23 plugin errored with: access denied
+#p.read_file_chunked()
+
+---
+// Error: 43-77 file not found (searched at files/missing.txt)
+#plugin("/files/plugin-host.wasm", files: ("data.txt": "/files/missing.txt"))
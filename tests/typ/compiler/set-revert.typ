@@ -0,0 +1,26 @@
+// Test reverting style properties.
+// Ref: false
+
+---
+#test(repr(revert), "revert")
+
+#set text(fill: red)
+#[
+  #set text(fill: blue)
+  #style(styles => test(styles.get(text, "fill"), blue))
+  #set text(fill: revert)
+  #style(styles => test(styles.get(text, "fill"), red))
+]
+
+---
+#set text(size: 14pt)
+#set text(size: revert)
+#style(styles => test(styles.get(text, "size"), 11pt))
+
+---
+// Only the innermost rule is skipped.
+#set par(leading: 1em)
+#set par(leading: 2em)
+#set par(leading: 3em)
+#set par(leading: revert)
+#style(styles => test(styles.get(par, "leading"), 2em))
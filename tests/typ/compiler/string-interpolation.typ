@@ -0,0 +1,27 @@
+// Test interpolated strings.
+// Ref: false
+
+---
+#let sum = 7
+#test(f"Total: {sum} items", "Total: 7 items")
+#test(f"{1.5}{"a"}{1 + 1}", "1.5a2")
+#test(f"", "")
+#test(f"No braces", "No braces")
+
+---
+// Test escapes.
+#test(f"\{x\}", "{x}")
+#test(f"\"{"q"}\"", "\"q\"")
+#test(f"\u{41}{"b"}", "Ab")
+#test(f"a}b", "a}b")
+
+---
+// Test code in interpolations.
+#let names = ("Anna", "Bob")
+#test(f"{names.join(", ")} and {let n = names.len(); n + 1} more", "Anna, Bob and 3 more")
+#test(f"{f"nested {1}"}!", "nested 1!")
+
+---
+// Error: 5-12 cannot interpolate content into a string
+// Hint: 5-12 use `repr` to get a textual representation
+#f"a {[*b*]}"
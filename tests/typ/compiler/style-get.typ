@@ -0,0 +1,28 @@
+// Test looking up style properties.
+// Ref: false
+
+---
+#set text(size: 14pt)
+#style(styles => test(styles.get(text, "size"), 14pt))
+#[
+  #set text(fill: red)
+  #style(styles => test(styles.get(text, "fill"), red))
+]
+#style(styles => test(styles.get(text, "fill"), black))
+#style(styles => test(styles.get(par, "justify"), false))
+
+---
+// Error: 18-41 text does not have a settable property `foo`
+#style(styles => styles.get(text, "foo"))
+
+---
+// Test that folded properties combine all set rules.
+#set align(left)
+#set align(top)
+#style(styles => test(styles.get(align, "alignment"), left + top))
+
+#set text(size: 10pt)
+#[
+  #set text(size: 2em)
+  #style(styles => test(styles.get(text, "size"), 20pt))
+]
@@ -0,0 +1,6 @@
+// Test the inputs of the system module.
+// Ref: false
+
+---
+#test(type(sys.inputs), dictionary)
+#test(sys.inputs, (:))
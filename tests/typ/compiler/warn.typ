@@ -0,0 +1,63 @@
+// Test user-defined warnings.
+// Ref: false
+
+---
+// Warning: 6-30 this is questionable
+#warn("this is questionable")
+
+---
+// Warning: 2:25-2:62 the `size` argument is deprecated
+#let f(size: none) = {
+  if size != none { warn("the `size` argument is deprecated") }
+  size
+}
+#test(f(size: 1), 1)
+
+---
+#assert.soft(1 < 2)
+
+---
+// Warning: 13-20 assertion failed
+#assert.soft(1 > 2)
+
+---
+// Warning: 13-43 assertion failed: math broke
+#assert.soft(1 > 2, message: "math broke")
+
+---
+#warn.promote("user")
+
+// Error: 6-23 this is wrong
+#warn("this is wrong")
+
+---
+#warn.promote("import")
+// Warning: 13-20 assertion failed
+#assert.soft(false)
+
+---
+// Error: 15-24 expected "markup", "import", "convergence", "user", "unknown-font", or "unresolved-reference"
+#warn.promote("unknown")
+
+---
+// Warning: 17-30 unknown font family: nonexistent
+#set text(font: "Nonexistent")
+
+---
+#warn.promote("unknown-font")
+
+// Error: 17-43 unknown font family: nonexistent
+#set text(font: ("Nonexistent", "PT Sans"))
+
+---
+// A show rule that doesn't look at the target deliberately hides missing
+// labels.
+#show ref: it => "@" + str(it.target)
+@missing
+
+---
+#warn.promote("unresolved-reference")
+#show ref: it => "@" + str(it.target)
+
+// Error: 1-9 label `<missing>` does not exist in the document
+@missing
@@ -178,6 +178,21 @@
 // Error: 8-10 expected at least one variant
 #symbol()
 
+---
+// Test symbol modules.
+#let chem = symbol.module("chem", (
+  bond: ("": "–", double: "=", triple: "≡"),
+  eq: 0x21CC,
+))
+#test(type(chem), module)
+#test(repr(chem.bond), "\"–\"")
+#test(repr(chem.bond.triple), "\"≡\"")
+#test(repr(chem.eq), "\"⇌\"")
+
+---
+// Error: 21-29 symbol `a` has no variants
+#symbol.module("x", (a: (:)))
+
 ---
 // Test conversion to string.
 #test(str(123), "123")
@@ -14,6 +14,16 @@
 // Error: 18-34 file is not valid utf-8
 #let data = read("/files/bad.txt")
 
+---
+// Test reading files in legacy encodings.
+#test(read("/files/hello.txt", encoding: auto), "Hello, world!\n")
+#test(read("/files/bad.txt", encoding: "latin1").slice(0, 4), "\u{fe}\u{ff}")
+#test(read("/files/bad.txt", encoding: "windows-1252").len(), 105)
+
+---
+// Error: 18-34 file is not valid utf-16
+#let data = read("/files/bad.txt", encoding: "utf16")
+
 ---
 // Test reading CSV data.
 // Ref: true
@@ -30,6 +40,70 @@
 // Error: 6-22 failed to parse CSV (found 3 instead of 2 fields in line 3)
 #csv("/files/bad.csv")
 
+---
+// Test CSV headers and types.
+#let data = csv.decode("name,age,member\nAlice,23,true\nBob,,false\n", header: true, types: auto)
+#test(data, ((name: "Alice", age: 23, member: true), (name: "Bob", age: none, member: false)))
+#test(csv.decode("a,b\n1,2.5\n", header: true, types: (b: float)), ((a: "1", b: 2.5),))
+#test(csv.decode("1,2.5,x\n", types: (int, float)), ((1, 2.5, "x"),))
+
+---
+// Test CSV dialect options and ragged rows.
+#test(csv.decode("'a;b';c\n", delimiter: ";", quote: "'"), (("a;b", "c"),))
+#test(csv.decode("\"a\\\"b\"\n", escape: "\\"), (("a\"b",),))
+#test(csv.decode("a,b\n1\n2,3,4\n", header: true, ragged: true), ((a: "1", b: none), (a: "2", b: "3")))
+
+---
+// Error: 13-18 failed to parse CSV (cannot convert `x` to integer in line 1)
+#csv.decode("x,1", types: (int,))
+
+---
+// Error: 27-37 cannot convert CSV fields to content
+#csv.decode("x,1", types: (content,))
+
+---
+// Test encoding CSV data.
+#test(csv.encode((("a", 1), ("b", none))), "a,1\nb,\n")
+#test(csv.encode((("x", true, 2.5),), delimiter: ";"), "x;true;2.5\n")
+#test(csv.decode(csv.encode((("a,b", "c"),))), (("a,b", "c"),))
+
+---
+// Error: 13-19 expected array of rows, found integer
+#csv.encode((1, 2))
+
+---
+// Test reading XLSX data.
+#test(xlsx("/files/data.xlsx"), (("Product", "Units"), ("Apple", 10)))
+
+---
+// Test selecting worksheets and converting cells.
+#let rows = xlsx("/files/data.xlsx", sheet: "Q3")
+#test(xlsx("/files/data.xlsx", sheet: 1), rows)
+#test(rows.len(), 3)
+#test(rows.at(1), ("Apple", 12, 0.5, true, datetime(year: 2023, month: 10, day: 1)))
+#test(rows.at(2), ("Pear", 7, 1.25, false, none))
+
+---
+// Test reading XLSX data with a header row.
+#let rows = xlsx("/files/data.xlsx", sheet: "Q3", header: true)
+#test(rows.len(), 2)
+#test(rows.first().Product, "Apple")
+#test(rows.first().Units, 12)
+#test(rows.last().Date, none)
+
+---
+// Test decoding XLSX bytes.
+#let rows = xlsx.decode(read("/files/data.xlsx", encoding: none), sheet: "Q2")
+#test(rows.last(), ("Apple", 10))
+
+---
+// Error: 7-25 worksheet `Q4` not found
+#xlsx("/files/data.xlsx", sheet: "Q4")
+
+---
+// Error: 7-25 worksheet index out of bounds: 2
+#xlsx("/files/data.xlsx", sheet: 2)
+
 ---
 // Test reading JSON data.
 #let data = json("/files/zoo.json")
@@ -79,6 +153,20 @@
   second: 57,
 ))
 
+---
+// Test TOML datetimes with offsets and fractional seconds.
+#let data = toml.decode(
+  "a = 1979-05-27T07:32:00-08:00\n" +
+  "b = 1979-05-27T00:32:00.999999+07:00\n" +
+  "c = 1979-05-27 07:32:00Z\n" +
+  "d = 00:32:00.5\n"
+)
+#let expected = datetime(year: 1979, month: 5, day: 27, hour: 7, minute: 32, second: 0)
+#test(data.a, expected)
+#test(data.b, datetime(year: 1979, month: 5, day: 27, hour: 0, minute: 32, second: 0))
+#test(data.c, expected)
+#test(data.d, datetime(hour: 0, minute: 32, second: 0))
+
 ---
 // Error: 7-24 failed to parse TOML (expected `.`, `=` at line 1 column 16)
 #toml("/files/bad.toml")
@@ -97,6 +185,69 @@
 #test(data.keys().contains("true"), true)
 #test(data.at("1"), "ok")
 
+---
+// Test reading relaxed JSON.
+#let data = json.decode(
+  "{\n  // The name.\n  \"name\": \"a//b\", /* inline */\n  \"list\": [1, 2,],\n}",
+  relaxed: true,
+)
+#test(data, (name: "a//b", list: (1, 2)))
+
+---
+// Error: 14-20 failed to parse JSON (trailing comma at line 1 column 4)
+#json.decode("[1,]")
+
+---
+// Test that plain JSON encodes values without a JSON counterpart as their
+// representation.
+#test(json.encode(1pt, pretty: false), "\"1pt\"")
+#test(json.encode((a: auto, b: <intro>), pretty: false), "{\"a\":\"auto\",\"b\":\"<intro>\"}")
+#test(json.decode(json.encode((type: "user"))), (type: "user"))
+
+---
+// Test round-tripping values through typed JSON.
+#let roundtrip(value) = json.decode(json.encode(value, typed: true), typed: true)
+#let values = (
+  none, true, 1, 2.5, "a", (1, 2pt), (type: "user", size: 1em + 2pt),
+  (type: "length", abs: 1), auto, 90deg, 50%, 50% + 1pt, 2fr, <intro>,
+  version(1, 2), datetime(year: 2023, month: 5, day: 1),
+  datetime(hour: 10, minute: 15, second: 0),
+  datetime(year: 2023, month: 5, day: 1, hour: 10, minute: 15, second: 0),
+  duration(seconds: 90), bytes((1, 2)), rgb("#ff0000"), top + left, text,
+  list.item,
+)
+#for value in values {
+  test(roundtrip(value), value)
+}
+#test(json.encode(2pt, typed: true, pretty: false), "{\"type\":\"length\",\"abs\":2.0,\"em\":0.0}")
+#test(repr(roundtrip(sym.arrow)), repr(sym.arrow))
+#for value in ([= Hi #emph[there] <intro>], list.item[A], [#terms.item[A][B]]) {
+  test(repr(roundtrip(value)), repr(value))
+}
+
+---
+// Error: 14-38 dictionary does not contain key "abs"
+#json.decode("{\"type\": \"length\"}", typed: true)
+
+---
+// Error: 14-79 `calc.pow` is not an element function
+#json.decode("{\"type\": \"content\", \"func\": \"calc.pow\", \"fields\": {}}", typed: true)
+
+---
+// Test converting ISO dates in JSON and YAML.
+#let data = json.decode("{\"day\": \"2023-05-01\", \"time\": \"2023-05-01T12:30:00.5+02:00\", \"name\": \"x\"}", datetimes: true)
+#test(data.day, datetime(year: 2023, month: 5, day: 1))
+#test(data.time, datetime(year: 2023, month: 5, day: 1, hour: 12, minute: 30, second: 0))
+#test(data.name, "x")
+#test(json.decode("\"2023-05-01\""), "2023-05-01")
+#test(yaml.decode("- 10:15:00\n", datetimes: true), (datetime(hour: 10, minute: 15, second: 0),))
+
+---
+// Test YAML anchors, merge keys, and streams.
+#let data = yaml.decode("base: &base\n  a: 1\n  b: 2\nderived:\n  <<: *base\n  b: 3\n")
+#test(data.derived, (b: 3, a: 1))
+#test(yaml.decode("a: 1\n---\nb: 2\n", stream: true), ((a: 1), (b: 2)))
+
 ---
 // Error: 7-24 failed to parse YAML (did not find expected ',' or ']' at line 2 column 1, while parsing a flow sequence at line 1 column 18)
 #yaml("/files/bad.yaml")
@@ -126,6 +277,46 @@
   ),
 ),))
 
+---
+// Test XML namespaces and paths.
+#let data = xml.decode(
+  "<svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>" +
+  "<g><a xlink:href='#x'><text>A</text></a></g><text>B</text></svg>"
+)
+#let svg = data.first()
+#test(svg.namespace, "http://www.w3.org/2000/svg")
+#test(xml.find(data, "svg/g/a").first().attrs, ("xlink:href": "#x"))
+#test(xml.find(data, "svg/text").map(e => e.children.first()), ("B",))
+#test(xml.find(data, "//text").map(e => e.children.first()), ("A", "B"))
+#test(xml.find(svg, "*").map(e => e.tag), ("g", "text"))
+#test(xml.find(data, "svg/missing"), ())
+
 ---
 // Error: 6-22 failed to parse XML (found closing tag 'data' instead of 'hello' in line 3)
 #xml("/files/bad.xml")
+
+---
+// Test MessagePack round trip.
+#let data = (name: "Debby", weight: 150, tags: ("cute", none))
+#test(msgpack.decode(msgpack.encode(data)), data)
+
+---
+// Test decompressing data in all supported formats.
+#let plain = read("/files/data.csv", encoding: none)
+#let gz = read("/files/data.csv.gz", encoding: none)
+#test(decompress(gz), plain)
+#test(decompress(gz, format: "gzip"), plain)
+#test(decompress(read("/files/data.csv.zz", encoding: none)), plain)
+#test(decompress(read("/files/data.csv.zst", encoding: none)), plain)
+#test(csv.decode(decompress(gz)), csv("/files/data.csv"))
+
+---
+// Test transparent decompression when loading data.
+#let rows = (("Name", "Amount"), ("Apples", "3"), ("Pears", "5"))
+#test(csv("/files/data.csv.gz"), rows)
+#test(csv("/files/data.csv.zz"), rows)
+#test(csv("/files/data.csv.zst"), rows)
+
+---
+// Error: 13-29 failed to detect compression format
+#decompress(bytes((1, 2, 3)))
@@ -0,0 +1,59 @@
+// Test data frames.
+// Ref: false
+
+---
+#let df = dataframe((
+  (name: "Ana", age: 31, score: 2.5),
+  (name: "Ben", age: 27),
+  (name: "Cleo", age: 45, score: 4),
+))
+#test(df.len(), 3)
+#test(df.columns(), ("name", "age", "score"))
+#test(df.types(), (name: str, age: int, score: float))
+#test(df.column("score"), (2.5, none, 4.0))
+#test(df.at(-1), (name: "Cleo", age: 45, score: 4.0))
+#test(df.select("age", "name").columns(), ("age", "name"))
+#test(df.filter(row => row.age > 30).column("name"), ("Ana", "Cleo"))
+#test(df.sort("age").column("name"), ("Ben", "Ana", "Cleo"))
+#test(df.sort("score", reverse: true).column("name"), ("Cleo", "Ana", "Ben"))
+
+---
+#let sales = dataframe((
+  region: ("North", "South", "North", "South", "East"),
+  amount: (120, 80, 45, none, 10),
+))
+#let grouped = sales.group-by(
+  "region",
+  aggregate: (amount: "sum"),
+)
+#test(grouped.rows(), (
+  (region: "North", amount: 165),
+  (region: "South", amount: 80),
+  (region: "East", amount: 10),
+))
+#test(sales.group-by("region", aggregate: (amount: "count")).column("amount"), (2, 1, 1))
+#test(sales.group-by("region", aggregate: (amount: "mean")).column("amount"), (82.5, 80.0, 10.0))
+
+---
+#let orders = dataframe((customer: (1, 2, 1, 3), total: (30, 12, 8, 5)))
+#let customers = dataframe((customer: (1, 2), name: ("Ana", "Ben")))
+#test(orders.join(customers, "customer").column("name"), ("Ana", "Ben", "Ana"))
+#test(orders.join(customers, "customer", how: "left").column("name"), ("Ana", "Ben", "Ana", none))
+
+---
+#let zoo = dataframe.csv("/files/zoo.csv")
+#test(zoo.len(), 3)
+#test(zoo.sort("Name", reverse: true).at(0).Species, "Dolphin")
+#test(dataframe.json("/files/zoo.json").types().weight, int)
+
+---
+// Error: 12-27 column `a` contains both int and str
+#dataframe((a: (1, "two")))
+
+---
+// Error: 2-32 data frame has no column `b`
+#dataframe((a: (1,))).sort("b")
+
+---
+// Error: 2-56 cannot compute the sum of column `a` of type str
+#dataframe((a: ("x",))).group-by(aggregate: (a: "sum"))
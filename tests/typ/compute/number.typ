@@ -0,0 +1,19 @@
+// Test locale-aware number formatting.
+// Ref: false
+
+---
+#test(float.format(1234.5), "1,234.5")
+#test(float.format(1234.5, locale: "de", precision: 2), "1.234,50")
+#test(float.format(1234.5, locale: "de", region: "CH"), "1’234.5")
+#test(float.format(-0.001, precision: 2), "0.00")
+#test(float.format(-1234.5, grouping: false), "−1234.5")
+#test(float.format(0.07, style: "percent"), "7%")
+#test(float.format(0.125, locale: "de", style: "percent"), "12,5\u{a0}%")
+#test(float.format(19.9, currency: "$"), "$19.90")
+#test(float.format(-19.9, locale: "de", currency: "€"), "−19,90\u{a0}€")
+#test(int.format(1234567, locale: "fr"), "1\u{202f}234\u{202f}567")
+#test(int.format(3, style: "percent", precision: 1), "300.0%")
+
+---
+// Error: 2-52 cannot format a percentage as a currency amount
+#float.format(1.0, style: "percent", currency: "€")
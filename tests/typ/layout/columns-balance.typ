@@ -0,0 +1,29 @@
+// Test balanced columns.
+// Ref: false
+
+---
+// Test that balancing halves the height of two columns.
+#style(styles => {
+  let body = range(6).map(str).join(linebreak())
+  let plain = measure(box(width: 100pt, columns(2, body)), styles)
+  let balanced = measure(box(width: 100pt, columns(2, balance: true, body)), styles)
+  test(balanced.height < plain.height * 0.6, true)
+  test(balanced.height > plain.height * 0.4, true)
+})
+
+---
+// Test balancing on the last page of a multi-page layout.
+#set page(height: 80pt, columns: 2)
+#set columns(balance: true)
+#for i in range(12) [Line #i \ ]
+
+---
+// Test balancing with column breaks.
+#columns(3, balance: true)[
+  A \ B \ C
+  #colbreak()
+  D \ E
+  #colbreak(weak: true)
+  F
+]
+
@@ -0,0 +1,144 @@
+// Test cells spanning multiple columns and rows. Marked cells record their
+// position, which is checked against the expected grid placement.
+// Ref: false
+
+---
+#set rect(width: 100%, height: 100%, inset: 3pt)
+#grid(
+  columns: (1fr, auto, 1fr),
+  rows: 16pt,
+  gutter: 3pt,
+  grid.cell(colspan: 2, rect(fill: aqua)[Wide]),
+  grid.cell(rowspan: 2, rect(fill: eastern)[Tall]),
+  rect[A], rect[B],
+  grid.cell(colspan: 3, rect(fill: teal)[Full]),
+)
+
+---
+// Test that cells are placed around spanning cells.
+#set page(width: 100pt, margin: 0pt)
+#let mark(name) = [#metadata(name) <cell>]
+#grid(
+  columns: (20pt, 30pt, 50pt),
+  rows: 10pt,
+  grid.cell(colspan: 2, mark("wide")),
+  grid.cell(rowspan: 2, mark("tall")),
+  mark("a"), mark("b"),
+  grid.cell(colspan: 3, mark("full")),
+)
+
+#locate(loc => {
+  let pos = (:)
+  for m in query(<cell>, loc) { pos.insert(m.value, m.location().position()) }
+  test((pos.wide.x, pos.tall.x, pos.a.x, pos.b.x, pos.full.x), (0pt, 50pt, 0pt, 20pt, 0pt))
+  test(pos.tall.y, pos.wide.y)
+  test(pos.b.y, pos.a.y)
+  test(pos.a.y - pos.wide.y, 10pt)
+  test(pos.full.y - pos.wide.y, 20pt)
+})
+
+---
+// A cell spanning the last auto columns widens only the last of them.
+#set page(width: 100pt, margin: 0pt)
+#let mark(name) = [#metadata(name) <cell>]
+#grid(
+  columns: (auto, auto, auto),
+  box(width: 10pt), box(width: 10pt), mark("after"),
+  grid.cell(colspan: 2, box(width: 50pt)), [],
+)
+
+#locate(loc => {
+  test(query(<cell>, loc).first().location().position().x, 50pt)
+})
+
+---
+// A cell spanning auto rows grows the last of them.
+#set page(width: 100pt, margin: 0pt)
+#let mark(name) = [#metadata(name) <cell>]
+#grid(
+  columns: 2,
+  grid.cell(rowspan: 2, box(height: 50pt)), [#mark("top")#box(height: 10pt)],
+  box(height: 10pt),
+  [#mark("below")#box(height: 10pt)], [],
+)
+
+#locate(loc => {
+  let (top, below) = query(<cell>, loc).map(m => m.location().position())
+  test(below.x, 0pt)
+  test(below.y - top.y, 50pt)
+})
+
+---
+// Spanning cells are mirrored in right-to-left text.
+#set page(width: 100pt, margin: 0pt)
+#set text(dir: rtl)
+#set align(left)
+#let mark(name) = [#metadata(name) <cell>]
+#grid(
+  columns: (20pt, 30pt, 50pt),
+  grid.cell(colspan: 2, mark("span")), mark("last"),
+)
+
+#locate(loc => {
+  let xs = query(<cell>, loc).map(m => m.location().position().x)
+  test(xs, (50pt, 0pt))
+})
+
+---
+// Test that a spanning cell widens the last auto column it spans.
+#grid(
+  columns: (auto, auto),
+  gutter: 3pt,
+  [A], [B],
+  grid.cell(colspan: 2)[A much wider cell],
+)
+
+---
+// Test that a spanning cell grows the last row it spans.
+#table(
+  columns: 2,
+  table.cell(rowspan: 2)[A \ B \ C \ D],
+  [1], [2],
+)
+
+---
+// Test strokes and fills of spanning cells in a table.
+#table(
+  columns: 4,
+  fill: (x, y) => if y == 0 { luma(230) },
+  table.cell(rowspan: 2)[*Region*],
+  table.cell(colspan: 3)[*Sales*],
+  [2021], [2022], [2023],
+  [North], [120], table.cell(colspan: 2)[135],
+  [South], [80], [95], [110],
+)
+
+---
+// Test spanning cells with gutter and right-to-left text.
+#set text(dir: rtl)
+#table(
+  columns: 3,
+  gutter: 2pt,
+  table.cell(colspan: 2)[A], [B],
+  [C], table.cell(rowspan: 2)[D], [E],
+  [F], [G],
+)
+
+---
+// Test a cell spanning rows across a page break.
+#set page(height: 80pt)
+#table(
+  columns: 2,
+  table.cell(rowspan: 4)[Spanning #metadata("span") <cell>],
+  ..range(4).map(n => [#text(size: 16pt, str(n)) #metadata(n) <cell>]),
+)
+
+#locate(loc => {
+  let pages = (:)
+  for m in query(<cell>, loc) { pages.insert(str(m.value), m.location().page()) }
+  test((pages.span, pages.at("0"), pages.at("3")), (1, 1, 2))
+})
+
+---
+// Error: 19-43 cell's colspan would exceed the number of columns
+#grid(columns: 2, grid.cell(colspan: 3)[A])
@@ -40,3 +40,15 @@
   h(1em)
   place(left, rect(width: 80pt, stroke: blue))
 })
+
+---
+// Test page metadata.
+// Ref: false
+#set page(name: "cover", tags: ("front-matter",))
+#locate(loc => {
+  let meta = loc.page-metadata()
+  test(meta.name, "cover")
+  test(meta.tags, ("front-matter",))
+})
+#set page(name: none, tags: ())
+#locate(loc => test(loc.page-metadata(), (name: none, tags: ())))
@@ -33,3 +33,13 @@ Second
 #pagebreak(to: "odd")
 
 Third
+
+---
+// Ref: false
+// Test styling blank pages.
+#set page(width: 80pt, height: 30pt, header: [Header], numbering: "1")
+#set pagebreak.blank(marginals: false)
+#show pagebreak.blank: align(center + horizon)[Blank]
+First
+#pagebreak(to: "odd")
+Third
@@ -49,3 +49,39 @@ Welcome \ here. Does this work well?
 #set text(dir: rtl)
 لآن وقد أظلم الليل وبدأت النجوم
 تنضخ وجه الطبيعة التي أعْيَتْ من طول ما انبعثت في النهار
+
+---
+// Test contextual first-line indent rules.
+// Ref: false
+#set par(first-line-indent: (amount: 12pt, after-heading: true, after-figure: true))
+= Heading
+Indented after the heading.
+
+#figure(rect())
+Indented after the figure.
+
+#set par(first-line-indent: (amount: 12pt, same-style: true))
+Not indented after the figure.
+
+#text(8pt)[Not indented after a differently styled paragraph.]
+
+---
+// Test indenting all paragraphs.
+// Ref: false
+#set par(first-line-indent: (amount: 12pt, all: true))
+Indented as the first paragraph.
+
+= Heading
+Indented after the heading.
+
+#block[Not indented as the only paragraph in a block.]
+
+#block[
+  Indented as the first paragraph in a block.
+
+  Indented as the second one.
+]
+
+---
+// Error: 28-55 unexpected key "before", valid keys are "amount", "after-heading", "after-figure", "same-style", and "all"
+#set par(first-line-indent: (amount: 1em, before: true))
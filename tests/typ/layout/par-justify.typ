@@ -63,3 +63,41 @@ int main() {
 }
 ```
 
+
+---
+// Test last line justification.
+// Ref: false
+#set page(width: 180pt)
+#set par(justify: true, justify-last: true)
+#lorem(20)
+
+#set par(justify-last: "center")
+#lorem(20)
+
+#set par(justify-last: 80%)
+#lorem(20)
+
+---
+// Test where the last line ends with each option. The text spans from 20pt
+// to 140pt.
+// Ref: false
+#set page(width: 160pt, margin: 20pt)
+#set par(justify: true)
+A B#metadata(none) <align>
+
+#par(justify-last: true)[A B#metadata(none) <justify>]
+
+#par(justify-last: "center")[A B#metadata(none) <center>]
+
+#par(justify-last: 80%)[A B#metadata(none) <short>]
+
+#par(justify-last: 0%)[A B#metadata(none) <long>]
+
+#locate(loc => {
+  let x(label) = query(label, loc).first().location().position().x
+  test(x(<align>) < 50pt, true)
+  test(x(<justify>) > 139pt, true)
+  test(x(<center>) > 70pt and x(<center>) < 110pt, true)
+  test(x(<short>) < 50pt, true)
+  test(x(<long>) > 139pt, true)
+})
@@ -0,0 +1,71 @@
+// Test line numbering.
+
+---
+// Ref: false
+#set page(width: 120pt, height: 120pt, margin: (left: 30pt, rest: 10pt))
+#set par.line(numbering: "1")
+#lorem(20)
+
+Another paragraph.
+
+---
+// Test restarting the numbering on each page in the right margin.
+// Ref: false
+#set page(width: 120pt, height: 80pt, margin: (right: 30pt, rest: 10pt))
+#set par.line(numbering: "(i)", number-margin: end, numbering-scope: "page")
+#lorem(40)
+
+---
+// Test querying numbered lines.
+// Ref: false
+#set par.line(numbering: "1")
+First line \
+second line \
+third line
+#locate(loc => {
+  let lines = query(par.line, loc)
+  test(lines.len(), 3)
+  test(lines.first().numbering, "1")
+  test(lines.map(it => it.location().position().y).sorted(), lines.map(it => it.location().position().y))
+})
+
+---
+// Test that lines are only numbered if a numbering is set.
+// Ref: false
+#set par.line(number-clearance: 2em)
+Unnumbered
+#locate(loc => test(query(par.line, loc).len(), 0))
+
+---
+// Error: 30-36 expected `start`, `left`, `right`, or `end`, found `center`
+#set par.line(number-margin: center)
+
+---
+// Error: 10-12 can only be used in set rules
+#par.line()
+
+---
+// Test that the numbering continues across pages and skips the footer.
+// Ref: false
+#set page(height: 80pt, footer: [Footer text])
+#set par.line(numbering: n => [#metadata(n) <num>#n])
+#lorem(40)
+#locate(loc => {
+  let nums = query(<num>, loc)
+  test(nums.last().location().page() > 1, true)
+  test(nums.map(it => it.value), range(1, nums.len() + 1))
+  test(query(par.line, loc).len(), nums.len())
+})
+
+---
+// Test that the numbers of two columns are placed in both margins.
+// Ref: false
+#set page(width: 200pt, height: 100pt, margin: 30pt, columns: 2)
+#set par.line(numbering: n => [#metadata(n) <num>#n])
+#lorem(30)
+#locate(loc => {
+  let xs = query(<num>, loc).map(it => it.location().position().x)
+  test(xs.all(x => x < 30pt or x > 170pt), true)
+  test(xs.any(x => x < 30pt), true)
+  test(xs.any(x => x > 170pt), true)
+})
@@ -34,3 +34,28 @@ fn main() {}
 - List
 
 Paragraph
+
+---
+// Test line height modes.
+// Ref: false
+#set par(line-height: "normal")
+#lorem(10)
+
+#set par(line-height: 1.5em)
+#lorem(5) #text(2em)[big] #lorem(5)
+
+#set par(line-height: auto)
+#lorem(10)
+
+---
+// Test the heights of lines in each mode.
+#style(styles => {
+  let height(body) = measure(block(width: 100pt, body), styles).height
+  let auto = height[A]
+  test(height(par(line-height: "normal")[A]) > auto, true)
+  test(height(par(line-height: 20pt)[A]), 20pt)
+  test(height(par(line-height: 20pt)[A #text(2em)[B] #linebreak() C]), 40pt)
+  test(height(par(line-height: 20pt, text(line-height: 40pt)[A])), 20pt)
+  test(height(text(line-height: 30pt)[A]), 30pt)
+  test(height[A #text(line-height: "normal")[B]], height(par(line-height: "normal")[A]))
+})
@@ -38,6 +38,14 @@ A#box(width: 1fr, repeat(rect(width: 6em, height: 0.7em)))B
 #set text(dir: rtl)
 ريجين#box(width: 1fr, repeat(rect(width: 4em, height: 0.7em)))سون
 
+---
+// Ref: false
+// Test gap and unjustified repeat.
+A #box(width: 1fr, repeat(gap: 1em)[-]) B \
+A #box(width: 1fr, repeat(gap: 2pt, justify: false)[.]) B \
+A #box(width: 1fr, repeat(justify: false)[.]) #box(width: 2fr, repeat[=]) B
+#box(width: 10pt, repeat(gap: -20pt)[.])
+
 ---
 // Error: 2:2-2:13 repeat with no size restrictions
 #set page(width: auto)
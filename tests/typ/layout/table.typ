@@ -68,3 +68,72 @@
 ---
 // Error: 14-19 expected color, gradient, pattern, none, array, or function, found string
 #table(fill: "hey")
+
+---
+// Ref: false
+// Test building a table from data.
+#let data = (
+  (name: "B", count: 2),
+  (name: "A", count: 3),
+  (name: "C"),
+)
+#table.from(data, sort: "name", totals: ("count",), stroke: none)
+#table.from(data, columns: ("count",), header: false, format: (count: v => [#v×]))
+#table.from(())
+
+---
+// Error: 32-42 cannot total unknown column `price`
+#table.from(((a: 1),), totals: ("price",))
+
+---
+// Error: 41-49 unexpected argument: hello
+#table.from(((a: 1),), columns: ("a",), hello: 5)
+
+---
+// Ref: false
+// Test merging equal cells.
+#table(
+  columns: 3,
+  merge-equal: (columns: (0, 1, 5)),
+  [A], [1], [x],
+  [A], [1], [y],
+  [B], [1], [z],
+  [B],
+)
+#table(columns: 2, gutter: 2pt, merge-equal: (columns: (0,)), [A], [1], [A], [2])
+
+---
+// Error: 21-48 unexpected key "rows", valid keys are "columns"
+#table(merge-equal: (columns: (0,), rows: (0,)))
+
+---
+// Ref: false
+// Test striped tables.
+#table(columns: 2, stripe: luma(240), [A], [B], [C], [D])
+#table(
+  columns: 3,
+  gutter: 2pt,
+  fill: (x, _) => if x == 0 { red.transparentize(50%) },
+  stripe: (fill: luma(240), bands: "columns", size: 2, offset: 1),
+  ..range(9).map(str),
+)
+
+---
+// Error: 16-37 unexpected key "width", valid keys are "fill", "bands", "size", and "offset"
+#table(stripe: (fill: red, width: 2))
+
+---
+// Ref: false
+// Test gutter rules.
+#table(columns: 3, stroke: none, gutter: 4pt, rule: 0.5pt, ..range(9).map(str))
+#grid(
+  columns: 2,
+  column-gutter: 6pt,
+  rule: blue,
+  rule-inset: (start: 2pt),
+  [A], [B], [C], [D],
+)
+
+---
+// Error: 29-54 unexpected key "middle", valid keys are "start" and "end"
+#grid(rule: 1pt, rule-inset: (start: 1pt, middle: 2pt))
@@ -43,3 +43,11 @@ $ lr(
     text("(", fill: #green) a/b
     text(")", fill: #blue)
   ) $
+
+---
+// Test fixed delimiter size steps.
+// Ref: false
+$ lr((x), size: "big") lr((x), size: "Bigg") abs(x/2, size: "bigg") $
+#set math.lr(size: "normal")
+$ (a/b) $
+
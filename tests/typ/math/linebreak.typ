@@ -0,0 +1,52 @@
+// Test line breaks in inline equations. The metadata after each equation is
+// beyond the right edge of the text if the equation wasn't broken.
+// Ref: false
+
+---
+#set page(width: 160pt, margin: 20pt)
+#set par(linebreaks: "optimized")
+Given $a + b + c + d + e + f + g + h = x + y + z$#metadata(none) <end>, we
+conclude.
+
+#locate(loc => {
+  let end = query(<end>, loc).first().location().position()
+  test(end.x <= 140pt, true)
+})
+
+---
+// Simple line breaking doesn't break inline equations.
+#set page(width: 160pt, margin: 20pt)
+#set par(linebreaks: "simple")
+Given $a + b + c + d + e + f + g + h = x + y + z$#metadata(none) <end>, we
+conclude.
+
+#locate(loc => {
+  let end = query(<end>, loc).first().location().position()
+  test(end.x > 140pt, true)
+})
+
+---
+// Breaking after relations only.
+#set page(width: 160pt, margin: 20pt)
+#set par(linebreaks: "optimized")
+#set math.equation(relation-penalty: 0, binary-penalty: none)
+Given $a + b + c + d + e + f + g + h = x + y + z$#metadata(none) <end>, we
+conclude.
+
+#locate(loc => {
+  let end = query(<end>, loc).first().location().position()
+  test(end.x <= 140pt, true)
+})
+
+---
+// Without penalties, inline equations can't break.
+#set page(width: 160pt, margin: 20pt)
+#set par(linebreaks: "optimized")
+#set math.equation(relation-penalty: none, binary-penalty: none)
+Given $a + b + c + d + e + f + g + h = x + y + z$#metadata(none) <end>, we
+conclude.
+
+#locate(loc => {
+  let end = query(<end>, loc).first().location().position()
+  test(end.x > 140pt, true)
+})
@@ -9,3 +9,27 @@ $ phi.alt := (1 + sqrt(5)) / 2 $ <ratio>
 
 With @ratio, we get
 $ F_n = round(1 / sqrt(5) phi.alt^n) $ <fib>
+
+---
+// Test per-section equation numbering.
+// Ref: false
+#set heading(numbering: "1.")
+#set math.equation(numbering: "(1.1)")
+
+= Basics
+$ a + b = c $ <a>
+$ c - b = a $ <b>
+
+= Products
+$ a dot b = c $ <c>
+
+#set math.equation(reset: none)
+$ a / b = c $ <d>
+
+#locate(loc => {
+  let at(label) = counter(math.equation).at(query(label, loc).first().location())
+  test(at(<a>), (1, 1))
+  test(at(<b>), (1, 2))
+  test(at(<c>), (2, 1))
+  test(at(<d>), (3,))
+})
@@ -39,3 +39,8 @@ $ v := vec(1 + 2, 2 - 4, sqrt(3), arrow(x)) + 1 $
 // Test using rules for symbols
 #show sym.tack: it => $#h(1em) it #h(1em)$
 $ a tack b $
+
+---
+// Test cramped style.
+// Ref: false
+$ x^2 + cramped(x^2) + display(cramped(x^2), cramped: false) $
@@ -0,0 +1,33 @@
+// Test appendices with their own heading numbering.
+// Ref: false
+
+---
+#set heading(numbering: "1.")
+= Introduction <intro>
+= Method
+
+#show: appendix
+= Proofs <proofs>
+== Lemma <lemma>
+
+#locate(loc => {
+  let numbers(label) = {
+    let it = query(label, loc).first()
+    numbering(it.numbering, ..counter(heading).at(it.location()))
+  }
+  test(numbers(<intro>), "1.")
+  test(numbers(<proofs>), "A")
+  test(numbers(<lemma>), "A.1")
+  test(query(<proofs>, loc).first().supplement, [Appendix])
+})
+
+---
+#set text(lang: "de")
+#show: appendix.with(numbering: "I.", supplement: none)
+= Grundlagen <basics>
+
+#locate(loc => {
+  let it = query(<basics>, loc).first()
+  test(it.numbering, "I.")
+  test(it.supplement, [])
+})
@@ -42,3 +42,12 @@ Now we have multiple bibliographies containing @glacier-melt @keshav2007read
 ---
 // Error: 15-55 duplicate bibliography keys: netwok, issue201, arrgh, quark, distress, glacier-melt, tolkien54, sharing, restful, mcintosh_anxiety, psychology25
 #bibliography(("/files/works.bib", "/files/works.bib"))
+
+---
+// Test annotated bibliography.
+// Ref: false
+@netwok @arrgh
+#bibliography(
+  "/files/works.bib",
+  annotations: (netwok: [A thorough study of pirate networks.]),
+)
@@ -0,0 +1,39 @@
+// Test element bounds and geometry helpers.
+// Ref: false
+
+---
+#set page(width: 100pt, height: 100pt, margin: 10pt)
+#box(width: 30pt, height: 20pt) <a>
+#rotate(90deg, box(width: 30pt, height: 20pt) <b>)
+
+#locate(loc => {
+  let a = query(<a>, loc).first().location().bounds()
+  test(a.page, 1)
+  test(a.x, 10pt)
+  test(a.width, 30pt)
+  test(a.height, 20pt)
+
+  // The bounds enclose the rotated box.
+  let b = query(<b>, loc).first().location().bounds()
+  test(calc.abs(b.width - 20pt) < 0.01pt, true)
+  test(calc.abs(b.height - 30pt) < 0.01pt, true)
+})
+
+---
+#let b = (page: 1, x: 0pt, y: 0pt, width: 20pt, height: 10pt)
+#test(geom.center(b), (10pt, 5pt))
+#test(geom.contains(b, (5pt, 5pt)), true)
+#test(geom.contains(b, (5pt, 15pt)), false)
+#test(
+  geom.overlap(b, (page: 1, x: 10pt, y: 5pt, width: 20pt, height: 10pt)),
+  (page: 1, x: 10pt, y: 5pt, width: 10pt, height: 5pt),
+)
+#test(geom.overlap(b, (page: 2, x: 0pt, y: 0pt, width: 20pt, height: 10pt)), none)
+#test(geom.boundary(b, (50pt, 5pt)), (20pt, 5pt))
+#test(geom.boundary(b, (10pt, 5pt)), (10pt, 5pt))
+#test(geom.crossing((0pt, 0pt), (20pt, 20pt), (0pt, 20pt), (20pt, 0pt)), (10pt, 10pt))
+#test(geom.crossing((0pt, 0pt), (20pt, 0pt), (0pt, 5pt), (20pt, 5pt)), none)
+
+---
+// Error: 16-22 point array must contain exactly two entries
+#geom.crossing((1pt,), (0pt, 0pt), (0pt, 0pt), (0pt, 0pt))
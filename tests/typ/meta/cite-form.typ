@@ -8,3 +8,28 @@ Nothing: #cite(<arrgh>, form: none)
 #cite(<netwok>, form: "prose") say stuff.
 
 #bibliography("/files/works.bib", style: "apa")
+
+---
+// Test that author-suppressed citations are enclosed like the style's
+// citations and that possessives follow the language. Each citation must end
+// where the text it should produce ends.
+// Ref: false
+#set page(width: 200pt)
+
+#let same-end(a, b) = locate(loc => {
+  let x(label) = query(label, loc).first().location().position().x
+  test(x(a), x(b))
+})
+
+#cite(<netwok>, form: "suppress-author")#metadata(none) <a1> \
+(2020)#metadata(none) <b1> \
+#cite(<issue201>, form: "possessive")#metadata(none) <a2> \
+cfr42’s#metadata(none) <b2> \
+#text(lang: "de")[#cite(<issue201>, form: "possessive")#metadata(none) <a3>] \
+cfr42s#metadata(none) <b3>
+
+#same-end(<a1>, <b1>)
+#same-end(<a2>, <b2>)
+#same-end(<a3>, <b3>)
+
+#bibliography("/files/works.bib", style: "apa")
@@ -47,3 +47,17 @@ Hello
   // Error: 4-15 pagebreaks are not allowed inside of containers
   #pagebreak()
 ]
+
+---
+// Test the export target.
+// Ref: false
+#style(styles => test(target(styles), "pdf"))
+
+---
+// Ref: false
+#set document(target: "html")
+#style(styles => test(target(styles), "html"))
+
+---
+// Error: 23-28 expected "pdf", "png", "svg", or "html"
+#set document(target: "epub")
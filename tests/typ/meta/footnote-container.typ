@@ -30,3 +30,62 @@ More #footnote[just for ...] footnotes #footnote[... testing. :)]
   [Hello], [A word #lang],
   [123], [A number #nums],
 )
+
+---
+// Footnotes in captions are not repeated in the outline and in placed content
+// land in the page's footnote area.
+// Ref: false
+#set page(height: 200pt)
+#outline(target: figure)
+#figure(rect[A], caption: [Caption #footnote[Source]])
+#place(bottom + right)[Placed #footnote[Note]]
+
+#locate(loc => {
+  let notes = query(footnote, loc)
+  test(notes.len(), 2)
+  test(counter(footnote).final(loc), (2,))
+})
+
+---
+// Footnotes in floating figures and tables land on the page of the float,
+// even if that moves the float to the next page.
+// Ref: false
+#set page(height: 120pt)
+#lorem(20)
+#figure(
+  placement: auto,
+  table(columns: 2, [A #footnote[In a table.]], [B]),
+  caption: [Floating #footnote[In a caption.]],
+)
+#lorem(10)
+
+#locate(loc => {
+  let fig = query(figure, loc).first()
+  let notes = query(footnote, loc)
+  test(notes.len(), 2)
+  for note in notes {
+    test(note.location().page(), fig.location().page())
+  }
+})
+
+---
+// Footnotes in table cells can be listed right below the table.
+// Ref: false
+#set page(height: 200pt)
+#table(
+  columns: 2,
+  notes: true,
+  [A #footnote[Alpha #metadata(none) <alpha>]], [B],
+  [C #footnote[Gamma]], [D],
+)
+#metadata(none) <after>
+Text #footnote[Page]
+
+#locate(loc => {
+  let note = query(<alpha>, loc).first().location().position()
+  let after = query(<after>, loc).first().location().position()
+  test(note.page, after.page)
+  test(note.y < after.y, true)
+  test(query(footnote, loc).len(), 3)
+  test(counter(footnote).final(loc), (3,))
+})
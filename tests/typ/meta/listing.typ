@@ -0,0 +1,34 @@
+// Test listings of arbitrary elements.
+// Ref: false
+
+---
+// Entries are recorded with their page numbers. Elements for which the entry
+// function returns none are left out.
+#let entries = state("entries", ())
+#show outline.entry: it => entries.update(e => e + ((it.body.text, it.page.text),))
+
+#listing(
+  figure.where(kind: "req"),
+  entry: it => if it.caption != none { it.caption.body },
+)
+#figure([Start.], kind: "req", supplement: [Req], caption: [R1])
+#pagebreak()
+#figure([Stop.], kind: "req", supplement: [Req])
+#figure([Go.], kind: "req", supplement: [Req], caption: [R3])
+#locate(loc => test(entries.final(loc), (("R1", "1"), ("R3", "2"))))
+
+---
+#let entries = state("entries", ())
+#show outline.entry: it => entries.update(e => e + ((it.level, it.body.text),))
+
+#listing(heading, entry: it => if it.level == 1 { it.body })
+= Introduction
+== Details
+= Conclusion
+#locate(loc => test(entries.final(loc), ((1, "Introduction"), (1, "Conclusion"))))
+
+---
+// Error: 2-19 cannot list metadata without an entry function
+// Hint: 2-19 specify how to display the element with `entry`
+#listing(metadata)
+#metadata("a")
@@ -85,3 +85,38 @@
 ---
 // Error: 17-19 number must be at least zero
 #numbering("1", -1)
+
+---
+// Test the numbering helpers.
+#test(numbering.roman(1994), "mcmxciv")
+#test(numbering.roman(14, upper: true), "XIV")
+#test(numbering.letter(28), "ab")
+#test(numbering.letter(3, upper: true), "C")
+#test(numbering.words(0), "zero")
+#test(numbering.words(-42), "minus forty-two")
+#test(numbering.words(1234567), "one million two hundred thirty-four thousand five hundred sixty-seven")
+#test(numbering.words(12, ordinal: true), "twelfth")
+#test(numbering.words(90, ordinal: true), "ninetieth")
+#test(numbering.words(101, ordinal: true), "one hundred first")
+
+---
+// Test spelling out numbers in German and French.
+#test(numbering.words(21, locale: "de"), "einundzwanzig")
+#test(numbering.words(1001, locale: "de"), "eintausendeins")
+#test(numbering.words(2000000, locale: "de"), "zwei Millionen")
+#test(numbering.words(3, locale: "de", ordinal: true), "dritte")
+#test(numbering.words(101, locale: "de", ordinal: true), "einhunderterste")
+#test(numbering.words(71, locale: "fr"), "soixante et onze")
+#test(numbering.words(200, locale: "fr"), "deux cents")
+#test(numbering.words(80000, locale: "fr"), "quatre-vingt mille")
+#test(numbering.words(1, locale: "fr", ordinal: true), "premier")
+#test(numbering.words(21, locale: "fr", ordinal: true), "vingt et unième")
+#test(numbering.words(1000, locale: "fr", ordinal: true), "millième")
+
+---
+// Error: 17-34 cannot spell out numbers in this language
+#numbering.words(1, locale: "ja")
+
+---
+// Error: 17-35 number must be positive for ordinals
+#numbering.words(0, ordinal: true)
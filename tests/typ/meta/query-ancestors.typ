@@ -0,0 +1,30 @@
+// Test element ancestry and enclosing sections.
+// Ref: false
+
+---
+#figure(caption: [A])[
+  #block[
+    #locate(loc => {
+      test(loc.parent().func(), block)
+      test(loc.ancestors().map(it => it.func()), (block, figure))
+    })
+  ] <b>
+]
+
+#locate(loc => test(loc.parent(), none))
+
+---
+#locate(loc => test(loc.headings(), ()))
+
+= A
+#locate(loc => test(loc.headings().map(it => it.body), ([A],)))
+
+== B
+=== C
+#locate(loc => test(loc.headings().map(it => it.body), ([A], [B], [C])))
+
+== D
+#locate(loc => test(loc.headings().map(it => it.body), ([A], [D])))
+
+= E
+#locate(loc => test(loc.headings().map(it => it.body), ([E],)))
@@ -0,0 +1,25 @@
+// Test query ordering and limits.
+// Ref: false
+
+---
+#figure(rect(), caption: [A])
+#figure(rect(), caption: [B])
+#figure(rect(), caption: [C])
+
+#locate(loc => {
+  let captions(..args) = query(figure, loc, ..args).map(it => it.caption.body)
+  test(captions(reverse: true), ([C], [B], [A]))
+  test(captions(limit: 2), ([A], [B]))
+  test(captions(offset: 1), ([B], [C]))
+  test(captions(offset: 1, limit: 1), ([B],))
+  test(captions(reverse: true, limit: 1), ([C],))
+  test(captions(offset: 5), ())
+})
+
+---
+= One
+= Two
+#locate(loc => {
+  let prev = query(selector(heading).before(loc), loc, reverse: true, limit: 1)
+  test(prev.first().body, [Two])
+})
@@ -0,0 +1,32 @@
+// Test scoped queries.
+// Ref: false
+
+---
+#block[
+  #figure(rect(), caption: [A])
+  #block[
+    #figure(rect(), caption: [B])
+  ] <inner>
+] <outer>
+#figure(rect(), caption: [C])
+
+#locate(loc => {
+  let captions(..args) = query(figure, loc, ..args).map(it => it.caption.body)
+  test(captions(), ([A], [B], [C]))
+  test(captions(within: <outer>), ([A], [B]))
+  test(captions(within: <inner>), ([B],))
+  let inner = query(<inner>, loc).first()
+  test(captions(within: inner.location()), ([B],))
+})
+
+---
+#figure(rect(), caption: [A]) <a>
+
+#locate(loc => {
+  test(query(figure.within(<a>), loc), ())
+  test(query(figure, loc, within: <missing>), ())
+})
+
+---
+// Error: 7-25 this selector cannot be used with show
+#show figure.within(<a>): none
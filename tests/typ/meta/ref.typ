@@ -46,3 +46,46 @@ $ A = 1 $ <eq2>
 
 #set ref(supplement: none)
 @fig1, @fig2, @eq1, @eq2
+
+---
+// Test per-target supplements and forms.
+// Ref: false
+#set heading(numbering: "1.")
+#set math.equation(numbering: "(1)")
+#set ref(
+  form: (equation: "full", heading: "number"),
+  supplement: (equation: [Eq.], figure: none),
+)
+
+= Intro <intro>
+$ a + b = c $ <eq>
+#figure(rect(), caption: [A box]) <fig>
+See @eq, @fig, and @intro.
+
+---
+// Test that per-target settings fold across set rules.
+// Ref: false
+#set heading(numbering: "1.")
+#set math.equation(numbering: "(1)")
+#set ref(supplement: (equation: [Eq.]))
+#set ref(form: (equation: "full"))
+#set ref(supplement: (figure: [Fig.]), form: (heading: "number"))
+
+// Measure inside of `locate`, so that the references only have to resolve
+// once the introspection loop is done.
+#let same(a, b) = style(styles => locate(_ => {
+  test(measure(a, styles).width, measure(b, styles).width)
+}))
+
+= Intro <intro>
+$ a + b = c $ <eq>
+#figure(rect(), caption: [A box]) <fig>
+
+#same(ref(<eq>), [Eq.~(1)])
+#same(ref(<fig>), [Fig.~1])
+#same(ref(<intro>), [1])
+
+// A bare value applies to all targets again.
+#set ref(supplement: [See])
+#same(ref(<eq>), [See~(1)])
+#same(ref(<fig>), [See~1])
@@ -0,0 +1,14 @@
+// Test review annotations.
+// Ref: false
+
+---
+#set ins(author: "Ana")
+The results are #ins[very] #del(author: "Bo")[not] promising.
+#comment(date: datetime(year: 2024, month: 1, day: 2))[Really?]
+
+#locate(loc => {
+  let annotations = query(selector(ins).or(del).or(comment), loc)
+  test(annotations.map(it => it.func()), (ins, del, comment))
+  test(annotations.map(it => it.author), ("Ana", "Bo", none))
+  test(annotations.last().date, datetime(year: 2024, month: 1, day: 2))
+})
@@ -54,3 +54,16 @@ Was: #locate(location => {
 #let s = state("s", 1)
 #locate(loc => s.update(s.final(loc) + 1))
 #s.display()
+
+---
+// Test final values outside of locate.
+// Ref: false
+#let total = counter(figure).final().first()
+#let last = state("x", 0).final()
+#figure(rect(), caption: [A])
+#figure(rect(), caption: [B])
+#state("x", 0).update(5)
+#locate(_ => {
+  test(total, 2)
+  test(last, 5)
+})
@@ -0,0 +1,32 @@
+// Test document statistics.
+// Ref: false
+
+---
+#set page(height: 100pt)
+= Intro
+Hello world, this is a test.
+== Details
+#figure(table[A][B], caption: [Numbers])
+#figure(rect(), caption: [Shape])
+#pagebreak()
+= Outro
+
+#locate(loc => {
+  let stats = statistics(loc)
+  test(stats.pages, 2)
+  test(stats.headings, (2, 1))
+  test(stats.figures, 2)
+  test(stats.tables, 1)
+  test(stats.words > 10, true)
+  test(stats.characters > stats.words, true)
+})
+
+---
+#hide[Invisible words]
+#locate(loc => test(statistics(loc).words, 0))
+
+---
+// Words that are split into differently styled runs count once, while words
+// in neighbouring columns are not joined.
+#columns(2)[*foo*bar _baz_ #colbreak() qux]
+#locate(loc => test(statistics(loc).words, 3))
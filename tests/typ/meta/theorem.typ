@@ -0,0 +1,58 @@
+// Test numbered environments.
+// Ref: false
+
+---
+// Theorems and lemmas share a counter, remarks have their own.
+#let lemma = theorem.with(kind: "lemma", group: "theorem")
+#show theorem.where(kind: "lemma"): set text(style: "italic")
+
+#theorem(title: [Euclid])[There are infinitely many primes.] <euclid>
+#lemma[Every number has a prime factor.] <factor>
+#theorem(kind: "remark")[Primes are neat.] <neat>
+#theorem[There is no largest prime.] <largest>
+
+By @euclid and @factor.
+
+#locate(loc => {
+  let number(label) = {
+    let elem = query(label, loc).first()
+    elem.counter.at(elem.location())
+  }
+  test(number(<euclid>), (1,))
+  test(number(<factor>), (2,))
+  test(number(<neat>), (1,))
+  test(number(<largest>), (3,))
+  test(counter(theorem.where(group: "theorem")).final(loc), (3,))
+  test(query(<factor>, loc).first().supplement, [Lemma])
+})
+
+---
+// The supplement of theorems is localized.
+#set text(lang: "de")
+#show theorem: it => {
+  test(it.supplement, if it.kind == "theorem" [Satz] else [Lemma])
+  it
+}
+
+#theorem[Es gibt unendlich viele Primzahlen.]
+#theorem(kind: "lemma")[Jede Zahl hat einen Primfaktor.]
+
+---
+#test(theorem[A].kind, "theorem")
+#test(theorem(kind: "lemma")[A].group, auto)
+
+---
+#show theorem: it => {
+  test(it.group, "theorem")
+  it
+}
+
+#theorem[A]
+#theorem(kind: "corollary", group: "theorem")[B]
+
+---
+#theorem(numbering: none)[Not numbered.] <plain>
+
+// Error: 1-7 cannot reference theorem without numbering
+// Hint: 1-7 you can enable theorem numbering with `#set theorem(numbering: "1.")`
+@plain
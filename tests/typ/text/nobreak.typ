@@ -0,0 +1,29 @@
+// Test nobreak, soft hyphens, and word joiners.
+// Ref: false
+
+---
+#set page(width: 80pt)
+#set text(hyphenate: true)
+The meeting starts at #nobreak[10:30 a.m.] in room #nobreak[B 204].
+
+---
+#test(shy().text, "\u{ad}")
+#test(wj().text, "\u{2060}")
+
+---
+#set page(width: 60pt)
+Extra#shy()ordinarily long#shy()winded words and a first#wj()---try.
+
+---
+// Test where lines can break, by comparing heights with a single line.
+#style(styles => {
+  let height(body) = measure(block(width: 40pt, body), styles).height
+  let single = height[A]
+  test(height[aaa bbb ccc ddd] > single, true)
+  test(height(nobreak[aaa bbb ccc ddd]), single)
+  test(height(text(hyphenate: true, nobreak[Extraordinarily])), single)
+  test(height[Extraordinarily], single)
+  test(height[Extra#shy()ordinarily], height[Extra ordinarily])
+  test(height[aaaaa-bbbbb] > single, true)
+  test(height[aaaaa-#wj()bbbbb], single)
+})
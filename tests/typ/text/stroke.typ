@@ -0,0 +1,14 @@
+// Test stroked text.
+// Ref: false
+
+---
+#set text(size: 20pt, weight: "bold")
+#text(stroke: 1pt + red)[Outlined]
+#text(fill: white, stroke: 0.5pt + black)[Hollow]
+#text(stroke: (paint: blue, thickness: 1pt, dash: "dashed"))[Dashed]
+#text(stroke: none)[Plain]
+
+---
+// Hint: 19-88 make sure to set `relative: auto` on your text stroke
+// Error: 19-88 gradients and patterns on text must be relative to the parent
+#set text(stroke: (paint: gradient.linear(red, blue, relative: "self"), thickness: 1pt))
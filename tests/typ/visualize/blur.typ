@@ -0,0 +1,10 @@
+// Test blurring content.
+// Ref: false
+
+---
+// Blurring does not affect layout.
+#let body = rect(width: 20pt, height: 10pt, fill: blue)
+#style(styles => test(measure(blur(4pt, body), styles), measure(body, styles)))
+#blur[Default radius]
+#blur(0pt)[Not blurred]
+
@@ -0,0 +1,69 @@
+// Test bar, line, pie, and Gantt charts.
+// Ref: false
+
+---
+#chart.bar(
+  height: 80pt,
+  ("Q1", 12), ("Q2", 18), ("Q3", 9), ("Q4", -3),
+)
+
+---
+#chart.line(
+  height: 80pt,
+  (label: "Revenue", data: (3, 5, 4, 7, 8)),
+  (label: "Costs", data: ((0, 2), (2, 3.5), (4, 4))),
+  (1, 1, 2),
+)
+
+---
+#chart.pie(
+  size: 60pt,
+  colors: (red, blue),
+  ("Rent", 1200), ("Food", 450), ("Other", 300), 0,
+)
+
+---
+#chart.gantt(
+  ("Design", 0, 3),
+  ("Build", 2, 7),
+  ("Test", 5, 8.5),
+  ("Launch", 8.5),
+)
+
+---
+// A timeline of milestones on dates.
+#chart.gantt(
+  row-height: 12pt,
+  fill: green,
+  ("Kickoff", datetime(year: 2023, month: 1, day: 9)),
+  ("Review", datetime(year: 2023, month: 2, day: 1)),
+  ("Release", datetime(year: 2023, month: 3, day: 15)),
+)
+
+---
+// Error: 12-21 datum must be a value or a pair of a label and a value
+#chart.bar((1, 2, 3))
+
+---
+// Error: 2-21 pie chart values must not be negative
+#chart.pie(1, -2, 3)
+
+---
+// Error: 2-73 cannot mix numbers and dates in a gantt chart
+#chart.gantt(("A", 0, 1), ("B", datetime(year: 2023, month: 1, day: 2)))
+
+---
+// Error: 2-26 task must not end before it starts
+#chart.gantt(("A", 3, 1))
+
+---
+// Error: 14-28 task must be an array of a label, a start, and an optional end
+#chart.gantt(("A", 0, 1, 2))
+
+---
+// Values of the same, very large magnitude still get an axis.
+#chart.line(height: 60pt, (1e20, 1e20))
+
+---
+// Error: 2-40 chart values span too large a range
+#chart.bar(("A", -1e308), ("B", 1e308))
@@ -0,0 +1,39 @@
+// Test connectors between labelled elements.
+// Ref: false
+
+---
+#set rect(inset: 6pt)
+#grid(
+  columns: 3,
+  column-gutter: 24pt,
+  row-gutter: 16pt,
+  [#rect[Start] <start>],
+  [],
+  [#rect[Check] <check>],
+  [],
+  [#rect[End] <end>],
+)
+
+#connect(<start>, <check>)
+#connect(<check>, <end>, style: "curved", stroke: blue)
+#connect(from: <end>, to: <start>, style: "straight", arrow: false)
+
+---
+// A connector doesn't take up any space.
+#locate(loc => {
+  let before = query(<before>, loc).first().location().position().y
+  let after = query(<after>, loc).first().location().position().y
+  test(after - before, 0pt)
+})
+#metadata(none) <before>
+#connect(<before>, <after>)
+#metadata(none) <after>
+
+---
+#box[A] <start>
+// Error: 2-29 label `<missing>` does not exist in the document
+#connect(<start>, <missing>)
+
+---
+// Error: 9-18 missing argument: to
+#connect(<start>)
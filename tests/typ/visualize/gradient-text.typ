@@ -6,11 +6,11 @@
 
 ---
 // Ref: false
-// Make sure they don't work when `relative: "self"`.
-
-// Hint: 17-61 make sure to set `relative: auto` on your text fill
-// Error: 17-61 gradients and patterns on text must be relative to the parent
-#set text(fill: gradient.linear(red, blue, relative: "self"))
+// Make sure they are applied glyph by glyph when `relative: "self"`.
+#let body = [Glyph by glyph]
+#let painted = text(fill: gradient.linear(red, blue, relative: "self"), body)
+#style(styles => test(measure(painted, styles), measure(body, styles)))
+#painted
 
 ---
 // Test that gradient fills on text work for globally defined gradients.
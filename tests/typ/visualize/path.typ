@@ -50,3 +50,39 @@
 ---
 // Error: 7-31 point array must contain exactly two entries
 #path(((0%, 0%), (0%, 0%, 0%)))
+
+---
+// Test measuring paths.
+#let p = path((0pt, 0pt), (64pt, 0pt), (64pt, 64pt))
+#test(path.length(p), 128pt)
+#test(path.point(p, 0%), (0pt, 0pt))
+#test(path.point(p, 25%), (32pt, 0pt))
+#test(path.point(p, 75%), (64pt, 32pt))
+#test(path.tangent(p, 25%), 0deg)
+#test(calc.round(path.tangent(p, 75%).deg()), 90.0)
+#test(path.length(path(closed: true, (0pt, 0pt), (64pt, 0pt), (64pt, 64pt), (0pt, 64pt))), 256pt)
+
+---
+// Test measuring a curved path.
+#let arc = path(
+  ((0pt, 0pt), (0pt, 0pt), (55.228pt, 0pt)),
+  ((100pt, 100pt), (0pt, -55.228pt)),
+)
+#test(calc.abs((path.length(arc) - 157.08pt).pt()) < 0.1, true)
+#test(calc.round(path.tangent(arc, 100%).deg()), 90.0)
+
+---
+// Error: 13-21 expected path, found rect
+#path.length(rect())
+
+---
+// Error: 13-42 cannot measure a path with relative coordinates
+#path.length(path((0%, 0pt), (1em, 0pt)))
+
+---
+// Error: 12-48 position must be between 0% and 100%
+#path.point(path((0pt, 0pt), (1pt, 0pt)), 150%)
+
+---
+// Error: 14-37 path must have at least two distinct points
+#path.tangent(path((0pt, 0pt)), 50%)
@@ -26,3 +26,10 @@
   #set text(fill: pat)
   #lorem(10)
 ]))
+
+---
+// Ref: false
+// Test glyph-by-glyph patterns and patterns with image bodies.
+#set text(size: 20pt, weight: "bold")
+#text(fill: pattern(size: (4pt, 4pt), relative: "self", circle(radius: 1pt)))[Dots]
+#text(fill: pattern(relative: "self", image("/files/tiger.jpg", width: 20pt)))[Tiger]
@@ -0,0 +1,16 @@
+// Test rounded corners of polygons and paths.
+// Ref: false
+
+---
+#let shape = polygon(radius: 5pt, (0pt, 0pt), (40pt, 0pt), (20pt, 30pt))
+#style(styles => test(measure(shape, styles), (width: 40pt, height: 30pt)))
+#polygon.regular(size: 20pt, vertices: 6)
+#set polygon(radius: 100pt)
+#polygon((0pt, 0pt), (10pt, 0pt), (10pt, 10pt))
+
+---
+#path(radius: 4pt, closed: true, (0pt, 0pt), (30pt, 0pt), ((30pt, 30pt), (10pt, 0pt)))
+
+---
+// Error: 18-19 expected length, found integer
+#polygon(radius: 5, (0pt, 0pt), (10pt, 10pt))
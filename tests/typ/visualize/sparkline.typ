@@ -0,0 +1,32 @@
+// Test sparklines and bullet bars.
+// Ref: false
+
+---
+Visitors: #chart.sparkline((3, 5, 4, 8, 6, 9, 12)) and rising.
+
+#table(
+  columns: 2,
+  [Sales], chart.bullet(72, target: 80),
+  [Costs], chart.bullet(120, max: 100, fill: red),
+  [Trend], chart.sparkline((1, 1, 1), stroke: green),
+)
+
+---
+#test(chart.sparkline((1, 2)).values, (1.0, 2.0))
+#test(chart.bullet(5).value, 5.0)
+
+---
+// Error: 18-34 chart values must be finite
+#chart.sparkline((1, calc.inf, 2))
+
+---
+// Error: 18-34 chart values must be finite
+#chart.sparkline((1, calc.nan, 2))
+
+---
+// Error: 15-23 chart values must be finite
+#chart.bullet(calc.nan)
+
+---
+// Error: 26-34 chart values must be finite
+#chart.bullet(5, target: calc.nan)